@@ -6,6 +6,7 @@
 //! # Security Model
 //!
 //! - Single admin address (MVP design)
+//! - Registered relayers may run automated maintenance (metrics snapshots) without admin powers
 //! - Admin can only be set once during initialization
 //! - Admin can transfer role to another address
 //! - All privileged operations require admin authorization
@@ -22,13 +23,15 @@
 //! Uses instance storage for:
 //! - Admin address (single source of truth)
 //! - Initialization flag (prevents re-initialization)
+//! - Relayer list
 
 use crate::errors::QuickLendXError;
-use soroban_sdk::{symbol_short, Address, Env, Symbol};
+use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
 
 /// Storage keys for admin management
 pub const ADMIN_KEY: Symbol = symbol_short!("admin");
 pub const ADMIN_INITIALIZED_KEY: Symbol = symbol_short!("adm_init");
+pub const RELAYERS_KEY: Symbol = symbol_short!("relayers");
 
 /// Admin storage and management operations
 pub struct AdminStorage;
@@ -158,6 +161,81 @@ impl AdminStorage {
         }
         Ok(())
     }
+
+    /// Get the registered relayer addresses
+    pub fn get_relayers(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&RELAYERS_KEY)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Check if an address is a registered relayer
+    pub fn is_relayer(env: &Env, address: &Address) -> bool {
+        Self::get_relayers(env).contains(address)
+    }
+
+    /// Register a relayer for automated processes
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the relayer was added
+    /// * `Ok(false)` if it was already registered
+    /// * `Err(QuickLendXError::NotAdmin)` if caller is not the admin
+    pub fn add_relayer(
+        env: &Env,
+        admin: &Address,
+        relayer: &Address,
+    ) -> Result<bool, QuickLendXError> {
+        admin.require_auth();
+        Self::require_admin(env, admin)?;
+
+        let mut relayers = Self::get_relayers(env);
+        if relayers.contains(relayer) {
+            return Ok(false);
+        }
+        relayers.push_back(relayer.clone());
+        env.storage().instance().set(&RELAYERS_KEY, &relayers);
+        emit_relayer_added(env, admin, relayer);
+        Ok(true)
+    }
+
+    /// Remove a registered relayer
+    ///
+    /// # Returns
+    /// * `Ok(())` if the relayer was removed
+    /// * `Err(QuickLendXError::NotAdmin)` if caller is not the admin
+    /// * `Err(QuickLendXError::StorageKeyNotFound)` if the address is not a relayer
+    pub fn remove_relayer(
+        env: &Env,
+        admin: &Address,
+        relayer: &Address,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        Self::require_admin(env, admin)?;
+
+        let mut relayers = Self::get_relayers(env);
+        let index = relayers
+            .first_index_of(relayer)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        relayers.remove(index);
+        env.storage().instance().set(&RELAYERS_KEY, &relayers);
+        emit_relayer_removed(env, admin, relayer);
+        Ok(())
+    }
+
+    /// Require that the caller authorized the call and is the admin or a relayer
+    ///
+    /// # Returns
+    /// * `Ok(())` if the caller is the admin or a registered relayer
+    /// * `Err(QuickLendXError::Unauthorized)` otherwise
+    pub fn require_admin_or_relayer(env: &Env, caller: &Address) -> Result<(), QuickLendXError> {
+        caller.require_auth();
+        if Self::is_admin(env, caller) || Self::is_relayer(env, caller) {
+            Ok(())
+        } else {
+            Err(QuickLendXError::Unauthorized)
+        }
+    }
 }
 
 /// Emit event when admin is first initialized
@@ -179,3 +257,19 @@ fn emit_admin_transferred(env: &Env, old_admin: &Address, new_admin: &Address) {
         ),
     );
 }
+
+/// Emit event when a relayer is registered
+fn emit_relayer_added(env: &Env, admin: &Address, relayer: &Address) {
+    env.events().publish(
+        (symbol_short!("rly_add"),),
+        (admin.clone(), relayer.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit event when a relayer is removed
+fn emit_relayer_removed(env: &Env, admin: &Address, relayer: &Address) {
+    env.events().publish(
+        (symbol_short!("rly_rem"),),
+        (admin.clone(), relayer.clone(), env.ledger().timestamp()),
+    );
+}
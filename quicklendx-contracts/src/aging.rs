@@ -0,0 +1,123 @@
+//! Running aging totals: the count and unpaid amount of funded invoices per due day, for the
+//! whole platform and for each business.
+//!
+//! Aging buckets move with the clock, so the totals are kept per due day (the UTC day of the
+//! due date) rather than per bucket, and `get_aging_report` sums the days into buckets relative
+//! to today without reading any invoices. `InvoiceStorage::update_invoice` keeps the totals in
+//! step: an invoice is added when it is funded, its amount shrinks as partial payments arrive,
+//! and it is removed once it is settled, defaulted or refunded. Each tracked invoice keeps the
+//! entry it was counted under, so it is always removed from exactly the day and amount it added.
+
+use crate::invoice::{Invoice, InvoiceStatus};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+const ENTRY_KEY: Symbol = symbol_short!("age_inv");
+const PLATFORM_KEY: Symbol = symbol_short!("age_all");
+const BUSINESS_KEY: Symbol = symbol_short!("age_biz");
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Funded invoices falling due on one day
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AgingDay {
+    /// Due date in days since the Unix epoch
+    pub due_day: u64,
+    pub count: u32,
+    pub outstanding_amount: i128,
+}
+
+/// What a tracked invoice is counted as
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct AgingEntry {
+    due_day: u64,
+    outstanding_amount: i128,
+}
+
+pub struct AgingTotals;
+
+impl AgingTotals {
+    /// Per-day totals for one business, or the whole platform with `None`, ordered by due day
+    pub fn get_days(env: &Env, business: Option<&Address>) -> Vec<AgingDay> {
+        let days = match business {
+            Some(business) => env
+                .storage()
+                .instance()
+                .get(&(BUSINESS_KEY, business.clone())),
+            None => env.storage().instance().get(&PLATFORM_KEY),
+        };
+        days.unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_days(env: &Env, business: Option<&Address>, days: &Vec<AgingDay>) {
+        match business {
+            Some(business) => env
+                .storage()
+                .instance()
+                .set(&(BUSINESS_KEY, business.clone()), days),
+            None => env.storage().instance().set(&PLATFORM_KEY, days),
+        }
+    }
+
+    /// Bring the totals in line with an invoice that was just stored
+    pub fn sync(env: &Env, invoice: &Invoice) {
+        let key = (ENTRY_KEY, invoice.id.clone());
+        let old: Option<AgingEntry> = env.storage().instance().get(&key);
+        let new = (invoice.status == InvoiceStatus::Funded).then(|| AgingEntry {
+            due_day: invoice.due_date / SECONDS_PER_DAY,
+            outstanding_amount: invoice.amount.saturating_sub(invoice.total_paid).max(0),
+        });
+        if old == new {
+            return;
+        }
+
+        if let Some(old) = old {
+            Self::adjust(env, &invoice.business, &old, -1);
+        }
+        match new {
+            Some(new) => {
+                Self::adjust(env, &invoice.business, &new, 1);
+                env.storage().instance().set(&key, &new);
+            }
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    /// Add (`sign` 1) or remove (`sign` -1) an entry in the platform and business totals
+    fn adjust(env: &Env, business: &Address, entry: &AgingEntry, sign: i32) {
+        let due_day = entry.due_day;
+        for scope in [None, Some(business)] {
+            let mut days = Self::get_days(env, scope);
+            let mut position = days.len();
+            for (idx, day) in days.iter().enumerate() {
+                if day.due_day >= due_day {
+                    position = idx as u32;
+                    break;
+                }
+            }
+
+            let mut day = match days.get(position) {
+                Some(day) if day.due_day == due_day => day,
+                _ => {
+                    let day = AgingDay {
+                        due_day,
+                        count: 0,
+                        outstanding_amount: 0,
+                    };
+                    days.insert(position, day.clone());
+                    day
+                }
+            };
+            day.count = day.count.saturating_add_signed(sign);
+            day.outstanding_amount = day
+                .outstanding_amount
+                .saturating_add(entry.outstanding_amount * sign as i128);
+            if day.count == 0 {
+                days.remove(position);
+            } else {
+                days.set(position, day);
+            }
+            Self::set_days(env, scope, &days);
+        }
+    }
+}
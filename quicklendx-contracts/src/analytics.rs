@@ -1,5 +1,7 @@
 use crate::errors::QuickLendXError;
-use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::insurance::InsuranceLog;
+use crate::investment::{InvestmentStatus, InvestmentStorage};
+use crate::invoice::{InvoiceCategory, InvoiceStatus, InvoiceStorage};
 use soroban_sdk::{contracttype, symbol_short, Address, Bytes, BytesN, Env, String, Vec};
 
 /// Time period for analytics reports
@@ -162,6 +164,70 @@ pub struct InvestorPerformanceMetrics {
     pub generated_at: u64,
 }
 
+/// Count and outstanding amount of funded invoices in one aging bucket
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AgingBucket {
+    pub count: u32,
+    pub outstanding_amount: i128,
+}
+
+/// Funded invoices bucketed by days to or past their due date, counted in whole UTC days
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AgingReport {
+    /// `None` for the platform-wide report
+    pub business: Option<Address>,
+    pub due_in_over_90: AgingBucket,
+    pub due_in_61_90: AgingBucket,
+    pub due_in_31_60: AgingBucket,
+    pub due_in_1_30: AgingBucket,
+    /// Due today
+    pub current: AgingBucket,
+    pub overdue_1_30: AgingBucket,
+    pub overdue_31_60: AgingBucket,
+    pub overdue_61_90: AgingBucket,
+    pub overdue_over_90: AgingBucket,
+    pub total: AgingBucket,
+    pub generated_at: u64,
+}
+
+/// Repayment expected from a funded invoice at its due date
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpectedCashflow {
+    pub invoice_id: BytesN<32>,
+    pub investor: Address,
+    pub business: Address,
+    pub currency: Address,
+    pub due_date: u64,
+    /// Invoice amount not yet paid
+    pub amount_due: i128,
+}
+
+/// Insurance program metrics: premiums and claims within a period, with the coverage and
+/// uninsured exposure outstanding when calculated
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceMetrics {
+    pub period: TimePeriod,
+    pub start_date: u64,
+    pub end_date: u64,
+    /// Premiums collected net of cancellation refunds
+    pub total_premiums: i128,
+    pub total_claims_paid: i128,
+    pub total_claims_recovered: i128,
+    /// Claims paid net of recoveries as a share of premiums, in basis points
+    pub loss_ratio_bps: i128,
+    pub loss_ratio_by_provider: Vec<(Address, i128)>,
+    /// Active investments in funded invoices
+    pub funded_exposure: i128,
+    pub coverage_outstanding: i128,
+    /// Part of `funded_exposure` not covered by active insurance
+    pub uninsured_exposure: i128,
+    pub timestamp: u64,
+}
+
 /// Analytics storage structure
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -203,6 +269,10 @@ impl AnalyticsStorage {
         (symbol_short!("inv_perf"),)
     }
 
+    fn insurance_metrics_key(period: &TimePeriod) -> (soroban_sdk::Symbol, TimePeriod) {
+        (symbol_short!("ins_met"), period.clone())
+    }
+
     fn analytics_data_key() -> (soroban_sdk::Symbol,) {
         (symbol_short!("analytics"),)
     }
@@ -283,6 +353,18 @@ impl AnalyticsStorage {
             .get(&Self::investor_performance_key())
     }
 
+    pub fn store_insurance_metrics(env: &Env, metrics: &InsuranceMetrics) {
+        env.storage()
+            .instance()
+            .set(&Self::insurance_metrics_key(&metrics.period), metrics);
+    }
+
+    pub fn get_insurance_metrics(env: &Env, period: &TimePeriod) -> Option<InsuranceMetrics> {
+        env.storage()
+            .instance()
+            .get(&Self::insurance_metrics_key(period))
+    }
+
     pub fn generate_report_id(env: &Env) -> BytesN<32> {
         let timestamp = env.ledger().timestamp();
         let sequence = env.ledger().sequence();
@@ -391,7 +473,7 @@ impl AnalyticsCalculator {
 
         // Get platform fee rate
         let platform_fee_config = crate::profits::PlatformFee::get_config(env);
-        let platform_fee_rate = platform_fee_config.fee_bps;
+        let platform_fee_rate = platform_fee_config.fee_bps as i128;
 
         // Calculate default rate
         let _current_timestamp = env.ledger().timestamp();
@@ -509,6 +591,8 @@ impl AnalyticsCalculator {
         let mut total_volume = 0i128;
         let mut total_fees = 0i128;
         let mut total_profits = 0i128;
+        let mut late_penalty_investor = 0i128;
+        let mut late_penalty_platform = 0i128;
         let mut volume_by_category = Vec::new(env);
         let mut currency_distribution = Vec::new(env);
 
@@ -588,10 +672,23 @@ impl AnalyticsCalculator {
                             total_fees = total_fees.saturating_add(platform_fee);
                             total_profits = total_profits.saturating_add(profit);
                         }
+                        if let Some(penalty) =
+                            crate::fees::FeeManager::get_late_penalty_record(env, &invoice_id)
+                        {
+                            late_penalty_investor =
+                                late_penalty_investor.saturating_add(penalty.investor_share);
+                            late_penalty_platform =
+                                late_penalty_platform.saturating_add(penalty.platform_share);
+                        }
                     }
                 }
             }
         }
+        // The platform's penalty share is paid by the business on top of the settlement, so it
+        // counts toward total fees but is not deducted from investor profits
+        let platform_fees = total_fees;
+        total_fees = total_fees.saturating_add(late_penalty_platform);
+        total_profits = total_profits.saturating_add(late_penalty_investor);
 
         // Calculate average return rate
         let average_return_rate = if total_volume > 0 {
@@ -604,14 +701,22 @@ impl AnalyticsCalculator {
 
         // Create fee breakdown
         let mut fee_breakdown = Vec::new(env);
-        fee_breakdown.push_back((String::from_str(env, "platform_fees"), total_fees));
+        fee_breakdown.push_back((String::from_str(env, "platform_fees"), platform_fees));
+        fee_breakdown.push_back((
+            String::from_str(env, "late_penalty_platform"),
+            late_penalty_platform,
+        ));
+        fee_breakdown.push_back((
+            String::from_str(env, "late_penalty_investor"),
+            late_penalty_investor,
+        ));
 
         // Create profit margins
         let mut profit_margins = Vec::new(env);
         profit_margins.push_back((String::from_str(env, "gross_profit"), total_profits));
         profit_margins.push_back((
             String::from_str(env, "net_profit"),
-            total_profits.saturating_sub(total_fees),
+            total_profits.saturating_sub(platform_fees),
         ));
 
         // Create volume by period (simplified for this implementation)
@@ -742,7 +847,7 @@ impl AnalyticsCalculator {
         // Calculate platform efficiency
         let platform_efficiency = {
             let fee_config = crate::profits::PlatformFee::get_config(env);
-            fee_config.fee_bps
+            fee_config.fee_bps as i128
         };
 
         Ok(PerformanceMetrics {
@@ -1256,4 +1361,162 @@ impl AnalyticsCalculator {
             generated_at: current_timestamp,
         })
     }
+
+    /// Bucket funded invoices by days to or past their due date, for one business or the whole
+    /// platform
+    ///
+    /// Reads the running per-day totals kept by `AgingTotals`, so the cost grows with the number
+    /// of distinct due days rather than the number of invoices. Amounts are the unpaid remainder
+    /// of each invoice.
+    pub fn calculate_aging_report(env: &Env, business: Option<Address>) -> AgingReport {
+        let now = env.ledger().timestamp();
+        let today = now / crate::aging::SECONDS_PER_DAY;
+        let empty = AgingBucket {
+            count: 0,
+            outstanding_amount: 0,
+        };
+        let mut report = AgingReport {
+            business: business.clone(),
+            due_in_over_90: empty.clone(),
+            due_in_61_90: empty.clone(),
+            due_in_31_60: empty.clone(),
+            due_in_1_30: empty.clone(),
+            current: empty.clone(),
+            overdue_1_30: empty.clone(),
+            overdue_31_60: empty.clone(),
+            overdue_61_90: empty.clone(),
+            overdue_over_90: empty.clone(),
+            total: empty,
+            generated_at: now,
+        };
+
+        for day in crate::aging::AgingTotals::get_days(env, business.as_ref()).iter() {
+            let bucket = if day.due_day >= today {
+                match day.due_day - today {
+                    0 => &mut report.current,
+                    1..=30 => &mut report.due_in_1_30,
+                    31..=60 => &mut report.due_in_31_60,
+                    61..=90 => &mut report.due_in_61_90,
+                    _ => &mut report.due_in_over_90,
+                }
+            } else {
+                match today - day.due_day {
+                    1..=30 => &mut report.overdue_1_30,
+                    31..=60 => &mut report.overdue_31_60,
+                    61..=90 => &mut report.overdue_61_90,
+                    _ => &mut report.overdue_over_90,
+                }
+            };
+            for bucket in [bucket, &mut report.total] {
+                bucket.count = bucket.count.saturating_add(day.count);
+                bucket.outstanding_amount = bucket
+                    .outstanding_amount
+                    .saturating_add(day.outstanding_amount);
+            }
+        }
+
+        report
+    }
+
+    /// Repayments of funded invoices due within `[from, to]`, ordered by due date, optionally
+    /// limited to one investor
+    ///
+    /// Each invoice contributes a single flow at its due date for the amount still unpaid;
+    /// partial payments already received reduce it.
+    pub fn calculate_expected_cashflows(
+        env: &Env,
+        from: u64,
+        to: u64,
+        investor: Option<Address>,
+    ) -> Vec<ExpectedCashflow> {
+        let mut cashflows: Vec<ExpectedCashflow> = Vec::new(env);
+        if from > to {
+            return cashflows;
+        }
+        let funded =
+            crate::invoice::InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded);
+        for invoice_id in funded.iter() {
+            let invoice = match crate::invoice::InvoiceStorage::get_invoice(env, &invoice_id) {
+                Some(invoice) if invoice.due_date >= from && invoice.due_date <= to => invoice,
+                _ => continue,
+            };
+            let invoice_investor = match invoice.investor.clone() {
+                Some(address) => address,
+                None => continue,
+            };
+            if investor
+                .as_ref()
+                .is_some_and(|wanted| *wanted != invoice_investor)
+            {
+                continue;
+            }
+            let amount_due = invoice.amount.saturating_sub(invoice.total_paid);
+            if amount_due <= 0 {
+                continue;
+            }
+
+            let cashflow = ExpectedCashflow {
+                invoice_id,
+                investor: invoice_investor,
+                business: invoice.business,
+                currency: invoice.currency,
+                due_date: invoice.due_date,
+                amount_due,
+            };
+            let mut position = cashflows.len();
+            for idx in 0..cashflows.len() {
+                if cashflows.get(idx).unwrap().due_date > cashflow.due_date {
+                    position = idx;
+                    break;
+                }
+            }
+            cashflows.insert(position, cashflow);
+        }
+        cashflows
+    }
+
+    /// Calculate insurance metrics for a period
+    pub fn calculate_insurance_metrics(env: &Env, period: TimePeriod) -> InsuranceMetrics {
+        let stats = InsuranceLog::get_stats(env, period);
+        let mut loss_ratio_by_provider = Vec::new(env);
+        for provider in stats.providers.iter() {
+            loss_ratio_by_provider.push_back((provider.provider, provider.loss_ratio_bps));
+        }
+
+        let mut funded_exposure: i128 = 0;
+        let mut coverage_outstanding: i128 = 0;
+        let mut uninsured_exposure: i128 = 0;
+        for invoice_id in InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded).iter()
+        {
+            let investment = match InvestmentStorage::get_investment_by_invoice(env, &invoice_id) {
+                Some(investment) if investment.status == InvestmentStatus::Active => investment,
+                _ => continue,
+            };
+            let mut covered: i128 = 0;
+            for coverage in investment.insurance.iter() {
+                if coverage.active {
+                    covered = covered.saturating_add(coverage.coverage_amount);
+                }
+            }
+            funded_exposure = funded_exposure.saturating_add(investment.amount);
+            coverage_outstanding = coverage_outstanding.saturating_add(covered);
+            uninsured_exposure =
+                uninsured_exposure.saturating_add(investment.amount.saturating_sub(covered).max(0));
+        }
+
+        InsuranceMetrics {
+            period: stats.period,
+            start_date: stats.start_date,
+            end_date: stats.end_date,
+            total_premiums: stats.premiums_collected,
+            total_claims_paid: stats.claims_paid_amount,
+            total_claims_recovered: stats.claims_recovered_amount,
+            loss_ratio_bps: stats.loss_ratio_bps,
+            loss_ratio_by_provider,
+            funded_exposure,
+            coverage_outstanding,
+            uninsured_exposure,
+            timestamp: env.ledger().timestamp(),
+        }
+    }
 }
@@ -22,6 +22,12 @@ pub enum AuditOperation {
     EscrowRefunded,
     PaymentProcessed,
     SettlementCompleted,
+    InvoiceRelisted,
+    InvoiceFrozen,
+    InvoiceUnfrozen,
+    InsuranceClaimPaid,
+    InvoiceBoosted,
+    InvoiceSplit,
 }
 
 /// Audit log entry structure
@@ -576,6 +582,66 @@ pub fn log_invoice_cancelled(env: &Env, invoice_id: BytesN<32>, actor: Address)
     );
 }
 
+/// Log a relisted invoice on both the original and the new record.
+pub fn log_invoice_relisted(
+    env: &Env,
+    invoice_id: BytesN<32>,
+    relisted_id: BytesN<32>,
+    actor: Address,
+    amount: i128,
+) {
+    log_operation(
+        env,
+        invoice_id,
+        AuditOperation::InvoiceRelisted,
+        actor.clone(),
+        None,
+        Some(String::from_str(env, "Relisted as new invoice")),
+        Some(amount),
+        None,
+    );
+    log_operation(
+        env,
+        relisted_id,
+        AuditOperation::InvoiceRelisted,
+        actor,
+        None,
+        Some(String::from_str(env, "Relisted from previous invoice")),
+        Some(amount),
+        None,
+    );
+}
+
+/// Log a split on the original invoice and on one of its parts.
+pub fn log_invoice_split(
+    env: &Env,
+    invoice_id: BytesN<32>,
+    part_id: BytesN<32>,
+    actor: Address,
+    amount: i128,
+) {
+    log_operation(
+        env,
+        invoice_id,
+        AuditOperation::InvoiceSplit,
+        actor.clone(),
+        None,
+        Some(String::from_str(env, "Split into new invoice")),
+        Some(amount),
+        None,
+    );
+    log_operation(
+        env,
+        part_id,
+        AuditOperation::InvoiceSplit,
+        actor,
+        None,
+        Some(String::from_str(env, "Split from previous invoice")),
+        Some(amount),
+        None,
+    );
+}
+
 /// Log bid placed.
 pub fn log_bid_placed(
     env: &Env,
@@ -1,6 +1,12 @@
 use crate::errors::QuickLendXError;
 use crate::invoice::Invoice;
-use soroban_sdk::{contracttype, symbol_short, BytesN, Env, String, Vec};
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, String, Symbol, Vec};
+
+/// Invoices stored per backup chunk
+pub const BACKUP_CHUNK_SIZE: u32 = 20;
+
+const MANIFEST_KEY: Symbol = symbol_short!("bkup_man");
+const CHUNK_KEY: Symbol = symbol_short!("bkup_chk");
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -12,6 +18,16 @@ pub struct Backup {
     pub status: BackupStatus,
 }
 
+/// Layout of a backup's invoice data: `chunk_count` chunks of up to `BACKUP_CHUNK_SIZE`
+/// invoices, keyed by (backup_id, chunk_index)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupManifest {
+    pub backup_id: BytesN<32>,
+    pub chunk_count: u32,
+    pub invoice_count: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum BackupStatus {
@@ -93,38 +109,95 @@ impl BackupStorage {
             .set(&symbol_short!("backups"), &new_backups);
     }
 
-    /// Store invoice data for a backup
-    pub fn store_backup_data(env: &Env, backup_id: &BytesN<32>, invoices: &Vec<Invoice>) {
-        let key = (symbol_short!("bkup_data"), backup_id.clone());
-        env.storage().instance().set(&key, invoices);
+    /// Store invoice data for a backup in chunks of `BACKUP_CHUNK_SIZE`, plus its manifest
+    ///
+    /// Chunks live in persistent storage, one entry each, so a backup's size is not bounded by
+    /// the size limit of a single ledger entry.
+    pub fn store_backup_data(
+        env: &Env,
+        backup_id: &BytesN<32>,
+        invoices: &Vec<Invoice>,
+    ) -> BackupManifest {
+        let mut chunk_count = 0;
+        let mut start = 0;
+        while start < invoices.len() {
+            let end = (start + BACKUP_CHUNK_SIZE).min(invoices.len());
+            env.storage().persistent().set(
+                &(CHUNK_KEY, backup_id.clone(), chunk_count),
+                &invoices.slice(start..end),
+            );
+            chunk_count += 1;
+            start = end;
+        }
+
+        let manifest = BackupManifest {
+            backup_id: backup_id.clone(),
+            chunk_count,
+            invoice_count: invoices.len(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(MANIFEST_KEY, backup_id.clone()), &manifest);
+        manifest
+    }
+
+    pub fn get_manifest(env: &Env, backup_id: &BytesN<32>) -> Option<BackupManifest> {
+        env.storage()
+            .persistent()
+            .get(&(MANIFEST_KEY, backup_id.clone()))
     }
 
-    /// Get invoice data from a backup
-    pub fn get_backup_data(env: &Env, backup_id: &BytesN<32>) -> Option<Vec<Invoice>> {
-        let key = (symbol_short!("bkup_data"), backup_id.clone());
-        env.storage().instance().get(&key)
+    /// Get one chunk of a backup's invoice data
+    pub fn get_chunk(env: &Env, backup_id: &BytesN<32>, chunk_index: u32) -> Option<Vec<Invoice>> {
+        env.storage()
+            .persistent()
+            .get(&(CHUNK_KEY, backup_id.clone(), chunk_index))
     }
 
-    /// Validate backup data integrity
-    pub fn validate_backup(env: &Env, backup_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+    /// Validate backup data integrity, one chunk at a time
+    ///
+    /// # Returns
+    /// * The backup's manifest
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the backup or its manifest does not exist
+    /// * `StorageError` if a chunk is missing or holds the wrong number of invoices, the counts
+    ///   disagree, or an invoice is invalid
+    pub fn validate_backup(
+        env: &Env,
+        backup_id: &BytesN<32>,
+    ) -> Result<BackupManifest, QuickLendXError> {
         let backup = Self::get_backup(env, backup_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
-
-        let data =
-            Self::get_backup_data(env, backup_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let manifest =
+            Self::get_manifest(env, backup_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
 
         // Check if count matches
-        if data.len() as u32 != backup.invoice_count {
+        if manifest.invoice_count != backup.invoice_count {
             return Err(QuickLendXError::StorageError);
         }
 
-        // Check each invoice has valid data
-        for invoice in data.iter() {
-            if invoice.amount <= 0 {
+        let mut remaining = manifest.invoice_count;
+        for chunk_index in 0..manifest.chunk_count {
+            let chunk = Self::get_chunk(env, backup_id, chunk_index)
+                .ok_or(QuickLendXError::StorageError)?;
+            // Every chunk but the last is full
+            if chunk.len() != remaining.min(BACKUP_CHUNK_SIZE) || chunk.is_empty() {
                 return Err(QuickLendXError::StorageError);
             }
+            remaining -= chunk.len();
+
+            // Check each invoice has valid data
+            for invoice in chunk.iter() {
+                if invoice.amount <= 0 {
+                    return Err(QuickLendXError::StorageError);
+                }
+            }
+        }
+        if remaining != 0 {
+            return Err(QuickLendXError::StorageError);
         }
 
-        Ok(())
+        Ok(manifest)
     }
 
     /// Clean up old backups (keep only the last N)
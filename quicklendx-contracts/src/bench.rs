@@ -0,0 +1,113 @@
+//! Cost budgets for the hot entrypoints, checked by `test_budget`.
+//!
+//! Each budget records the CPU instructions and ledger entries (read + written) metered for
+//! one representative top-level invocation in the test host. A change that raises
+//! instructions by more than `REGRESSION_TOLERANCE_BPS`, or touches more ledger entries than
+//! recorded, fails the budget tests. When a cost increase is intended, re-measure and update
+//! the budget in the same change so the new baseline is reviewed.
+//!
+//! Host metering does not model Wasm VM instantiation or transaction size, so these numbers
+//! track relative regressions rather than on-chain fees.
+
+use soroban_sdk::Env;
+
+/// Instructions may exceed the recorded budget by this much before a check fails (10%)
+pub const REGRESSION_TOLERANCE_BPS: i64 = 1_000;
+
+/// Resources metered for one top-level invocation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostMeasurement {
+    pub instructions: i64,
+    pub mem_bytes: i64,
+    pub read_entries: u32,
+    pub write_entries: u32,
+}
+
+impl CostMeasurement {
+    /// Ledger entries touched (read-only plus written)
+    pub fn entries(&self) -> u32 {
+        self.read_entries + self.write_entries
+    }
+}
+
+/// Recorded cost of an entrypoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CostBudget {
+    pub entrypoint: &'static str,
+    pub instructions: i64,
+    pub entries: u32,
+}
+
+impl CostBudget {
+    /// Highest instruction count that still passes
+    pub fn instruction_limit(&self) -> i64 {
+        self.instructions + self.instructions * REGRESSION_TOLERANCE_BPS / 10_000
+    }
+}
+
+/// Fifth bid on an invoice with four open bids
+pub const PLACE_BID: CostBudget = CostBudget {
+    entrypoint: "place_bid",
+    instructions: 2_650_000,
+    entries: 3,
+};
+
+/// Accepting one of five open bids
+pub const ACCEPT_BID_AND_FUND: CostBudget = CostBudget {
+    entrypoint: "accept_bid_and_fund",
+    instructions: 4_050_000,
+    entries: 8,
+};
+
+/// Full settlement of a funded invoice
+pub const SETTLE_INVOICE: CostBudget = CostBudget {
+    entrypoint: "settle_invoice",
+    instructions: 4_000_000,
+    entries: 8,
+};
+
+/// A page of 10 from 11 verified invoices
+pub const GET_AVAILABLE_INVOICES_PAGED: CostBudget = CostBudget {
+    entrypoint: "get_available_invoices_paged",
+    instructions: 4_700_000,
+    entries: 1,
+};
+
+/// Ranking five open bids
+pub const GET_RANKED_BIDS: CostBudget = CostBudget {
+    entrypoint: "get_ranked_bids",
+    instructions: 7_400_000,
+    entries: 1,
+};
+
+/// Resources of the most recent top-level contract invocation in `env`
+pub fn measure_last_invocation(env: &Env) -> CostMeasurement {
+    let resources = env.cost_estimate().resources();
+    CostMeasurement {
+        instructions: resources.instructions,
+        mem_bytes: resources.mem_bytes,
+        read_entries: resources.read_entries,
+        write_entries: resources.write_entries,
+    }
+}
+
+/// Whether a measurement stays within its budget
+pub fn within_budget(measurement: &CostMeasurement, budget: &CostBudget) -> bool {
+    measurement.instructions <= budget.instruction_limit()
+        && measurement.entries() <= budget.entries
+}
+
+/// Measure the last invocation and panic with both figures if it regressed its budget
+pub fn assert_within_budget(env: &Env, budget: &CostBudget) -> CostMeasurement {
+    let measurement = measure_last_invocation(env);
+    if !within_budget(&measurement, budget) {
+        panic!(
+            "{} exceeded its cost budget: measured {:?}, budget {:?} (instruction limit {})",
+            budget.entrypoint,
+            measurement,
+            budget,
+            budget.instruction_limit()
+        );
+    }
+    measurement
+}
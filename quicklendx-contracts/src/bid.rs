@@ -1,9 +1,18 @@
 use core::cmp::Ordering;
 use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Vec};
 
+use crate::bid_bond::BidBonds;
+use crate::errors::QuickLendXError;
 use crate::events::emit_bid_expired;
+use crate::list_versions::{ListIndex, ListVersions};
+use crate::rounding::{self, Rounding};
 
 const DEFAULT_BID_TTL: u64 = 7 * 24 * 60 * 60;
+const DEFAULT_MIN_BID_TTL: u64 = 60 * 60;
+const DEFAULT_MAX_BID_TTL: u64 = 30 * 24 * 60 * 60;
+const SECONDS_PER_YEAR: i128 = 365 * 24 * 60 * 60;
+/// Shortest term used for APR, so bids close to the due date do not divide by zero
+const MIN_APR_TERM: u64 = 24 * 60 * 60;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -13,6 +22,8 @@ pub enum BidStatus {
     Accepted,
     Expired,
     Cancelled,
+    /// The business proposed different terms; awaiting the investor's response
+    Countered,
 }
 
 #[contracttype]
@@ -26,6 +37,61 @@ pub struct Bid {
     pub timestamp: u64,
     pub status: BidStatus,
     pub expiration_timestamp: u64,
+    /// Discount off the invoice amount the bid was placed at, in basis points; `None` for bids
+    /// placed with explicit amounts
+    pub discount_rate_bps: Option<u32>,
+}
+
+/// Where the capital behind a bid comes from, as declared by the investor
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BidFundingSource {
+    /// The investor's own capital; assumed for bids without a declaration
+    Personal,
+    Pool,
+    CreditFacility,
+}
+
+/// Terms proposed by the business in response to a bid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterOffer {
+    pub bid_id: BytesN<32>,
+    pub proposed_amount: i128,
+    pub proposed_return: i128,
+    pub countered_at: u64,
+}
+
+/// Admin-configured bounds on how long a bid may stay open, in seconds from placement
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidTtlBounds {
+    pub min_ttl: u64,
+    pub max_ttl: u64,
+}
+
+/// Admin-configured minimum improvement over the highest open bid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BidIncrementRule {
+    /// Fixed amount in the invoice currency's smallest unit
+    Absolute(i128),
+    /// Basis points of the highest open bid amount
+    Bps(u32),
+}
+
+impl BidIncrementRule {
+    /// Smallest increment over `best_amount` allowed by this rule (at least 1)
+    pub fn increment(&self, best_amount: i128) -> i128 {
+        let increment = match self {
+            BidIncrementRule::Absolute(amount) => *amount,
+            // Round up so small bids cannot round the tick down to zero
+            BidIncrementRule::Bps(bps) => {
+                rounding::bps_of(best_amount, *bps as i128, Rounding::Ceil)
+            }
+        };
+        increment.max(1)
+    }
 }
 
 impl Bid {
@@ -33,9 +99,50 @@ impl Bid {
         current_timestamp > self.expiration_timestamp
     }
 
+    /// Placed or countered: the bid can still lead to funding
+    pub fn is_open(&self) -> bool {
+        self.status == BidStatus::Placed || self.status == BidStatus::Countered
+    }
+
+    /// Amounts for a bid at `discount_rate_bps` off the invoice amount: the investor advances
+    /// the discounted amount and is repaid the full invoice amount
+    ///
+    /// # Returns
+    /// * `(bid_amount, expected_return)`
+    pub fn discounted_amounts(invoice_amount: i128, discount_rate_bps: u32) -> (i128, i128) {
+        let (_, bid_amount) = rounding::split_bps(invoice_amount, discount_rate_bps as i128);
+        (bid_amount, invoice_amount)
+    }
+
+    /// Annualized return in basis points from placement to `due_date`
+    ///
+    /// Bids on the same invoice share a due date, so this makes offers with different
+    /// amounts comparable. Terms shorter than a day are treated as one day.
+    pub fn effective_apr_bps(&self, due_date: u64) -> i128 {
+        if self.bid_amount <= 0 {
+            return 0;
+        }
+        let term = due_date.saturating_sub(self.timestamp).max(MIN_APR_TERM) as i128;
+        rounding::mul_div(
+            self.expected_return
+                .saturating_sub(self.bid_amount)
+                .saturating_mul(rounding::BPS),
+            SECONDS_PER_YEAR,
+            self.bid_amount.saturating_mul(term),
+            Rounding::HalfEven,
+        )
+    }
+
     pub fn default_expiration(now: u64) -> u64 {
         now.saturating_add(DEFAULT_BID_TTL)
     }
+
+    /// Expiration after one more default TTL, capped at the maximum TTL from `now`
+    pub fn extended_expiration(&self, now: u64, bounds: &BidTtlBounds) -> u64 {
+        self.expiration_timestamp
+            .saturating_add(DEFAULT_BID_TTL)
+            .min(now.saturating_add(bounds.max_ttl))
+    }
 }
 
 pub struct BidStorage;
@@ -45,6 +152,157 @@ impl BidStorage {
         (symbol_short!("bids"), invoice_id.clone())
     }
 
+    fn ttl_bounds_key() -> soroban_sdk::Symbol {
+        symbol_short!("bid_ttl")
+    }
+
+    /// Get the bid TTL bounds, defaulting to 1 hour to 30 days
+    pub fn get_ttl_bounds(env: &Env) -> BidTtlBounds {
+        env.storage()
+            .instance()
+            .get(&Self::ttl_bounds_key())
+            .unwrap_or(BidTtlBounds {
+                min_ttl: DEFAULT_MIN_BID_TTL,
+                max_ttl: DEFAULT_MAX_BID_TTL,
+            })
+    }
+
+    pub fn set_ttl_bounds(
+        env: &Env,
+        min_ttl: u64,
+        max_ttl: u64,
+    ) -> Result<BidTtlBounds, QuickLendXError> {
+        if min_ttl == 0 || min_ttl > max_ttl {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        let bounds = BidTtlBounds { min_ttl, max_ttl };
+        env.storage()
+            .instance()
+            .set(&Self::ttl_bounds_key(), &bounds);
+        Ok(bounds)
+    }
+
+    /// Check that an investor-chosen expiration falls within the configured TTL bounds
+    pub fn validate_expiration(
+        env: &Env,
+        now: u64,
+        expiration_timestamp: u64,
+    ) -> Result<(), QuickLendXError> {
+        let bounds = Self::get_ttl_bounds(env);
+        if expiration_timestamp < now.saturating_add(bounds.min_ttl)
+            || expiration_timestamp > now.saturating_add(bounds.max_ttl)
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+
+    fn counter_offer_key(bid_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("bid_ctr"), bid_id.clone())
+    }
+
+    pub fn store_counter_offer(env: &Env, counter: &CounterOffer) {
+        env.storage()
+            .instance()
+            .set(&Self::counter_offer_key(&counter.bid_id), counter);
+    }
+
+    pub fn get_counter_offer(env: &Env, bid_id: &BytesN<32>) -> Option<CounterOffer> {
+        env.storage()
+            .instance()
+            .get(&Self::counter_offer_key(bid_id))
+    }
+
+    pub fn remove_counter_offer(env: &Env, bid_id: &BytesN<32>) {
+        env.storage()
+            .instance()
+            .remove(&Self::counter_offer_key(bid_id));
+    }
+
+    fn funding_currency_key(bid_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("bid_fcur"), bid_id.clone())
+    }
+
+    /// Record that the bid will be funded in `currency` rather than the invoice currency
+    pub fn set_funding_currency(env: &Env, bid_id: &BytesN<32>, currency: &Address) {
+        env.storage()
+            .instance()
+            .set(&Self::funding_currency_key(bid_id), currency);
+    }
+
+    /// Token the bid will be funded in, if not the invoice currency
+    pub fn get_funding_currency(env: &Env, bid_id: &BytesN<32>) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Self::funding_currency_key(bid_id))
+    }
+
+    fn funding_source_key(bid_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("bid_fsrc"), bid_id.clone())
+    }
+
+    /// Record the investor's declared funding source for the bid
+    pub fn set_funding_source(env: &Env, bid_id: &BytesN<32>, source: &BidFundingSource) {
+        env.storage()
+            .instance()
+            .set(&Self::funding_source_key(bid_id), source);
+    }
+
+    /// Declared funding source of the bid, `Personal` when none was declared
+    pub fn get_funding_source(env: &Env, bid_id: &BytesN<32>) -> BidFundingSource {
+        env.storage()
+            .instance()
+            .get(&Self::funding_source_key(bid_id))
+            .unwrap_or(BidFundingSource::Personal)
+    }
+
+    fn increment_rule_key() -> soroban_sdk::Symbol {
+        symbol_short!("bid_tick")
+    }
+
+    /// Get the bid increment rule, if one is configured
+    pub fn get_increment_rule(env: &Env) -> Option<BidIncrementRule> {
+        env.storage().instance().get(&Self::increment_rule_key())
+    }
+
+    /// Set or clear the bid increment rule
+    pub fn set_increment_rule(
+        env: &Env,
+        rule: Option<BidIncrementRule>,
+    ) -> Result<(), QuickLendXError> {
+        match rule {
+            Some(rule) => {
+                let valid = match rule {
+                    BidIncrementRule::Absolute(amount) => amount > 0,
+                    BidIncrementRule::Bps(bps) => bps > 0 && bps <= 10_000,
+                };
+                if !valid {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                env.storage()
+                    .instance()
+                    .set(&Self::increment_rule_key(), &rule);
+            }
+            None => env.storage().instance().remove(&Self::increment_rule_key()),
+        }
+        Ok(())
+    }
+
+    /// Highest amount among the invoice's open bids
+    pub fn get_highest_open_bid_amount(env: &Env, invoice_id: &BytesN<32>) -> Option<i128> {
+        let mut highest: Option<i128> = None;
+        for bid_id in Self::get_bids_for_invoice(env, invoice_id).iter() {
+            if let Some(bid) = Self::get_bid(env, &bid_id) {
+                if bid.status == BidStatus::Placed
+                    && highest.is_none_or(|amount| bid.bid_amount > amount)
+                {
+                    highest = Some(bid.bid_amount);
+                }
+            }
+        }
+        highest
+    }
+
     fn investor_bids_key(investor: &Address) -> (soroban_sdk::Symbol, Address) {
         (symbol_short!("bid_inv"), investor.clone())
     }
@@ -70,6 +328,7 @@ impl BidStorage {
         if !exists {
             bids.push_back(bid_id.clone());
             env.storage().instance().set(&key, &bids);
+            ListVersions::bump(env, &[ListIndex::InvestorBids(investor.clone())]);
         }
     }
 
@@ -77,12 +336,32 @@ impl BidStorage {
         env.storage().instance().set(&bid.bid_id, bid);
         // Add to investor index
         Self::add_to_investor_bids(env, &bid.investor, &bid.bid_id);
+        crate::watchers::InvoiceWatchers::add(
+            env,
+            &bid.invoice_id,
+            &bid.investor,
+            crate::watchers::WatcherRole::Bidder,
+        );
     }
     pub fn get_bid(env: &Env, bid_id: &BytesN<32>) -> Option<Bid> {
         env.storage().instance().get(bid_id)
     }
+    /// Check whether a bid is stored under `bid_id` without loading it. Bid IDs
+    /// carry the `0xB1D0` prefix, which keeps other entities sharing the
+    /// instance key space from matching.
+    pub fn exists(env: &Env, bid_id: &BytesN<32>) -> bool {
+        let bytes = bid_id.to_array();
+        bytes[0] == 0xB1 && bytes[1] == 0xD0 && env.storage().instance().has(bid_id)
+    }
     pub fn update_bid(env: &Env, bid: &Bid) {
         env.storage().instance().set(&bid.bid_id, bid);
+        ListVersions::bump(
+            env,
+            &[
+                ListIndex::InvoiceBids(bid.invoice_id.clone()),
+                ListIndex::InvestorBids(bid.investor.clone()),
+            ],
+        );
     }
     pub fn get_bids_for_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<BytesN<32>> {
         env.storage()
@@ -106,6 +385,7 @@ impl BidStorage {
             env.storage()
                 .instance()
                 .set(&Self::invoice_key(invoice_id), &bids);
+            ListVersions::bump(env, &[ListIndex::InvoiceBids(invoice_id.clone())]);
         }
     }
     fn refresh_expired_bids(env: &Env, invoice_id: &BytesN<32>) -> u32 {
@@ -117,10 +397,12 @@ impl BidStorage {
         while idx < bid_ids.len() {
             let bid_id = bid_ids.get(idx).unwrap();
             if let Some(mut bid) = Self::get_bid(env, &bid_id) {
-                if bid.status == BidStatus::Placed && bid.is_expired(current_timestamp) {
+                if bid.is_open() && bid.is_expired(current_timestamp) {
                     bid.status = BidStatus::Expired;
                     Self::update_bid(env, &bid);
                     emit_bid_expired(env, &bid);
+                    // On failure the bond stays recorded for `reclaim_bid_bond`
+                    let _ = BidBonds::release(env, &bid_id);
                     expired += 1;
                 } else {
                     active.push_back(bid_id);
@@ -140,6 +422,20 @@ impl BidStorage {
         Self::refresh_expired_bids(env, invoice_id)
     }
 
+    /// Open bids placed by `investor` on the invoice that have expired but not been cleaned up
+    pub fn count_expired_bids_by(env: &Env, invoice_id: &BytesN<32>, investor: &Address) -> u32 {
+        let current_timestamp = env.ledger().timestamp();
+        let mut count = 0u32;
+        for bid_id in Self::get_bids_for_invoice(env, invoice_id).iter() {
+            if let Some(bid) = Self::get_bid(env, &bid_id) {
+                if &bid.investor == investor && bid.is_open() && bid.is_expired(current_timestamp) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     pub fn get_bid_records_for_invoice(env: &Env, invoice_id: &BytesN<32>) -> Vec<Bid> {
         let _ = Self::refresh_expired_bids(env, invoice_id);
         let mut bids = Vec::new(env);
@@ -262,6 +558,34 @@ impl BidStorage {
 
         ranked
     }
+    /// Placed bids ordered from the lowest effective APR (cheapest financing for the business)
+    /// to the highest; ties go to the larger advance, then the earlier bid
+    pub fn rank_bids_by_apr(env: &Env, invoice_id: &BytesN<32>, due_date: u64) -> Vec<Bid> {
+        let mut ranked: Vec<Bid> = Vec::new(env);
+        for bid in Self::get_bid_records_for_invoice(env, invoice_id).iter() {
+            if bid.status != BidStatus::Placed {
+                continue;
+            }
+            let apr = bid.effective_apr_bps(due_date);
+            let mut position = ranked.len();
+            for idx in 0..ranked.len() {
+                let other = ranked.get(idx).unwrap();
+                let other_apr = other.effective_apr_bps(due_date);
+                let ahead = apr < other_apr
+                    || (apr == other_apr
+                        && (bid.bid_amount > other.bid_amount
+                            || (bid.bid_amount == other.bid_amount
+                                && bid.timestamp < other.timestamp)));
+                if ahead {
+                    position = idx;
+                    break;
+                }
+            }
+            ranked.insert(position, bid);
+        }
+        ranked
+    }
+
     /// Generates a unique 32-byte bid ID using timestamp and a simple counter.
     /// This approach avoids potential serialization issues with large counters.
     pub fn generate_unique_bid_id(env: &Env) -> BytesN<32> {
@@ -0,0 +1,144 @@
+//! Per-invoice bid analytics for businesses.
+//!
+//! Statistics are updated as each bid is placed, so reading them does not rescan the invoice's
+//! bids: the discounts seen so far are kept sorted for the median, and the tier of each bid's
+//! investor is tallied. A bid's discount is how far its advance falls below the invoice amount,
+//! in basis points.
+
+use crate::bid::{Bid, BidStorage};
+use crate::invoice::Invoice;
+use crate::rounding::{self, Rounding};
+use crate::verification::InvestorTier;
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, Symbol, Vec};
+
+const BID_STATS_KEY: Symbol = symbol_short!("bid_stat");
+
+/// Number of bids placed by investors of one tier
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TierBidCount {
+    pub tier: InvestorTier,
+    pub bids: u32,
+}
+
+/// Running statistics kept for an invoice's bids
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BidStats {
+    pub first_bid_at: u64,
+    /// Discounts of every bid placed, ascending
+    pub discounts_bps: Vec<i128>,
+    pub tiers: Vec<TierBidCount>,
+}
+
+/// What a business sees when deciding whether to accept a bid or wait for more
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvoiceBidAnalytics {
+    pub invoice_id: BytesN<32>,
+    /// Bids placed, including ones since withdrawn, expired or rejected
+    pub bid_count: u32,
+    /// Lowest discount any bid offered; 0 without bids
+    pub min_discount_bps: i128,
+    /// Median discount over all bids placed, rounded half to even; 0 without bids
+    pub median_discount_bps: i128,
+    /// Lowest discount among the bids that can still be accepted
+    pub best_open_discount_bps: Option<i128>,
+    /// Seconds from invoice upload to its first bid
+    pub time_to_first_bid: Option<u64>,
+    /// Bids per investor tier, in order of first appearance
+    pub tier_distribution: Vec<TierBidCount>,
+}
+
+pub struct BidAnalytics;
+
+impl BidAnalytics {
+    /// Discount of `bid` off the invoice amount, in basis points
+    pub fn discount_bps(invoice: &Invoice, bid: &Bid) -> i128 {
+        rounding::mul_div(
+            invoice.amount.saturating_sub(bid.bid_amount),
+            rounding::BPS,
+            invoice.amount,
+            Rounding::HalfEven,
+        )
+    }
+
+    fn get_stats(env: &Env, invoice_id: &BytesN<32>) -> Option<BidStats> {
+        env.storage()
+            .instance()
+            .get(&(BID_STATS_KEY, invoice_id.clone()))
+    }
+
+    /// Fold a newly placed bid into its invoice's statistics
+    pub fn record_bid(env: &Env, invoice: &Invoice, bid: &Bid, tier: InvestorTier) {
+        let mut stats = Self::get_stats(env, &invoice.id).unwrap_or(BidStats {
+            first_bid_at: bid.timestamp,
+            discounts_bps: Vec::new(env),
+            tiers: Vec::new(env),
+        });
+
+        let discount = Self::discount_bps(invoice, bid);
+        let position = stats
+            .discounts_bps
+            .iter()
+            .position(|existing| existing > discount)
+            .unwrap_or(stats.discounts_bps.len() as usize);
+        stats.discounts_bps.insert(position as u32, discount);
+
+        match stats.tiers.iter().position(|count| count.tier == tier) {
+            Some(idx) => {
+                let mut count = stats.tiers.get_unchecked(idx as u32);
+                count.bids += 1;
+                stats.tiers.set(idx as u32, count);
+            }
+            None => stats.tiers.push_back(TierBidCount { tier, bids: 1 }),
+        }
+
+        env.storage()
+            .instance()
+            .set(&(BID_STATS_KEY, invoice.id.clone()), &stats);
+    }
+
+    pub fn get(env: &Env, invoice: &Invoice) -> InvoiceBidAnalytics {
+        let stats = Self::get_stats(env, &invoice.id);
+        let discounts = stats
+            .as_ref()
+            .map_or_else(|| Vec::new(env), |stats| stats.discounts_bps.clone());
+        let count = discounts.len();
+        let median_discount_bps = match count {
+            0 => 0,
+            _ if count % 2 == 1 => discounts.get_unchecked(count / 2),
+            _ => rounding::mul_div(
+                discounts
+                    .get_unchecked(count / 2 - 1)
+                    .saturating_add(discounts.get_unchecked(count / 2)),
+                1,
+                2,
+                Rounding::HalfEven,
+            ),
+        };
+
+        let mut best_open_discount_bps: Option<i128> = None;
+        for bid in BidStorage::get_bid_records_for_invoice(env, &invoice.id).iter() {
+            if !bid.is_open() || bid.is_expired(env.ledger().timestamp()) {
+                continue;
+            }
+            let discount = Self::discount_bps(invoice, &bid);
+            if best_open_discount_bps.is_none_or(|best| discount < best) {
+                best_open_discount_bps = Some(discount);
+            }
+        }
+
+        InvoiceBidAnalytics {
+            invoice_id: invoice.id.clone(),
+            bid_count: count,
+            min_discount_bps: discounts.first().unwrap_or(0),
+            median_discount_bps,
+            best_open_discount_bps,
+            time_to_first_bid: stats
+                .as_ref()
+                .map(|stats| stats.first_bid_at.saturating_sub(invoice.created_at)),
+            tier_distribution: stats.map_or_else(|| Vec::new(env), |stats| stats.tiers),
+        }
+    }
+}
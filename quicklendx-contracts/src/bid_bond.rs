@@ -0,0 +1,154 @@
+//! Bid bonds: refundable deposits investors lock when placing a bid, so that bids which are
+//! never honored cost something.
+//!
+//! The bond is an admin-configured share of the bid amount, held by the contract. It is
+//! returned when the bid is withdrawn, expires or loses, and counts toward the escrowed amount
+//! when the bid funds the invoice.
+
+use crate::bid::{Bid, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_bid_bond_locked, emit_bid_bond_released};
+use crate::payments::transfer_funds;
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
+
+const BOND_BPS_KEY: Symbol = symbol_short!("bond_bps");
+/// Upper bound on the bond rate (10% of the bid amount)
+pub const MAX_BID_BOND_BPS: u32 = 1_000;
+
+/// Deposit held by the contract for an open bid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidBond {
+    pub bid_id: BytesN<32>,
+    pub investor: Address,
+    pub currency: Address,
+    pub amount: i128,
+    pub locked_at: u64,
+}
+
+pub struct BidBonds;
+
+impl BidBonds {
+    fn bond_key(bid_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+        (symbol_short!("bid_bond"), bid_id.clone())
+    }
+
+    /// Bond rate in basis points of the bid amount; 0 when bonds are disabled
+    pub fn get_bond_bps(env: &Env) -> u32 {
+        env.storage().instance().get(&BOND_BPS_KEY).unwrap_or(0)
+    }
+
+    pub fn set_bond_bps(env: &Env, bps: u32) -> Result<(), QuickLendXError> {
+        if bps > MAX_BID_BOND_BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage().instance().set(&BOND_BPS_KEY, &bps);
+        Ok(())
+    }
+
+    /// Bond required for a bid of `bid_amount`, rounded up
+    pub fn required_bond(env: &Env, bid_amount: i128) -> i128 {
+        let bps = Self::get_bond_bps(env);
+        if bps == 0 || bid_amount <= 0 {
+            return 0;
+        }
+        rounding::bps_of(bid_amount, bps as i128, Rounding::Ceil)
+    }
+
+    pub fn get_bond(env: &Env, bid_id: &BytesN<32>) -> Option<BidBond> {
+        env.storage().instance().get(&Self::bond_key(bid_id))
+    }
+
+    /// Transfer the required bond from the investor into the contract
+    ///
+    /// # Returns
+    /// * The bond amount locked (0 when bonds are disabled)
+    pub fn lock(env: &Env, bid: &Bid, currency: &Address) -> Result<i128, QuickLendXError> {
+        let amount = Self::required_bond(env, bid.bid_amount);
+        if amount == 0 {
+            return Ok(0);
+        }
+        transfer_funds(
+            env,
+            currency,
+            &bid.investor,
+            &env.current_contract_address(),
+            amount,
+        )?;
+        let bond = BidBond {
+            bid_id: bid.bid_id.clone(),
+            investor: bid.investor.clone(),
+            currency: currency.clone(),
+            amount,
+            locked_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&Self::bond_key(&bid.bid_id), &bond);
+        emit_bid_bond_locked(env, &bond);
+        Ok(amount)
+    }
+
+    /// Return a bid's bond to the investor; no-op when no bond is held
+    pub fn release(env: &Env, bid_id: &BytesN<32>) -> Result<i128, QuickLendXError> {
+        let bond = match Self::get_bond(env, bid_id) {
+            Some(bond) => bond,
+            None => return Ok(0),
+        };
+        transfer_funds(
+            env,
+            &bond.currency,
+            &env.current_contract_address(),
+            &bond.investor,
+            bond.amount,
+        )?;
+        env.storage().instance().remove(&Self::bond_key(bid_id));
+        emit_bid_bond_released(env, &bond);
+        Ok(bond.amount)
+    }
+
+    /// Part of the bond that counts toward funding `funding_amount` from this bid
+    pub fn prepaid_amount(env: &Env, bid_id: &BytesN<32>, funding_amount: i128) -> i128 {
+        Self::get_bond(env, bid_id).map_or(0, |bond| bond.amount.min(funding_amount))
+    }
+
+    /// Consume the bond once its bid has funded an invoice, returning any part that exceeds
+    /// the funded amount (possible after a lower counter-offer)
+    pub fn apply_to_funding(
+        env: &Env,
+        bid_id: &BytesN<32>,
+        funding_amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let bond = match Self::get_bond(env, bid_id) {
+            Some(bond) => bond,
+            None => return Ok(()),
+        };
+        let excess = bond.amount.saturating_sub(funding_amount);
+        if excess > 0 {
+            transfer_funds(
+                env,
+                &bond.currency,
+                &env.current_contract_address(),
+                &bond.investor,
+                excess,
+            )?;
+        }
+        env.storage().instance().remove(&Self::bond_key(bid_id));
+        Ok(())
+    }
+
+    /// Return the bonds of every other bid on an invoice that can no longer be funded
+    pub fn release_losing_bonds(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        winning_bid_id: Option<&BytesN<32>>,
+    ) -> Result<(), QuickLendXError> {
+        for bid_id in BidStorage::get_bids_for_invoice(env, invoice_id).iter() {
+            if winning_bid_id != Some(&bid_id) {
+                Self::release(env, &bid_id)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Marketplace boosts: a business pays to feature a verified invoice for a number of days.
+//!
+//! The admin sets the fee per day, charged in the invoice's currency and routed to the treasury,
+//! and the longest boost that can be bought at once. Boosting an invoice that is already featured
+//! extends its boost from the current expiry. Featured invoices are listed first by
+//! `get_available_invoices_paged` until `boosted_until` passes.
+
+use crate::audit::{log_operation, AuditOperation};
+use crate::errors::QuickLendXError;
+use crate::events::emit_invoice_boosted;
+use crate::fees::FeeManager;
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::invoice_freeze::InvoiceFreezes;
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, String, Symbol};
+
+const BOOST_CONFIG_KEY: Symbol = symbol_short!("bst_cfg");
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Price and limit of marketplace boosts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoostConfig {
+    /// Fee per day of boost, in the invoice's currency
+    pub fee_per_day: i128,
+    /// Longest boost that can be bought in one call
+    pub max_days: u32,
+}
+
+pub struct InvoiceBoosts;
+
+impl InvoiceBoosts {
+    pub fn get_config(env: &Env) -> Option<BoostConfig> {
+        env.storage().instance().get(&BOOST_CONFIG_KEY)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if the fee is negative or `max_days` is 0
+    pub fn set_config(env: &Env, config: &BoostConfig) -> Result<(), QuickLendXError> {
+        if config.fee_per_day < 0 || config.max_days == 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage().instance().set(&BOOST_CONFIG_KEY, config);
+        Ok(())
+    }
+
+    /// Charge the business for `days` of boost and feature the invoice; the caller has checked
+    /// the business's authorization
+    ///
+    /// # Returns
+    /// * The updated invoice
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `OperationNotAllowed` if the invoice is frozen or boosts are not
+    ///   configured
+    /// * `InvalidStatus` if the invoice is not Verified
+    /// * `InvalidAmount` if `days` is 0 or above `max_days`
+    /// * Transfer errors
+    pub fn boost(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        days: u32,
+    ) -> Result<Invoice, QuickLendXError> {
+        let mut invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let config = Self::get_config(env).ok_or(QuickLendXError::OperationNotAllowed)?;
+        if days == 0 || days > config.max_days {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let fee = config.fee_per_day.saturating_mul(days as i128);
+        if fee > 0 {
+            FeeManager::route_platform_fee(env, &invoice.currency, &invoice.business, fee)?;
+        }
+
+        let now = env.ledger().timestamp();
+        let start = invoice
+            .boosted_until
+            .filter(|until| *until > now)
+            .unwrap_or(now);
+        let boosted_until = start.saturating_add((days as u64).saturating_mul(SECONDS_PER_DAY));
+        invoice.boosted_until = Some(boosted_until);
+        InvoiceStorage::update_invoice(env, &invoice);
+
+        log_operation(
+            env,
+            invoice_id.clone(),
+            AuditOperation::InvoiceBoosted,
+            invoice.business.clone(),
+            None,
+            Some(String::from_str(env, "Invoice boosted")),
+            Some(fee),
+            None,
+        );
+        emit_invoice_boosted(env, &invoice, days, fee);
+        Ok(invoice)
+    }
+}
@@ -0,0 +1,175 @@
+//! Post-default collections: one collection case per defaulted invoice.
+//!
+//! A case opens when the invoice defaults, with what the business owed at that moment as its
+//! target. Each recovery collected through `record_default_recovery` is added to the case's
+//! history and running total, moving it to `PartiallyRecovered` and then `Recovered` once the
+//! target is met. The admin can write off a case it no longer pursues, after which no further
+//! recoveries are accepted.
+
+use crate::errors::QuickLendXError;
+use crate::events::emit_collection_case_updated;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const CASE_KEY: Symbol = symbol_short!("col_case");
+const HISTORY_KEY: Symbol = symbol_short!("col_hist");
+/// Most recoveries kept in a case's history; older entries are dropped but still counted in
+/// the case's totals
+pub const MAX_COLLECTION_ENTRIES: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CollectionStatus {
+    /// Defaulted with nothing recovered yet
+    Open,
+    PartiallyRecovered,
+    /// Recoveries have reached the amount due at default
+    Recovered,
+    /// Closed by the admin; no further recoveries are accepted
+    WrittenOff,
+}
+
+/// Collections on a defaulted invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionCase {
+    pub invoice_id: BytesN<32>,
+    /// Face value plus late penalties owed when the invoice defaulted
+    pub amount_due: i128,
+    pub total_recovered: i128,
+    /// Part of `amount_due` still to be recovered
+    pub outstanding: i128,
+    pub status: CollectionStatus,
+    pub opened_at: u64,
+    pub updated_at: u64,
+    /// When the case was recovered in full or written off
+    pub closed_at: Option<u64>,
+}
+
+/// A recovery collected on a defaulted invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionEntry {
+    pub payer: Address,
+    pub amount: i128,
+    /// Part paid to the investor
+    pub investor_amount: i128,
+    /// Part returned to the insurance pool as subrogation of the claim it paid
+    pub clawback_amount: i128,
+    pub recorded_at: u64,
+}
+
+pub struct Collections;
+
+impl Collections {
+    pub fn get_case(env: &Env, invoice_id: &BytesN<32>) -> Option<CollectionCase> {
+        env.storage()
+            .instance()
+            .get(&(CASE_KEY, invoice_id.clone()))
+    }
+
+    fn set_case(env: &Env, case: &CollectionCase) {
+        env.storage()
+            .instance()
+            .set(&(CASE_KEY, case.invoice_id.clone()), case);
+        emit_collection_case_updated(env, case);
+    }
+
+    /// Recoveries collected on the invoice, oldest first
+    pub fn get_history(env: &Env, invoice_id: &BytesN<32>) -> Vec<CollectionEntry> {
+        env.storage()
+            .instance()
+            .get(&(HISTORY_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Open the collection case of an invoice that just defaulted owing `amount_due`. Does
+    /// nothing if the invoice already has one.
+    pub fn open(env: &Env, invoice_id: &BytesN<32>, amount_due: i128) -> CollectionCase {
+        if let Some(case) = Self::get_case(env, invoice_id) {
+            return case;
+        }
+        let now = env.ledger().timestamp();
+        let case = CollectionCase {
+            invoice_id: invoice_id.clone(),
+            amount_due,
+            total_recovered: 0,
+            outstanding: amount_due.max(0),
+            status: CollectionStatus::Open,
+            opened_at: now,
+            updated_at: now,
+            closed_at: None,
+        };
+        Self::set_case(env, &case);
+        case
+    }
+
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice's case was written off
+    pub fn ensure_collectable(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        match Self::get_case(env, invoice_id) {
+            Some(case) if case.status == CollectionStatus::WrittenOff => {
+                Err(QuickLendXError::OperationNotAllowed)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Add a recovery to the invoice's case, opening it against `amount_due` if the invoice
+    /// defaulted before cases were tracked
+    pub fn record(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        amount_due: i128,
+        entry: CollectionEntry,
+    ) -> CollectionCase {
+        let mut case = Self::open(env, invoice_id, amount_due);
+        case.total_recovered = case.total_recovered.saturating_add(entry.amount);
+        case.outstanding = case.amount_due.saturating_sub(case.total_recovered).max(0);
+        case.updated_at = entry.recorded_at;
+        if case.outstanding == 0 {
+            if case.status != CollectionStatus::Recovered {
+                case.closed_at = Some(entry.recorded_at);
+            }
+            case.status = CollectionStatus::Recovered;
+        } else {
+            case.status = CollectionStatus::PartiallyRecovered;
+        }
+
+        let mut history = Self::get_history(env, invoice_id);
+        if history.len() >= MAX_COLLECTION_ENTRIES {
+            history.pop_front();
+        }
+        history.push_back(entry);
+        env.storage()
+            .instance()
+            .set(&(HISTORY_KEY, invoice_id.clone()), &history);
+
+        Self::set_case(env, &case);
+        case
+    }
+
+    /// Stop pursuing the rest of a defaulted invoice
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no collection case
+    /// * `InvalidStatus` if the case was already recovered in full or written off
+    pub fn write_off(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Result<CollectionCase, QuickLendXError> {
+        let mut case =
+            Self::get_case(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if matches!(
+            case.status,
+            CollectionStatus::Recovered | CollectionStatus::WrittenOff
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let now = env.ledger().timestamp();
+        case.status = CollectionStatus::WrittenOff;
+        case.updated_at = now;
+        case.closed_at = Some(now);
+        Self::set_case(env, &case);
+        Ok(case)
+    }
+}
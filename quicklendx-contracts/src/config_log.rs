@@ -0,0 +1,194 @@
+//! Configuration change log: a platform-wide history of admin parameter changes (fees,
+//! treasury, limits, currencies, grace periods), kept separate from the per-invoice audit trail.
+//!
+//! Entries are stored under sequential ids so the history can be paged without loading it whole.
+
+use crate::bid::BidIncrementRule;
+use crate::fees::{FeeType, ParticipantTier};
+use crate::invoice::InvoiceCategory;
+use crate::settlement::SettlementTimelockPolicy;
+use crate::verification::InvoiceSignoffPolicy;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+const CONFIG_CHANGE_COUNT_KEY: Symbol = symbol_short!("cfg_cnt");
+/// Maximum number of entries returned by a single history query
+pub const MAX_CONFIG_HISTORY_PAGE: u32 = 100;
+
+/// Configuration parameter that was changed
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigParameter {
+    PlatformFeeBps,
+    TreasuryAddress,
+    LatePenaltyInvestorShareBps,
+    TierFeeRate(ParticipantTier),
+    FeeBaseBps(FeeType),
+    FeeMin(FeeType),
+    FeeMax(FeeType),
+    FeeActive(FeeType),
+    RevenueTreasury,
+    RevenueTreasuryShareBps,
+    RevenueDeveloperShareBps,
+    RevenuePlatformShareBps,
+    RevenueAutoDistribution,
+    RevenueMinDistribution,
+    CurrencyWhitelisted(Address),
+    Relayer(Address),
+    BidMinTtl,
+    BidMaxTtl,
+    /// `I128` for an absolute increment, `U32` for basis points
+    BidIncrement,
+    BidBondBps,
+    CategoryCap(InvoiceCategory),
+    MinInvoiceAmount,
+    MaxDueDateDays,
+    GracePeriodSeconds,
+    KeeperRewardPerBid,
+    KeeperMaxRewardPerCall,
+    KeeperFeeShareBps,
+    MaxActiveBidsPerInvoice,
+    MaxActiveBidsPerInvestor,
+    MaxBidsPerWindow,
+    BidRateWindowSeconds,
+    InvoiceSignoffThreshold,
+    InvoiceSignoffsRequired,
+    InvoiceVerifier(Address),
+    MaxDescriptionLength,
+    MaxTagLength,
+    MaxTagsPerInvoice,
+    MaxFeedbackLength,
+    MaxDisputeReasonLength,
+    MaxDisputeEvidenceLength,
+    /// `U32` from `CharacterPolicy::code`
+    ContentCharacterPolicy,
+    /// `U32` count of blocked terms
+    BlockedTerms,
+    SettlementTimelockThreshold,
+    SettlementTimelockDelay,
+    LateFeeDailyBps,
+    LateFeeAccrualCapBps,
+    VerificationCoolingOff,
+    SuitabilityDisclosureVersion,
+    KeeperSettlementReward,
+    EscrowReleaseApprovalRequired,
+}
+
+/// Value of a configuration parameter before or after a change
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigValue {
+    /// The parameter was not configured
+    Unset,
+    Bool(bool),
+    U32(u32),
+    U64(u64),
+    I128(i128),
+    Address(Address),
+}
+
+impl ConfigValue {
+    pub fn from_bid_increment(rule: Option<BidIncrementRule>) -> Self {
+        match rule {
+            Some(BidIncrementRule::Absolute(amount)) => ConfigValue::I128(amount),
+            Some(BidIncrementRule::Bps(bps)) => ConfigValue::U32(bps),
+            None => ConfigValue::Unset,
+        }
+    }
+
+    /// `(threshold, required sign-offs)` values of a sign-off policy
+    pub fn from_signoff_policy(policy: Option<InvoiceSignoffPolicy>) -> (Self, Self) {
+        match policy {
+            Some(policy) => (
+                ConfigValue::I128(policy.threshold_amount),
+                ConfigValue::U32(policy.required_signoffs),
+            ),
+            None => (ConfigValue::Unset, ConfigValue::Unset),
+        }
+    }
+
+    /// `(threshold, delay)` values of a settlement timelock policy
+    pub fn from_settlement_timelock(policy: Option<SettlementTimelockPolicy>) -> (Self, Self) {
+        match policy {
+            Some(policy) => (
+                ConfigValue::I128(policy.threshold_amount),
+                ConfigValue::U64(policy.delay_seconds),
+            ),
+            None => (ConfigValue::Unset, ConfigValue::Unset),
+        }
+    }
+}
+
+/// A single recorded configuration change
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigChange {
+    pub change_id: u64,
+    pub parameter: ConfigParameter,
+    pub old_value: ConfigValue,
+    pub new_value: ConfigValue,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+pub struct ConfigLog;
+
+impl ConfigLog {
+    fn change_key(change_id: u64) -> (Symbol, u64) {
+        (symbol_short!("cfg_chg"), change_id)
+    }
+
+    /// Number of changes recorded so far
+    pub fn count(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&CONFIG_CHANGE_COUNT_KEY)
+            .unwrap_or(0u64)
+    }
+
+    /// Record a change; no-op when the value did not actually change
+    pub fn record(
+        env: &Env,
+        parameter: ConfigParameter,
+        old_value: ConfigValue,
+        new_value: ConfigValue,
+        actor: &Address,
+    ) {
+        if old_value == new_value {
+            return;
+        }
+        let change_id = Self::count(env);
+        let change = ConfigChange {
+            change_id,
+            parameter,
+            old_value,
+            new_value,
+            actor: actor.clone(),
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&Self::change_key(change_id), &change);
+        env.storage()
+            .instance()
+            .set(&CONFIG_CHANGE_COUNT_KEY, &(change_id + 1));
+    }
+
+    /// Get changes oldest first, starting at `offset`; `limit` is capped at
+    /// `MAX_CONFIG_HISTORY_PAGE`
+    pub fn get_history(env: &Env, offset: u32, limit: u32) -> Vec<ConfigChange> {
+        let mut result = Vec::new(env);
+        let count = Self::count(env);
+        let start = offset as u64;
+        let end = start
+            .saturating_add(limit.min(MAX_CONFIG_HISTORY_PAGE) as u64)
+            .min(count);
+        let mut change_id = start;
+        while change_id < end {
+            if let Some(change) = env.storage().instance().get(&Self::change_key(change_id)) {
+                result.push_back(change);
+            }
+            change_id += 1;
+        }
+        result
+    }
+}
@@ -1,17 +1,38 @@
 //! Multi-currency whitelist: admin-managed list of token addresses allowed for invoice currency.
 //! Rejects invoice creation and bids for non-whitelisted tokens (e.g. USDC, EURC, stablecoins).
+//! Addresses are probed for the token interface before they are whitelisted, so a non-token
+//! address cannot be added and later break escrow creation for every invoice that uses it.
+//!
+//! Investors may fund an invoice in another whitelisted token once the admin configures a
+//! conversion oracle for the pair; `CurrencyConversion` prices the funding leg through it.
 
 use crate::admin::AdminStorage;
+use crate::config_log::{ConfigLog, ConfigParameter, ConfigValue};
 use crate::errors::QuickLendXError;
-use soroban_sdk::{symbol_short, Address, Env, Vec};
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contractclient, symbol_short, token, Address, Env, Symbol, Vec};
 
 const WHITELIST_KEY: soroban_sdk::Symbol = symbol_short!("curr_wl");
+const CONVERSION_ORACLE_KEY: Symbol = symbol_short!("fx_orcl");
+
+/// Scale of the rates returned by conversion oracles: a rate of `RATE_SCALE` converts one unit
+/// of the invoice currency into one unit of the funding currency
+pub const RATE_SCALE: i128 = 10_000_000;
+
+/// Interface a conversion oracle (a price feed or an AMM adapter) must implement
+#[contractclient(name = "ConversionOracleClient")]
+pub trait ConversionOracle {
+    /// Smallest units of `quote` worth one smallest unit of `base`, times `RATE_SCALE`
+    fn rate(env: Env, base: Address, quote: Address) -> i128;
+}
 
 /// Currency whitelist storage and operations.
 pub struct CurrencyWhitelist;
 
 impl CurrencyWhitelist {
     /// Add a token address to the whitelist (admin only).
+    ///
+    /// Returns `InvalidCurrency` if the address does not implement the token interface.
     pub fn add_currency(
         env: &Env,
         admin: &Address,
@@ -27,11 +48,38 @@ impl CurrencyWhitelist {
         if list.iter().any(|a| a == *currency) {
             return Ok(()); // idempotent: already present
         }
+        Self::require_token_interface(env, currency)?;
         list.push_back(currency.clone());
         env.storage().instance().set(&WHITELIST_KEY, &list);
+        ConfigLog::record(
+            env,
+            ConfigParameter::CurrencyWhitelisted(currency.clone()),
+            ConfigValue::Bool(false),
+            ConfigValue::Bool(true),
+            admin,
+        );
         Ok(())
     }
 
+    /// Probe the calls escrow relies on: `decimals`, `balance` and a zero-amount `transfer`
+    /// from the contract to itself, which moves no funds. Any failure, including the address
+    /// not being a contract, rejects the currency.
+    fn require_token_interface(env: &Env, currency: &Address) -> Result<(), QuickLendXError> {
+        let token_client = token::Client::new(env, currency);
+        let contract = env.current_contract_address();
+        let implements_token = matches!(token_client.try_decimals(), Ok(Ok(_)))
+            && matches!(token_client.try_balance(&contract), Ok(Ok(_)))
+            && matches!(
+                token_client.try_transfer(&contract, &contract, &0),
+                Ok(Ok(()))
+            );
+        if implements_token {
+            Ok(())
+        } else {
+            Err(QuickLendXError::InvalidCurrency)
+        }
+    }
+
     /// Remove a token address from the whitelist (admin only).
     pub fn remove_currency(
         env: &Env,
@@ -51,6 +99,15 @@ impl CurrencyWhitelist {
                 new_list.push_back(a);
             }
         }
+        if new_list.len() != list.len() {
+            ConfigLog::record(
+                env,
+                ConfigParameter::CurrencyWhitelisted(currency.clone()),
+                ConfigValue::Bool(true),
+                ConfigValue::Bool(false),
+                admin,
+            );
+        }
         env.storage().instance().set(&WHITELIST_KEY, &new_list);
         Ok(())
     }
@@ -69,7 +126,7 @@ impl CurrencyWhitelist {
             .unwrap_or_else(|| Vec::new(env))
     }
 
-    /// Require that the currency is whitelisted; otherwise return InvalidCurrency.
+    /// Require that the currency is whitelisted; otherwise return CurrencyNotWhitelisted.
     /// When the whitelist is empty, all currencies are allowed (backward compatibility).
     pub fn require_allowed_currency(env: &Env, currency: &Address) -> Result<(), QuickLendXError> {
         let list = Self::get_whitelisted_currencies(env);
@@ -79,7 +136,87 @@ impl CurrencyWhitelist {
         if Self::is_allowed_currency(env, currency) {
             Ok(())
         } else {
-            Err(QuickLendXError::InvalidCurrency)
+            Err(QuickLendXError::CurrencyNotWhitelisted)
+        }
+    }
+}
+
+/// Conversion between an invoice's currency and the token an investor funds it in
+pub struct CurrencyConversion;
+
+impl CurrencyConversion {
+    fn oracle_key(
+        invoice_currency: &Address,
+        funding_currency: &Address,
+    ) -> (Symbol, Address, Address) {
+        (
+            CONVERSION_ORACLE_KEY,
+            invoice_currency.clone(),
+            funding_currency.clone(),
+        )
+    }
+
+    pub fn get_oracle(
+        env: &Env,
+        invoice_currency: &Address,
+        funding_currency: &Address,
+    ) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&Self::oracle_key(invoice_currency, funding_currency))
+    }
+
+    /// Configure or, with `None`, remove the oracle pricing `funding_currency` against
+    /// `invoice_currency`
+    ///
+    /// # Errors
+    /// * `InvalidCurrency` if both currencies are the same
+    /// * `CurrencyNotWhitelisted` if either currency is not whitelisted
+    pub fn set_oracle(
+        env: &Env,
+        invoice_currency: &Address,
+        funding_currency: &Address,
+        oracle: Option<Address>,
+    ) -> Result<(), QuickLendXError> {
+        if invoice_currency == funding_currency {
+            return Err(QuickLendXError::InvalidCurrency);
+        }
+        let key = Self::oracle_key(invoice_currency, funding_currency);
+        match oracle {
+            Some(oracle) => {
+                CurrencyWhitelist::require_allowed_currency(env, invoice_currency)?;
+                CurrencyWhitelist::require_allowed_currency(env, funding_currency)?;
+                env.storage().instance().set(&key, &oracle);
+            }
+            None => env.storage().instance().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// Amount of `funding_currency` worth `amount` of `invoice_currency` at the oracle's
+    /// current rate, rounded up so the funding leg always covers the invoice leg
+    ///
+    /// # Errors
+    /// * `CurrencyNotWhitelisted` if the funding currency is not whitelisted
+    /// * `InvalidCurrency` if no oracle is configured for the pair
+    /// * `InvalidAmount` if the oracle returns a non-positive rate
+    pub fn convert(
+        env: &Env,
+        amount: i128,
+        invoice_currency: &Address,
+        funding_currency: &Address,
+    ) -> Result<i128, QuickLendXError> {
+        if invoice_currency == funding_currency {
+            return Ok(amount);
+        }
+        CurrencyWhitelist::require_allowed_currency(env, funding_currency)?;
+        let oracle = Self::get_oracle(env, invoice_currency, funding_currency)
+            .ok_or(QuickLendXError::InvalidCurrency)?;
+        let rate =
+            ConversionOracleClient::new(env, &oracle).rate(invoice_currency, funding_currency);
+        if rate <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
         }
+        Ok(rounding::mul_div(amount, rate, RATE_SCALE, Rounding::Ceil))
     }
 }
@@ -0,0 +1,161 @@
+//! Deal room access log for private invoices.
+//!
+//! A business can mark an invoice private. Verified investors then request access to its full
+//! details and the business grants it explicitly; every request and grant is recorded so the
+//! business knows who has seen sensitive terms, and investors can list the deal rooms they
+//! have been admitted to.
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_deal_room_access_granted, emit_deal_room_access_requested};
+use crate::invoice::InvoiceStorage;
+use crate::verification::InvestorVerificationStorage;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const PRIVATE_KEY: Symbol = symbol_short!("dr_priv");
+const INVOICE_LOG_KEY: Symbol = symbol_short!("dr_inv");
+const INVESTOR_LOG_KEY: Symbol = symbol_short!("dr_invr");
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DealRoomAccessStatus {
+    Requested,
+    Granted,
+}
+
+/// An investor's access to a private invoice's deal room
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DealRoomAccess {
+    pub invoice_id: BytesN<32>,
+    pub investor: Address,
+    pub status: DealRoomAccessStatus,
+    pub requested_at: u64,
+    pub granted_at: Option<u64>,
+}
+
+pub struct DealRoom;
+
+impl DealRoom {
+    pub fn is_private(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .get(&(PRIVATE_KEY, invoice_id.clone()))
+            .unwrap_or(false)
+    }
+
+    pub fn set_private(env: &Env, invoice_id: &BytesN<32>, private: bool) {
+        env.storage()
+            .instance()
+            .set(&(PRIVATE_KEY, invoice_id.clone()), &private);
+    }
+
+    /// All access requests and grants for an invoice, in request order
+    pub fn get_invoice_access_log(env: &Env, invoice_id: &BytesN<32>) -> Vec<DealRoomAccess> {
+        env.storage()
+            .instance()
+            .get(&(INVOICE_LOG_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// An investor's deal room access entries across invoices
+    pub fn get_investor_access_log(env: &Env, investor: &Address) -> Vec<DealRoomAccess> {
+        let invoice_ids: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&(INVESTOR_LOG_KEY, investor.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+        let mut entries = Vec::new(env);
+        for invoice_id in invoice_ids.iter() {
+            if let Some(access) = Self::find(env, &invoice_id, investor) {
+                entries.push_back(access);
+            }
+        }
+        entries
+    }
+
+    pub fn has_access(env: &Env, invoice_id: &BytesN<32>, investor: &Address) -> bool {
+        Self::find(env, invoice_id, investor)
+            .is_some_and(|access| access.status == DealRoomAccessStatus::Granted)
+    }
+
+    /// Record a verified investor's request to view a private invoice.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `NotInvestor` if the investor is not verified
+    /// * `OperationNotAllowed` if the invoice is not private or access was already requested
+    pub fn request_access(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        investor: &Address,
+    ) -> Result<DealRoomAccess, QuickLendXError> {
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if !InvestorVerificationStorage::is_investor_verified(env, investor) {
+            return Err(QuickLendXError::NotInvestor);
+        }
+        if !Self::is_private(env, invoice_id) || Self::find(env, invoice_id, investor).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let access = DealRoomAccess {
+            invoice_id: invoice_id.clone(),
+            investor: investor.clone(),
+            status: DealRoomAccessStatus::Requested,
+            requested_at: env.ledger().timestamp(),
+            granted_at: None,
+        };
+        let mut log = Self::get_invoice_access_log(env, invoice_id);
+        log.push_back(access.clone());
+        env.storage()
+            .instance()
+            .set(&(INVOICE_LOG_KEY, invoice_id.clone()), &log);
+
+        let investor_key = (INVESTOR_LOG_KEY, investor.clone());
+        let mut invoice_ids: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&investor_key)
+            .unwrap_or_else(|| Vec::new(env));
+        invoice_ids.push_back(invoice_id.clone());
+        env.storage().instance().set(&investor_key, &invoice_ids);
+
+        emit_deal_room_access_requested(env, invoice_id, investor);
+        Ok(access)
+    }
+
+    /// Grant a pending access request.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investor has not requested access
+    /// * `InvalidStatus` if access was already granted
+    pub fn grant_access(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        investor: &Address,
+    ) -> Result<DealRoomAccess, QuickLendXError> {
+        let mut log = Self::get_invoice_access_log(env, invoice_id);
+        let index = log
+            .iter()
+            .position(|access| access.investor == *investor)
+            .ok_or(QuickLendXError::StorageKeyNotFound)? as u32;
+        let mut access = log.get(index).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if access.status != DealRoomAccessStatus::Requested {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        access.status = DealRoomAccessStatus::Granted;
+        access.granted_at = Some(env.ledger().timestamp());
+        log.set(index, access.clone());
+        env.storage()
+            .instance()
+            .set(&(INVOICE_LOG_KEY, invoice_id.clone()), &log);
+
+        emit_deal_room_access_granted(env, invoice_id, investor);
+        Ok(access)
+    }
+
+    fn find(env: &Env, invoice_id: &BytesN<32>, investor: &Address) -> Option<DealRoomAccess> {
+        Self::get_invoice_access_log(env, invoice_id)
+            .iter()
+            .find(|access| access.investor == *investor)
+    }
+}
@@ -0,0 +1,218 @@
+//! Debtor registry and invoice acknowledgment.
+//!
+//! A business registers the address of the customer who owes an invoice. That debtor can then
+//! acknowledge the invoice on-chain, confirming the receivable is genuine. Acknowledged invoices
+//! are flagged for investors and lower the default probability estimate at verification.
+//! The debtor's record across every business that invoiced it is aggregated into a history
+//! investors can use for underwriting.
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_acknowledged, emit_invoice_debtor_registered};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const INVOICE_DEBTOR_KEY: Symbol = symbol_short!("dbt_inv");
+const DEBTOR_INVOICES_KEY: Symbol = symbol_short!("dbt_list");
+const ACKNOWLEDGED_KEY: Symbol = symbol_short!("dbt_ack");
+
+/// The customer that owes an invoice, and whether they confirmed it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceDebtor {
+    pub invoice_id: BytesN<32>,
+    pub debtor: Address,
+    pub registered_at: u64,
+    pub acknowledged_at: Option<u64>,
+}
+
+/// How a debtor's invoices have fared, across all businesses that registered it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebtorHistory {
+    pub debtor: Address,
+    pub invoice_count: u32,
+    /// Distinct businesses that invoiced the debtor
+    pub business_count: u32,
+    pub acknowledged_count: u32,
+    /// Invoices that were funded by an investor
+    pub financed_count: u32,
+    pub financed_amount: i128,
+    pub paid_count: u32,
+    /// Paid invoices settled no later than their due date
+    pub paid_on_time_count: u32,
+    pub defaulted_count: u32,
+    /// Funded invoices not yet paid or defaulted
+    pub outstanding_count: u32,
+}
+
+pub struct DebtorRegistry;
+
+impl DebtorRegistry {
+    pub fn get_invoice_debtor(env: &Env, invoice_id: &BytesN<32>) -> Option<InvoiceDebtor> {
+        env.storage()
+            .instance()
+            .get(&(INVOICE_DEBTOR_KEY, invoice_id.clone()))
+    }
+
+    pub fn is_acknowledged(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        Self::get_invoice_debtor(env, invoice_id)
+            .is_some_and(|record| record.acknowledged_at.is_some())
+    }
+
+    /// Invoices a debtor has been registered against
+    pub fn get_debtor_invoices(env: &Env, debtor: &Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(DEBTOR_INVOICES_KEY, debtor.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Every acknowledged invoice, in acknowledgment order
+    pub fn get_acknowledged_invoices(env: &Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&ACKNOWLEDGED_KEY)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Aggregate the outcomes of every invoice the debtor is registered against
+    pub fn get_history(env: &Env, debtor: &Address) -> DebtorHistory {
+        let mut history = DebtorHistory {
+            debtor: debtor.clone(),
+            invoice_count: 0,
+            business_count: 0,
+            acknowledged_count: 0,
+            financed_count: 0,
+            financed_amount: 0,
+            paid_count: 0,
+            paid_on_time_count: 0,
+            defaulted_count: 0,
+            outstanding_count: 0,
+        };
+        let mut businesses: Vec<Address> = Vec::new(env);
+        for invoice_id in Self::get_debtor_invoices(env, debtor).iter() {
+            let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) else {
+                continue;
+            };
+            history.invoice_count += 1;
+            if !businesses.contains(&invoice.business) {
+                businesses.push_back(invoice.business.clone());
+            }
+            if Self::is_acknowledged(env, &invoice_id) {
+                history.acknowledged_count += 1;
+            }
+            if invoice.funded_at.is_some() {
+                history.financed_count += 1;
+                history.financed_amount = history
+                    .financed_amount
+                    .saturating_add(invoice.funded_amount);
+            }
+            match invoice.status {
+                InvoiceStatus::Paid => {
+                    history.paid_count += 1;
+                    if invoice
+                        .settled_at
+                        .is_some_and(|settled_at| settled_at <= invoice.due_date)
+                    {
+                        history.paid_on_time_count += 1;
+                    }
+                }
+                InvoiceStatus::Defaulted => history.defaulted_count += 1,
+                InvoiceStatus::Funded => history.outstanding_count += 1,
+                _ => {}
+            }
+        }
+        history.business_count = businesses.len();
+        history
+    }
+
+    fn set_debtor_invoices(env: &Env, debtor: &Address, invoice_ids: &Vec<BytesN<32>>) {
+        env.storage()
+            .instance()
+            .set(&(DEBTOR_INVOICES_KEY, debtor.clone()), invoice_ids);
+    }
+
+    /// Register (or replace) the debtor of an unfunded invoice.
+    ///
+    /// # Errors
+    /// * `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the debtor is the business itself or the invoice was
+    ///   already acknowledged
+    pub fn register(
+        env: &Env,
+        invoice: &Invoice,
+        debtor: &Address,
+    ) -> Result<InvoiceDebtor, QuickLendXError> {
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if *debtor == invoice.business {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if let Some(previous) = Self::get_invoice_debtor(env, &invoice.id) {
+            if previous.acknowledged_at.is_some() {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            let mut invoice_ids = Self::get_debtor_invoices(env, &previous.debtor);
+            if let Some(index) = invoice_ids.first_index_of(&invoice.id) {
+                invoice_ids.remove(index);
+                Self::set_debtor_invoices(env, &previous.debtor, &invoice_ids);
+            }
+        }
+
+        let record = InvoiceDebtor {
+            invoice_id: invoice.id.clone(),
+            debtor: debtor.clone(),
+            registered_at: env.ledger().timestamp(),
+            acknowledged_at: None,
+        };
+        env.storage()
+            .instance()
+            .set(&(INVOICE_DEBTOR_KEY, invoice.id.clone()), &record);
+        let mut invoice_ids = Self::get_debtor_invoices(env, debtor);
+        invoice_ids.push_back(invoice.id.clone());
+        Self::set_debtor_invoices(env, debtor, &invoice_ids);
+
+        emit_invoice_debtor_registered(env, &invoice.id, debtor);
+        Ok(record)
+    }
+
+    /// Record the registered debtor's confirmation that the invoice is genuine.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound` if no debtor is registered
+    /// * `InvalidStatus` if the invoice is no longer outstanding
+    /// * `OperationNotAllowed` if the invoice was already acknowledged
+    pub fn acknowledge(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Result<InvoiceDebtor, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        let mut record =
+            Self::get_invoice_debtor(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        record.debtor.require_auth();
+        if !matches!(
+            invoice.status,
+            InvoiceStatus::Pending | InvoiceStatus::Verified | InvoiceStatus::Funded
+        ) {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if record.acknowledged_at.is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        record.acknowledged_at = Some(env.ledger().timestamp());
+        env.storage()
+            .instance()
+            .set(&(INVOICE_DEBTOR_KEY, invoice_id.clone()), &record);
+        let mut acknowledged = Self::get_acknowledged_invoices(env);
+        acknowledged.push_back(invoice_id.clone());
+        env.storage()
+            .instance()
+            .set(&ACKNOWLEDGED_KEY, &acknowledged);
+
+        emit_invoice_acknowledged(env, invoice_id, &record.debtor);
+        Ok(record)
+    }
+}
@@ -1,16 +1,162 @@
+use crate::collections::{CollectionEntry, Collections};
 use crate::errors::QuickLendXError;
 use crate::events::{
-    emit_dispute_created, emit_dispute_resolved, emit_dispute_under_review, emit_insurance_claimed,
-    emit_invoice_defaulted, emit_invoice_expired,
+    emit_default_recovery, emit_dispute_created, emit_dispute_resolved, emit_dispute_under_review,
+    emit_insurance_claimed, emit_invoice_defaulted, emit_invoice_expired,
 };
+use crate::insurance::{InsuranceClaimStatus, InsuranceClaims, InsuranceLog, InsuranceRecordKind};
+use crate::insurance_pool::InsurancePools;
 use crate::investment::{InvestmentStatus, InvestmentStorage};
 use crate::invoice::{Dispute, DisputeStatus, InvoiceStatus, InvoiceStorage};
-use crate::notifications::NotificationSystem;
-use soroban_sdk::{Address, BytesN, Env, String, Vec};
+use crate::invoice_freeze::InvoiceFreezes;
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::payments::{freeze_escrow, transfer_funds, unfreeze_escrow};
+use crate::protocol_limits::ContentPolicy;
+use crate::settlement::get_payoff_quote;
+use crate::state_machine::TransitionRole;
+use crate::tax_lots::{LotDisposal, TaxLots};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
 
 /// Default grace period in seconds (7 days)
 pub const DEFAULT_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
 
+/// Amounts recovered on a defaulted invoice and how they were split
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultRecovery {
+    pub invoice_id: BytesN<32>,
+    pub investment_id: BytesN<32>,
+    pub total_recovered: i128,
+    /// Part paid to the investor
+    pub investor_recovered: i128,
+    /// Part returned to the insurance pool, reducing the claim it paid
+    pub claim_clawback: i128,
+    pub last_recovered_at: u64,
+}
+
+fn recovery_key(invoice_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+    (symbol_short!("dflt_rec"), invoice_id.clone())
+}
+
+fn amount_due_key(invoice_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+    (symbol_short!("dflt_due"), invoice_id.clone())
+}
+
+/// Face value plus late penalties accrued up to the moment the invoice defaulted
+pub fn get_amount_due_at_default(env: &Env, invoice_id: &BytesN<32>) -> Option<i128> {
+    env.storage().instance().get(&amount_due_key(invoice_id))
+}
+
+/// Get the recoveries collected on a defaulted invoice, if any
+pub fn get_default_recovery(env: &Env, invoice_id: &BytesN<32>) -> Option<DefaultRecovery> {
+    env.storage().instance().get(&recovery_key(invoice_id))
+}
+
+/// Collect a recovery on a defaulted invoice from `payer`
+///
+/// The investor is paid first, up to the loss the insurance claim did not cover. The rest
+/// goes back to the currency's insurance pool until the claim it paid is fully clawed back, so the
+/// investor is never compensated beyond their loss. Anything left after that goes to the
+/// investor.
+///
+/// # Errors
+/// * `InvalidAmount` if `amount` is not positive
+/// * `InvalidStatus` if the invoice is not defaulted
+/// * `OperationNotAllowed` if its collection case was written off
+/// * `StorageKeyNotFound` if the invoice has no investment
+pub fn record_default_recovery(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    amount: i128,
+) -> Result<DefaultRecovery, QuickLendXError> {
+    payer.require_auth();
+    if amount <= 0 {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+    if invoice.status != InvoiceStatus::Defaulted {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    Collections::ensure_collectable(env, invoice_id)?;
+    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id)
+        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+
+    let mut recovery = get_default_recovery(env, invoice_id).unwrap_or(DefaultRecovery {
+        invoice_id: invoice_id.clone(),
+        investment_id: investment.investment_id.clone(),
+        total_recovered: 0,
+        investor_recovered: 0,
+        claim_clawback: 0,
+        last_recovered_at: 0,
+    });
+
+    // A denied claim pays nothing, so the investor bears the whole loss
+    let claim = investment.claimed_insurance().filter(|_| {
+        InsuranceClaims::get(env, &investment.investment_id)
+            .is_none_or(|claim| claim.status != InsuranceClaimStatus::Denied)
+    });
+    let claim_amount = claim.as_ref().map_or(0, |(_, amount)| *amount);
+    let uncovered_loss = investment.amount.saturating_sub(claim_amount).max(0);
+    let investor_room = uncovered_loss
+        .saturating_sub(recovery.investor_recovered)
+        .max(0);
+    // Only coverage the pool has actually paid can be clawed back
+    let claim_paid = InsuranceClaims::get(env, &investment.investment_id)
+        .map_or(claim_amount, |claim| claim.paid_amount);
+    let clawback_room = claim_paid.saturating_sub(recovery.claim_clawback).max(0);
+
+    let mut investor_amount = amount.min(investor_room);
+    let clawback_amount = (amount - investor_amount).min(clawback_room);
+    investor_amount = amount - clawback_amount;
+
+    if investor_amount > 0 {
+        transfer_funds(
+            env,
+            &invoice.currency,
+            payer,
+            &investment.investor,
+            investor_amount,
+        )?;
+        TaxLots::add_proceeds(env, &investment.investment_id, investor_amount);
+    }
+    if let Some((provider, _)) = claim.filter(|_| clawback_amount > 0) {
+        InsurancePools::recover_claim(env, &invoice.currency, payer, clawback_amount)?;
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::ClaimRecovered,
+            &provider,
+            &investment.investment_id,
+            clawback_amount,
+        );
+    }
+
+    recovery.total_recovered = recovery.total_recovered.saturating_add(amount);
+    recovery.investor_recovered = recovery.investor_recovered.saturating_add(investor_amount);
+    recovery.claim_clawback = recovery.claim_clawback.saturating_add(clawback_amount);
+    recovery.last_recovered_at = env.ledger().timestamp();
+    env.storage()
+        .instance()
+        .set(&recovery_key(invoice_id), &recovery);
+    Collections::record(
+        env,
+        invoice_id,
+        get_amount_due_at_default(env, invoice_id).unwrap_or(invoice.amount),
+        CollectionEntry {
+            payer: payer.clone(),
+            amount,
+            investor_amount,
+            clawback_amount,
+            recorded_at: recovery.last_recovered_at,
+        },
+    );
+
+    emit_default_recovery(env, invoice_id, payer, investor_amount, clawback_amount);
+    Ok(recovery)
+}
+
 /// Mark an invoice as defaulted (admin or automated process)
 /// Checks due date + grace period before marking as defaulted
 ///
@@ -58,6 +204,7 @@ pub fn mark_invoice_defaulted(
 pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
     let mut invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     // Validate invoice is in funded status
     if invoice.status != InvoiceStatus::Funded {
@@ -69,15 +216,27 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
         return Err(QuickLendXError::InvalidStatus);
     }
 
+    // Freeze what the business owes, late penalties included, before the status changes
+    if let Ok(quote) = get_payoff_quote(env, invoice_id, env.ledger().timestamp()) {
+        env.storage()
+            .instance()
+            .set(&amount_due_key(invoice_id), &quote.payoff_amount);
+    }
+
     // Remove from funded status list
     InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Funded, invoice_id);
 
     // Mark invoice as defaulted
-    invoice.mark_as_defaulted();
+    invoice.mark_as_defaulted(TransitionRole::Keeper)?;
     InvoiceStorage::update_invoice(env, &invoice);
 
     // Add to defaulted status list
     InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Defaulted, invoice_id);
+    Collections::open(
+        env,
+        invoice_id,
+        get_amount_due_at_default(env, invoice_id).unwrap_or(invoice.amount),
+    );
 
     // Emit expiration event
     emit_invoice_expired(env, &invoice);
@@ -86,19 +245,38 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
     if let Some(mut investment) = InvestmentStorage::get_investment_by_invoice(env, invoice_id) {
         investment.status = InvestmentStatus::Defaulted;
 
-        let claim_details = investment
-            .process_insurance_claim()
-            .and_then(|(provider, amount)| {
-                if amount > 0 {
-                    Some((provider, amount))
-                } else {
-                    None
-                }
-            });
+        // Under claim adjudication the investor files the claim for review instead
+        let claim_details = if InsuranceClaims::get_adjudication(env).is_some() {
+            None
+        } else {
+            investment
+                .process_insurance_claim()
+                .and_then(|(provider, amount)| {
+                    if amount > 0 {
+                        Some((provider, amount))
+                    } else {
+                        None
+                    }
+                })
+        };
 
         InvestmentStorage::update_investment(env, &investment);
+        TaxLots::dispose(
+            env,
+            &investment.investment_id,
+            LotDisposal::Default,
+            claim_details.as_ref().map_or(0, |(_, amount)| *amount),
+        );
 
         if let Some((provider, coverage_amount)) = claim_details {
+            // Default claims are filed and approved in one step, then paid from the pool
+            InsuranceClaims::file(
+                env,
+                &investment,
+                &invoice.currency,
+                &provider,
+                coverage_amount,
+            )?;
             emit_insurance_claimed(
                 env,
                 &investment.investment_id,
@@ -113,7 +291,11 @@ pub fn handle_default(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLen
     emit_invoice_defaulted(env, &invoice);
 
     // Send notification
-    let _ = NotificationSystem::notify_invoice_defaulted(env, &invoice);
+    NotificationHooks::fire(
+        env,
+        &invoice,
+        NotificationHook::StatusChanged(InvoiceStatus::Funded, InvoiceStatus::Defaulted),
+    );
 
     Ok(())
 }
@@ -130,6 +312,7 @@ pub fn create_dispute(
 
     let mut invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     // Check if dispute already exists
     if invoice.dispute_status != DisputeStatus::None {
@@ -147,14 +330,9 @@ pub fn create_dispute(
         }
     }
 
-    // Validate reason and evidence
-    if reason.len() == 0 || reason.len() > 500 {
-        return Err(QuickLendXError::InvalidDisputeReason);
-    }
-
-    if evidence.len() == 0 || evidence.len() > 1000 {
-        return Err(QuickLendXError::InvalidDisputeEvidence);
-    }
+    // Validate reason and evidence against the content policy
+    ContentPolicy::validate_dispute_reason(env, &reason)?;
+    ContentPolicy::validate_dispute_evidence(env, &evidence)?;
 
     // Create dispute
     let dispute = Dispute {
@@ -177,8 +355,14 @@ pub fn create_dispute(
     // Update invoice in storage
     InvoiceStorage::update_invoice(env, &invoice);
 
+    // Hold the escrow until the dispute is resolved
+    if invoice.status == InvoiceStatus::Funded {
+        freeze_escrow(env, invoice_id);
+    }
+
     // Emit dispute created event
     emit_dispute_created(env, invoice_id, creator, &reason);
+    NotificationHooks::fire(env, &invoice, NotificationHook::DisputeOpened);
 
     Ok(())
 }
@@ -193,6 +377,7 @@ pub fn put_dispute_under_review(
 
     let mut invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     // Check if dispute exists and is in disputed status
     if invoice.dispute_status != DisputeStatus::Disputed {
@@ -222,6 +407,7 @@ pub fn resolve_dispute(
 
     let mut invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     // Check if dispute exists and is under review
     if invoice.dispute_status != DisputeStatus::UnderReview {
@@ -229,9 +415,7 @@ pub fn resolve_dispute(
     }
 
     // Validate resolution
-    if resolution.len() == 0 || resolution.len() > 500 {
-        return Err(QuickLendXError::InvalidDisputeReason);
-    }
+    ContentPolicy::validate_dispute_reason(env, &resolution)?;
 
     // Update dispute with resolution
     if invoice.dispute_status != DisputeStatus::None {
@@ -246,8 +430,11 @@ pub fn resolve_dispute(
     // Update invoice in storage
     InvoiceStorage::update_invoice(env, &invoice);
 
+    unfreeze_escrow(env, invoice_id);
+
     // Emit dispute resolved event
     emit_dispute_resolved(env, invoice_id, resolver, &resolution);
+    NotificationHooks::fire(env, &invoice, NotificationHook::DisputeResolved);
 
     Ok(())
 }
@@ -0,0 +1,323 @@
+//! Prefunded investor deposit accounts.
+//!
+//! Investors can deposit tokens into the contract ahead of time. When one of their bids is
+//! accepted, the escrow draws from the deposit in the funding currency first and only transfers
+//! the shortfall from the investor's wallet, so a fully prefunded bid funds without relying on a
+//! token allowance at acceptance time. Deposits are held in the contract's segregated balance and
+//! can be withdrawn at any time.
+//!
+//! When the admin sets a `WithdrawalQueuePolicy`, withdrawals at or above its threshold cannot be
+//! paid out directly. The investor requests them instead: the amount is reserved from the deposit
+//! straight away, so bids can no longer draw on it, and it can only be paid out once the delay has
+//! elapsed. Until then the investor can cancel and the amount returns to the deposit.
+
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_investor_deposit, emit_investor_withdrawal, emit_withdrawal_cancelled,
+    emit_withdrawal_requested,
+};
+use crate::payments::transfer_funds;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+const DEPOSIT_KEY: Symbol = symbol_short!("dep_bal");
+const QUEUE_POLICY_KEY: Symbol = symbol_short!("wdq_pol");
+const PENDING_WITHDRAWAL_KEY: Symbol = symbol_short!("wdq_pend");
+const PENDING_IDS_KEY: Symbol = symbol_short!("wdq_ids");
+const NEXT_WITHDRAWAL_ID_KEY: Symbol = symbol_short!("wdq_next");
+/// Longest delay the admin can impose on large withdrawals (7 days)
+pub const MAX_WITHDRAWAL_DELAY: u64 = 7 * 24 * 60 * 60;
+
+/// Withdrawals at or above `threshold_amount` must be requested and executed after
+/// `delay_seconds`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalQueuePolicy {
+    pub threshold_amount: i128,
+    pub delay_seconds: u64,
+}
+
+/// A large withdrawal waiting out the delay; its amount is already taken out of the deposit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingWithdrawal {
+    pub id: u64,
+    pub investor: Address,
+    pub currency: Address,
+    pub amount: i128,
+    pub requested_at: u64,
+    /// Earliest time the withdrawal can be executed
+    pub executable_at: u64,
+}
+
+pub struct InvestorDeposits;
+
+impl InvestorDeposits {
+    pub fn get(env: &Env, investor: &Address, currency: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(DEPOSIT_KEY, investor.clone(), currency.clone()))
+            .unwrap_or(0)
+    }
+
+    fn set(env: &Env, investor: &Address, currency: &Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&(DEPOSIT_KEY, investor.clone(), currency.clone()), &amount);
+    }
+
+    /// Move `amount` from the investor's wallet into their deposit; the caller has checked the
+    /// investor's authorization
+    ///
+    /// # Returns
+    /// * The new deposit balance
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `CurrencyNotWhitelisted` if the currency is not whitelisted
+    /// * Transfer errors
+    pub fn deposit(
+        env: &Env,
+        investor: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        CurrencyWhitelist::require_allowed_currency(env, currency)?;
+        transfer_funds(
+            env,
+            currency,
+            investor,
+            &env.current_contract_address(),
+            amount,
+        )?;
+        let balance = Self::get(env, investor, currency).saturating_add(amount);
+        Self::set(env, investor, currency, balance);
+        emit_investor_deposit(env, investor, currency, amount, balance);
+        Ok(balance)
+    }
+
+    /// Pay `amount` of the investor's deposit back to their wallet; the caller has checked the
+    /// investor's authorization
+    ///
+    /// # Returns
+    /// * The remaining deposit balance
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `OperationNotAllowed` if the amount must be queued with `request_withdrawal`
+    /// * `InsufficientFunds` if it exceeds the deposit
+    /// * Transfer errors
+    pub fn withdraw(
+        env: &Env,
+        investor: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if Self::requires_queue(env, amount) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let balance = Self::get(env, investor, currency);
+        if amount > balance {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        Self::set(env, investor, currency, balance - amount);
+        transfer_funds(
+            env,
+            currency,
+            &env.current_contract_address(),
+            investor,
+            amount,
+        )?;
+        emit_investor_withdrawal(env, investor, currency, amount, balance - amount);
+        Ok(balance - amount)
+    }
+
+    /// Take up to `amount` from the investor's deposit for funds the contract keeps holding
+    ///
+    /// # Returns
+    /// * The amount taken, which the caller does not need to transfer from the investor
+    pub fn draw(env: &Env, investor: &Address, currency: &Address, amount: i128) -> i128 {
+        let balance = Self::get(env, investor, currency);
+        let drawn = balance.min(amount).max(0);
+        if drawn > 0 {
+            Self::set(env, investor, currency, balance - drawn);
+        }
+        drawn
+    }
+
+    pub fn get_queue_policy(env: &Env) -> Option<WithdrawalQueuePolicy> {
+        env.storage().instance().get(&QUEUE_POLICY_KEY)
+    }
+
+    /// Set or clear the policy. Withdrawals already pending keep their `executable_at`.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if the threshold is not positive or the delay is outside
+    ///   1..=`MAX_WITHDRAWAL_DELAY`
+    pub fn set_queue_policy(
+        env: &Env,
+        policy: Option<WithdrawalQueuePolicy>,
+    ) -> Result<(), QuickLendXError> {
+        match policy {
+            Some(policy) => {
+                if policy.threshold_amount <= 0
+                    || policy.delay_seconds == 0
+                    || policy.delay_seconds > MAX_WITHDRAWAL_DELAY
+                {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                env.storage().instance().set(&QUEUE_POLICY_KEY, &policy);
+            }
+            None => env.storage().instance().remove(&QUEUE_POLICY_KEY),
+        }
+        Ok(())
+    }
+
+    /// Whether a withdrawal of `amount` must go through the queue
+    pub fn requires_queue(env: &Env, amount: i128) -> bool {
+        Self::get_queue_policy(env).is_some_and(|policy| amount >= policy.threshold_amount)
+    }
+
+    pub fn get_pending_withdrawal(env: &Env, id: u64) -> Option<PendingWithdrawal> {
+        env.storage().instance().get(&(PENDING_WITHDRAWAL_KEY, id))
+    }
+
+    /// All queued withdrawals, oldest first
+    pub fn get_pending_withdrawals(env: &Env) -> Vec<PendingWithdrawal> {
+        let mut pending = Vec::new(env);
+        for id in Self::pending_ids(env).iter() {
+            if let Some(withdrawal) = Self::get_pending_withdrawal(env, id) {
+                pending.push_back(withdrawal);
+            }
+        }
+        pending
+    }
+
+    fn pending_ids(env: &Env) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&PENDING_IDS_KEY)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn remove_pending(env: &Env, id: u64) {
+        env.storage()
+            .instance()
+            .remove(&(PENDING_WITHDRAWAL_KEY, id));
+        let mut ids = Self::pending_ids(env);
+        if let Some(index) = ids.first_index_of(id) {
+            ids.remove(index);
+        }
+        env.storage().instance().set(&PENDING_IDS_KEY, &ids);
+    }
+
+    /// Queue a withdrawal at or above the policy threshold, reserving it from the deposit; the
+    /// caller has checked the investor's authorization
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `OperationNotAllowed` if the amount is below the policy threshold (or no policy is set)
+    /// * `InsufficientFunds` if it exceeds the deposit
+    pub fn request_withdrawal(
+        env: &Env,
+        investor: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> Result<PendingWithdrawal, QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let policy = Self::get_queue_policy(env).ok_or(QuickLendXError::OperationNotAllowed)?;
+        if amount < policy.threshold_amount {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let balance = Self::get(env, investor, currency);
+        if amount > balance {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        Self::set(env, investor, currency, balance - amount);
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&NEXT_WITHDRAWAL_ID_KEY)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&NEXT_WITHDRAWAL_ID_KEY, &(id + 1));
+        let now = env.ledger().timestamp();
+        let pending = PendingWithdrawal {
+            id,
+            investor: investor.clone(),
+            currency: currency.clone(),
+            amount,
+            requested_at: now,
+            executable_at: now.saturating_add(policy.delay_seconds),
+        };
+        env.storage()
+            .instance()
+            .set(&(PENDING_WITHDRAWAL_KEY, id), &pending);
+        let mut ids = Self::pending_ids(env);
+        ids.push_back(id);
+        env.storage().instance().set(&PENDING_IDS_KEY, &ids);
+
+        emit_withdrawal_requested(env, &pending);
+        Ok(pending)
+    }
+
+    /// Drop a queued withdrawal and return its amount to the deposit
+    ///
+    /// # Returns
+    /// * The new deposit balance
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no withdrawal with this ID is pending
+    pub fn cancel_withdrawal(env: &Env, id: u64) -> Result<i128, QuickLendXError> {
+        let pending =
+            Self::get_pending_withdrawal(env, id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Self::remove_pending(env, id);
+        let balance =
+            Self::get(env, &pending.investor, &pending.currency).saturating_add(pending.amount);
+        Self::set(env, &pending.investor, &pending.currency, balance);
+
+        emit_withdrawal_cancelled(env, &pending);
+        Ok(balance)
+    }
+
+    /// Pay out a queued withdrawal once its delay has elapsed. The investor authorized it when
+    /// requesting, so anyone may execute it.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no withdrawal with this ID is pending
+    /// * `OperationNotAllowed` if the delay has not elapsed
+    /// * Transfer errors
+    pub fn execute_withdrawal(env: &Env, id: u64) -> Result<PendingWithdrawal, QuickLendXError> {
+        let pending =
+            Self::get_pending_withdrawal(env, id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Self::remove_pending(env, id);
+        transfer_funds(
+            env,
+            &pending.currency,
+            &env.current_contract_address(),
+            &pending.investor,
+            pending.amount,
+        )?;
+        emit_investor_withdrawal(
+            env,
+            &pending.investor,
+            &pending.currency,
+            pending.amount,
+            Self::get(env, &pending.investor, &pending.currency),
+        );
+        Ok(pending)
+    }
+}
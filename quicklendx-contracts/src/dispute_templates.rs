@@ -0,0 +1,278 @@
+//! Dispute resolution templates: the standard outcomes the admin can resolve a dispute with.
+//!
+//! Each template has a numeric code, a description that becomes the dispute's resolution text,
+//! and the default share of the escrow that goes back to the investor. Resolving a dispute with
+//! a template records a structured outcome for the invoice, and if the invoice is still funded
+//! its escrow is split straight away: the business is paid its share and the investor is
+//! refunded the rest. Free-text `resolve_dispute` keeps working alongside the templates.
+
+use crate::errors::QuickLendXError;
+use crate::escrow::refund_funded_invoice;
+use crate::events::{
+    emit_dispute_outcome_recorded, emit_dispute_template_updated, emit_escrow_released,
+};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::payments::{release_escrow_partial, EscrowStatus, EscrowStorage};
+use crate::protocol_limits::ContentPolicy;
+use crate::rounding::{self, Rounding};
+use crate::state_machine::TransitionRole;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const TEMPLATE_KEY: Symbol = symbol_short!("dsp_tpl");
+const TEMPLATE_LIST_KEY: Symbol = symbol_short!("dsp_tpls");
+const OUTCOME_KEY: Symbol = symbol_short!("dsp_out");
+/// Most templates the registry holds
+pub const MAX_DISPUTE_TEMPLATES: u32 = 50;
+const BPS_DENOMINATOR: u32 = 10_000;
+
+/// A standard dispute resolution
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolutionTemplate {
+    pub code: u32,
+    /// Recorded as the dispute's resolution text
+    pub description: String,
+    /// Share of the escrow refunded to the investor, in basis points; the business gets the rest
+    pub investor_share_bps: u32,
+    /// Inactive templates are kept for past outcomes but cannot resolve new disputes
+    pub active: bool,
+    /// Disputes resolved with the template
+    pub times_used: u32,
+    pub updated_at: u64,
+}
+
+/// How a dispute was resolved with a template
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeOutcome {
+    pub invoice_id: BytesN<32>,
+    pub template_code: u32,
+    pub investor_share_bps: u32,
+    /// Escrow refunded to the investor, in the invoice currency (0 if nothing was executed)
+    pub investor_amount: i128,
+    /// Escrow paid to the business, in the invoice currency (0 if nothing was executed)
+    pub business_amount: i128,
+    /// Whether the split was executed against a held escrow
+    pub executed: bool,
+    pub resolved_by: Address,
+    pub resolved_at: u64,
+}
+
+pub struct DisputeTemplates;
+
+impl DisputeTemplates {
+    pub fn get(env: &Env, code: u32) -> Option<ResolutionTemplate> {
+        env.storage().instance().get(&(TEMPLATE_KEY, code))
+    }
+
+    fn set(env: &Env, template: &ResolutionTemplate) {
+        env.storage()
+            .instance()
+            .set(&(TEMPLATE_KEY, template.code), template);
+    }
+
+    /// Every template, active or not, in the order they were added
+    pub fn list(env: &Env) -> Vec<ResolutionTemplate> {
+        let codes: Vec<u32> = env
+            .storage()
+            .instance()
+            .get(&TEMPLATE_LIST_KEY)
+            .unwrap_or_else(|| Vec::new(env));
+        let mut templates = Vec::new(env);
+        for code in codes.iter() {
+            if let Some(template) = Self::get(env, code) {
+                templates.push_back(template);
+            }
+        }
+        templates
+    }
+
+    fn validate(
+        env: &Env,
+        description: &String,
+        investor_share_bps: u32,
+    ) -> Result<(), QuickLendXError> {
+        ContentPolicy::validate_dispute_reason(env, description)?;
+        if investor_share_bps > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    /// Add an active template under `code`
+    ///
+    /// # Errors
+    /// * `InvalidDisputeReason` if the description fails the content policy
+    /// * `InvalidAmount` if `investor_share_bps` exceeds 10,000
+    /// * `OperationNotAllowed` if `code` is taken or the registry is full
+    pub fn add(
+        env: &Env,
+        code: u32,
+        description: String,
+        investor_share_bps: u32,
+    ) -> Result<ResolutionTemplate, QuickLendXError> {
+        Self::validate(env, &description, investor_share_bps)?;
+        if Self::get(env, code).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let mut codes: Vec<u32> = env
+            .storage()
+            .instance()
+            .get(&TEMPLATE_LIST_KEY)
+            .unwrap_or_else(|| Vec::new(env));
+        if codes.len() >= MAX_DISPUTE_TEMPLATES {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        codes.push_back(code);
+        env.storage().instance().set(&TEMPLATE_LIST_KEY, &codes);
+
+        let template = ResolutionTemplate {
+            code,
+            description,
+            investor_share_bps,
+            active: true,
+            times_used: 0,
+            updated_at: env.ledger().timestamp(),
+        };
+        Self::set(env, &template);
+        emit_dispute_template_updated(env, &template);
+        Ok(template)
+    }
+
+    /// Change a template's description and default split. Outcomes already recorded keep the
+    /// split they were resolved with.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `InvalidDisputeReason`, `InvalidAmount` as for `add`
+    pub fn update(
+        env: &Env,
+        code: u32,
+        description: String,
+        investor_share_bps: u32,
+    ) -> Result<ResolutionTemplate, QuickLendXError> {
+        let mut template = Self::get(env, code).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Self::validate(env, &description, investor_share_bps)?;
+        template.description = description;
+        template.investor_share_bps = investor_share_bps;
+        template.updated_at = env.ledger().timestamp();
+        Self::set(env, &template);
+        emit_dispute_template_updated(env, &template);
+        Ok(template)
+    }
+
+    /// Retire or reinstate a template
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `InvalidStatus` if it is already in that state
+    pub fn set_active(
+        env: &Env,
+        code: u32,
+        active: bool,
+    ) -> Result<ResolutionTemplate, QuickLendXError> {
+        let mut template = Self::get(env, code).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if template.active == active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        template.active = active;
+        template.updated_at = env.ledger().timestamp();
+        Self::set(env, &template);
+        emit_dispute_template_updated(env, &template);
+        Ok(template)
+    }
+
+    pub fn get_outcome(env: &Env, invoice_id: &BytesN<32>) -> Option<DisputeOutcome> {
+        env.storage()
+            .instance()
+            .get(&(OUTCOME_KEY, invoice_id.clone()))
+    }
+
+    /// Check that `code` names an active template and return it
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `OperationNotAllowed` if the template is inactive
+    pub fn get_active(env: &Env, code: u32) -> Result<ResolutionTemplate, QuickLendXError> {
+        let template = Self::get(env, code).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if !template.active {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(template)
+    }
+
+    /// Record the outcome of a dispute just resolved with `template`, splitting the invoice's
+    /// escrow if it is still held for a funded invoice
+    ///
+    /// With no investor share the whole escrow is released to the business and the invoice
+    /// stays funded. Otherwise the business share is released and the rest is refunded to the
+    /// investor, which marks the invoice refunded.
+    pub fn record_outcome(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        resolver: &Address,
+        mut template: ResolutionTemplate,
+    ) -> Result<DisputeOutcome, QuickLendXError> {
+        let (investor_amount, business_amount, executed) =
+            Self::execute_split(env, invoice_id, resolver, template.investor_share_bps)?;
+
+        let outcome = DisputeOutcome {
+            invoice_id: invoice_id.clone(),
+            template_code: template.code,
+            investor_share_bps: template.investor_share_bps,
+            investor_amount,
+            business_amount,
+            executed,
+            resolved_by: resolver.clone(),
+            resolved_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(OUTCOME_KEY, invoice_id.clone()), &outcome);
+
+        template.times_used = template.times_used.saturating_add(1);
+        Self::set(env, &template);
+        emit_dispute_outcome_recorded(env, &outcome);
+        Ok(outcome)
+    }
+
+    fn execute_split(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        resolver: &Address,
+        investor_share_bps: u32,
+    ) -> Result<(i128, i128, bool), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        let Some(escrow) = EscrowStorage::get_escrow_by_invoice(env, invoice_id) else {
+            return Ok((0, 0, false));
+        };
+        if invoice.status != InvoiceStatus::Funded || escrow.status != EscrowStatus::Held {
+            return Ok((0, 0, false));
+        }
+
+        let remaining = escrow.amount - escrow.released_amount;
+        let investor_amount = rounding::mul_div(
+            remaining,
+            i128::from(investor_share_bps),
+            i128::from(BPS_DENOMINATOR),
+            Rounding::Floor,
+        );
+        let business_amount = remaining - investor_amount;
+
+        if business_amount > 0 {
+            release_escrow_partial(env, invoice_id, business_amount)?;
+            emit_escrow_released(
+                env,
+                &escrow.escrow_id,
+                invoice_id,
+                &escrow.business,
+                business_amount,
+            );
+        }
+        if investor_amount > 0 {
+            refund_funded_invoice(env, invoice, resolver, TransitionRole::Admin)?;
+        }
+        Ok((investor_amount, business_amount, true))
+    }
+}
@@ -0,0 +1,124 @@
+//! Document hashes attached to invoices.
+//!
+//! Businesses keep PDFs, purchase orders and contracts off-chain and anchor each one to an
+//! invoice by its content hash, so verifiers and investors can check that the file they were
+//! sent is the one the business committed to. Documents can be removed only while the invoice
+//! is still unfunded.
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_document_attached, emit_invoice_document_removed};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const DOCUMENTS_KEY: Symbol = symbol_short!("docs");
+/// Upper bound on documents per invoice
+pub const MAX_DOCUMENTS_PER_INVOICE: u32 = 20;
+/// Upper bound on the length of a document type
+pub const MAX_DOC_TYPE_LENGTH: u32 = 50;
+/// Upper bound on the length of a URI hint
+pub const MAX_URI_HINT_LENGTH: u32 = 256;
+
+/// An off-chain document anchored to an invoice by its content hash
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceDocument {
+    /// SHA-256 of the document contents
+    pub doc_hash: BytesN<32>,
+    /// Free-form kind, e.g. "invoice_pdf", "purchase_order", "contract"
+    pub doc_type: String,
+    /// Where the document can be fetched (IPFS CID, URL); not verified on-chain
+    pub uri_hint: String,
+    pub attached_by: Address,
+    pub attached_at: u64,
+}
+
+pub struct InvoiceDocuments;
+
+impl InvoiceDocuments {
+    /// Documents attached to an invoice, in attachment order
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Vec<InvoiceDocument> {
+        env.storage()
+            .instance()
+            .get(&(DOCUMENTS_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set(env: &Env, invoice_id: &BytesN<32>, documents: &Vec<InvoiceDocument>) {
+        env.storage()
+            .instance()
+            .set(&(DOCUMENTS_KEY, invoice_id.clone()), documents);
+    }
+
+    /// Attach a document hash to an invoice on behalf of its business.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`
+    /// * `InvalidDescription` if the type or URI hint is empty or too long
+    /// * `OperationNotAllowed` if the hash is already attached or the invoice has
+    ///   `MAX_DOCUMENTS_PER_INVOICE` documents
+    pub fn attach(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        doc_hash: BytesN<32>,
+        doc_type: String,
+        uri_hint: String,
+    ) -> Result<InvoiceDocument, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if doc_type.len() == 0
+            || doc_type.len() > MAX_DOC_TYPE_LENGTH
+            || uri_hint.len() == 0
+            || uri_hint.len() > MAX_URI_HINT_LENGTH
+        {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+
+        let mut documents = Self::get(env, invoice_id);
+        if documents.len() >= MAX_DOCUMENTS_PER_INVOICE
+            || documents.iter().any(|doc| doc.doc_hash == doc_hash)
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let document = InvoiceDocument {
+            doc_hash,
+            doc_type,
+            uri_hint,
+            attached_by: invoice.business,
+            attached_at: env.ledger().timestamp(),
+        };
+        documents.push_back(document.clone());
+        Self::set(env, invoice_id, &documents);
+
+        emit_invoice_document_attached(env, invoice_id, &document);
+        Ok(document)
+    }
+
+    /// Remove a document while the invoice is still Pending or Verified.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` once the invoice has been funded or closed
+    /// * `StorageKeyNotFound` if the hash is not attached
+    pub fn remove(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        doc_hash: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let mut documents = Self::get(env, invoice_id);
+        let index = documents
+            .iter()
+            .position(|doc| doc.doc_hash == *doc_hash)
+            .ok_or(QuickLendXError::StorageKeyNotFound)? as u32;
+        documents.remove(index);
+        Self::set(env, invoice_id, &documents);
+
+        emit_invoice_document_removed(env, invoice_id, doc_hash);
+        Ok(())
+    }
+}
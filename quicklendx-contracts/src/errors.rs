@@ -17,7 +17,7 @@ pub enum QuickLendXError {
     PaymentTooLow = 1008,
     OperationNotAllowed = 1009,
     InsufficientFunds = 1010,
-    InvalidAddress = 1011,
+    DuplicatePayment = 1011,
     InvalidDescription = 1012,
     InvoiceDueDateInvalid = 1013,
     NotInvestor = 1014,
@@ -25,7 +25,7 @@ pub enum QuickLendXError {
     InvalidCurrency = 1016,
     InvalidTimestamp = 1017,
     StorageError = 1018,
-    PlatformAccountNotConfigured = 1019,
+    BidExceedsLimit = 1019,
     InvalidCoveragePercentage = 1020,
     InvalidRating = 1021,
     NotFunded = 1022,
@@ -36,10 +36,10 @@ pub enum QuickLendXError {
     KYCNotFound = 1027,
     InvalidKYCStatus = 1028,
     AuditLogNotFound = 1029,
-    AuditIntegrityError = 1030,
-    AuditQueryError = 1031,
-    InvalidFeeConfiguration = 1032,
-    TreasuryNotConfigured = 1033,
+    InvoiceNotVerified = 1030,
+    BidNotPlaced = 1031,
+    CurrencyNotWhitelisted = 1032,
+    BidExpired = 1033,
     InvalidFeeBasisPoints = 1034,
     InvalidTag = 1035,
     TagLimitExceeded = 1036,
@@ -72,7 +72,7 @@ impl From<QuickLendXError> for Symbol {
             QuickLendXError::PaymentTooLow => symbol_short!("PAY_LOW"),
             QuickLendXError::OperationNotAllowed => symbol_short!("OP_NA"),
             QuickLendXError::InsufficientFunds => symbol_short!("INSUF"),
-            QuickLendXError::InvalidAddress => symbol_short!("INV_ADR"),
+            QuickLendXError::DuplicatePayment => symbol_short!("DUP_PAY"),
             QuickLendXError::InvalidDescription => symbol_short!("INV_DS"),
             QuickLendXError::InvoiceDueDateInvalid => symbol_short!("INV_DI"),
             QuickLendXError::NotInvestor => symbol_short!("NOT_INV"),
@@ -80,7 +80,7 @@ impl From<QuickLendXError> for Symbol {
             QuickLendXError::InvalidCurrency => symbol_short!("INV_CR"),
             QuickLendXError::InvalidTimestamp => symbol_short!("INV_TM"),
             QuickLendXError::StorageError => symbol_short!("STORE"),
-            QuickLendXError::PlatformAccountNotConfigured => symbol_short!("PLT_NC"),
+            QuickLendXError::BidExceedsLimit => symbol_short!("BID_LIM"),
             QuickLendXError::InvalidCoveragePercentage => symbol_short!("INS_CV"),
             QuickLendXError::InvalidRating => symbol_short!("INV_RT"),
             QuickLendXError::NotFunded => symbol_short!("NOT_FD"),
@@ -91,10 +91,10 @@ impl From<QuickLendXError> for Symbol {
             QuickLendXError::KYCNotFound => symbol_short!("KYC_NF"),
             QuickLendXError::InvalidKYCStatus => symbol_short!("KYC_IS"),
             QuickLendXError::AuditLogNotFound => symbol_short!("AUD_NF"),
-            QuickLendXError::AuditIntegrityError => symbol_short!("AUD_IE"),
-            QuickLendXError::AuditQueryError => symbol_short!("AUD_QE"),
-            QuickLendXError::InvalidFeeConfiguration => symbol_short!("FEE_CFG"),
-            QuickLendXError::TreasuryNotConfigured => symbol_short!("TRS_NC"),
+            QuickLendXError::InvoiceNotVerified => symbol_short!("INV_NV"),
+            QuickLendXError::BidNotPlaced => symbol_short!("BID_NP"),
+            QuickLendXError::CurrencyNotWhitelisted => symbol_short!("CUR_NW"),
+            QuickLendXError::BidExpired => symbol_short!("BID_EXP"),
             QuickLendXError::InvalidFeeBasisPoints => symbol_short!("FEE_BPS"),
             QuickLendXError::InvalidTag => symbol_short!("INV_TAG"),
             QuickLendXError::TagLimitExceeded => symbol_short!("TAG_LIM"),
@@ -4,12 +4,25 @@
 //! creates escrow via payments, and updates bid, invoice, and investment state.
 
 use crate::admin::AdminStorage;
-use crate::bid::{BidStatus, BidStorage};
+use crate::bid::{Bid, BidStatus, BidStorage};
+use crate::bid_bond::BidBonds;
+use crate::currency::CurrencyConversion;
 use crate::errors::QuickLendXError;
-use crate::events::{emit_escrow_refunded, emit_invoice_funded};
+use crate::events::{
+    emit_bid_accepted, emit_escrow_refunded, emit_invoice_bidding_expired, emit_invoice_funded,
+    emit_invoice_funding_expired,
+};
+use crate::exposure::ExposureLimits;
+use crate::fees::FeeManager;
 use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
-use crate::invoice::{InvoiceStatus, InvoiceStorage};
-use crate::payments::{create_escrow, refund_escrow};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::invoice_freeze::InvoiceFreezes;
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::payments::{create_escrow, ensure_can_pay, escrow_shortfall, refund_escrow};
+use crate::required_insurance::InsuranceRequirements;
+use crate::state_machine::TransitionRole;
+use crate::tax_lots::{LotDisposal, TaxLots};
+use crate::verification::validate_investor_investment;
 use soroban_sdk::{Address, BytesN, Env, Vec};
 
 /// Accept a bid and fund the invoice: transfer in from investor, create escrow, update state.
@@ -20,21 +33,50 @@ use soroban_sdk::{Address, BytesN, Env, Vec};
 /// * `Ok(escrow_id)` - The new escrow ID
 ///
 /// # Errors
-/// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `InvoiceAlreadyFunded`,
-///   `InvoiceNotAvailableForFunding`, `Unauthorized`, or errors from `create_escrow`
+/// * `InvoiceNotFound`, `StorageKeyNotFound`, `BidNotPlaced`, `BidExpired`,
+///   `InvoiceAlreadyFunded`, `InvoiceNotAvailableForFunding`, `Unauthorized`, or errors from
+///   `create_escrow`
 pub fn accept_bid_and_fund(
     env: &Env,
     invoice_id: &BytesN<32>,
     bid_id: &BytesN<32>,
 ) -> Result<BytesN<32>, QuickLendXError> {
     // 1. Retrieve Invoice
-    let mut invoice =
+    let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
 
     // 2. Auth checks
     // Verify that the caller is the business owner of the invoice
     invoice.business.require_auth();
 
+    fund_invoice_with_bid(env, invoice_id, bid_id, TransitionRole::Business)
+}
+
+/// Fund an invoice from a placed bid without business authorization.
+///
+/// Used by `accept_bid_and_fund` after the business has authorized, and by buy-it-now
+/// bids that meet the invoice's `instant_funding_price` (the business opted in at upload).
+/// `role` is the party the funding is made for, checked against the invoice state machine.
+///
+/// # Returns
+/// * `Ok(escrow_id)` - The new escrow ID
+///
+/// # Errors
+/// * `InvoiceNotFound`, `StorageKeyNotFound`, `BidNotPlaced`, `BidExpired`,
+///   `InvoiceAlreadyFunded`, `InvoiceNotAvailableForFunding`, `Unauthorized`, or errors from
+///   `create_escrow`
+/// * `OperationNotAllowed` if the invoice requires insurance the bid has not attached, or errors
+///   from buying the attached coverage
+pub fn fund_invoice_with_bid(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    bid_id: &BytesN<32>,
+    role: TransitionRole,
+) -> Result<BytesN<32>, QuickLendXError> {
+    let mut invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+
     // 3. Invariant checks
     // Invoice must be in Verified status
     if invoice.status != InvoiceStatus::Verified {
@@ -45,34 +87,14 @@ pub fn accept_bid_and_fund(
         return Err(QuickLendXError::InvoiceNotAvailableForFunding);
     }
 
-    // 4. Retrieve Bid
+    // 4. Retrieve Bid and check it can fund the invoice before anything is written
     let mut bid = BidStorage::get_bid(env, bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
-
-    // Bid must match invoice
-    if bid.invoice_id != *invoice_id {
-        return Err(QuickLendXError::Unauthorized);
-    }
-
-    // Bid must be Placed
-    if bid.status != BidStatus::Placed {
-        return Err(QuickLendXError::InvalidStatus);
-    }
-
-    // Check bid expiration
-    if bid.is_expired(env.ledger().timestamp()) {
-        return Err(QuickLendXError::InvalidStatus);
-    }
+    ensure_bid_can_fund(env, &invoice, &bid)?;
 
     // 5. Lock funds in escrow
-    // This calls payments::create_escrow which calls token transfer and emits emit_escrow_created
-    let escrow_id = create_escrow(
-        env,
-        invoice_id,
-        &bid.investor,
-        &invoice.business,
-        bid.bid_amount,
-        &invoice.currency,
-    )?;
+    // This calls payments::create_escrow which calls token transfer and emits emit_escrow_created.
+    let escrow_id = escrow_bid_funds(env, &invoice, &bid)?;
+    BidBonds::release_losing_bonds(env, invoice_id, Some(bid_id))?;
 
     // 6. Update states
 
@@ -87,12 +109,15 @@ pub fn accept_bid_and_fund(
         bid.investor.clone(),
         bid.bid_amount,
         env.ledger().timestamp(),
-    );
+        role,
+    )?;
     InvoiceStorage::update_invoice(env, &invoice);
+    InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Verified, invoice_id);
+    InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Funded, invoice_id);
 
     // Create Investment
     let investment_id = InvestmentStorage::generate_unique_investment_id(env);
-    let investment = Investment {
+    let mut investment = Investment {
         investment_id: investment_id.clone(),
         invoice_id: invoice_id.clone(),
         investor: bid.investor.clone(),
@@ -102,6 +127,9 @@ pub fn accept_bid_and_fund(
         insurance: Vec::new(env),
     };
     InvestmentStorage::store_investment(env, &investment);
+    ExposureLimits::record_investment_source(env, &investment_id, bid_id);
+    FeeManager::lock_fee_quote(env, invoice_id, &invoice.business, &bid.investor);
+    InsuranceRequirements::buy_at_funding(env, &invoice, bid_id, &mut investment)?;
 
     // 7. Events
     emit_invoice_funded(env, invoice_id, &bid.investor, bid.bid_amount);
@@ -109,6 +137,250 @@ pub fn accept_bid_and_fund(
     Ok(escrow_id)
 }
 
+/// Check that a placed bid can fund a Verified `invoice` now, without changing anything.
+///
+/// `fund_invoice_with_bid` makes these checks before its first write: the bid is placed on the
+/// invoice and unexpired, it fits the exposure caps, it carries any insurance the invoice
+/// requires, and the investor can pay both the escrow shortfall (after any bid bond and deposit)
+/// and the premium of the attached coverage.
+///
+/// # Errors
+/// * `Unauthorized` if the bid is for another invoice, `BidNotPlaced`, `BidExpired`
+/// * Exposure limit errors, `OperationNotAllowed` if required insurance is missing, or errors
+///   from `InsurancePurchases::check`
+/// * Conversion errors when funding in another token
+/// * `InsufficientFunds`, `OperationNotAllowed` (insufficient allowance) from `ensure_can_pay`
+pub fn ensure_bid_can_fund(env: &Env, invoice: &Invoice, bid: &Bid) -> Result<(), QuickLendXError> {
+    if bid.invoice_id != invoice.id {
+        return Err(QuickLendXError::Unauthorized);
+    }
+    if bid.status != BidStatus::Placed {
+        return Err(QuickLendXError::BidNotPlaced);
+    }
+    if bid.is_expired(env.ledger().timestamp()) {
+        return Err(QuickLendXError::BidExpired);
+    }
+
+    ExposureLimits::check_funding(env, &invoice.category, bid.bid_amount)?;
+    ExposureLimits::check_business_funding(env, &invoice.business, bid.bid_amount)?;
+    ExposureLimits::check_source_funding(env, bid)?;
+    InsuranceRequirements::ensure_met(env, invoice, &bid.bid_id)?;
+    let premium = InsuranceRequirements::premium_at_funding(env, invoice, bid)?;
+
+    let funding_currency = BidStorage::get_funding_currency(env, &bid.bid_id)
+        .unwrap_or_else(|| invoice.currency.clone());
+    if funding_currency != invoice.currency {
+        let funding_amount =
+            CurrencyConversion::convert(env, bid.bid_amount, &invoice.currency, &funding_currency)?;
+        let shortfall = escrow_shortfall(env, &bid.investor, &funding_currency, funding_amount);
+        ensure_can_pay(env, &funding_currency, &bid.investor, shortfall)?;
+        return ensure_can_pay(env, &invoice.currency, &bid.investor, premium);
+    }
+
+    let prepaid = BidBonds::prepaid_amount(env, &bid.bid_id, bid.bid_amount);
+    let shortfall = escrow_shortfall(
+        env,
+        &bid.investor,
+        &invoice.currency,
+        bid.bid_amount - prepaid,
+    );
+    ensure_can_pay(env, &invoice.currency, &bid.investor, shortfall + premium)
+}
+
+/// Lock a bid's advance in escrow, in the token the bid funds in.
+///
+/// A bid bond is posted in the invoice currency, so it counts toward the escrowed amount only
+/// when the bid funds in the invoice currency; otherwise it is returned to the investor.
+pub fn escrow_bid_funds(
+    env: &Env,
+    invoice: &Invoice,
+    bid: &Bid,
+) -> Result<BytesN<32>, QuickLendXError> {
+    let funding_currency = BidStorage::get_funding_currency(env, &bid.bid_id)
+        .unwrap_or_else(|| invoice.currency.clone());
+    if funding_currency != invoice.currency {
+        let escrow_id = create_escrow(
+            env,
+            invoice,
+            &bid.investor,
+            bid.bid_amount,
+            &funding_currency,
+            0,
+        )?;
+        BidBonds::release(env, &bid.bid_id)?;
+        return Ok(escrow_id);
+    }
+
+    let prepaid = BidBonds::prepaid_amount(env, &bid.bid_id, bid.bid_amount);
+    let escrow_id = create_escrow(
+        env,
+        invoice,
+        &bid.investor,
+        bid.bid_amount,
+        &invoice.currency,
+        prepaid,
+    )?;
+    BidBonds::apply_to_funding(env, &bid.bid_id, bid.bid_amount)?;
+    Ok(escrow_id)
+}
+
+/// Accept a business's counter-offer and fund the invoice on the countered terms.
+///
+/// Caller (investor who placed the bid) must be authorized. The bid takes the proposed amount
+/// and return, then funds the invoice through the same escrow path as `accept_bid_and_fund`.
+///
+/// # Returns
+/// * `Ok(escrow_id)` - The new escrow ID
+///
+/// # Errors
+/// * `StorageKeyNotFound` if the bid or counter-offer does not exist
+/// * `InvalidStatus` if the bid is not Countered, `BidExpired` if it has expired
+/// * Investment limit errors, or any error from `fund_invoice_with_bid`
+pub fn accept_counter_offer(env: &Env, bid_id: &BytesN<32>) -> Result<BytesN<32>, QuickLendXError> {
+    let mut bid = BidStorage::get_bid(env, bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+    bid.investor.require_auth();
+
+    if bid.status != BidStatus::Countered {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    if bid.is_expired(env.ledger().timestamp()) {
+        return Err(QuickLendXError::BidExpired);
+    }
+    let counter =
+        BidStorage::get_counter_offer(env, bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+    validate_investor_investment(env, &bid.investor, counter.proposed_amount)?;
+
+    bid.bid_amount = counter.proposed_amount;
+    bid.expected_return = counter.proposed_return;
+    bid.discount_rate_bps = None;
+    bid.status = BidStatus::Placed;
+    BidStorage::update_bid(env, &bid);
+    BidStorage::remove_counter_offer(env, bid_id);
+
+    let escrow_id = fund_invoice_with_bid(env, &bid.invoice_id, bid_id, TransitionRole::Investor)?;
+
+    let funded = InvoiceStorage::get_invoice(env, &bid.invoice_id)
+        .ok_or(QuickLendXError::InvoiceNotFound)?;
+    emit_bid_accepted(env, &bid, &bid.invoice_id, &funded.business);
+    NotificationHooks::fire(env, &funded, NotificationHook::InvoiceFunded(bid.clone()));
+
+    Ok(escrow_id)
+}
+
+/// Close bidding on an invoice once its bidding deadline has passed.
+///
+/// Keeper-callable (no authorization). Accepts the top-ranked placed bid that passes
+/// `ensure_bid_can_fund` through the same escrow path as `accept_bid_and_fund`, skipping
+/// higher-ranked bids that fail it (for example, the investor's allowance was revoked). If no
+/// bid can fund the invoice, it is marked `Expired`.
+///
+/// # Returns
+/// * `Ok(Some(bid_id))` - The accepted bid
+/// * `Ok(None)` - No valid bid; the invoice expired
+///
+/// # Errors
+/// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Verified
+/// * `OperationNotAllowed` if no bidding deadline is set or it has not passed yet
+/// * Any error from `fund_invoice_with_bid` for the bid being accepted, which reverts the call
+pub fn finalize_bidding(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+) -> Result<Option<BytesN<32>>, QuickLendXError> {
+    let mut invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+    if invoice.status != InvoiceStatus::Verified {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let deadline = invoice
+        .bidding_deadline
+        .ok_or(QuickLendXError::OperationNotAllowed)?;
+    if env.ledger().timestamp() < deadline {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    // rank_bids refreshes expired bids and only returns Placed ones
+    for bid in BidStorage::rank_bids(env, invoice_id).iter() {
+        if ensure_bid_can_fund(env, &invoice, &bid).is_err() {
+            continue;
+        }
+        fund_invoice_with_bid(env, invoice_id, &bid.bid_id, TransitionRole::Keeper)?;
+        emit_bid_accepted(env, &bid, invoice_id, &invoice.business);
+        let funded =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        NotificationHooks::fire(env, &funded, NotificationHook::InvoiceFunded(bid.clone()));
+        return Ok(Some(bid.bid_id));
+    }
+
+    invoice.mark_as_expired(env, env.current_contract_address())?;
+    InvoiceStorage::update_invoice(env, &invoice);
+    BidBonds::release_losing_bonds(env, invoice_id, None)?;
+    InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Verified, invoice_id);
+    InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Expired, invoice_id);
+    emit_invoice_bidding_expired(env, &invoice);
+    NotificationHooks::fire(
+        env,
+        &invoice,
+        NotificationHook::StatusChanged(InvoiceStatus::Verified, InvoiceStatus::Expired),
+    );
+
+    Ok(None)
+}
+
+/// Expire an invoice that was not funded by its funding deadline.
+///
+/// Keeper-callable (no authorization). Marks the invoice `Expired`, expires its open bids
+/// (returning their bonds and dropping pending counter-offers) and notifies the business.
+///
+/// # Returns
+/// * `Ok(bids_expired)` - The number of open bids expired
+///
+/// # Errors
+/// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+/// * `OperationNotAllowed` if no funding deadline is set or it has not passed yet
+pub fn expire_unfunded_invoice(env: &Env, invoice_id: &BytesN<32>) -> Result<u32, QuickLendXError> {
+    let mut invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+    if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let deadline = invoice
+        .funding_deadline
+        .ok_or(QuickLendXError::OperationNotAllowed)?;
+    if env.ledger().timestamp() <= deadline {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    let old_status = invoice.status.clone();
+    invoice.mark_as_expired(env, env.current_contract_address())?;
+    InvoiceStorage::update_invoice(env, &invoice);
+    InvoiceStorage::remove_from_status_invoices(env, &old_status, invoice_id);
+    InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Expired, invoice_id);
+
+    let mut bids_expired = 0u32;
+    for bid_id in BidStorage::get_bids_for_invoice(env, invoice_id).iter() {
+        let mut bid = match BidStorage::get_bid(env, &bid_id) {
+            Some(bid) if bid.is_open() => bid,
+            _ => continue,
+        };
+        bid.status = BidStatus::Expired;
+        BidStorage::update_bid(env, &bid);
+        BidStorage::remove_counter_offer(env, &bid_id);
+        bids_expired += 1;
+    }
+    BidBonds::release_losing_bonds(env, invoice_id, None)?;
+
+    emit_invoice_funding_expired(env, &invoice);
+    NotificationHooks::fire(
+        env,
+        &invoice,
+        NotificationHook::StatusChanged(old_status.clone(), InvoiceStatus::Expired),
+    );
+
+    Ok(bids_expired)
+}
+
 /// Explicitly refund escrowed funds to the investor.
 ///
 /// Can be triggered by the Admin or the Business owner of the invoice.
@@ -116,14 +388,16 @@ pub fn accept_bid_and_fund(
 ///
 /// # Errors
 /// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `Unauthorized`, `NotAdmin`
+/// * `OperationNotAllowed` if the escrow is frozen by an unresolved dispute
 pub fn refund_escrow_funds(
     env: &Env,
     invoice_id: &BytesN<32>,
     caller: &Address,
 ) -> Result<(), QuickLendXError> {
     // 1. Retrieve Invoice
-    let mut invoice =
+    let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     // 2. Authorization check
     // Caller must be either the Admin or the Business owner
@@ -137,6 +411,29 @@ pub fn refund_escrow_funds(
     // Explicitly require auth from the caller
     caller.require_auth();
 
+    let role = if is_admin {
+        TransitionRole::Admin
+    } else {
+        TransitionRole::Business
+    };
+    refund_funded_invoice(env, invoice, caller, role)
+}
+
+/// Refund a funded invoice's escrow to the investor and mark the invoice, its accepted bid and
+/// its investment refunded. Callers check authorization.
+///
+/// # Errors
+/// * `InvalidStatus` if the invoice is not Funded or the escrow is not Held
+/// * `StorageKeyNotFound` if the invoice has no escrow
+/// * `OperationNotAllowed` if the escrow is frozen by an unresolved dispute
+pub fn refund_funded_invoice(
+    env: &Env,
+    mut invoice: Invoice,
+    caller: &Address,
+    role: TransitionRole,
+) -> Result<(), QuickLendXError> {
+    let invoice_id = &invoice.id.clone();
+
     // 3. State check
     // Invoice must be in Funded status to be eligible for refund
     if invoice.status != InvoiceStatus::Funded {
@@ -149,13 +446,13 @@ pub fn refund_escrow_funds(
 
     // 5. Transfer funds and update escrow state
     // This calls payments::refund_escrow which handles the token transfer and status update
-    refund_escrow(env, invoice_id)?;
+    let refunded = refund_escrow(env, invoice_id)?;
 
     // 6. Update internal states
 
     // Update Invoice status to Refunded
     let previous_status = invoice.status.clone();
-    invoice.mark_as_refunded(env, caller.clone());
+    invoice.mark_as_refunded(env, caller.clone(), role)?;
     InvoiceStorage::update_invoice(env, &invoice);
 
     // Update status indices
@@ -177,6 +474,12 @@ pub fn refund_escrow_funds(
     if let Some(mut investment) = InvestmentStorage::get_investment_by_invoice(env, invoice_id) {
         investment.status = InvestmentStatus::Refunded;
         InvestmentStorage::update_investment(env, &investment);
+        TaxLots::dispose(
+            env,
+            &investment.investment_id,
+            LotDisposal::Refund,
+            refunded,
+        );
     }
 
     // 7. Emit events
@@ -185,7 +488,12 @@ pub fn refund_escrow_funds(
         &escrow.escrow_id,
         invoice_id,
         &escrow.investor,
-        escrow.amount,
+        refunded,
+    );
+    NotificationHooks::fire(
+        env,
+        &invoice,
+        NotificationHook::StatusChanged(previous_status, InvoiceStatus::Refunded),
     );
 
     Ok(())
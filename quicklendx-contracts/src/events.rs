@@ -1,9 +1,25 @@
-use crate::bid::Bid;
+use crate::bid::{Bid, CounterOffer};
+use crate::bid_bond::BidBond;
+use crate::collections::CollectionCase;
+use crate::deposits::PendingWithdrawal;
+use crate::dispute_templates::{DisputeOutcome, ResolutionTemplate};
+use crate::documents::InvoiceDocument;
+use crate::fees::{FeePromotion, LatePenaltyRecord};
+use crate::insurance::InsuranceClaim;
+use crate::insurance_pool::{PremiumRefund, PremiumSplit};
+use crate::insurance_registry::InsuranceProvider;
 use crate::invoice::{Invoice, InvoiceMetadata};
-use crate::payments::Escrow;
+use crate::invoice_freeze::InvoiceFreeze;
+use crate::invoice_messages::InvoiceMessage;
+use crate::notifications::DeadLetter;
+use crate::payments::{Escrow, ReleaseApproval};
+use crate::payout::PayoutAddressChange;
 use crate::profits::PlatformFeeConfig;
+use crate::reconciliation::EscrowReconciliation;
+use crate::settlement::{OverpaymentRefund, PendingSettlement, SettlementReceipt};
+use crate::suitability::SuitabilityAcknowledgment;
 use crate::verification::InvestorVerification;
-use soroban_sdk::{symbol_short, Address, BytesN, Env, String};
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Vec};
 
 pub fn emit_invoice_uploaded(env: &Env, invoice: &Invoice) {
     env.events().publish(
@@ -66,6 +82,44 @@ pub fn emit_invoice_metadata_cleared(env: &Env, invoice: &Invoice) {
     );
 }
 
+/// Emit event when an invoice's line items are replaced
+pub fn emit_invoice_line_items_updated(env: &Env, invoice: &Invoice) {
+    env.events().publish(
+        (symbol_short!("inv_items"),),
+        (
+            invoice.id.clone(),
+            invoice.metadata_line_items.len(),
+            invoice.amount,
+        ),
+    );
+}
+
+/// Emit event when a business assigns an invoice number
+pub fn emit_invoice_number_set(env: &Env, invoice: &Invoice, invoice_number: &String) {
+    env.events().publish(
+        (symbol_short!("inv_num"),),
+        (
+            invoice.id.clone(),
+            invoice.business.clone(),
+            invoice_number.clone(),
+        ),
+    );
+}
+
+/// Emit event when a business pays to feature an invoice in the marketplace
+pub fn emit_invoice_boosted(env: &Env, invoice: &Invoice, days: u32, fee: i128) {
+    env.events().publish(
+        (symbol_short!("inv_bst"),),
+        (
+            invoice.id.clone(),
+            invoice.business.clone(),
+            days,
+            fee,
+            invoice.boosted_until,
+        ),
+    );
+}
+
 pub fn emit_investor_verified(env: &Env, verification: &InvestorVerification) {
     env.events().publish(
         (symbol_short!("inv_veri"),),
@@ -131,6 +185,48 @@ pub fn emit_invoice_expired(env: &Env, invoice: &crate::invoice::Invoice) {
     );
 }
 
+/// Emit event when bidding closes without the invoice being funded
+pub fn emit_invoice_bidding_expired(env: &Env, invoice: &crate::invoice::Invoice) {
+    env.events().publish(
+        (symbol_short!("inv_unf"),),
+        (
+            invoice.id.clone(),
+            invoice.business.clone(),
+            invoice.bidding_deadline.unwrap_or(0),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+pub fn emit_invoice_funding_expired(env: &Env, invoice: &crate::invoice::Invoice) {
+    env.events().publish(
+        (symbol_short!("inv_fexp"),),
+        (
+            invoice.id.clone(),
+            invoice.business.clone(),
+            invoice.funding_deadline.unwrap_or(0),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a stale verification sends an invoice back to Pending
+pub fn emit_invoice_verification_expired(
+    env: &Env,
+    invoice: &crate::invoice::Invoice,
+    verified_at: u64,
+) {
+    env.events().publish(
+        (symbol_short!("inv_stal"),),
+        (
+            invoice.id.clone(),
+            invoice.business.clone(),
+            verified_at,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
 pub fn emit_invoice_defaulted(env: &Env, invoice: &crate::invoice::Invoice) {
     env.events().publish(
         (symbol_short!("inv_def"),),
@@ -182,6 +278,49 @@ pub fn emit_insurance_premium_collected(
     );
 }
 
+/// Emit event when a premium is divided between the insurance pool and the protocol treasury
+pub fn emit_insurance_premium_split(
+    env: &Env,
+    investment_id: &BytesN<32>,
+    payer: &Address,
+    currency: &Address,
+    split: &PremiumSplit,
+) {
+    env.events().publish(
+        (symbol_short!("prm_splt"),),
+        (
+            investment_id.clone(),
+            payer.clone(),
+            currency.clone(),
+            split.premium,
+            split.pool_amount,
+            split.protocol_fee,
+            split.fee_recipient.clone(),
+        ),
+    );
+}
+
+/// Emit event when an investor cancels coverage and the unused premium is refunded
+pub fn emit_insurance_cancelled(
+    env: &Env,
+    investment_id: &BytesN<32>,
+    investor: &Address,
+    provider: &Address,
+    refund: &PremiumRefund,
+) {
+    env.events().publish(
+        (symbol_short!("ins_cncl"),),
+        (
+            investment_id.clone(),
+            investor.clone(),
+            provider.clone(),
+            refund.unused_premium,
+            refund.cancellation_fee,
+            refund.refund,
+        ),
+    );
+}
+
 pub fn emit_insurance_claimed(
     env: &Env,
     investment_id: &BytesN<32>,
@@ -207,6 +346,121 @@ pub fn emit_platform_fee_updated(env: &Env, config: &PlatformFeeConfig) {
     );
 }
 
+/// Emit event when insurance coverage is paid to an investor from the currency's insurance pool
+pub fn emit_insurance_claim_paid(env: &Env, claim: &InsuranceClaim, amount: i128) {
+    env.events().publish(
+        (symbol_short!("ins_pay"),),
+        (
+            claim.investment_id.clone(),
+            claim.provider.clone(),
+            claim.investor.clone(),
+            amount,
+            claim.coverage_amount - claim.paid_amount,
+        ),
+    );
+}
+
+/// Emit event when an investor files a default claim for review
+pub fn emit_insurance_claim_filed(env: &Env, claim: &InsuranceClaim) {
+    env.events().publish(
+        (symbol_short!("ins_cfil"),),
+        (
+            claim.investment_id.clone(),
+            claim.provider.clone(),
+            claim.investor.clone(),
+            claim.coverage_amount,
+            claim.review_deadline,
+        ),
+    );
+}
+
+/// Emit event when a reviewed default claim is approved or denied
+pub fn emit_insurance_claim_resolved(env: &Env, claim: &InsuranceClaim) {
+    env.events().publish(
+        (symbol_short!("ins_cres"),),
+        (
+            claim.investment_id.clone(),
+            claim.provider.clone(),
+            claim.status.clone(),
+            claim.paid_amount,
+        ),
+    );
+}
+
+/// Emit event when an insurance provider is registered, changes status or gets a new capacity
+pub fn emit_insurance_provider_updated(env: &Env, record: &InsuranceProvider) {
+    env.events().publish(
+        (symbol_short!("ins_prv"),),
+        (
+            record.provider.clone(),
+            record.status.clone(),
+            record.capacity,
+            record.updated_at,
+        ),
+    );
+}
+
+/// Emit event when a provider stakes into an insurance pool
+pub fn emit_insurance_pool_staked(
+    env: &Env,
+    provider: &Address,
+    currency: &Address,
+    amount: i128,
+    shares: i128,
+) {
+    env.events().publish(
+        (symbol_short!("ipl_stk"),),
+        (provider.clone(), currency.clone(), amount, shares),
+    );
+}
+
+/// Emit event when a provider withdraws stake from an insurance pool
+pub fn emit_insurance_pool_unstaked(
+    env: &Env,
+    provider: &Address,
+    currency: &Address,
+    amount: i128,
+    shares: i128,
+) {
+    env.events().publish(
+        (symbol_short!("ipl_uns"),),
+        (provider.clone(), currency.clone(), amount, shares),
+    );
+}
+
+/// Emit event when a recovery is collected on a defaulted invoice
+pub fn emit_default_recovery(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payer: &Address,
+    investor_amount: i128,
+    clawback_amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("dflt_rec"),),
+        (
+            invoice_id.clone(),
+            payer.clone(),
+            investor_amount,
+            clawback_amount,
+        ),
+    );
+}
+
+/// Emit event when a defaulted invoice's collection case opens or changes
+pub fn emit_collection_case_updated(env: &Env, case: &CollectionCase) {
+    env.events().publish(
+        (symbol_short!("col_upd"),),
+        (
+            case.invoice_id.clone(),
+            case.status.clone(),
+            case.total_recovered,
+            case.outstanding,
+            case.updated_at,
+        ),
+    );
+}
+
 /// Emit event when escrow is created
 pub fn emit_escrow_created(env: &Env, escrow: &Escrow) {
     env.events().publish(
@@ -259,6 +513,69 @@ pub fn emit_escrow_refunded(
     );
 }
 
+/// Emit event when a dispute freezes an invoice's escrow
+pub fn emit_escrow_frozen(env: &Env, escrow_id: &BytesN<32>, invoice_id: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("esc_frz"),),
+        (escrow_id.clone(), invoice_id.clone()),
+    );
+}
+
+/// Emit event when the admin freezes an invoice
+pub fn emit_invoice_frozen(env: &Env, freeze: &InvoiceFreeze) {
+    env.events().publish(
+        (symbol_short!("inv_frz"),),
+        (
+            freeze.invoice_id.clone(),
+            freeze.frozen_by.clone(),
+            freeze.reason.clone(),
+            freeze.frozen_at,
+        ),
+    );
+}
+
+/// Emit event when the admin lifts an invoice's freeze
+pub fn emit_invoice_unfrozen(env: &Env, invoice_id: &BytesN<32>, admin: &Address) {
+    env.events().publish(
+        (symbol_short!("inv_ufrz"),),
+        (invoice_id.clone(), admin.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit event when an invoice's escrow is approved for release
+pub fn emit_escrow_release_approved(env: &Env, approval: &ReleaseApproval) {
+    env.events().publish(
+        (symbol_short!("rel_apr"),),
+        (
+            approval.invoice_id.clone(),
+            approval.approver.clone(),
+            approval.approved_at,
+        ),
+    );
+}
+
+/// Emit event when a resolved dispute unfreezes an invoice's escrow
+pub fn emit_escrow_unfrozen(env: &Env, escrow_id: &BytesN<32>, invoice_id: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("esc_unf"),),
+        (escrow_id.clone(), invoice_id.clone()),
+    );
+}
+
+/// Emit alert when the escrows held in a currency are not covered by the contract's balances
+pub fn emit_escrow_reconciliation_alert(env: &Env, report: &EscrowReconciliation) {
+    env.events().publish(
+        (symbol_short!("esc_alrt"),),
+        (
+            report.currency.clone(),
+            report.escrowed,
+            report.segregated_balance,
+            report.token_balance,
+            report.checked_at,
+        ),
+    );
+}
+
 pub fn emit_bid_expired(env: &Env, bid: &Bid) {
     env.events().publish(
         (symbol_short!("bid_exp"),),
@@ -272,6 +589,75 @@ pub fn emit_bid_expired(env: &Env, bid: &Bid) {
     );
 }
 
+/// Emit event when a bid's expiration is extended by its investor
+pub fn emit_bid_expiration_extended(env: &Env, bid: &Bid, old_expiration: u64) {
+    env.events().publish(
+        (symbol_short!("bid_ext"),),
+        (
+            bid.bid_id.clone(),
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            old_expiration,
+            bid.expiration_timestamp,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a business counters a bid
+pub fn emit_bid_countered(env: &Env, bid: &Bid, counter: &CounterOffer) {
+    env.events().publish(
+        (symbol_short!("bid_ctr"),),
+        (
+            bid.bid_id.clone(),
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            counter.proposed_amount,
+            counter.proposed_return,
+            counter.countered_at,
+        ),
+    );
+}
+
+/// Emit event when an investor rejects a counter-offer
+pub fn emit_counter_rejected(env: &Env, bid: &Bid) {
+    env.events().publish(
+        (symbol_short!("ctr_rej"),),
+        (
+            bid.bid_id.clone(),
+            bid.invoice_id.clone(),
+            bid.investor.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a bid bond is locked in the contract
+pub fn emit_bid_bond_locked(env: &Env, bond: &BidBond) {
+    env.events().publish(
+        (symbol_short!("bnd_lock"),),
+        (
+            bond.bid_id.clone(),
+            bond.investor.clone(),
+            bond.amount,
+            bond.locked_at,
+        ),
+    );
+}
+
+/// Emit event when a bid bond is returned to the investor
+pub fn emit_bid_bond_released(env: &Env, bond: &BidBond) {
+    env.events().publish(
+        (symbol_short!("bnd_rel"),),
+        (
+            bond.bid_id.clone(),
+            bond.investor.clone(),
+            bond.amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
 /// Emit event when a bid is placed
 pub fn emit_bid_placed(env: &Env, bid: &Bid) {
     env.events().publish(
@@ -457,6 +843,34 @@ pub fn emit_dispute_resolved(
     );
 }
 
+/// Emit event when a dispute resolution template is added or changed
+pub fn emit_dispute_template_updated(env: &Env, template: &ResolutionTemplate) {
+    env.events().publish(
+        (symbol_short!("dsp_tpl"),),
+        (
+            template.code,
+            template.investor_share_bps,
+            template.active,
+            template.updated_at,
+        ),
+    );
+}
+
+/// Emit event when a dispute is resolved with a template
+pub fn emit_dispute_outcome_recorded(env: &Env, outcome: &DisputeOutcome) {
+    env.events().publish(
+        (symbol_short!("dsp_out"),),
+        (
+            outcome.invoice_id.clone(),
+            outcome.template_code,
+            outcome.investor_amount,
+            outcome.business_amount,
+            outcome.executed,
+            outcome.resolved_at,
+        ),
+    );
+}
+
 pub fn emit_invoice_funded(env: &Env, invoice_id: &BytesN<32>, investor: &Address, amount: i128) {
     env.events().publish(
         (symbol_short!("inv_fnd"),),
@@ -491,6 +905,21 @@ pub fn emit_platform_metrics_updated(
     );
 }
 
+/// Emit event when an insurance metrics snapshot is stored
+pub fn emit_insurance_metrics_updated(env: &Env, metrics: &crate::analytics::InsuranceMetrics) {
+    env.events().publish(
+        (symbol_short!("ins_met"),),
+        (
+            metrics.period.clone(),
+            metrics.total_premiums,
+            metrics.total_claims_paid,
+            metrics.loss_ratio_bps,
+            metrics.uninsured_exposure,
+            metrics.timestamp,
+        ),
+    );
+}
+
 /// Emit event when performance metrics are updated
 pub fn emit_performance_metrics_updated(
     env: &Env,
@@ -677,6 +1106,26 @@ pub fn emit_platform_fee_routed(
     );
 }
 
+/// Emit event when a broker is paid its share of an invoice's platform fee
+pub fn emit_broker_fee_paid(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    broker: &Address,
+    currency: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("brk_fee"),),
+        (
+            invoice_id.clone(),
+            broker.clone(),
+            currency.clone(),
+            amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
 /// Emit event when treasury configuration is updated
 pub fn emit_treasury_configured(env: &Env, treasury_address: &Address, configured_by: &Address) {
     env.events().publish(
@@ -707,6 +1156,68 @@ pub fn emit_platform_fee_config_updated(
     );
 }
 
+/// Emit event when the late penalty split is updated
+pub fn emit_late_penalty_split_updated(
+    env: &Env,
+    investor_share_bps: u32,
+    platform_share_bps: u32,
+    updated_by: &Address,
+) {
+    env.events().publish(
+        (symbol_short!("late_cfg"),),
+        (
+            investor_share_bps,
+            platform_share_bps,
+            updated_by.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a zero-fee promotion is scheduled
+pub fn emit_fee_promotion_scheduled(env: &Env, promotion: &FeePromotion) {
+    env.events().publish(
+        (symbol_short!("fee_prm"),),
+        (
+            promotion.starts_at,
+            promotion.ends_at,
+            promotion.categories.clone(),
+        ),
+    );
+}
+
+/// Emit event when a scheduled or running zero-fee promotion is cancelled early
+pub fn emit_fee_promotion_cancelled(env: &Env, promotion: &FeePromotion) {
+    env.events().publish(
+        (symbol_short!("fee_pcnl"),),
+        (
+            promotion.starts_at,
+            promotion.ends_at,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a settlement's platform fee is waived by a fee promotion
+pub fn emit_platform_fee_waived(env: &Env, invoice_id: &BytesN<32>, amount: i128) {
+    env.events()
+        .publish((symbol_short!("fee_wavd"),), (invoice_id.clone(), amount));
+}
+
+/// Emit event when a late penalty is collected at settlement
+pub fn emit_late_penalty_applied(env: &Env, record: &LatePenaltyRecord) {
+    env.events().publish(
+        (symbol_short!("late_pen"),),
+        (
+            record.invoice_id.clone(),
+            record.penalty_amount,
+            record.investor_share,
+            record.platform_share,
+            record.applied_at,
+        ),
+    );
+}
+
 /// Emit detailed profit and fee breakdown event for transparency
 ///
 /// This event provides full visibility into settlement calculations:
@@ -739,3 +1250,415 @@ pub fn emit_profit_fee_breakdown(
         ),
     );
 }
+
+/// Emit event when a business requests a new payout address
+pub fn emit_payout_change_requested(env: &Env, change: &PayoutAddressChange) {
+    env.events().publish(
+        (symbol_short!("pay_req"),),
+        (
+            change.business.clone(),
+            change.new_address.clone(),
+            change.effective_at,
+            change.investors_notified,
+        ),
+    );
+}
+
+/// Emit event when a pending payout address change takes effect
+pub fn emit_payout_address_changed(
+    env: &Env,
+    business: &Address,
+    old_address: &Address,
+    new_address: &Address,
+) {
+    env.events().publish(
+        (symbol_short!("pay_chg"),),
+        (
+            business.clone(),
+            old_address.clone(),
+            new_address.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a pending payout address change is cancelled
+pub fn emit_payout_change_cancelled(
+    env: &Env,
+    business: &Address,
+    new_address: &Address,
+    cancelled_by: &Address,
+) {
+    env.events().publish(
+        (symbol_short!("pay_cnl"),),
+        (business.clone(), new_address.clone(), cancelled_by.clone()),
+    );
+}
+
+/// Emit event when a Cancelled/Expired invoice is relisted as a new invoice
+pub fn emit_invoice_relisted(env: &Env, invoice_id: &BytesN<32>, relisted_id: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("inv_rlst"),),
+        (
+            invoice_id.clone(),
+            relisted_id.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an invoice is split into several new invoices
+pub fn emit_invoice_split(env: &Env, invoice_id: &BytesN<32>, parts: &Vec<BytesN<32>>) {
+    env.events().publish(
+        (symbol_short!("inv_splt"),),
+        (invoice_id.clone(), parts.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit event when an investor requests access to a private invoice's deal room
+pub fn emit_deal_room_access_requested(env: &Env, invoice_id: &BytesN<32>, investor: &Address) {
+    env.events().publish(
+        (symbol_short!("dr_req"),),
+        (
+            invoice_id.clone(),
+            investor.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a business grants an investor deal room access
+pub fn emit_deal_room_access_granted(env: &Env, invoice_id: &BytesN<32>, investor: &Address) {
+    env.events().publish(
+        (symbol_short!("dr_grant"),),
+        (
+            invoice_id.clone(),
+            investor.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a participant posts to an invoice's message board
+pub fn emit_invoice_message_posted(env: &Env, message: &InvoiceMessage) {
+    env.events().publish(
+        (symbol_short!("msg_post"),),
+        (
+            message.invoice_id.clone(),
+            message.message_id,
+            message.author.clone(),
+            message.posted_at,
+        ),
+    );
+}
+
+/// Emit event when the admin removes a message board post
+pub fn emit_invoice_message_removed(env: &Env, message: &InvoiceMessage, moderator: &Address) {
+    env.events().publish(
+        (symbol_short!("msg_rm"),),
+        (
+            message.invoice_id.clone(),
+            message.message_id,
+            moderator.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a recurring schedule mints its next invoice
+pub fn emit_recurring_invoice_minted(
+    env: &Env,
+    schedule_id: &BytesN<32>,
+    invoice_id: &BytesN<32>,
+    sequence: u32,
+) {
+    env.events().publish(
+        (symbol_short!("rec_mint"),),
+        (schedule_id.clone(), invoice_id.clone(), sequence),
+    );
+}
+
+/// Emit event when a business anchors a document hash to an invoice
+pub fn emit_invoice_document_attached(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    document: &InvoiceDocument,
+) {
+    env.events().publish(
+        (symbol_short!("doc_add"),),
+        (
+            invoice_id.clone(),
+            document.doc_hash.clone(),
+            document.doc_type.clone(),
+            document.attached_at,
+        ),
+    );
+}
+
+/// Emit event when a business removes a document hash from an invoice
+pub fn emit_invoice_document_removed(env: &Env, invoice_id: &BytesN<32>, doc_hash: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("doc_rm"),),
+        (
+            invoice_id.clone(),
+            doc_hash.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a business registers the debtor of an invoice
+pub fn emit_invoice_debtor_registered(env: &Env, invoice_id: &BytesN<32>, debtor: &Address) {
+    env.events().publish(
+        (symbol_short!("dbt_reg"),),
+        (invoice_id.clone(), debtor.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit event when a debtor acknowledges an invoice
+pub fn emit_invoice_acknowledged(env: &Env, invoice_id: &BytesN<32>, debtor: &Address) {
+    env.events().publish(
+        (symbol_short!("inv_ack"),),
+        (invoice_id.clone(), debtor.clone(), env.ledger().timestamp()),
+    );
+}
+
+/// Emit event when a business defines (or clears, with a count of 0) an invoice's milestones
+pub fn emit_invoice_milestones_set(env: &Env, invoice_id: &BytesN<32>, milestone_count: u32) {
+    env.events().publish(
+        (symbol_short!("ms_set"),),
+        (
+            invoice_id.clone(),
+            milestone_count,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a milestone's share of the escrow is released to the business
+pub fn emit_milestone_released(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    milestone_idx: u32,
+    business: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("ms_rel"),),
+        (invoice_id.clone(), milestone_idx, business.clone(), amount),
+    );
+}
+
+/// Emit event when a business initiates a timelocked settlement
+pub fn emit_settlement_initiated(env: &Env, pending: &PendingSettlement) {
+    env.events().publish(
+        (symbol_short!("stl_init"),),
+        (
+            pending.invoice_id.clone(),
+            pending.business.clone(),
+            pending.payment_amount,
+            pending.executable_at,
+        ),
+    );
+}
+
+/// Emit event when a pending timelocked settlement is cancelled
+pub fn emit_settlement_cancelled(env: &Env, pending: &PendingSettlement) {
+    env.events().publish(
+        (symbol_short!("stl_cncl"),),
+        (
+            pending.invoice_id.clone(),
+            pending.business.clone(),
+            pending.payment_amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an invoice is verified inside its cooling-off window by emergency override
+pub fn emit_verification_override(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    admin: &Address,
+    approver: &Address,
+) {
+    env.events().publish(
+        (symbol_short!("ver_ovr"),),
+        (
+            invoice_id.clone(),
+            admin.clone(),
+            approver.clone(),
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an investor acknowledges the risk disclosure
+pub fn emit_suitability_acknowledged(env: &Env, acknowledgment: &SuitabilityAcknowledgment) {
+    env.events().publish(
+        (symbol_short!("suit_ack"),),
+        (
+            acknowledgment.investor.clone(),
+            acknowledgment.version,
+            acknowledgment.disclosure_hash.clone(),
+            acknowledgment.acknowledged_at,
+        ),
+    );
+}
+
+/// Emit event when a settlement pays more than the amount due
+pub fn emit_overpayment_recorded(env: &Env, refund: &OverpaymentRefund) {
+    env.events().publish(
+        (symbol_short!("ovr_pay"),),
+        (
+            refund.invoice_id.clone(),
+            refund.payer.clone(),
+            refund.currency.clone(),
+            refund.amount,
+            refund.recorded_at,
+        ),
+    );
+}
+
+/// Emit the business's settlement confirmation with the net amounts
+pub fn emit_settlement_receipt(env: &Env, receipt: &SettlementReceipt) {
+    env.events().publish(
+        (symbol_short!("stl_rcpt"),),
+        (
+            receipt.invoice_id.clone(),
+            receipt.business.clone(),
+            receipt.amount_settled,
+            receipt.investor_return,
+            receipt.platform_fee,
+            receipt.late_penalty,
+            receipt.financing_cost,
+        ),
+    );
+}
+
+/// Emit event when an investor's settlement payout is held in the contract for them to claim
+pub fn emit_investor_payout_held(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    investor: &Address,
+    currency: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (symbol_short!("pay_held"),),
+        (
+            invoice_id.clone(),
+            investor.clone(),
+            currency.clone(),
+            amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an investor claims their held payouts
+pub fn emit_investor_claim_paid(env: &Env, investor: &Address, currency: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("inv_clm"),),
+        (
+            investor.clone(),
+            currency.clone(),
+            amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an investor prefunds their deposit account
+pub fn emit_investor_deposit(
+    env: &Env,
+    investor: &Address,
+    currency: &Address,
+    amount: i128,
+    balance: i128,
+) {
+    env.events().publish(
+        (symbol_short!("dep_in"),),
+        (
+            investor.clone(),
+            currency.clone(),
+            amount,
+            balance,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an investor withdraws from their deposit account
+pub fn emit_investor_withdrawal(
+    env: &Env,
+    investor: &Address,
+    currency: &Address,
+    amount: i128,
+    balance: i128,
+) {
+    env.events().publish(
+        (symbol_short!("dep_out"),),
+        (
+            investor.clone(),
+            currency.clone(),
+            amount,
+            balance,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a large deposit withdrawal is queued
+pub fn emit_withdrawal_requested(env: &Env, pending: &PendingWithdrawal) {
+    env.events().publish(
+        (symbol_short!("wd_req"),),
+        (
+            pending.id,
+            pending.investor.clone(),
+            pending.currency.clone(),
+            pending.amount,
+            pending.executable_at,
+        ),
+    );
+}
+
+/// Emit event when a queued deposit withdrawal is cancelled
+pub fn emit_withdrawal_cancelled(env: &Env, pending: &PendingWithdrawal) {
+    env.events().publish(
+        (symbol_short!("wd_cncl"),),
+        (
+            pending.id,
+            pending.investor.clone(),
+            pending.currency.clone(),
+            pending.amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when an overpayment is refunded to its payer
+pub fn emit_overpayment_refunded(env: &Env, refund: &OverpaymentRefund) {
+    env.events().publish(
+        (symbol_short!("ovr_ref"),),
+        (
+            refund.invoice_id.clone(),
+            refund.payer.clone(),
+            refund.amount,
+            env.ledger().timestamp(),
+        ),
+    );
+}
+
+/// Emit event when a notification fails inside a flow and is kept as a dead letter
+pub fn emit_notification_dead_lettered(env: &Env, dead_letter: &DeadLetter) {
+    env.events().publish(
+        (symbol_short!("ntf_dlq"),),
+        (
+            dead_letter.id,
+            dead_letter.invoice_id.clone(),
+            dead_letter.trigger.clone(),
+            dead_letter.error_code,
+        ),
+    );
+}
@@ -0,0 +1,409 @@
+//! Category exposure caps: admin-configured limits on the total outstanding funded volume per
+//! invoice category, enforced whenever an invoice is funded.
+//!
+//! Outstanding volume is the funded amount of invoices currently in `Funded` status, so it
+//! frees up automatically when invoices are settled, defaulted or refunded.
+//!
+//! Funding source limits cap how much each investor can have outstanding from bids declared as
+//! funded from one source type (personal capital, a pool or a credit facility). The source of
+//! an accepted bid is kept with its investment, and an investor's outstanding amount for a source
+//! is the amount of their active investments funded from it.
+//!
+//! Business financing caps limit a business's own outstanding funded volume. The admin can
+//! schedule overrides with start and end timestamps (a higher cap for Q4, say); the override in
+//! force replaces the base cap while it lasts, and the base cap applies again once it ends,
+//! without any further call.
+
+use crate::bid::{Bid, BidFundingSource, BidStorage};
+use crate::errors::QuickLendXError;
+use crate::investment::{InvestmentStatus, InvestmentStorage};
+use crate::invoice::{InvoiceCategory, InvoiceStatus, InvoiceStorage};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, Symbol, Vec};
+
+const CATEGORY_CAPS_KEY: Symbol = symbol_short!("cat_cap");
+const SOURCE_LIMITS_KEY: Symbol = symbol_short!("src_cap");
+const INVESTMENT_SOURCE_KEY: Symbol = symbol_short!("inv_fsrc");
+const BUSINESS_CAP_KEY: Symbol = symbol_short!("bus_cap");
+const BUSINESS_CAP_OVERRIDES_KEY: Symbol = symbol_short!("bus_ovr");
+/// Most scheduled cap overrides a business can have pending or in force
+pub const MAX_BUSINESS_CAP_OVERRIDES: u32 = 10;
+
+/// Cap and current utilization for an invoice category
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryExposure {
+    pub category: InvoiceCategory,
+    /// `None` when the category is uncapped
+    pub cap: Option<i128>,
+    pub outstanding: i128,
+    /// Volume that can still be funded; `None` when uncapped
+    pub remaining: Option<i128>,
+}
+
+/// An investor's outstanding amount from one funding source, against its per-investor limit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundingSourceExposure {
+    pub investor: Address,
+    pub source: BidFundingSource,
+    /// `None` when the source is unlimited
+    pub limit: Option<i128>,
+    pub outstanding: i128,
+    /// Amount that can still be funded from the source; `None` when unlimited
+    pub remaining: Option<i128>,
+}
+
+/// A business financing cap in force from `starts_at` until just before `ends_at`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BusinessCapOverride {
+    pub cap: i128,
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+/// A business's financing cap in force now and its outstanding funded volume
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BusinessExposure {
+    pub business: Address,
+    pub base_cap: Option<i128>,
+    /// Override cap if one is in force, otherwise the base cap
+    pub cap: Option<i128>,
+    /// End of the override in force, if any
+    pub override_ends_at: Option<u64>,
+    pub outstanding: i128,
+    pub remaining: Option<i128>,
+}
+
+pub struct ExposureLimits;
+
+impl ExposureLimits {
+    /// Get the configured caps by category
+    pub fn get_caps(env: &Env) -> Map<InvoiceCategory, i128> {
+        env.storage()
+            .instance()
+            .get(&CATEGORY_CAPS_KEY)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Set or clear the cap for a category
+    pub fn set_cap(
+        env: &Env,
+        category: InvoiceCategory,
+        cap: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let mut caps = Self::get_caps(env);
+        match cap {
+            Some(cap) if cap <= 0 => return Err(QuickLendXError::InvalidAmount),
+            Some(cap) => caps.set(category, cap),
+            None => {
+                caps.remove(category);
+            }
+        }
+        env.storage().instance().set(&CATEGORY_CAPS_KEY, &caps);
+        Ok(())
+    }
+
+    /// Total funded amount of the category's invoices currently in `Funded` status
+    pub fn outstanding(env: &Env, category: &InvoiceCategory) -> i128 {
+        let mut total: i128 = 0;
+        for invoice_id in InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded).iter()
+        {
+            if let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) {
+                if invoice.category == *category {
+                    total = total.saturating_add(invoice.funded_amount);
+                }
+            }
+        }
+        total
+    }
+
+    pub fn get_exposure(env: &Env, category: InvoiceCategory) -> CategoryExposure {
+        let cap = Self::get_caps(env).get(category.clone());
+        let outstanding = Self::outstanding(env, &category);
+        CategoryExposure {
+            category,
+            cap,
+            outstanding,
+            remaining: cap.map(|cap| cap.saturating_sub(outstanding).max(0)),
+        }
+    }
+
+    /// Exposure for every capped category
+    pub fn get_capped_exposures(env: &Env) -> Vec<CategoryExposure> {
+        let mut result = Vec::new(env);
+        for (category, _) in Self::get_caps(env).iter() {
+            result.push_back(Self::get_exposure(env, category));
+        }
+        result
+    }
+
+    /// Check that funding `amount` more in the category stays within its cap
+    pub fn check_funding(
+        env: &Env,
+        category: &InvoiceCategory,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        if let Some(cap) = Self::get_caps(env).get(category.clone()) {
+            if Self::outstanding(env, category).saturating_add(amount) > cap {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the configured per-investor limits by funding source
+    pub fn get_source_limits(env: &Env) -> Map<BidFundingSource, i128> {
+        env.storage()
+            .instance()
+            .get(&SOURCE_LIMITS_KEY)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Set or clear the per-investor limit for a funding source
+    pub fn set_source_limit(
+        env: &Env,
+        source: BidFundingSource,
+        limit: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let mut limits = Self::get_source_limits(env);
+        match limit {
+            Some(limit) if limit <= 0 => return Err(QuickLendXError::InvalidAmount),
+            Some(limit) => limits.set(source, limit),
+            None => {
+                limits.remove(source);
+            }
+        }
+        env.storage().instance().set(&SOURCE_LIMITS_KEY, &limits);
+        Ok(())
+    }
+
+    /// Funding source of an investment, `Personal` when its bid declared none
+    pub fn get_investment_source(env: &Env, investment_id: &BytesN<32>) -> BidFundingSource {
+        env.storage()
+            .instance()
+            .get(&(INVESTMENT_SOURCE_KEY, investment_id.clone()))
+            .unwrap_or(BidFundingSource::Personal)
+    }
+
+    /// Keep the declared source of the accepted bid with the investment it funded
+    pub fn record_investment_source(env: &Env, investment_id: &BytesN<32>, bid_id: &BytesN<32>) {
+        let source = BidStorage::get_funding_source(env, bid_id);
+        if source != BidFundingSource::Personal {
+            env.storage()
+                .instance()
+                .set(&(INVESTMENT_SOURCE_KEY, investment_id.clone()), &source);
+        }
+    }
+
+    /// Amount of the investor's active investments funded from `source`
+    pub fn source_outstanding(env: &Env, investor: &Address, source: &BidFundingSource) -> i128 {
+        let mut total: i128 = 0;
+        for investment_id in InvestmentStorage::get_investments_by_investor(env, investor).iter() {
+            if let Some(investment) = InvestmentStorage::get_investment(env, &investment_id) {
+                if investment.status == InvestmentStatus::Active
+                    && Self::get_investment_source(env, &investment_id) == *source
+                {
+                    total = total.saturating_add(investment.amount);
+                }
+            }
+        }
+        total
+    }
+
+    pub fn get_source_exposure(
+        env: &Env,
+        investor: Address,
+        source: BidFundingSource,
+    ) -> FundingSourceExposure {
+        let limit = Self::get_source_limits(env).get(source.clone());
+        let outstanding = Self::source_outstanding(env, &investor, &source);
+        FundingSourceExposure {
+            investor,
+            source,
+            limit,
+            outstanding,
+            remaining: limit.map(|limit| limit.saturating_sub(outstanding).max(0)),
+        }
+    }
+
+    /// Check that funding the bid stays within the limit for its declared source
+    pub fn check_source_funding(env: &Env, bid: &Bid) -> Result<(), QuickLendXError> {
+        let source = BidStorage::get_funding_source(env, &bid.bid_id);
+        if let Some(limit) = Self::get_source_limits(env).get(source.clone()) {
+            if Self::source_outstanding(env, &bid.investor, &source).saturating_add(bid.bid_amount)
+                > limit
+            {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_business_cap(env: &Env, business: &Address) -> Option<i128> {
+        env.storage()
+            .instance()
+            .get(&(BUSINESS_CAP_KEY, business.clone()))
+    }
+
+    /// Set or clear a business's base financing cap
+    pub fn set_business_cap(
+        env: &Env,
+        business: &Address,
+        cap: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let key = (BUSINESS_CAP_KEY, business.clone());
+        match cap {
+            Some(cap) if cap <= 0 => return Err(QuickLendXError::InvalidAmount),
+            Some(cap) => env.storage().instance().set(&key, &cap),
+            None => env.storage().instance().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// Overrides that are pending or in force, in start order
+    pub fn get_business_cap_overrides(env: &Env, business: &Address) -> Vec<BusinessCapOverride> {
+        let now = env.ledger().timestamp();
+        let mut current = Vec::new(env);
+        let overrides: Vec<BusinessCapOverride> = env
+            .storage()
+            .instance()
+            .get(&(BUSINESS_CAP_OVERRIDES_KEY, business.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+        for entry in overrides.iter() {
+            if entry.ends_at > now {
+                current.push_back(entry);
+            }
+        }
+        current
+    }
+
+    fn set_business_cap_overrides(
+        env: &Env,
+        business: &Address,
+        overrides: &Vec<BusinessCapOverride>,
+    ) {
+        let key = (BUSINESS_CAP_OVERRIDES_KEY, business.clone());
+        if overrides.is_empty() {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, overrides);
+        }
+    }
+
+    /// Schedule a cap override for a business; ended overrides are dropped as a side effect
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if the cap is not positive
+    /// * `InvalidTimestamp` if the override does not end after it starts, or has already ended
+    /// * `OperationNotAllowed` if it overlaps another override of the business, or the business
+    ///   already has `MAX_BUSINESS_CAP_OVERRIDES`
+    pub fn add_business_cap_override(
+        env: &Env,
+        business: &Address,
+        cap_override: BusinessCapOverride,
+    ) -> Result<(), QuickLendXError> {
+        if cap_override.cap <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if cap_override.ends_at <= cap_override.starts_at
+            || cap_override.ends_at <= env.ledger().timestamp()
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        let overrides = Self::get_business_cap_overrides(env, business);
+        if overrides.len() >= MAX_BUSINESS_CAP_OVERRIDES {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let mut updated = Vec::new(env);
+        let mut inserted = false;
+        for entry in overrides.iter() {
+            if entry.starts_at < cap_override.ends_at && cap_override.starts_at < entry.ends_at {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            if !inserted && cap_override.starts_at < entry.starts_at {
+                updated.push_back(cap_override.clone());
+                inserted = true;
+            }
+            updated.push_back(entry);
+        }
+        if !inserted {
+            updated.push_back(cap_override);
+        }
+        Self::set_business_cap_overrides(env, business, &updated);
+        Ok(())
+    }
+
+    /// Remove the override of a business starting at `starts_at`
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the business has no pending or current override starting then
+    pub fn remove_business_cap_override(
+        env: &Env,
+        business: &Address,
+        starts_at: u64,
+    ) -> Result<(), QuickLendXError> {
+        let mut overrides = Self::get_business_cap_overrides(env, business);
+        let idx = overrides
+            .iter()
+            .position(|entry| entry.starts_at == starts_at)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        overrides.remove(idx as u32);
+        Self::set_business_cap_overrides(env, business, &overrides);
+        Ok(())
+    }
+
+    /// Override in force for a business now, if any
+    fn current_business_cap_override(env: &Env, business: &Address) -> Option<BusinessCapOverride> {
+        let now = env.ledger().timestamp();
+        Self::get_business_cap_overrides(env, business)
+            .iter()
+            .find(|entry| entry.starts_at <= now)
+    }
+
+    /// Total funded amount of the business's invoices currently in `Funded` status
+    pub fn business_outstanding(env: &Env, business: &Address) -> i128 {
+        let mut total: i128 = 0;
+        for invoice_id in InvoiceStorage::get_business_invoices(env, business).iter() {
+            if let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) {
+                if invoice.status == InvoiceStatus::Funded {
+                    total = total.saturating_add(invoice.funded_amount);
+                }
+            }
+        }
+        total
+    }
+
+    pub fn get_business_exposure(env: &Env, business: &Address) -> BusinessExposure {
+        let base_cap = Self::get_business_cap(env, business);
+        let current = Self::current_business_cap_override(env, business);
+        let cap = current.as_ref().map(|entry| entry.cap).or(base_cap);
+        let outstanding = Self::business_outstanding(env, business);
+        BusinessExposure {
+            business: business.clone(),
+            base_cap,
+            cap,
+            override_ends_at: current.map(|entry| entry.ends_at),
+            outstanding,
+            remaining: cap.map(|cap| cap.saturating_sub(outstanding).max(0)),
+        }
+    }
+
+    /// Check that funding `amount` more for the business stays within the cap in force now
+    pub fn check_business_funding(
+        env: &Env,
+        business: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let cap = Self::current_business_cap_override(env, business)
+            .map(|entry| entry.cap)
+            .or_else(|| Self::get_business_cap(env, business));
+        if let Some(cap) = cap {
+            if Self::business_outstanding(env, business).saturating_add(amount) > cap {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
 use crate::errors::QuickLendXError;
-use soroban_sdk::{contracttype, symbol_short, vec, Address, Env, Map, Symbol, Vec};
+use crate::events::{emit_fee_promotion_cancelled, emit_fee_promotion_scheduled};
+use crate::invoice::{Invoice, InvoiceCategory};
+use crate::profits::{PlatformFee, PlatformFeeConfig as LegacyPlatformFeeConfig};
+use crate::rounding::{self, Rounding};
+use crate::verification::{BusinessVerificationStorage, InvestorTier, InvestorVerificationStorage};
+use soroban_sdk::{contracttype, symbol_short, vec, Address, BytesN, Env, Map, Symbol, Vec};
 
 // Constants
 const MAX_FEE_BPS: u32 = 1000;
@@ -7,6 +12,7 @@ const MIN_FEE_BPS: u32 = 0;
 const BPS_DENOMINATOR: i128 = 10_000;
 const DEFAULT_PLATFORM_FEE_BPS: u32 = 200; // 2%
 const MAX_PLATFORM_FEE_BPS: u32 = 1000; // 10%
+const DEFAULT_LATE_PENALTY_INVESTOR_SHARE_BPS: u32 = 5_000; // 50% to investor
 
 // Storage keys
 const FEE_CONFIG_KEY: Symbol = symbol_short!("fee_cfg");
@@ -14,6 +20,17 @@ const REVENUE_KEY: Symbol = symbol_short!("revenue");
 const VOLUME_KEY: Symbol = symbol_short!("volume");
 const TREASURY_CONFIG_KEY: Symbol = symbol_short!("treasury");
 const PLATFORM_FEE_KEY: Symbol = symbol_short!("plt_fee");
+/// Key previously written by `profits::PlatformFee::set_config`; read only for migration
+const LEGACY_PLATFORM_FEE_KEY: Symbol = symbol_short!("pf_cfg");
+const LATE_PENALTY_SPLIT_KEY: Symbol = symbol_short!("late_spl");
+const LATE_PENALTY_KEY: Symbol = symbol_short!("late_pen");
+const FEE_QUOTE_KEY: Symbol = symbol_short!("fee_qt");
+const TIER_FEE_KEY: Symbol = symbol_short!("tier_fee");
+const LATE_ACCRUAL_KEY: Symbol = symbol_short!("late_acc");
+const FEE_PROMOTION_KEY: Symbol = symbol_short!("fee_promo");
+/// Longest zero-fee promotion the admin may schedule (180 days)
+pub const MAX_FEE_PROMOTION_DURATION: u64 = 180 * SECONDS_PER_DAY;
+const SECONDS_PER_DAY: u64 = 86_400;
 
 /// Fee types supported by the platform
 #[contracttype]
@@ -36,6 +53,15 @@ pub enum VolumeTier {
     Platinum,
 }
 
+/// Participant tier used for differential platform fee rates
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParticipantTier {
+    UnverifiedBusiness,
+    VerifiedBusiness,
+    Investor(InvestorTier),
+}
+
 /// Fee structure configuration
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -80,6 +106,86 @@ pub struct PlatformFeeConfig {
     pub updated_by: Address,
 }
 
+/// Split of late payment penalties between the investor and the platform
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatePenaltySplitConfig {
+    pub investor_share_bps: u32,
+    pub platform_share_bps: u32,
+    pub updated_at: u64,
+    pub updated_by: Address,
+}
+
+/// Daily late fee accrual on top of the flat `LatePayment` penalty
+///
+/// Each full day past the due date adds `daily_bps` of the invoice face value, up to
+/// `max_accrual_bps` in total.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LateFeeAccrual {
+    pub daily_bps: u32,
+    pub max_accrual_bps: u32,
+}
+
+/// Fee terms locked onto an invoice when it is funded
+///
+/// Settlement uses the quote instead of the live configuration, so fee changes after funding
+/// do not affect deals already in flight.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeQuote {
+    pub platform_fee_bps: u32,
+    /// False when no active `LatePayment` fee structure existed at funding
+    pub late_penalty_active: bool,
+    pub late_penalty_bps: u32,
+    pub late_penalty_min: i128,
+    pub late_penalty_max: i128,
+    /// Daily accrual on top of the flat penalty, see `LateFeeAccrual`
+    pub late_daily_bps: u32,
+    pub late_accrual_cap_bps: u32,
+    pub penalty_investor_share_bps: u32,
+    pub quoted_at: u64,
+}
+
+/// Settlement fee waterfall: how a settlement payment is distributed
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettlementWaterfall {
+    pub total_payment: i128,
+    pub late_penalty: i128,
+    pub investor_penalty_share: i128,
+    pub platform_penalty_share: i128,
+    /// Total transferred to the investor, including its penalty share
+    pub investor_return: i128,
+    /// Total routed to the platform, including its penalty share
+    pub platform_fee: i128,
+    /// Platform fee on profit not charged because a fee promotion was running
+    pub platform_fee_waived: i128,
+}
+
+/// Launch window in which settlements pay no platform fee on profit
+///
+/// Applies from `starts_at` until just before `ends_at` to invoices in `categories`, or to all
+/// invoices when `categories` is empty. Late penalties are still collected.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeePromotion {
+    pub starts_at: u64,
+    pub ends_at: u64,
+    pub categories: Vec<InvoiceCategory>,
+}
+
+/// Late penalty collected when settling an invoice
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatePenaltyRecord {
+    pub invoice_id: BytesN<32>,
+    pub penalty_amount: i128,
+    pub investor_share: i128,
+    pub platform_share: i128,
+    pub applied_at: u64,
+}
+
 /// Revenue configuration
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -154,9 +260,12 @@ impl FeeManager {
         ];
         env.storage().instance().set(&FEE_CONFIG_KEY, &default_fees);
 
-        // Initialize platform fee configuration
+        // Initialize platform fee configuration, carrying over a legacy rate if one was set
+        let fee_bps = Self::take_legacy_platform_fee(env)
+            .map(|legacy| legacy.fee_bps)
+            .unwrap_or(DEFAULT_PLATFORM_FEE_BPS);
         let platform_fee_config = PlatformFeeConfig {
-            fee_bps: DEFAULT_PLATFORM_FEE_BPS,
+            fee_bps,
             treasury_address: None,
             updated_at: env.ledger().timestamp(),
             updated_by: admin.clone(),
@@ -222,29 +331,420 @@ impl FeeManager {
 
     /// Get platform fee configuration
     pub fn get_platform_fee_config(env: &Env) -> Result<PlatformFeeConfig, QuickLendXError> {
+        Ok(Self::load_platform_fee_config(env))
+    }
+
+    /// Load the platform fee configuration, the single source of truth for settlement fees.
+    ///
+    /// Falls back to a rate written by the legacy `profits::PlatformFee` setter that has not
+    /// been migrated yet, and then to the 2% default.
+    pub fn load_platform_fee_config(env: &Env) -> PlatformFeeConfig {
         env.storage()
             .instance()
             .get(&PLATFORM_FEE_KEY)
-            .ok_or(QuickLendXError::StorageKeyNotFound)
+            .or_else(|| Self::get_legacy_platform_fee(env))
+            .unwrap_or_else(|| PlatformFeeConfig {
+                fee_bps: DEFAULT_PLATFORM_FEE_BPS,
+                treasury_address: None,
+                updated_at: 0,
+                updated_by: env.current_contract_address(),
+            })
+    }
+
+    /// Move a fee rate stored by the legacy `profits::PlatformFee` setter into the unified
+    /// configuration and delete the legacy entry.
+    ///
+    /// If a unified configuration already exists it wins, since it is the one settlement used.
+    /// Returns `true` if a legacy entry was found.
+    pub fn migrate_legacy_platform_fee(
+        env: &Env,
+        admin: &Address,
+    ) -> Result<bool, QuickLendXError> {
+        admin.require_auth();
+
+        let legacy = match Self::take_legacy_platform_fee(env) {
+            Some(legacy) => legacy,
+            None => return Ok(false),
+        };
+
+        if !env.storage().instance().has(&PLATFORM_FEE_KEY) {
+            env.storage().instance().set(&PLATFORM_FEE_KEY, &legacy);
+        }
+
+        Ok(true)
+    }
+
+    fn get_legacy_platform_fee(env: &Env) -> Option<PlatformFeeConfig> {
+        let legacy: LegacyPlatformFeeConfig =
+            env.storage().instance().get(&LEGACY_PLATFORM_FEE_KEY)?;
+        Some(PlatformFeeConfig {
+            fee_bps: legacy.fee_bps.clamp(0, MAX_PLATFORM_FEE_BPS as i128) as u32,
+            treasury_address: None,
+            updated_at: legacy.updated_at,
+            updated_by: legacy.updated_by,
+        })
+    }
+
+    fn take_legacy_platform_fee(env: &Env) -> Option<PlatformFeeConfig> {
+        let legacy = Self::get_legacy_platform_fee(env)?;
+        env.storage().instance().remove(&LEGACY_PLATFORM_FEE_KEY);
+        Some(legacy)
+    }
+
+    /// Get the late penalty split, defaulting to an even split between investor and platform
+    pub fn get_late_penalty_split(env: &Env) -> LatePenaltySplitConfig {
+        env.storage()
+            .instance()
+            .get(&LATE_PENALTY_SPLIT_KEY)
+            .unwrap_or(LatePenaltySplitConfig {
+                investor_share_bps: DEFAULT_LATE_PENALTY_INVESTOR_SHARE_BPS,
+                platform_share_bps: BPS_DENOMINATOR as u32
+                    - DEFAULT_LATE_PENALTY_INVESTOR_SHARE_BPS,
+                updated_at: 0,
+                updated_by: env.current_contract_address(),
+            })
+    }
+
+    /// Get the daily late fee accrual (none by default)
+    pub fn get_late_fee_accrual(env: &Env) -> LateFeeAccrual {
+        env.storage()
+            .instance()
+            .get(&LATE_ACCRUAL_KEY)
+            .unwrap_or(LateFeeAccrual {
+                daily_bps: 0,
+                max_accrual_bps: 0,
+            })
+    }
+
+    /// Set the daily late fee accrual applied to invoices funded from now on
+    ///
+    /// # Errors
+    /// * `InvalidFeeBasisPoints` if `max_accrual_bps` exceeds 10,000 or `daily_bps` exceeds
+    ///   `max_accrual_bps`
+    pub fn set_late_fee_accrual(
+        env: &Env,
+        daily_bps: u32,
+        max_accrual_bps: u32,
+    ) -> Result<LateFeeAccrual, QuickLendXError> {
+        if max_accrual_bps as i128 > BPS_DENOMINATOR || daily_bps > max_accrual_bps {
+            return Err(QuickLendXError::InvalidFeeBasisPoints);
+        }
+        let accrual = LateFeeAccrual {
+            daily_bps,
+            max_accrual_bps,
+        };
+        env.storage().instance().set(&LATE_ACCRUAL_KEY, &accrual);
+        Ok(accrual)
+    }
+
+    /// Set the investor's share of late penalties; the platform receives the remainder
+    pub fn set_late_penalty_split(
+        env: &Env,
+        admin: &Address,
+        investor_share_bps: u32,
+    ) -> Result<LatePenaltySplitConfig, QuickLendXError> {
+        admin.require_auth();
+
+        if investor_share_bps as i128 > BPS_DENOMINATOR {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let config = LatePenaltySplitConfig {
+            investor_share_bps,
+            platform_share_bps: BPS_DENOMINATOR as u32 - investor_share_bps,
+            updated_at: env.ledger().timestamp(),
+            updated_by: admin.clone(),
+        };
+        env.storage()
+            .instance()
+            .set(&LATE_PENALTY_SPLIT_KEY, &config);
+        Ok(config)
+    }
+
+    /// Get the platform fee rates configured per participant tier
+    pub fn get_tier_fee_rates(env: &Env) -> Map<ParticipantTier, u32> {
+        env.storage()
+            .instance()
+            .get(&TIER_FEE_KEY)
+            .unwrap_or(Map::new(env))
     }
 
-    /// Calculate platform fee for settlement
-    pub fn calculate_platform_fee(
+    /// Set the platform fee rate for a participant tier
+    pub fn set_tier_fee_rate(
         env: &Env,
+        admin: &Address,
+        tier: ParticipantTier,
+        fee_bps: u32,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        if fee_bps > MAX_PLATFORM_FEE_BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let mut rates = Self::get_tier_fee_rates(env);
+        rates.set(tier, fee_bps);
+        env.storage().instance().set(&TIER_FEE_KEY, &rates);
+        Ok(())
+    }
+
+    /// Remove the platform fee rate for a participant tier, reverting it to the base rate
+    pub fn remove_tier_fee_rate(
+        env: &Env,
+        admin: &Address,
+        tier: ParticipantTier,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        let mut rates = Self::get_tier_fee_rates(env);
+        if rates.remove(tier).is_none() {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+        env.storage().instance().set(&TIER_FEE_KEY, &rates);
+        Ok(())
+    }
+
+    /// Platform fee rate for a business/investor pair
+    ///
+    /// Looks up rates for the business's verification tier and the investor's tier. The
+    /// lowest matching rate applies, so established counterparties on either side get
+    /// their discount; the base platform rate applies when no tier rate matches.
+    pub fn effective_platform_fee_bps(env: &Env, business: &Address, investor: &Address) -> u32 {
+        let rates = Self::get_tier_fee_rates(env);
+        let base = Self::load_platform_fee_config(env).fee_bps;
+        if rates.is_empty() {
+            return base;
+        }
+
+        let business_tier = if BusinessVerificationStorage::is_business_verified(env, business) {
+            ParticipantTier::VerifiedBusiness
+        } else {
+            ParticipantTier::UnverifiedBusiness
+        };
+        let investor_rate = InvestorVerificationStorage::get(env, investor)
+            .and_then(|verification| rates.get(ParticipantTier::Investor(verification.tier)));
+
+        match (rates.get(business_tier), investor_rate) {
+            (Some(business_rate), Some(investor_rate)) => business_rate.min(investor_rate),
+            (Some(rate), None) | (None, Some(rate)) => rate,
+            (None, None) => base,
+        }
+    }
+
+    /// Get the fee promotion that is scheduled or running; `None` once it has ended
+    pub fn get_fee_promotion(env: &Env) -> Option<FeePromotion> {
+        let promotion: FeePromotion = env.storage().instance().get(&FEE_PROMOTION_KEY)?;
+        (promotion.ends_at > env.ledger().timestamp()).then_some(promotion)
+    }
+
+    /// Schedule a zero-fee promotion, replacing any other
+    ///
+    /// # Errors
+    /// * `InvalidTimestamp` if the window does not end after it starts and after now, or is
+    ///   longer than `MAX_FEE_PROMOTION_DURATION`
+    pub fn set_fee_promotion(
+        env: &Env,
+        admin: &Address,
+        promotion: &FeePromotion,
+    ) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        if promotion.ends_at <= promotion.starts_at
+            || promotion.ends_at <= env.ledger().timestamp()
+            || promotion.ends_at - promotion.starts_at > MAX_FEE_PROMOTION_DURATION
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        env.storage().instance().set(&FEE_PROMOTION_KEY, promotion);
+        emit_fee_promotion_scheduled(env, promotion);
+        Ok(())
+    }
+
+    /// Cancel the scheduled or running fee promotion
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no promotion is scheduled or running
+    pub fn cancel_fee_promotion(env: &Env, admin: &Address) -> Result<(), QuickLendXError> {
+        admin.require_auth();
+        let promotion = Self::get_fee_promotion(env).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        env.storage().instance().remove(&FEE_PROMOTION_KEY);
+        emit_fee_promotion_cancelled(env, &promotion);
+        Ok(())
+    }
+
+    /// Whether a fee promotion covers settlements of `category` invoices now
+    pub fn promotion_applies(env: &Env, category: &InvoiceCategory) -> bool {
+        let now = env.ledger().timestamp();
+        Self::get_fee_promotion(env).is_some_and(|promotion| {
+            promotion.starts_at <= now
+                && (promotion.categories.is_empty() || promotion.categories.contains(category))
+        })
+    }
+
+    /// Build a fee quote from the live fee configuration for a business/investor pair
+    pub fn current_fee_quote(env: &Env, business: &Address, investor: &Address) -> FeeQuote {
+        let late_penalty = Self::get_fee_structure(env, &FeeType::LatePayment)
+            .ok()
+            .filter(|structure| structure.is_active);
+        let accrual = Self::get_late_fee_accrual(env);
+        FeeQuote {
+            platform_fee_bps: Self::effective_platform_fee_bps(env, business, investor),
+            late_penalty_active: late_penalty.is_some(),
+            late_penalty_bps: late_penalty.as_ref().map_or(0, |fee| fee.base_fee_bps),
+            late_penalty_min: late_penalty.as_ref().map_or(0, |fee| fee.min_fee),
+            late_penalty_max: late_penalty.as_ref().map_or(0, |fee| fee.max_fee),
+            late_daily_bps: accrual.daily_bps,
+            late_accrual_cap_bps: accrual.max_accrual_bps,
+            penalty_investor_share_bps: Self::get_late_penalty_split(env).investor_share_bps,
+            quoted_at: env.ledger().timestamp(),
+        }
+    }
+
+    /// Snapshot the live fee configuration onto an invoice at funding time
+    pub fn lock_fee_quote(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        business: &Address,
+        investor: &Address,
+    ) -> FeeQuote {
+        let quote = Self::current_fee_quote(env, business, investor);
+        env.storage()
+            .instance()
+            .set(&(FEE_QUOTE_KEY, invoice_id.clone()), &quote);
+        quote
+    }
+
+    /// Get the fee quote locked when an invoice was funded, if any
+    pub fn get_fee_quote(env: &Env, invoice_id: &BytesN<32>) -> Option<FeeQuote> {
+        env.storage()
+            .instance()
+            .get(&(FEE_QUOTE_KEY, invoice_id.clone()))
+    }
+
+    /// Full days elapsed since an invoice's due date at `as_of` (0 until it is overdue)
+    pub fn days_overdue(invoice: &Invoice, as_of: u64) -> u64 {
+        as_of.saturating_sub(invoice.due_date) / SECONDS_PER_DAY
+    }
+
+    /// Calculate the late penalty owed on an invoice settled after its due date
+    ///
+    /// The penalty is the quoted flat `LatePayment` fee on the invoice face value plus the
+    /// quoted daily accrual for each full day overdue, capped at the accrual cap. Only the
+    /// part of the payment above face value is collected as penalty, so a late payer who pays
+    /// exactly the invoice amount still settles. Returns 0 when the quote has neither.
+    pub fn calculate_late_penalty(
+        quote: &FeeQuote,
+        invoice_amount: i128,
+        total_payment: i128,
+        days_overdue: u64,
+    ) -> i128 {
+        let flat = if quote.late_penalty_active {
+            rounding::bps_of(
+                invoice_amount,
+                quote.late_penalty_bps as i128,
+                Rounding::Floor,
+            )
+            .clamp(quote.late_penalty_min, quote.late_penalty_max)
+        } else {
+            0
+        };
+        let accrual_bps = (quote.late_daily_bps as u64)
+            .saturating_mul(days_overdue)
+            .min(quote.late_accrual_cap_bps as u64);
+        let accrued = rounding::bps_of(invoice_amount, accrual_bps as i128, Rounding::Floor);
+        let penalty = flat.saturating_add(accrued);
+        let collectible = total_payment.saturating_sub(invoice_amount).max(0);
+        penalty.min(collectible).max(0)
+    }
+
+    /// Split a late penalty into `(investor_share, platform_share)`
+    ///
+    /// The investor share rounds down; the platform share is the remainder, so no dust is lost.
+    pub fn split_late_penalty(quote: &FeeQuote, penalty: i128) -> (i128, i128) {
+        if penalty <= 0 {
+            return (0, 0);
+        }
+        rounding::split_bps(penalty, quote.penalty_investor_share_bps as i128)
+    }
+
+    /// Build the settlement fee waterfall
+    ///
+    /// Uses the fee quote locked at funding, falling back to the live configuration for
+    /// invoices funded before quotes were recorded.
+    ///
+    /// 1. Late penalty (if paid after the due date) is carved out of the payment and split
+    ///    per the quote
+    /// 2. The remaining payment is split by the quoted platform fee on profit
+    pub fn calculate_settlement_waterfall(
+        env: &Env,
+        invoice: &Invoice,
+        investor: &Address,
         investment_amount: i128,
-        payment_amount: i128,
-    ) -> Result<(i128, i128), QuickLendXError> {
-        let config = Self::get_platform_fee_config(env)?;
+        total_payment: i128,
+    ) -> Result<SettlementWaterfall, QuickLendXError> {
+        let quote = Self::get_fee_quote(env, &invoice.id)
+            .unwrap_or_else(|| Self::current_fee_quote(env, &invoice.business, investor));
+        let invoice_amount = invoice.amount;
+        let now = env.ledger().timestamp();
+        let late_penalty = if invoice.is_overdue(now) {
+            Self::calculate_late_penalty(
+                &quote,
+                invoice_amount,
+                total_payment,
+                Self::days_overdue(invoice, now),
+            )
+        } else {
+            0
+        };
+        let (investor_penalty_share, platform_penalty_share) =
+            Self::split_late_penalty(&quote, late_penalty);
 
-        if payment_amount <= investment_amount {
-            return Ok((payment_amount, 0));
+        let (mut investor_return, mut platform_fee) = PlatformFee::calculate_with_fee_bps(
+            investment_amount,
+            total_payment.saturating_sub(late_penalty),
+            quote.platform_fee_bps as i128,
+        );
+        let mut platform_fee_waived = 0;
+        if Self::promotion_applies(env, &invoice.category) {
+            investor_return = investor_return.saturating_add(platform_fee);
+            platform_fee_waived = platform_fee;
+            platform_fee = 0;
         }
 
-        let profit = payment_amount.saturating_sub(investment_amount);
-        let platform_fee = profit.saturating_mul(config.fee_bps as i128) / BPS_DENOMINATOR;
-        let investor_return = payment_amount.saturating_sub(platform_fee);
+        Ok(SettlementWaterfall {
+            total_payment,
+            late_penalty,
+            investor_penalty_share,
+            platform_penalty_share,
+            investor_return: investor_return.saturating_add(investor_penalty_share),
+            platform_fee: platform_fee.saturating_add(platform_penalty_share),
+            platform_fee_waived,
+        })
+    }
+
+    /// Record the late penalty applied when settling an invoice
+    pub fn record_late_penalty(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        waterfall: &SettlementWaterfall,
+    ) -> LatePenaltyRecord {
+        let record = LatePenaltyRecord {
+            invoice_id: invoice_id.clone(),
+            penalty_amount: waterfall.late_penalty,
+            investor_share: waterfall.investor_penalty_share,
+            platform_share: waterfall.platform_penalty_share,
+            applied_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(LATE_PENALTY_KEY, invoice_id.clone()), &record);
+        record
+    }
 
-        Ok((investor_return, platform_fee))
+    /// Get the late penalty applied to an invoice, if any
+    pub fn get_late_penalty_record(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Option<LatePenaltyRecord> {
+        env.storage()
+            .instance()
+            .get(&(LATE_PENALTY_KEY, invoice_id.clone()))
     }
 
     /// Get treasury address if configured
@@ -353,14 +853,17 @@ impl FeeManager {
             }
             let mut fee = Self::calculate_base_fee(&structure, transaction_amount)?;
             if structure.fee_type != FeeType::LatePayment {
-                fee =
-                    fee.saturating_sub(fee.saturating_mul(tier_discount as i128) / BPS_DENOMINATOR);
+                fee = fee.saturating_sub(rounding::bps_of(
+                    fee,
+                    tier_discount as i128,
+                    Rounding::Floor,
+                ));
             }
             if is_early_payment && structure.fee_type == FeeType::Platform {
-                fee = fee.saturating_sub(fee.saturating_mul(1000) / BPS_DENOMINATOR);
+                fee = fee.saturating_sub(rounding::bps_of(fee, 1000, Rounding::Floor));
             }
             if is_late_payment && structure.fee_type == FeeType::LatePayment {
-                fee = fee.saturating_add(fee.saturating_mul(2000) / BPS_DENOMINATOR);
+                fee = fee.saturating_add(rounding::bps_of(fee, 2000, Rounding::Floor));
             }
             total_fees = total_fees.saturating_add(fee);
         }
@@ -368,7 +871,7 @@ impl FeeManager {
     }
 
     fn calculate_base_fee(structure: &FeeStructure, amount: i128) -> Result<i128, QuickLendXError> {
-        let fee = amount.saturating_mul(structure.base_fee_bps as i128) / BPS_DENOMINATOR;
+        let fee = rounding::bps_of(amount, structure.base_fee_bps as i128, Rounding::Floor);
         let fee = if fee < structure.min_fee {
             structure.min_fee
         } else if fee > structure.max_fee {
@@ -504,13 +1007,13 @@ impl FeeManager {
             return Err(QuickLendXError::InvalidAmount);
         }
         let amount = revenue_data.pending_distribution;
-        let treasury_amount =
-            amount.saturating_mul(config.treasury_share_bps as i128) / BPS_DENOMINATOR;
-        let developer_amount =
-            amount.saturating_mul(config.developer_share_bps as i128) / BPS_DENOMINATOR;
-        let platform_amount = amount
-            .saturating_sub(treasury_amount)
-            .saturating_sub(developer_amount);
+        let ([treasury_amount, developer_amount], platform_amount) = rounding::allocate_bps(
+            amount,
+            [
+                config.treasury_share_bps as i128,
+                config.developer_share_bps as i128,
+            ],
+        );
         revenue_data.total_distributed = revenue_data.total_distributed.saturating_add(amount);
         revenue_data.pending_distribution = 0;
         env.storage().instance().set(&revenue_key, &revenue_data);
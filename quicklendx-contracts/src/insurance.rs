@@ -0,0 +1,879 @@
+//! Insurance program statistics: records premiums and claims as they happen so the admin can
+//! monitor premiums, claims and loss ratios per provider over an analytics `TimePeriod`.
+//!
+//! Records are stored under sequential ids. Outstanding coverage is read from the active
+//! investments of funded invoices at query time. The capacity and solvency checks made on every
+//! purchase instead read running totals per provider and per currency, which
+//! `InvestmentStorage::update_investment` keeps in step as coverage is bought, cancelled or
+//! ends with its investment.
+//!
+//! Each provider's capacity is set by the reserves the admin records for it. Once a leverage
+//! multiplier is configured, a provider cannot sell coverage beyond its reserves times the
+//! multiplier, so the coverage it owes stays backed.
+//!
+//! Claims are paid in tokens from the insurance pool of the invoice's currency (see
+//! `insurance_pool`). When an insured investment defaults, the coverage is paid to the investor
+//! from the pool straight away; any part the pool cannot cover stays owed on the `InsuranceClaim`
+//! and is paid with `pay_insurance_claim` once providers stake more.
+//!
+//! Once the admin configures claim adjudication, defaults no longer pay out automatically. The
+//! investor files the claim with `file_insurance_claim`, and the provider or the arbitrator
+//! approves or denies it within the review period. A claim nobody resolves in time is approved
+//! by anyone calling `approve_expired_insurance_claim`.
+
+use crate::admin::AdminStorage;
+use crate::analytics::{AnalyticsCalculator, TimePeriod};
+use crate::audit::{log_operation, AuditOperation};
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_insurance_added, emit_insurance_claim_filed, emit_insurance_claim_paid,
+    emit_insurance_claim_resolved, emit_insurance_premium_collected, emit_insurance_premium_split,
+};
+use crate::insurance_pool::InsurancePools;
+use crate::insurance_registry::InsuranceProviderRegistry;
+use crate::investment::{Investment, InvestmentStatus, InvestmentStorage};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::premium_schedule::PremiumSchedules;
+use crate::rounding::{self, Rounding};
+use crate::tax_lots::TaxLots;
+use crate::watchers::{InvoiceWatchers, WatcherRole};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const INSURANCE_RECORD_COUNT_KEY: Symbol = symbol_short!("ins_cnt");
+const INSURANCE_RESERVE_KEY: Symbol = symbol_short!("ins_rsv");
+const INSURANCE_MULTIPLIER_KEY: Symbol = symbol_short!("ins_mult");
+const INSURANCE_CLAIM_KEY: Symbol = symbol_short!("ins_claim");
+const CLAIM_ADJUDICATION_KEY: Symbol = symbol_short!("clm_adj");
+const COVERAGE_ENTRY_KEY: Symbol = symbol_short!("cov_inv");
+const PROVIDER_COVERAGE_KEY: Symbol = symbol_short!("cov_prov");
+const CURRENCY_COVERAGE_KEY: Symbol = symbol_short!("cov_cur");
+/// Longest review period the admin may give providers and the arbitrator (30 days)
+pub const MAX_CLAIM_REVIEW_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// Kind of insurance activity
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InsuranceRecordKind {
+    PremiumCollected,
+    /// Unused premium paid back on cancelled coverage
+    PremiumRefunded,
+    ClaimFiled,
+    ClaimApproved,
+    ClaimDenied,
+    ClaimPaid,
+    /// Paid coverage returned to the provider out of a later default recovery
+    ClaimRecovered,
+}
+
+/// A single premium or claim step
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceRecord {
+    pub kind: InsuranceRecordKind,
+    pub provider: Address,
+    pub investment_id: BytesN<32>,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+/// Premiums, claims and outstanding coverage for one provider
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderInsuranceStats {
+    pub provider: Address,
+    pub policies_written: u32,
+    /// Premiums collected net of cancellation refunds
+    pub premiums_collected: i128,
+    pub claims_filed: u32,
+    pub claims_approved: u32,
+    pub claims_denied: u32,
+    pub claims_paid: u32,
+    pub claims_paid_amount: i128,
+    pub claims_recovered_amount: i128,
+    /// Claims paid net of recoveries as a share of premiums collected, in basis points
+    pub loss_ratio_bps: i128,
+    /// Coverage still owed on active investments (not limited to the period)
+    pub active_coverage: i128,
+}
+
+/// Review settings for default claims; without them claims are approved when filed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimAdjudication {
+    /// Seconds the provider or arbitrator has to resolve a filed claim
+    pub review_period: u64,
+    /// Reviews claims alongside the provider; the admin when unset
+    pub arbitrator: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InsuranceClaimStatus {
+    /// Awaiting review until `review_deadline`
+    Pending,
+    Approved,
+    Denied,
+}
+
+/// A default claim on an insured investment and how much of it has been paid
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceClaim {
+    pub investment_id: BytesN<32>,
+    pub invoice_id: BytesN<32>,
+    pub provider: Address,
+    pub investor: Address,
+    pub currency: Address,
+    pub coverage_amount: i128,
+    /// Paid to the investor from the insurance pool so far
+    pub paid_amount: i128,
+    pub filed_at: u64,
+    pub status: InsuranceClaimStatus,
+    /// Last moment a pending claim can be approved or denied; later it can only be approved
+    pub review_deadline: u64,
+    pub resolved_at: Option<u64>,
+}
+
+/// Insurance program health for a period
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceStats {
+    pub period: TimePeriod,
+    pub start_date: u64,
+    pub end_date: u64,
+    pub policies_written: u32,
+    pub premiums_collected: i128,
+    pub claims_filed: u32,
+    pub claims_approved: u32,
+    pub claims_denied: u32,
+    pub claims_paid: u32,
+    pub claims_paid_amount: i128,
+    pub claims_recovered_amount: i128,
+    pub loss_ratio_bps: i128,
+    pub active_coverage: i128,
+    pub providers: Vec<ProviderInsuranceStats>,
+}
+
+pub struct InsuranceLog;
+
+impl InsuranceLog {
+    fn record_key(record_id: u64) -> (Symbol, u64) {
+        (symbol_short!("ins_rec"), record_id)
+    }
+
+    pub fn count(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&INSURANCE_RECORD_COUNT_KEY)
+            .unwrap_or(0u64)
+    }
+
+    /// Record a premium or claim step
+    pub fn record(
+        env: &Env,
+        kind: InsuranceRecordKind,
+        provider: &Address,
+        investment_id: &BytesN<32>,
+        amount: i128,
+    ) {
+        let record_id = Self::count(env);
+        let record = InsuranceRecord {
+            kind,
+            provider: provider.clone(),
+            investment_id: investment_id.clone(),
+            amount,
+            timestamp: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&Self::record_key(record_id), &record);
+        env.storage()
+            .instance()
+            .set(&INSURANCE_RECORD_COUNT_KEY, &(record_id + 1));
+    }
+
+    fn loss_ratio_bps(
+        claims_paid_amount: i128,
+        claims_recovered_amount: i128,
+        premiums_collected: i128,
+    ) -> i128 {
+        if premiums_collected <= 0 {
+            return 0;
+        }
+        claims_paid_amount
+            .saturating_sub(claims_recovered_amount)
+            .saturating_mul(10_000)
+            / premiums_collected
+    }
+
+    fn provider_index(stats: &Vec<ProviderInsuranceStats>, provider: &Address) -> Option<u32> {
+        (0..stats.len()).find(|&idx| stats.get(idx).unwrap().provider == *provider)
+    }
+
+    fn provider_entry(
+        stats: &mut Vec<ProviderInsuranceStats>,
+        provider: &Address,
+    ) -> (u32, ProviderInsuranceStats) {
+        if let Some(idx) = Self::provider_index(stats, provider) {
+            return (idx, stats.get(idx).unwrap());
+        }
+        let entry = ProviderInsuranceStats {
+            provider: provider.clone(),
+            policies_written: 0,
+            premiums_collected: 0,
+            claims_filed: 0,
+            claims_approved: 0,
+            claims_denied: 0,
+            claims_paid: 0,
+            claims_paid_amount: 0,
+            claims_recovered_amount: 0,
+            loss_ratio_bps: 0,
+            active_coverage: 0,
+        };
+        stats.push_back(entry.clone());
+        (stats.len() - 1, entry)
+    }
+
+    /// Aggregate premiums and claims within a period, with current outstanding coverage
+    pub fn get_stats(env: &Env, period: TimePeriod) -> InsuranceStats {
+        let (start_date, end_date) =
+            AnalyticsCalculator::get_period_dates(env.ledger().timestamp(), period.clone());
+        let mut providers: Vec<ProviderInsuranceStats> = Vec::new(env);
+
+        for record_id in 0..Self::count(env) {
+            let record: InsuranceRecord =
+                match env.storage().instance().get(&Self::record_key(record_id)) {
+                    Some(record) => record,
+                    None => continue,
+                };
+            if record.timestamp < start_date || record.timestamp > end_date {
+                continue;
+            }
+            let (idx, mut entry) = Self::provider_entry(&mut providers, &record.provider);
+            match record.kind {
+                InsuranceRecordKind::PremiumCollected => {
+                    entry.policies_written = entry.policies_written.saturating_add(1);
+                    entry.premiums_collected =
+                        entry.premiums_collected.saturating_add(record.amount);
+                }
+                InsuranceRecordKind::PremiumRefunded => {
+                    entry.premiums_collected =
+                        entry.premiums_collected.saturating_sub(record.amount);
+                }
+                InsuranceRecordKind::ClaimFiled => {
+                    entry.claims_filed = entry.claims_filed.saturating_add(1)
+                }
+                InsuranceRecordKind::ClaimApproved => {
+                    entry.claims_approved = entry.claims_approved.saturating_add(1)
+                }
+                InsuranceRecordKind::ClaimDenied => {
+                    entry.claims_denied = entry.claims_denied.saturating_add(1)
+                }
+                InsuranceRecordKind::ClaimPaid => {
+                    entry.claims_paid = entry.claims_paid.saturating_add(1);
+                    entry.claims_paid_amount =
+                        entry.claims_paid_amount.saturating_add(record.amount);
+                }
+                InsuranceRecordKind::ClaimRecovered => {
+                    entry.claims_recovered_amount =
+                        entry.claims_recovered_amount.saturating_add(record.amount);
+                }
+            }
+            providers.set(idx, entry);
+        }
+
+        // Outstanding coverage on active investments of funded invoices
+        for invoice_id in InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded).iter()
+        {
+            let investment = match InvestmentStorage::get_investment_by_invoice(env, &invoice_id) {
+                Some(investment) if investment.status == InvestmentStatus::Active => investment,
+                _ => continue,
+            };
+            for coverage in investment.insurance.iter() {
+                if !coverage.active {
+                    continue;
+                }
+                let (idx, mut entry) = Self::provider_entry(&mut providers, &coverage.provider);
+                entry.active_coverage = entry
+                    .active_coverage
+                    .saturating_add(coverage.coverage_amount);
+                providers.set(idx, entry);
+            }
+        }
+
+        let mut stats = InsuranceStats {
+            period,
+            start_date,
+            end_date,
+            policies_written: 0,
+            premiums_collected: 0,
+            claims_filed: 0,
+            claims_approved: 0,
+            claims_denied: 0,
+            claims_paid: 0,
+            claims_paid_amount: 0,
+            claims_recovered_amount: 0,
+            loss_ratio_bps: 0,
+            active_coverage: 0,
+            providers: Vec::new(env),
+        };
+        for mut entry in providers.iter() {
+            entry.loss_ratio_bps = Self::loss_ratio_bps(
+                entry.claims_paid_amount,
+                entry.claims_recovered_amount,
+                entry.premiums_collected,
+            );
+            stats.policies_written = stats
+                .policies_written
+                .saturating_add(entry.policies_written);
+            stats.premiums_collected = stats
+                .premiums_collected
+                .saturating_add(entry.premiums_collected);
+            stats.claims_filed = stats.claims_filed.saturating_add(entry.claims_filed);
+            stats.claims_approved = stats.claims_approved.saturating_add(entry.claims_approved);
+            stats.claims_denied = stats.claims_denied.saturating_add(entry.claims_denied);
+            stats.claims_paid = stats.claims_paid.saturating_add(entry.claims_paid);
+            stats.claims_paid_amount = stats
+                .claims_paid_amount
+                .saturating_add(entry.claims_paid_amount);
+            stats.claims_recovered_amount = stats
+                .claims_recovered_amount
+                .saturating_add(entry.claims_recovered_amount);
+            stats.active_coverage = stats.active_coverage.saturating_add(entry.active_coverage);
+            stats.providers.push_back(entry);
+        }
+        stats.loss_ratio_bps = Self::loss_ratio_bps(
+            stats.claims_paid_amount,
+            stats.claims_recovered_amount,
+            stats.premiums_collected,
+        );
+        stats
+    }
+}
+
+/// A provider's reserves compared with the coverage it has sold
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceSolvency {
+    pub provider: Address,
+    /// Pool assets recorded for the provider
+    pub reserve: i128,
+    /// Coverage still owed on active investments of funded invoices
+    pub active_coverage: i128,
+    /// Coverage allowed per unit of reserve, in basis points; 0 when the limit is disabled
+    pub multiplier_bps: u32,
+    /// Most coverage the provider may have outstanding; `None` when the limit is disabled
+    pub coverage_limit: Option<i128>,
+    /// Reserve as a share of active coverage, in basis points; `None` without active coverage
+    pub solvency_ratio_bps: Option<i128>,
+}
+
+/// Active coverage an investment counts toward the running totals
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct CoverageEntry {
+    provider: Address,
+    currency: Address,
+    coverage_amount: i128,
+}
+
+pub struct InsuranceReserves;
+
+impl InsuranceReserves {
+    pub fn get_reserve(env: &Env, provider: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(INSURANCE_RESERVE_KEY, provider.clone()))
+            .unwrap_or(0)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if `reserve` is negative
+    pub fn set_reserve(
+        env: &Env,
+        provider: &Address,
+        reserve: i128,
+    ) -> Result<(), QuickLendXError> {
+        if reserve < 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&(INSURANCE_RESERVE_KEY, provider.clone()), &reserve);
+        Ok(())
+    }
+
+    pub fn get_multiplier_bps(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&INSURANCE_MULTIPLIER_KEY)
+            .unwrap_or(0)
+    }
+
+    pub fn set_multiplier_bps(env: &Env, multiplier_bps: u32) {
+        env.storage()
+            .instance()
+            .set(&INSURANCE_MULTIPLIER_KEY, &multiplier_bps);
+    }
+
+    /// Coverage `provider` still owes on active investments of funded invoices
+    pub fn active_coverage(env: &Env, provider: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(PROVIDER_COVERAGE_KEY, provider.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Coverage all providers still owe on active investments of funded invoices in `currency`
+    pub fn active_coverage_in(env: &Env, currency: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(CURRENCY_COVERAGE_KEY, currency.clone()))
+            .unwrap_or(0)
+    }
+
+    /// Bring the running coverage totals in line with an investment that was just stored: its
+    /// active coverage counts while the investment is active
+    pub fn sync_coverage(env: &Env, investment: &Investment) {
+        let key = (COVERAGE_ENTRY_KEY, investment.investment_id.clone());
+        let old: Option<CoverageEntry> = env.storage().instance().get(&key);
+        let active = investment
+            .insurance
+            .iter()
+            .find(|coverage| coverage.active)
+            .filter(|_| investment.status == InvestmentStatus::Active);
+        let new = match active {
+            Some(coverage) => {
+                let currency = match &old {
+                    Some(old) => old.currency.clone(),
+                    None => match InvoiceStorage::get_invoice(env, &investment.invoice_id) {
+                        Some(invoice) => invoice.currency,
+                        None => return,
+                    },
+                };
+                Some(CoverageEntry {
+                    provider: coverage.provider,
+                    currency,
+                    coverage_amount: coverage.coverage_amount,
+                })
+            }
+            None => None,
+        };
+        if old == new {
+            return;
+        }
+
+        if let Some(old) = old {
+            Self::adjust_coverage(env, &old, -old.coverage_amount);
+        }
+        match new {
+            Some(new) => {
+                Self::adjust_coverage(env, &new, new.coverage_amount);
+                env.storage().instance().set(&key, &new);
+            }
+            None => env.storage().instance().remove(&key),
+        }
+    }
+
+    fn adjust_coverage(env: &Env, entry: &CoverageEntry, amount: i128) {
+        let provider_key = (PROVIDER_COVERAGE_KEY, entry.provider.clone());
+        let total = Self::active_coverage(env, &entry.provider).saturating_add(amount);
+        env.storage().instance().set(&provider_key, &total);
+        let currency_key = (CURRENCY_COVERAGE_KEY, entry.currency.clone());
+        let total = Self::active_coverage_in(env, &entry.currency).saturating_add(amount);
+        env.storage().instance().set(&currency_key, &total);
+    }
+
+    pub fn get_solvency(env: &Env, provider: &Address) -> InsuranceSolvency {
+        let reserve = Self::get_reserve(env, provider);
+        let active_coverage = Self::active_coverage(env, provider);
+        let multiplier_bps = Self::get_multiplier_bps(env);
+        InsuranceSolvency {
+            provider: provider.clone(),
+            reserve,
+            active_coverage,
+            multiplier_bps,
+            coverage_limit: (multiplier_bps > 0)
+                .then(|| rounding::bps_of(reserve, multiplier_bps as i128, Rounding::Floor)),
+            solvency_ratio_bps: (active_coverage > 0).then(|| {
+                rounding::mul_div(reserve, rounding::BPS, active_coverage, Rounding::Floor)
+            }),
+        }
+    }
+
+    /// Check that `provider` can sell `coverage_amount` more coverage without becoming
+    /// under-reserved
+    ///
+    /// # Errors
+    /// * `InsufficientFunds` if the coverage would exceed the provider's coverage limit
+    pub fn ensure_capacity(
+        env: &Env,
+        provider: &Address,
+        coverage_amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let solvency = Self::get_solvency(env, provider);
+        if let Some(limit) = solvency.coverage_limit {
+            if solvency.active_coverage.saturating_add(coverage_amount) > limit {
+                return Err(QuickLendXError::InsufficientFunds);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct InsurancePurchases;
+
+impl InsurancePurchases {
+    /// Run the checks `buy` makes before charging the premium, without changing anything
+    ///
+    /// # Returns
+    /// * The premium `buy` would charge
+    ///
+    /// # Errors
+    /// * As for `buy`, apart from transfer errors
+    pub fn check(
+        env: &Env,
+        investment: &Investment,
+        invoice: &Invoice,
+        provider: &Address,
+        coverage_percentage: u32,
+    ) -> Result<i128, QuickLendXError> {
+        let premium =
+            PremiumSchedules::quote(env, investment, invoice, coverage_percentage)?.premium;
+        let coverage_amount = investment.clone().add_insurance(
+            provider.clone(),
+            coverage_percentage,
+            premium,
+            env.ledger().timestamp(),
+        )?;
+        InsuranceProviderRegistry::ensure_can_underwrite(env, provider, coverage_amount)?;
+        InsuranceReserves::ensure_capacity(env, provider, coverage_amount)?;
+        Ok(premium)
+    }
+
+    /// Buy coverage of `coverage_percentage` from `provider` for an investment, charging the
+    /// premium from the premium schedule to the investor and storing the updated investment.
+    /// The caller has checked the investor's authorization and holds the payment guard.
+    ///
+    /// # Returns
+    /// * The premium charged
+    ///
+    /// # Errors
+    /// * `InvalidCoveragePercentage`, `InvalidAmount` from the premium quote
+    /// * `OperationNotAllowed` if coverage is already active
+    /// * `Unauthorized` if the provider is not an approved insurance provider
+    /// * `InsufficientFunds` if the coverage would exceed the provider's capacity or reserves
+    /// * Transfer errors if the investor cannot pay the premium
+    pub fn buy(
+        env: &Env,
+        investment: &mut Investment,
+        invoice: &Invoice,
+        provider: &Address,
+        coverage_percentage: u32,
+    ) -> Result<i128, QuickLendXError> {
+        let premium = Self::check(env, investment, invoice, provider, coverage_percentage)?;
+        let coverage_amount = investment.add_insurance(
+            provider.clone(),
+            coverage_percentage,
+            premium,
+            env.ledger().timestamp(),
+        )?;
+        let split =
+            InsurancePools::collect_premium(env, &invoice.currency, &investment.investor, premium)?;
+
+        InvestmentStorage::update_investment(env, investment);
+        InvoiceWatchers::add(env, &investment.invoice_id, provider, WatcherRole::Insurer);
+
+        emit_insurance_added(
+            env,
+            &investment.investment_id,
+            &investment.invoice_id,
+            &investment.investor,
+            provider,
+            coverage_percentage,
+            coverage_amount,
+            premium,
+        );
+        emit_insurance_premium_collected(env, &investment.investment_id, provider, premium);
+        emit_insurance_premium_split(
+            env,
+            &investment.investment_id,
+            &investment.investor,
+            &invoice.currency,
+            &split,
+        );
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::PremiumCollected,
+            provider,
+            &investment.investment_id,
+            premium,
+        );
+        Ok(premium)
+    }
+}
+
+pub struct InsuranceClaims;
+
+impl InsuranceClaims {
+    pub fn get(env: &Env, investment_id: &BytesN<32>) -> Option<InsuranceClaim> {
+        env.storage()
+            .instance()
+            .get(&(INSURANCE_CLAIM_KEY, investment_id.clone()))
+    }
+
+    fn set(env: &Env, claim: &InsuranceClaim) {
+        env.storage()
+            .instance()
+            .set(&(INSURANCE_CLAIM_KEY, claim.investment_id.clone()), claim);
+    }
+
+    pub fn get_adjudication(env: &Env) -> Option<ClaimAdjudication> {
+        env.storage().instance().get(&CLAIM_ADJUDICATION_KEY)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if the review period is zero or exceeds `MAX_CLAIM_REVIEW_PERIOD`
+    pub fn set_adjudication(
+        env: &Env,
+        adjudication: Option<ClaimAdjudication>,
+    ) -> Result<(), QuickLendXError> {
+        match adjudication {
+            Some(adjudication) => {
+                if adjudication.review_period == 0
+                    || adjudication.review_period > MAX_CLAIM_REVIEW_PERIOD
+                {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                env.storage()
+                    .instance()
+                    .set(&CLAIM_ADJUDICATION_KEY, &adjudication);
+            }
+            None => env.storage().instance().remove(&CLAIM_ADJUDICATION_KEY),
+        }
+        Ok(())
+    }
+
+    /// File the default claim of an investment whose coverage was just processed
+    ///
+    /// With claim adjudication configured the claim waits for review; otherwise it is approved
+    /// and paid from the currency's insurance pool as far as the pool reaches.
+    pub fn file(
+        env: &Env,
+        investment: &Investment,
+        currency: &Address,
+        provider: &Address,
+        coverage_amount: i128,
+    ) -> Result<InsuranceClaim, QuickLendXError> {
+        let now = env.ledger().timestamp();
+        let adjudication = Self::get_adjudication(env);
+        let claim = InsuranceClaim {
+            investment_id: investment.investment_id.clone(),
+            invoice_id: investment.invoice_id.clone(),
+            provider: provider.clone(),
+            investor: investment.investor.clone(),
+            currency: currency.clone(),
+            coverage_amount,
+            paid_amount: 0,
+            filed_at: now,
+            status: InsuranceClaimStatus::Pending,
+            review_deadline: adjudication.as_ref().map_or(now, |adjudication| {
+                now.saturating_add(adjudication.review_period)
+            }),
+            resolved_at: None,
+        };
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::ClaimFiled,
+            provider,
+            &investment.investment_id,
+            coverage_amount,
+        );
+        if adjudication.is_some() {
+            Self::set(env, &claim);
+            emit_insurance_claim_filed(env, &claim);
+            return Ok(claim);
+        }
+        Self::approve(env, claim)
+    }
+
+    /// File the claim on a defaulted investment's coverage for review (investor only)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment does not exist or has no coverage to claim
+    /// * `Unauthorized` if `investor` did not make the investment
+    /// * `InvalidStatus` if the investment has not defaulted
+    /// * `OperationNotAllowed` if a claim was already filed
+    pub fn file_for_investor(
+        env: &Env,
+        investor: &Address,
+        investment_id: &BytesN<32>,
+    ) -> Result<InsuranceClaim, QuickLendXError> {
+        let mut investment = InvestmentStorage::get_investment(env, investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if investment.investor != *investor {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if investment.status != InvestmentStatus::Defaulted {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if Self::get(env, investment_id).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let invoice = InvoiceStorage::get_invoice(env, &investment.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let (provider, coverage_amount) = investment
+            .process_insurance_claim()
+            .filter(|(_, amount)| *amount > 0)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        InvestmentStorage::update_investment(env, &investment);
+
+        let approved_at_filing = Self::get_adjudication(env).is_none();
+        let claim = Self::file(
+            env,
+            &investment,
+            &invoice.currency,
+            &provider,
+            coverage_amount,
+        )?;
+        if approved_at_filing {
+            TaxLots::add_proceeds(env, investment_id, coverage_amount);
+        }
+        Ok(claim)
+    }
+
+    /// Approve or deny a pending claim before its review deadline
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim was already resolved
+    /// * `Unauthorized` if `reviewer` is neither the claim's provider nor the arbitrator
+    /// * `OperationNotAllowed` if the review deadline has passed
+    pub fn resolve(
+        env: &Env,
+        reviewer: &Address,
+        investment_id: &BytesN<32>,
+        approve: bool,
+    ) -> Result<InsuranceClaim, QuickLendXError> {
+        let mut claim = Self::pending(env, investment_id)?;
+        let arbitrator = Self::get_adjudication(env)
+            .and_then(|adjudication| adjudication.arbitrator)
+            .or_else(|| AdminStorage::get_admin(env));
+        if *reviewer != claim.provider && Some(reviewer.clone()) != arbitrator {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if env.ledger().timestamp() > claim.review_deadline {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if approve {
+            TaxLots::add_proceeds(env, investment_id, claim.coverage_amount);
+            let claim = Self::approve(env, claim)?;
+            emit_insurance_claim_resolved(env, &claim);
+            return Ok(claim);
+        }
+        claim.status = InsuranceClaimStatus::Denied;
+        claim.resolved_at = Some(env.ledger().timestamp());
+        Self::set(env, &claim);
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::ClaimDenied,
+            &claim.provider,
+            investment_id,
+            claim.coverage_amount,
+        );
+        emit_insurance_claim_resolved(env, &claim);
+        Ok(claim)
+    }
+
+    /// Approve a pending claim whose review deadline passed without a decision (anyone may
+    /// call)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim was already resolved
+    /// * `OperationNotAllowed` if the review deadline has not passed
+    pub fn approve_expired(
+        env: &Env,
+        investment_id: &BytesN<32>,
+    ) -> Result<InsuranceClaim, QuickLendXError> {
+        let claim = Self::pending(env, investment_id)?;
+        if env.ledger().timestamp() <= claim.review_deadline {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        TaxLots::add_proceeds(env, investment_id, claim.coverage_amount);
+        let claim = Self::approve(env, claim)?;
+        emit_insurance_claim_resolved(env, &claim);
+        Ok(claim)
+    }
+
+    fn pending(env: &Env, investment_id: &BytesN<32>) -> Result<InsuranceClaim, QuickLendXError> {
+        let claim = Self::get(env, investment_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if claim.status != InsuranceClaimStatus::Pending {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        Ok(claim)
+    }
+
+    /// Approve a claim, record it as owed by the pool and pay as much as the pool holds
+    fn approve(env: &Env, mut claim: InsuranceClaim) -> Result<InsuranceClaim, QuickLendXError> {
+        claim.status = InsuranceClaimStatus::Approved;
+        claim.resolved_at = Some(env.ledger().timestamp());
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::ClaimApproved,
+            &claim.provider,
+            &claim.investment_id,
+            claim.coverage_amount,
+        );
+        InsurancePools::add_owed_claims(env, &claim.currency, claim.coverage_amount);
+        Self::set(env, &claim);
+        Self::pay(env, &claim.investment_id)
+    }
+
+    /// Pay the unpaid part of a claim from the currency's insurance pool, as far as it reaches
+    ///
+    /// # Returns
+    /// * The updated claim
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim has not been approved
+    /// * Transfer errors
+    pub fn pay(env: &Env, investment_id: &BytesN<32>) -> Result<InsuranceClaim, QuickLendXError> {
+        let mut claim = Self::get(env, investment_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if claim.status != InsuranceClaimStatus::Approved {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let payment = InsurancePools::pay_claim(
+            env,
+            &claim.currency,
+            &claim.investor,
+            claim.coverage_amount.saturating_sub(claim.paid_amount),
+        )?;
+        if payment <= 0 {
+            return Ok(claim);
+        }
+        claim.paid_amount += payment;
+        Self::set(env, &claim);
+
+        InsuranceLog::record(
+            env,
+            InsuranceRecordKind::ClaimPaid,
+            &claim.provider,
+            investment_id,
+            payment,
+        );
+        log_operation(
+            env,
+            claim.invoice_id.clone(),
+            AuditOperation::InsuranceClaimPaid,
+            claim.provider.clone(),
+            None,
+            Some(String::from_str(env, "Insurance claim paid")),
+            Some(payment),
+            None,
+        );
+        emit_insurance_claim_paid(env, &claim, payment);
+        Ok(claim)
+    }
+}
@@ -0,0 +1,530 @@
+//! Insurance pools: providers stake capital into a shared pool per currency.
+//!
+//! Staking mints pool shares at the pool's current share price. Premiums paid for coverage on
+//! invoices in the currency are added to the pool, raising the value of every share, and default
+//! claims are paid out of it, so stakers share premiums and losses in proportion to their shares.
+//! A pool fully drained by claims starts a new epoch: the worthless shares of the old epoch are
+//! void and the next stake starts the share price afresh.
+//!
+//! The admin can divert a share of each premium to the protocol treasury; the rest goes to the
+//! pool.
+//!
+//! An investor who cancels coverage before the invoice is due gets back the unused part of the
+//! premium, pro rata to the time left until the due date, less a cancellation fee that stays in
+//! the pool. The refund is paid from the pool.
+//!
+//! Each stake is locked for the admin-configured lockup period. Unstaking is also refused while it
+//! would leave the pool holding less than the coverage it has sold and the claims it still owes.
+
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{emit_insurance_pool_staked, emit_insurance_pool_unstaked};
+use crate::fees::FeeManager;
+use crate::insurance::InsuranceReserves;
+use crate::payments::transfer_funds;
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+
+const POOL_KEY: Symbol = symbol_short!("ipl");
+const STAKE_KEY: Symbol = symbol_short!("ipl_stk");
+const LOCKUP_KEY: Symbol = symbol_short!("ipl_lock");
+const PREMIUM_FEE_KEY: Symbol = symbol_short!("ipl_fee");
+const CANCELLATION_FEE_KEY: Symbol = symbol_short!("ipl_cfee");
+/// Longest lockup the admin can impose on stakes (90 days)
+pub const MAX_POOL_LOCKUP: u64 = 90 * 24 * 60 * 60;
+/// Largest share of a premium the protocol can take (50%)
+pub const MAX_PREMIUM_FEE_BPS: u32 = 5_000;
+/// Share of the unused premium kept on cancellation unless the admin sets another (10%)
+pub const DEFAULT_CANCELLATION_FEE_BPS: u32 = 1_000;
+
+/// Balances of one currency's insurance pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsurancePoolState {
+    pub currency: Address,
+    /// Tokens the pool holds
+    pub total_assets: i128,
+    pub total_shares: i128,
+    /// Premiums added to the pool, net of the protocol fee
+    pub premiums_collected: i128,
+    /// Unused premiums paid back on cancelled coverage
+    pub premiums_refunded: i128,
+    pub claims_paid: i128,
+    /// Claim payments returned to the pool from default recoveries
+    pub claims_recovered: i128,
+    /// Filed claims not yet paid for lack of assets
+    pub owed_claims: i128,
+    /// Bumped each time claims drain the pool; stakes from earlier epochs are void
+    pub epoch: u32,
+}
+
+/// How much of a pool is committed to coverage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsurancePoolUtilization {
+    pub currency: Address,
+    pub total_assets: i128,
+    pub total_shares: i128,
+    /// Coverage still owed on active investments of funded invoices in the currency
+    pub active_coverage: i128,
+    pub owed_claims: i128,
+    /// Active coverage and owed claims as a share of assets, in basis points; `None` for an
+    /// empty pool
+    pub utilization_bps: Option<i128>,
+    /// Assets stakers could withdraw without under-funding the pool
+    pub available: i128,
+}
+
+/// How a collected premium was divided
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PremiumSplit {
+    pub premium: i128,
+    /// Part added to the insurance pool
+    pub pool_amount: i128,
+    /// Part routed to the protocol treasury
+    pub protocol_fee: i128,
+    /// Where the protocol fee went: the treasury, or the contract when none is configured;
+    /// `None` without a fee
+    pub fee_recipient: Option<Address>,
+}
+
+/// Premium returned on cancelled coverage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PremiumRefund {
+    pub premium: i128,
+    /// Part of the premium for the time left until the invoice due date
+    pub unused_premium: i128,
+    /// Part of the unused premium kept by the pool
+    pub cancellation_fee: i128,
+    /// Paid to the investor
+    pub refund: i128,
+}
+
+/// A provider's position in a pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceStake {
+    pub provider: Address,
+    pub currency: Address,
+    pub shares: i128,
+    /// Current value of the shares
+    pub value: i128,
+    pub locked_until: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct StakeRecord {
+    shares: i128,
+    locked_until: u64,
+    epoch: u32,
+}
+
+pub struct InsurancePools;
+
+impl InsurancePools {
+    pub fn get_pool(env: &Env, currency: &Address) -> InsurancePoolState {
+        env.storage()
+            .instance()
+            .get(&(POOL_KEY, currency.clone()))
+            .unwrap_or(InsurancePoolState {
+                currency: currency.clone(),
+                total_assets: 0,
+                total_shares: 0,
+                premiums_collected: 0,
+                premiums_refunded: 0,
+                claims_paid: 0,
+                claims_recovered: 0,
+                owed_claims: 0,
+                epoch: 0,
+            })
+    }
+
+    fn set_pool(env: &Env, pool: &InsurancePoolState) {
+        env.storage()
+            .instance()
+            .set(&(POOL_KEY, pool.currency.clone()), pool);
+    }
+
+    pub fn get_lockup(env: &Env) -> u64 {
+        env.storage().instance().get(&LOCKUP_KEY).unwrap_or(0)
+    }
+
+    /// Stakes made from now on are locked for `seconds`; existing locks are unchanged
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `seconds` exceeds `MAX_POOL_LOCKUP`
+    pub fn set_lockup(env: &Env, seconds: u64) -> Result<(), QuickLendXError> {
+        if seconds > MAX_POOL_LOCKUP {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage().instance().set(&LOCKUP_KEY, &seconds);
+        Ok(())
+    }
+
+    /// The provider's stake, with shares from a drained epoch counted as zero
+    fn get_record(env: &Env, provider: &Address, pool: &InsurancePoolState) -> StakeRecord {
+        env.storage()
+            .instance()
+            .get::<_, StakeRecord>(&(STAKE_KEY, provider.clone(), pool.currency.clone()))
+            .filter(|record| record.epoch == pool.epoch)
+            .unwrap_or(StakeRecord {
+                shares: 0,
+                locked_until: 0,
+                epoch: pool.epoch,
+            })
+    }
+
+    fn set_record(env: &Env, provider: &Address, currency: &Address, record: &StakeRecord) {
+        env.storage()
+            .instance()
+            .set(&(STAKE_KEY, provider.clone(), currency.clone()), record);
+    }
+
+    fn share_value(pool: &InsurancePoolState, shares: i128) -> i128 {
+        rounding::mul_div(
+            shares,
+            pool.total_assets,
+            pool.total_shares,
+            Rounding::Floor,
+        )
+    }
+
+    pub fn get_stake(env: &Env, provider: &Address, currency: &Address) -> InsuranceStake {
+        let pool = Self::get_pool(env, currency);
+        let record = Self::get_record(env, provider, &pool);
+        InsuranceStake {
+            provider: provider.clone(),
+            currency: currency.clone(),
+            shares: record.shares,
+            value: Self::share_value(&pool, record.shares),
+            locked_until: record.locked_until,
+        }
+    }
+
+    pub fn get_utilization(env: &Env, currency: &Address) -> InsurancePoolUtilization {
+        let pool = Self::get_pool(env, currency);
+        let active_coverage = InsuranceReserves::active_coverage_in(env, currency);
+        let committed = active_coverage.saturating_add(pool.owed_claims);
+        InsurancePoolUtilization {
+            currency: currency.clone(),
+            total_assets: pool.total_assets,
+            total_shares: pool.total_shares,
+            active_coverage,
+            owed_claims: pool.owed_claims,
+            utilization_bps: (pool.total_assets > 0).then(|| {
+                rounding::mul_div(
+                    committed,
+                    rounding::BPS,
+                    pool.total_assets,
+                    Rounding::HalfEven,
+                )
+            }),
+            available: pool.total_assets.saturating_sub(committed).max(0),
+        }
+    }
+
+    /// Move `amount` from the provider into the pool for new shares; the caller has checked the
+    /// provider's authorization
+    ///
+    /// # Returns
+    /// * The provider's stake after the deposit
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive or too small to mint a share
+    /// * `CurrencyNotWhitelisted` if the currency is not whitelisted
+    /// * Transfer errors
+    pub fn stake(
+        env: &Env,
+        provider: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> Result<InsuranceStake, QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        CurrencyWhitelist::require_allowed_currency(env, currency)?;
+        let mut pool = Self::get_pool(env, currency);
+        let shares = if pool.total_shares == 0 {
+            amount
+        } else {
+            rounding::mul_div(
+                amount,
+                pool.total_shares,
+                pool.total_assets,
+                Rounding::Floor,
+            )
+        };
+        if shares <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        transfer_funds(
+            env,
+            currency,
+            provider,
+            &env.current_contract_address(),
+            amount,
+        )?;
+
+        let mut record = Self::get_record(env, provider, &pool);
+        record.shares = record.shares.saturating_add(shares);
+        record.locked_until = record.locked_until.max(
+            env.ledger()
+                .timestamp()
+                .saturating_add(Self::get_lockup(env)),
+        );
+        pool.total_assets = pool.total_assets.saturating_add(amount);
+        pool.total_shares = pool.total_shares.saturating_add(shares);
+        Self::set_record(env, provider, currency, &record);
+        Self::set_pool(env, &pool);
+
+        emit_insurance_pool_staked(env, provider, currency, amount, shares);
+        Ok(Self::get_stake(env, provider, currency))
+    }
+
+    /// Pay `amount` of the provider's stake back to it, burning the shares it is worth; the
+    /// caller has checked the provider's authorization
+    ///
+    /// # Returns
+    /// * The provider's stake after the withdrawal
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `OperationNotAllowed` if the stake is still locked
+    /// * `InsufficientFunds` if it exceeds the stake's value, or the pool would be left with less
+    ///   than its active coverage and owed claims
+    /// * Transfer errors
+    pub fn unstake(
+        env: &Env,
+        provider: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> Result<InsuranceStake, QuickLendXError> {
+        if amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let mut pool = Self::get_pool(env, currency);
+        let mut record = Self::get_record(env, provider, &pool);
+        if env.ledger().timestamp() < record.locked_until {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let shares =
+            rounding::mul_div(amount, pool.total_shares, pool.total_assets, Rounding::Ceil);
+        if shares <= 0 || shares > record.shares {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        if amount > Self::get_utilization(env, currency).available {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+
+        record.shares -= shares;
+        pool.total_assets -= amount;
+        pool.total_shares -= shares;
+        Self::set_record(env, provider, currency, &record);
+        Self::set_pool(env, &pool);
+        transfer_funds(
+            env,
+            currency,
+            &env.current_contract_address(),
+            provider,
+            amount,
+        )?;
+
+        emit_insurance_pool_unstaked(env, provider, currency, amount, shares);
+        Ok(Self::get_stake(env, provider, currency))
+    }
+
+    /// Share of each premium routed to the protocol treasury, in basis points
+    pub fn get_premium_fee_bps(env: &Env) -> u32 {
+        env.storage().instance().get(&PREMIUM_FEE_KEY).unwrap_or(0)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if `fee_bps` exceeds `MAX_PREMIUM_FEE_BPS`
+    pub fn set_premium_fee_bps(env: &Env, fee_bps: u32) -> Result<(), QuickLendXError> {
+        if fee_bps > MAX_PREMIUM_FEE_BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage().instance().set(&PREMIUM_FEE_KEY, &fee_bps);
+        Ok(())
+    }
+
+    /// Share of the unused premium kept on cancellation, in basis points
+    pub fn get_cancellation_fee_bps(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&CANCELLATION_FEE_KEY)
+            .unwrap_or(DEFAULT_CANCELLATION_FEE_BPS)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if `fee_bps` exceeds 10,000
+    pub fn set_cancellation_fee_bps(env: &Env, fee_bps: u32) -> Result<(), QuickLendXError> {
+        if fee_bps as i128 > rounding::BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&CANCELLATION_FEE_KEY, &fee_bps);
+        Ok(())
+    }
+
+    /// Refund due on coverage bought at `purchased_at` for `premium` and cancelled now, for an
+    /// invoice due at `due_date`
+    pub fn premium_refund(
+        env: &Env,
+        premium: i128,
+        purchased_at: u64,
+        due_date: u64,
+    ) -> PremiumRefund {
+        let term = due_date.saturating_sub(purchased_at);
+        let remaining = due_date.saturating_sub(env.ledger().timestamp()).min(term);
+        let unused_premium = if term == 0 {
+            0
+        } else {
+            rounding::mul_div(premium, remaining as i128, term as i128, Rounding::Floor)
+        };
+        let cancellation_fee = rounding::bps_of(
+            unused_premium,
+            Self::get_cancellation_fee_bps(env) as i128,
+            Rounding::Ceil,
+        );
+        PremiumRefund {
+            premium,
+            unused_premium,
+            cancellation_fee,
+            refund: unused_premium - cancellation_fee,
+        }
+    }
+
+    /// Pay a cancellation refund of `amount` to `to` from the pool
+    ///
+    /// # Errors
+    /// * `InsufficientFunds` if the pool holds less than `amount`
+    /// * Transfer errors
+    pub fn refund_premium(
+        env: &Env,
+        currency: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let mut pool = Self::get_pool(env, currency);
+        if amount > pool.total_assets {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        pool.total_assets -= amount;
+        pool.premiums_refunded = pool.premiums_refunded.saturating_add(amount);
+        if pool.total_assets == 0 {
+            pool.total_shares = 0;
+            pool.epoch += 1;
+        }
+        Self::set_pool(env, &pool);
+        transfer_funds(env, currency, &env.current_contract_address(), to, amount)
+    }
+
+    /// Collect a premium from `payer`: the protocol fee share goes to the treasury and the rest
+    /// into the pool
+    ///
+    /// # Errors
+    /// * Transfer errors
+    pub fn collect_premium(
+        env: &Env,
+        currency: &Address,
+        payer: &Address,
+        premium: i128,
+    ) -> Result<PremiumSplit, QuickLendXError> {
+        let protocol_fee = rounding::bps_of(
+            premium,
+            Self::get_premium_fee_bps(env) as i128,
+            Rounding::Floor,
+        );
+        let pool_amount = premium - protocol_fee;
+        let fee_recipient = if protocol_fee > 0 {
+            Some(FeeManager::route_platform_fee(
+                env,
+                currency,
+                payer,
+                protocol_fee,
+            )?)
+        } else {
+            None
+        };
+        transfer_funds(
+            env,
+            currency,
+            payer,
+            &env.current_contract_address(),
+            pool_amount,
+        )?;
+        let mut pool = Self::get_pool(env, currency);
+        pool.total_assets = pool.total_assets.saturating_add(pool_amount);
+        pool.premiums_collected = pool.premiums_collected.saturating_add(pool_amount);
+        Self::set_pool(env, &pool);
+        Ok(PremiumSplit {
+            premium,
+            pool_amount,
+            protocol_fee,
+            fee_recipient,
+        })
+    }
+
+    /// Return `amount` of a paid claim to the pool from a default recovery paid by `payer`
+    ///
+    /// # Errors
+    /// * Transfer errors
+    pub fn recover_claim(
+        env: &Env,
+        currency: &Address,
+        payer: &Address,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        transfer_funds(
+            env,
+            currency,
+            payer,
+            &env.current_contract_address(),
+            amount,
+        )?;
+        let mut pool = Self::get_pool(env, currency);
+        pool.total_assets = pool.total_assets.saturating_add(amount);
+        pool.claims_recovered = pool.claims_recovered.saturating_add(amount);
+        Self::set_pool(env, &pool);
+        Ok(())
+    }
+
+    /// Record a newly filed claim as owed by the pool until it is paid
+    pub fn add_owed_claims(env: &Env, currency: &Address, amount: i128) {
+        let mut pool = Self::get_pool(env, currency);
+        pool.owed_claims = pool.owed_claims.saturating_add(amount);
+        Self::set_pool(env, &pool);
+    }
+
+    /// Pay up to `amount` of an owed claim to `to` from the pool
+    ///
+    /// # Returns
+    /// * The amount paid, limited by the pool's assets
+    pub fn pay_claim(
+        env: &Env,
+        currency: &Address,
+        to: &Address,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        let mut pool = Self::get_pool(env, currency);
+        let payment = amount.min(pool.total_assets);
+        if payment <= 0 {
+            return Ok(0);
+        }
+        pool.total_assets -= payment;
+        pool.claims_paid = pool.claims_paid.saturating_add(payment);
+        pool.owed_claims = pool.owed_claims.saturating_sub(payment).max(0);
+        if pool.total_assets == 0 {
+            pool.total_shares = 0;
+            pool.epoch += 1;
+        }
+        Self::set_pool(env, &pool);
+        transfer_funds(env, currency, &env.current_contract_address(), to, payment)?;
+        Ok(payment)
+    }
+}
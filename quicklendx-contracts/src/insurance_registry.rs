@@ -0,0 +1,205 @@
+//! Insurance provider registry: only providers the admin has approved can be named on new
+//! coverage.
+//!
+//! Providers apply with the most coverage they want to underwrite; the admin approves,
+//! suspends or reinstates them and can change their capacity. Suspension only stops new
+//! coverage: policies already written stay in force and their claims are still paid from the
+//! insurance pool.
+//!
+//! A provider's outstanding coverage is the coverage still active on funded investments, so it
+//! frees up as insured invoices settle, default or have their cover cancelled. New coverage that
+//! would take it past the provider's capacity is rejected.
+
+use crate::errors::QuickLendXError;
+use crate::events::emit_insurance_provider_updated;
+use crate::insurance::InsuranceReserves;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+const PROVIDER_KEY: Symbol = symbol_short!("ins_prv");
+const PROVIDER_LIST_KEY: Symbol = symbol_short!("ins_prvs");
+/// Most providers the registry holds
+pub const MAX_INSURANCE_PROVIDERS: u32 = 100;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InsuranceProviderStatus {
+    /// Registered and awaiting admin approval
+    Pending,
+    Approved,
+    Suspended,
+}
+
+/// A registered insurance provider
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceProvider {
+    pub provider: Address,
+    pub status: InsuranceProviderStatus,
+    /// Most coverage the provider may have outstanding on active investments
+    pub capacity: i128,
+    pub registered_at: u64,
+    /// When the provider was last approved
+    pub approved_at: Option<u64>,
+    pub updated_at: u64,
+}
+
+/// A provider's capacity and how much of it outstanding coverage uses
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProviderCapacity {
+    pub provider: Address,
+    pub capacity: i128,
+    pub outstanding_coverage: i128,
+    pub remaining: i128,
+}
+
+pub struct InsuranceProviderRegistry;
+
+impl InsuranceProviderRegistry {
+    pub fn get(env: &Env, provider: &Address) -> Option<InsuranceProvider> {
+        env.storage()
+            .instance()
+            .get(&(PROVIDER_KEY, provider.clone()))
+    }
+
+    fn set(env: &Env, record: &InsuranceProvider) {
+        env.storage()
+            .instance()
+            .set(&(PROVIDER_KEY, record.provider.clone()), record);
+        emit_insurance_provider_updated(env, record);
+    }
+
+    /// Every registered provider, in registration order
+    pub fn list(env: &Env) -> Vec<InsuranceProvider> {
+        let providers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&PROVIDER_LIST_KEY)
+            .unwrap_or_else(|| Vec::new(env));
+        let mut records = Vec::new(env);
+        for provider in providers.iter() {
+            if let Some(record) = Self::get(env, &provider) {
+                records.push_back(record);
+            }
+        }
+        records
+    }
+
+    /// Register `provider` for approval with the coverage capacity it applies for
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `capacity` is not positive
+    /// * `OperationNotAllowed` if the provider is already registered or the registry is full
+    pub fn register(
+        env: &Env,
+        provider: &Address,
+        capacity: i128,
+    ) -> Result<InsuranceProvider, QuickLendXError> {
+        if capacity <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if Self::get(env, provider).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let mut providers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&PROVIDER_LIST_KEY)
+            .unwrap_or_else(|| Vec::new(env));
+        if providers.len() >= MAX_INSURANCE_PROVIDERS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        providers.push_back(provider.clone());
+        env.storage().instance().set(&PROVIDER_LIST_KEY, &providers);
+
+        let now = env.ledger().timestamp();
+        let record = InsuranceProvider {
+            provider: provider.clone(),
+            status: InsuranceProviderStatus::Pending,
+            capacity,
+            registered_at: now,
+            approved_at: None,
+            updated_at: now,
+        };
+        Self::set(env, &record);
+        Ok(record)
+    }
+
+    /// Move a registered provider to `status`
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the provider is not registered
+    /// * `InvalidStatus` if it already has that status, or a suspension targets a provider
+    ///   that was never approved
+    pub fn set_status(
+        env: &Env,
+        provider: &Address,
+        status: InsuranceProviderStatus,
+    ) -> Result<InsuranceProvider, QuickLendXError> {
+        let mut record = Self::get(env, provider).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if record.status == status
+            || (status == InsuranceProviderStatus::Suspended
+                && record.status == InsuranceProviderStatus::Pending)
+        {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let now = env.ledger().timestamp();
+        if status == InsuranceProviderStatus::Approved {
+            record.approved_at = Some(now);
+        }
+        record.status = status;
+        record.updated_at = now;
+        Self::set(env, &record);
+        Ok(record)
+    }
+
+    /// # Errors
+    /// * `StorageKeyNotFound` if the provider is not registered
+    /// * `InvalidAmount` if `capacity` is not positive
+    pub fn set_capacity(
+        env: &Env,
+        provider: &Address,
+        capacity: i128,
+    ) -> Result<InsuranceProvider, QuickLendXError> {
+        let mut record = Self::get(env, provider).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if capacity <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        record.capacity = capacity;
+        record.updated_at = env.ledger().timestamp();
+        Self::set(env, &record);
+        Ok(record)
+    }
+
+    /// Capacity usage of a registered provider
+    pub fn get_capacity(env: &Env, provider: &Address) -> Option<ProviderCapacity> {
+        let record = Self::get(env, provider)?;
+        let outstanding_coverage = InsuranceReserves::active_coverage(env, provider);
+        Some(ProviderCapacity {
+            provider: provider.clone(),
+            capacity: record.capacity,
+            outstanding_coverage,
+            remaining: record.capacity.saturating_sub(outstanding_coverage).max(0),
+        })
+    }
+
+    /// Check that `provider` may write `coverage_amount` more coverage
+    ///
+    /// # Errors
+    /// * `Unauthorized` if the provider is not registered and approved
+    /// * `InsufficientFunds` if the coverage would take it past its capacity
+    pub fn ensure_can_underwrite(
+        env: &Env,
+        provider: &Address,
+        coverage_amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        Self::get(env, provider)
+            .filter(|record| record.status == InsuranceProviderStatus::Approved)
+            .ok_or(QuickLendXError::Unauthorized)?;
+        let usage = Self::get_capacity(env, provider).ok_or(QuickLendXError::Unauthorized)?;
+        if coverage_amount > usage.remaining {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        Ok(())
+    }
+}
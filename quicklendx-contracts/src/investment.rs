@@ -1,4 +1,6 @@
 use crate::errors::QuickLendXError;
+use crate::list_versions::{ListIndex, ListVersions};
+use crate::rounding::{self, Rounding};
 use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
 
 /// Premium rate applied to the covered amount expressed in basis points (1/10,000).
@@ -12,6 +14,9 @@ pub struct InsuranceCoverage {
     pub premium_amount: i128,
     pub coverage_percentage: u32,
     pub active: bool,
+    pub purchased_at: u64,
+    /// Set when the investor cancelled the coverage; cancelled coverage is never claimed
+    pub cancelled_at: Option<u64>,
 }
 
 #[contracttype]
@@ -38,19 +43,23 @@ pub struct Investment {
 
 impl Investment {
     pub fn calculate_premium(amount: i128, coverage_percentage: u32) -> i128 {
+        Self::calculate_premium_at_rate(amount, coverage_percentage, DEFAULT_INSURANCE_PREMIUM_BPS)
+    }
+
+    /// Premium for covering `coverage_percentage` of `amount` at `rate_bps` of the covered
+    /// amount, at least 1 when anything is covered
+    pub fn calculate_premium_at_rate(
+        amount: i128,
+        coverage_percentage: u32,
+        rate_bps: i128,
+    ) -> i128 {
         if amount <= 0 || coverage_percentage == 0 {
             return 0;
         }
 
-        let coverage_amount = amount
-            .saturating_mul(coverage_percentage as i128)
-            .checked_div(100)
-            .unwrap_or(0);
-
-        let premium = coverage_amount
-            .saturating_mul(DEFAULT_INSURANCE_PREMIUM_BPS)
-            .checked_div(10_000)
-            .unwrap_or(0);
+        let coverage_amount =
+            rounding::mul_div(amount, coverage_percentage as i128, 100, Rounding::Floor);
+        let premium = rounding::bps_of(coverage_amount, rate_bps, Rounding::Floor);
 
         if premium == 0 && coverage_amount > 0 {
             1
@@ -64,6 +73,7 @@ impl Investment {
         provider: Address,
         coverage_percentage: u32,
         premium: i128,
+        purchased_at: u64,
     ) -> Result<i128, QuickLendXError> {
         if coverage_percentage == 0 || coverage_percentage > 100 {
             return Err(QuickLendXError::InvalidCoveragePercentage);
@@ -79,11 +89,12 @@ impl Investment {
             }
         }
 
-        let coverage_amount = self
-            .amount
-            .saturating_mul(coverage_percentage as i128)
-            .checked_div(100)
-            .unwrap_or(0);
+        let coverage_amount = rounding::mul_div(
+            self.amount,
+            coverage_percentage as i128,
+            100,
+            Rounding::Floor,
+        );
 
         self.insurance.push_back(InsuranceCoverage {
             provider,
@@ -91,6 +102,8 @@ impl Investment {
             premium_amount: premium,
             coverage_percentage,
             active: true,
+            purchased_at,
+            cancelled_at: None,
         });
 
         Ok(coverage_amount)
@@ -105,6 +118,34 @@ impl Investment {
         false
     }
 
+    /// Provider and amount of the coverage claimed at default, if any
+    pub fn claimed_insurance(&self) -> Option<(Address, i128)> {
+        for coverage in self.insurance.iter() {
+            if !coverage.active && coverage.cancelled_at.is_none() {
+                return Some((coverage.provider, coverage.coverage_amount));
+            }
+        }
+        None
+    }
+
+    /// Deactivate the active coverage as cancelled at `timestamp`
+    ///
+    /// # Returns
+    /// * The cancelled coverage, or `None` when no coverage is active
+    pub fn cancel_insurance(&mut self, timestamp: u64) -> Option<InsuranceCoverage> {
+        for idx in 0..self.insurance.len() {
+            if let Some(mut coverage) = self.insurance.get(idx) {
+                if coverage.active {
+                    coverage.active = false;
+                    coverage.cancelled_at = Some(timestamp);
+                    self.insurance.set(idx, coverage.clone());
+                    return Some(coverage);
+                }
+            }
+        }
+        None
+    }
+
     pub fn process_insurance_claim(&mut self) -> Option<(Address, i128)> {
         let len = self.insurance.len();
         for idx in 0..len {
@@ -164,10 +205,23 @@ impl InvestmentStorage {
 
         // Add to investor index
         Self::add_to_investor_index(env, &investment.investor, &investment.investment_id);
+        crate::watchers::InvoiceWatchers::add(
+            env,
+            &investment.invoice_id,
+            &investment.investor,
+            crate::watchers::WatcherRole::Funder,
+        );
+        crate::tax_lots::TaxLots::open(env, investment);
     }
     pub fn get_investment(env: &Env, investment_id: &BytesN<32>) -> Option<Investment> {
         env.storage().instance().get(investment_id)
     }
+    /// Check whether an investment is stored under `investment_id` without
+    /// loading it. Investment IDs carry the `0x1A4E` prefix.
+    pub fn exists(env: &Env, investment_id: &BytesN<32>) -> bool {
+        let bytes = investment_id.to_array();
+        bytes[0] == 0x1A && bytes[1] == 0x4E && env.storage().instance().has(investment_id)
+    }
     pub fn get_investment_by_invoice(env: &Env, invoice_id: &BytesN<32>) -> Option<Investment> {
         let index_key = Self::invoice_index_key(invoice_id);
         let investment_id: Option<BytesN<32>> = env.storage().instance().get(&index_key);
@@ -177,6 +231,11 @@ impl InvestmentStorage {
         env.storage()
             .instance()
             .set(&investment.investment_id, investment);
+        crate::insurance::InsuranceReserves::sync_coverage(env, investment);
+        ListVersions::bump(
+            env,
+            &[ListIndex::InvestorInvestments(investment.investor.clone())],
+        );
 
         env.storage().instance().set(
             &Self::invoice_index_key(&investment.invoice_id),
@@ -212,6 +271,32 @@ impl InvestmentStorage {
         if !exists {
             investments.push_back(investment_id.clone());
             env.storage().instance().set(&key, &investments);
+            ListVersions::bump(env, &[ListIndex::InvestorInvestments(investor.clone())]);
+        }
+    }
+}
+
+/// Invariant hooks for property tests and simulations
+#[cfg(any(test, feature = "testutils"))]
+impl InvestmentStorage {
+    /// Active investments in `invoice_id` held by any of `investors`
+    pub fn count_active_for_invoice(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        investors: &Vec<Address>,
+    ) -> u32 {
+        let mut active = 0;
+        for investor in investors.iter() {
+            for investment_id in Self::get_investments_by_investor(env, &investor).iter() {
+                if let Some(investment) = Self::get_investment(env, &investment_id) {
+                    if investment.invoice_id == *invoice_id
+                        && investment.status == InvestmentStatus::Active
+                    {
+                        active += 1;
+                    }
+                }
+            }
         }
+        active
     }
 }
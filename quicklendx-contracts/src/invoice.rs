@@ -2,6 +2,8 @@ use core::cmp::{max, min};
 use soroban_sdk::{contracttype, symbol_short, vec, Address, BytesN, Env, String, Vec};
 
 use crate::errors::QuickLendXError;
+use crate::list_versions::{ListIndex, ListVersions};
+use crate::state_machine::{InvoiceStateMachine, TransitionRole};
 
 const DEFAULT_INVOICE_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60; // 7 days default grace period
 
@@ -16,6 +18,7 @@ pub enum InvoiceStatus {
     Defaulted, // Invoice payment is overdue/defaulted
     Cancelled, // Invoice has been cancelled by the business owner
     Refunded,  // Invoice has been refunded (prevents multiple refunds/releases)
+    Expired,   // Bidding closed without the invoice being funded
 }
 
 /// Dispute status enumeration
@@ -69,6 +72,34 @@ pub struct InvoiceRating {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LineItemRecord(pub String, pub i128, pub i128, pub i128);
 
+/// Named form of a line item: description, quantity, unit price and subtotal
+#[contracttype]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceLineItem {
+    pub description: String,
+    pub quantity: i128,
+    pub unit_price: i128,
+    /// Must equal `quantity * unit_price`
+    pub subtotal: i128,
+}
+
+impl From<LineItemRecord> for InvoiceLineItem {
+    fn from(record: LineItemRecord) -> Self {
+        Self {
+            description: record.0,
+            quantity: record.1,
+            unit_price: record.2,
+            subtotal: record.3,
+        }
+    }
+}
+
+impl From<InvoiceLineItem> for LineItemRecord {
+    fn from(item: InvoiceLineItem) -> Self {
+        LineItemRecord(item.description, item.quantity, item.unit_price, item.subtotal)
+    }
+}
+
 /// Metadata associated with an invoice
 #[contracttype]
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -85,6 +116,7 @@ pub struct InvoiceMetadata {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PaymentRecord {
     pub amount: i128,           // Amount paid in this transaction
+    pub payer: Address,         // Address the payment was made from
     pub timestamp: u64,         // When the payment was recorded
     pub transaction_id: String, // External transaction reference
 }
@@ -119,6 +151,14 @@ pub struct Invoice {
     pub dispute: Dispute,                    // Dispute details if any
     pub total_paid: i128,                    // Aggregate amount paid towards the invoice
     pub payment_history: Vec<PaymentRecord>, // History of partial payments
+    pub instant_funding_price: Option<i128>, // Buy-it-now price; bids at or above it fund at once
+    pub bidding_deadline: Option<u64>,       // When `finalize_bidding` may accept the best bid
+    pub default_probability_bps: Option<u32>, // Estimated default probability, set at verification
+    pub funding_deadline: Option<u64>,        // After this, an unfunded invoice may be expired
+    pub relisted_from: Option<BytesN<32>>,    // Cancelled/expired invoice this one relists
+    pub invoice_number: Option<String>,       // Business-assigned number, unique per business
+    pub boosted_until: Option<u64>,           // Featured in the marketplace until this time
+    pub required_insurance_pct: Option<u32>,  // Coverage % a bid must attach, set at verification
 }
 
 // Use the main error enum from errors.rs
@@ -182,6 +222,14 @@ impl Invoice {
             },
             total_paid: 0,
             payment_history: vec![env],
+            instant_funding_price: None,
+            bidding_deadline: None,
+            default_probability_bps: None,
+            funding_deadline: None,
+            relisted_from: None,
+            invoice_number: None,
+            boosted_until: None,
+            required_insurance_pct: None,
         };
 
         // Log invoice creation
@@ -207,11 +255,24 @@ impl Invoice {
         BytesN::from_array(env, &id_bytes)
     }
 
+    /// Check whether `id` has the shape of an invoice ID: a timestamp (whose
+    /// top byte is zero) followed by zero padding after the counter. Bid,
+    /// investment and other entity IDs start with a non-zero type prefix.
+    pub fn is_invoice_id(id: &BytesN<32>) -> bool {
+        let bytes = id.to_array();
+        bytes[0] == 0 && bytes[16..].iter().all(|b| *b == 0)
+    }
+
     /// Check if invoice is available for funding
     pub fn is_available_for_funding(&self) -> bool {
         self.status == InvoiceStatus::Verified && self.funded_amount == 0
     }
 
+    /// Check if the invoice is featured in the marketplace at `current_timestamp`
+    pub fn is_boosted(&self, current_timestamp: u64) -> bool {
+        self.boosted_until.is_some_and(|until| current_timestamp < until)
+    }
+
     pub const DEFAULT_GRACE_PERIOD: u64 = DEFAULT_INVOICE_GRACE_PERIOD;
 
     /// Check if invoice is overdue
@@ -239,18 +300,27 @@ impl Invoice {
             return Ok(false);
         }
 
+        // A frozen invoice cannot default until the admin lifts the freeze
+        if crate::invoice_freeze::InvoiceFreezes::is_frozen(env, &self.id) {
+            return Ok(false);
+        }
         crate::defaults::handle_default(env, &self.id)?;
         Ok(true)
     }
 
     /// Mark invoice as funded with audit logging
+    ///
+    /// # Errors
+    /// * `InvalidStatus` or `Unauthorized` if `role` may not fund the invoice in its status
     pub fn mark_as_funded(
         &mut self,
         env: &Env,
         investor: Address,
         funded_amount: i128,
         timestamp: u64,
-    ) {
+        role: TransitionRole,
+    ) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(&self.status, &InvoiceStatus::Funded, role)?;
         let old_status = self.status.clone();
         self.status = InvoiceStatus::Funded;
         self.funded_amount = funded_amount;
@@ -266,20 +336,35 @@ impl Invoice {
             self.status.clone(),
         );
         log_invoice_funded(env, self.id.clone(), investor, funded_amount);
+        Ok(())
     }
 
     /// Mark invoice as paid with audit logging
-    pub fn mark_as_paid(&mut self, env: &Env, actor: Address, timestamp: u64) {
+    pub fn mark_as_paid(
+        &mut self,
+        env: &Env,
+        actor: Address,
+        timestamp: u64,
+        role: TransitionRole,
+    ) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(&self.status, &InvoiceStatus::Paid, role)?;
         let old_status = self.status.clone();
         self.status = InvoiceStatus::Paid;
         self.settled_at = Some(timestamp);
 
         // Log status change
         log_invoice_status_change(env, self.id.clone(), actor, old_status, self.status.clone());
+        Ok(())
     }
 
     /// Mark invoice as refunded with audit logging
-    pub fn mark_as_refunded(&mut self, env: &Env, actor: Address) {
+    pub fn mark_as_refunded(
+        &mut self,
+        env: &Env,
+        actor: Address,
+        role: TransitionRole,
+    ) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(&self.status, &InvoiceStatus::Refunded, role)?;
         let old_status = self.status.clone();
         self.status = InvoiceStatus::Refunded;
 
@@ -292,12 +377,14 @@ impl Invoice {
             self.status.clone(),
         );
         log_invoice_refunded(env, self.id.clone(), actor);
+        Ok(())
     }
 
     /// Add a payment record and update totals
     pub fn record_payment(
         &mut self,
         env: &Env,
+        payer: &Address,
         amount: i128,
         transaction_id: String,
     ) -> Result<u32, QuickLendXError> {
@@ -307,6 +394,7 @@ impl Invoice {
 
         let record = PaymentRecord {
             amount,
+            payer: payer.clone(),
             timestamp: env.ledger().timestamp(),
             transaction_id,
         };
@@ -369,26 +457,71 @@ impl Invoice {
         }
     }
 
-    /// Verify the invoice with audit logging
-    pub fn verify(&mut self, env: &Env, actor: Address) {
+    /// Verify the invoice with audit logging (admin or invoice verifiers)
+    pub fn verify(&mut self, env: &Env, actor: Address) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(
+            &self.status,
+            &InvoiceStatus::Verified,
+            TransitionRole::Admin,
+        )?;
         let old_status = self.status.clone();
         self.status = InvoiceStatus::Verified;
+        self.default_probability_bps = Some(crate::risk::estimate_default_probability(env, self));
+        self.required_insurance_pct =
+            crate::required_insurance::InsuranceRequirements::coverage_for(env, &self.business);
+        crate::verification::VerificationStaleness::record_verified(env, &self.id);
 
         // Log status change
         log_invoice_status_change(env, self.id.clone(), actor, old_status, self.status.clone());
+        Ok(())
     }
 
     /// Mark invoice as defaulted
-    pub fn mark_as_defaulted(&mut self) {
+    pub fn mark_as_defaulted(&mut self, role: TransitionRole) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(&self.status, &InvoiceStatus::Defaulted, role)?;
         self.status = InvoiceStatus::Defaulted;
+        Ok(())
+    }
+
+    /// Mark the invoice as expired after bidding or its funding window closed without funding
+    pub fn mark_as_expired(&mut self, env: &Env, actor: Address) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(
+            &self.status,
+            &InvoiceStatus::Expired,
+            TransitionRole::Keeper,
+        )?;
+
+        let old_status = self.status.clone();
+        self.status = InvoiceStatus::Expired;
+
+        // Log status change
+        log_invoice_status_change(env, self.id.clone(), actor, old_status, self.status.clone());
+        Ok(())
+    }
+
+    /// Send a verified invoice back to Pending for re-verification once its verification is stale
+    pub fn revert_to_pending(&mut self, env: &Env, actor: Address) -> Result<(), QuickLendXError> {
+        InvoiceStateMachine::ensure_transition(
+            &self.status,
+            &InvoiceStatus::Pending,
+            TransitionRole::Keeper,
+        )?;
+
+        let old_status = self.status.clone();
+        self.status = InvoiceStatus::Pending;
+
+        // Log status change
+        log_invoice_status_change(env, self.id.clone(), actor, old_status, self.status.clone());
+        Ok(())
     }
 
     /// Cancel the invoice (only if Pending or Verified, not Funded)
     pub fn cancel(&mut self, env: &Env, actor: Address) -> Result<(), QuickLendXError> {
-        // Can only cancel if Pending or Verified (not yet funded)
-        if self.status != InvoiceStatus::Pending && self.status != InvoiceStatus::Verified {
-            return Err(QuickLendXError::InvalidStatus);
-        }
+        InvoiceStateMachine::ensure_transition(
+            &self.status,
+            &InvoiceStatus::Cancelled,
+            TransitionRole::Business,
+        )?;
 
         let old_status = self.status.clone();
         self.status = InvoiceStatus::Cancelled;
@@ -490,16 +623,13 @@ impl Invoice {
     /// Add a tag to the invoice
     pub fn add_tag(
         &mut self,
-        _env: &Env,
+        env: &Env,
         tag: String,
     ) -> Result<(), crate::errors::QuickLendXError> {
-        // Validate tag length (1-50 characters)
-        if tag.len() < 1 || tag.len() > 50 {
-            return Err(crate::errors::QuickLendXError::InvalidTag);
-        }
-
-        // Check tag limit (max 10 tags per invoice)
-        if self.tags.len() >= 10 {
+        // Validate the tag and the tag limit against the content policy
+        let policy = crate::protocol_limits::ContentPolicy::get(env);
+        policy.check_tag(&tag)?;
+        if self.tags.len() >= policy.max_tags_per_invoice {
             return Err(crate::errors::QuickLendXError::TagLimitExceeded);
         }
 
@@ -655,6 +785,13 @@ impl InvoiceStorage {
         for tag in invoice.tags.iter() {
             Self::add_tag_index(env, &tag, &invoice.id);
         }
+
+        crate::watchers::InvoiceWatchers::add(
+            env,
+            &invoice.id,
+            &invoice.business,
+            crate::watchers::WatcherRole::Business,
+        );
     }
 
     /// Get an invoice by ID
@@ -662,9 +799,54 @@ impl InvoiceStorage {
         env.storage().instance().get(invoice_id)
     }
 
-    /// Update an invoice
+    /// Check whether an invoice is stored under `invoice_id` without loading it
+    pub fn exists(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        Invoice::is_invoice_id(invoice_id) && env.storage().instance().has(invoice_id)
+    }
+
+    /// Update an invoice, keeping the running aging totals in step
     pub fn update_invoice(env: &Env, invoice: &Invoice) {
         env.storage().instance().set(&invoice.id, invoice);
+        crate::aging::AgingTotals::sync(env, invoice);
+    }
+
+    /// Record that `invoice_id` was relisted as `relisted_id`
+    pub fn set_relisted_as(env: &Env, invoice_id: &BytesN<32>, relisted_id: &BytesN<32>) {
+        let key = (symbol_short!("relisted"), invoice_id.clone());
+        env.storage().instance().set(&key, relisted_id);
+    }
+
+    /// Get the invoice that `invoice_id` was relisted as, if any
+    pub fn get_relisted_as(env: &Env, invoice_id: &BytesN<32>) -> Option<BytesN<32>> {
+        let key = (symbol_short!("relisted"), invoice_id.clone());
+        env.storage().instance().get(&key)
+    }
+
+    /// Get the invoice a business has numbered `invoice_number`, if any
+    pub fn get_invoice_by_number(
+        env: &Env,
+        business: &Address,
+        invoice_number: &String,
+    ) -> Option<BytesN<32>> {
+        let key = (symbol_short!("inv_num"), business.clone(), invoice_number.clone());
+        env.storage().instance().get(&key)
+    }
+
+    /// Point a business's invoice number at `invoice_id`
+    pub fn set_invoice_number_index(
+        env: &Env,
+        business: &Address,
+        invoice_number: &String,
+        invoice_id: &BytesN<32>,
+    ) {
+        let key = (symbol_short!("inv_num"), business.clone(), invoice_number.clone());
+        env.storage().instance().set(&key, invoice_id);
+    }
+
+    /// Free a business's invoice number for reuse
+    pub fn remove_invoice_number_index(env: &Env, business: &Address, invoice_number: &String) {
+        let key = (symbol_short!("inv_num"), business.clone(), invoice_number.clone());
+        env.storage().instance().remove(&key);
     }
 
     /// Get all invoices for a business
@@ -686,6 +868,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted => symbol_short!("default"),
             InvoiceStatus::Cancelled => symbol_short!("canceld"),
             InvoiceStatus::Refunded => symbol_short!("refundd"),
+            InvoiceStatus::Expired => symbol_short!("expired"),
         };
         env.storage()
             .instance()
@@ -699,6 +882,7 @@ impl InvoiceStorage {
         let mut invoices = Self::get_business_invoices(env, business);
         invoices.push_back(invoice_id.clone());
         env.storage().instance().set(&key, &invoices);
+        ListVersions::bump(env, &[ListIndex::BusinessInvoices(business.clone())]);
     }
 
     /// Add invoice to status invoices list
@@ -711,6 +895,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted => symbol_short!("default"),
             InvoiceStatus::Cancelled => symbol_short!("canceld"),
             InvoiceStatus::Refunded => symbol_short!("refundd"),
+            InvoiceStatus::Expired => symbol_short!("expired"),
         };
         let mut invoices = env
             .storage()
@@ -719,6 +904,7 @@ impl InvoiceStorage {
             .unwrap_or_else(|| Vec::new(env));
         invoices.push_back(invoice_id.clone());
         env.storage().instance().set(&key, &invoices);
+        ListVersions::bump(env, &[ListIndex::InvoicesByStatus(status.clone())]);
     }
 
     /// Remove invoice from status invoices list
@@ -731,6 +917,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted => symbol_short!("default"),
             InvoiceStatus::Cancelled => symbol_short!("canceld"),
             InvoiceStatus::Refunded => symbol_short!("refundd"),
+            InvoiceStatus::Expired => symbol_short!("expired"),
         };
         let invoices = Self::get_invoices_by_status(env, status);
 
@@ -743,6 +930,7 @@ impl InvoiceStorage {
         }
 
         env.storage().instance().set(&key, &new_invoices);
+        ListVersions::bump(env, &[ListIndex::InvoicesByStatus(status.clone())]);
     }
 
     /// Get invoices with ratings above a threshold
@@ -817,6 +1005,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted,
             InvoiceStatus::Cancelled,
             InvoiceStatus::Refunded,
+            InvoiceStatus::Expired,
         ];
 
         for status in all_statuses.iter() {
@@ -866,6 +1055,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted,
             InvoiceStatus::Cancelled,
             InvoiceStatus::Refunded,
+            InvoiceStatus::Expired,
         ];
 
         for status in all_statuses.iter() {
@@ -895,6 +1085,7 @@ impl InvoiceStorage {
             InvoiceStatus::Defaulted,
             InvoiceStatus::Cancelled,
             InvoiceStatus::Refunded,
+            InvoiceStatus::Expired,
         ];
 
         // Start with candidates from the first tag
@@ -1030,3 +1221,64 @@ impl InvoiceStorage {
             .unwrap_or_else(|| Vec::new(env))
     }
 }
+
+/// Invariant hooks for property tests and simulations
+#[cfg(any(test, feature = "testutils"))]
+impl InvoiceStorage {
+    const ALL_STATUSES: [InvoiceStatus; 8] = [
+        InvoiceStatus::Pending,
+        InvoiceStatus::Verified,
+        InvoiceStatus::Funded,
+        InvoiceStatus::Paid,
+        InvoiceStatus::Defaulted,
+        InvoiceStatus::Cancelled,
+        InvoiceStatus::Refunded,
+        InvoiceStatus::Expired,
+    ];
+
+    /// Check the storage invariants of one invoice, returning the first violation:
+    /// - it is listed exactly once in its own status index and in no other
+    /// - it is listed exactly once in its business's index
+    /// - amounts are non-negative and funding fields match the status
+    pub fn check_invariants(env: &Env, invoice_id: &BytesN<32>) -> Result<(), &'static str> {
+        let invoice = Self::get_invoice(env, invoice_id).ok_or("invoice missing")?;
+
+        for status in Self::ALL_STATUSES.iter() {
+            let listed = Self::get_invoices_by_status(env, status)
+                .iter()
+                .filter(|id| id == invoice_id)
+                .count();
+            if *status == invoice.status && listed != 1 {
+                return Err("invoice not listed exactly once under its status");
+            }
+            if *status != invoice.status && listed != 0 {
+                return Err("invoice listed under a status it does not have");
+            }
+        }
+        let in_business_index = Self::get_business_invoices(env, &invoice.business)
+            .iter()
+            .filter(|id| id == invoice_id)
+            .count();
+        if in_business_index != 1 {
+            return Err("invoice not listed exactly once in its business index");
+        }
+
+        if invoice.amount <= 0 || invoice.funded_amount < 0 || invoice.total_paid < 0 {
+            return Err("negative invoice amount");
+        }
+        match invoice.status {
+            InvoiceStatus::Pending | InvoiceStatus::Verified => {
+                if invoice.investor.is_some() || invoice.funded_amount != 0 {
+                    return Err("unfunded invoice has an investor");
+                }
+            }
+            InvoiceStatus::Funded => {
+                if invoice.investor.is_none() || invoice.funded_amount <= 0 {
+                    return Err("funded invoice has no investor");
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
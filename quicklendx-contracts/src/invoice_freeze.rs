@@ -0,0 +1,126 @@
+//! Administrative freezes on individual invoices.
+//!
+//! The admin can freeze an invoice pending a compliance investigation. Unlike a dispute, which
+//! the invoice's parties raise and which only holds the escrow, a freeze blocks every state change
+//! and fund movement on the invoice (bids, funding, payments, escrow releases and refunds,
+//! defaults, disputes and edits) until the admin lifts it. Both the freeze and its removal are
+//! written to the audit trail and notified to the business and the investor.
+
+use crate::audit::{log_operation, AuditOperation};
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_frozen, emit_invoice_unfrozen};
+use crate::invoice::InvoiceStorage;
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::protocol_limits::ContentPolicy;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol};
+
+const INVOICE_FREEZE_KEY: Symbol = symbol_short!("frz_inv");
+
+/// An active freeze on an invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceFreeze {
+    pub invoice_id: BytesN<32>,
+    pub reason: String,
+    pub frozen_by: Address,
+    pub frozen_at: u64,
+}
+
+pub struct InvoiceFreezes;
+
+impl InvoiceFreezes {
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Option<InvoiceFreeze> {
+        env.storage()
+            .instance()
+            .get(&(INVOICE_FREEZE_KEY, invoice_id.clone()))
+    }
+
+    pub fn is_frozen(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .has(&(INVOICE_FREEZE_KEY, invoice_id.clone()))
+    }
+
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice is frozen
+    pub fn ensure_not_frozen(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        if Self::is_frozen(env, invoice_id) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Freeze an invoice; the caller has checked that `admin` is the admin
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `OperationNotAllowed` if it is already frozen
+    /// * `InvalidDescription` if `reason` is empty or rejected by the content policy
+    pub fn freeze(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        admin: &Address,
+        reason: String,
+    ) -> Result<InvoiceFreeze, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        Self::ensure_not_frozen(env, invoice_id)?;
+        ContentPolicy::validate_description(env, &reason)?;
+
+        let freeze = InvoiceFreeze {
+            invoice_id: invoice_id.clone(),
+            reason: reason.clone(),
+            frozen_by: admin.clone(),
+            frozen_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(INVOICE_FREEZE_KEY, invoice_id.clone()), &freeze);
+
+        log_operation(
+            env,
+            invoice_id.clone(),
+            AuditOperation::InvoiceFrozen,
+            admin.clone(),
+            None,
+            Some(String::from_str(env, "Invoice frozen")),
+            None,
+            Some(reason),
+        );
+        emit_invoice_frozen(env, &freeze);
+        NotificationHooks::fire(env, &invoice, NotificationHook::InvoiceFrozen);
+        Ok(freeze)
+    }
+
+    /// Lift an invoice's freeze; the caller has checked that `admin` is the admin
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `StorageKeyNotFound` if it is not frozen
+    pub fn unfreeze(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        admin: &Address,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        let freeze = Self::get(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        env.storage()
+            .instance()
+            .remove(&(INVOICE_FREEZE_KEY, invoice_id.clone()));
+
+        log_operation(
+            env,
+            invoice_id.clone(),
+            AuditOperation::InvoiceUnfrozen,
+            admin.clone(),
+            Some(String::from_str(env, "Invoice frozen")),
+            Some(String::from_str(env, "Invoice unfrozen")),
+            None,
+            Some(freeze.reason),
+        );
+        emit_invoice_unfrozen(env, invoice_id, admin);
+        NotificationHooks::fire(env, &invoice, NotificationHook::InvoiceUnfrozen);
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+//! Invoice message boards: a shared thread for the business and the investors of an invoice.
+//!
+//! Only participants can post: the business and every investor holding an investment in the
+//! invoice. Posts are stored on-chain and readable by anyone, oldest first, so they must not
+//! carry confidential terms. The admin can remove a post; it keeps its place in the thread
+//! with its body cleared.
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_message_posted, emit_invoice_message_removed};
+use crate::investment::InvestmentStorage;
+use crate::invoice::InvoiceStorage;
+use crate::protocol_limits::ContentPolicy;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const MESSAGE_KEY: Symbol = symbol_short!("imsg");
+const MESSAGE_COUNT_KEY: Symbol = symbol_short!("imsg_cnt");
+/// Most posts one invoice's board can hold
+pub const MAX_MESSAGES_PER_INVOICE: u32 = 200;
+/// Most posts returned per page
+pub const MAX_MESSAGE_PAGE: u32 = 50;
+
+/// A post on an invoice's message board
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceMessage {
+    pub invoice_id: BytesN<32>,
+    /// Position in the thread, from 0
+    pub message_id: u32,
+    pub author: Address,
+    /// Empty once the post is removed
+    pub body: String,
+    pub posted_at: u64,
+    pub removed: bool,
+}
+
+pub struct InvoiceMessages;
+
+impl InvoiceMessages {
+    fn message_key(invoice_id: &BytesN<32>, message_id: u32) -> (Symbol, BytesN<32>, u32) {
+        (MESSAGE_KEY, invoice_id.clone(), message_id)
+    }
+
+    pub fn count(env: &Env, invoice_id: &BytesN<32>) -> u32 {
+        env.storage()
+            .instance()
+            .get(&(MESSAGE_COUNT_KEY, invoice_id.clone()))
+            .unwrap_or(0)
+    }
+
+    pub fn get(env: &Env, invoice_id: &BytesN<32>, message_id: u32) -> Option<InvoiceMessage> {
+        env.storage()
+            .instance()
+            .get(&Self::message_key(invoice_id, message_id))
+    }
+
+    /// The business and the investors of an invoice
+    pub fn is_participant(env: &Env, invoice_id: &BytesN<32>, address: &Address) -> bool {
+        InvoiceStorage::get_invoice(env, invoice_id).is_some_and(|invoice| {
+            invoice.business == *address
+                || InvestmentStorage::get_investment_by_invoice(env, invoice_id)
+                    .is_some_and(|investment| investment.investor == *address)
+        })
+    }
+
+    /// Post `body` to the invoice's board; the caller has checked `author`'s authorization
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `Unauthorized` if `author` is not a participant
+    /// * `InvalidDescription` if the body is empty, too long or rejected by the content policy
+    /// * `OperationNotAllowed` if the board is full
+    pub fn post(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        author: &Address,
+        body: String,
+    ) -> Result<InvoiceMessage, QuickLendXError> {
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if !Self::is_participant(env, invoice_id, author) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        ContentPolicy::validate_message(env, &body)?;
+        let message_id = Self::count(env, invoice_id);
+        if message_id >= MAX_MESSAGES_PER_INVOICE {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let message = InvoiceMessage {
+            invoice_id: invoice_id.clone(),
+            message_id,
+            author: author.clone(),
+            body,
+            posted_at: env.ledger().timestamp(),
+            removed: false,
+        };
+        env.storage()
+            .instance()
+            .set(&Self::message_key(invoice_id, message_id), &message);
+        env.storage()
+            .instance()
+            .set(&(MESSAGE_COUNT_KEY, invoice_id.clone()), &(message_id + 1));
+        emit_invoice_message_posted(env, &message);
+        Ok(message)
+    }
+
+    /// Clear a post's body and mark it removed
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the post does not exist
+    /// * `OperationNotAllowed` if it was already removed
+    pub fn remove(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        message_id: u32,
+        moderator: &Address,
+    ) -> Result<InvoiceMessage, QuickLendXError> {
+        let mut message =
+            Self::get(env, invoice_id, message_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if message.removed {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        message.body = String::from_str(env, "");
+        message.removed = true;
+        env.storage()
+            .instance()
+            .set(&Self::message_key(invoice_id, message_id), &message);
+        emit_invoice_message_removed(env, &message, moderator);
+        Ok(message)
+    }
+
+    /// Up to `limit` posts (at most `MAX_MESSAGE_PAGE`) from `offset`, oldest first
+    pub fn page(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<InvoiceMessage> {
+        let mut result = Vec::new(env);
+        let end = offset
+            .saturating_add(limit.min(MAX_MESSAGE_PAGE))
+            .min(Self::count(env, invoice_id));
+        let mut message_id = offset;
+        while message_id < end {
+            if let Some(message) = Self::get(env, invoice_id, message_id) {
+                result.push_back(message);
+            }
+            message_id += 1;
+        }
+        result
+    }
+}
@@ -0,0 +1,185 @@
+//! Reusable invoice templates.
+//!
+//! High-volume uploaders store the fields that rarely change (currency, category, tags,
+//! description and customer metadata) once, then create invoices from a template with only the
+//! amount and due date, keeping call payloads small and inputs consistent.
+
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::invoice::{InvoiceCategory, InvoiceMetadata, LineItemRecord};
+use crate::protocol_limits::ContentPolicy;
+use crate::verification;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const TEMPLATE_COUNTER_KEY: Symbol = symbol_short!("tpl_cnt");
+const BUSINESS_TEMPLATES_KEY: Symbol = symbol_short!("tpl_bus");
+/// Upper bound on templates per business
+pub const MAX_TEMPLATES_PER_BUSINESS: u32 = 20;
+
+/// Customer metadata applied to every invoice created from a template
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplateMetadata {
+    pub customer_name: String,
+    pub customer_address: String,
+    pub tax_id: String,
+    pub notes: String,
+}
+
+impl TemplateMetadata {
+    /// Full invoice metadata with a single line item covering `amount`
+    pub fn to_invoice_metadata(
+        &self,
+        env: &Env,
+        description: &String,
+        amount: i128,
+    ) -> InvoiceMetadata {
+        let mut line_items = Vec::new(env);
+        line_items.push_back(LineItemRecord(description.clone(), 1, amount, amount));
+        InvoiceMetadata {
+            customer_name: self.customer_name.clone(),
+            customer_address: self.customer_address.clone(),
+            tax_id: self.tax_id.clone(),
+            line_items,
+            notes: self.notes.clone(),
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceTemplate {
+    pub template_id: BytesN<32>,
+    pub business: Address,
+    pub name: String,
+    pub currency: Address,
+    pub category: InvoiceCategory,
+    pub tags: Vec<String>,
+    pub description: String,
+    // Metadata defaults are flattened like `Invoice`'s metadata fields
+    pub metadata_customer_name: Option<String>,
+    pub metadata_customer_address: Option<String>,
+    pub metadata_tax_id: Option<String>,
+    pub metadata_notes: Option<String>,
+    pub created_at: u64,
+}
+
+impl InvoiceTemplate {
+    pub fn metadata(&self) -> Option<TemplateMetadata> {
+        Some(TemplateMetadata {
+            customer_name: self.metadata_customer_name.clone()?,
+            customer_address: self.metadata_customer_address.clone()?,
+            tax_id: self.metadata_tax_id.clone()?,
+            notes: self.metadata_notes.clone()?,
+        })
+    }
+}
+
+pub struct InvoiceTemplates;
+
+impl InvoiceTemplates {
+    pub fn get(env: &Env, template_id: &BytesN<32>) -> Option<InvoiceTemplate> {
+        env.storage().instance().get(template_id)
+    }
+
+    pub fn get_business_templates(env: &Env, business: &Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(BUSINESS_TEMPLATES_KEY, business.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn generate_id(env: &Env) -> BytesN<32> {
+        let counter: u32 = env
+            .storage()
+            .instance()
+            .get(&TEMPLATE_COUNTER_KEY)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&TEMPLATE_COUNTER_KEY, &(counter + 1));
+
+        let mut id_bytes = [0u8; 32];
+        id_bytes[0] = 0x54; // 'T' for Template
+        id_bytes[1..9].copy_from_slice(&env.ledger().timestamp().to_be_bytes());
+        id_bytes[9..13].copy_from_slice(&counter.to_be_bytes());
+        BytesN::from_array(env, &id_bytes)
+    }
+
+    /// Store a template for a business.
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the name, description or a metadata field is empty or
+    ///   rejected by the content policy
+    /// * `CurrencyNotWhitelisted`, `InvalidTag`, `TagLimitExceeded`
+    /// * `OperationNotAllowed` if the business already has `MAX_TEMPLATES_PER_BUSINESS`
+    pub fn create(
+        env: &Env,
+        business: &Address,
+        name: String,
+        currency: Address,
+        category: InvoiceCategory,
+        tags: Vec<String>,
+        description: String,
+        metadata: Option<TemplateMetadata>,
+    ) -> Result<InvoiceTemplate, QuickLendXError> {
+        ContentPolicy::validate_description(env, &name)?;
+        ContentPolicy::validate_description(env, &description)?;
+        if let Some(defaults) = &metadata {
+            ContentPolicy::validate_description(env, &defaults.customer_name)?;
+            ContentPolicy::validate_description(env, &defaults.customer_address)?;
+            ContentPolicy::validate_description(env, &defaults.tax_id)?;
+            if defaults.notes.len() > 0 {
+                ContentPolicy::validate_description(env, &defaults.notes)?;
+            }
+        }
+        CurrencyWhitelist::require_allowed_currency(env, &currency)?;
+        verification::validate_invoice_category(&category)?;
+        verification::validate_invoice_tags(env, &tags)?;
+
+        let mut owned = Self::get_business_templates(env, business);
+        if owned.len() >= MAX_TEMPLATES_PER_BUSINESS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let template = InvoiceTemplate {
+            template_id: Self::generate_id(env),
+            business: business.clone(),
+            name,
+            currency,
+            category,
+            tags,
+            description,
+            metadata_customer_name: metadata.as_ref().map(|m| m.customer_name.clone()),
+            metadata_customer_address: metadata.as_ref().map(|m| m.customer_address.clone()),
+            metadata_tax_id: metadata.as_ref().map(|m| m.tax_id.clone()),
+            metadata_notes: metadata.map(|m| m.notes),
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&template.template_id, &template);
+        owned.push_back(template.template_id.clone());
+        env.storage()
+            .instance()
+            .set(&(BUSINESS_TEMPLATES_KEY, business.clone()), &owned);
+        Ok(template)
+    }
+
+    /// Delete a template. Invoices already created from it are unaffected.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the template does not exist
+    pub fn delete(env: &Env, template_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        let template = Self::get(env, template_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        env.storage().instance().remove(template_id);
+
+        let key = (BUSINESS_TEMPLATES_KEY, template.business);
+        let mut owned = Self::get_business_templates(env, &key.1);
+        if let Some(index) = owned.first_index_of(template_id) {
+            owned.remove(index);
+            env.storage().instance().set(&key, &owned);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+//! Keeper operation analytics: records permissionless maintenance calls (overdue checks,
+//! bid cleanups, bidding finalization, stale verification reversions, metrics snapshots) so ops
+//! can verify they are running.
+//!
+//! Calls are kept in a bounded log in instance storage; `get_stats` aggregates the log
+//! over an analytics `TimePeriod`.
+//!
+//! Keepers can also earn rewards for cleaning up expired bids and for settling due invoices
+//! from their debtors' pre-approved funds. Rewards are paid from a per-currency pool funded by a
+//! share of platform fees at settlement.
+
+use crate::analytics::{AnalyticsCalculator, TimePeriod};
+use crate::errors::QuickLendXError;
+use crate::payments::transfer_funds;
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Map, Symbol, Vec};
+
+const KEEPER_LOG_KEY: Symbol = symbol_short!("kpr_log");
+const KEEPER_REWARD_CONFIG_KEY: Symbol = symbol_short!("kpr_rwd");
+const KEEPER_POOL_KEY: Symbol = symbol_short!("kpr_pool");
+const KEEPER_SETTLEMENT_REWARD_KEY: Symbol = symbol_short!("kpr_stl");
+/// Oldest records are dropped beyond this many entries
+const MAX_KEEPER_LOG_LEN: u32 = 200;
+
+/// Kind of maintenance operation performed by a keeper
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeeperOperation {
+    OverdueCheck,
+    BidCleanup,
+    BiddingFinalization,
+    FundingExpiry,
+    MetricsSnapshot,
+    RecurringMint,
+    AutoSettlement,
+    StaleVerification,
+}
+
+/// A single keeper call
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperCallRecord {
+    pub operation: KeeperOperation,
+    /// `None` for permissionless entrypoints that do not identify the caller
+    pub caller: Option<Address>,
+    /// Items acted on (e.g. overdue invoices found, bids expired)
+    pub items_processed: u32,
+    /// Items scanned; the main driver of the call's resource cost
+    pub items_scanned: u32,
+    pub reward_paid: i128,
+    pub timestamp: u64,
+}
+
+/// Call count for one keeper operation
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperOperationCount {
+    pub operation: KeeperOperation,
+    pub calls: u32,
+    pub items_processed: u32,
+}
+
+/// Aggregated keeper activity for a period
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeeperStats {
+    pub period: TimePeriod,
+    pub start_date: u64,
+    pub end_date: u64,
+    pub total_calls: u32,
+    pub calls_by_operation: Vec<KeeperOperationCount>,
+    pub total_items_processed: u32,
+    pub total_items_scanned: u32,
+    pub max_items_scanned: u32,
+    pub total_rewards_paid: i128,
+    pub identified_callers: Vec<Address>,
+    pub last_call_at: Option<u64>,
+}
+
+/// Admin-configured keeper rewards for expired-bid cleanup
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperRewardConfig {
+    /// Reward per expired bid cleaned, in the invoice currency's smallest unit
+    pub reward_per_bid: i128,
+    /// Most a single cleanup call can earn
+    pub max_reward_per_call: i128,
+    /// Share of each platform fee diverted into the reward pool, in basis points
+    pub fee_share_bps: u32,
+}
+
+pub struct KeeperStorage;
+
+impl KeeperStorage {
+    /// Record a keeper call
+    pub fn record_call(
+        env: &Env,
+        operation: KeeperOperation,
+        caller: Option<Address>,
+        items_processed: u32,
+        items_scanned: u32,
+        reward_paid: i128,
+    ) {
+        let mut log = Self::get_log(env);
+        if log.len() >= MAX_KEEPER_LOG_LEN {
+            log.pop_front();
+        }
+        log.push_back(KeeperCallRecord {
+            operation,
+            caller,
+            items_processed,
+            items_scanned,
+            reward_paid,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().instance().set(&KEEPER_LOG_KEY, &log);
+    }
+
+    /// Get the keeper call log, oldest first
+    pub fn get_log(env: &Env) -> Vec<KeeperCallRecord> {
+        env.storage()
+            .instance()
+            .get(&KEEPER_LOG_KEY)
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// Aggregate keeper calls within a period
+    pub fn get_stats(env: &Env, period: TimePeriod) -> KeeperStats {
+        let (start_date, end_date) =
+            AnalyticsCalculator::get_period_dates(env.ledger().timestamp(), period.clone());
+
+        let mut stats = KeeperStats {
+            period,
+            start_date,
+            end_date,
+            total_calls: 0,
+            calls_by_operation: Vec::new(env),
+            total_items_processed: 0,
+            total_items_scanned: 0,
+            max_items_scanned: 0,
+            total_rewards_paid: 0,
+            identified_callers: Vec::new(env),
+            last_call_at: None,
+        };
+
+        for record in Self::get_log(env).iter() {
+            if record.timestamp < start_date || record.timestamp > end_date {
+                continue;
+            }
+            stats.total_calls = stats.total_calls.saturating_add(1);
+            stats.total_items_processed = stats
+                .total_items_processed
+                .saturating_add(record.items_processed);
+            stats.total_items_scanned = stats
+                .total_items_scanned
+                .saturating_add(record.items_scanned);
+            stats.max_items_scanned = stats.max_items_scanned.max(record.items_scanned);
+            stats.total_rewards_paid = stats.total_rewards_paid.saturating_add(record.reward_paid);
+            stats.last_call_at = Some(record.timestamp);
+
+            if let Some(caller) = record.caller.clone() {
+                if !stats.identified_callers.contains(&caller) {
+                    stats.identified_callers.push_back(caller);
+                }
+            }
+
+            let mut found = false;
+            for i in 0..stats.calls_by_operation.len() {
+                let mut count = stats.calls_by_operation.get(i).unwrap();
+                if count.operation == record.operation {
+                    count.calls = count.calls.saturating_add(1);
+                    count.items_processed =
+                        count.items_processed.saturating_add(record.items_processed);
+                    stats.calls_by_operation.set(i, count);
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                stats.calls_by_operation.push_back(KeeperOperationCount {
+                    operation: record.operation.clone(),
+                    calls: 1,
+                    items_processed: record.items_processed,
+                });
+            }
+        }
+
+        stats
+    }
+
+    /// Get the keeper reward configuration; rewards are disabled by default
+    pub fn get_reward_config(env: &Env) -> KeeperRewardConfig {
+        env.storage()
+            .instance()
+            .get(&KEEPER_REWARD_CONFIG_KEY)
+            .unwrap_or(KeeperRewardConfig {
+                reward_per_bid: 0,
+                max_reward_per_call: 0,
+                fee_share_bps: 0,
+            })
+    }
+
+    pub fn set_reward_config(
+        env: &Env,
+        config: &KeeperRewardConfig,
+    ) -> Result<(), QuickLendXError> {
+        if config.reward_per_bid < 0
+            || config.max_reward_per_call < 0
+            || config.fee_share_bps > 10_000
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&KEEPER_REWARD_CONFIG_KEY, config);
+        Ok(())
+    }
+
+    /// Reward per invoice settled through `auto_settle_due`; disabled (0) by default
+    pub fn get_settlement_reward(env: &Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&KEEPER_SETTLEMENT_REWARD_KEY)
+            .unwrap_or(0)
+    }
+
+    pub fn set_settlement_reward(env: &Env, reward: i128) -> Result<(), QuickLendXError> {
+        if reward < 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        env.storage()
+            .instance()
+            .set(&KEEPER_SETTLEMENT_REWARD_KEY, &reward);
+        Ok(())
+    }
+
+    /// Reward pool balances held by the contract, by currency
+    pub fn get_reward_pools(env: &Env) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get(&KEEPER_POOL_KEY)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    pub fn get_reward_pool(env: &Env, currency: &Address) -> i128 {
+        Self::get_reward_pools(env)
+            .get(currency.clone())
+            .unwrap_or(0)
+    }
+
+    fn set_reward_pool(env: &Env, currency: &Address, balance: i128) {
+        let mut pools = Self::get_reward_pools(env);
+        pools.set(currency.clone(), balance);
+        env.storage().instance().set(&KEEPER_POOL_KEY, &pools);
+    }
+
+    /// Divert the configured share of a platform fee from `payer` into the reward pool
+    ///
+    /// # Returns
+    /// * The amount diverted; the caller routes the rest of the fee as usual
+    pub fn fund_from_platform_fee(
+        env: &Env,
+        currency: &Address,
+        payer: &Address,
+        platform_fee: i128,
+    ) -> Result<i128, QuickLendXError> {
+        let share = rounding::bps_of(
+            platform_fee,
+            Self::get_reward_config(env).fee_share_bps as i128,
+            Rounding::Floor,
+        );
+        if share <= 0 {
+            return Ok(0);
+        }
+        transfer_funds(env, currency, payer, &env.current_contract_address(), share)?;
+        Self::set_reward_pool(
+            env,
+            currency,
+            Self::get_reward_pool(env, currency).saturating_add(share),
+        );
+        Ok(share)
+    }
+
+    /// Pay a keeper for cleaning `expired_bids` bids, capped per call and by the pool balance
+    ///
+    /// # Returns
+    /// * The reward paid (0 when rewards are disabled or the pool is empty)
+    pub fn pay_cleanup_reward(
+        env: &Env,
+        keeper: &Address,
+        currency: &Address,
+        expired_bids: u32,
+    ) -> Result<i128, QuickLendXError> {
+        let config = Self::get_reward_config(env);
+        let pool = Self::get_reward_pool(env, currency);
+        let reward = config
+            .reward_per_bid
+            .saturating_mul(expired_bids as i128)
+            .min(config.max_reward_per_call)
+            .min(pool);
+        if reward <= 0 {
+            return Ok(0);
+        }
+        Self::set_reward_pool(env, currency, pool - reward);
+        transfer_funds(
+            env,
+            currency,
+            &env.current_contract_address(),
+            keeper,
+            reward,
+        )?;
+        Ok(reward)
+    }
+
+    /// Pay a keeper the settlement reward for one auto-settled invoice, capped by the pool
+    ///
+    /// # Returns
+    /// * The reward paid (0 when the reward is disabled or the pool is empty)
+    pub fn pay_settlement_reward(
+        env: &Env,
+        keeper: &Address,
+        currency: &Address,
+    ) -> Result<i128, QuickLendXError> {
+        let pool = Self::get_reward_pool(env, currency);
+        let reward = Self::get_settlement_reward(env).min(pool);
+        if reward <= 0 {
+            return Ok(0);
+        }
+        Self::set_reward_pool(env, currency, pool - reward);
+        transfer_funds(
+            env,
+            currency,
+            &env.current_contract_address(),
+            keeper,
+            reward,
+        )?;
+        Ok(reward)
+    }
+}
@@ -2,26 +2,63 @@
 use soroban_sdk::{contract, contractimpl, symbol_short, Address, BytesN, Env, Map, String, Vec};
 
 mod admin;
+mod aging;
 mod analytics;
 mod audit;
 mod backup;
+#[cfg(any(test, feature = "testutils"))]
+pub mod bench;
 mod bid;
+mod bid_analytics;
+mod bid_bond;
+mod boost;
+mod collections;
+mod config_log;
 mod currency;
+mod deal_room;
+mod deposits;
+mod debtor;
 mod defaults;
 mod dispute;
+mod dispute_templates;
+mod documents;
 mod errors;
 mod escrow;
 mod events;
+mod exposure;
 mod fees;
+mod insurance;
+mod insurance_pool;
+mod insurance_registry;
 mod investment;
 mod invoice;
+mod invoice_freeze;
+mod invoice_messages;
+mod invoice_template;
+mod keeper;
+mod lineage;
+mod list_versions;
+mod milestones;
+mod notification_hooks;
 mod notifications;
 mod payments;
+mod payout;
+mod premium_schedule;
 mod profits;
 mod protocol_limits;
+mod reconciliation;
+mod recurring;
+mod referral;
 mod reentrancy;
+mod required_insurance;
+mod risk;
+mod rounding;
 mod settlement;
+mod simulation;
+mod state_machine;
 mod storage;
+mod suitability;
+mod tax_lots;
 #[cfg(test)]
 mod test_admin;
 #[cfg(test)]
@@ -34,37 +71,62 @@ mod test_profit_fee;
 #[cfg(test)]
 mod test_storage;
 mod test_refund;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
 mod verification;
+mod watchers;
 
 #[cfg(test)]
  mod test_invoice_metadata;
 use admin::AdminStorage;
 use bid::{Bid, BidStatus, BidStorage};
+use bid_bond::BidBonds;
+use config_log::{ConfigLog, ConfigParameter, ConfigValue};
+use deal_room::{DealRoom, DealRoomAccess};
+use debtor::{DebtorHistory, DebtorRegistry, InvoiceDebtor};
 use defaults::{
     create_dispute as do_create_dispute, get_dispute_details as do_get_dispute_details,
     get_invoices_by_dispute_status as do_get_invoices_by_dispute_status,
     get_invoices_with_disputes as do_get_invoices_with_disputes,
     handle_default as do_handle_default, mark_invoice_defaulted as do_mark_invoice_defaulted,
-    put_dispute_under_review as do_put_dispute_under_review, resolve_dispute as do_resolve_dispute,
+    put_dispute_under_review as do_put_dispute_under_review,
+    record_default_recovery as do_record_default_recovery, resolve_dispute as do_resolve_dispute,
 };
+use documents::{InvoiceDocument, InvoiceDocuments};
 use errors::QuickLendXError;
 use escrow::{
-    accept_bid_and_fund as do_accept_bid_and_fund, refund_escrow_funds as do_refund_escrow_funds,
+    accept_bid_and_fund as do_accept_bid_and_fund, accept_counter_offer as do_accept_counter_offer,
+    expire_unfunded_invoice as do_expire_unfunded_invoice, finalize_bidding as do_finalize_bidding,
+    fund_invoice_with_bid as do_fund_invoice_with_bid,
+    refund_escrow_funds as do_refund_escrow_funds,
 };
 use events::{
     emit_audit_query, emit_audit_validation, emit_bid_accepted, emit_bid_placed,
     emit_bid_withdrawn, emit_escrow_created, emit_escrow_refunded, emit_escrow_released,
-    emit_insurance_added, emit_insurance_premium_collected, emit_investor_verified,
-    emit_invoice_cancelled, emit_invoice_metadata_cleared, emit_invoice_metadata_updated,
-    emit_invoice_uploaded, emit_invoice_verified,
+    emit_insurance_cancelled, emit_investor_verified, emit_invoice_cancelled,
+    emit_invoice_line_items_updated, emit_invoice_metadata_cleared, emit_invoice_metadata_updated,
+    emit_invoice_number_set, emit_invoice_relisted, emit_invoice_split, emit_invoice_uploaded,
+    emit_invoice_verified,
 };
 use investment::{InsuranceCoverage, Investment, InvestmentStatus, InvestmentStorage};
-use invoice::{DisputeStatus, Invoice, InvoiceMetadata, InvoiceStatus, InvoiceStorage};
-use payments::{create_escrow, refund_escrow, release_escrow, EscrowStorage};
-use profits::{calculate_profit as do_calculate_profit, PlatformFee, PlatformFeeConfig};
+use invoice::{
+    DisputeStatus, Invoice, InvoiceMetadata, InvoiceStatus, InvoiceStorage, PaymentRecord,
+};
+use invoice_freeze::{InvoiceFreeze, InvoiceFreezes};
+use invoice_template::{InvoiceTemplate, InvoiceTemplates, TemplateMetadata};
+use milestones::{InvoiceMilestone, InvoiceMilestones, MilestoneSpec};
+use payments::{refund_escrow, release_escrow, EscrowStorage};
+use payout::{PayoutAddressChange, PayoutRouting};
+use profits::calculate_profit as do_calculate_profit;
+use reconciliation::{EscrowReconciliation, StateDigest};
+use recurring::{RecurringSchedule, RecurringSchedules, RecurringTemplate};
 use settlement::{
+    get_amount_due as do_get_amount_due, get_payoff_quote as do_get_payoff_quote,
     process_partial_payment as do_process_partial_payment, settle_invoice as do_settle_invoice,
+    SettlementTimelock,
 };
+use state_machine::{InvoiceStateMachine, StatusTransition, TransitionRole};
+use tax_lots::{TaxLot, TaxLots};
 use verification::{
     calculate_investment_limit, calculate_investor_risk_score, determine_investor_tier,
     get_business_verification_status, get_investor_analytics,
@@ -72,16 +134,20 @@ use verification::{
     reject_investor as do_reject_investor, set_investment_limit,
     submit_investor_kyc as do_submit_investor_kyc, submit_kyc_application,
     update_investor_analytics, validate_bid, validate_investor_investment,
-    validate_invoice_metadata, verify_business, verify_investor as do_verify_investor,
-    verify_invoice_data, BusinessVerificationStatus, BusinessVerificationStorage,
-    InvestorRiskLevel, InvestorTier, InvestorVerification, InvestorVerificationStorage,
+    validate_invoice_metadata, validate_line_items, verify_business,
+    verify_investor as do_verify_investor, verify_invoice_data, BatchVerificationResult,
+    BusinessVerificationStatus, BusinessVerificationStorage, InvestorRiskLevel, InvestorTier,
+    InvestorVerification, InvestorVerificationStorage, InvoiceVerificationOutcome,
+    InvoiceVerificationResult,
 };
+use watchers::{InvoiceWatcher, InvoiceWatchers};
 
-use crate::backup::{Backup, BackupStatus, BackupStorage};
+use crate::backup::{Backup, BackupManifest, BackupStatus, BackupStorage};
 use crate::notifications::{
-    Notification, NotificationDeliveryStatus, NotificationPreferences, NotificationStats,
-    NotificationSystem,
+    DeadLetter, Notification, NotificationDeliveryStatus, NotificationPreferences,
+    NotificationStats, NotificationSystem,
 };
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
 use analytics::{
     AnalyticsCalculator, AnalyticsStorage, BusinessReport, FinancialMetrics, InvestorAnalytics,
     InvestorPerformanceMetrics, InvestorReport, PerformanceMetrics, PlatformMetrics, TimePeriod,
@@ -141,6 +207,43 @@ impl QuickLendXContract {
         AdminStorage::get_admin(&env)
     }
 
+    /// Register a relayer that may run automated processes such as metrics snapshots
+    /// without holding admin powers (admin only).
+    pub fn add_relayer(env: Env, admin: Address, relayer: Address) -> Result<(), QuickLendXError> {
+        if AdminStorage::add_relayer(&env, &admin, &relayer)? {
+            ConfigLog::record(
+                &env,
+                ConfigParameter::Relayer(relayer),
+                ConfigValue::Bool(false),
+                ConfigValue::Bool(true),
+                &admin,
+            );
+        }
+        Ok(())
+    }
+
+    /// Remove a registered relayer (admin only).
+    pub fn remove_relayer(
+        env: Env,
+        admin: Address,
+        relayer: Address,
+    ) -> Result<(), QuickLendXError> {
+        AdminStorage::remove_relayer(&env, &admin, &relayer)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::Relayer(relayer),
+            ConfigValue::Bool(true),
+            ConfigValue::Bool(false),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the registered relayer addresses.
+    pub fn get_relayers(env: Env) -> Vec<Address> {
+        AdminStorage::get_relayers(&env)
+    }
+
     /// Add a token address to the currency whitelist (admin only).
     pub fn add_currency(
         env: Env,
@@ -169,6 +272,37 @@ impl QuickLendXContract {
         currency::CurrencyWhitelist::get_whitelisted_currencies(&env)
     }
 
+    /// Set or remove the conversion-rate oracle for funding `invoice_currency` invoices in
+    /// `funding_currency` (admin only)
+    ///
+    /// The oracle must implement `rate(base, quote) -> i128`, quoting units of `quote` per unit
+    /// of `base` scaled by `RATE_SCALE` (10^7); an AMM can be plugged in through an adapter
+    /// exposing the same function.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidCurrency` if the currencies are the same
+    /// * `CurrencyNotWhitelisted` if either currency is not whitelisted
+    pub fn set_conversion_oracle(
+        env: Env,
+        invoice_currency: Address,
+        funding_currency: Address,
+        oracle: Option<Address>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        currency::CurrencyConversion::set_oracle(&env, &invoice_currency, &funding_currency, oracle)
+    }
+
+    /// Conversion-rate oracle configured for a currency pair
+    pub fn get_conversion_oracle(
+        env: Env,
+        invoice_currency: Address,
+        funding_currency: Address,
+    ) -> Option<Address> {
+        currency::CurrencyConversion::get_oracle(&env, &invoice_currency, &funding_currency)
+    }
+
     // ============================================================================
     // Invoice Management Functions
     // ============================================================================
@@ -211,9 +345,7 @@ impl QuickLendXContract {
             return Err(QuickLendXError::InvoiceDueDateInvalid);
         }
 
-        if description.len() == 0 {
-            return Err(QuickLendXError::InvalidDescription);
-        }
+        protocol_limits::ContentPolicy::validate_description(&env, &description)?;
 
         currency::CurrencyWhitelist::require_allowed_currency(&env, &currency)?;
 
@@ -224,7 +356,7 @@ impl QuickLendXContract {
 
         // Validate category and tags
         verification::validate_invoice_category(&category)?;
-        verification::validate_invoice_tags(&tags)?;
+        verification::validate_invoice_tags(&env, &tags)?;
 
         // Create new invoice
         let invoice = Invoice::new(
@@ -260,6 +392,65 @@ impl QuickLendXContract {
         description: String,
         category: invoice::InvoiceCategory,
         tags: Vec<String>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        Self::upload_invoice_impl(
+            env,
+            business,
+            amount,
+            currency,
+            due_date,
+            description,
+            category,
+            tags,
+            None,
+        )
+    }
+
+    /// Upload an invoice that opts in to buy-it-now funding (business only)
+    ///
+    /// A bid at or above `instant_funding_price` funds the invoice immediately,
+    /// creating escrow in the same call without waiting for business acceptance.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `instant_funding_price` is not positive or exceeds `amount`
+    /// * Any error from `upload_invoice`
+    pub fn upload_invoice_with_buy_now(
+        env: Env,
+        business: Address,
+        amount: i128,
+        currency: Address,
+        due_date: u64,
+        description: String,
+        category: invoice::InvoiceCategory,
+        tags: Vec<String>,
+        instant_funding_price: i128,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        if instant_funding_price <= 0 || instant_funding_price > amount {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        Self::upload_invoice_impl(
+            env,
+            business,
+            amount,
+            currency,
+            due_date,
+            description,
+            category,
+            tags,
+            Some(instant_funding_price),
+        )
+    }
+
+    fn upload_invoice_impl(
+        env: Env,
+        business: Address,
+        amount: i128,
+        currency: Address,
+        due_date: u64,
+        description: String,
+        category: invoice::InvoiceCategory,
+        tags: Vec<String>,
+        instant_funding_price: Option<i128>,
     ) -> Result<BytesN<32>, QuickLendXError> {
         // Only the business can upload their own invoice
         business.require_auth();
@@ -281,10 +472,10 @@ impl QuickLendXContract {
 
         // Validate category and tags
         verification::validate_invoice_category(&category)?;
-        verification::validate_invoice_tags(&tags)?;
+        verification::validate_invoice_tags(&env, &tags)?;
 
         // Create and store invoice
-        let invoice = Invoice::new(
+        let mut invoice = Invoice::new(
             &env,
             business.clone(),
             amount,
@@ -294,16 +485,107 @@ impl QuickLendXContract {
             category,
             tags,
         );
+        invoice.instant_funding_price = instant_funding_price;
         InvoiceStorage::store_invoice(&env, &invoice);
         emit_invoice_uploaded(&env, &invoice);
         audit::log_invoice_uploaded(&env, invoice.id.clone(), business, invoice.amount);
 
         // Send notification
-        let _ = NotificationSystem::notify_invoice_created(&env, &invoice);
+        NotificationHooks::fire(&env, &invoice, NotificationHook::InvoiceCreated);
 
         Ok(invoice.id)
     }
 
+    /// Store a reusable invoice template (business only)
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the name, description or a required metadata field is empty
+    /// * `CurrencyNotWhitelisted`, `InvalidTag`, `TagLimitExceeded`
+    /// * `OperationNotAllowed` if the business already has the maximum number of templates
+    pub fn create_invoice_template(
+        env: Env,
+        business: Address,
+        name: String,
+        currency: Address,
+        category: invoice::InvoiceCategory,
+        tags: Vec<String>,
+        description: String,
+        metadata: Option<TemplateMetadata>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        business.require_auth();
+        InvoiceTemplates::create(
+            &env,
+            &business,
+            name,
+            currency,
+            category,
+            tags,
+            description,
+            metadata,
+        )
+        .map(|template| template.template_id)
+    }
+
+    /// Delete an invoice template (business only)
+    pub fn delete_invoice_template(
+        env: Env,
+        template_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let template =
+            InvoiceTemplates::get(&env, &template_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        template.business.require_auth();
+        InvoiceTemplates::delete(&env, &template_id)
+    }
+
+    pub fn get_invoice_template(env: Env, template_id: BytesN<32>) -> Option<InvoiceTemplate> {
+        InvoiceTemplates::get(&env, &template_id)
+    }
+
+    pub fn get_business_invoice_templates(env: Env, business: Address) -> Vec<BytesN<32>> {
+        InvoiceTemplates::get_business_templates(&env, &business)
+    }
+
+    /// Upload an invoice using a stored template's currency, category, tags, description and
+    /// metadata (business only)
+    ///
+    /// Template metadata is applied with a single line item covering `amount`.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the template does not exist
+    /// * Any error from `upload_invoice`
+    pub fn upload_from_template(
+        env: Env,
+        template_id: BytesN<32>,
+        amount: i128,
+        due_date: u64,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        let template =
+            InvoiceTemplates::get(&env, &template_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let defaults = template.metadata();
+        let invoice_id = Self::upload_invoice_impl(
+            env.clone(),
+            template.business,
+            amount,
+            template.currency,
+            due_date,
+            template.description.clone(),
+            template.category,
+            template.tags,
+            None,
+        )?;
+
+        if let Some(defaults) = defaults {
+            let metadata = defaults.to_invoice_metadata(&env, &template.description, amount);
+            let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+                .ok_or(QuickLendXError::InvoiceNotFound)?;
+            invoice.set_metadata(&env, Some(metadata.clone()));
+            InvoiceStorage::update_invoice(&env, &invoice);
+            InvoiceStorage::add_metadata_indexes(&env, &invoice);
+            emit_invoice_metadata_updated(&env, &invoice, &metadata);
+        }
+        Ok(invoice_id)
+    }
+
     /// Accept a bid and fund the invoice using escrow (transfer in from investor).
     ///
     /// Business must be authorized. Invoice must be Verified and bid Placed.
@@ -313,7 +595,7 @@ impl QuickLendXContract {
     /// * `Ok(BytesN<32>)` - The new escrow ID
     ///
     /// # Errors
-    /// * `InvoiceNotFound`, `StorageKeyNotFound`, `InvalidStatus`, `InvoiceAlreadyFunded`, `InvoiceNotAvailableForFunding`, `Unauthorized`
+    /// * `InvoiceNotFound`, `StorageKeyNotFound`, `BidNotPlaced`, `BidExpired`, `InvoiceAlreadyFunded`, `InvoiceNotAvailableForFunding`, `Unauthorized`
     /// * `OperationNotAllowed` if reentrancy is detected
     pub fn accept_bid_and_fund(
         env: Env,
@@ -323,1527 +605,5088 @@ impl QuickLendXContract {
         reentrancy::with_payment_guard(&env, || do_accept_bid_and_fund(&env, &invoice_id, &bid_id))
     }
 
-    /// Verify an invoice (admin or automated process)
-    pub fn verify_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
-
+    /// Set the bidding deadline for an invoice (business only)
+    ///
+    /// After the deadline, anyone may call `finalize_bidding` to accept the best bid.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidTimestamp` if the deadline is not in the future or is after the due date
+    pub fn set_bidding_deadline(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bidding_deadline: u64,
+    ) -> Result<(), QuickLendXError> {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
-        // Only allow verification if pending
-        if invoice.status != InvoiceStatus::Pending {
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        invoice.business.require_auth();
+
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
             return Err(QuickLendXError::InvalidStatus);
         }
-
-        // Remove from pending status list
-        // Remove from old status list (Pending)
-        InvoiceStorage::remove_from_status_invoices(&env, &InvoiceStatus::Pending, &invoice_id);
-
-        invoice.verify(&env, admin.clone());
-        InvoiceStorage::update_invoice(&env, &invoice);
-
-        // Add to verified status list
-        // Add to new status list (Verified)
-        InvoiceStorage::add_to_status_invoices(&env, &InvoiceStatus::Verified, &invoice_id);
-
-        emit_invoice_verified(&env, &invoice);
-        audit::log_invoice_verified(&env, invoice_id.clone(), admin);
-
-        // Send notification
-        let _ = NotificationSystem::notify_invoice_verified(&env, &invoice);
-
-        // If invoice is funded (has escrow), release escrow funds to business
-        if invoice.status == InvoiceStatus::Funded {
-            Self::release_escrow_funds(env.clone(), invoice_id)?;
+        if bidding_deadline <= env.ledger().timestamp() || bidding_deadline > invoice.due_date {
+            return Err(QuickLendXError::InvalidTimestamp);
         }
 
-        Ok(())
-    }
-
-    /// Cancel an invoice (business only, before funding)
-    pub fn cancel_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-
-        // Only the business owner can cancel their own invoice
-        invoice.business.require_auth();
-
-        // Remove from old status list
-        InvoiceStorage::remove_from_status_invoices(&env, &invoice.status, &invoice_id);
-
-        // Cancel the invoice (only works if Pending or Verified)
-        invoice.cancel(&env, invoice.business.clone())?;
-
-        // Update storage
+        invoice.bidding_deadline = Some(bidding_deadline);
         InvoiceStorage::update_invoice(&env, &invoice);
-
-        // Add to cancelled status list
-        InvoiceStorage::add_to_status_invoices(&env, &InvoiceStatus::Cancelled, &invoice_id);
-
-        // Emit event
-        emit_invoice_cancelled(&env, &invoice);
-        audit::log_invoice_cancelled(&env, invoice_id, invoice.business.clone());
-
-        // Send notification (optional - could notify interested investors)
-        let _ = NotificationSystem::notify_invoice_status_changed(
-            &env,
-            &invoice,
-            &InvoiceStatus::Pending, // Could be Pending or Verified
-            &InvoiceStatus::Cancelled,
-        );
-
         Ok(())
     }
 
-    /// Get an invoice by ID.
+    /// Close bidding after the invoice's bidding deadline (keeper-callable)
+    ///
+    /// Accepts the top-ranked bid via the escrow path, or expires the invoice if no bid
+    /// can fund it. Protected by reentrancy guard.
     ///
     /// # Returns
-    /// * `Ok(Invoice)` - The invoice data
-    /// * `Err(InvoiceNotFound)` if the ID does not exist
-    pub fn get_invoice(env: Env, invoice_id: BytesN<32>) -> Result<Invoice, QuickLendXError> {
-        InvoiceStorage::get_invoice(&env, &invoice_id).ok_or(QuickLendXError::InvoiceNotFound)
-    }
-
-    /// Get all invoices for a business
-    pub fn get_invoice_by_business(env: Env, business: Address) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_business_invoices(&env, &business)
-    }
-
-    /// Get all invoices for a specific business
-    pub fn get_business_invoices(env: Env, business: Address) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_business_invoices(&env, &business)
+    /// * `Ok(Some(bid_id))` - The accepted bid
+    /// * `Ok(None)` - No valid bids; the invoice is now `Expired`
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Verified
+    /// * `OperationNotAllowed` if no deadline is set or it has not passed
+    pub fn finalize_bidding(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<Option<BytesN<32>>, QuickLendXError> {
+        let accepted =
+            reentrancy::with_payment_guard(&env, || do_finalize_bidding(&env, &invoice_id))?;
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::BiddingFinalization,
+            None,
+            1,
+            1,
+            0,
+        );
+        Ok(accepted)
     }
 
-    /// Update structured metadata for an invoice
-    pub fn update_invoice_metadata(
+    /// Set the funding deadline for an invoice (business only)
+    ///
+    /// If no bid has been accepted by the deadline, anyone may call `expire_unfunded_invoice`.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidTimestamp` if the deadline is not in the future or is after the due date
+    pub fn set_funding_deadline(
         env: Env,
         invoice_id: BytesN<32>,
-        metadata: InvoiceMetadata,
+        funding_deadline: u64,
     ) -> Result<(), QuickLendXError> {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
-
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
         invoice.business.require_auth();
-        validate_invoice_metadata(&metadata, invoice.amount)?;
 
-        if let Some(existing) = invoice.metadata() {
-            InvoiceStorage::remove_metadata_indexes(&env, &existing, &invoice.id);
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if funding_deadline <= env.ledger().timestamp() || funding_deadline > invoice.due_date {
+            return Err(QuickLendXError::InvalidTimestamp);
         }
 
-        invoice.set_metadata(&env, Some(metadata.clone()));
+        invoice.funding_deadline = Some(funding_deadline);
         InvoiceStorage::update_invoice(&env, &invoice);
-        InvoiceStorage::add_metadata_indexes(&env, &invoice);
-
-        emit_invoice_metadata_updated(&env, &invoice, &metadata);
         Ok(())
     }
 
-    /// Clear metadata attached to an invoice
-    pub fn clear_invoice_metadata(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
+    /// Expire an invoice left unfunded past its funding deadline (keeper-callable)
+    ///
+    /// Moves the invoice to `Expired`, expires its open bids and notifies the business.
+    ///
+    /// # Returns
+    /// * `Ok(bids_expired)` - The number of open bids expired
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if no funding deadline is set or it has not passed
+    pub fn expire_unfunded_invoice(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<u32, QuickLendXError> {
+        let scanned = BidStorage::get_bids_for_invoice(&env, &invoice_id).len();
+        let expired =
+            reentrancy::with_payment_guard(&env, || do_expire_unfunded_invoice(&env, &invoice_id))?;
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::FundingExpiry,
+            None,
+            expired,
+            scanned,
+            0,
+        );
+        Ok(expired)
+    }
 
-        invoice.business.require_auth();
+    /// Verify an invoice (admin or automated process)
+    ///
+    /// Invoices at or above the sign-off policy threshold (see `set_invoice_signoff_policy`)
+    /// only record the admin's sign-off and stay Pending until enough distinct verifiers
+    /// have signed off. No sign-off is accepted inside the invoice's cooling-off window (see
+    /// `set_verification_cooling_off`). Verification never releases escrowed funds; see
+    /// `approve_release` and `release_escrow_funds`.
+    pub fn verify_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        Self::sign_off_and_verify(&env, &invoice_id, &admin, true).map(|_| ())
+    }
 
-        if let Some(existing) = invoice.metadata() {
-            InvoiceStorage::remove_metadata_indexes(&env, &existing, &invoice.id);
-            invoice.set_metadata(&env, None);
-            InvoiceStorage::update_invoice(&env, &invoice);
-            emit_invoice_metadata_cleared(&env, &invoice);
+    /// Verify an invoice inside its cooling-off window (emergency override)
+    ///
+    /// Needs both the admin and `approver`, a registered invoice verifier other than the
+    /// admin, to authorize the call. Only the cooling-off window is waived: the admin's
+    /// sign-off is recorded as usual and high-value invoices still need their other sign-offs.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if the invoice was verified
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `Unauthorized` if `approver` is the admin or not a registered invoice verifier
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending
+    /// * `OperationNotAllowed` if the admin already signed off
+    pub fn emergency_verify_invoice(
+        env: Env,
+        invoice_id: BytesN<32>,
+        approver: Address,
+    ) -> Result<bool, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        approver.require_auth();
+        if approver == admin
+            || !verification::InvoiceSignoffs::get_verifiers(&env).contains(&approver)
+        {
+            return Err(QuickLendXError::Unauthorized);
         }
+        let verified = Self::sign_off_and_verify(&env, &invoice_id, &admin, false)?;
+        events::emit_verification_override(&env, &invoice_id, &admin, &approver);
+        Ok(verified)
+    }
 
+    /// Set the minimum time between an invoice's upload and its first sign-off; 0 disables
+    /// the cooling-off window (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidTimestamp` if `seconds` exceeds `MAX_VERIFICATION_COOLING_OFF` (7 days)
+    pub fn set_verification_cooling_off(env: Env, seconds: u64) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = verification::VerificationCoolingOff::get_window(&env);
+        verification::VerificationCoolingOff::set_window(&env, seconds)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::VerificationCoolingOff,
+            ConfigValue::U64(old),
+            ConfigValue::U64(seconds),
+            &admin,
+        );
         Ok(())
     }
 
-    /// Get invoices indexed by customer name
-    pub fn get_invoices_by_customer(env: Env, customer_name: String) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_customer(&env, &customer_name)
+    /// Get the verification cooling-off window in seconds (0 when disabled)
+    pub fn get_verification_cooling_off(env: Env) -> u64 {
+        verification::VerificationCoolingOff::get_window(&env)
     }
 
-    /// Get invoices indexed by tax id
-    pub fn get_invoices_by_tax_id(env: Env, tax_id: String) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_tax_id(&env, &tax_id)
+    /// Set how long an invoice's verification stays valid while it waits for funding; 0
+    /// disables the window (admin only)
+    ///
+    /// Once it lapses, `revert_stale_verifications` sends the invoice back to Pending.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidTimestamp` if `seconds` exceeds `MAX_VERIFICATION_STALENESS` (365 days)
+    pub fn set_verification_staleness(env: Env, seconds: u64) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        verification::VerificationStaleness::set_window(&env, seconds)
     }
 
-    /// Get all invoices by status
-    pub fn get_invoices_by_status(env: Env, status: InvoiceStatus) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_status(&env, &status)
+    /// Get the verification staleness window in seconds (0 when disabled)
+    pub fn get_verification_staleness(env: Env) -> u64 {
+        verification::VerificationStaleness::get_window(&env)
     }
 
-    /// Get all available invoices (verified and not funded)
-    pub fn get_available_invoices(env: Env) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Verified)
+    /// Get when a verified invoice's verification goes stale; `None` if the invoice is not
+    /// Verified or the staleness window is disabled
+    pub fn get_verification_stale_at(env: Env, invoice_id: BytesN<32>) -> Option<u64> {
+        InvoiceStorage::get_invoice(&env, &invoice_id)
+            .and_then(|invoice| verification::VerificationStaleness::stale_at(&env, &invoice))
     }
 
-    /// Update invoice status (admin function)
-    pub fn update_invoice_status(
+    /// Send Verified invoices whose verification is stale back to Pending (keeper-callable)
+    ///
+    /// Reverts up to `limit` unfrozen invoices verified longer than the staleness window ago,
+    /// clears their sign-offs and notifies their businesses. They must be verified again
+    /// before they can be funded.
+    ///
+    /// # Returns
+    /// * The invoices sent back to Pending
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if `limit` exceeds `MAX_STALE_REVERSIONS` (20)
+    pub fn revert_stale_verifications(
+        env: Env,
+        limit: u32,
+    ) -> Result<Vec<BytesN<32>>, QuickLendXError> {
+        let (reverted, scanned) = verification::VerificationStaleness::revert_stale(&env, limit)?;
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::StaleVerification,
+            None,
+            reverted.len(),
+            scanned,
+            0,
+        );
+        Ok(reverted)
+    }
+
+    /// Sign off on a pending invoice as the admin or a registered invoice verifier
+    ///
+    /// The invoice is verified once its checklist has the required number of distinct
+    /// sign-offs (one for invoices below the policy threshold).
+    ///
+    /// # Returns
+    /// * `Ok(true)` if this sign-off verified the invoice
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending
+    /// * `InvalidTimestamp` if the invoice is inside its cooling-off window
+    /// * `Unauthorized` if `verifier` is neither the admin nor a registered verifier
+    /// * `OperationNotAllowed` if `verifier` already signed off
+    pub fn sign_off_invoice(
         env: Env,
+        verifier: Address,
         invoice_id: BytesN<32>,
-        new_status: InvoiceStatus,
+    ) -> Result<bool, QuickLendXError> {
+        verifier.require_auth();
+        Self::sign_off_and_verify(&env, &invoice_id, &verifier, true)
+    }
+
+    /// Get the verification checklist of a high-value invoice, if any sign-off was recorded
+    pub fn get_verification_checklist(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<verification::VerificationChecklist> {
+        verification::InvoiceSignoffs::get_checklist(&env, &invoice_id)
+    }
+
+    /// Set or clear the multi-sign-off policy for high-value invoices (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the threshold is not positive or `required_signoffs` is outside
+    ///   1..=5
+    pub fn set_invoice_signoff_policy(
+        env: Env,
+        policy: Option<verification::InvoiceSignoffPolicy>,
     ) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let (old_threshold, old_required) =
+            ConfigValue::from_signoff_policy(verification::InvoiceSignoffs::get_policy(&env));
+        verification::InvoiceSignoffs::set_policy(&env, policy.clone())?;
+        let (new_threshold, new_required) = ConfigValue::from_signoff_policy(policy);
+        ConfigLog::record(
+            &env,
+            ConfigParameter::InvoiceSignoffThreshold,
+            old_threshold,
+            new_threshold,
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::InvoiceSignoffsRequired,
+            old_required,
+            new_required,
+            &admin,
+        );
+        Ok(())
+    }
 
-        // Remove from old status list
-        InvoiceStorage::remove_from_status_invoices(&env, &invoice.status, &invoice_id);
+    /// Get the multi-sign-off policy for high-value invoices, if set
+    pub fn get_invoice_signoff_policy(env: Env) -> Option<verification::InvoiceSignoffPolicy> {
+        verification::InvoiceSignoffs::get_policy(&env)
+    }
 
-        // Update status
-        match new_status {
-            InvoiceStatus::Verified => invoice.verify(&env, invoice.business.clone()),
-            InvoiceStatus::Paid => {
-                invoice.mark_as_paid(&env, invoice.business.clone(), env.ledger().timestamp())
-            }
-            InvoiceStatus::Defaulted => invoice.mark_as_defaulted(),
-            InvoiceStatus::Funded => {
-                // For testing purposes - normally funding happens via accept_bid
-                invoice.mark_as_funded(
-                    &env,
-                    invoice.business.clone(),
-                    invoice.amount,
-                    env.ledger().timestamp(),
-                );
-            }
-            _ => return Err(QuickLendXError::InvalidStatus),
+    /// Register or remove an invoice verifier who may sign off on invoices (admin only)
+    pub fn set_invoice_verifier(
+        env: Env,
+        verifier: Address,
+        enabled: bool,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        if verification::InvoiceSignoffs::set_verifier(&env, &verifier, enabled) {
+            ConfigLog::record(
+                &env,
+                ConfigParameter::InvoiceVerifier(verifier),
+                ConfigValue::Bool(!enabled),
+                ConfigValue::Bool(enabled),
+                &admin,
+            );
         }
+        Ok(())
+    }
 
-        // Store updated invoice
-        InvoiceStorage::update_invoice(&env, &invoice);
+    /// Get the registered invoice verifiers
+    pub fn get_invoice_verifiers(env: Env) -> Vec<Address> {
+        verification::InvoiceSignoffs::get_verifiers(&env)
+    }
 
-        // Add to new status list
-        InvoiceStorage::add_to_status_invoices(&env, &invoice.status, &invoice_id);
+    /// Record a sign-off and verify the invoice once its checklist is complete
+    fn sign_off_and_verify(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        signer: &Address,
+        enforce_cooling_off: bool,
+    ) -> Result<bool, QuickLendXError> {
+        let mut invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
+        // Only allow verification if pending
+        if invoice.status != InvoiceStatus::Pending {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if enforce_cooling_off {
+            verification::VerificationCoolingOff::check(env, &invoice)?;
+        }
+        if !verification::InvoiceSignoffs::record_signoff(env, &invoice, signer)? {
+            return Ok(false);
+        }
 
-        // Emit event
-        env.events().publish(
-            (symbol_short!("updated"),),
-            (invoice_id, new_status.clone()),
+        // Remove from old status list (Pending)
+        InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Pending, invoice_id);
+
+        invoice.verify(env, signer.clone())?;
+        InvoiceStorage::update_invoice(env, &invoice);
+
+        // Add to new status list (Verified)
+        InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Verified, invoice_id);
+
+        emit_invoice_verified(env, &invoice);
+        audit::log_invoice_verified(env, invoice_id.clone(), signer.clone());
+
+        // Send notification
+        NotificationHooks::fire(
+            env,
+            &invoice,
+            NotificationHook::StatusChanged(InvoiceStatus::Pending, InvoiceStatus::Verified),
         );
 
-        // Send notifications based on status change
-        match new_status {
-            InvoiceStatus::Verified => {
-                let _ = NotificationSystem::notify_invoice_verified(&env, &invoice);
-            }
-            InvoiceStatus::Paid => {
-                let _ = NotificationSystem::notify_payment_received(&env, &invoice, invoice.amount);
-            }
-            InvoiceStatus::Defaulted => {
-                let _ = NotificationSystem::notify_invoice_defaulted(&env, &invoice);
-            }
-            _ => {}
+        Ok(true)
+    }
+
+    /// Verify up to `MAX_BATCH_VERIFICATION` invoices in one call (admin only)
+    ///
+    /// Each invoice goes through the same path as `verify_invoice`. Invoices that cannot be
+    /// verified (not found, not Pending, already signed off) are skipped rather than failing
+    /// the batch; each verified invoice emits its own `inv_ver` event.
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `OperationNotAllowed` if more than `MAX_BATCH_VERIFICATION` IDs are given
+    pub fn verify_invoices(
+        env: Env,
+        invoice_ids: Vec<BytesN<32>>,
+    ) -> Result<BatchVerificationResult, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        if invoice_ids.len() > verification::MAX_BATCH_VERIFICATION {
+            return Err(QuickLendXError::OperationNotAllowed);
         }
 
-        Ok(())
+        let mut batch = BatchVerificationResult {
+            results: Vec::new(&env),
+            verified: 0,
+            awaiting_signoffs: 0,
+            skipped: 0,
+        };
+        for invoice_id in invoice_ids.iter() {
+            let outcome = match Self::sign_off_and_verify(&env, &invoice_id, &admin, true) {
+                Ok(true) => {
+                    batch.verified += 1;
+                    InvoiceVerificationOutcome::Verified
+                }
+                Ok(false) => {
+                    batch.awaiting_signoffs += 1;
+                    InvoiceVerificationOutcome::AwaitingSignoffs
+                }
+                Err(error) => {
+                    batch.skipped += 1;
+                    InvoiceVerificationOutcome::from_error(error)
+                }
+            };
+            batch.results.push_back(InvoiceVerificationResult {
+                invoice_id,
+                outcome,
+            });
+        }
+        Ok(batch)
     }
 
-    /// Get invoice count by status
-    pub fn get_invoice_count_by_status(env: Env, status: InvoiceStatus) -> u32 {
-        let invoices = InvoiceStorage::get_invoices_by_status(&env, &status);
-        invoices.len() as u32
-    }
+    /// Cancel an invoice (business only, before funding)
+    pub fn cancel_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
 
-    /// Get total invoice count
-    pub fn get_total_invoice_count(env: Env) -> u32 {
-        let pending = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Pending);
-        let verified = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Verified);
-        let funded = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Funded);
-        let paid = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Paid);
-        let defaulted = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Defaulted);
-        let cancelled = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Cancelled);
+        // Only the business owner can cancel their own invoice
+        invoice.business.require_auth();
 
-        pending + verified + funded + paid + defaulted + cancelled
+        Self::cancel_invoice_record(&env, invoice)?;
+        Ok(())
     }
 
-    /// Get a bid by ID
-    pub fn get_bid(env: Env, bid_id: BytesN<32>) -> Option<Bid> {
-        BidStorage::get_bid(&env, &bid_id)
-    }
+    /// Move a Pending or Verified invoice to Cancelled; the caller has checked the business's
+    /// authorization and that the invoice is not frozen
+    fn cancel_invoice_record(env: &Env, mut invoice: Invoice) -> Result<Invoice, QuickLendXError> {
+        let invoice_id = invoice.id.clone();
 
-    /// Get the highest ranked bid for an invoice
-    pub fn get_best_bid(env: Env, invoice_id: BytesN<32>) -> Option<Bid> {
-        BidStorage::get_best_bid(&env, &invoice_id)
-    }
+        // Remove from old status list
+        let previous_status = invoice.status.clone();
+        InvoiceStorage::remove_from_status_invoices(env, &previous_status, &invoice_id);
 
-    /// Get all bids for an invoice sorted using the platform ranking rules
-    pub fn get_ranked_bids(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
-        BidStorage::rank_bids(&env, &invoice_id)
-    }
+        // Cancel the invoice (only works if Pending or Verified)
+        invoice.cancel(env, invoice.business.clone())?;
 
-    /// Get bids filtered by status
-    pub fn get_bids_by_status(env: Env, invoice_id: BytesN<32>, status: BidStatus) -> Vec<Bid> {
-        BidStorage::get_bids_by_status(&env, &invoice_id, status)
-    }
+        // Update storage
+        InvoiceStorage::update_invoice(env, &invoice);
 
-    /// Get bids filtered by investor
-    pub fn get_bids_by_investor(env: Env, invoice_id: BytesN<32>, investor: Address) -> Vec<Bid> {
-        BidStorage::get_bids_by_investor(&env, &invoice_id, &investor)
-    }
+        // Add to cancelled status list
+        InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Cancelled, &invoice_id);
+        BidBonds::release_losing_bonds(env, &invoice_id, None)?;
 
-    /// Get all bids for an invoice
-    /// Returns a list of all bid records (including expired, withdrawn, etc.)
-    /// Use get_bids_by_status to filter by status if needed
-    pub fn get_bids_for_invoice(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
-        BidStorage::get_bid_records_for_invoice(&env, &invoice_id)
-    }
+        // Emit event
+        emit_invoice_cancelled(env, &invoice);
+        audit::log_invoice_cancelled(env, invoice_id, invoice.business.clone());
 
-    /// Remove bids that have passed their expiration window
-    pub fn cleanup_expired_bids(env: Env, invoice_id: BytesN<32>) -> u32 {
-        BidStorage::cleanup_expired_bids(&env, &invoice_id)
+        NotificationHooks::fire(
+            env,
+            &invoice,
+            NotificationHook::StatusChanged(previous_status, InvoiceStatus::Cancelled),
+        );
+
+        Ok(invoice)
     }
 
-    /// Place a bid on an invoice
+    /// Split a Pending or Verified invoice into smaller invoices (business only)
     ///
-    /// Validates:
-    /// - Invoice exists and is verified
-    /// - Bid amount is positive
-    /// - Investor is authorized and verified
-    /// - Creates and stores the bid
-    pub fn place_bid(
+    /// The original is cancelled and one Pending invoice is created per amount, with the same
+    /// currency, due date, description, category, tags, customer metadata and funding deadline.
+    /// The parts must be verified again before they can be funded. Each part is linked to the
+    /// original in `get_invoice_lineage`.
+    ///
+    /// # Returns
+    /// * `Ok(ids)` - the new invoices, in the order of `amounts`
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidAmount` if there are fewer than 2 or more than `MAX_SPLIT_PARTS` amounts
+    /// * `InvoiceAmountInvalid` if an amount is not positive or they do not sum to the
+    ///   invoice amount
+    /// * `OperationNotAllowed` if the invoice is frozen
+    pub fn split_invoice(
         env: Env,
-        investor: Address,
         invoice_id: BytesN<32>,
-        bid_amount: i128,
-        expected_return: i128,
-    ) -> Result<BytesN<32>, QuickLendXError> {
-        // Authorization check: Only the investor can place their own bid
-        investor.require_auth();
+        amounts: Vec<i128>,
+    ) -> Result<Vec<BytesN<32>>, QuickLendXError> {
+        let original = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        original.business.require_auth();
 
-        // Validate bid amount is positive
-        if bid_amount <= 0 {
+        if amounts.len() < 2 || amounts.len() > lineage::MAX_SPLIT_PARTS {
             return Err(QuickLendXError::InvalidAmount);
         }
-
-        // Validate invoice exists and is verified
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-        if invoice.status != InvoiceStatus::Verified {
-            return Err(QuickLendXError::InvalidStatus);
-        }
-        currency::CurrencyWhitelist::require_allowed_currency(&env, &invoice.currency)?;
-
-        let verification = do_get_investor_verification(&env, &investor)
-            .ok_or(QuickLendXError::BusinessNotVerified)?;
-        match verification.status {
-            BusinessVerificationStatus::Verified => {
-                if bid_amount > verification.investment_limit {
-                    return Err(QuickLendXError::InvalidAmount);
-                }
-            }
-            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
-            BusinessVerificationStatus::Rejected => {
-                return Err(QuickLendXError::BusinessNotVerified)
+        let mut total = 0i128;
+        for amount in amounts.iter() {
+            if amount <= 0 {
+                return Err(QuickLendXError::InvoiceAmountInvalid);
             }
+            total = total.saturating_add(amount);
+        }
+        if total != original.amount {
+            return Err(QuickLendXError::InvoiceAmountInvalid);
         }
 
-        BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        validate_bid(&env, &invoice, bid_amount, expected_return, &investor)?;
-        // Create bid
-        let bid_id = BidStorage::generate_unique_bid_id(&env);
-        let current_timestamp = env.ledger().timestamp();
-        let bid = Bid {
-            bid_id: bid_id.clone(),
-            invoice_id: invoice_id.clone(),
-            investor: investor.clone(),
-            bid_amount,
-            expected_return,
-            timestamp: current_timestamp,
-            status: BidStatus::Placed,
-            expiration_timestamp: Bid::default_expiration(current_timestamp),
-        };
-        BidStorage::store_bid(&env, &bid);
-        // Track bid for this invoice
-        BidStorage::add_bid_to_invoice(&env, &invoice_id, &bid_id);
-
-        // Emit bid placed event
-        emit_bid_placed(&env, &bid);
-        audit::log_bid_placed(&env, invoice_id, investor, bid_amount, bid_id.clone());
+        let original = Self::cancel_invoice_record(&env, original)?;
+        let mut parts = Vec::new(&env);
+        for amount in amounts.iter() {
+            let mut invoice = Invoice::new(
+                &env,
+                original.business.clone(),
+                amount,
+                original.currency.clone(),
+                original.due_date,
+                original.description.clone(),
+                original.category.clone(),
+                original.tags.clone(),
+            );
+            invoice.metadata_customer_name = original.metadata_customer_name.clone();
+            invoice.metadata_customer_address = original.metadata_customer_address.clone();
+            invoice.metadata_tax_id = original.metadata_tax_id.clone();
+            invoice.metadata_notes = original.metadata_notes.clone();
+            invoice.funding_deadline = original.funding_deadline;
+            InvoiceStorage::store_invoice(&env, &invoice);
+            lineage::InvoiceLineage::link(
+                &env,
+                &invoice_id,
+                &invoice.id,
+                lineage::LineageKind::Split,
+            );
 
-        // Send notification for business about new bid
-        let _ = NotificationSystem::notify_bid_received(&env, &invoice, &bid);
+            emit_invoice_uploaded(&env, &invoice);
+            audit::log_invoice_split(
+                &env,
+                invoice_id.clone(),
+                invoice.id.clone(),
+                invoice.business.clone(),
+                amount,
+            );
+            NotificationHooks::fire(&env, &invoice, NotificationHook::InvoiceCreated);
+            parts.push_back(invoice.id);
+        }
+        emit_invoice_split(&env, &invoice_id, &parts);
 
-        Ok(bid_id)
+        Ok(parts)
     }
 
-    /// Accept a bid (business only)
-    pub fn accept_bid(
-        env: Env,
-        invoice_id: BytesN<32>,
-        bid_id: BytesN<32>,
-    ) -> Result<(), QuickLendXError> {
-        reentrancy::with_payment_guard(&env, || {
-            Self::accept_bid_impl(env.clone(), invoice_id.clone(), bid_id.clone())
-        })
+    /// Get the chain of custody of an invoice: the original receivable it descends from and
+    /// every invoice that was relisted or split from it, parents first, with current amounts
+    /// and statuses
+    pub fn get_invoice_lineage(env: Env, invoice_id: BytesN<32>) -> Vec<lineage::LineageEntry> {
+        lineage::InvoiceLineage::lineage(&env, &invoice_id)
     }
 
-    fn accept_bid_impl(
+    /// Relist a Cancelled or Expired invoice as a fresh Pending invoice (business only)
+    ///
+    /// Copies amount, currency, description, category, tags, metadata and buy-it-now price
+    /// into a new record with a new due date and optional funding deadline. The new invoice
+    /// records `relisted_from`; `get_relisted_invoice` maps the original to its relisting.
+    /// An invoice number moves to the relisted invoice.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Cancelled or Expired
+    /// * `OperationNotAllowed` if the invoice was already relisted or split
+    /// * `BusinessNotVerified`, `InvoiceDueDateInvalid`
+    /// * `InvalidTimestamp` if the funding deadline is not in (now, new_due_date]
+    pub fn relist_invoice(
         env: Env,
         invoice_id: BytesN<32>,
-        bid_id: BytesN<32>,
-    ) -> Result<(), QuickLendXError> {
-        BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+        new_due_date: u64,
+        new_funding_deadline: Option<u64>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        let original = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
-        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
-        let invoice_id = bid.invoice_id.clone();
-        BidStorage::cleanup_expired_bids(&env, &invoice_id);
-        let mut bid =
-            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
-        invoice.business.require_auth();
-        if invoice.status != InvoiceStatus::Verified || bid.status != BidStatus::Placed {
-            return Err(QuickLendXError::InvalidStatus);
-        }
+        original.business.require_auth();
 
-        let escrow_id = create_escrow(
+        if original.status != InvoiceStatus::Cancelled && original.status != InvoiceStatus::Expired
+        {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if InvoiceStorage::get_relisted_as(&env, &invoice_id).is_some()
+            || !lineage::InvoiceLineage::get_children(&env, &invoice_id).is_empty()
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if !BusinessVerificationStorage::is_business_verified(&env, &original.business) {
+            return Err(QuickLendXError::BusinessNotVerified);
+        }
+        verify_invoice_data(
             &env,
-            &invoice_id,
-            &bid.investor,
-            &invoice.business,
-            bid.bid_amount,
-            &invoice.currency,
+            &original.business,
+            original.amount,
+            &original.currency,
+            new_due_date,
+            &original.description,
         )?;
-        bid.status = BidStatus::Accepted;
-        BidStorage::update_bid(&env, &bid);
-        invoice.mark_as_funded(
-            &env,
-            bid.investor.clone(),
-            bid.bid_amount,
-            env.ledger().timestamp(),
-        );
-        InvoiceStorage::update_invoice(&env, &invoice);
-        let investment_id = InvestmentStorage::generate_unique_investment_id(&env);
-        let investment = Investment {
-            investment_id: investment_id.clone(),
-            invoice_id: invoice_id.clone(),
-            investor: bid.investor.clone(),
-            amount: bid.bid_amount,
-            funded_at: env.ledger().timestamp(),
-            status: InvestmentStatus::Active,
-            insurance: Vec::new(&env),
-        };
-        InvestmentStorage::store_investment(&env, &investment);
+        currency::CurrencyWhitelist::require_allowed_currency(&env, &original.currency)?;
+        if let Some(deadline) = new_funding_deadline {
+            if deadline <= env.ledger().timestamp() || deadline > new_due_date {
+                return Err(QuickLendXError::InvalidTimestamp);
+            }
+        }
 
-        let escrow = EscrowStorage::get_escrow(&env, &escrow_id)
-            .expect("Escrow should exist after creation");
-        emit_escrow_created(&env, &escrow);
-        emit_bid_accepted(&env, &bid, &invoice_id, &invoice.business);
-        audit::log_bid_accepted(
+        let mut invoice = Invoice::new(
             &env,
-            invoice_id.clone(),
-            invoice.business.clone(),
-            bid.bid_amount,
+            original.business.clone(),
+            original.amount,
+            original.currency.clone(),
+            new_due_date,
+            original.description.clone(),
+            original.category.clone(),
+            original.tags.clone(),
         );
-        audit::log_escrow_created(
+        invoice.metadata_customer_name = original.metadata_customer_name.clone();
+        invoice.metadata_customer_address = original.metadata_customer_address.clone();
+        invoice.metadata_tax_id = original.metadata_tax_id.clone();
+        invoice.metadata_notes = original.metadata_notes.clone();
+        invoice.metadata_line_items = original.metadata_line_items.clone();
+        invoice.instant_funding_price = original.instant_funding_price;
+        invoice.funding_deadline = new_funding_deadline;
+        invoice.relisted_from = Some(invoice_id.clone());
+        invoice.invoice_number = original.invoice_number.clone();
+        InvoiceStorage::store_invoice(&env, &invoice);
+        InvoiceStorage::set_relisted_as(&env, &invoice_id, &invoice.id);
+        lineage::InvoiceLineage::link(
             &env,
-            invoice_id.clone(),
-            bid.investor.clone(),
-            bid.bid_amount,
-            escrow_id,
+            &invoice_id,
+            &invoice.id,
+            lineage::LineageKind::Relisted,
         );
-        let _ = NotificationSystem::notify_bid_accepted(&env, &invoice, &bid);
-        let _ = NotificationSystem::notify_invoice_status_changed(
+        if let Some(number) = &invoice.invoice_number {
+            InvoiceStorage::set_invoice_number_index(&env, &invoice.business, number, &invoice.id);
+        }
+
+        emit_invoice_uploaded(&env, &invoice);
+        emit_invoice_relisted(&env, &invoice_id, &invoice.id);
+        audit::log_invoice_relisted(
             &env,
-            &invoice,
-            &InvoiceStatus::Verified,
-            &InvoiceStatus::Funded,
+            invoice_id,
+            invoice.id.clone(),
+            invoice.business.clone(),
+            invoice.amount,
         );
+        NotificationHooks::fire(&env, &invoice, NotificationHook::InvoiceCreated);
 
-        Ok(())
+        Ok(invoice.id)
     }
 
-    /// Add insurance coverage to an active investment (investor only).
-    ///
-    /// # Arguments
-    /// * `investment_id` - The investment to insure
-    /// * `provider` - Insurance provider address
-    /// * `coverage_percentage` - Coverage as a percentage (e.g. 80 for 80%)
+    /// Get the invoice a Cancelled/Expired invoice was relisted as, if any
+    pub fn get_relisted_invoice(env: Env, invoice_id: BytesN<32>) -> Option<BytesN<32>> {
+        InvoiceStorage::get_relisted_as(&env, &invoice_id)
+    }
+
+    ///== Recurring Invoices ==///
+
+    /// Register a recurring invoice schedule (verified business only)
     ///
-    /// # Returns
-    /// * `Ok(())` on success
+    /// The first invoice can be minted immediately; each later one once `interval_seconds`
+    /// has elapsed, until `total_count` invoices exist or the schedule is cancelled.
     ///
     /// # Errors
-    /// * `StorageKeyNotFound` if investment does not exist
-    /// * `InvalidStatus` if investment is not Active
-    /// * `InvalidAmount` if computed premium is zero
-    pub fn add_investment_insurance(
+    /// * `BusinessNotVerified`, `CurrencyNotWhitelisted`, `InvalidDescription`
+    /// * `InvalidAmount` if the amount is not positive or the count is outside 1..=120
+    /// * `InvalidTimestamp` if the interval is under a day or the payment terms are zero
+    pub fn create_recurring_schedule(
         env: Env,
-        investment_id: BytesN<32>,
-        provider: Address,
-        coverage_percentage: u32,
+        business: Address,
+        template: RecurringTemplate,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        business.require_auth();
+        RecurringSchedules::create(&env, &business, template).map(|s| s.schedule_id)
+    }
+
+    /// Stop a recurring schedule (business only); minted invoices are unaffected
+    pub fn cancel_recurring_schedule(
+        env: Env,
+        schedule_id: BytesN<32>,
     ) -> Result<(), QuickLendXError> {
-        let mut investment = InvestmentStorage::get_investment(&env, &investment_id)
+        let schedule = RecurringSchedules::get(&env, &schedule_id)
             .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        schedule.business.require_auth();
+        RecurringSchedules::cancel(&env, &schedule_id)
+    }
 
-        investment.investor.require_auth();
-
-        if investment.status != InvestmentStatus::Active {
-            return Err(QuickLendXError::InvalidStatus);
-        }
-
-        let premium = Investment::calculate_premium(investment.amount, coverage_percentage);
-        if premium <= 0 {
-            return Err(QuickLendXError::InvalidAmount);
-        }
-
-        let coverage_amount =
-            investment.add_insurance(provider.clone(), coverage_percentage, premium)?;
-
-        InvestmentStorage::update_investment(&env, &investment);
-
-        emit_insurance_added(
+    /// Mint the next invoice of every recurring schedule whose interval has elapsed
+    /// (keeper-callable). Returns the number of invoices minted.
+    pub fn mint_due_recurring_invoices(env: Env) -> u32 {
+        let (minted, scanned) = RecurringSchedules::mint_due(&env);
+        keeper::KeeperStorage::record_call(
             &env,
-            &investment_id,
-            &investment.invoice_id,
-            &investment.investor,
-            &provider,
-            coverage_percentage,
-            coverage_amount,
-            premium,
+            keeper::KeeperOperation::RecurringMint,
+            None,
+            minted,
+            scanned,
+            0,
         );
-        emit_insurance_premium_collected(&env, &investment_id, &provider, premium);
-
-        Ok(())
+        minted
     }
 
-    /// Withdraw a bid (investor only, before acceptance)
-    ///
-    /// Validates:
-    /// - Bid exists
-    /// - Caller is the bid owner (authorization check)
-    /// - Bid is in Placed status (prevents withdrawal of accepted/expired/withdrawn bids)
-    /// - Updates bid status to Withdrawn
-    pub fn withdraw_bid(env: Env, bid_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        // Get bid and validate it exists
-        let mut bid =
-            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+    pub fn get_recurring_schedule(env: Env, schedule_id: BytesN<32>) -> Option<RecurringSchedule> {
+        RecurringSchedules::get(&env, &schedule_id)
+    }
 
-        // Authorization check: Only the investor who owns the bid can withdraw it
-        bid.investor.require_auth();
+    pub fn get_business_recurring_schedules(env: Env, business: Address) -> Vec<BytesN<32>> {
+        RecurringSchedules::get_business_schedules(&env, &business)
+    }
 
-        // Status validation: Only allow withdrawal if bid is placed
-        // Prevents withdrawal of accepted, withdrawn, or expired bids
-        if bid.status != BidStatus::Placed {
-            return Err(QuickLendXError::OperationNotAllowed);
-        }
-        bid.status = BidStatus::Withdrawn;
-        BidStorage::update_bid(&env, &bid);
+    /// Recurring schedule an invoice was minted from, if any
+    pub fn get_invoice_recurring_schedule(env: Env, invoice_id: BytesN<32>) -> Option<BytesN<32>> {
+        RecurringSchedules::get_invoice_schedule(&env, &invoice_id)
+    }
 
-        // Emit bid withdrawn event
-        emit_bid_withdrawn(&env, &bid);
-        audit::log_bid_withdrawn(&env, bid.invoice_id.clone(), bid.investor.clone(), bid_id);
+    ///== Deal Room ==///
 
+    /// Mark an invoice private so investors must request access to its full details
+    pub fn set_invoice_private(
+        env: Env,
+        invoice_id: BytesN<32>,
+        private: bool,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        DealRoom::set_private(&env, &invoice_id, private);
         Ok(())
     }
 
-    /// Settle an invoice (business or automated process)
-    pub fn settle_invoice(
+    pub fn is_invoice_private(env: Env, invoice_id: BytesN<32>) -> bool {
+        DealRoom::is_private(&env, &invoice_id)
+    }
+
+    /// Request access to a private invoice's deal room (verified investors only)
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `NotInvestor` if the investor is not verified
+    /// * `OperationNotAllowed` if the invoice is not private or access was already requested
+    pub fn request_access(
         env: Env,
+        investor: Address,
         invoice_id: BytesN<32>,
-        payment_amount: i128,
-    ) -> Result<(), QuickLendXError> {
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+    ) -> Result<DealRoomAccess, QuickLendXError> {
+        investor.require_auth();
+        DealRoom::request_access(&env, &invoice_id, &investor)
+    }
 
-        let result = reentrancy::with_payment_guard(&env, || {
-            do_settle_invoice(&env, &invoice_id, payment_amount)
-        });
+    /// Grant an investor's pending deal room access request (business only)
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound` if the investor has not requested access
+    /// * `InvalidStatus` if access was already granted
+    pub fn grant_access(
+        env: Env,
+        invoice_id: BytesN<32>,
+        investor: Address,
+    ) -> Result<DealRoomAccess, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        DealRoom::grant_access(&env, &invoice_id, &investor)
+    }
 
-        if result.is_ok() {
-            if let Some(inv) = investment {
-                let is_successful = payment_amount >= inv.amount;
-                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, is_successful);
-            }
-        }
+    /// Who requested and was granted access to an invoice's deal room
+    pub fn get_deal_room_access_log(env: Env, invoice_id: BytesN<32>) -> Vec<DealRoomAccess> {
+        DealRoom::get_invoice_access_log(&env, &invoice_id)
+    }
 
-        result
+    /// Deal rooms an investor has requested or been granted access to
+    pub fn get_investor_deal_room_access(env: Env, investor: Address) -> Vec<DealRoomAccess> {
+        DealRoom::get_investor_access_log(&env, &investor)
     }
 
-    /// Get the investment record for a funded invoice.
+    pub fn has_deal_room_access(env: Env, invoice_id: BytesN<32>, investor: Address) -> bool {
+        DealRoom::has_access(&env, &invoice_id, &investor)
+    }
+
+    /// Post to an invoice's message board (the business or an investor in the invoice)
     ///
-    /// # Returns
-    /// * `Ok(Investment)` - The investment tied to the invoice
-    /// * `Err(StorageKeyNotFound)` if the invoice has no investment
-    pub fn get_invoice_investment(
+    /// Posts are public on-chain; keep confidential terms in the deal room.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `Unauthorized` if `author` is not a participant
+    /// * `InvalidDescription` if `body` is empty, over 500 bytes or rejected by the content policy
+    /// * `OperationNotAllowed` if the board already holds 200 posts
+    pub fn post_invoice_message(
         env: Env,
         invoice_id: BytesN<32>,
-    ) -> Result<Investment, QuickLendXError> {
-        InvestmentStorage::get_investment_by_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)
+        author: Address,
+        body: String,
+    ) -> Result<invoice_messages::InvoiceMessage, QuickLendXError> {
+        author.require_auth();
+        invoice_messages::InvoiceMessages::post(&env, &invoice_id, &author, body)
     }
 
-    /// Get an investment by ID.
+    /// Remove a post from an invoice's message board (admin only)
     ///
-    /// # Returns
-    /// * `Ok(Investment)` - The investment record
-    /// * `Err(StorageKeyNotFound)` if the ID does not exist
-    pub fn get_investment(
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the post does not exist
+    /// * `OperationNotAllowed` if it was already removed
+    pub fn remove_invoice_message(
         env: Env,
-        investment_id: BytesN<32>,
-    ) -> Result<Investment, QuickLendXError> {
-        InvestmentStorage::get_investment(&env, &investment_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)
+        invoice_id: BytesN<32>,
+        message_id: u32,
+    ) -> Result<invoice_messages::InvoiceMessage, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        invoice_messages::InvoiceMessages::remove(&env, &invoice_id, message_id, &admin)
     }
 
-    /// Query insurance coverage for an investment.
-    ///
-    /// # Arguments
-    /// * `investment_id` - The investment to query
-    ///
-    /// # Returns
-    /// * `Ok(Vec<InsuranceCoverage>)` - All insurance records for the investment
-    /// * `Err(StorageKeyNotFound)` if the investment does not exist
+    /// Get an invoice's message board posts, oldest first (at most 50 per page)
+    pub fn get_invoice_messages(
+        env: Env,
+        invoice_id: BytesN<32>,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<invoice_messages::InvoiceMessage> {
+        invoice_messages::InvoiceMessages::page(&env, &invoice_id, offset, limit)
+    }
+
+    /// Get the number of posts on an invoice's message board, removed posts included
+    pub fn get_invoice_message_count(env: Env, invoice_id: BytesN<32>) -> u32 {
+        invoice_messages::InvoiceMessages::count(&env, &invoice_id)
+    }
+
+    ///== Debtor Acknowledgment ==///
+
+    /// Register the customer address that owes an unfunded invoice (business only)
     ///
-    /// # Security Notes
-    /// - Returns all insurance records (active and inactive)
-    /// - No authorization required for queries
-    pub fn query_investment_insurance(
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if the debtor is the business or the invoice was already
+    ///   acknowledged
+    pub fn register_invoice_debtor(
         env: Env,
-        investment_id: BytesN<32>,
-    ) -> Result<Vec<InsuranceCoverage>, QuickLendXError> {
-        let investment = InvestmentStorage::get_investment(&env, &investment_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)?;
-        Ok(investment.insurance)
+        invoice_id: BytesN<32>,
+        debtor: Address,
+    ) -> Result<InvoiceDebtor, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        DebtorRegistry::register(&env, &invoice, &debtor)
     }
 
-    /// Process a partial payment towards an invoice
-    pub fn process_partial_payment(
+    /// Confirm, as the registered debtor, that an invoice is genuine
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound` if no debtor is registered
+    /// * `InvalidStatus` if the invoice is no longer outstanding
+    /// * `OperationNotAllowed` if the invoice was already acknowledged
+    pub fn acknowledge_invoice(
         env: Env,
         invoice_id: BytesN<32>,
-        payment_amount: i128,
-        transaction_id: String,
-    ) -> Result<(), QuickLendXError> {
-        do_process_partial_payment(&env, &invoice_id, payment_amount, transaction_id)
+    ) -> Result<InvoiceDebtor, QuickLendXError> {
+        DebtorRegistry::acknowledge(&env, &invoice_id)
     }
 
-    /// Handle invoice default (admin or automated process)
-    /// This is the internal handler - use mark_invoice_defaulted for public API
-    pub fn handle_default(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        // Get the investment to track investor analytics
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+    pub fn get_invoice_debtor(env: Env, invoice_id: BytesN<32>) -> Option<InvoiceDebtor> {
+        DebtorRegistry::get_invoice_debtor(&env, &invoice_id)
+    }
 
-        let result = do_handle_default(&env, &invoice_id);
+    pub fn is_invoice_acknowledged(env: Env, invoice_id: BytesN<32>) -> bool {
+        DebtorRegistry::is_acknowledged(&env, &invoice_id)
+    }
 
-        // Update investor analytics for failed investment
-        if result.is_ok() {
-            if let Some(inv) = investment {
-                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
-            }
-        }
+    /// Invoices a debtor has been registered against
+    pub fn get_debtor_invoices(env: Env, debtor: Address) -> Vec<BytesN<32>> {
+        DebtorRegistry::get_debtor_invoices(&env, &debtor)
+    }
 
-        result
+    /// Every acknowledged invoice, in acknowledgment order
+    pub fn get_acknowledged_invoices(env: Env) -> Vec<BytesN<32>> {
+        DebtorRegistry::get_acknowledged_invoices(&env)
     }
 
-    /// Mark an invoice as defaulted (admin or automated process)
-    /// Checks due date + grace period before marking as defaulted
-    ///
-    /// # Arguments
-    /// * `invoice_id` - The invoice ID to mark as defaulted
-    /// * `grace_period` - Optional grace period in seconds (defaults to 7 days)
+    /// How often a debtor's invoices were financed, paid on time or defaulted, across all
+    /// businesses that registered it
+    pub fn get_debtor_history(env: Env, debtor: Address) -> DebtorHistory {
+        DebtorRegistry::get_history(&env, &debtor)
+    }
+
+    ///== Invoice Documents ==///
+
+    /// Anchor an off-chain document to an invoice by its content hash (business only)
     ///
-    /// # Returns
-    /// * `Ok(())` if the invoice was successfully marked as defaulted
-    /// * `Err(QuickLendXError)` if the operation fails
-    pub fn mark_invoice_defaulted(
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidDescription` if the type or URI hint is empty or too long
+    /// * `OperationNotAllowed` if the hash is already attached or the document limit is reached
+    pub fn attach_document(
         env: Env,
         invoice_id: BytesN<32>,
-        grace_period: Option<u64>,
-    ) -> Result<(), QuickLendXError> {
-        // Get the investment to track investor analytics
-        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+        doc_hash: BytesN<32>,
+        doc_type: String,
+        uri_hint: String,
+    ) -> Result<InvoiceDocument, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        InvoiceDocuments::attach(&env, &invoice_id, doc_hash, doc_type, uri_hint)
+    }
 
-        let result = do_mark_invoice_defaulted(&env, &invoice_id, grace_period);
+    /// Remove a document from an invoice that has not been funded yet (business only)
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is no longer Pending or Verified
+    /// * `StorageKeyNotFound` if the hash is not attached
+    pub fn remove_document(
+        env: Env,
+        invoice_id: BytesN<32>,
+        doc_hash: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        InvoiceDocuments::remove(&env, &invoice_id, &doc_hash)
+    }
 
-        // Update investor analytics for failed investment
-        if result.is_ok() {
-            if let Some(inv) = investment {
-                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
-            }
-        }
+    /// Documents attached to an invoice, in attachment order
+    pub fn get_invoice_documents(env: Env, invoice_id: BytesN<32>) -> Vec<InvoiceDocument> {
+        InvoiceDocuments::get(&env, &invoice_id)
+    }
 
-        result
+    /// Parties to inform of an invoice's state changes: its business, bidders, funder and
+    /// insurers, in the order they became involved
+    pub fn get_invoice_watchers(env: Env, invoice_id: BytesN<32>) -> Vec<InvoiceWatcher> {
+        InvoiceWatchers::get(&env, &invoice_id)
     }
 
-    /// Calculate profit and platform fee
-    pub fn calculate_profit(
+    /// Payments recorded against an invoice (amount, payer, timestamp, transaction ID), oldest
+    /// first. Their running total is the invoice's `total_paid`.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    pub fn get_payment_history(
         env: Env,
-        investment_amount: i128,
-        payment_amount: i128,
-    ) -> (i128, i128) {
-        do_calculate_profit(&env, investment_amount, payment_amount)
+        invoice_id: BytesN<32>,
+    ) -> Result<Vec<PaymentRecord>, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(invoice.payment_history)
     }
 
-    /// Retrieve the current platform fee configuration
-    pub fn get_platform_fee(env: Env) -> PlatformFeeConfig {
-        PlatformFee::get_config(&env)
+    /// Get an invoice by ID.
+    ///
+    /// # Returns
+    /// * `Ok(Invoice)` - The invoice data
+    /// * `Err(InvoiceNotFound)` if the ID does not exist
+    pub fn get_invoice(env: Env, invoice_id: BytesN<32>) -> Result<Invoice, QuickLendXError> {
+        InvoiceStorage::get_invoice(&env, &invoice_id).ok_or(QuickLendXError::InvoiceNotFound)
     }
 
-    /// Update the platform fee basis points (admin only)
-    pub fn set_platform_fee(env: Env, new_fee_bps: i128) -> Result<(), QuickLendXError> {
-        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        PlatformFee::set_config(&env, &admin, new_fee_bps)?;
-        Ok(())
+    /// Check whether an invoice exists without loading it
+    pub fn invoice_exists(env: Env, invoice_id: BytesN<32>) -> bool {
+        InvoiceStorage::exists(&env, &invoice_id)
     }
 
-    // Rating Functions (from feat-invoice_rating_system)
+    /// Get all invoices for a business
+    pub fn get_invoice_by_business(env: Env, business: Address) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_business_invoices(&env, &business)
+    }
 
-    /// Add a rating to an invoice (investor only)
-    pub fn add_invoice_rating(
+    /// Get all invoices for a specific business
+    pub fn get_business_invoices(env: Env, business: Address) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_business_invoices(&env, &business)
+    }
+
+    /// Update structured metadata for an invoice
+    pub fn update_invoice_metadata(
         env: Env,
         invoice_id: BytesN<32>,
-        rating: u32,
-        feedback: String,
-        rater: Address,
+        metadata: InvoiceMetadata,
     ) -> Result<(), QuickLendXError> {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
 
-        // Only the investor who funded the invoice can rate it
-        rater.require_auth();
+        invoice.business.require_auth();
+        validate_invoice_metadata(&env, &metadata, invoice.amount)?;
 
-        invoice.add_rating(rating, feedback, rater.clone(), env.ledger().timestamp())?;
-        InvoiceStorage::update_invoice(&env, &invoice);
+        if let Some(existing) = invoice.metadata() {
+            InvoiceStorage::remove_metadata_indexes(&env, &existing, &invoice.id);
+        }
 
-        // Emit rating event
-        env.events()
-            .publish((symbol_short!("rated"),), (invoice_id, rating, rater));
+        invoice.set_metadata(&env, Some(metadata.clone()));
+        InvoiceStorage::update_invoice(&env, &invoice);
+        InvoiceStorage::add_metadata_indexes(&env, &invoice);
 
+        emit_invoice_metadata_updated(&env, &invoice, &metadata);
         Ok(())
     }
 
-    /// Get invoices with ratings above a threshold
-    pub fn get_invoices_with_rating_above(env: Env, threshold: u32) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_with_rating_above(&env, threshold)
+    /// Clear metadata attached to an invoice
+    pub fn clear_invoice_metadata(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+
+        invoice.business.require_auth();
+
+        if let Some(existing) = invoice.metadata() {
+            InvoiceStorage::remove_metadata_indexes(&env, &existing, &invoice.id);
+            invoice.set_metadata(&env, None);
+            InvoiceStorage::update_invoice(&env, &invoice);
+            emit_invoice_metadata_cleared(&env, &invoice);
+        }
+
+        Ok(())
     }
 
-    /// Get business invoices with ratings above a threshold
-    pub fn get_business_rated_invoices(
+    /// Replace an invoice's line items (business only)
+    ///
+    /// Line items can only change before funding, so investors diligence a fixed itemization.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidDescription` if the list or a description is empty
+    /// * `InvalidAmount` if a quantity is not positive, a unit price is negative, or a subtotal
+    ///   is not quantity times unit price
+    /// * `InvoiceAmountInvalid` if the subtotals do not sum to the invoice amount
+    /// * `OperationNotAllowed` if there are more than `MAX_LINE_ITEMS` items
+    pub fn set_invoice_line_items(
         env: Env,
-        business: Address,
-        threshold: u32,
-    ) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_business_invoices_with_rating_above(&env, &business, threshold)
-    }
+        invoice_id: BytesN<32>,
+        line_items: Vec<invoice::InvoiceLineItem>,
+    ) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        invoice.business.require_auth();
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
 
-    /// Get count of invoices with ratings
-    pub fn get_invoices_with_ratings_count(env: Env) -> u32 {
-        InvoiceStorage::get_invoices_with_ratings_count(&env)
+        let mut records = Vec::new(&env);
+        for item in line_items.iter() {
+            records.push_back(invoice::LineItemRecord::from(item));
+        }
+        validate_line_items(&env, &records, invoice.amount)?;
+
+        invoice.metadata_line_items = records;
+        InvoiceStorage::update_invoice(&env, &invoice);
+        emit_invoice_line_items_updated(&env, &invoice);
+        Ok(())
     }
 
-    /// Get invoice rating statistics
-    pub fn get_invoice_rating_stats(
+    /// Get an invoice's itemization for due diligence
+    pub fn get_invoice_line_items(
         env: Env,
         invoice_id: BytesN<32>,
-    ) -> Result<(Option<u32>, u32, Option<u32>, Option<u32>), QuickLendXError> {
+    ) -> Result<Vec<invoice::InvoiceLineItem>, QuickLendXError> {
         let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let mut items = Vec::new(&env);
+        for record in invoice.metadata_line_items.iter() {
+            items.push_back(invoice::InvoiceLineItem::from(record));
+        }
+        Ok(items)
+    }
 
-        Ok((
-            invoice.average_rating,
-            invoice.total_ratings,
-            invoice.get_highest_rating(),
-            invoice.get_lowest_rating(),
-        ))
+    /// Get invoices indexed by customer name
+    pub fn get_invoices_by_customer(env: Env, customer_name: String) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_customer(&env, &customer_name)
     }
 
-    // Business KYC/Verification Functions (from main)
+    /// Get invoices indexed by tax id
+    pub fn get_invoices_by_tax_id(env: Env, tax_id: String) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_tax_id(&env, &tax_id)
+    }
 
-    /// Submit KYC application (business only)
-    pub fn submit_kyc_application(
+    /// Assign (or change) an invoice's business-assigned number (business only)
+    ///
+    /// Numbers are unique per business, so integrations can look invoices up with
+    /// `get_invoice_by_number` instead of tracking invoice IDs. Changing a number frees the
+    /// previous one.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidDescription` if the number is empty, too long or rejected by the content policy
+    /// * `OperationNotAllowed` if another invoice of the business already uses the number
+    pub fn set_invoice_number(
         env: Env,
-        business: Address,
-        kyc_data: String,
+        invoice_id: BytesN<32>,
+        invoice_number: String,
     ) -> Result<(), QuickLendXError> {
-        submit_kyc_application(&env, &business, kyc_data)
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        invoice.business.require_auth();
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        protocol_limits::ContentPolicy::validate_invoice_number(&env, &invoice_number)?;
+        if let Some(existing) =
+            InvoiceStorage::get_invoice_by_number(&env, &invoice.business, &invoice_number)
+        {
+            if existing != invoice_id {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            return Ok(());
+        }
+
+        if let Some(previous) = &invoice.invoice_number {
+            InvoiceStorage::remove_invoice_number_index(&env, &invoice.business, previous);
+        }
+        InvoiceStorage::set_invoice_number_index(
+            &env,
+            &invoice.business,
+            &invoice_number,
+            &invoice_id,
+        );
+        invoice.invoice_number = Some(invoice_number.clone());
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        emit_invoice_number_set(&env, &invoice, &invoice_number);
+        Ok(())
     }
 
-    /// Submit investor verification request
-    pub fn submit_investor_kyc(
+    /// Get the invoice a business numbered `invoice_number`, if any
+    pub fn get_invoice_by_number(
         env: Env,
-        investor: Address,
-        kyc_data: String,
-    ) -> Result<(), QuickLendXError> {
-        do_submit_investor_kyc(&env, &investor, kyc_data)
+        business: Address,
+        invoice_number: String,
+    ) -> Option<BytesN<32>> {
+        InvoiceStorage::get_invoice_by_number(&env, &business, &invoice_number)
     }
 
-    /// Verify an investor and set an investment limit
-    pub fn verify_investor(
+    /// Get all invoices by status
+    pub fn get_invoices_by_status(env: Env, status: InvoiceStatus) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_status(&env, &status)
+    }
+
+    /// Get all available invoices (verified and not funded)
+    pub fn get_available_invoices(env: Env) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Verified)
+    }
+
+    /// Update invoice status (admin only)
+    ///
+    /// Limited to the transitions the invoice state machine lets the admin make: verifying a
+    /// Pending invoice, and marking a Funded invoice Paid or Defaulted.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set, `InvoiceNotFound`
+    /// * `InvalidStatus` if the transition is illegal, `Unauthorized` if only another role may
+    ///   make it
+    /// * `OperationNotAllowed` if the invoice is frozen
+    pub fn update_invoice_status(
         env: Env,
-        investor: Address,
-        investment_limit: i128,
+        invoice_id: BytesN<32>,
+        new_status: InvoiceStatus,
     ) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        let verification = do_verify_investor(&env, &admin, &investor, investment_limit)?;
-        emit_investor_verified(&env, &verification);
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        let old_status = invoice.status.clone();
+
+        // Update status; the state machine rejects transitions the admin may not make
+        match new_status {
+            InvoiceStatus::Verified => invoice.verify(&env, admin)?,
+            InvoiceStatus::Paid => invoice.mark_as_paid(
+                &env,
+                admin,
+                env.ledger().timestamp(),
+                TransitionRole::Admin,
+            )?,
+            InvoiceStatus::Defaulted => invoice.mark_as_defaulted(TransitionRole::Admin)?,
+            _ => {
+                InvoiceStateMachine::ensure_transition(
+                    &old_status,
+                    &new_status,
+                    TransitionRole::Admin,
+                )?;
+                // Legal transitions the admin may make all have a branch above
+                return Err(QuickLendXError::InvalidStatus);
+            }
+        }
+
+        // Move between status lists
+        InvoiceStorage::remove_from_status_invoices(&env, &old_status, &invoice_id);
+
+        // Store updated invoice
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        // Add to new status list
+        InvoiceStorage::add_to_status_invoices(&env, &invoice.status, &invoice_id);
+
+        // Emit event
+        env.events().publish(
+            (symbol_short!("updated"),),
+            (invoice_id, new_status.clone()),
+        );
+
+        NotificationHooks::fire(
+            &env,
+            &invoice,
+            NotificationHook::StatusChanged(old_status, new_status),
+        );
+
         Ok(())
     }
 
-    /// Reject an investor verification request
-    pub fn reject_investor(
+    /// Freeze an invoice pending an investigation (admin only)
+    ///
+    /// Until `unfreeze_invoice`, every state change and fund movement on the invoice fails with
+    /// `OperationNotAllowed`. The business and the investor are notified.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set, `InvoiceNotFound`
+    /// * `OperationNotAllowed` if the invoice is already frozen
+    /// * `InvalidDescription` if `reason` is empty or rejected by the content policy
+    pub fn freeze_invoice(
         env: Env,
-        investor: Address,
+        invoice_id: BytesN<32>,
         reason: String,
     ) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        do_reject_investor(&env, &admin, &investor, reason)
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        InvoiceFreezes::freeze(&env, &invoice_id, &admin, reason)?;
+        Ok(())
     }
 
-    /// Get investor verification record if available
-    pub fn get_investor_verification(env: Env, investor: Address) -> Option<InvestorVerification> {
-        do_get_investor_verification(&env, &investor)
+    /// Lift an invoice's freeze (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set, `InvoiceNotFound`
+    /// * `StorageKeyNotFound` if the invoice is not frozen
+    pub fn unfreeze_invoice(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        InvoiceFreezes::unfreeze(&env, &invoice_id, &admin)
     }
 
-    /// Set investment limit for a verified investor (admin only)
-    pub fn set_investment_limit(
-        env: Env,
-        investor: Address,
-        new_limit: i128,
-    ) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        verification::set_investment_limit(&env, &admin, &investor, new_limit)
+    /// Get the freeze on an invoice, if any
+    pub fn get_invoice_freeze(env: Env, invoice_id: BytesN<32>) -> Option<InvoiceFreeze> {
+        InvoiceFreezes::get(&env, &invoice_id)
     }
 
-    /// Verify business (admin only)
-    pub fn verify_business(
+    /// Statuses the invoice may move to next, with the roles allowed to move it there
+    ///
+    /// Empty for terminal statuses (Paid, Defaulted, Cancelled, Refunded, Expired).
+    pub fn get_allowed_transitions(
         env: Env,
-        admin: Address,
-        business: Address,
-    ) -> Result<(), QuickLendXError> {
-        verify_business(&env, &admin, &business)
+        invoice_id: BytesN<32>,
+    ) -> Result<Vec<StatusTransition>, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(InvoiceStateMachine::allowed_transitions(&env, &invoice.status))
     }
 
-    /// Reject business (admin only)
-    pub fn reject_business(
-        env: Env,
-        admin: Address,
-        business: Address,
-        reason: String,
-    ) -> Result<(), QuickLendXError> {
-        reject_business(&env, &admin, &business, reason)
+    /// Get invoice count by status
+    pub fn get_invoice_count_by_status(env: Env, status: InvoiceStatus) -> u32 {
+        let invoices = InvoiceStorage::get_invoices_by_status(&env, &status);
+        invoices.len() as u32
     }
 
-    /// Get business verification status
-    pub fn get_business_verification_status(
-        env: Env,
-        business: Address,
-    ) -> Option<verification::BusinessVerification> {
-        get_business_verification_status(&env, &business)
+    /// Get total invoice count
+    pub fn get_total_invoice_count(env: Env) -> u32 {
+        let pending = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Pending);
+        let verified = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Verified);
+        let funded = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Funded);
+        let paid = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Paid);
+        let defaulted = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Defaulted);
+        let cancelled = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Cancelled);
+        let expired = Self::get_invoice_count_by_status(env.clone(), InvoiceStatus::Expired);
+
+        pending + verified + funded + paid + defaulted + cancelled + expired
     }
 
-    /// Set admin address (initialization function)
-    pub fn set_admin(env: Env, admin: Address) -> Result<(), QuickLendXError> {
-        if let Some(current_admin) = BusinessVerificationStorage::get_admin(&env) {
-            current_admin.require_auth();
-        } else {
-            admin.require_auth();
+    /// Get a bid by ID
+    pub fn get_bid(env: Env, bid_id: BytesN<32>) -> Option<Bid> {
+        BidStorage::get_bid(&env, &bid_id)
+    }
+
+    /// Check whether a bid exists without loading it
+    pub fn bid_exists(env: Env, bid_id: BytesN<32>) -> bool {
+        BidStorage::exists(&env, &bid_id)
+    }
+
+    /// Get the highest ranked bid for an invoice
+    pub fn get_best_bid(env: Env, invoice_id: BytesN<32>) -> Option<Bid> {
+        BidStorage::get_best_bid(&env, &invoice_id)
+    }
+
+    /// Get all bids for an invoice sorted using the platform ranking rules
+    pub fn get_ranked_bids(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
+        BidStorage::rank_bids(&env, &invoice_id)
+    }
+
+    /// Get placed bids ordered by effective APR, lowest (cheapest for the business) first
+    pub fn get_bids_ranked_by_apr(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
+        match InvoiceStorage::get_invoice(&env, &invoice_id) {
+            Some(invoice) => BidStorage::rank_bids_by_apr(&env, &invoice_id, invoice.due_date),
+            None => Vec::new(&env),
         }
-        BusinessVerificationStorage::set_admin(&env, &admin);
-        Ok(())
     }
 
-    /// Get admin address
-    pub fn get_admin(env: Env) -> Option<Address> {
-        BusinessVerificationStorage::get_admin(&env)
+    /// Get a bid's annualized return in basis points, from placement to the invoice due date
+    pub fn get_bid_effective_apr(env: Env, bid_id: BytesN<32>) -> Result<i128, QuickLendXError> {
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let invoice = InvoiceStorage::get_invoice(&env, &bid.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(bid.effective_apr_bps(invoice.due_date))
     }
 
-    /// Get all verified businesses
-    pub fn get_verified_businesses(env: Env) -> Vec<Address> {
-        BusinessVerificationStorage::get_verified_businesses(&env)
+    /// Bid statistics for an invoice: count, min/median discount over all bids, best discount
+    /// still open, time to first bid and the tiers of the bidding investors
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`
+    pub fn get_invoice_bid_analytics(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<bid_analytics::InvoiceBidAnalytics, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(bid_analytics::BidAnalytics::get(&env, &invoice))
     }
 
-    /// Get all pending businesses
-    pub fn get_pending_businesses(env: Env) -> Vec<Address> {
-        BusinessVerificationStorage::get_pending_businesses(&env)
+    pub fn get_bids_by_status(env: Env, invoice_id: BytesN<32>, status: BidStatus) -> Vec<Bid> {
+        BidStorage::get_bids_by_status(&env, &invoice_id, status)
     }
 
-    /// Get all rejected businesses
-    pub fn get_rejected_businesses(env: Env) -> Vec<Address> {
-        BusinessVerificationStorage::get_rejected_businesses(&env)
+    /// Get bids filtered by investor
+    pub fn get_bids_by_investor(env: Env, invoice_id: BytesN<32>, investor: Address) -> Vec<Bid> {
+        BidStorage::get_bids_by_investor(&env, &invoice_id, &investor)
     }
 
-    // ========================================
-    // Enhanced Investor Verification Functions
-    // ========================================
+    /// Recalculate an invoice's default probability estimate from current history
+    ///
+    /// The estimate is set at verification; this refreshes it for open invoices as businesses
+    /// and categories build up repayment history. Callable by anyone.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Verified or Funded
+    pub fn recalculate_default_probability(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<u32, QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Verified && invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let estimate = risk::estimate_default_probability(&env, &invoice);
+        invoice.default_probability_bps = Some(estimate);
+        InvoiceStorage::update_invoice(&env, &invoice);
+        Ok(estimate)
+    }
 
-    /// Get all verified investors
-    pub fn get_verified_investors(env: Env) -> Vec<Address> {
-        InvestorVerificationStorage::get_verified_investors(&env)
+    /// Get a category's historical default rate in basis points, as used by the estimate
+    pub fn get_category_loss_rate(env: Env, category: invoice::InvoiceCategory) -> u32 {
+        risk::category_loss_rate_bps(&env, &category)
     }
 
-    /// Get all pending investors
-    pub fn get_pending_investors(env: Env) -> Vec<Address> {
-        InvestorVerificationStorage::get_pending_investors(&env)
+    /// Get risk metrics over an investor's active investments, for dashboards
+    ///
+    /// Reports the amount-weighted time to due date, concentration by business and category,
+    /// the insured share, and the loss expected on the uninsured amount from each category's
+    /// historical default rate.
+    pub fn get_portfolio_risk(env: Env, investor: Address) -> risk::PortfolioRisk {
+        risk::portfolio_risk(&env, &investor)
     }
 
-    /// Get all rejected investors
-    pub fn get_rejected_investors(env: Env) -> Vec<Address> {
-        InvestorVerificationStorage::get_rejected_investors(&env)
+    /// Get all bids for an invoice
+    /// Returns a list of all bid records (including expired, withdrawn, etc.)
+    /// Use get_bids_by_status to filter by status if needed
+    pub fn get_bids_for_invoice(env: Env, invoice_id: BytesN<32>) -> Vec<Bid> {
+        BidStorage::get_bid_records_for_invoice(&env, &invoice_id)
     }
 
-    /// Get investors by tier
-    pub fn get_investors_by_tier(env: Env, tier: InvestorTier) -> Vec<Address> {
-        InvestorVerificationStorage::get_investors_by_tier(&env, tier)
+    /// Remove bids that have passed their expiration window
+    pub fn cleanup_expired_bids(env: Env, invoice_id: BytesN<32>) -> u32 {
+        let scanned = BidStorage::get_bids_for_invoice(&env, &invoice_id).len();
+        let removed = BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::BidCleanup,
+            None,
+            removed,
+            scanned,
+            0,
+        );
+        removed
     }
 
-    /// Get investors by risk level
-    pub fn get_investors_by_risk_level(env: Env, risk_level: InvestorRiskLevel) -> Vec<Address> {
-        InvestorVerificationStorage::get_investors_by_risk_level(&env, risk_level)
+    /// Remove expired bids and pay the calling keeper from the keeper reward pool
+    ///
+    /// The reward is `reward_per_bid` per expired bid removed, capped by
+    /// `max_reward_per_call` and the pool balance in the invoice currency
+    /// (see `set_keeper_reward_config`). Bids the keeper placed itself are removed but not
+    /// rewarded.
+    ///
+    /// # Returns
+    /// * The reward paid to the keeper
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    pub fn keeper_cleanup_expired_bids(
+        env: Env,
+        keeper: Address,
+        invoice_id: BytesN<32>,
+    ) -> Result<i128, QuickLendXError> {
+        keeper.require_auth();
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let scanned = BidStorage::get_bids_for_invoice(&env, &invoice_id).len();
+        let own_bids = BidStorage::count_expired_bids_by(&env, &invoice_id, &keeper);
+        let removed = BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let reward = reentrancy::with_payment_guard(&env, || {
+            keeper::KeeperStorage::pay_cleanup_reward(
+                &env,
+                &keeper,
+                &invoice.currency,
+                removed - own_bids,
+            )
+        })?;
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::BidCleanup,
+            Some(keeper),
+            removed,
+            scanned,
+            reward,
+        );
+        Ok(reward)
     }
 
-    /// Calculate investor risk score
-    pub fn calculate_investor_risk_score(
+    /// Settle due invoices from their debtors' pre-approved funds (callable by any keeper)
+    ///
+    /// Scans funded invoices past their due date and settles up to `limit` whose registered
+    /// debtor holds and has approved the contract for the payoff amount. The keeper earns the
+    /// settlement reward (see `set_keeper_settlement_reward`) for each invoice settled, paid
+    /// from the keeper reward pool in the invoice currency.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if `limit` exceeds `MAX_AUTO_SETTLEMENTS` (10)
+    pub fn auto_settle_due(
+        env: Env,
+        keeper: Address,
+        limit: u32,
+    ) -> Result<settlement::AutoSettlementResult, QuickLendXError> {
+        keeper.require_auth();
+        let result = reentrancy::with_payment_guard(&env, || {
+            settlement::auto_settle_due(&env, &keeper, limit)
+        })?;
+
+        for entry in result.settlements.iter() {
+            if let Some(inv) = InvestmentStorage::get_investment_by_invoice(&env, &entry.invoice_id)
+            {
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, true);
+            }
+        }
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::AutoSettlement,
+            Some(keeper),
+            result.settlements.len(),
+            result.scanned,
+            result.total_reward,
+        );
+        Ok(result)
+    }
+
+    /// Place a bid on an invoice
+    ///
+    /// Validates:
+    /// - Invoice exists and is verified
+    /// - Bid amount is positive
+    /// - Investor is authorized and verified
+    /// - Creates and stores the bid
+    /// - Locks the bid bond when bonds are enabled (see `set_bid_bond_bps`)
+    /// - Funds the invoice immediately if the bid meets its buy-it-now price
+    ///
+    /// The bid expires after the default bid TTL (7 days).
+    pub fn place_bid(
         env: Env,
         investor: Address,
-        kyc_data: String,
-    ) -> Result<u32, QuickLendXError> {
-        calculate_investor_risk_score(&env, &investor, &kyc_data)
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        Self::place_bid_impl(
+            env,
+            investor,
+            invoice_id,
+            bid_amount,
+            expected_return,
+            None,
+            None,
+            None,
+        )
     }
 
-    /// Determine investor tier
-    pub fn determine_investor_tier(
+    /// Place a bid that expires at an investor-chosen timestamp
+    ///
+    /// Same as `place_bid`, except the expiration must fall within the admin-configured
+    /// bid TTL bounds (see `get_bid_ttl_bounds`), otherwise `InvalidTimestamp` is returned.
+    pub fn place_bid_with_expiration(
         env: Env,
         investor: Address,
-        risk_score: u32,
-    ) -> Result<InvestorTier, QuickLendXError> {
-        determine_investor_tier(&env, &investor, risk_score)
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        expiration_timestamp: u64,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        Self::place_bid_impl(
+            env,
+            investor,
+            invoice_id,
+            bid_amount,
+            expected_return,
+            Some(expiration_timestamp),
+            None,
+            None,
+        )
     }
 
-    /// Calculate investment limit for investor
-    pub fn calculate_investment_limit(
-        _env: Env,
-        tier: InvestorTier,
-        risk_level: InvestorRiskLevel,
-        base_limit: i128,
-    ) -> i128 {
-        calculate_investment_limit(&tier, &risk_level, base_limit)
+    /// Place a bid expressed as a discount off the invoice amount, in basis points
+    ///
+    /// The investor advances the invoice amount less the discount and is repaid the full
+    /// invoice amount; the rate is stored on the bid. Otherwise behaves like `place_bid`.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if the rate is 0 or at least 10,000, or rounds to no discount
+    /// * Any error from `place_bid`
+    pub fn place_bid_at_discount(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        discount_rate_bps: u32,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        if discount_rate_bps == 0 || discount_rate_bps >= 10_000 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let (bid_amount, expected_return) =
+            Bid::discounted_amounts(invoice.amount, discount_rate_bps);
+        Self::place_bid_impl(
+            env,
+            investor,
+            invoice_id,
+            bid_amount,
+            expected_return,
+            None,
+            Some(discount_rate_bps),
+            None,
+        )
+    }
+
+    /// Place a bid to be funded in a different whitelisted token than the invoice currency
+    ///
+    /// The bid stays denominated in the invoice currency. When it is accepted, the advance is
+    /// converted at the rate of the admin-configured oracle for the pair (see
+    /// `set_conversion_oracle`) and escrowed in `funding_currency`; settlement is paid in the
+    /// invoice currency. Bid bonds are not applied to the funding, and are released instead.
+    ///
+    /// # Errors
+    /// * `CurrencyNotWhitelisted` if `funding_currency` is not whitelisted
+    /// * `InvalidCurrency` if the pair has no oracle
+    /// * `InvalidAmount` if the oracle reports a non-positive rate
+    /// * Any error from `place_bid`
+    pub fn place_bid_in_currency(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        funding_currency: Address,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        Self::place_bid_impl(
+            env,
+            investor,
+            invoice_id,
+            bid_amount,
+            expected_return,
+            None,
+            None,
+            Some(funding_currency),
+        )
+    }
+
+    /// Token a bid will be funded in, if it differs from the invoice currency
+    pub fn get_bid_funding_currency(env: Env, bid_id: BytesN<32>) -> Option<Address> {
+        BidStorage::get_funding_currency(&env, &bid_id)
+    }
+
+    fn place_bid_impl(
+        env: Env,
+        investor: Address,
+        invoice_id: BytesN<32>,
+        bid_amount: i128,
+        expected_return: i128,
+        expiration_timestamp: Option<u64>,
+        discount_rate_bps: Option<u32>,
+        funding_currency: Option<Address>,
+    ) -> Result<BytesN<32>, QuickLendXError> {
+        // Authorization check: Only the investor can place their own bid
+        investor.require_auth();
+
+        // Validate bid amount is positive
+        if bid_amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        // Validate invoice exists and is verified
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvoiceNotVerified);
+        }
+        currency::CurrencyWhitelist::require_allowed_currency(&env, &invoice.currency)?;
+        let funding_currency = funding_currency.filter(|currency| *currency != invoice.currency);
+        if let Some(currency) = &funding_currency {
+            // Fails now rather than at funding if the pair cannot be priced
+            currency::CurrencyConversion::convert(&env, bid_amount, &invoice.currency, currency)?;
+        }
+
+        let verification = do_get_investor_verification(&env, &investor)
+            .ok_or(QuickLendXError::BusinessNotVerified)?;
+        match verification.status {
+            BusinessVerificationStatus::Verified => {
+                if bid_amount > verification.investment_limit {
+                    return Err(QuickLendXError::BidExceedsLimit);
+                }
+            }
+            BusinessVerificationStatus::Pending => return Err(QuickLendXError::KYCAlreadyPending),
+            BusinessVerificationStatus::Rejected => {
+                return Err(QuickLendXError::BusinessNotVerified)
+            }
+        }
+
+        suitability::InvestorSuitability::require_current(&env, &investor)?;
+
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        validate_bid(&env, &invoice, bid_amount, expected_return, &investor)?;
+        let current_timestamp = env.ledger().timestamp();
+        let expiration_timestamp = match expiration_timestamp {
+            Some(expiration) => {
+                BidStorage::validate_expiration(&env, current_timestamp, expiration)?;
+                expiration
+            }
+            None => Bid::default_expiration(current_timestamp),
+        };
+        // Create bid
+        let bid_id = BidStorage::generate_unique_bid_id(&env);
+        let bid = Bid {
+            bid_id: bid_id.clone(),
+            invoice_id: invoice_id.clone(),
+            investor: investor.clone(),
+            bid_amount,
+            expected_return,
+            timestamp: current_timestamp,
+            status: BidStatus::Placed,
+            expiration_timestamp,
+            discount_rate_bps,
+        };
+        BidStorage::store_bid(&env, &bid);
+        // Track bid for this invoice
+        BidStorage::add_bid_to_invoice(&env, &invoice_id, &bid_id);
+        if let Some(currency) = &funding_currency {
+            BidStorage::set_funding_currency(&env, &bid_id, currency);
+        }
+        bid_analytics::BidAnalytics::record_bid(&env, &invoice, &bid, verification.tier);
+        // Lock the bid bond, if bonds are enabled
+        BidBonds::lock(&env, &bid, &invoice.currency)?;
+
+        // Emit bid placed event
+        emit_bid_placed(&env, &bid);
+        audit::log_bid_placed(
+            &env,
+            invoice_id.clone(),
+            investor,
+            bid_amount,
+            bid_id.clone(),
+        );
+
+        // Send notification for business about new bid
+        NotificationHooks::fire(&env, &invoice, NotificationHook::BidPlaced(bid.clone()));
+
+        // Buy-it-now: a bid at or above the instant funding price funds immediately, unless the
+        // invoice requires insurance, which the investor must attach first
+        if invoice
+            .instant_funding_price
+            .is_some_and(|price| bid_amount >= price)
+            && required_insurance::InsuranceRequirements::ensure_met(&env, &invoice, &bid_id)
+                .is_ok()
+        {
+            reentrancy::with_payment_guard(&env, || {
+                do_fund_invoice_with_bid(&env, &invoice_id, &bid_id, TransitionRole::Investor)
+            })?;
+        }
+
+        Ok(bid_id)
+    }
+
+    /// Accept a bid (business only)
+    pub fn accept_bid(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            Self::accept_bid_impl(env.clone(), invoice_id.clone(), bid_id.clone())
+        })
+    }
+
+    fn accept_bid_impl(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let invoice_id = bid.invoice_id.clone();
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        invoice.business.require_auth();
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvoiceNotVerified);
+        }
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        escrow::ensure_bid_can_fund(&env, &invoice, &bid)?;
+
+        let escrow_id = escrow::escrow_bid_funds(&env, &invoice, &bid)?;
+        BidBonds::release_losing_bonds(&env, &invoice_id, Some(&bid_id))?;
+        bid.status = BidStatus::Accepted;
+        BidStorage::update_bid(&env, &bid);
+        invoice.mark_as_funded(
+            &env,
+            bid.investor.clone(),
+            bid.bid_amount,
+            env.ledger().timestamp(),
+            TransitionRole::Business,
+        )?;
+        InvoiceStorage::update_invoice(&env, &invoice);
+        InvoiceStorage::remove_from_status_invoices(&env, &InvoiceStatus::Verified, &invoice_id);
+        InvoiceStorage::add_to_status_invoices(&env, &InvoiceStatus::Funded, &invoice_id);
+        let investment_id = InvestmentStorage::generate_unique_investment_id(&env);
+        let mut investment = Investment {
+            investment_id: investment_id.clone(),
+            invoice_id: invoice_id.clone(),
+            investor: bid.investor.clone(),
+            amount: bid.bid_amount,
+            funded_at: env.ledger().timestamp(),
+            status: InvestmentStatus::Active,
+            insurance: Vec::new(&env),
+        };
+        InvestmentStorage::store_investment(&env, &investment);
+        exposure::ExposureLimits::record_investment_source(&env, &investment_id, &bid_id);
+        fees::FeeManager::lock_fee_quote(&env, &invoice_id, &invoice.business, &bid.investor);
+        required_insurance::InsuranceRequirements::buy_at_funding(
+            &env,
+            &invoice,
+            &bid_id,
+            &mut investment,
+        )?;
+
+        let escrow = EscrowStorage::get_escrow(&env, &escrow_id)
+            .expect("Escrow should exist after creation");
+        emit_escrow_created(&env, &escrow);
+        emit_bid_accepted(&env, &bid, &invoice_id, &invoice.business);
+        audit::log_bid_accepted(
+            &env,
+            invoice_id.clone(),
+            invoice.business.clone(),
+            bid.bid_amount,
+        );
+        audit::log_escrow_created(
+            &env,
+            invoice_id.clone(),
+            bid.investor.clone(),
+            bid.bid_amount,
+            escrow_id,
+        );
+        NotificationHooks::fire(&env, &invoice, NotificationHook::InvoiceFunded(bid.clone()));
+
+        Ok(())
+    }
+
+    /// Add insurance coverage to an active investment (investor only).
+    ///
+    /// # Arguments
+    /// * `investment_id` - The investment to insure
+    /// * `provider` - Insurance provider address
+    /// * `coverage_percentage` - Coverage as a percentage (e.g. 80 for 80%)
+    ///
+    /// # Returns
+    /// * `Ok(())` on success
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if investment does not exist
+    /// * `InvalidStatus` if investment is not Active
+    /// * `InvalidAmount` if the premium from the premium schedule is zero
+    /// * `Unauthorized` if the provider is not an approved insurance provider
+    /// * `InsufficientFunds` if the coverage would exceed the provider's capacity or reserves
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * Transfer errors if the investor cannot pay the premium into the insurance pool
+    pub fn add_investment_insurance(
+        env: Env,
+        investment_id: BytesN<32>,
+        provider: Address,
+        coverage_percentage: u32,
+    ) -> Result<(), QuickLendXError> {
+        let mut investment = InvestmentStorage::get_investment(&env, &investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+
+        investment.investor.require_auth();
+
+        if investment.status != InvestmentStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let invoice = InvoiceStorage::get_invoice(&env, &investment.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        reentrancy::with_payment_guard(&env, || {
+            insurance::InsurancePurchases::buy(
+                &env,
+                &mut investment,
+                &invoice,
+                &provider,
+                coverage_percentage,
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// Cancel the active insurance coverage on an investment before the invoice is due
+    /// (investor only).
+    ///
+    /// The investor gets back the premium for the time left until the due date, pro rata over
+    /// the coverage term, less the cancellation fee. The refund is paid from the insurance pool.
+    ///
+    /// # Returns
+    /// * `Ok(PremiumRefund)` - The unused premium, the fee kept and the amount refunded
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment does not exist
+    /// * `InvalidStatus` if the investment is not Active
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `OperationNotAllowed` if the invoice is already due or no coverage is active
+    /// * `InsufficientFunds` if the insurance pool cannot cover the refund
+    pub fn cancel_investment_insurance(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Result<insurance_pool::PremiumRefund, QuickLendXError> {
+        let mut investment = InvestmentStorage::get_investment(&env, &investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        investment.investor.require_auth();
+        if investment.status != InvestmentStatus::Active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let invoice = InvoiceStorage::get_invoice(&env, &investment.invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let now = env.ledger().timestamp();
+        if now >= invoice.due_date {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let coverage = investment
+            .cancel_insurance(now)
+            .ok_or(QuickLendXError::OperationNotAllowed)?;
+
+        let refund = insurance_pool::InsurancePools::premium_refund(
+            &env,
+            coverage.premium_amount,
+            coverage.purchased_at,
+            invoice.due_date,
+        );
+        if refund.refund > 0 {
+            reentrancy::with_payment_guard(&env, || {
+                insurance_pool::InsurancePools::refund_premium(
+                    &env,
+                    &invoice.currency,
+                    &investment.investor,
+                    refund.refund,
+                )
+            })?;
+        }
+        InvestmentStorage::update_investment(&env, &investment);
+
+        emit_insurance_cancelled(
+            &env,
+            &investment_id,
+            &investment.investor,
+            &coverage.provider,
+            &refund,
+        );
+        insurance::InsuranceLog::record(
+            &env,
+            insurance::InsuranceRecordKind::PremiumRefunded,
+            &coverage.provider,
+            &investment_id,
+            refund.refund,
+        );
+        Ok(refund)
+    }
+
+    /// Withdraw a bid (investor only, before acceptance)
+    ///
+    /// Validates:
+    /// - Bid exists
+    /// - Caller is the bid owner (authorization check)
+    /// - Bid is in Placed status (prevents withdrawal of accepted/expired/withdrawn bids)
+    /// - Updates bid status to Withdrawn
+    /// - Returns the bid bond, if one is held
+    pub fn withdraw_bid(env: Env, bid_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        // Get bid and validate it exists
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+
+        // Authorization check: Only the investor who owns the bid can withdraw it
+        bid.investor.require_auth();
+
+        // Status validation: Only allow withdrawal if bid is placed
+        // Prevents withdrawal of accepted, withdrawn, or expired bids
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::BidNotPlaced);
+        }
+        bid.status = BidStatus::Withdrawn;
+        BidStorage::update_bid(&env, &bid);
+        BidBonds::release(&env, &bid_id)?;
+
+        // Emit bid withdrawn event
+        emit_bid_withdrawn(&env, &bid);
+        audit::log_bid_withdrawn(&env, bid.invoice_id.clone(), bid.investor.clone(), bid_id);
+
+        Ok(())
+    }
+
+    /// Extend an open bid by another default bid TTL (investor only)
+    ///
+    /// The new expiration is capped at the maximum bid TTL from now.
+    ///
+    /// # Errors
+    /// * `BidNotPlaced`, `BidExpired`
+    /// * `OperationNotAllowed` if the bid is already at the maximum TTL
+    pub fn extend_bid_expiration(env: Env, bid_id: BytesN<32>) -> Result<u64, QuickLendXError> {
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+
+        let now = env.ledger().timestamp();
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::BidNotPlaced);
+        }
+        if bid.is_expired(now) {
+            return Err(QuickLendXError::BidExpired);
+        }
+
+        let old_expiration = bid.expiration_timestamp;
+        let new_expiration = bid.extended_expiration(now, &BidStorage::get_ttl_bounds(&env));
+        if new_expiration <= old_expiration {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        bid.expiration_timestamp = new_expiration;
+        BidStorage::update_bid(&env, &bid);
+
+        events::emit_bid_expiration_extended(&env, &bid, old_expiration);
+        Ok(new_expiration)
+    }
+
+    /// Set the bounds for investor-chosen bid expirations, in seconds from placement (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidTimestamp` if `min_ttl` is zero or greater than `max_ttl`
+    pub fn set_bid_ttl_bounds(env: Env, min_ttl: u64, max_ttl: u64) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = BidStorage::get_ttl_bounds(&env);
+        let new = BidStorage::set_ttl_bounds(&env, min_ttl, max_ttl)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::BidMinTtl,
+            ConfigValue::U64(old.min_ttl),
+            ConfigValue::U64(new.min_ttl),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::BidMaxTtl,
+            ConfigValue::U64(old.max_ttl),
+            ConfigValue::U64(new.max_ttl),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Set per-investor bid limits enforced in `place_bid` (admin only)
+    ///
+    /// `max_active_bids_per_investor` and `max_bids_per_window` of 0 disable the platform-wide
+    /// and rate limits. Bids over a limit fail with `OperationNotAllowed`.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `max_active_bids_per_invoice` is zero
+    /// * `InvalidTimestamp` if a rate limit is set with a window of zero or over 30 days
+    pub fn set_bid_limits(
+        env: Env,
+        max_active_bids_per_invoice: u32,
+        max_active_bids_per_investor: u32,
+        max_bids_per_window: u32,
+        bid_rate_window_seconds: u64,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = protocol_limits::BidLimits::get(&env);
+        protocol_limits::BidLimits::set(
+            &env,
+            &protocol_limits::BidLimits {
+                max_active_bids_per_invoice,
+                max_active_bids_per_investor,
+                max_bids_per_window,
+                bid_rate_window_seconds,
+            },
+        )?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::MaxActiveBidsPerInvoice,
+            ConfigValue::U32(old.max_active_bids_per_invoice),
+            ConfigValue::U32(max_active_bids_per_invoice),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::MaxActiveBidsPerInvestor,
+            ConfigValue::U32(old.max_active_bids_per_investor),
+            ConfigValue::U32(max_active_bids_per_investor),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::MaxBidsPerWindow,
+            ConfigValue::U32(old.max_bids_per_window),
+            ConfigValue::U32(max_bids_per_window),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::BidRateWindowSeconds,
+            ConfigValue::U64(old.bid_rate_window_seconds),
+            ConfigValue::U64(bid_rate_window_seconds),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the per-investor bid limits
+    pub fn get_bid_limits(env: Env) -> protocol_limits::BidLimits {
+        protocol_limits::BidLimits::get(&env)
+    }
+
+    /// Set the content policy applied to descriptions, metadata, tags, rating feedback and
+    /// dispute text (admin only). Existing content is not re-validated.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if a limit is zero or above the hard caps
+    /// * `InvalidDescription` if the blocked terms are too many, empty or too long
+    pub fn set_content_policy(
+        env: Env,
+        policy: protocol_limits::ContentPolicy,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = protocol_limits::ContentPolicy::get(&env);
+        protocol_limits::ContentPolicy::set(&env, &policy)?;
+        for (parameter, old_value, new_value) in [
+            (
+                ConfigParameter::MaxDescriptionLength,
+                old.max_description_length,
+                policy.max_description_length,
+            ),
+            (
+                ConfigParameter::MaxTagLength,
+                old.max_tag_length,
+                policy.max_tag_length,
+            ),
+            (
+                ConfigParameter::MaxTagsPerInvoice,
+                old.max_tags_per_invoice,
+                policy.max_tags_per_invoice,
+            ),
+            (
+                ConfigParameter::MaxFeedbackLength,
+                old.max_feedback_length,
+                policy.max_feedback_length,
+            ),
+            (
+                ConfigParameter::MaxDisputeReasonLength,
+                old.max_dispute_reason_length,
+                policy.max_dispute_reason_length,
+            ),
+            (
+                ConfigParameter::MaxDisputeEvidenceLength,
+                old.max_dispute_evidence_length,
+                policy.max_dispute_evidence_length,
+            ),
+            (
+                ConfigParameter::ContentCharacterPolicy,
+                old.character_policy.code(),
+                policy.character_policy.code(),
+            ),
+            (
+                ConfigParameter::BlockedTerms,
+                old.blocked_terms.len(),
+                policy.blocked_terms.len(),
+            ),
+        ] {
+            ConfigLog::record(
+                &env,
+                parameter,
+                ConfigValue::U32(old_value),
+                ConfigValue::U32(new_value),
+                &admin,
+            );
+        }
+        Ok(())
+    }
+
+    /// Get the content policy
+    pub fn get_content_policy(env: Env) -> protocol_limits::ContentPolicy {
+        protocol_limits::ContentPolicy::get(&env)
+    }
+
+    /// Set or clear the minimum increment a new bid must add over the highest open bid
+    /// (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if an absolute increment is not positive or a bps increment is
+    ///   outside 1..=10,000
+    pub fn set_bid_increment_rule(
+        env: Env,
+        rule: Option<bid::BidIncrementRule>,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = BidStorage::get_increment_rule(&env);
+        BidStorage::set_increment_rule(&env, rule.clone())?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::BidIncrement,
+            ConfigValue::from_bid_increment(old),
+            ConfigValue::from_bid_increment(rule),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the bid increment rule, if one is configured
+    pub fn get_bid_increment_rule(env: Env) -> Option<bid::BidIncrementRule> {
+        BidStorage::get_increment_rule(&env)
+    }
+
+    /// Get the smallest bid amount `place_bid` currently accepts for an invoice
+    pub fn get_min_next_bid(env: Env, invoice_id: BytesN<32>) -> i128 {
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        verification::min_next_bid_amount(&env, &invoice_id)
+    }
+
+    /// Set or clear the cap on outstanding funded volume for an invoice category (admin only)
+    ///
+    /// Funding that would take the category's outstanding volume above the cap fails with
+    /// `OperationNotAllowed`.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the cap is not positive
+    pub fn set_category_cap(
+        env: Env,
+        category: invoice::InvoiceCategory,
+        cap: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old_cap = exposure::ExposureLimits::get_caps(&env).get(category.clone());
+        exposure::ExposureLimits::set_cap(&env, category.clone(), cap)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::CategoryCap(category),
+            old_cap.map_or(ConfigValue::Unset, ConfigValue::I128),
+            cap.map_or(ConfigValue::Unset, ConfigValue::I128),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get a category's cap, outstanding funded volume and remaining capacity
+    pub fn get_category_exposure(
+        env: Env,
+        category: invoice::InvoiceCategory,
+    ) -> exposure::CategoryExposure {
+        exposure::ExposureLimits::get_exposure(&env, category)
+    }
+
+    /// Get cap utilization for every capped category
+    pub fn get_category_exposures(env: Env) -> Vec<exposure::CategoryExposure> {
+        exposure::ExposureLimits::get_capped_exposures(&env)
+    }
+
+    /// Set or clear a business's base cap on outstanding funded volume (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the cap is not positive
+    pub fn set_business_financing_cap(
+        env: Env,
+        business: Address,
+        cap: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        exposure::ExposureLimits::set_business_cap(&env, &business, cap)
+    }
+
+    /// Schedule a financing cap that replaces a business's base cap between two timestamps
+    /// (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the cap is not positive
+    /// * `InvalidTimestamp` if the override does not end after it starts, or has already ended
+    /// * `OperationNotAllowed` if it overlaps another override of the business, or the business
+    ///   has too many pending overrides
+    pub fn add_business_cap_override(
+        env: Env,
+        business: Address,
+        cap_override: exposure::BusinessCapOverride,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        exposure::ExposureLimits::add_business_cap_override(&env, &business, cap_override)
+    }
+
+    /// Cancel a business's pending or current cap override by its start time (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if no such override is pending or in force
+    pub fn remove_business_cap_override(
+        env: Env,
+        business: Address,
+        starts_at: u64,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        exposure::ExposureLimits::remove_business_cap_override(&env, &business, starts_at)
+    }
+
+    /// Get a business's pending and current cap overrides
+    pub fn get_business_cap_overrides(
+        env: Env,
+        business: Address,
+    ) -> Vec<exposure::BusinessCapOverride> {
+        exposure::ExposureLimits::get_business_cap_overrides(&env, &business)
+    }
+
+    /// Get the financing cap in force for a business, its outstanding funded volume and
+    /// remaining capacity
+    pub fn get_business_exposure(env: Env, business: Address) -> exposure::BusinessExposure {
+        exposure::ExposureLimits::get_business_exposure(&env, &business)
+    }
+
+    /// Declare where the capital behind a bid comes from (investor only)
+    ///
+    /// Bids without a declaration count as `Personal`. The source decides which per-investor
+    /// funding source limit the bid counts against when it is accepted.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `BidNotPlaced` if the bid is no longer placed
+    pub fn set_bid_funding_source(
+        env: Env,
+        bid_id: BytesN<32>,
+        source: bid::BidFundingSource,
+    ) -> Result<(), QuickLendXError> {
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::BidNotPlaced);
+        }
+        BidStorage::set_funding_source(&env, &bid_id, &source);
+        Ok(())
+    }
+
+    /// Get the declared funding source of a bid
+    pub fn get_bid_funding_source(env: Env, bid_id: BytesN<32>) -> bid::BidFundingSource {
+        BidStorage::get_funding_source(&env, &bid_id)
+    }
+
+    /// Get the funding source of an investment, as declared on the bid that funded it
+    pub fn get_investment_funding_source(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> bid::BidFundingSource {
+        exposure::ExposureLimits::get_investment_source(&env, &investment_id)
+    }
+
+    /// Set or clear the limit on what each investor may have outstanding from a funding source
+    /// (admin only)
+    ///
+    /// Accepting a bid that would take the investor's active investments from the bid's source
+    /// above the limit fails with `OperationNotAllowed`.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the limit is not positive
+    pub fn set_funding_source_limit(
+        env: Env,
+        source: bid::BidFundingSource,
+        limit: Option<i128>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        exposure::ExposureLimits::set_source_limit(&env, source, limit)
+    }
+
+    /// Get an investor's outstanding amount, limit and remaining capacity for a funding source
+    pub fn get_funding_source_exposure(
+        env: Env,
+        investor: Address,
+        source: bid::BidFundingSource,
+    ) -> exposure::FundingSourceExposure {
+        exposure::ExposureLimits::get_source_exposure(&env, investor, source)
+    }
+
+    /// Get the bounds for investor-chosen bid expirations
+    pub fn get_bid_ttl_bounds(env: Env) -> bid::BidTtlBounds {
+        BidStorage::get_ttl_bounds(&env)
+    }
+
+    /// Counter a bid with different terms (business only)
+    ///
+    /// The bid moves to `Countered` until the investor accepts or rejects the counter-offer.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound` if the invoice or bid does not exist
+    /// * `InvoiceNotVerified`, `BidNotPlaced`
+    /// * `Unauthorized` if the bid is for another invoice
+    /// * `InvalidAmount` if the proposed amount is not positive, exceeds the invoice amount,
+    ///   or the proposed return does not exceed it
+    pub fn counter_bid(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+        proposed_amount: i128,
+        proposed_return: i128,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        if invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvoiceNotVerified);
+        }
+
+        BidStorage::cleanup_expired_bids(&env, &invoice_id);
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if bid.invoice_id != invoice_id {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::BidNotPlaced);
+        }
+        if proposed_amount <= 0
+            || proposed_amount > invoice.amount
+            || proposed_return <= proposed_amount
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        let counter = bid::CounterOffer {
+            bid_id: bid_id.clone(),
+            proposed_amount,
+            proposed_return,
+            countered_at: env.ledger().timestamp(),
+        };
+        bid.status = BidStatus::Countered;
+        BidStorage::update_bid(&env, &bid);
+        BidStorage::store_counter_offer(&env, &counter);
+
+        events::emit_bid_countered(&env, &bid, &counter);
+        Ok(())
+    }
+
+    /// Accept a counter-offer and fund the invoice on the countered terms (investor only)
+    ///
+    /// # Returns
+    /// * `Ok(escrow_id)` - The new escrow ID
+    pub fn accept_counter(env: Env, bid_id: BytesN<32>) -> Result<BytesN<32>, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || do_accept_counter_offer(&env, &bid_id))
+    }
+
+    /// Reject a counter-offer, restoring the bid to `Placed` on its original terms (investor only)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `InvalidStatus` if the bid is not Countered
+    pub fn reject_counter(env: Env, bid_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let mut bid =
+            BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        if bid.status != BidStatus::Countered {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        bid.status = BidStatus::Placed;
+        BidStorage::update_bid(&env, &bid);
+        BidStorage::remove_counter_offer(&env, &bid_id);
+
+        events::emit_counter_rejected(&env, &bid);
+        Ok(())
+    }
+
+    /// Get the pending counter-offer on a bid, if any
+    pub fn get_counter_offer(env: Env, bid_id: BytesN<32>) -> Option<bid::CounterOffer> {
+        BidStorage::get_counter_offer(&env, &bid_id)
+    }
+
+    /// Set the bid bond rate in basis points of the bid amount; 0 disables bonds (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `bps` exceeds `MAX_BID_BOND_BPS`
+    pub fn set_bid_bond_bps(env: Env, bps: u32) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old_bps = BidBonds::get_bond_bps(&env);
+        BidBonds::set_bond_bps(&env, bps)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::BidBondBps,
+            ConfigValue::U32(old_bps),
+            ConfigValue::U32(bps),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the bid bond rate in basis points
+    pub fn get_bid_bond_bps(env: Env) -> u32 {
+        BidBonds::get_bond_bps(&env)
+    }
+
+    /// Get the bond held for a bid, if any
+    pub fn get_bid_bond(env: Env, bid_id: BytesN<32>) -> Option<bid_bond::BidBond> {
+        BidBonds::get_bond(&env, &bid_id)
+    }
+
+    /// Return a bond that is still held for a bid that can no longer fund its invoice
+    /// (investor only)
+    ///
+    /// Bonds are normally returned automatically; this covers bids that lost while their
+    /// bond could not be returned.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid or its bond does not exist
+    /// * `OperationNotAllowed` if the bid is accepted or can still fund the invoice
+    pub fn reclaim_bid_bond(env: Env, bid_id: BytesN<32>) -> Result<i128, QuickLendXError> {
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        if BidBonds::get_bond(&env, &bid_id).is_none() {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+        let invoice_open = InvoiceStorage::get_invoice(&env, &bid.invoice_id)
+            .is_some_and(|invoice| invoice.status == InvoiceStatus::Verified);
+        let still_open = bid.is_open() && !bid.is_expired(env.ledger().timestamp());
+        if bid.status == BidStatus::Accepted || (still_open && invoice_open) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        BidBonds::release(&env, &bid_id)
+    }
+
+    /// Settle an invoice (business or automated process)
+    pub fn settle_invoice(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payment_amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+
+        let result = reentrancy::with_payment_guard(&env, || {
+            do_settle_invoice(&env, &invoice_id, payment_amount)
+        });
+
+        if result.is_ok() {
+            if let Some(inv) = investment {
+                let is_successful = payment_amount >= inv.amount;
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, is_successful);
+            }
+        }
+
+        result
+    }
+
+    /// Settle several funded invoices in one call (each invoice's business)
+    ///
+    /// Each `(invoice_id, payment_amount)` entry settles as `settle_invoice` would; an entry
+    /// that fails is skipped and reported in the result without affecting the others. Entries
+    /// at or above the settlement timelock threshold are skipped and must be settled through
+    /// `initiate_settlement`.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if more than `MAX_BATCH_SETTLEMENT` entries are given
+    pub fn settle_invoices(
+        env: Env,
+        settlements: Vec<(BytesN<32>, i128)>,
+    ) -> Result<settlement::BatchSettlementResult, QuickLendXError> {
+        let batch = reentrancy::with_payment_guard(&env, || {
+            settlement::settle_invoices(&env, &settlements)
+        })?;
+
+        for entry in batch.results.iter() {
+            if entry.outcome != settlement::InvoiceSettlementOutcome::Settled {
+                continue;
+            }
+            if let Some(inv) = InvestmentStorage::get_investment_by_invoice(&env, &entry.invoice_id)
+            {
+                let is_successful = entry.payment_amount >= inv.amount;
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, is_successful);
+            }
+        }
+
+        Ok(batch)
+    }
+
+    /// Pay a funded invoice directly as its registered debtor (debtor only)
+    ///
+    /// The debtor transfers the payment in the invoice currency and the invoice is settled
+    /// through the usual waterfall: investor return, keeper share and platform fee. Anything
+    /// above the amount due is refundable to the debtor via `claim_overpayment_refund`.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no registered debtor
+    /// * `PaymentTooLow` if the payment does not cover the invoice amount
+    /// * Any error from `settle_invoice` other than the timelock, which does not apply
+    pub fn pay_invoice_as_debtor(
+        env: Env,
+        invoice_id: BytesN<32>,
+        amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+
+        let result = reentrancy::with_payment_guard(&env, || {
+            settlement::pay_as_debtor(&env, &invoice_id, amount)
+        });
+
+        if result.is_ok() {
+            if let Some(inv) = investment {
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, true);
+            }
+        }
+
+        result
+    }
+
+    /// Claim the surplus paid above the amount due when an invoice was settled (payer only)
+    ///
+    /// # Returns
+    /// * `Ok(amount)` - the amount refunded
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no overpayment
+    /// * `OperationNotAllowed` if it was already claimed, or on re-entry
+    pub fn claim_overpayment_refund(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<i128, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            settlement::Overpayments::claim(&env, &invoice_id).map(|refund| refund.amount)
+        })
+    }
+
+    /// Settlement receipts for a business, newest first
+    ///
+    /// Only the last `MAX_SETTLEMENT_RECEIPTS` (50) settlements are kept.
+    pub fn get_business_settlements_paged(
+        env: Env,
+        business: Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<settlement::SettlementReceipt> {
+        settlement::SettlementReceipts::get_paged(&env, &business, offset, limit)
+    }
+
+    /// Get the overpayment recorded when an invoice was settled, if any
+    pub fn get_overpayment_refund(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<settlement::OverpaymentRefund> {
+        settlement::Overpayments::get(&env, &invoice_id)
+    }
+
+    /// Claim settlement payouts held for the investor in `currency` (investor only)
+    ///
+    /// A payout is held when its transfer to the investor was rejected at settlement, e.g.
+    /// because of a frozen trustline; the settlement completes and the investor is notified.
+    ///
+    /// # Returns
+    /// * `Ok(amount)` - the amount paid out
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if nothing is held for the investor in `currency`
+    /// * `OperationNotAllowed` on re-entry, transfer errors
+    pub fn claim_investor_balance(
+        env: Env,
+        investor: Address,
+        currency: Address,
+    ) -> Result<i128, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            settlement::InvestorClaims::claim(&env, &investor, &currency)
+        })
+    }
+
+    /// Get the settlement payouts held for the investor in `currency`
+    pub fn get_investor_claimable_balance(env: Env, investor: Address, currency: Address) -> i128 {
+        settlement::InvestorClaims::get(&env, &investor, &currency)
+    }
+
+    /// Prefund the investor's deposit account in `currency`
+    ///
+    /// Accepted bids draw their escrow from the deposit before transferring from the investor's
+    /// wallet, so fully prefunded bids fund without a token allowance.
+    ///
+    /// # Returns
+    /// * `Ok(balance)` - the new deposit balance
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `CurrencyNotWhitelisted` if the currency is not whitelisted
+    /// * `OperationNotAllowed` on re-entry, transfer errors
+    pub fn deposit(
+        env: Env,
+        investor: Address,
+        currency: Address,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        investor.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            deposits::InvestorDeposits::deposit(&env, &investor, &currency, amount)
+        })
+    }
+
+    /// Withdraw from the investor's deposit account in `currency`
+    ///
+    /// # Returns
+    /// * `Ok(balance)` - the remaining deposit balance
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `InsufficientFunds` if it exceeds the deposit
+    /// * `OperationNotAllowed` if the amount must be queued with `request_withdrawal`, on
+    ///   re-entry, transfer errors
+    pub fn withdraw(
+        env: Env,
+        investor: Address,
+        currency: Address,
+        amount: i128,
+    ) -> Result<i128, QuickLendXError> {
+        investor.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            deposits::InvestorDeposits::withdraw(&env, &investor, &currency, amount)
+        })
+    }
+
+    /// Get the investor's deposit balance in `currency`
+    pub fn get_deposit_balance(env: Env, investor: Address, currency: Address) -> i128 {
+        deposits::InvestorDeposits::get(&env, &investor, &currency)
+    }
+
+    /// Set or clear the queue for large deposit withdrawals (admin only)
+    ///
+    /// While set, `withdraw` rejects amounts at or above `threshold_amount`; they must be
+    /// requested with `request_withdrawal` and executed after `delay_seconds`.
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `InvalidAmount` if the threshold is not positive or the delay is outside
+    ///   1..=`MAX_WITHDRAWAL_DELAY`
+    pub fn set_withdrawal_queue(
+        env: Env,
+        policy: Option<deposits::WithdrawalQueuePolicy>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        deposits::InvestorDeposits::set_queue_policy(&env, policy)
+    }
+
+    /// Get the queue for large deposit withdrawals, if set
+    pub fn get_withdrawal_queue(env: Env) -> Option<deposits::WithdrawalQueuePolicy> {
+        deposits::InvestorDeposits::get_queue_policy(&env)
+    }
+
+    /// Queue a withdrawal from the investor's deposit at or above the queue threshold
+    ///
+    /// The amount leaves the deposit straight away and is paid out by `execute_withdrawal`
+    /// once the delay has elapsed; the investor can `cancel_withdrawal` until then.
+    ///
+    /// # Errors
+    /// * `InvalidAmount`, `InsufficientFunds` if it exceeds the deposit
+    /// * `OperationNotAllowed` if the amount is below the threshold or no queue is set
+    pub fn request_withdrawal(
+        env: Env,
+        investor: Address,
+        currency: Address,
+        amount: i128,
+    ) -> Result<deposits::PendingWithdrawal, QuickLendXError> {
+        investor.require_auth();
+        deposits::InvestorDeposits::request_withdrawal(&env, &investor, &currency, amount)
+    }
+
+    /// Cancel a queued withdrawal, returning its amount to the deposit (investor only)
+    ///
+    /// # Returns
+    /// * `Ok(balance)` - the new deposit balance
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no withdrawal with this ID is pending
+    pub fn cancel_withdrawal(env: Env, withdrawal_id: u64) -> Result<i128, QuickLendXError> {
+        let pending = deposits::InvestorDeposits::get_pending_withdrawal(&env, withdrawal_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        pending.investor.require_auth();
+        deposits::InvestorDeposits::cancel_withdrawal(&env, withdrawal_id)
+    }
+
+    /// Pay out a queued withdrawal once its delay has elapsed (investor or automated process)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no withdrawal with this ID is pending
+    /// * `OperationNotAllowed` if the delay has not elapsed, on re-entry, transfer errors
+    pub fn execute_withdrawal(
+        env: Env,
+        withdrawal_id: u64,
+    ) -> Result<deposits::PendingWithdrawal, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            deposits::InvestorDeposits::execute_withdrawal(&env, withdrawal_id)
+        })
+    }
+
+    /// Get a queued withdrawal by ID, if still pending
+    pub fn get_pending_withdrawal(
+        env: Env,
+        withdrawal_id: u64,
+    ) -> Option<deposits::PendingWithdrawal> {
+        deposits::InvestorDeposits::get_pending_withdrawal(&env, withdrawal_id)
+    }
+
+    /// Get all queued withdrawals, oldest first
+    pub fn get_pending_withdrawals(env: Env) -> Vec<deposits::PendingWithdrawal> {
+        deposits::InvestorDeposits::get_pending_withdrawals(&env)
+    }
+
+    /// Set or clear the timelock for large settlements (admin only)
+    ///
+    /// While set, `settle_invoice` and `process_partial_payment` reject payments at or above
+    /// `threshold_amount`; they must be initiated with `initiate_settlement` and confirmed
+    /// after `delay_seconds`.
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `InvalidAmount` if the threshold is not positive or the delay is outside
+    ///   1..=`MAX_SETTLEMENT_DELAY`
+    pub fn set_settlement_timelock(
+        env: Env,
+        policy: Option<settlement::SettlementTimelockPolicy>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let (old_threshold, old_delay) =
+            ConfigValue::from_settlement_timelock(SettlementTimelock::get_policy(&env));
+        SettlementTimelock::set_policy(&env, policy.clone())?;
+        let (new_threshold, new_delay) = ConfigValue::from_settlement_timelock(policy);
+        ConfigLog::record(
+            &env,
+            ConfigParameter::SettlementTimelockThreshold,
+            old_threshold,
+            new_threshold,
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::SettlementTimelockDelay,
+            old_delay,
+            new_delay,
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the timelock for large settlements, if set
+    pub fn get_settlement_timelock(env: Env) -> Option<settlement::SettlementTimelockPolicy> {
+        SettlementTimelock::get_policy(&env)
+    }
+
+    /// Start a timelocked settlement for a payment at or above the threshold (business only)
+    ///
+    /// No funds move until `confirm_settlement`; the business can `cancel_settlement` until
+    /// then.
+    ///
+    /// # Errors
+    /// * `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded
+    /// * `OperationNotAllowed` if the amount is below the threshold or a settlement is pending
+    pub fn initiate_settlement(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payment_amount: i128,
+    ) -> Result<settlement::PendingSettlement, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        SettlementTimelock::initiate(&env, &invoice_id, payment_amount)
+    }
+
+    /// Cancel a pending timelocked settlement (business only)
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `StorageKeyNotFound` if no settlement is pending
+    pub fn cancel_settlement(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        SettlementTimelock::cancel(&env, &invoice_id)
+    }
+
+    /// Execute a pending settlement once its delay has elapsed (business or automated process)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no settlement is pending
+    /// * `OperationNotAllowed` if the delay has not elapsed
+    /// * Settlement errors, as for `settle_invoice`
+    pub fn confirm_settlement(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+
+        let pending = reentrancy::with_payment_guard(&env, || {
+            SettlementTimelock::confirm(&env, &invoice_id)
+        })?;
+
+        if let Some(inv) = investment {
+            let is_successful = pending.payment_amount >= inv.amount;
+            let _ = update_investor_analytics(&env, &inv.investor, inv.amount, is_successful);
+        }
+        Ok(())
+    }
+
+    /// Get the pending timelocked settlement of an invoice, if any
+    pub fn get_pending_settlement(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<settlement::PendingSettlement> {
+        SettlementTimelock::get_pending(&env, &invoice_id)
+    }
+
+    /// Get the investment record for a funded invoice.
+    ///
+    /// # Returns
+    /// * `Ok(Investment)` - The investment tied to the invoice
+    /// * `Err(StorageKeyNotFound)` if the invoice has no investment
+    pub fn get_invoice_investment(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<Investment, QuickLendXError> {
+        InvestmentStorage::get_investment_by_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    /// Get an investment by ID.
+    ///
+    /// # Returns
+    /// * `Ok(Investment)` - The investment record
+    /// * `Err(StorageKeyNotFound)` if the ID does not exist
+    pub fn get_investment(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Result<Investment, QuickLendXError> {
+        InvestmentStorage::get_investment(&env, &investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    /// Check whether an investment exists without loading it
+    pub fn investment_exists(env: Env, investment_id: BytesN<32>) -> bool {
+        InvestmentStorage::exists(&env, &investment_id)
+    }
+
+    /// Query insurance coverage for an investment.
+    ///
+    /// # Arguments
+    /// * `investment_id` - The investment to query
+    ///
+    /// # Returns
+    /// * `Ok(Vec<InsuranceCoverage>)` - All insurance records for the investment
+    /// * `Err(StorageKeyNotFound)` if the investment does not exist
+    ///
+    /// # Security Notes
+    /// - Returns all insurance records (active and inactive)
+    /// - No authorization required for queries
+    pub fn query_investment_insurance(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Result<Vec<InsuranceCoverage>, QuickLendXError> {
+        let investment = InvestmentStorage::get_investment(&env, &investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Ok(investment.insurance)
+    }
+
+    /// Get the amount that pays off a funded invoice at `as_of`, for `settle_invoice`
+    ///
+    /// Returns a `PayoffQuote` with the principal, accrued return and late fee; `payoff_amount`
+    /// is their sum.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `InvalidStatus` if the invoice is not Funded
+    pub fn get_payoff_amount(
+        env: Env,
+        invoice_id: BytesN<32>,
+        as_of: u64,
+    ) -> Result<settlement::PayoffQuote, QuickLendXError> {
+        do_get_payoff_quote(&env, &invoice_id, as_of)
+    }
+
+    /// Get the current amount owed on an invoice, including accrued late penalties
+    ///
+    /// Funded invoices return the payoff amount as of now; Defaulted invoices return the
+    /// amount due when they defaulted, less recoveries collected since.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is neither Funded nor Defaulted
+    pub fn get_amount_due(env: Env, invoice_id: BytesN<32>) -> Result<i128, QuickLendXError> {
+        do_get_amount_due(&env, &invoice_id)
+    }
+
+    /// Preview `accept_bid_and_fund` without committing
+    ///
+    /// Returns the resulting statuses, escrow transfer, balances and projected settlement fees.
+    ///
+    /// # Errors
+    /// * Same as `accept_bid_and_fund`
+    pub fn simulate_accept_bid(
+        env: Env,
+        invoice_id: BytesN<32>,
+        bid_id: BytesN<32>,
+    ) -> Result<simulation::AcceptBidSimulation, QuickLendXError> {
+        simulation::simulate_accept_bid(&env, &invoice_id, &bid_id)
+    }
+
+    /// Preview defaulting a funded invoice without committing
+    ///
+    /// Returns the resulting statuses, insurance payout and investor loss, and whether the
+    /// grace period has passed.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvoiceNotAvailableForFunding` if the invoice is not Funded
+    pub fn simulate_default(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<simulation::DefaultSimulation, QuickLendXError> {
+        simulation::simulate_default(&env, &invoice_id)
+    }
+
+    /// Preview how `settle_invoice` would split a payment, without committing
+    ///
+    /// Returns the investor principal and profit, platform fee, late penalty, insurance coverage
+    /// released and the residual refundable to the business, and whether the payment needs the
+    /// settlement timelock.
+    ///
+    /// # Errors
+    /// * Same as `settle_invoice`, except that the timelock is reported instead
+    pub fn preview_settlement(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payment_amount: i128,
+    ) -> Result<simulation::SettlementPreview, QuickLendXError> {
+        simulation::preview_settlement(&env, &invoice_id, payment_amount)
+    }
+
+    /// Process a partial payment towards an invoice
+    ///
+    /// # Errors
+    /// * `DuplicatePayment` if `transaction_id` was already applied to the invoice
+    pub fn process_partial_payment(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payment_amount: i128,
+        transaction_id: String,
+    ) -> Result<(), QuickLendXError> {
+        do_process_partial_payment(&env, &invoice_id, payment_amount, transaction_id)
+    }
+
+    /// Whether a partial payment with `transaction_id` was already applied to the invoice
+    pub fn is_payment_transaction_applied(
+        env: Env,
+        invoice_id: BytesN<32>,
+        transaction_id: String,
+    ) -> bool {
+        settlement::PaymentTransactions::is_applied(&env, &invoice_id, &transaction_id)
+    }
+
+    /// Handle invoice default (admin or automated process)
+    /// This is the internal handler - use mark_invoice_defaulted for public API
+    pub fn handle_default(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        // Get the investment to track investor analytics
+        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+
+        let result = do_handle_default(&env, &invoice_id);
+
+        // Update investor analytics for failed investment
+        if result.is_ok() {
+            if let Some(inv) = investment {
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
+            }
+        }
+
+        result
+    }
+
+    /// Mark an invoice as defaulted (admin or automated process)
+    /// Checks due date + grace period before marking as defaulted
+    ///
+    /// # Arguments
+    /// * `invoice_id` - The invoice ID to mark as defaulted
+    /// * `grace_period` - Optional grace period in seconds (defaults to 7 days)
+    ///
+    /// # Returns
+    /// * `Ok(())` if the invoice was successfully marked as defaulted
+    /// * `Err(QuickLendXError)` if the operation fails
+    pub fn mark_invoice_defaulted(
+        env: Env,
+        invoice_id: BytesN<32>,
+        grace_period: Option<u64>,
+    ) -> Result<(), QuickLendXError> {
+        // Get the investment to track investor analytics
+        let investment = InvestmentStorage::get_investment_by_invoice(&env, &invoice_id);
+
+        let result = do_mark_invoice_defaulted(&env, &invoice_id, grace_period);
+
+        // Update investor analytics for failed investment
+        if result.is_ok() {
+            if let Some(inv) = investment {
+                let _ = update_investor_analytics(&env, &inv.investor, inv.amount, false);
+            }
+        }
+
+        result
+    }
+
+    /// Collect a recovery on a defaulted invoice from `payer`
+    ///
+    /// The investor receives the recovery up to the loss their insurance claim did not cover;
+    /// the rest claws back the paid claim for the insurance provider.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `amount` is not positive
+    /// * `InvalidStatus` if the invoice is not defaulted
+    /// * `OperationNotAllowed` if its collection case was written off
+    pub fn record_default_recovery(
+        env: Env,
+        invoice_id: BytesN<32>,
+        payer: Address,
+        amount: i128,
+    ) -> Result<defaults::DefaultRecovery, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            do_record_default_recovery(&env, &invoice_id, &payer, amount)
+        })
+    }
+
+    /// Get the recoveries collected on a defaulted invoice, if any
+    pub fn get_default_recovery(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<defaults::DefaultRecovery> {
+        defaults::get_default_recovery(&env, &invoice_id)
+    }
+
+    /// Get the collection case of a defaulted invoice, if any
+    pub fn get_collection_case(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<collections::CollectionCase> {
+        collections::Collections::get_case(&env, &invoice_id)
+    }
+
+    /// Recoveries collected on a defaulted invoice, oldest first
+    pub fn get_collection_history(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Vec<collections::CollectionEntry> {
+        collections::Collections::get_history(&env, &invoice_id)
+    }
+
+    /// Write off what is still outstanding on a defaulted invoice (admin only)
+    ///
+    /// No further recoveries can be recorded on the invoice afterwards.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the invoice has no collection case
+    /// * `InvalidStatus` if the case was already recovered in full or written off
+    pub fn write_off_collection(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<collections::CollectionCase, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        collections::Collections::write_off(&env, &invoice_id)
+    }
+
+    /// Calculate profit and platform fee
+    pub fn calculate_profit(
+        env: Env,
+        investment_amount: i128,
+        payment_amount: i128,
+    ) -> (i128, i128) {
+        do_calculate_profit(&env, investment_amount, payment_amount)
+    }
+
+    /// Retrieve the current platform fee configuration
+    ///
+    /// @deprecated Use `get_platform_fee_config()` instead
+    pub fn get_platform_fee(env: Env) -> profits::PlatformFeeConfig {
+        fees::FeeManager::load_platform_fee_config(&env).into()
+    }
+
+    /// Update the platform fee basis points (admin only)
+    ///
+    /// @deprecated Use `update_platform_fee_bps()` instead
+    pub fn set_platform_fee(env: Env, new_fee_bps: i128) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        let new_fee_bps = u32::try_from(new_fee_bps).map_err(|_| QuickLendXError::InvalidAmount)?;
+        let config = Self::set_platform_fee_bps(&env, &admin, new_fee_bps)?;
+        events::emit_platform_fee_updated(&env, &config.into());
+        Ok(())
+    }
+
+    // Rating Functions (from feat-invoice_rating_system)
+
+    /// Add a rating to an invoice (investor only)
+    pub fn add_invoice_rating(
+        env: Env,
+        invoice_id: BytesN<32>,
+        rating: u32,
+        feedback: String,
+        rater: Address,
+    ) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+
+        // Only the investor who funded the invoice can rate it
+        rater.require_auth();
+        protocol_limits::ContentPolicy::validate_feedback(&env, &feedback)?;
+
+        invoice.add_rating(rating, feedback, rater.clone(), env.ledger().timestamp())?;
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        // Emit rating event
+        env.events()
+            .publish((symbol_short!("rated"),), (invoice_id, rating, rater));
+
+        Ok(())
+    }
+
+    /// Get invoices with ratings above a threshold
+    pub fn get_invoices_with_rating_above(env: Env, threshold: u32) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_with_rating_above(&env, threshold)
+    }
+
+    /// Get business invoices with ratings above a threshold
+    pub fn get_business_rated_invoices(
+        env: Env,
+        business: Address,
+        threshold: u32,
+    ) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_business_invoices_with_rating_above(&env, &business, threshold)
+    }
+
+    /// Get count of invoices with ratings
+    pub fn get_invoices_with_ratings_count(env: Env) -> u32 {
+        InvoiceStorage::get_invoices_with_ratings_count(&env)
+    }
+
+    /// Get invoice rating statistics
+    pub fn get_invoice_rating_stats(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<(Option<u32>, u32, Option<u32>, Option<u32>), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+
+        Ok((
+            invoice.average_rating,
+            invoice.total_ratings,
+            invoice.get_highest_rating(),
+            invoice.get_lowest_rating(),
+        ))
+    }
+
+    // Business KYC/Verification Functions (from main)
+
+    /// Submit KYC application (business only)
+    pub fn submit_kyc_application(
+        env: Env,
+        business: Address,
+        kyc_data: String,
+    ) -> Result<(), QuickLendXError> {
+        submit_kyc_application(&env, &business, kyc_data)
+    }
+
+    /// Submit investor verification request
+    pub fn submit_investor_kyc(
+        env: Env,
+        investor: Address,
+        kyc_data: String,
+    ) -> Result<(), QuickLendXError> {
+        do_submit_investor_kyc(&env, &investor, kyc_data)
+    }
+
+    /// Verify an investor and set an investment limit
+    pub fn verify_investor(
+        env: Env,
+        investor: Address,
+        investment_limit: i128,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        let verification = do_verify_investor(&env, &admin, &investor, investment_limit)?;
+        emit_investor_verified(&env, &verification);
+        Ok(())
+    }
+
+    /// Reject an investor verification request
+    pub fn reject_investor(
+        env: Env,
+        investor: Address,
+        reason: String,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        do_reject_investor(&env, &admin, &investor, reason)
+    }
+
+    /// Get investor verification record if available
+    pub fn get_investor_verification(env: Env, investor: Address) -> Option<InvestorVerification> {
+        do_get_investor_verification(&env, &investor)
+    }
+
+    /// Set investment limit for a verified investor (admin only)
+    pub fn set_investment_limit(
+        env: Env,
+        investor: Address,
+        new_limit: i128,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        verification::set_investment_limit(&env, &admin, &investor, new_limit)
+    }
+
+    /// Verify business (admin only)
+    pub fn verify_business(
+        env: Env,
+        admin: Address,
+        business: Address,
+    ) -> Result<(), QuickLendXError> {
+        verify_business(&env, &admin, &business)
+    }
+
+    /// Reject business (admin only)
+    pub fn reject_business(
+        env: Env,
+        admin: Address,
+        business: Address,
+        reason: String,
+    ) -> Result<(), QuickLendXError> {
+        reject_business(&env, &admin, &business, reason)
+    }
+
+    /// Get business verification status
+    pub fn get_business_verification_status(
+        env: Env,
+        business: Address,
+    ) -> Option<verification::BusinessVerification> {
+        get_business_verification_status(&env, &business)
+    }
+
+    /// Set admin address (initialization function)
+    pub fn set_admin(env: Env, admin: Address) -> Result<(), QuickLendXError> {
+        if let Some(current_admin) = BusinessVerificationStorage::get_admin(&env) {
+            current_admin.require_auth();
+        } else {
+            admin.require_auth();
+        }
+        BusinessVerificationStorage::set_admin(&env, &admin);
+        Ok(())
+    }
+
+    /// Get admin address
+    pub fn get_admin(env: Env) -> Option<Address> {
+        BusinessVerificationStorage::get_admin(&env)
+    }
+
+    /// Get all verified businesses
+    pub fn get_verified_businesses(env: Env) -> Vec<Address> {
+        BusinessVerificationStorage::get_verified_businesses(&env)
+    }
+
+    /// Get all pending businesses
+    pub fn get_pending_businesses(env: Env) -> Vec<Address> {
+        BusinessVerificationStorage::get_pending_businesses(&env)
+    }
+
+    /// Get all rejected businesses
+    pub fn get_rejected_businesses(env: Env) -> Vec<Address> {
+        BusinessVerificationStorage::get_rejected_businesses(&env)
+    }
+
+    // ========================================
+    // Enhanced Investor Verification Functions
+    // ========================================
+
+    /// Get all verified investors
+    pub fn get_verified_investors(env: Env) -> Vec<Address> {
+        InvestorVerificationStorage::get_verified_investors(&env)
+    }
+
+    /// Get all pending investors
+    pub fn get_pending_investors(env: Env) -> Vec<Address> {
+        InvestorVerificationStorage::get_pending_investors(&env)
+    }
+
+    /// Get all rejected investors
+    pub fn get_rejected_investors(env: Env) -> Vec<Address> {
+        InvestorVerificationStorage::get_rejected_investors(&env)
+    }
+
+    /// Get investors by tier
+    pub fn get_investors_by_tier(env: Env, tier: InvestorTier) -> Vec<Address> {
+        InvestorVerificationStorage::get_investors_by_tier(&env, tier)
+    }
+
+    /// Get investors by risk level
+    pub fn get_investors_by_risk_level(env: Env, risk_level: InvestorRiskLevel) -> Vec<Address> {
+        InvestorVerificationStorage::get_investors_by_risk_level(&env, risk_level)
+    }
+
+    /// Calculate investor risk score
+    pub fn calculate_investor_risk_score(
+        env: Env,
+        investor: Address,
+        kyc_data: String,
+    ) -> Result<u32, QuickLendXError> {
+        calculate_investor_risk_score(&env, &investor, &kyc_data)
+    }
+
+    /// Determine investor tier
+    pub fn determine_investor_tier(
+        env: Env,
+        investor: Address,
+        risk_score: u32,
+    ) -> Result<InvestorTier, QuickLendXError> {
+        determine_investor_tier(&env, &investor, risk_score)
+    }
+
+    /// Calculate investment limit for investor
+    pub fn calculate_investment_limit(
+        _env: Env,
+        tier: InvestorTier,
+        risk_level: InvestorRiskLevel,
+        base_limit: i128,
+    ) -> i128 {
+        calculate_investment_limit(&tier, &risk_level, base_limit)
+    }
+
+    /// Update investor analytics after investment
+    pub fn update_investor_analytics(
+        env: Env,
+        investor: Address,
+        investment_amount: i128,
+        is_successful: bool,
+    ) -> Result<(), QuickLendXError> {
+        update_investor_analytics(&env, &investor, investment_amount, is_successful)
+    }
+
+    /// Get investor analytics
+    pub fn get_investor_analytics(
+        env: Env,
+        investor: Address,
+    ) -> Result<InvestorVerification, QuickLendXError> {
+        get_investor_analytics(&env, &investor)
+    }
+
+    /// Validate investor investment
+    pub fn validate_investor_investment(
+        env: Env,
+        investor: Address,
+        investment_amount: i128,
+    ) -> Result<(), QuickLendXError> {
+        validate_investor_investment(&env, &investor, investment_amount)
+    }
+
+    /// Check if investor is verified
+    pub fn is_investor_verified(env: Env, investor: Address) -> bool {
+        InvestorVerificationStorage::is_investor_verified(&env, &investor)
+    }
+
+    /// Publish a new investor risk disclosure by its document hash (admin only)
+    ///
+    /// Bumps the disclosure version. From then on every investor must acknowledge the new
+    /// version with `acknowledge_suitability` before placing further bids.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    pub fn set_suitability_disclosure(
+        env: Env,
+        disclosure_hash: BytesN<32>,
+    ) -> Result<suitability::SuitabilityDisclosure, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let disclosure = suitability::InvestorSuitability::publish(&env, &disclosure_hash);
+        ConfigLog::record(
+            &env,
+            ConfigParameter::SuitabilityDisclosureVersion,
+            ConfigValue::U32(disclosure.version - 1),
+            ConfigValue::U32(disclosure.version),
+            &admin,
+        );
+        Ok(disclosure)
+    }
+
+    /// Get the current investor risk disclosure, if one was published
+    pub fn get_suitability_disclosure(env: Env) -> Option<suitability::SuitabilityDisclosure> {
+        suitability::InvestorSuitability::get_disclosure(&env)
+    }
+
+    /// Acknowledge the current risk disclosure (investor only)
+    ///
+    /// `disclosure_hash` must match the published disclosure, so the investor confirms the
+    /// document they were shown is the one on record.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no disclosure was published
+    /// * `InvalidDescription` if `disclosure_hash` is not the current disclosure's hash
+    pub fn acknowledge_suitability(
+        env: Env,
+        investor: Address,
+        disclosure_hash: BytesN<32>,
+    ) -> Result<suitability::SuitabilityAcknowledgment, QuickLendXError> {
+        investor.require_auth();
+        let acknowledgment =
+            suitability::InvestorSuitability::acknowledge(&env, &investor, &disclosure_hash)?;
+        events::emit_suitability_acknowledged(&env, &acknowledgment);
+        Ok(acknowledgment)
+    }
+
+    /// Get an investor's latest risk disclosure acknowledgment, if any
+    pub fn get_suitability_acknowledgment(
+        env: Env,
+        investor: Address,
+    ) -> Option<suitability::SuitabilityAcknowledgment> {
+        suitability::InvestorSuitability::get_acknowledgment(&env, &investor)
+    }
+
+    /// Get escrow details for an invoice
+    pub fn get_escrow_details(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<payments::Escrow, QuickLendXError> {
+        EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)
+    }
+
+    /// Get escrow status for an invoice
+    pub fn get_escrow_status(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<payments::EscrowStatus, QuickLendXError> {
+        let escrow = EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Ok(escrow.status)
+    }
+
+    /// Amount of `currency` the contract holds for escrows, bonds, keeper rewards and retained
+    /// fees; transfers out of the contract cannot exceed it
+    pub fn get_segregated_balance(env: Env, currency: Address) -> i128 {
+        payments::SegregatedBalances::get(&env, &currency)
+    }
+
+    /// Release escrow funds to business upon invoice verification
+    ///
+    /// Invoices split into milestones are paid out through `release_milestone` instead.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound`, `InvalidStatus` if the escrow is not Held
+    /// * `OperationNotAllowed` if the invoice has milestones, an unresolved dispute froze the
+    ///   escrow, or release approval is required and the escrow was not approved
+    pub fn release_escrow_funds(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            let escrow = EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
+                .ok_or(QuickLendXError::StorageKeyNotFound)?;
+            if InvoiceMilestones::has_milestones(&env, &invoice_id) {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            payments::ReleaseApprovals::check(&env, &invoice_id)?;
+
+            release_escrow(&env, &invoice_id)?;
+
+            emit_escrow_released(
+                &env,
+                &escrow.escrow_id,
+                &invoice_id,
+                &escrow.business,
+                escrow.amount - escrow.released_amount,
+            );
+
+            Ok(())
+        })
+    }
+
+    /// Approve releasing an invoice's escrow to the business, as the admin or a registered
+    /// invoice verifier
+    ///
+    /// Only gates `release_escrow_funds` while release approval is required; the approval
+    /// itself moves no funds.
+    ///
+    /// # Errors
+    /// * `Unauthorized` if `approver` is neither the admin nor a registered invoice verifier
+    /// * `StorageKeyNotFound` if the invoice has no escrow, `InvalidStatus` if it is not Held
+    /// * `OperationNotAllowed` if the escrow is frozen by a dispute or already approved
+    pub fn approve_release(
+        env: Env,
+        invoice_id: BytesN<32>,
+        approver: Address,
+    ) -> Result<payments::ReleaseApproval, QuickLendXError> {
+        approver.require_auth();
+        if !AdminStorage::is_admin(&env, &approver)
+            && !verification::InvoiceSignoffs::get_verifiers(&env).contains(&approver)
+        {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        payments::ReleaseApprovals::approve(&env, &invoice_id, &approver)
+    }
+
+    /// Get the release approval recorded for an invoice's escrow, if any
+    pub fn get_release_approval(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<payments::ReleaseApproval> {
+        payments::ReleaseApprovals::get(&env, &invoice_id)
+    }
+
+    /// Require (or stop requiring) an `approve_release` before escrow is released (admin only)
+    pub fn set_release_approval_required(env: Env, required: bool) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = payments::ReleaseApprovals::is_required(&env);
+        payments::ReleaseApprovals::set_required(&env, required);
+        ConfigLog::record(
+            &env,
+            ConfigParameter::EscrowReleaseApprovalRequired,
+            ConfigValue::Bool(old),
+            ConfigValue::Bool(required),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Whether escrow releases need an `approve_release` first
+    pub fn is_release_approval_required(env: Env) -> bool {
+        payments::ReleaseApprovals::is_required(&env)
+    }
+
+    ///== Invoice Milestones ==///
+
+    /// Split an unfunded invoice into milestones (business only); an empty list removes them
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `InvalidAmount`, `InvoiceAmountInvalid` if an amount is not positive or the amounts
+    ///   do not sum to the invoice amount
+    /// * `InvoiceDueDateInvalid` if due dates are past, decreasing or after the invoice due date
+    /// * `InvalidDescription`, `OperationNotAllowed` if there are too many milestones
+    pub fn set_invoice_milestones(
+        env: Env,
+        invoice_id: BytesN<32>,
+        milestones: Vec<MilestoneSpec>,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        InvoiceMilestones::define(&env, &invoice_id, &milestones)
+    }
+
+    /// Release one milestone's share of the escrow to the business once its work is verified
+    /// (admin only)
+    ///
+    /// # Returns
+    /// * `Ok(amount)` - the escrow amount paid to the business's payout address
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded
+    /// * `StorageKeyNotFound` if there is no such milestone
+    /// * `OperationNotAllowed` if the milestone was already released
+    pub fn release_milestone(
+        env: Env,
+        invoice_id: BytesN<32>,
+        milestone_idx: u32,
+    ) -> Result<i128, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            InvoiceMilestones::release(&env, &invoice_id, milestone_idx)
+        })
+    }
+
+    /// Milestones of an invoice with their release status (empty for regular invoices)
+    pub fn get_invoice_milestones(env: Env, invoice_id: BytesN<32>) -> Vec<InvoiceMilestone> {
+        InvoiceMilestones::get(&env, &invoice_id)
+    }
+
+    /// Refund escrow funds to investor if verification fails or as an explicit manual refund.
+    ///
+    /// Can be triggered by Admin or Business owner. Invoice must be Funded.
+    /// Protected by payment reentrancy guard.
+    pub fn refund_escrow_funds(
+        env: Env,
+        invoice_id: BytesN<32>,
+        caller: Address,
+    ) -> Result<(), QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || do_refund_escrow_funds(&env, &invoice_id, &caller))
+    }
+
+    ///== Payout Routing ==///
+
+    /// Request a new payout address for escrow releases. Takes effect after a 72h timelock;
+    /// the business and its active investors are notified immediately.
+    pub fn request_payout_address_change(
+        env: Env,
+        business: Address,
+        new_address: Address,
+    ) -> Result<PayoutAddressChange, QuickLendXError> {
+        business.require_auth();
+        PayoutRouting::request_change(&env, &business, &new_address)
+    }
+
+    /// Apply a pending payout address change once its timelock has elapsed
+    pub fn confirm_payout_address_change(
+        env: Env,
+        business: Address,
+    ) -> Result<Address, QuickLendXError> {
+        business.require_auth();
+        PayoutRouting::confirm_change(&env, &business)
+    }
+
+    /// Cancel a pending payout address change (business or admin)
+    pub fn cancel_payout_address_change(
+        env: Env,
+        caller: Address,
+        business: Address,
+    ) -> Result<(), QuickLendXError> {
+        caller.require_auth();
+        if caller != business && !AdminStorage::is_admin(&env, &caller) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        PayoutRouting::cancel_change(&env, &business, &caller)
+    }
+
+    /// Address escrow releases for the business are paid to
+    pub fn get_payout_address(env: Env, business: Address) -> Address {
+        PayoutRouting::get_payout_address(&env, &business)
+    }
+
+    /// Pending payout address change for the business, if any
+    pub fn get_pending_payout_change(env: Env, business: Address) -> Option<PayoutAddressChange> {
+        PayoutRouting::get_pending_change(&env, &business)
+    }
+
+    ///== Notification Management Functions ==///
+
+    /// Get a notification by ID
+    pub fn get_notification(env: Env, notification_id: BytesN<32>) -> Option<Notification> {
+        NotificationSystem::get_notification(&env, &notification_id)
+    }
+
+    /// Update notification delivery status
+    pub fn update_notification_status(
+        env: Env,
+        notification_id: BytesN<32>,
+        status: NotificationDeliveryStatus,
+    ) -> Result<(), QuickLendXError> {
+        NotificationSystem::update_notification_status(&env, &notification_id, status)
+    }
+
+    /// Get all notifications for a user
+    pub fn get_user_notifications(env: Env, user: Address) -> Vec<BytesN<32>> {
+        NotificationSystem::get_user_notifications(&env, &user)
+    }
+
+    /// Get user notification preferences
+    pub fn get_notification_preferences(env: Env, user: Address) -> NotificationPreferences {
+        NotificationSystem::get_user_preferences(&env, &user)
+    }
+
+    /// Update user notification preferences
+    pub fn update_notification_preferences(
+        env: Env,
+        user: Address,
+        preferences: NotificationPreferences,
+    ) -> Result<(), QuickLendXError> {
+        user.require_auth();
+        NotificationSystem::update_user_preferences(&env, &user, preferences);
+        Ok(())
+    }
+
+    /// Get notification statistics for a user
+    pub fn get_user_notification_stats(env: Env, user: Address) -> NotificationStats {
+        NotificationSystem::get_user_notification_stats(&env, &user)
+    }
+
+    /// Get notifications that failed inside a flow and await replay, oldest first
+    pub fn get_notification_dead_letters(env: Env) -> Vec<DeadLetter> {
+        NotificationSystem::get_dead_letters(&env)
+    }
+
+    /// Send a dead-lettered notification again (admin only)
+    ///
+    /// # Returns
+    /// * `Ok(true)` if it was sent and removed, `Ok(false)` if it failed again
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `StorageKeyNotFound` if no dead letter has this ID, `InvoiceNotFound`
+    pub fn replay_notification_dead_letter(env: Env, id: u64) -> Result<bool, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        NotificationSystem::replay_dead_letter(&env, id)
+    }
+
+    /// Check for overdue invoices and send notifications (admin or automated process)
+    pub fn check_overdue_invoices(env: Env) -> Result<u32, QuickLendXError> {
+        Self::check_overdue_invoices_grace(env, Invoice::DEFAULT_GRACE_PERIOD)
+    }
+
+    /// Check for overdue invoices with a custom grace period (in seconds)
+    pub fn check_overdue_invoices_grace(
+        env: Env,
+        grace_period: u64,
+    ) -> Result<u32, QuickLendXError> {
+        let current_timestamp = env.ledger().timestamp();
+        let funded_invoices = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded);
+        let mut overdue_count = 0u32;
+
+        for invoice_id in funded_invoices.iter() {
+            if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
+                if invoice.is_overdue(current_timestamp) {
+                    NotificationHooks::fire(&env, &invoice, NotificationHook::PaymentOverdue);
+                    overdue_count += 1;
+                }
+                let _ = invoice.check_and_handle_expiration(&env, grace_period)?;
+            }
+        }
+
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::OverdueCheck,
+            None,
+            overdue_count,
+            funded_invoices.len(),
+            0,
+        );
+
+        Ok(overdue_count)
+    }
+
+    /// Check whether a specific invoice has expired and trigger default handling when necessary
+    pub fn check_invoice_expiration(
+        env: Env,
+        invoice_id: BytesN<32>,
+        grace_period: Option<u64>,
+    ) -> Result<bool, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        let grace = grace_period.unwrap_or(Invoice::DEFAULT_GRACE_PERIOD);
+        invoice.check_and_handle_expiration(&env, grace)
+    }
+
+    /// Create a backup of all invoice data
+    pub fn create_backup(env: Env, description: String) -> Result<BytesN<32>, QuickLendXError> {
+        // Only admin can create backups
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+
+        // Get all invoices
+        let pending = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Pending);
+        let verified = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Verified);
+        let funded = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded);
+        let paid = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Paid);
+        let defaulted = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Defaulted);
+
+        // Combine all invoices
+        let mut all_invoices = Vec::new(&env);
+        for status_vec in [pending, verified, funded, paid, defaulted].iter() {
+            for invoice_id in status_vec.iter() {
+                if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
+                    all_invoices.push_back(invoice);
+                }
+            }
+        }
+
+        // Create backup
+        let backup_id = BackupStorage::generate_backup_id(&env);
+        let backup = Backup {
+            backup_id: backup_id.clone(),
+            timestamp: env.ledger().timestamp(),
+            description,
+            invoice_count: all_invoices.len() as u32,
+            status: BackupStatus::Active,
+        };
+
+        // Store backup and data
+        BackupStorage::store_backup(&env, &backup);
+        BackupStorage::store_backup_data(&env, &backup_id, &all_invoices);
+        BackupStorage::add_to_backup_list(&env, &backup_id);
+
+        // Clean up old backups (keep last 5)
+        BackupStorage::cleanup_old_backups(&env, 5)?;
+
+        // Emit event
+        events::emit_backup_created(&env, &backup_id, backup.invoice_count);
+
+        Ok(backup_id)
+    }
+
+    /// Restore invoice data from a backup
+    pub fn restore_backup(env: Env, backup_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        // Only admin can restore backups
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+
+        // Validate backup first
+        let manifest = BackupStorage::validate_backup(&env, &backup_id)?;
+
+        // Clear current invoice data
+        Self::clear_all_invoices(&env)?;
+
+        // Restore invoices chunk by chunk
+        for chunk_index in 0..manifest.chunk_count {
+            let chunk = BackupStorage::get_chunk(&env, &backup_id, chunk_index)
+                .ok_or(QuickLendXError::StorageError)?;
+            for invoice in chunk.iter() {
+                InvoiceStorage::store_invoice(&env, &invoice);
+            }
+        }
+
+        // Emit event
+        events::emit_backup_restored(&env, &backup_id, manifest.invoice_count);
+
+        Ok(())
+    }
+
+    /// Validate a backup's integrity
+    pub fn validate_backup(env: Env, backup_id: BytesN<32>) -> Result<bool, QuickLendXError> {
+        let result = BackupStorage::validate_backup(&env, &backup_id).is_ok();
+        events::emit_backup_validated(&env, &backup_id, result);
+        Ok(result)
+    }
+
+    /// Archive a backup (mark as no longer active)
+    pub fn archive_backup(env: Env, backup_id: BytesN<32>) -> Result<(), QuickLendXError> {
+        // Only admin can archive backups
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+
+        let mut backup = BackupStorage::get_backup(&env, &backup_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+
+        backup.status = BackupStatus::Archived;
+        BackupStorage::update_backup(&env, &backup);
+        BackupStorage::remove_from_backup_list(&env, &backup_id);
+
+        events::emit_backup_archived(&env, &backup_id);
+
+        Ok(())
+    }
+
+    /// Get all available backups
+    pub fn get_backups(env: Env) -> Vec<BytesN<32>> {
+        BackupStorage::get_all_backups(&env)
+    }
+
+    /// Get backup details
+    pub fn get_backup_details(env: Env, backup_id: BytesN<32>) -> Option<Backup> {
+        BackupStorage::get_backup(&env, &backup_id)
+    }
+
+    /// Get the chunk layout of a backup's invoice data
+    pub fn get_backup_manifest(env: Env, backup_id: BytesN<32>) -> Option<BackupManifest> {
+        BackupStorage::get_manifest(&env, &backup_id)
+    }
+
+    /// Get one chunk of a backup's invoices (up to `BACKUP_CHUNK_SIZE`, 20)
+    pub fn get_backup_chunk(
+        env: Env,
+        backup_id: BytesN<32>,
+        chunk_index: u32,
+    ) -> Option<Vec<Invoice>> {
+        BackupStorage::get_chunk(&env, &backup_id, chunk_index)
+    }
+
+    /// Internal function to clear all invoice data
+    fn clear_all_invoices(env: &Env) -> Result<(), QuickLendXError> {
+        // Clear all status lists
+        for status in [
+            InvoiceStatus::Pending,
+            InvoiceStatus::Verified,
+            InvoiceStatus::Funded,
+            InvoiceStatus::Paid,
+            InvoiceStatus::Defaulted,
+            InvoiceStatus::Cancelled,
+            InvoiceStatus::Expired,
+        ]
+        .iter()
+        {
+            let invoices = InvoiceStorage::get_invoices_by_status(env, status);
+            for invoice_id in invoices.iter() {
+                // Remove from status list
+                InvoiceStorage::remove_from_status_invoices(env, status, &invoice_id);
+                // Remove the invoice itself
+                env.storage().instance().remove(&invoice_id);
+            }
+        }
+
+        // Clear all business invoices
+        let verified_businesses = BusinessVerificationStorage::get_verified_businesses(env);
+        for business in verified_businesses.iter() {
+            let _ = InvoiceStorage::get_business_invoices(env, &business);
+            let key = (symbol_short!("business"), business.clone());
+            env.storage().instance().remove(&key);
+            list_versions::ListVersions::bump(
+                env,
+                &[list_versions::ListIndex::BusinessInvoices(business.clone())],
+            );
+        }
+
+        Ok(())
+    }
+    /// Get audit trail for an invoice
+    pub fn get_invoice_audit_trail(env: Env, invoice_id: BytesN<32>) -> Vec<BytesN<32>> {
+        AuditStorage::get_invoice_audit_trail(&env, &invoice_id)
+    }
+
+    /// Get audit entry by ID
+    pub fn get_audit_entry(
+        env: Env,
+        audit_id: BytesN<32>,
+    ) -> Result<AuditLogEntry, QuickLendXError> {
+        AuditStorage::get_audit_entry(&env, &audit_id).ok_or(QuickLendXError::AuditLogNotFound)
+    }
+
+    /// Query audit logs with filters
+    pub fn query_audit_logs(env: Env, filter: AuditQueryFilter, limit: u32) -> Vec<AuditLogEntry> {
+        let results = AuditStorage::query_audit_logs(&env, &filter, limit);
+        emit_audit_query(
+            &env,
+            String::from_str(&env, "query_audit_logs"),
+            results.len() as u32,
+        );
+        results
+    }
+
+    /// Get audit statistics
+    pub fn get_audit_stats(env: Env) -> AuditStats {
+        AuditStorage::get_audit_stats(&env)
+    }
+
+    /// Validate audit log integrity for an invoice
+    pub fn validate_invoice_audit_integrity(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<bool, QuickLendXError> {
+        let is_valid = AuditStorage::validate_invoice_audit_integrity(&env, &invoice_id)?;
+        emit_audit_validation(&env, &invoice_id, is_valid);
+        Ok(is_valid)
+    }
+
+    /// Get audit entries by operation type
+    pub fn get_audit_entries_by_operation(env: Env, operation: AuditOperation) -> Vec<BytesN<32>> {
+        AuditStorage::get_audit_entries_by_operation(&env, &operation)
+    }
+
+    /// Get audit entries by actor
+    pub fn get_audit_entries_by_actor(env: Env, actor: Address) -> Vec<BytesN<32>> {
+        AuditStorage::get_audit_entries_by_actor(&env, &actor)
+    }
+
+    // Category and Tag Management Functions
+
+    /// Get invoices by category
+    pub fn get_invoices_by_category(
+        env: Env,
+        category: invoice::InvoiceCategory,
+    ) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_category(&env, &category)
+    }
+
+    /// Get invoices by category and status
+    pub fn get_invoices_by_cat_status(
+        env: Env,
+        category: invoice::InvoiceCategory,
+        status: InvoiceStatus,
+    ) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_category_and_status(&env, &category, &status)
+    }
+
+    /// Get invoices by tag
+    pub fn get_invoices_by_tag(env: Env, tag: String) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_tag(&env, &tag)
+    }
+
+    /// Get invoices by multiple tags (AND logic)
+    pub fn get_invoices_by_tags(env: Env, tags: Vec<String>) -> Vec<BytesN<32>> {
+        InvoiceStorage::get_invoices_by_tags(&env, &tags)
+    }
+
+    /// Get invoice count by category
+    pub fn get_invoice_count_by_category(env: Env, category: invoice::InvoiceCategory) -> u32 {
+        InvoiceStorage::get_invoice_count_by_category(&env, &category)
+    }
+
+    /// Get invoice count by tag
+    pub fn get_invoice_count_by_tag(env: Env, tag: String) -> u32 {
+        InvoiceStorage::get_invoice_count_by_tag(&env, &tag)
+    }
+
+    /// Get all available categories
+    pub fn get_all_categories(env: Env) -> Vec<invoice::InvoiceCategory> {
+        InvoiceStorage::get_all_categories(&env)
+    }
+
+    /// Update invoice category (business owner only)
+    pub fn update_invoice_category(
+        env: Env,
+        invoice_id: BytesN<32>,
+        new_category: invoice::InvoiceCategory,
+    ) -> Result<(), QuickLendXError> {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+
+        // Only the business owner can update the category
+        invoice.business.require_auth();
+
+        let old_category = invoice.category.clone();
+        invoice.update_category(new_category.clone());
+
+        // Validate the new category
+        verification::validate_invoice_category(&new_category)?;
+
+        // Update the invoice
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        // Emit event
+        events::emit_invoice_category_updated(
+            &env,
+            &invoice_id,
+            &invoice.business,
+            &old_category,
+            &new_category,
+        );
+
+        // Update indexes
+        InvoiceStorage::remove_category_index(&env, &old_category, &invoice_id);
+        InvoiceStorage::add_category_index(&env, &new_category, &invoice_id);
+
+        Ok(())
     }
 
-    /// Update investor analytics after investment
-    pub fn update_investor_analytics(
+    /// Add tag to invoice (business owner only)
+    pub fn add_invoice_tag(
         env: Env,
-        investor: Address,
-        investment_amount: i128,
-        is_successful: bool,
+        invoice_id: BytesN<32>,
+        tag: String,
     ) -> Result<(), QuickLendXError> {
-        update_investor_analytics(&env, &investor, investment_amount, is_successful)
-    }
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
 
-    /// Get investor analytics
-    pub fn get_investor_analytics(
-        env: Env,
-        investor: Address,
-    ) -> Result<InvestorVerification, QuickLendXError> {
-        get_investor_analytics(&env, &investor)
+        // Only the business owner can add tags
+        invoice.business.require_auth();
+
+        // Add the tag
+        invoice.add_tag(&env, tag.clone())?;
+
+        // Update the invoice
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        // Emit event
+        events::emit_invoice_tag_added(&env, &invoice_id, &invoice.business, &tag);
+
+        // Update index
+        InvoiceStorage::add_tag_index(&env, &tag, &invoice_id);
+
+        Ok(())
     }
 
-    /// Validate investor investment
-    pub fn validate_investor_investment(
+    /// Remove tag from invoice (business owner only)
+    pub fn remove_invoice_tag(
         env: Env,
-        investor: Address,
-        investment_amount: i128,
+        invoice_id: BytesN<32>,
+        tag: String,
     ) -> Result<(), QuickLendXError> {
-        validate_investor_investment(&env, &investor, investment_amount)
-    }
+        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
 
-    /// Check if investor is verified
-    pub fn is_investor_verified(env: Env, investor: Address) -> bool {
-        InvestorVerificationStorage::is_investor_verified(&env, &investor)
+        // Only the business owner can remove tags
+        invoice.business.require_auth();
+
+        // Remove the tag
+        invoice.remove_tag(tag.clone())?;
+
+        // Update the invoice
+        InvoiceStorage::update_invoice(&env, &invoice);
+
+        // Emit event
+        events::emit_invoice_tag_removed(&env, &invoice_id, &invoice.business, &tag);
+
+        // Update index
+        InvoiceStorage::remove_tag_index(&env, &tag, &invoice_id);
+
+        Ok(())
     }
 
-    /// Get escrow details for an invoice
-    pub fn get_escrow_details(
+    /// Get all tags for an invoice
+    pub fn get_invoice_tags(
         env: Env,
         invoice_id: BytesN<32>,
-    ) -> Result<payments::Escrow, QuickLendXError> {
-        EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)
+    ) -> Result<Vec<String>, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(invoice.get_tags())
     }
 
-    /// Get escrow status for an invoice
-    pub fn get_escrow_status(
+    /// Check if invoice has a specific tag
+    pub fn invoice_has_tag(
         env: Env,
         invoice_id: BytesN<32>,
-    ) -> Result<payments::EscrowStatus, QuickLendXError> {
-        let escrow = EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)?;
-        Ok(escrow.status)
+        tag: String,
+    ) -> Result<bool, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(invoice.has_tag(tag))
     }
 
-    /// Release escrow funds to business upon invoice verification
-    pub fn release_escrow_funds(env: Env, invoice_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        reentrancy::with_payment_guard(&env, || {
-            let escrow = EscrowStorage::get_escrow_by_invoice(&env, &invoice_id)
-                .ok_or(QuickLendXError::StorageKeyNotFound)?;
-
-            release_escrow(&env, &invoice_id)?;
-
-            emit_escrow_released(
-                &env,
-                &escrow.escrow_id,
-                &invoice_id,
-                &escrow.business,
-                escrow.amount,
-            );
-
-            Ok(())
-        })
-    }
+    // Dispute Resolution Functions
 
-    /// Refund escrow funds to investor if verification fails or as an explicit manual refund.
-    ///
-    /// Can be triggered by Admin or Business owner. Invoice must be Funded.
-    /// Protected by payment reentrancy guard.
-    pub fn refund_escrow_funds(
+    /// Create a dispute for an invoice
+    pub fn create_dispute(
         env: Env,
         invoice_id: BytesN<32>,
-        caller: Address,
+        creator: Address,
+        reason: String,
+        evidence: String,
     ) -> Result<(), QuickLendXError> {
-        reentrancy::with_payment_guard(&env, || do_refund_escrow_funds(&env, &invoice_id, &caller))
+        do_create_dispute(&env, &invoice_id, &creator, reason, evidence)
     }
 
-    ///== Notification Management Functions ==///
-
-    /// Get a notification by ID
-    pub fn get_notification(env: Env, notification_id: BytesN<32>) -> Option<Notification> {
-        NotificationSystem::get_notification(&env, &notification_id)
+    /// Put a dispute under review (admin function)
+    pub fn put_dispute_under_review(
+        env: Env,
+        invoice_id: BytesN<32>,
+        reviewer: Address,
+    ) -> Result<(), QuickLendXError> {
+        do_put_dispute_under_review(&env, &invoice_id, &reviewer)
     }
 
-    /// Update notification delivery status
-    pub fn update_notification_status(
+    /// Resolve a dispute (admin function)
+    pub fn resolve_dispute(
         env: Env,
-        notification_id: BytesN<32>,
-        status: NotificationDeliveryStatus,
+        invoice_id: BytesN<32>,
+        resolver: Address,
+        resolution: String,
     ) -> Result<(), QuickLendXError> {
-        NotificationSystem::update_notification_status(&env, &notification_id, status)
+        do_resolve_dispute(&env, &invoice_id, &resolver, resolution)
     }
 
-    /// Get all notifications for a user
-    pub fn get_user_notifications(env: Env, user: Address) -> Vec<BytesN<32>> {
-        NotificationSystem::get_user_notifications(&env, &user)
+    /// Get dispute details for an invoice
+    pub fn get_dispute_details(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<Option<invoice::Dispute>, QuickLendXError> {
+        do_get_dispute_details(&env, &invoice_id)
     }
 
-    /// Get user notification preferences
-    pub fn get_notification_preferences(env: Env, user: Address) -> NotificationPreferences {
-        NotificationSystem::get_user_preferences(&env, &user)
+    /// Get all invoices with disputes
+    pub fn get_invoices_with_disputes(env: Env) -> Vec<BytesN<32>> {
+        do_get_invoices_with_disputes(&env)
     }
 
-    /// Update user notification preferences
-    pub fn update_notification_preferences(
+    /// Get invoices by dispute status
+    pub fn get_invoices_by_dispute_status(
         env: Env,
-        user: Address,
-        preferences: NotificationPreferences,
-    ) -> Result<(), QuickLendXError> {
-        user.require_auth();
-        NotificationSystem::update_user_preferences(&env, &user, preferences);
-        Ok(())
+        dispute_status: DisputeStatus,
+    ) -> Vec<BytesN<32>> {
+        do_get_invoices_by_dispute_status(&env, dispute_status)
     }
 
-    /// Get notification statistics for a user
-    pub fn get_user_notification_stats(env: Env, user: Address) -> NotificationStats {
-        NotificationSystem::get_user_notification_stats(&env, &user)
+    /// Get dispute status for an invoice
+    pub fn get_invoice_dispute_status(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Result<DisputeStatus, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        Ok(invoice.dispute_status)
     }
 
-    /// Check for overdue invoices and send notifications (admin or automated process)
-    pub fn check_overdue_invoices(env: Env) -> Result<u32, QuickLendXError> {
-        Self::check_overdue_invoices_grace(env, Invoice::DEFAULT_GRACE_PERIOD)
+    /// Add a standard dispute resolution under `code` (admin only)
+    ///
+    /// `investor_share_bps` is the share of the escrow refunded to the investor when a dispute
+    /// is resolved with the template; the business is paid the rest.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidDisputeReason` if the description fails the content policy
+    /// * `InvalidAmount` if `investor_share_bps` exceeds 10,000
+    /// * `OperationNotAllowed` if `code` is taken or the registry is full
+    pub fn add_dispute_template(
+        env: Env,
+        code: u32,
+        description: String,
+        investor_share_bps: u32,
+    ) -> Result<dispute_templates::ResolutionTemplate, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        dispute_templates::DisputeTemplates::add(&env, code, description, investor_share_bps)
     }
 
-    /// Check for overdue invoices with a custom grace period (in seconds)
-    pub fn check_overdue_invoices_grace(
+    /// Change a dispute template's description and default split (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `InvalidDisputeReason`, `InvalidAmount` as for `add_dispute_template`
+    pub fn update_dispute_template(
         env: Env,
-        grace_period: u64,
-    ) -> Result<u32, QuickLendXError> {
-        let current_timestamp = env.ledger().timestamp();
-        let funded_invoices = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded);
-        let mut overdue_count = 0u32;
-
-        for invoice_id in funded_invoices.iter() {
-            if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
-                if invoice.is_overdue(current_timestamp) {
-                    let _ = NotificationSystem::notify_payment_overdue(&env, &invoice);
-                    overdue_count += 1;
-                }
-                let _ = invoice.check_and_handle_expiration(&env, grace_period)?;
-            }
-        }
-
-        Ok(overdue_count)
+        code: u32,
+        description: String,
+        investor_share_bps: u32,
+    ) -> Result<dispute_templates::ResolutionTemplate, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        dispute_templates::DisputeTemplates::update(&env, code, description, investor_share_bps)
     }
 
-    /// Check whether a specific invoice has expired and trigger default handling when necessary
-    pub fn check_invoice_expiration(
+    /// Retire a dispute template, or reinstate a retired one (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `InvalidStatus` if it is already in that state
+    pub fn set_dispute_template_active(
         env: Env,
-        invoice_id: BytesN<32>,
-        grace_period: Option<u64>,
-    ) -> Result<bool, QuickLendXError> {
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-        let grace = grace_period.unwrap_or(Invoice::DEFAULT_GRACE_PERIOD);
-        invoice.check_and_handle_expiration(&env, grace)
+        code: u32,
+        active: bool,
+    ) -> Result<dispute_templates::ResolutionTemplate, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        dispute_templates::DisputeTemplates::set_active(&env, code, active)
     }
 
-    /// Create a backup of all invoice data
-    pub fn create_backup(env: Env, description: String) -> Result<BytesN<32>, QuickLendXError> {
-        // Only admin can create backups
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
+    /// Get a dispute template
+    pub fn get_dispute_template(
+        env: Env,
+        code: u32,
+    ) -> Option<dispute_templates::ResolutionTemplate> {
+        dispute_templates::DisputeTemplates::get(&env, code)
+    }
 
-        // Get all invoices
-        let pending = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Pending);
-        let verified = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Verified);
-        let funded = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Funded);
-        let paid = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Paid);
-        let defaulted = InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Defaulted);
+    /// Every dispute template, active or not
+    pub fn get_dispute_templates(env: Env) -> Vec<dispute_templates::ResolutionTemplate> {
+        dispute_templates::DisputeTemplates::list(&env)
+    }
 
-        // Combine all invoices
-        let mut all_invoices = Vec::new(&env);
-        for status_vec in [pending, verified, funded, paid, defaulted].iter() {
-            for invoice_id in status_vec.iter() {
-                if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
-                    all_invoices.push_back(invoice);
-                }
-            }
+    /// Resolve a dispute under review with a template (admin only)
+    ///
+    /// The template's description becomes the resolution text and a structured outcome is
+    /// recorded. If the invoice is still funded its escrow is split by the template: the
+    /// business share is released and the investor share refunded, which marks the invoice
+    /// refunded.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set, `Unauthorized` if `resolver` is not the admin
+    /// * `StorageKeyNotFound` if there is no template under `code`
+    /// * `OperationNotAllowed` if the template is inactive or the invoice is frozen
+    /// * `InvoiceNotFound`, `DisputeNotUnderReview` as for `resolve_dispute`
+    pub fn resolve_dispute_with_template(
+        env: Env,
+        invoice_id: BytesN<32>,
+        resolver: Address,
+        code: u32,
+    ) -> Result<dispute_templates::DisputeOutcome, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        if resolver != admin {
+            return Err(QuickLendXError::Unauthorized);
         }
+        reentrancy::with_payment_guard(&env, || {
+            let template = dispute_templates::DisputeTemplates::get_active(&env, code)?;
+            do_resolve_dispute(&env, &invoice_id, &resolver, template.description.clone())?;
+            dispute_templates::DisputeTemplates::record_outcome(
+                &env,
+                &invoice_id,
+                &resolver,
+                template,
+            )
+        })
+    }
 
-        // Create backup
-        let backup_id = BackupStorage::generate_backup_id(&env);
-        let backup = Backup {
-            backup_id: backup_id.clone(),
-            timestamp: env.ledger().timestamp(),
-            description,
-            invoice_count: all_invoices.len() as u32,
-            status: BackupStatus::Active,
-        };
+    /// Structured outcome of a dispute resolved with a template
+    pub fn get_dispute_outcome(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<dispute_templates::DisputeOutcome> {
+        dispute_templates::DisputeTemplates::get_outcome(&env, &invoice_id)
+    }
 
-        // Store backup and data
-        BackupStorage::store_backup(&env, &backup);
-        BackupStorage::store_backup_data(&env, &backup_id, &all_invoices);
-        BackupStorage::add_to_backup_list(&env, &backup_id);
+    // Analytics and Reporting Functions
 
-        // Clean up old backups (keep last 5)
-        BackupStorage::cleanup_old_backups(&env, 5)?;
+    /// Get current platform metrics
+    pub fn get_platform_metrics(env: Env) -> Result<PlatformMetrics, QuickLendXError> {
+        AnalyticsCalculator::calculate_platform_metrics(&env)
+    }
 
-        // Emit event
-        events::emit_backup_created(&env, &backup_id, backup.invoice_count);
+    /// Compact snapshot for reconciling off-chain systems with the contract
+    ///
+    /// Returns invoice counts per status, escrowed and outstanding totals and the tracked
+    /// balance per currency, and the latest invoice, bid, investment, escrow, audit and
+    /// config-change sequence numbers.
+    pub fn export_state_digest(env: Env) -> StateDigest {
+        reconciliation::export_state_digest(&env)
+    }
 
-        Ok(backup_id)
+    /// Check that the escrows held in `currency` are backed by the contract's balances
+    ///
+    /// Sums the escrows still held or frozen and compares them with the tracked segregated
+    /// balance and the contract's actual token balance. Emits an alert event when the escrows
+    /// are not covered.
+    pub fn reconcile_escrows(env: Env, currency: Address) -> EscrowReconciliation {
+        reconciliation::reconcile_escrows(&env, &currency)
     }
 
-    /// Restore invoice data from a backup
-    pub fn restore_backup(env: Env, backup_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        // Only admin can restore backups
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
+    /// Update platform metrics (admin or relayer)
+    pub fn update_platform_metrics(env: Env, caller: Address) -> Result<(), QuickLendXError> {
+        AdminStorage::require_admin_or_relayer(&env, &caller)?;
 
-        // Validate backup first
-        BackupStorage::validate_backup(&env, &backup_id)?;
+        let metrics = AnalyticsCalculator::calculate_platform_metrics(&env)?;
+        AnalyticsStorage::store_platform_metrics(&env, &metrics);
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::MetricsSnapshot,
+            Some(caller),
+            1,
+            metrics.total_invoices,
+            0,
+        );
 
-        // Get backup data
-        let invoices = BackupStorage::get_backup_data(&env, &backup_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        // Emit event
+        events::emit_platform_metrics_updated(
+            &env,
+            metrics.total_invoices,
+            metrics.total_volume,
+            metrics.total_fees_collected,
+            metrics.success_rate,
+        );
 
-        // Clear current invoice data
-        Self::clear_all_invoices(&env)?;
+        Ok(())
+    }
 
-        // Restore invoices
-        for invoice in invoices.iter() {
-            InvoiceStorage::store_invoice(&env, &invoice);
-        }
+    /// Get performance metrics
+    pub fn get_performance_metrics(env: Env) -> Result<PerformanceMetrics, QuickLendXError> {
+        AnalyticsCalculator::calculate_performance_metrics(&env)
+    }
+
+    /// Update performance metrics (admin or relayer)
+    pub fn update_performance_metrics(env: Env, caller: Address) -> Result<(), QuickLendXError> {
+        AdminStorage::require_admin_or_relayer(&env, &caller)?;
+
+        let metrics = AnalyticsCalculator::calculate_performance_metrics(&env)?;
+        AnalyticsStorage::store_performance_metrics(&env, &metrics);
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::MetricsSnapshot,
+            Some(caller),
+            1,
+            0,
+            0,
+        );
 
         // Emit event
-        events::emit_backup_restored(&env, &backup_id, invoices.len() as u32);
+        events::emit_performance_metrics_updated(
+            &env,
+            metrics.average_settlement_time,
+            metrics.transaction_success_rate,
+            metrics.user_satisfaction_score,
+        );
 
         Ok(())
     }
 
-    /// Validate a backup's integrity
-    pub fn validate_backup(env: Env, backup_id: BytesN<32>) -> Result<bool, QuickLendXError> {
-        let result = BackupStorage::validate_backup(&env, &backup_id).is_ok();
-        events::emit_backup_validated(&env, &backup_id, result);
-        Ok(result)
+    /// Get keeper activity (overdue checks, bid cleanups, bidding finalization, metrics
+    /// snapshots) within a period
+    pub fn get_keeper_stats(env: Env, period: TimePeriod) -> keeper::KeeperStats {
+        keeper::KeeperStorage::get_stats(&env, period)
     }
 
-    /// Archive a backup (mark as no longer active)
-    pub fn archive_backup(env: Env, backup_id: BytesN<32>) -> Result<(), QuickLendXError> {
-        // Only admin can archive backups
+    /// Configure keeper rewards for expired-bid cleanup (admin only)
+    ///
+    /// `fee_share_bps` of each platform fee collected at settlement is diverted into a
+    /// per-currency reward pool; keepers calling `keeper_cleanup_expired_bids` earn
+    /// `reward_per_bid` per expired bid, up to `max_reward_per_call`.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if a reward is negative or `fee_share_bps` exceeds 10,000
+    pub fn set_keeper_reward_config(
+        env: Env,
+        reward_per_bid: i128,
+        max_reward_per_call: i128,
+        fee_share_bps: u32,
+    ) -> Result<(), QuickLendXError> {
         let admin =
             BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
         admin.require_auth();
+        let old = keeper::KeeperStorage::get_reward_config(&env);
+        let config = keeper::KeeperRewardConfig {
+            reward_per_bid,
+            max_reward_per_call,
+            fee_share_bps,
+        };
+        keeper::KeeperStorage::set_reward_config(&env, &config)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::KeeperRewardPerBid,
+            ConfigValue::I128(old.reward_per_bid),
+            ConfigValue::I128(reward_per_bid),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::KeeperMaxRewardPerCall,
+            ConfigValue::I128(old.max_reward_per_call),
+            ConfigValue::I128(max_reward_per_call),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::KeeperFeeShareBps,
+            ConfigValue::U32(old.fee_share_bps),
+            ConfigValue::U32(fee_share_bps),
+            &admin,
+        );
+        Ok(())
+    }
 
-        let mut backup = BackupStorage::get_backup(&env, &backup_id)
-            .ok_or(QuickLendXError::StorageKeyNotFound)?;
-
-        backup.status = BackupStatus::Archived;
-        BackupStorage::update_backup(&env, &backup);
-        BackupStorage::remove_from_backup_list(&env, &backup_id);
-
-        events::emit_backup_archived(&env, &backup_id);
-
+    /// Set the reward a keeper earns per invoice settled through `auto_settle_due` (admin only)
+    ///
+    /// Paid from the same reward pool as cleanup rewards; 0 disables it.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `reward` is negative
+    pub fn set_keeper_settlement_reward(env: Env, reward: i128) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = keeper::KeeperStorage::get_settlement_reward(&env);
+        keeper::KeeperStorage::set_settlement_reward(&env, reward)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::KeeperSettlementReward,
+            ConfigValue::I128(old),
+            ConfigValue::I128(reward),
+            &admin,
+        );
         Ok(())
     }
 
-    /// Get all available backups
-    pub fn get_backups(env: Env) -> Vec<BytesN<32>> {
-        BackupStorage::get_all_backups(&env)
+    /// Get the keeper reward per auto-settled invoice
+    pub fn get_keeper_settlement_reward(env: Env) -> i128 {
+        keeper::KeeperStorage::get_settlement_reward(&env)
     }
 
-    /// Get backup details
-    pub fn get_backup_details(env: Env, backup_id: BytesN<32>) -> Option<Backup> {
-        BackupStorage::get_backup(&env, &backup_id)
+    /// Get the keeper reward configuration
+    pub fn get_keeper_reward_config(env: Env) -> keeper::KeeperRewardConfig {
+        keeper::KeeperStorage::get_reward_config(&env)
     }
 
-    /// Internal function to clear all invoice data
-    fn clear_all_invoices(env: &Env) -> Result<(), QuickLendXError> {
-        // Clear all status lists
-        for status in [
-            InvoiceStatus::Pending,
-            InvoiceStatus::Verified,
-            InvoiceStatus::Funded,
-            InvoiceStatus::Paid,
-            InvoiceStatus::Defaulted,
-            InvoiceStatus::Cancelled,
-        ]
-        .iter()
-        {
-            let invoices = InvoiceStorage::get_invoices_by_status(env, status);
-            for invoice_id in invoices.iter() {
-                // Remove from status list
-                InvoiceStorage::remove_from_status_invoices(env, status, &invoice_id);
-                // Remove the invoice itself
-                env.storage().instance().remove(&invoice_id);
-            }
-        }
+    /// Get the keeper reward pool balance for a currency
+    pub fn get_keeper_reward_pool(env: Env, currency: Address) -> i128 {
+        keeper::KeeperStorage::get_reward_pool(&env, &currency)
+    }
 
-        // Clear all business invoices
-        let verified_businesses = BusinessVerificationStorage::get_verified_businesses(env);
-        for business in verified_businesses.iter() {
-            let _ = InvoiceStorage::get_business_invoices(env, &business);
-            let key = (symbol_short!("business"), business.clone());
-            env.storage().instance().remove(&key);
-        }
+    /// Register a broker who introduces invoices, or update its terms (admin only)
+    ///
+    /// The broker earns `fee_bps` of the platform fee (after the keeper reward share) of each
+    /// invoice attributed to it, capped at `max_fee_per_invoice` when set.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidFeeBasisPoints` if `fee_bps` exceeds 5,000
+    /// * `InvalidAmount` if the cap is negative
+    pub fn register_broker(
+        env: Env,
+        broker: Address,
+        fee_bps: u32,
+        max_fee_per_invoice: Option<i128>,
+    ) -> Result<referral::Broker, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        referral::Referrals::register_broker(&env, &broker, fee_bps, max_fee_per_invoice)
+    }
 
-        Ok(())
+    /// Stop paying a broker on future settlements (admin only); earnings are kept
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the broker is not registered
+    pub fn deactivate_broker(env: Env, broker: Address) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        referral::Referrals::deactivate_broker(&env, &broker)
     }
-    /// Get audit trail for an invoice
-    pub fn get_invoice_audit_trail(env: Env, invoice_id: BytesN<32>) -> Vec<BytesN<32>> {
-        AuditStorage::get_invoice_audit_trail(&env, &invoice_id)
+
+    /// Get a broker's terms and referral counts
+    pub fn get_broker(env: Env, broker: Address) -> Option<referral::Broker> {
+        referral::Referrals::get_broker(&env, &broker)
     }
 
-    /// Get audit entry by ID
-    pub fn get_audit_entry(
+    /// Get the broker fees paid to a broker so far, by currency
+    pub fn get_broker_earnings(env: Env, broker: Address) -> Map<Address, i128> {
+        referral::Referrals::get_earnings(&env, &broker)
+    }
+
+    /// Attribute an invoice to the broker who introduced it (business only, once)
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound` if the invoice does not exist
+    /// * `OperationNotAllowed` if the invoice is frozen or already attributed, or the broker
+    ///   is deactivated
+    /// * `InvalidStatus` if the invoice is already funded
+    /// * `StorageKeyNotFound` if the broker is not registered
+    pub fn set_invoice_introducer(
         env: Env,
-        audit_id: BytesN<32>,
-    ) -> Result<AuditLogEntry, QuickLendXError> {
-        AuditStorage::get_audit_entry(&env, &audit_id).ok_or(QuickLendXError::AuditLogNotFound)
+        invoice_id: BytesN<32>,
+        broker: Address,
+    ) -> Result<(), QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        InvoiceFreezes::ensure_not_frozen(&env, &invoice_id)?;
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        referral::Referrals::tag_invoice(&env, &invoice_id, &broker)
     }
 
-    /// Query audit logs with filters
-    pub fn query_audit_logs(env: Env, filter: AuditQueryFilter, limit: u32) -> Vec<AuditLogEntry> {
-        let results = AuditStorage::query_audit_logs(&env, &filter, limit);
-        emit_audit_query(
-            &env,
-            String::from_str(&env, "query_audit_logs"),
-            results.len() as u32,
-        );
-        results
+    /// Get the broker an invoice is attributed to
+    pub fn get_invoice_introducer(env: Env, invoice_id: BytesN<32>) -> Option<Address> {
+        referral::Referrals::get_invoice_broker(&env, &invoice_id)
     }
 
-    /// Get audit statistics
-    pub fn get_audit_stats(env: Env) -> AuditStats {
-        AuditStorage::get_audit_stats(&env)
+    /// Get funded invoices bucketed by days past due (current, 1-30, 31-60, 61-90, 90+),
+    /// for one business or, when `business` is `None`, the whole platform
+    pub fn get_aging_report(env: Env, business: Option<Address>) -> analytics::AgingReport {
+        AnalyticsCalculator::calculate_aging_report(&env, business)
     }
 
-    /// Validate audit log integrity for an invoice
-    pub fn validate_invoice_audit_integrity(
+    /// Get repayments of funded invoices due between `from` and `to` (inclusive), ordered by
+    /// due date, for one investor or, when `investor` is `None`, the whole platform
+    pub fn get_expected_cashflows(
         env: Env,
-        invoice_id: BytesN<32>,
-    ) -> Result<bool, QuickLendXError> {
-        let is_valid = AuditStorage::validate_invoice_audit_integrity(&env, &invoice_id)?;
-        emit_audit_validation(&env, &invoice_id, is_valid);
-        Ok(is_valid)
+        from: u64,
+        to: u64,
+        investor: Option<Address>,
+    ) -> Vec<analytics::ExpectedCashflow> {
+        AnalyticsCalculator::calculate_expected_cashflows(&env, from, to, investor)
     }
 
-    /// Get audit entries by operation type
-    pub fn get_audit_entries_by_operation(env: Env, operation: AuditOperation) -> Vec<BytesN<32>> {
-        AuditStorage::get_audit_entries_by_operation(&env, &operation)
+    /// Get insurance premiums, claims and loss ratios per provider within a period, with the
+    /// coverage currently outstanding on active investments
+    pub fn get_insurance_stats(env: Env, period: TimePeriod) -> insurance::InsuranceStats {
+        insurance::InsuranceLog::get_stats(&env, period)
     }
 
-    /// Get audit entries by actor
-    pub fn get_audit_entries_by_actor(env: Env, actor: Address) -> Vec<BytesN<32>> {
-        AuditStorage::get_audit_entries_by_actor(&env, &actor)
+    /// Apply to become an insurance provider with the most coverage `provider` wants to
+    /// underwrite (provider only)
+    ///
+    /// The provider can be named on coverage once the admin approves it with `approve_provider`.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if `capacity` is not positive
+    /// * `OperationNotAllowed` if the provider is already registered or the registry is full
+    pub fn register_insurance_provider(
+        env: Env,
+        provider: Address,
+        capacity: i128,
+    ) -> Result<insurance_registry::InsuranceProvider, QuickLendXError> {
+        provider.require_auth();
+        insurance_registry::InsuranceProviderRegistry::register(&env, &provider, capacity)
     }
 
-    // Category and Tag Management Functions
+    /// Approve a registered insurance provider, or reinstate a suspended one (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the provider is not registered
+    /// * `InvalidStatus` if it is already approved
+    pub fn approve_provider(
+        env: Env,
+        provider: Address,
+    ) -> Result<insurance_registry::InsuranceProvider, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_registry::InsuranceProviderRegistry::set_status(
+            &env,
+            &provider,
+            insurance_registry::InsuranceProviderStatus::Approved,
+        )
+    }
 
-    /// Get invoices by category
-    pub fn get_invoices_by_category(
+    /// Suspend an approved insurance provider so it cannot be named on new coverage (admin only)
+    ///
+    /// Existing coverage stays in force.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the provider is not registered
+    /// * `InvalidStatus` if it is not approved
+    pub fn suspend_provider(
         env: Env,
-        category: invoice::InvoiceCategory,
-    ) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_category(&env, &category)
+        provider: Address,
+    ) -> Result<insurance_registry::InsuranceProvider, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_registry::InsuranceProviderRegistry::set_status(
+            &env,
+            &provider,
+            insurance_registry::InsuranceProviderStatus::Suspended,
+        )
+    }
+
+    /// Change the most coverage a registered insurance provider may have outstanding (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if the provider is not registered
+    /// * `InvalidAmount` if `capacity` is not positive
+    pub fn set_provider_capacity(
+        env: Env,
+        provider: Address,
+        capacity: i128,
+    ) -> Result<insurance_registry::InsuranceProvider, QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_registry::InsuranceProviderRegistry::set_capacity(&env, &provider, capacity)
     }
 
-    /// Get invoices by category and status
-    pub fn get_invoices_by_cat_status(
+    /// Get a registered insurance provider
+    pub fn get_insurance_provider(
         env: Env,
-        category: invoice::InvoiceCategory,
-        status: InvoiceStatus,
-    ) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_category_and_status(&env, &category, &status)
+        provider: Address,
+    ) -> Option<insurance_registry::InsuranceProvider> {
+        insurance_registry::InsuranceProviderRegistry::get(&env, &provider)
     }
 
-    /// Get invoices by tag
-    pub fn get_invoices_by_tag(env: Env, tag: String) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_tag(&env, &tag)
+    /// Get a registered insurance provider's capacity, outstanding coverage and remaining
+    /// capacity
+    pub fn get_insurance_provider_capacity(
+        env: Env,
+        provider: Address,
+    ) -> Option<insurance_registry::ProviderCapacity> {
+        insurance_registry::InsuranceProviderRegistry::get_capacity(&env, &provider)
     }
 
-    /// Get invoices by multiple tags (AND logic)
-    pub fn get_invoices_by_tags(env: Env, tags: Vec<String>) -> Vec<BytesN<32>> {
-        InvoiceStorage::get_invoices_by_tags(&env, &tags)
+    /// Get every registered insurance provider, in registration order
+    pub fn get_insurance_providers(env: Env) -> Vec<insurance_registry::InsuranceProvider> {
+        insurance_registry::InsuranceProviderRegistry::list(&env)
     }
 
-    /// Get invoice count by category
-    pub fn get_invoice_count_by_category(env: Env, category: invoice::InvoiceCategory) -> u32 {
-        InvoiceStorage::get_invoice_count_by_category(&env, &category)
+    /// Record the pool assets backing an insurance provider's coverage (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `reserve` is negative
+    pub fn set_insurance_reserve(
+        env: Env,
+        provider: Address,
+        reserve: i128,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance::InsuranceReserves::set_reserve(&env, &provider, reserve)
     }
 
-    /// Get invoice count by tag
-    pub fn get_invoice_count_by_tag(env: Env, tag: String) -> u32 {
-        InvoiceStorage::get_invoice_count_by_tag(&env, &tag)
+    /// Set how much coverage a provider may sell per unit of reserve, in basis points
+    /// (admin only)
+    ///
+    /// 20,000 lets a provider owe twice its reserves; 0 disables the limit.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    pub fn set_insurance_multiplier(env: Env, multiplier_bps: u32) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance::InsuranceReserves::set_multiplier_bps(&env, multiplier_bps);
+        Ok(())
     }
 
-    /// Get all available categories
-    pub fn get_all_categories(env: Env) -> Vec<invoice::InvoiceCategory> {
-        InvoiceStorage::get_all_categories(&env)
+    /// Get a provider's reserves, active coverage, coverage limit and solvency ratio
+    pub fn get_insurance_solvency(env: Env, provider: Address) -> insurance::InsuranceSolvency {
+        insurance::InsuranceReserves::get_solvency(&env, &provider)
     }
 
-    /// Update invoice category (business owner only)
-    pub fn update_invoice_category(
+    /// Stake into the insurance pool for `currency` (provider only)
+    ///
+    /// The stake earns a share of the premiums paid into the pool and bears a share of the
+    /// default claims paid from it, and is locked for the configured lockup.
+    ///
+    /// # Returns
+    /// * `Ok(stake)` - the provider's stake after the deposit
+    ///
+    /// # Errors
+    /// * `InvalidAmount`, `CurrencyNotWhitelisted`
+    /// * `OperationNotAllowed` on re-entry, transfer errors
+    pub fn stake_insurance_pool(
         env: Env,
-        invoice_id: BytesN<32>,
-        new_category: invoice::InvoiceCategory,
-    ) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-
-        // Only the business owner can update the category
-        invoice.business.require_auth();
-
-        let old_category = invoice.category.clone();
-        invoice.update_category(new_category.clone());
-
-        // Validate the new category
-        verification::validate_invoice_category(&new_category)?;
-
-        // Update the invoice
-        InvoiceStorage::update_invoice(&env, &invoice);
-
-        // Emit event
-        events::emit_invoice_category_updated(
-            &env,
-            &invoice_id,
-            &invoice.business,
-            &old_category,
-            &new_category,
-        );
-
-        // Update indexes
-        InvoiceStorage::remove_category_index(&env, &old_category, &invoice_id);
-        InvoiceStorage::add_category_index(&env, &new_category, &invoice_id);
-
-        Ok(())
+        provider: Address,
+        currency: Address,
+        amount: i128,
+    ) -> Result<insurance_pool::InsuranceStake, QuickLendXError> {
+        provider.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            insurance_pool::InsurancePools::stake(&env, &provider, &currency, amount)
+        })
     }
 
-    /// Add tag to invoice (business owner only)
-    pub fn add_invoice_tag(
+    /// Withdraw `amount` of a provider's stake from the insurance pool for `currency` (provider
+    /// only)
+    ///
+    /// # Returns
+    /// * `Ok(stake)` - the provider's remaining stake
+    ///
+    /// # Errors
+    /// * `InvalidAmount`
+    /// * `OperationNotAllowed` while the stake is locked, on re-entry
+    /// * `InsufficientFunds` if `amount` exceeds the stake's value, or the pool would drop below
+    ///   its active coverage and unpaid claims
+    /// * Transfer errors
+    pub fn unstake_insurance_pool(
         env: Env,
-        invoice_id: BytesN<32>,
-        tag: String,
-    ) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-
-        // Only the business owner can add tags
-        invoice.business.require_auth();
+        provider: Address,
+        currency: Address,
+        amount: i128,
+    ) -> Result<insurance_pool::InsuranceStake, QuickLendXError> {
+        provider.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            insurance_pool::InsurancePools::unstake(&env, &provider, &currency, amount)
+        })
+    }
 
-        // Add the tag
-        invoice.add_tag(&env, tag.clone())?;
+    /// Get a provider's stake in the insurance pool for `currency`
+    pub fn get_insurance_stake(
+        env: Env,
+        provider: Address,
+        currency: Address,
+    ) -> insurance_pool::InsuranceStake {
+        insurance_pool::InsurancePools::get_stake(&env, &provider, &currency)
+    }
 
-        // Update the invoice
-        InvoiceStorage::update_invoice(&env, &invoice);
+    /// Get the insurance pool for `currency` and how much of it is committed to coverage
+    pub fn get_insurance_pool(
+        env: Env,
+        currency: Address,
+    ) -> insurance_pool::InsurancePoolUtilization {
+        insurance_pool::InsurancePools::get_utilization(&env, &currency)
+    }
 
-        // Emit event
-        events::emit_invoice_tag_added(&env, &invoice_id, &invoice.business, &tag);
+    /// Get the insurance pool totals for `currency`: premiums collected and claims paid
+    pub fn get_insurance_pool_state(
+        env: Env,
+        currency: Address,
+    ) -> insurance_pool::InsurancePoolState {
+        insurance_pool::InsurancePools::get_pool(&env, &currency)
+    }
 
-        // Update index
-        InvoiceStorage::add_tag_index(&env, &tag, &invoice_id);
+    /// Set the share of each insurance premium routed to the protocol treasury, in basis points
+    /// (admin only)
+    ///
+    /// The rest of the premium goes to the insurance pool.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `fee_bps` exceeds `MAX_PREMIUM_FEE_BPS` (5,000)
+    pub fn set_insurance_premium_fee(env: Env, fee_bps: u32) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_pool::InsurancePools::set_premium_fee_bps(&env, fee_bps)
+    }
 
-        Ok(())
+    /// Get the share of each insurance premium routed to the protocol treasury, in basis points
+    pub fn get_insurance_premium_fee(env: Env) -> u32 {
+        insurance_pool::InsurancePools::get_premium_fee_bps(&env)
     }
 
-    /// Remove tag from invoice (business owner only)
-    pub fn remove_invoice_tag(
+    /// Set the risk-based insurance premium schedule (admin only)
+    ///
+    /// Applies to policies bought from now on; existing coverage keeps the premium it was
+    /// charged.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if a rate or adjustment exceeds `MAX_PREMIUM_RATE_BPS` (5,000) or a
+    ///   factor has more than 10 entries
+    /// * `OperationNotAllowed` if bands are not in ascending order or a category repeats
+    pub fn set_insurance_premium_schedule(
         env: Env,
-        invoice_id: BytesN<32>,
-        tag: String,
+        schedule: premium_schedule::PremiumSchedule,
     ) -> Result<(), QuickLendXError> {
-        let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-
-        // Only the business owner can remove tags
-        invoice.business.require_auth();
-
-        // Remove the tag
-        invoice.remove_tag(tag.clone())?;
-
-        // Update the invoice
-        InvoiceStorage::update_invoice(&env, &invoice);
-
-        // Emit event
-        events::emit_invoice_tag_removed(&env, &invoice_id, &invoice.business, &tag);
-
-        // Update index
-        InvoiceStorage::remove_tag_index(&env, &tag, &invoice_id);
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        premium_schedule::PremiumSchedules::set(&env, &schedule)
+    }
 
-        Ok(())
+    /// Get the insurance premium schedule; a flat 2% of the covered amount unless the admin set
+    /// one
+    pub fn get_insurance_premium_schedule(env: Env) -> premium_schedule::PremiumSchedule {
+        premium_schedule::PremiumSchedules::get(&env)
     }
 
-    /// Get all tags for an invoice
-    pub fn get_invoice_tags(
+    /// Quote the premium `add_investment_insurance` would charge for `coverage_percentage` of an
+    /// investment, with the adjustments that make up its rate
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment does not exist
+    /// * `InvoiceNotFound` if its invoice does not exist
+    /// * `InvalidCoveragePercentage` if `coverage_percentage` exceeds 100
+    /// * `InvalidAmount` if nothing would be covered
+    pub fn quote_insurance_premium(
         env: Env,
-        invoice_id: BytesN<32>,
-    ) -> Result<Vec<String>, QuickLendXError> {
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+        investment_id: BytesN<32>,
+        coverage_percentage: u32,
+    ) -> Result<premium_schedule::PremiumQuote, QuickLendXError> {
+        let investment = InvestmentStorage::get_investment(&env, &investment_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        let invoice = InvoiceStorage::get_invoice(&env, &investment.invoice_id)
             .ok_or(QuickLendXError::InvoiceNotFound)?;
-        Ok(invoice.get_tags())
+        premium_schedule::PremiumSchedules::quote(&env, &investment, &invoice, coverage_percentage)
     }
 
-    /// Check if invoice has a specific tag
-    pub fn invoice_has_tag(
-        env: Env,
-        invoice_id: BytesN<32>,
-        tag: String,
-    ) -> Result<bool, QuickLendXError> {
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-        Ok(invoice.has_tag(tag))
+    /// Set the share of the unused premium kept when coverage is cancelled, in basis points
+    /// (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `fee_bps` exceeds 10,000
+    pub fn set_insurance_cancellation_fee(env: Env, fee_bps: u32) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_pool::InsurancePools::set_cancellation_fee_bps(&env, fee_bps)
     }
 
-    // Dispute Resolution Functions
+    /// Get the share of the unused premium kept when coverage is cancelled, in basis points
+    pub fn get_insurance_cancellation_fee(env: Env) -> u32 {
+        insurance_pool::InsurancePools::get_cancellation_fee_bps(&env)
+    }
 
-    /// Create a dispute for an invoice
-    pub fn create_dispute(
+    /// Require insurance on invoices from businesses below a credit score, or remove the
+    /// requirement with `None` (admin only)
+    ///
+    /// Applies to invoices verified afterwards; each carries its requirement in
+    /// `required_insurance_pct`.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `min_credit_score` is not 1-10,000
+    /// * `InvalidCoveragePercentage` if `min_coverage_percentage` is not 1-100
+    pub fn set_insurance_requirement(
         env: Env,
-        invoice_id: BytesN<32>,
-        creator: Address,
-        reason: String,
-        evidence: String,
+        policy: Option<required_insurance::InsuranceRequirement>,
     ) -> Result<(), QuickLendXError> {
-        do_create_dispute(&env, &invoice_id, &creator, reason, evidence)
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        required_insurance::InsuranceRequirements::set_policy(&env, policy)
     }
 
-    /// Put a dispute under review (admin function)
-    pub fn put_dispute_under_review(
-        env: Env,
-        invoice_id: BytesN<32>,
-        reviewer: Address,
-    ) -> Result<(), QuickLendXError> {
-        do_put_dispute_under_review(&env, &invoice_id, &reviewer)
+    /// Get the insurance requirement for low-credit businesses, if one is set
+    pub fn get_insurance_requirement(env: Env) -> Option<required_insurance::InsuranceRequirement> {
+        required_insurance::InsuranceRequirements::get_policy(&env)
     }
 
-    /// Resolve a dispute (admin function)
-    pub fn resolve_dispute(
+    /// Get a business's credit score in basis points, as compared with the insurance requirement
+    pub fn get_business_credit_score(env: Env, business: Address) -> u32 {
+        risk::business_credit_score(&env, &business)
+    }
+
+    /// Attach the coverage to buy if a bid is accepted, or clear it with `None` (investor only)
+    ///
+    /// Required for bids on invoices with `required_insurance_pct`, which can only be accepted
+    /// with at least that much coverage attached. The premium is charged at acceptance.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the bid does not exist
+    /// * `BidNotPlaced` if the bid is no longer placed
+    /// * `InvalidCoveragePercentage` if the coverage is not 1-100
+    pub fn set_bid_insurance(
         env: Env,
-        invoice_id: BytesN<32>,
-        resolver: Address,
-        resolution: String,
+        bid_id: BytesN<32>,
+        election: Option<required_insurance::BidInsurance>,
     ) -> Result<(), QuickLendXError> {
-        do_resolve_dispute(&env, &invoice_id, &resolver, resolution)
+        let bid = BidStorage::get_bid(&env, &bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        bid.investor.require_auth();
+        if bid.status != BidStatus::Placed {
+            return Err(QuickLendXError::BidNotPlaced);
+        }
+        required_insurance::InsuranceRequirements::set_bid_insurance(&env, &bid_id, election)
     }
 
-    /// Get dispute details for an invoice
-    pub fn get_dispute_details(
+    /// Get the coverage attached to a bid, if any
+    pub fn get_bid_insurance(
         env: Env,
-        invoice_id: BytesN<32>,
-    ) -> Result<Option<invoice::Dispute>, QuickLendXError> {
-        do_get_dispute_details(&env, &invoice_id)
+        bid_id: BytesN<32>,
+    ) -> Option<required_insurance::BidInsurance> {
+        required_insurance::InsuranceRequirements::get_bid_insurance(&env, &bid_id)
     }
 
-    /// Get all invoices with disputes
-    pub fn get_invoices_with_disputes(env: Env) -> Vec<BytesN<32>> {
-        do_get_invoices_with_disputes(&env)
+    /// Set how long new insurance pool stakes stay locked (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `seconds` exceeds 90 days
+    pub fn set_insurance_pool_lockup(env: Env, seconds: u64) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        insurance_pool::InsurancePools::set_lockup(&env, seconds)
     }
 
-    /// Get invoices by dispute status
-    pub fn get_invoices_by_dispute_status(
-        env: Env,
-        dispute_status: DisputeStatus,
-    ) -> Vec<BytesN<32>> {
-        do_get_invoices_by_dispute_status(&env, dispute_status)
+    /// Get the insurance pool stake lockup in seconds
+    pub fn get_insurance_pool_lockup(env: Env) -> u64 {
+        insurance_pool::InsurancePools::get_lockup(&env)
     }
 
-    /// Get dispute status for an invoice
-    pub fn get_invoice_dispute_status(
+    /// Pay the unpaid part of a default claim from the currency's insurance pool, as far as the
+    /// pool reaches (anyone may call)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `OperationNotAllowed` on re-entry, transfer errors
+    pub fn pay_insurance_claim(
         env: Env,
-        invoice_id: BytesN<32>,
-    ) -> Result<DisputeStatus, QuickLendXError> {
-        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
-            .ok_or(QuickLendXError::InvoiceNotFound)?;
-        Ok(invoice.dispute_status)
+        investment_id: BytesN<32>,
+    ) -> Result<insurance::InsuranceClaim, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            insurance::InsuranceClaims::pay(&env, &investment_id)
+        })
     }
 
-    // Analytics and Reporting Functions
-
-    /// Get current platform metrics
-    pub fn get_platform_metrics(env: Env) -> Result<PlatformMetrics, QuickLendXError> {
-        AnalyticsCalculator::calculate_platform_metrics(&env)
+    /// Get the default claim on an insured investment, if one was filed
+    pub fn get_insurance_claim(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Option<insurance::InsuranceClaim> {
+        insurance::InsuranceClaims::get(&env, &investment_id)
     }
 
-    /// Update platform metrics (admin only)
-    pub fn update_platform_metrics(env: Env) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+    /// Require default claims to be filed by the investor and reviewed by the provider or an
+    /// arbitrator, or pay them automatically again with `None` (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if the review period is zero or longer than 30 days
+    pub fn set_insurance_claim_adjudication(
+        env: Env,
+        adjudication: Option<insurance::ClaimAdjudication>,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
         admin.require_auth();
+        insurance::InsuranceClaims::set_adjudication(&env, adjudication)
+    }
 
-        let metrics = AnalyticsCalculator::calculate_platform_metrics(&env)?;
-        AnalyticsStorage::store_platform_metrics(&env, &metrics);
-
-        // Emit event
-        events::emit_platform_metrics_updated(
-            &env,
-            metrics.total_invoices,
-            metrics.total_volume,
-            metrics.total_fees_collected,
-            metrics.success_rate,
-        );
-
-        Ok(())
+    /// Get the claim review settings, if claims are adjudicated
+    pub fn get_insurance_claim_adjudication(env: Env) -> Option<insurance::ClaimAdjudication> {
+        insurance::InsuranceClaims::get_adjudication(&env)
     }
 
-    /// Get performance metrics
-    pub fn get_performance_metrics(env: Env) -> Result<PerformanceMetrics, QuickLendXError> {
-        AnalyticsCalculator::calculate_performance_metrics(&env)
+    /// File the claim on a defaulted investment's coverage (investor only)
+    ///
+    /// The claim waits for review when claims are adjudicated and is paid straight away
+    /// otherwise.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment does not exist or has no coverage to claim
+    /// * `Unauthorized` if `investor` did not make the investment
+    /// * `InvalidStatus` if the investment has not defaulted
+    /// * `OperationNotAllowed` if a claim was already filed, or on re-entry
+    pub fn file_insurance_claim(
+        env: Env,
+        investor: Address,
+        investment_id: BytesN<32>,
+    ) -> Result<insurance::InsuranceClaim, QuickLendXError> {
+        investor.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            insurance::InsuranceClaims::file_for_investor(&env, &investor, &investment_id)
+        })
     }
 
-    /// Update performance metrics (admin only)
-    pub fn update_performance_metrics(env: Env) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
+    /// Approve a pending claim and pay it from the insurance pool (provider or arbitrator)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim was already resolved
+    /// * `Unauthorized` if `reviewer` is neither the claim's provider nor the arbitrator
+    /// * `OperationNotAllowed` if the review deadline has passed, or on re-entry
+    pub fn approve_insurance_claim(
+        env: Env,
+        reviewer: Address,
+        investment_id: BytesN<32>,
+    ) -> Result<insurance::InsuranceClaim, QuickLendXError> {
+        reviewer.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            insurance::InsuranceClaims::resolve(&env, &reviewer, &investment_id, true)
+        })
+    }
 
-        let metrics = AnalyticsCalculator::calculate_performance_metrics(&env)?;
-        AnalyticsStorage::store_performance_metrics(&env, &metrics);
+    /// Deny a pending claim (provider or arbitrator)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim was already resolved
+    /// * `Unauthorized` if `reviewer` is neither the claim's provider nor the arbitrator
+    /// * `OperationNotAllowed` if the review deadline has passed
+    pub fn deny_insurance_claim(
+        env: Env,
+        reviewer: Address,
+        investment_id: BytesN<32>,
+    ) -> Result<insurance::InsuranceClaim, QuickLendXError> {
+        reviewer.require_auth();
+        insurance::InsuranceClaims::resolve(&env, &reviewer, &investment_id, false)
+    }
 
-        // Emit event
-        events::emit_performance_metrics_updated(
-            &env,
-            metrics.average_settlement_time,
-            metrics.transaction_success_rate,
-            metrics.user_satisfaction_score,
-        );
+    /// Approve and pay a claim left unresolved past its review deadline (anyone may call)
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the investment has no claim
+    /// * `InvalidStatus` if the claim was already resolved
+    /// * `OperationNotAllowed` if the review deadline has not passed, or on re-entry
+    pub fn approve_expired_insurance_claim(
+        env: Env,
+        investment_id: BytesN<32>,
+    ) -> Result<insurance::InsuranceClaim, QuickLendXError> {
+        reentrancy::with_payment_guard(&env, || {
+            insurance::InsuranceClaims::approve_expired(&env, &investment_id)
+        })
+    }
 
-        Ok(())
+    /// Get an investor's tax lots held during a period: each investment's cost basis and
+    /// acquisition date, and its disposal (settlement, refund, default) with the proceeds
+    /// received, for lot-level tax reporting
+    pub fn get_tax_lots(env: Env, investor: Address, period: TimePeriod) -> Vec<TaxLot> {
+        TaxLots::get_for_period(&env, &investor, period)
     }
 
     /// Get user behavior metrics
@@ -1892,6 +5735,42 @@ impl QuickLendXContract {
         Ok(metrics)
     }
 
+    /// Get insurance metrics for a period: premiums, claims paid and loss ratio in total and per
+    /// provider, with the coverage and uninsured exposure currently outstanding
+    pub fn get_insurance_metrics(env: Env, period: TimePeriod) -> analytics::InsuranceMetrics {
+        AnalyticsCalculator::calculate_insurance_metrics(&env, period)
+    }
+
+    /// Store a snapshot of the insurance metrics for a period (admin or relayer)
+    pub fn update_insurance_metrics(
+        env: Env,
+        caller: Address,
+        period: TimePeriod,
+    ) -> Result<(), QuickLendXError> {
+        AdminStorage::require_admin_or_relayer(&env, &caller)?;
+
+        let metrics = AnalyticsCalculator::calculate_insurance_metrics(&env, period);
+        AnalyticsStorage::store_insurance_metrics(&env, &metrics);
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::MetricsSnapshot,
+            Some(caller),
+            1,
+            metrics.loss_ratio_by_provider.len(),
+            0,
+        );
+        events::emit_insurance_metrics_updated(&env, &metrics);
+        Ok(())
+    }
+
+    /// Get the last stored insurance metrics snapshot for a period
+    pub fn get_stored_insurance_metrics(
+        env: Env,
+        period: TimePeriod,
+    ) -> Option<analytics::InsuranceMetrics> {
+        AnalyticsStorage::get_insurance_metrics(&env, &period)
+    }
+
     /// Generate business report
     pub fn generate_business_report(
         env: Env,
@@ -2092,14 +5971,23 @@ impl QuickLendXContract {
         Ok(())
     }
 
-    /// Update platform investor performance metrics (admin only)
-    pub fn update_investor_performance_data(env: Env) -> Result<(), QuickLendXError> {
-        let admin =
-            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
+    /// Update platform investor performance metrics (admin or relayer)
+    pub fn update_investor_performance_data(
+        env: Env,
+        caller: Address,
+    ) -> Result<(), QuickLendXError> {
+        AdminStorage::require_admin_or_relayer(&env, &caller)?;
 
         let metrics = AnalyticsCalculator::calc_investor_perf_metrics(&env)?;
         AnalyticsStorage::store_investor_performance(&env, &metrics);
+        keeper::KeeperStorage::record_call(
+            &env,
+            keeper::KeeperOperation::MetricsSnapshot,
+            Some(caller),
+            1,
+            metrics.total_investors,
+            0,
+        );
 
         // Emit event
         events::emit_investor_performance_updated(
@@ -2125,10 +6013,18 @@ impl QuickLendXContract {
     pub fn configure_treasury(env: Env, treasury_address: Address) -> Result<(), QuickLendXError> {
         let admin =
             BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
 
+        // `FeeManager::configure_treasury` requires the admin's authorization
+        let old_treasury = fees::FeeManager::get_treasury_address(&env);
         let _treasury_config =
             fees::FeeManager::configure_treasury(&env, &admin, treasury_address.clone())?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::TreasuryAddress,
+            old_treasury.map_or(ConfigValue::Unset, ConfigValue::Address),
+            ConfigValue::Address(treasury_address.clone()),
+            &admin,
+        );
 
         // Emit event
         events::emit_treasury_configured(&env, &treasury_address, &admin);
@@ -2140,17 +6036,34 @@ impl QuickLendXContract {
     pub fn update_platform_fee_bps(env: Env, new_fee_bps: u32) -> Result<(), QuickLendXError> {
         let admin =
             BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
-        admin.require_auth();
+        Self::set_platform_fee_bps(&env, &admin, new_fee_bps)?;
+        Ok(())
+    }
 
-        let old_config = fees::FeeManager::get_platform_fee_config(&env)?;
+    /// Set the platform fee as `admin`, shared by `update_platform_fee_bps` and the deprecated
+    /// `set_platform_fee`, which resolve the admin differently
+    fn set_platform_fee_bps(
+        env: &Env,
+        admin: &Address,
+        new_fee_bps: u32,
+    ) -> Result<fees::PlatformFeeConfig, QuickLendXError> {
+        // `FeeManager::update_platform_fee` requires the admin's authorization
+        let old_config = fees::FeeManager::get_platform_fee_config(env)?;
         let old_fee_bps = old_config.fee_bps;
 
-        let _new_config = fees::FeeManager::update_platform_fee(&env, &admin, new_fee_bps)?;
+        let new_config = fees::FeeManager::update_platform_fee(env, admin, new_fee_bps)?;
+        ConfigLog::record(
+            env,
+            ConfigParameter::PlatformFeeBps,
+            ConfigValue::U32(old_fee_bps),
+            ConfigValue::U32(new_fee_bps),
+            admin,
+        );
 
         // Emit event
-        events::emit_platform_fee_config_updated(&env, old_fee_bps, new_fee_bps, &admin);
+        events::emit_platform_fee_config_updated(env, old_fee_bps, new_fee_bps, admin);
 
-        Ok(())
+        Ok(new_config)
     }
 
     /// Get current platform fee configuration
@@ -2158,6 +6071,199 @@ impl QuickLendXContract {
         fees::FeeManager::get_platform_fee_config(&env)
     }
 
+    /// Migrate a fee rate stored by the deprecated `set_platform_fee` into the unified
+    /// fee configuration (admin only)
+    ///
+    /// # Returns
+    /// * `Ok(true)` if a legacy configuration was found and migrated
+    /// * `Ok(false)` if there was nothing to migrate
+    pub fn migrate_platform_fee_config(env: Env) -> Result<bool, QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        fees::FeeManager::migrate_legacy_platform_fee(&env, &admin)
+    }
+
+    /// Set the investor's share of late payment penalties in basis points (admin only)
+    ///
+    /// The platform receives the remainder. Applied to penalties collected at settlement.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `investor_share_bps` exceeds 10,000
+    pub fn set_late_penalty_split(
+        env: Env,
+        investor_share_bps: u32,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        let old_config = fees::FeeManager::get_late_penalty_split(&env);
+        let config = fees::FeeManager::set_late_penalty_split(&env, &admin, investor_share_bps)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::LatePenaltyInvestorShareBps,
+            ConfigValue::U32(old_config.investor_share_bps),
+            ConfigValue::U32(config.investor_share_bps),
+            &admin,
+        );
+        events::emit_late_penalty_split_updated(
+            &env,
+            config.investor_share_bps,
+            config.platform_share_bps,
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the current late penalty split
+    pub fn get_late_penalty_split(env: Env) -> fees::LatePenaltySplitConfig {
+        fees::FeeManager::get_late_penalty_split(&env)
+    }
+
+    /// Set the daily late fee accrual added to the flat late penalty (admin only)
+    ///
+    /// Each full day past the due date adds `daily_bps` of the face value to the penalty, up
+    /// to `max_accrual_bps`. Locked into fee quotes at funding like the other fee terms.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidFeeBasisPoints` if `max_accrual_bps` exceeds 10,000 or `daily_bps` exceeds it
+    pub fn set_late_fee_accrual(
+        env: Env,
+        daily_bps: u32,
+        max_accrual_bps: u32,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let old = fees::FeeManager::get_late_fee_accrual(&env);
+        let new = fees::FeeManager::set_late_fee_accrual(&env, daily_bps, max_accrual_bps)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::LateFeeDailyBps,
+            ConfigValue::U32(old.daily_bps),
+            ConfigValue::U32(new.daily_bps),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::LateFeeAccrualCapBps,
+            ConfigValue::U32(old.max_accrual_bps),
+            ConfigValue::U32(new.max_accrual_bps),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the daily late fee accrual
+    pub fn get_late_fee_accrual(env: Env) -> fees::LateFeeAccrual {
+        fees::FeeManager::get_late_fee_accrual(&env)
+    }
+
+    /// Set the platform fee rate for a participant tier (admin only)
+    ///
+    /// Tier rates let established counterparties pay lower fees. At funding, the lowest rate
+    /// matching the business's verification tier or the investor's tier is locked into the
+    /// invoice's fee quote; the base platform rate applies when none match.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidAmount` if `fee_bps` exceeds the maximum platform fee (10%)
+    pub fn set_tier_fee_rate(
+        env: Env,
+        tier: fees::ParticipantTier,
+        fee_bps: u32,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        let old_rate = fees::FeeManager::get_tier_fee_rates(&env).get(tier.clone());
+        fees::FeeManager::set_tier_fee_rate(&env, &admin, tier.clone(), fee_bps)?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::TierFeeRate(tier),
+            old_rate.map_or(ConfigValue::Unset, ConfigValue::U32),
+            ConfigValue::U32(fee_bps),
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Remove the platform fee rate for a participant tier (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if no rate is configured for the tier
+    pub fn remove_tier_fee_rate(
+        env: Env,
+        tier: fees::ParticipantTier,
+    ) -> Result<(), QuickLendXError> {
+        let admin =
+            BusinessVerificationStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        let old_rate = fees::FeeManager::get_tier_fee_rates(&env).get(tier.clone());
+        fees::FeeManager::remove_tier_fee_rate(&env, &admin, tier.clone())?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::TierFeeRate(tier),
+            old_rate.map_or(ConfigValue::Unset, ConfigValue::U32),
+            ConfigValue::Unset,
+            &admin,
+        );
+        Ok(())
+    }
+
+    /// Get the platform fee rates configured per participant tier
+    pub fn get_tier_fee_rates(env: Env) -> Map<fees::ParticipantTier, u32> {
+        fees::FeeManager::get_tier_fee_rates(&env)
+    }
+
+    /// Get the platform fee rate that would apply to a business/investor pair if funded now
+    pub fn get_effective_fee_bps(env: Env, business: Address, investor: Address) -> u32 {
+        fees::FeeManager::effective_platform_fee_bps(&env, &business, &investor)
+    }
+
+    /// Schedule a launch window in which settlements pay no platform fee, for every invoice or
+    /// only the categories listed, replacing any earlier promotion (admin only)
+    ///
+    /// The fee returns automatically when the window ends.
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `InvalidTimestamp` if the window does not end after it starts and after now, or is
+    ///   longer than 180 days
+    pub fn set_fee_promotion(
+        env: Env,
+        promotion: fees::FeePromotion,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        fees::FeeManager::set_fee_promotion(&env, &admin, &promotion)
+    }
+
+    /// End the scheduled or running fee promotion early (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin` if no admin is set
+    /// * `StorageKeyNotFound` if no promotion is scheduled or running
+    pub fn cancel_fee_promotion(env: Env) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        fees::FeeManager::cancel_fee_promotion(&env, &admin)
+    }
+
+    /// Get the fee promotion that is scheduled or running, if any
+    pub fn get_fee_promotion(env: Env) -> Option<fees::FeePromotion> {
+        fees::FeeManager::get_fee_promotion(&env)
+    }
+
+    /// Get the fee terms locked when an invoice was funded, if any
+    pub fn get_invoice_fee_quote(env: Env, invoice_id: BytesN<32>) -> Option<fees::FeeQuote> {
+        fees::FeeManager::get_fee_quote(&env, &invoice_id)
+    }
+
+    /// Get the late penalty collected when an invoice was settled, if any
+    pub fn get_late_penalty_record(
+        env: Env,
+        invoice_id: BytesN<32>,
+    ) -> Option<fees::LatePenaltyRecord> {
+        fees::FeeManager::get_late_penalty_record(&env, &invoice_id)
+    }
+
     /// Get treasury address if configured
     pub fn get_treasury_address(env: Env) -> Option<Address> {
         fees::FeeManager::get_treasury_address(&env)
@@ -2173,15 +6279,49 @@ impl QuickLendXContract {
         max_fee: i128,
         is_active: bool,
     ) -> Result<fees::FeeStructure, QuickLendXError> {
-        fees::FeeManager::update_fee_structure(
+        let old = fees::FeeManager::get_fee_structure(&env, &fee_type).ok();
+        let updated = fees::FeeManager::update_fee_structure(
             &env,
             &admin,
-            fee_type,
+            fee_type.clone(),
             base_fee_bps,
             min_fee,
             max_fee,
             is_active,
-        )
+        )?;
+        ConfigLog::record(
+            &env,
+            ConfigParameter::FeeBaseBps(fee_type.clone()),
+            old.as_ref()
+                .map_or(ConfigValue::Unset, |f| ConfigValue::U32(f.base_fee_bps)),
+            ConfigValue::U32(updated.base_fee_bps),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::FeeMin(fee_type.clone()),
+            old.as_ref()
+                .map_or(ConfigValue::Unset, |f| ConfigValue::I128(f.min_fee)),
+            ConfigValue::I128(updated.min_fee),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::FeeMax(fee_type.clone()),
+            old.as_ref()
+                .map_or(ConfigValue::Unset, |f| ConfigValue::I128(f.max_fee)),
+            ConfigValue::I128(updated.max_fee),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::FeeActive(fee_type),
+            old.as_ref()
+                .map_or(ConfigValue::Unset, |f| ConfigValue::Bool(f.is_active)),
+            ConfigValue::Bool(updated.is_active),
+            &admin,
+        );
+        Ok(updated)
     }
 
     /// Get fee structure for a fee type
@@ -2241,6 +6381,7 @@ impl QuickLendXContract {
             return Err(QuickLendXError::NotAdmin);
         }
 
+        let old = fees::FeeManager::get_revenue_split_config(&env).ok();
         let config = fees::RevenueConfig {
             treasury_address,
             treasury_share_bps,
@@ -2249,7 +6390,69 @@ impl QuickLendXContract {
             auto_distribution,
             min_distribution_amount,
         };
-        fees::FeeManager::configure_revenue_distribution(&env, &admin, config)
+        fees::FeeManager::configure_revenue_distribution(&env, &admin, config.clone())?;
+        Self::log_revenue_config_changes(&env, &admin, old, &config);
+        Ok(())
+    }
+
+    fn log_revenue_config_changes(
+        env: &Env,
+        admin: &Address,
+        old: Option<fees::RevenueConfig>,
+        new: &fees::RevenueConfig,
+    ) {
+        let changes = [
+            (
+                ConfigParameter::RevenueTreasury,
+                old.as_ref()
+                    .map(|c| ConfigValue::Address(c.treasury_address.clone())),
+                ConfigValue::Address(new.treasury_address.clone()),
+            ),
+            (
+                ConfigParameter::RevenueTreasuryShareBps,
+                old.as_ref().map(|c| ConfigValue::U32(c.treasury_share_bps)),
+                ConfigValue::U32(new.treasury_share_bps),
+            ),
+            (
+                ConfigParameter::RevenueDeveloperShareBps,
+                old.as_ref()
+                    .map(|c| ConfigValue::U32(c.developer_share_bps)),
+                ConfigValue::U32(new.developer_share_bps),
+            ),
+            (
+                ConfigParameter::RevenuePlatformShareBps,
+                old.as_ref().map(|c| ConfigValue::U32(c.platform_share_bps)),
+                ConfigValue::U32(new.platform_share_bps),
+            ),
+            (
+                ConfigParameter::RevenueAutoDistribution,
+                old.as_ref().map(|c| ConfigValue::Bool(c.auto_distribution)),
+                ConfigValue::Bool(new.auto_distribution),
+            ),
+            (
+                ConfigParameter::RevenueMinDistribution,
+                old.as_ref()
+                    .map(|c| ConfigValue::I128(c.min_distribution_amount)),
+                ConfigValue::I128(new.min_distribution_amount),
+            ),
+        ];
+        for (parameter, old_value, new_value) in changes {
+            ConfigLog::record(
+                env,
+                parameter,
+                old_value.unwrap_or(ConfigValue::Unset),
+                new_value,
+                admin,
+            );
+        }
+    }
+
+    /// Get platform configuration changes, oldest first (at most 100 per page)
+    ///
+    /// Covers fees, treasury, revenue split, currency whitelist, bid TTL bounds and protocol
+    /// limits. Per-invoice operations are in the audit trail instead.
+    pub fn get_config_history(env: Env, offset: u32, limit: u32) -> Vec<config_log::ConfigChange> {
+        ConfigLog::get_history(&env, offset, limit)
     }
 
     /// Get current revenue split configuration
@@ -2295,6 +6498,15 @@ impl QuickLendXContract {
     // Query Functions for Frontend Integration
     // ========================================
 
+    /// Get the version counter of an index
+    ///
+    /// The counter increases whenever an entry is added to or removed from the index, or an
+    /// entry's status changes, so a client polling a large list only needs to refetch it when
+    /// the version moved. Never-changed indexes report 0.
+    pub fn get_list_version(env: Env, index: list_versions::ListIndex) -> u64 {
+        list_versions::ListVersions::get(&env, &index)
+    }
+
     /// Get invoices by business with optional status filter and pagination
     pub fn get_business_invoices_paged(
         env: Env,
@@ -2369,7 +6581,58 @@ impl QuickLendXContract {
         result
     }
 
+    /// Set the price of marketplace boosts (admin only)
+    ///
+    /// # Errors
+    /// * `NotAdmin`, `InvalidAmount` if the fee is negative or `max_days` is 0
+    pub fn set_boost_config(
+        env: Env,
+        fee_per_day: i128,
+        max_days: u32,
+    ) -> Result<(), QuickLendXError> {
+        let admin = AdminStorage::get_admin(&env).ok_or(QuickLendXError::NotAdmin)?;
+        admin.require_auth();
+        let config = boost::BoostConfig {
+            fee_per_day,
+            max_days,
+        };
+        boost::InvoiceBoosts::set_config(&env, &config)
+    }
+
+    /// Get the price of marketplace boosts, if configured
+    pub fn get_boost_config(env: Env) -> Option<boost::BoostConfig> {
+        boost::InvoiceBoosts::get_config(&env)
+    }
+
+    /// Feature a verified invoice in the marketplace for `days` (business only)
+    ///
+    /// The fee is `fee_per_day * days` in the invoice's currency, paid to the treasury. Boosting
+    /// an invoice that is already featured extends the boost.
+    ///
+    /// # Returns
+    /// * `Ok(invoice)` - the invoice with its new `boosted_until`
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Verified
+    /// * `InvalidAmount` if `days` is 0 or above the configured maximum
+    /// * `OperationNotAllowed` if boosts are not configured, the invoice is frozen, on
+    ///   re-entry, transfer errors
+    pub fn boost_invoice(
+        env: Env,
+        invoice_id: BytesN<32>,
+        days: u32,
+    ) -> Result<Invoice, QuickLendXError> {
+        let invoice = InvoiceStorage::get_invoice(&env, &invoice_id)
+            .ok_or(QuickLendXError::InvoiceNotFound)?;
+        invoice.business.require_auth();
+        reentrancy::with_payment_guard(&env, || {
+            boost::InvoiceBoosts::boost(&env, &invoice_id, days)
+        })
+    }
+
     /// Get available invoices with pagination and optional filters
+    ///
+    /// Boosted invoices come first, each group keeping the listing order.
     pub fn get_available_invoices_paged(
         env: Env,
         min_amount: Option<i128>,
@@ -2380,7 +6643,9 @@ impl QuickLendXContract {
     ) -> Vec<BytesN<32>> {
         let verified_invoices =
             InvoiceStorage::get_invoices_by_status(&env, &InvoiceStatus::Verified);
+        let now = env.ledger().timestamp();
         let mut filtered = Vec::new(&env);
+        let mut unboosted = Vec::new(&env);
 
         for invoice_id in verified_invoices.iter() {
             if let Some(invoice) = InvoiceStorage::get_invoice(&env, &invoice_id) {
@@ -2401,9 +6666,14 @@ impl QuickLendXContract {
                         continue;
                     }
                 }
-                filtered.push_back(invoice_id);
+                if invoice.is_boosted(now) {
+                    filtered.push_back(invoice_id);
+                } else {
+                    unboosted.push_back(invoice_id);
+                }
             }
         }
+        filtered.append(&unboosted);
 
         // Apply pagination
         let mut result = Vec::new(&env);
@@ -2510,6 +6780,11 @@ mod test_bid;
 
 #[cfg(test)]
 mod test_bid_ranking;
+#[cfg(test)]
+mod test_budget;
+
+#[cfg(test)]
+mod test_config_log;
 
 #[cfg(test)]
 mod test_fees;
@@ -2525,18 +6800,30 @@ mod test_currency;
 mod test_errors;
 #[cfg(test)]
 mod test_events;
+#[cfg(test)]
+mod test_exposure;
 
 #[cfg(test)]
 mod test_default;
+#[cfg(test)]
+mod test_deposits;
 
 #[cfg(test)]
 mod test_investment_queries;
 #[cfg(test)]
+mod test_keeper;
+#[cfg(test)]
+mod test_lifecycle_props;
+#[cfg(test)]
 mod test_partial_payments;
 #[cfg(test)]
 mod test_queries;
 #[cfg(test)]
 mod test_reentrancy;
+#[cfg(test)]
+mod test_rounding;
+#[cfg(test)]
+mod test_settlement;
 
 mod test_investor_kyc;
 #[cfg(test)]
@@ -0,0 +1,125 @@
+//! Chain of custody for invoices that are relisted or split.
+//!
+//! Whenever an invoice is replaced by one or more new invoices, each new invoice is linked to the
+//! one it came from, with the kind of change. `lineage` walks the links up to the original
+//! receivable and back down, so auditors can trace every invoice it turned into with amounts and
+//! statuses. Links are never removed.
+
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, Symbol, Vec};
+
+const PARENT_KEY: Symbol = symbol_short!("lin_par");
+const CHILDREN_KEY: Symbol = symbol_short!("lin_chd");
+/// Upper bound on entries returned by `lineage`
+pub const MAX_LINEAGE_ENTRIES: u32 = 50;
+/// Most invoices one invoice can be split into
+pub const MAX_SPLIT_PARTS: u32 = 10;
+
+/// How an invoice came from its parent
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LineageKind {
+    /// The original receivable, with no parent
+    Original,
+    /// Relisted with new terms after the parent was cancelled or expired
+    Relisted,
+    /// One of the parts the parent was split into
+    Split,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineageLink {
+    pub parent: BytesN<32>,
+    pub kind: LineageKind,
+    pub linked_at: u64,
+}
+
+/// An invoice in a lineage, as it stands now
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineageEntry {
+    pub invoice_id: BytesN<32>,
+    pub amount: i128,
+    pub status: InvoiceStatus,
+    pub created_at: u64,
+    /// Invoice this one came from; `None` for the original
+    pub parent: Option<BytesN<32>>,
+    pub kind: LineageKind,
+    /// Links between this invoice and the original
+    pub depth: u32,
+}
+
+pub struct InvoiceLineage;
+
+impl InvoiceLineage {
+    pub fn get_parent(env: &Env, invoice_id: &BytesN<32>) -> Option<LineageLink> {
+        env.storage()
+            .instance()
+            .get(&(PARENT_KEY, invoice_id.clone()))
+    }
+
+    pub fn get_children(env: &Env, invoice_id: &BytesN<32>) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(CHILDREN_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Record that `child` came from `parent`
+    pub fn link(env: &Env, parent: &BytesN<32>, child: &BytesN<32>, kind: LineageKind) {
+        let link = LineageLink {
+            parent: parent.clone(),
+            kind,
+            linked_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(PARENT_KEY, child.clone()), &link);
+        let mut children = Self::get_children(env, parent);
+        children.push_back(child.clone());
+        env.storage()
+            .instance()
+            .set(&(CHILDREN_KEY, parent.clone()), &children);
+    }
+
+    /// The original invoice `invoice_id` descends from, and every invoice descending from it,
+    /// parents before children; at most `MAX_LINEAGE_ENTRIES`
+    pub fn lineage(env: &Env, invoice_id: &BytesN<32>) -> Vec<LineageEntry> {
+        let mut root = invoice_id.clone();
+        let mut hops = 0;
+        while let Some(link) = Self::get_parent(env, &root) {
+            if hops >= MAX_LINEAGE_ENTRIES {
+                break;
+            }
+            root = link.parent;
+            hops += 1;
+        }
+
+        let mut entries = Vec::new(env);
+        let mut queue = Vec::new(env);
+        queue.push_back((root, 0u32));
+        while let Some((id, depth)) = queue.pop_front() {
+            if entries.len() >= MAX_LINEAGE_ENTRIES {
+                break;
+            }
+            let Some(invoice) = InvoiceStorage::get_invoice(env, &id) else {
+                continue;
+            };
+            let link = Self::get_parent(env, &id);
+            entries.push_back(LineageEntry {
+                invoice_id: id.clone(),
+                amount: invoice.amount,
+                status: invoice.status,
+                created_at: invoice.created_at,
+                parent: link.as_ref().map(|link| link.parent.clone()),
+                kind: link.map_or(LineageKind::Original, |link| link.kind),
+                depth,
+            });
+            for child in Self::get_children(env, &id).iter() {
+                queue.push_back((child, depth + 1));
+            }
+        }
+        entries
+    }
+}
@@ -0,0 +1,52 @@
+//! Version counters for the lists behind paged queries.
+//!
+//! Each index's counter is bumped whenever an entry is added to or removed from it, or an
+//! entry's status changes. `get_list_version` exposes them: a client paging through a list reads
+//! the version of each index the query walks before the first page and after the last, and
+//! restarts if any moved, since the pages may then skip or repeat entries. A client polling a
+//! list can likewise skip refetching it while its version is unchanged.
+//!
+//! All counters live in one map under a single instance key, so a bump is one write however
+//! many indexes it touches.
+
+use crate::invoice::InvoiceStatus;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, Symbol};
+
+const LIST_VERSION_KEY: Symbol = symbol_short!("list_ver");
+
+/// A versioned index
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ListIndex {
+    InvoicesByStatus(InvoiceStatus),
+    BusinessInvoices(Address),
+    InvestorInvestments(Address),
+    InvoiceBids(BytesN<32>),
+    InvestorBids(Address),
+}
+
+pub struct ListVersions;
+
+impl ListVersions {
+    fn get_all(env: &Env) -> Map<ListIndex, u64> {
+        env.storage()
+            .instance()
+            .get(&LIST_VERSION_KEY)
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Current version of `index`, 0 if it never changed
+    pub fn get(env: &Env, index: &ListIndex) -> u64 {
+        Self::get_all(env).get(index.clone()).unwrap_or(0)
+    }
+
+    /// Bump every index in `indexes` with a single write
+    pub fn bump(env: &Env, indexes: &[ListIndex]) {
+        let mut versions = Self::get_all(env);
+        for index in indexes {
+            let version = versions.get(index.clone()).unwrap_or(0);
+            versions.set(index.clone(), version.saturating_add(1));
+        }
+        env.storage().instance().set(&LIST_VERSION_KEY, &versions);
+    }
+}
@@ -0,0 +1,216 @@
+//! Milestone-based invoices with staged escrow release.
+//!
+//! A business can split an unfunded invoice into milestones, each with its own amount and due
+//! date. Once the invoice is funded, the escrow is no longer released in one go: the admin
+//! releases each milestone as its work is verified, and the business receives the milestone's
+//! share of the escrow (its amount over the invoice amount). The last milestone released gets
+//! whatever remains in escrow, so rounding never strands funds.
+
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_milestones_set, emit_milestone_released};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::payments::{release_escrow_partial, EscrowStatus, EscrowStorage};
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, String, Symbol, Vec};
+
+const MILESTONES_KEY: Symbol = symbol_short!("milestone");
+/// Upper bound on milestones per invoice
+pub const MAX_MILESTONES_PER_INVOICE: u32 = 12;
+/// Upper bound on the length of a milestone description
+pub const MAX_MILESTONE_DESCRIPTION_LENGTH: u32 = 256;
+
+/// A milestone as submitted by the business
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneSpec {
+    /// Part of the invoice amount this milestone covers
+    pub amount: i128,
+    pub due_date: u64,
+    pub description: String,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MilestoneStatus {
+    Pending,
+    Released,
+}
+
+/// A stored milestone and the escrow paid out against it
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceMilestone {
+    pub amount: i128,
+    pub due_date: u64,
+    pub description: String,
+    pub status: MilestoneStatus,
+    /// Escrow paid to the business when the milestone was released
+    pub released_amount: i128,
+    pub released_at: u64,
+}
+
+pub struct InvoiceMilestones;
+
+impl InvoiceMilestones {
+    /// Milestones of an invoice, in the order they were defined (empty for regular invoices)
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Vec<InvoiceMilestone> {
+        env.storage()
+            .instance()
+            .get(&(MILESTONES_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    pub fn has_milestones(env: &Env, invoice_id: &BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .has(&(MILESTONES_KEY, invoice_id.clone()))
+    }
+
+    fn set(env: &Env, invoice_id: &BytesN<32>, milestones: &Vec<InvoiceMilestone>) {
+        env.storage()
+            .instance()
+            .set(&(MILESTONES_KEY, invoice_id.clone()), milestones);
+    }
+
+    /// Replace an unfunded invoice's milestones; an empty list turns it back into a regular
+    /// invoice.
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Pending or Verified
+    /// * `OperationNotAllowed` if there are more than `MAX_MILESTONES_PER_INVOICE` milestones
+    /// * `InvalidAmount` if a milestone amount is not positive
+    /// * `InvoiceAmountInvalid` if the amounts do not sum to the invoice amount
+    /// * `InvoiceDueDateInvalid` if due dates decrease, are in the past, or fall after the
+    ///   invoice due date
+    /// * `InvalidDescription` if a description is empty or too long
+    pub fn define(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        specs: &Vec<MilestoneSpec>,
+    ) -> Result<(), QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Pending && invoice.status != InvoiceStatus::Verified {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if specs.len() > MAX_MILESTONES_PER_INVOICE {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        if specs.is_empty() {
+            env.storage()
+                .instance()
+                .remove(&(MILESTONES_KEY, invoice_id.clone()));
+            emit_invoice_milestones_set(env, invoice_id, 0);
+            return Ok(());
+        }
+
+        let now = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        let mut previous_due = now;
+        let mut milestones = Vec::new(env);
+        for spec in specs.iter() {
+            if spec.amount <= 0 {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+            if spec.due_date <= now
+                || spec.due_date < previous_due
+                || spec.due_date > invoice.due_date
+            {
+                return Err(QuickLendXError::InvoiceDueDateInvalid);
+            }
+            if spec.description.len() == 0
+                || spec.description.len() > MAX_MILESTONE_DESCRIPTION_LENGTH
+            {
+                return Err(QuickLendXError::InvalidDescription);
+            }
+            total = total
+                .checked_add(spec.amount)
+                .ok_or(QuickLendXError::InvoiceAmountInvalid)?;
+            previous_due = spec.due_date;
+            milestones.push_back(InvoiceMilestone {
+                amount: spec.amount,
+                due_date: spec.due_date,
+                description: spec.description.clone(),
+                status: MilestoneStatus::Pending,
+                released_amount: 0,
+                released_at: 0,
+            });
+        }
+        if total != invoice.amount {
+            return Err(QuickLendXError::InvoiceAmountInvalid);
+        }
+
+        Self::set(env, invoice_id, &milestones);
+        emit_invoice_milestones_set(env, invoice_id, milestones.len());
+        Ok(())
+    }
+
+    /// Pay the business the escrow share of one milestone.
+    ///
+    /// # Returns
+    /// * `Ok(amount)` - the escrow amount released for the milestone
+    ///
+    /// # Errors
+    /// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded or its escrow is not
+    ///   Held
+    /// * `StorageKeyNotFound` if the invoice has no milestone at `milestone_idx`
+    /// * `OperationNotAllowed` if the milestone was already released or a dispute froze the escrow
+    pub fn release(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        milestone_idx: u32,
+    ) -> Result<i128, QuickLendXError> {
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if escrow.status == EscrowStatus::Frozen {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if escrow.status != EscrowStatus::Held {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+
+        let mut milestones = Self::get(env, invoice_id);
+        let mut milestone = milestones
+            .get(milestone_idx)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let pending = milestones
+            .iter()
+            .filter(|m| m.status == MilestoneStatus::Pending)
+            .count();
+        let remaining = escrow.amount - escrow.released_amount;
+        let amount = if pending == 1 {
+            remaining
+        } else {
+            rounding::mul_div(
+                escrow.amount,
+                milestone.amount,
+                invoice.amount,
+                Rounding::Floor,
+            )
+        }
+        .min(remaining);
+
+        if amount > 0 {
+            release_escrow_partial(env, invoice_id, amount)?;
+        }
+
+        milestone.status = MilestoneStatus::Released;
+        milestone.released_amount = amount;
+        milestone.released_at = env.ledger().timestamp();
+        milestones.set(milestone_idx, milestone);
+        Self::set(env, invoice_id, &milestones);
+
+        emit_milestone_released(env, invoice_id, milestone_idx, &escrow.business, amount);
+        Ok(amount)
+    }
+}
@@ -0,0 +1,173 @@
+//! Notification hooks: the one place that declares which notifications each state change sends.
+//!
+//! Modules report what happened with `NotificationHooks::fire` instead of picking notification
+//! templates themselves, so every path making the same change notifies the same way. Status
+//! changes are resolved through a transition table that must cover every transition the state
+//! machine allows; `uncovered_transitions` lists any it misses.
+
+use crate::bid::Bid;
+use crate::invoice::{Invoice, InvoiceStatus};
+use crate::notifications::{NotificationSystem, NotificationTrigger};
+#[cfg(test)]
+use crate::state_machine::InvoiceStateMachine;
+use soroban_sdk::{Address, Env, Vec};
+
+/// A state change reported by a module
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NotificationHook {
+    InvoiceCreated,
+    /// The invoice moved from the first status to the second
+    StatusChanged(InvoiceStatus, InvoiceStatus),
+    BidPlaced(Bid),
+    /// The bid was accepted and funded the invoice
+    InvoiceFunded(Bid),
+    /// A payment of this amount settled the invoice
+    InvoiceSettled(i128),
+    PaymentOverdue,
+    /// The investor's settlement payout was held for them to claim
+    PayoutHeld(Address),
+    InvoiceFrozen,
+    InvoiceUnfrozen,
+    DisputeOpened,
+    DisputeResolved,
+    /// A late penalty of this amount was charged at settlement
+    LatePenaltyApplied(i128),
+}
+
+/// What a status transition notifies, before the invoice's details are filled in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum StatusTemplate {
+    Verified,
+    StatusChanged,
+    PaymentReceived,
+    Defaulted,
+}
+
+use InvoiceStatus as S;
+use StatusTemplate as T;
+
+const TRANSITION_TEMPLATES: &[(InvoiceStatus, InvoiceStatus, &[StatusTemplate])] = &[
+    (S::Pending, S::Verified, &[T::Verified]),
+    (S::Pending, S::Cancelled, &[T::StatusChanged]),
+    (S::Pending, S::Expired, &[T::StatusChanged]),
+    (S::Verified, S::Pending, &[T::StatusChanged]),
+    (S::Verified, S::Funded, &[T::StatusChanged]),
+    (S::Verified, S::Cancelled, &[T::StatusChanged]),
+    (S::Verified, S::Expired, &[T::StatusChanged]),
+    (S::Funded, S::Paid, &[T::PaymentReceived]),
+    (S::Funded, S::Defaulted, &[T::Defaulted]),
+    (S::Funded, S::Refunded, &[T::StatusChanged]),
+];
+
+pub struct NotificationHooks;
+
+impl NotificationHooks {
+    /// Notifications `hook` sends about `invoice`, in order
+    pub fn triggers(
+        env: &Env,
+        invoice: &Invoice,
+        hook: &NotificationHook,
+    ) -> Vec<NotificationTrigger> {
+        let mut triggers = Vec::new(env);
+        match hook {
+            NotificationHook::InvoiceCreated => {
+                triggers.push_back(NotificationTrigger::InvoiceCreated)
+            }
+            NotificationHook::StatusChanged(from, to) => {
+                Self::push_transition(&mut triggers, from, to, invoice.amount)
+            }
+            NotificationHook::BidPlaced(bid) => {
+                triggers.push_back(NotificationTrigger::BidReceived(bid.clone()))
+            }
+            NotificationHook::InvoiceFunded(bid) => {
+                triggers.push_back(NotificationTrigger::BidAccepted(bid.clone()));
+                Self::push_transition(&mut triggers, &S::Verified, &S::Funded, invoice.amount);
+            }
+            NotificationHook::InvoiceSettled(amount) => {
+                Self::push_transition(&mut triggers, &S::Funded, &S::Paid, *amount)
+            }
+            NotificationHook::PaymentOverdue => {
+                triggers.push_back(NotificationTrigger::PaymentOverdue)
+            }
+            NotificationHook::PayoutHeld(investor) => {
+                triggers.push_back(NotificationTrigger::PayoutHeld(investor.clone()))
+            }
+            NotificationHook::InvoiceFrozen => {
+                triggers.push_back(NotificationTrigger::InvoiceFrozen)
+            }
+            NotificationHook::InvoiceUnfrozen => {
+                triggers.push_back(NotificationTrigger::InvoiceUnfrozen)
+            }
+            NotificationHook::DisputeOpened => {
+                triggers.push_back(NotificationTrigger::DisputeOpened)
+            }
+            NotificationHook::DisputeResolved => {
+                triggers.push_back(NotificationTrigger::DisputeResolved)
+            }
+            NotificationHook::LatePenaltyApplied(penalty) => {
+                triggers.push_back(NotificationTrigger::LatePenaltyApplied(*penalty))
+            }
+        }
+        triggers
+    }
+
+    /// Append the templates declared for `from -> to`; `amount` is the payment received when
+    /// the invoice is paid
+    fn push_transition(
+        triggers: &mut Vec<NotificationTrigger>,
+        from: &InvoiceStatus,
+        to: &InvoiceStatus,
+        amount: i128,
+    ) {
+        let Some((_, _, templates)) = TRANSITION_TEMPLATES
+            .iter()
+            .find(|(f, t, _)| f == from && t == to)
+        else {
+            return;
+        };
+        for template in templates.iter() {
+            triggers.push_back(match template {
+                T::Verified => NotificationTrigger::InvoiceVerified,
+                T::StatusChanged => {
+                    NotificationTrigger::InvoiceStatusChanged(from.clone(), to.clone())
+                }
+                T::PaymentReceived => NotificationTrigger::PaymentReceived(amount),
+                T::Defaulted => NotificationTrigger::InvoiceDefaulted,
+            });
+        }
+    }
+
+    /// Send the notifications declared for `hook`; failures are dead-lettered, see
+    /// `NotificationSystem::send`
+    pub fn fire(env: &Env, invoice: &Invoice, hook: NotificationHook) {
+        for trigger in Self::triggers(env, invoice, &hook).iter() {
+            NotificationSystem::send(env, invoice, trigger);
+        }
+    }
+
+    /// State machine transitions that send no notification
+    #[cfg(test)]
+    pub fn uncovered_transitions(env: &Env) -> Vec<(InvoiceStatus, InvoiceStatus)> {
+        let mut uncovered = Vec::new(env);
+        for from in [
+            S::Pending,
+            S::Verified,
+            S::Funded,
+            S::Paid,
+            S::Defaulted,
+            S::Cancelled,
+            S::Refunded,
+            S::Expired,
+        ] {
+            for transition in InvoiceStateMachine::allowed_transitions(env, &from).iter() {
+                let covered = TRANSITION_TEMPLATES.iter().any(|(f, t, templates)| {
+                    *f == from && *t == transition.to && !templates.is_empty()
+                });
+                if !covered {
+                    uncovered.push_back((from.clone(), transition.to));
+                }
+            }
+        }
+        uncovered
+    }
+}
@@ -46,6 +46,50 @@ pub enum DataKey {
     UserPreferences(Address),
     Notification(BytesN<32>),
     NotificationType(NotificationType),
+    DeadLetters,
+    NextDeadLetterId,
+}
+
+/// Most failed notifications kept for replay; the oldest is dropped beyond this
+pub const MAX_DEAD_LETTERS: u32 = 100;
+
+/// The flow step that triggered a notification, with what is needed to send it again
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NotificationTrigger {
+    InvoiceCreated,
+    InvoiceVerified,
+    /// Old and new status
+    InvoiceStatusChanged(InvoiceStatus, InvoiceStatus),
+    /// Amount received
+    PaymentReceived(i128),
+    PaymentOverdue,
+    InvoiceDefaulted,
+    BidReceived(Bid),
+    BidAccepted(Bid),
+    /// Investor whose settlement payout was held
+    PayoutHeld(Address),
+    /// The admin froze the invoice pending an investigation
+    InvoiceFrozen,
+    InvoiceUnfrozen,
+    DisputeOpened,
+    DisputeResolved,
+    /// Penalty charged for paying late
+    LatePenaltyApplied(i128),
+}
+
+/// A notification that failed inside a flow, kept for operators to inspect and replay
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeadLetter {
+    pub id: u64,
+    pub invoice_id: BytesN<32>,
+    pub trigger: NotificationTrigger,
+    /// `QuickLendXError` code of the latest failure
+    pub error_code: u32,
+    pub failed_at: u64,
+    /// Failed replays so far
+    pub replay_attempts: u32,
 }
 
 /// Notification statistics
@@ -623,6 +667,141 @@ impl NotificationSystem {
         Ok(())
     }
 
+    /// Tell the investor their settlement payout was held and must be claimed manually
+    pub fn notify_payout_held(
+        env: &Env,
+        invoice: &Invoice,
+        investor: &Address,
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        Self::create_notification(
+            env,
+            investor.clone(),
+            NotificationType::PaymentReceived,
+            NotificationPriority::High,
+            String::from_str(env, "Investment Payout Held"),
+            String::from_str(
+                env,
+                "Your payout could not be transferred; claim it with claim_investor_balance",
+            ),
+            Some(invoice.id.clone()),
+        )?;
+        Ok(())
+    }
+
+    /// Tell the business and the investor that the invoice was frozen or unfrozen
+    pub fn notify_invoice_freeze(
+        env: &Env,
+        invoice: &Invoice,
+        frozen: bool,
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        let (title, message, priority) = if frozen {
+            (
+                String::from_str(env, "Invoice Frozen"),
+                String::from_str(
+                    env,
+                    "An invoice you are party to was frozen pending an investigation",
+                ),
+                NotificationPriority::Critical,
+            )
+        } else {
+            (
+                String::from_str(env, "Invoice Unfrozen"),
+                String::from_str(env, "An invoice you are party to is no longer frozen"),
+                NotificationPriority::High,
+            )
+        };
+
+        Self::create_notification(
+            env,
+            invoice.business.clone(),
+            NotificationType::SystemAlert,
+            priority.clone(),
+            title.clone(),
+            message.clone(),
+            Some(invoice.id.clone()),
+        )?;
+        if let Some(investor) = &invoice.investor {
+            Self::create_notification(
+                env,
+                investor.clone(),
+                NotificationType::SystemAlert,
+                priority,
+                title,
+                message,
+                Some(invoice.id.clone()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Tell the business and the investor that a dispute on the invoice was opened or resolved
+    pub fn notify_dispute(
+        env: &Env,
+        invoice: &Invoice,
+        resolved: bool,
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        let (title, message) = if resolved {
+            (
+                String::from_str(env, "Dispute Resolved"),
+                String::from_str(
+                    env,
+                    "The dispute on an invoice you are party to was resolved",
+                ),
+            )
+        } else {
+            (
+                String::from_str(env, "Dispute Opened"),
+                String::from_str(
+                    env,
+                    "A dispute was opened on an invoice you are party to; its escrow is held",
+                ),
+            )
+        };
+
+        Self::create_notification(
+            env,
+            invoice.business.clone(),
+            NotificationType::InvoiceStatusChanged,
+            NotificationPriority::High,
+            title.clone(),
+            message.clone(),
+            Some(invoice.id.clone()),
+        )?;
+        if let Some(investor) = &invoice.investor {
+            Self::create_notification(
+                env,
+                investor.clone(),
+                NotificationType::InvoiceStatusChanged,
+                NotificationPriority::High,
+                title,
+                message,
+                Some(invoice.id.clone()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Tell the business a late payment penalty was charged on the invoice
+    pub fn notify_late_penalty(
+        env: &Env,
+        invoice: &Invoice,
+        _: i128, // penalty
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        Self::create_notification(
+            env,
+            invoice.business.clone(),
+            NotificationType::PaymentOverdue,
+            NotificationPriority::Medium,
+            String::from_str(env, "Late Penalty Applied"),
+            String::from_str(
+                env,
+                "A late payment penalty was charged when your invoice was settled",
+            ),
+            Some(invoice.id.clone()),
+        )?;
+        Ok(())
+    }
+
     /// Create invoice defaulted notification
     pub fn notify_invoice_defaulted(
         env: &Env,
@@ -660,4 +839,173 @@ impl NotificationSystem {
 
         Ok(())
     }
+
+    /// Alert a business and its active investors that a payout address change was requested
+    pub fn notify_payout_change_requested(
+        env: &Env,
+        business: &Address,
+        investors: &Vec<Address>,
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        let title = String::from_str(env, "Payout Address Change Requested");
+        let message = String::from_str(
+            env,
+            "A new payout address was requested for your account; cancel it if you did not request it",
+        );
+
+        Self::create_notification(
+            env,
+            business.clone(),
+            NotificationType::SystemAlert,
+            NotificationPriority::Critical,
+            title,
+            message,
+            None,
+        )?;
+
+        let investor_title = String::from_str(env, "Business Payout Address Change");
+        let investor_message = String::from_str(
+            env,
+            "A business you fund has requested a new payout address",
+        );
+        for investor in investors.iter() {
+            Self::create_notification(
+                env,
+                investor,
+                NotificationType::SystemAlert,
+                NotificationPriority::High,
+                investor_title.clone(),
+                investor_message.clone(),
+                None,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Delivery from flows, with failures kept in a dead-letter store
+impl NotificationSystem {
+    /// Send the notification for `trigger` about `invoice`. A failure does not abort the calling
+    /// flow; it is recorded as a dead letter instead.
+    pub fn send(env: &Env, invoice: &Invoice, trigger: NotificationTrigger) {
+        if let Err(error) = Self::dispatch(env, invoice, &trigger) {
+            Self::record_dead_letter(env, &invoice.id, trigger, error);
+        }
+    }
+
+    fn dispatch(
+        env: &Env,
+        invoice: &Invoice,
+        trigger: &NotificationTrigger,
+    ) -> Result<(), crate::errors::QuickLendXError> {
+        match trigger {
+            NotificationTrigger::InvoiceCreated => Self::notify_invoice_created(env, invoice),
+            NotificationTrigger::InvoiceVerified => Self::notify_invoice_verified(env, invoice),
+            NotificationTrigger::InvoiceStatusChanged(old_status, new_status) => {
+                Self::notify_invoice_status_changed(env, invoice, old_status, new_status)
+            }
+            NotificationTrigger::PaymentReceived(amount) => {
+                Self::notify_payment_received(env, invoice, *amount)
+            }
+            NotificationTrigger::PaymentOverdue => Self::notify_payment_overdue(env, invoice),
+            NotificationTrigger::InvoiceDefaulted => Self::notify_invoice_defaulted(env, invoice),
+            NotificationTrigger::BidReceived(bid) => Self::notify_bid_received(env, invoice, bid),
+            NotificationTrigger::BidAccepted(bid) => Self::notify_bid_accepted(env, invoice, bid),
+            NotificationTrigger::PayoutHeld(investor) => {
+                Self::notify_payout_held(env, invoice, investor)
+            }
+            NotificationTrigger::InvoiceFrozen => Self::notify_invoice_freeze(env, invoice, true),
+            NotificationTrigger::InvoiceUnfrozen => {
+                Self::notify_invoice_freeze(env, invoice, false)
+            }
+            NotificationTrigger::DisputeOpened => Self::notify_dispute(env, invoice, false),
+            NotificationTrigger::DisputeResolved => Self::notify_dispute(env, invoice, true),
+            NotificationTrigger::LatePenaltyApplied(penalty) => {
+                Self::notify_late_penalty(env, invoice, *penalty)
+            }
+        }
+    }
+
+    /// Failed notifications awaiting replay, oldest first
+    pub fn get_dead_letters(env: &Env) -> Vec<DeadLetter> {
+        env.storage()
+            .instance()
+            .get(&DataKey::DeadLetters)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_dead_letters(env: &Env, dead_letters: &Vec<DeadLetter>) {
+        env.storage()
+            .instance()
+            .set(&DataKey::DeadLetters, dead_letters);
+    }
+
+    fn record_dead_letter(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        trigger: NotificationTrigger,
+        error: crate::errors::QuickLendXError,
+    ) {
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextDeadLetterId)
+            .unwrap_or(1);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextDeadLetterId, &(id + 1));
+
+        let dead_letter = DeadLetter {
+            id,
+            invoice_id: invoice_id.clone(),
+            trigger,
+            error_code: error as u32,
+            failed_at: env.ledger().timestamp(),
+            replay_attempts: 0,
+        };
+        let mut dead_letters = Self::get_dead_letters(env);
+        if dead_letters.len() >= MAX_DEAD_LETTERS {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(dead_letter.clone());
+        Self::set_dead_letters(env, &dead_letters);
+
+        crate::events::emit_notification_dead_lettered(env, &dead_letter);
+    }
+
+    /// Send a dead-lettered notification again, about the invoice's current state.
+    ///
+    /// # Returns
+    /// * `Ok(true)` if it was sent and removed from the dead letters
+    /// * `Ok(false)` if it failed again; the entry stays with the new error and attempt count
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no dead letter has this ID
+    /// * `InvoiceNotFound` if its invoice no longer exists
+    pub fn replay_dead_letter(env: &Env, id: u64) -> Result<bool, crate::errors::QuickLendXError> {
+        let mut dead_letters = Self::get_dead_letters(env);
+        let index = dead_letters
+            .iter()
+            .position(|dead_letter| dead_letter.id == id)
+            .ok_or(crate::errors::QuickLendXError::StorageKeyNotFound)? as u32;
+        let mut dead_letter = dead_letters.get(index).unwrap();
+        let invoice = crate::invoice::InvoiceStorage::get_invoice(env, &dead_letter.invoice_id)
+            .ok_or(crate::errors::QuickLendXError::InvoiceNotFound)?;
+
+        match Self::dispatch(env, &invoice, &dead_letter.trigger) {
+            Ok(()) => {
+                dead_letters.remove(index);
+                Self::set_dead_letters(env, &dead_letters);
+                Ok(true)
+            }
+            Err(error) => {
+                dead_letter.error_code = error as u32;
+                dead_letter.failed_at = env.ledger().timestamp();
+                dead_letter.replay_attempts += 1;
+                dead_letters.set(index, dead_letter);
+                Self::set_dead_letters(env, &dead_letters);
+                Ok(false)
+            }
+        }
+    }
 }
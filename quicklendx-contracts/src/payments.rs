@@ -1,11 +1,37 @@
 //! Payment and escrow operations: create escrow, release, refund, and token transfers.
 //!
 //! Public release/refund entry points are wrapped with a reentrancy guard in lib.rs.
+//!
+//! Verifying an invoice never moves escrowed funds. By default any Held escrow can be released
+//! with `release_escrow_funds`; once the admin requires release approval, an escrow is only
+//! released after the admin or an invoice verifier approved it with `approve_release`.
+//!
+//! Every transfer goes through `transfer_funds`, which keeps a per-currency ledger of what the
+//! contract holds for participants (escrows, bid bonds, keeper rewards, retained fees). Funds can
+//! only leave the contract against that ledger, so no path can pay out one currency's
+//! obligations with tokens that were never received for them.
+//!
+//! An escrow records two legs: `amount` in the invoice currency, which the bid, the investment
+//! and settlement are denominated in, and `funding_amount` in the token the investor actually
+//! paid. The legs differ only when the investor funded in another whitelisted token; releases and
+//! refunds then move the funding token, split pro rata to the invoice-currency amounts.
 
+use crate::currency::CurrencyConversion;
+use crate::deposits::InvestorDeposits;
 use crate::errors::QuickLendXError;
-use crate::events::emit_escrow_created;
+use crate::events::{
+    emit_escrow_created, emit_escrow_frozen, emit_escrow_release_approved, emit_escrow_unfrozen,
+};
+use crate::invoice::Invoice;
+use crate::invoice_freeze::InvoiceFreezes;
+use crate::payout::PayoutRouting;
+use crate::rounding::{self, Rounding};
 use soroban_sdk::token;
-use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
+
+const SEGREGATED_KEY: Symbol = symbol_short!("seg_bal");
+const RELEASE_APPROVAL_REQUIRED_KEY: Symbol = symbol_short!("rel_req");
+const RELEASE_APPROVAL_KEY: Symbol = symbol_short!("rel_apr");
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -13,6 +39,7 @@ pub enum EscrowStatus {
     Held,     // Funds are held in escrow
     Released, // Funds released to business
     Refunded, // Funds refunded to investor
+    Frozen,   // Funds held while the invoice is disputed; neither released nor refunded
 }
 
 #[contracttype]
@@ -26,6 +53,15 @@ pub struct Escrow {
     pub currency: Address,
     pub created_at: u64,
     pub status: EscrowStatus,
+    /// Part of `amount` already paid to the business by milestone releases
+    pub released_amount: i128,
+    /// Token the investor funded in; the invoice currency unless the bid chose another one
+    pub funding_currency: Address,
+    /// `amount` converted into `funding_currency` when the invoice was funded; what the
+    /// contract actually holds
+    pub funding_amount: i128,
+    /// Part of `funding_amount` already paid to the business
+    pub funding_released: i128,
 }
 
 pub struct EscrowStorage;
@@ -83,39 +119,177 @@ impl EscrowStorage {
     }
 }
 
-/// Create escrow: transfer `amount` from investor to contract and store escrow record.
+/// Sign-off allowing an invoice's escrow to be released to the business
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseApproval {
+    pub invoice_id: BytesN<32>,
+    pub approver: Address,
+    pub approved_at: u64,
+}
+
+pub struct ReleaseApprovals;
+
+impl ReleaseApprovals {
+    /// Whether escrow releases need an approval first; off by default
+    pub fn is_required(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&RELEASE_APPROVAL_REQUIRED_KEY)
+            .unwrap_or(false)
+    }
+
+    pub fn set_required(env: &Env, required: bool) {
+        env.storage()
+            .instance()
+            .set(&RELEASE_APPROVAL_REQUIRED_KEY, &required);
+    }
+
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Option<ReleaseApproval> {
+        env.storage()
+            .instance()
+            .get(&(RELEASE_APPROVAL_KEY, invoice_id.clone()))
+    }
+
+    /// Approve releasing the invoice's escrow. The caller checks that `approver` is allowed to.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no escrow, `InvalidStatus` if it is not Held
+    /// * `OperationNotAllowed` if the escrow is frozen by a dispute or already approved
+    pub fn approve(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        approver: &Address,
+    ) -> Result<ReleaseApproval, QuickLendXError> {
+        let escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
+            .ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if escrow.status == EscrowStatus::Frozen || Self::get(env, invoice_id).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        if escrow.status != EscrowStatus::Held {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let approval = ReleaseApproval {
+            invoice_id: invoice_id.clone(),
+            approver: approver.clone(),
+            approved_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(RELEASE_APPROVAL_KEY, invoice_id.clone()), &approval);
+        emit_escrow_release_approved(env, &approval);
+        Ok(approval)
+    }
+
+    /// # Errors
+    /// * `OperationNotAllowed` if approvals are required and the invoice has none
+    pub fn check(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        if Self::is_required(env) && Self::get(env, invoice_id).is_none() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+}
+
+/// Per-currency total of the funds the contract received through `transfer_funds` and has not
+/// paid out yet
+pub struct SegregatedBalances;
+
+impl SegregatedBalances {
+    pub fn get(env: &Env, currency: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(SEGREGATED_KEY, currency.clone()))
+            .unwrap_or(0)
+    }
+
+    fn set(env: &Env, currency: &Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&(SEGREGATED_KEY, currency.clone()), &amount);
+    }
+
+    fn credit(env: &Env, currency: &Address, amount: i128) {
+        Self::set(
+            env,
+            currency,
+            Self::get(env, currency).saturating_add(amount),
+        );
+    }
+
+    /// # Errors
+    /// * `InsufficientFunds` if `amount` exceeds what the contract holds in `currency`
+    fn debit(env: &Env, currency: &Address, amount: i128) -> Result<(), QuickLendXError> {
+        let held = Self::get(env, currency);
+        if amount > held {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        Self::set(env, currency, held - amount);
+        Ok(())
+    }
+}
+
+/// Create escrow: transfer `amount` of the invoice currency, converted into `funding_currency`,
+/// from investor to contract and store escrow record.
+///
+/// `prepaid` is the part of the funding amount the contract already holds for the investor (a
+/// bid bond) and is not transferred again. The rest is drawn from the investor's deposit in the
+/// funding currency first, and only the shortfall is transferred from the investor.
 ///
 /// # Returns
 /// * `Ok(escrow_id)` - The new escrow ID
 ///
 /// # Errors
-/// * `InvalidAmount` if amount <= 0, or token/allowance errors from transfer
+/// * `InvalidAmount` if amount <= 0 or prepaid is outside `0..=funding amount`, or
+///   token/allowance errors from transfer
+/// * Conversion errors from `CurrencyConversion::convert` when funding in another token
 pub fn create_escrow(
     env: &Env,
-    invoice_id: &BytesN<32>,
+    invoice: &Invoice,
     investor: &Address,
-    business: &Address,
     amount: i128,
-    currency: &Address,
+    funding_currency: &Address,
+    prepaid: i128,
 ) -> Result<BytesN<32>, QuickLendXError> {
     if amount <= 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
+    let funding_amount =
+        CurrencyConversion::convert(env, amount, &invoice.currency, funding_currency)?;
+    if prepaid < 0 || prepaid > funding_amount {
+        return Err(QuickLendXError::InvalidAmount);
+    }
 
-    // Move funds from investor into contract-controlled escrow
+    // Move funds from investor into contract-controlled escrow. The deposit is only drawn once
+    // the wallet transfer has succeeded, so a failed transfer leaves it untouched.
     let contract_address = env.current_contract_address();
-    transfer_funds(env, currency, investor, &contract_address, amount)?;
+    let owed = funding_amount - prepaid;
+    let shortfall = escrow_shortfall(env, investor, funding_currency, owed);
+    if shortfall > 0 {
+        transfer_funds(
+            env,
+            funding_currency,
+            investor,
+            &contract_address,
+            shortfall,
+        )?;
+    }
+    InvestorDeposits::draw(env, investor, funding_currency, owed - shortfall);
 
     let escrow_id = EscrowStorage::generate_unique_escrow_id(env);
     let escrow = Escrow {
         escrow_id: escrow_id.clone(),
-        invoice_id: invoice_id.clone(),
+        invoice_id: invoice.id.clone(),
         investor: investor.clone(),
-        business: business.clone(),
+        business: invoice.business.clone(),
         amount,
-        currency: currency.clone(),
+        currency: invoice.currency.clone(),
         created_at: env.ledger().timestamp(),
         status: EscrowStatus::Held,
+        released_amount: 0,
+        funding_currency: funding_currency.clone(),
+        funding_amount,
+        funding_released: 0,
     };
 
     EscrowStorage::store_escrow(env, &escrow);
@@ -123,68 +297,194 @@ pub fn create_escrow(
     Ok(escrow_id)
 }
 
-/// Release escrow funds to business (contract → business). Escrow must be Held.
+/// Freeze a Held escrow while its invoice is disputed. Does nothing if the invoice has no Held
+/// escrow.
+pub fn freeze_escrow(env: &Env, invoice_id: &BytesN<32>) {
+    let Some(mut escrow) = EscrowStorage::get_escrow_by_invoice(env, invoice_id) else {
+        return;
+    };
+    if escrow.status != EscrowStatus::Held {
+        return;
+    }
+    escrow.status = EscrowStatus::Frozen;
+    EscrowStorage::update_escrow(env, &escrow);
+    emit_escrow_frozen(env, &escrow.escrow_id, invoice_id);
+}
+
+/// Return a Frozen escrow to Held once its invoice's dispute is resolved. Does nothing if the
+/// escrow is not frozen.
+pub fn unfreeze_escrow(env: &Env, invoice_id: &BytesN<32>) {
+    let Some(mut escrow) = EscrowStorage::get_escrow_by_invoice(env, invoice_id) else {
+        return;
+    };
+    if escrow.status != EscrowStatus::Frozen {
+        return;
+    }
+    escrow.status = EscrowStatus::Held;
+    EscrowStorage::update_escrow(env, &escrow);
+    emit_escrow_unfrozen(env, &escrow.escrow_id, invoice_id);
+}
+
+/// Release what is left in escrow to the business's payout address (contract → business).
+/// Escrow must be Held.
 ///
 /// # Errors
 /// * `StorageKeyNotFound` if no escrow for invoice, `InvalidStatus` if not Held
+/// * `OperationNotAllowed` if the escrow is frozen by a dispute
 pub fn release_escrow(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+    let escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
+        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    release_escrow_partial(env, invoice_id, escrow.amount - escrow.released_amount)
+}
+
+/// Release `amount` of a Held escrow to the business's payout address. The escrow becomes
+/// Released once everything it holds has been paid out.
+///
+/// # Errors
+/// * `StorageKeyNotFound` if no escrow for invoice, `InvalidStatus` if not Held
+/// * `OperationNotAllowed` if the escrow is frozen by a dispute
+/// * `InvalidAmount` if `amount` is not positive or exceeds what is left in escrow
+pub fn release_escrow_partial(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    amount: i128,
+) -> Result<(), QuickLendXError> {
     let mut escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
         .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
+    if escrow.status == EscrowStatus::Frozen {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
     if escrow.status != EscrowStatus::Held {
         return Err(QuickLendXError::InvalidStatus);
     }
+    if amount <= 0 || amount > escrow.amount - escrow.released_amount {
+        return Err(QuickLendXError::InvalidAmount);
+    }
 
-    // Transfer funds from escrow (contract) to business
+    // The last release pays out whatever is left of the funding leg, so rounding never strands
+    // funding tokens in the contract
+    let funding_part = if escrow.released_amount + amount == escrow.amount {
+        escrow.funding_amount - escrow.funding_released
+    } else {
+        rounding::mul_div(
+            amount,
+            escrow.funding_amount,
+            escrow.amount,
+            Rounding::Floor,
+        )
+    };
+
+    // Transfer funds from escrow (contract) to the business's payout address
     let contract_address = env.current_contract_address();
-    transfer_funds(
-        env,
-        &escrow.currency,
-        &contract_address,
-        &escrow.business,
-        escrow.amount,
-    )?;
+    let payout_address = PayoutRouting::get_payout_address(env, &escrow.business);
+    if funding_part > 0 {
+        transfer_funds(
+            env,
+            &escrow.funding_currency,
+            &contract_address,
+            &payout_address,
+            funding_part,
+        )?;
+    }
 
     // Update escrow status
-    escrow.status = EscrowStatus::Released;
+    escrow.funding_released += funding_part;
+    escrow.released_amount += amount;
+    if escrow.released_amount == escrow.amount {
+        escrow.status = EscrowStatus::Released;
+    }
     EscrowStorage::update_escrow(env, &escrow);
 
     Ok(())
 }
 
-/// Refund escrow funds to investor (contract → investor). Escrow must be Held.
+/// Refund what is left in escrow to the investor (contract → investor). Escrow must be Held.
+///
+/// The investor gets back what is left of the funding leg, in the token they funded in.
+///
+/// # Returns
+/// * `Ok(amount)` - the refunded amount in the invoice currency, less any milestone releases
+///   already paid out
 ///
 /// # Errors
 /// * `StorageKeyNotFound` if no escrow for invoice, `InvalidStatus` if not Held
-pub fn refund_escrow(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+/// * `OperationNotAllowed` if the escrow is frozen by a dispute
+pub fn refund_escrow(env: &Env, invoice_id: &BytesN<32>) -> Result<i128, QuickLendXError> {
     let mut escrow = EscrowStorage::get_escrow_by_invoice(env, invoice_id)
         .ok_or(QuickLendXError::StorageKeyNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
+    if escrow.status == EscrowStatus::Frozen {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
     if escrow.status != EscrowStatus::Held {
         return Err(QuickLendXError::InvalidStatus);
     }
 
     // Refund funds from escrow (contract) back to investor
     let contract_address = env.current_contract_address();
-    transfer_funds(
-        env,
-        &escrow.currency,
-        &contract_address,
-        &escrow.investor,
-        escrow.amount,
-    )?;
+    let remaining = escrow.amount - escrow.released_amount;
+    let funding_remaining = escrow.funding_amount - escrow.funding_released;
+    if funding_remaining > 0 {
+        transfer_funds(
+            env,
+            &escrow.funding_currency,
+            &contract_address,
+            &escrow.investor,
+            funding_remaining,
+        )?;
+    }
 
     // Update escrow status
     escrow.status = EscrowStatus::Refunded;
     EscrowStorage::update_escrow(env, &escrow);
 
+    Ok(remaining)
+}
+
+/// Part of `owed` that `create_escrow` transfers from the investor's wallet, after drawing on
+/// their deposit in `funding_currency`
+pub fn escrow_shortfall(
+    env: &Env,
+    investor: &Address,
+    funding_currency: &Address,
+    owed: i128,
+) -> i128 {
+    let deposit = InvestorDeposits::get(env, investor, funding_currency);
+    owed - deposit.clamp(0, owed.max(0))
+}
+
+/// Check that `from` holds and has approved the contract for at least `amount`, so a series of
+/// transfers totalling `amount` cannot fail halfway.
+///
+/// # Errors
+/// * `InsufficientFunds`, `OperationNotAllowed` (insufficient allowance), as for `transfer_funds`
+pub fn ensure_can_pay(
+    env: &Env,
+    currency: &Address,
+    from: &Address,
+    amount: i128,
+) -> Result<(), QuickLendXError> {
+    let token_client = token::Client::new(env, currency);
+    if token_client.balance(from) < amount {
+        return Err(QuickLendXError::InsufficientFunds);
+    }
+    if token_client.allowance(from, &env.current_contract_address()) < amount {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
     Ok(())
 }
 
 /// Transfer token funds from one address to another. Uses allowance when `from` is not the contract.
 ///
+/// Transfers into the contract credit the currency's segregated balance and transfers out of it
+/// debit that balance.
+///
 /// # Errors
-/// * `InvalidAmount`, `InsufficientFunds`, `OperationNotAllowed` (insufficient allowance)
+/// * `InvalidAmount`, `InsufficientFunds` (including a payout beyond the segregated balance),
+///   `OperationNotAllowed` (insufficient allowance)
 pub fn transfer_funds(
     env: &Env,
     currency: &Address,
@@ -210,6 +510,7 @@ pub fn transfer_funds(
     }
 
     if from == &contract_address {
+        SegregatedBalances::debit(env, currency, amount)?;
         token_client.transfer(from, to, &amount);
         return Ok(());
     }
@@ -220,5 +521,62 @@ pub fn transfer_funds(
     }
 
     token_client.transfer_from(&contract_address, from, to, &amount);
+    if to == &contract_address {
+        SegregatedBalances::credit(env, currency, amount);
+    }
     Ok(())
 }
+
+/// Like `transfer_funds`, but a transfer the token contract itself rejects (e.g. a frozen
+/// recipient trustline) returns `Ok(false)` with nothing moved, instead of reverting.
+///
+/// # Errors
+/// * As for `transfer_funds`, for the checks made before calling the token
+pub fn try_transfer_funds(
+    env: &Env,
+    currency: &Address,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<bool, QuickLendXError> {
+    if amount <= 0 {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+
+    if from == to {
+        return Ok(true);
+    }
+
+    let token_client = token::Client::new(env, currency);
+    let contract_address = env.current_contract_address();
+
+    if token_client.balance(from) < amount {
+        return Err(QuickLendXError::InsufficientFunds);
+    }
+
+    if from == &contract_address {
+        if SegregatedBalances::get(env, currency) < amount {
+            return Err(QuickLendXError::InsufficientFunds);
+        }
+        if token_client.try_transfer(from, to, &amount).is_err() {
+            return Ok(false);
+        }
+        SegregatedBalances::debit(env, currency, amount)?;
+        return Ok(true);
+    }
+
+    if token_client.allowance(from, &contract_address) < amount {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    if token_client
+        .try_transfer_from(&contract_address, from, to, &amount)
+        .is_err()
+    {
+        return Ok(false);
+    }
+    if to == &contract_address {
+        SegregatedBalances::credit(env, currency, amount);
+    }
+    Ok(true)
+}
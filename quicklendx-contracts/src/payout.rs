@@ -0,0 +1,143 @@
+//! Business payout routing: the address escrow releases are paid to.
+//!
+//! A business is paid at its own address until it registers a payout address. Changing the
+//! payout address is a two-step, timelocked flow: the request is announced to the business
+//! and to every investor currently funding one of its invoices, and only takes effect once
+//! `PAYOUT_CHANGE_TIMELOCK` has elapsed. This gives investors and the admin a window to react
+//! (and cancel) if a business account is taken over mid-financing.
+
+use crate::errors::QuickLendXError;
+use crate::events::{
+    emit_payout_address_changed, emit_payout_change_cancelled, emit_payout_change_requested,
+};
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::notifications::NotificationSystem;
+use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol, Vec};
+
+const PAYOUT_ADDRESS_KEY: Symbol = symbol_short!("payout");
+const PENDING_PAYOUT_KEY: Symbol = symbol_short!("payout_p");
+/// Delay between requesting and confirming a payout address change (72 hours)
+pub const PAYOUT_CHANGE_TIMELOCK: u64 = 3 * 24 * 60 * 60;
+
+/// A requested payout address change waiting out the timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutAddressChange {
+    pub business: Address,
+    pub new_address: Address,
+    pub requested_at: u64,
+    /// Earliest time the change can be confirmed
+    pub effective_at: u64,
+    /// Investors alerted when the change was requested
+    pub investors_notified: u32,
+}
+
+pub struct PayoutRouting;
+
+impl PayoutRouting {
+    /// Address escrow releases for `business` are paid to (the business itself by default)
+    pub fn get_payout_address(env: &Env, business: &Address) -> Address {
+        env.storage()
+            .instance()
+            .get(&(PAYOUT_ADDRESS_KEY, business.clone()))
+            .unwrap_or(business.clone())
+    }
+
+    pub fn get_pending_change(env: &Env, business: &Address) -> Option<PayoutAddressChange> {
+        env.storage()
+            .instance()
+            .get(&(PENDING_PAYOUT_KEY, business.clone()))
+    }
+
+    /// Request a new payout address. Replaces any pending request and restarts the timelock.
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if `new_address` is already the payout address
+    pub fn request_change(
+        env: &Env,
+        business: &Address,
+        new_address: &Address,
+    ) -> Result<PayoutAddressChange, QuickLendXError> {
+        if *new_address == Self::get_payout_address(env, business) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let now = env.ledger().timestamp();
+        let investors = Self::active_investors(env, business);
+        let change = PayoutAddressChange {
+            business: business.clone(),
+            new_address: new_address.clone(),
+            requested_at: now,
+            effective_at: now.saturating_add(PAYOUT_CHANGE_TIMELOCK),
+            investors_notified: investors.len(),
+        };
+        env.storage()
+            .instance()
+            .set(&(PENDING_PAYOUT_KEY, business.clone()), &change);
+
+        NotificationSystem::notify_payout_change_requested(env, business, &investors)?;
+        emit_payout_change_requested(env, &change);
+        Ok(change)
+    }
+
+    /// Apply the pending change once its timelock has elapsed.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no change is pending
+    /// * `OperationNotAllowed` if the timelock has not elapsed
+    pub fn confirm_change(env: &Env, business: &Address) -> Result<Address, QuickLendXError> {
+        let change =
+            Self::get_pending_change(env, business).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if env.ledger().timestamp() < change.effective_at {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let old_address = Self::get_payout_address(env, business);
+        env.storage()
+            .instance()
+            .set(&(PAYOUT_ADDRESS_KEY, business.clone()), &change.new_address);
+        env.storage()
+            .instance()
+            .remove(&(PENDING_PAYOUT_KEY, business.clone()));
+
+        emit_payout_address_changed(env, business, &old_address, &change.new_address);
+        Ok(change.new_address)
+    }
+
+    /// Drop the pending change.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no change is pending
+    pub fn cancel_change(
+        env: &Env,
+        business: &Address,
+        cancelled_by: &Address,
+    ) -> Result<(), QuickLendXError> {
+        let change =
+            Self::get_pending_change(env, business).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        env.storage()
+            .instance()
+            .remove(&(PENDING_PAYOUT_KEY, business.clone()));
+
+        emit_payout_change_cancelled(env, business, &change.new_address, cancelled_by);
+        Ok(())
+    }
+
+    /// Distinct investors of the business's currently funded invoices
+    fn active_investors(env: &Env, business: &Address) -> Vec<Address> {
+        let mut investors = Vec::new(env);
+        for invoice_id in InvoiceStorage::get_business_invoices(env, business).iter() {
+            if let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) {
+                if invoice.status != InvoiceStatus::Funded {
+                    continue;
+                }
+                if let Some(investor) = invoice.investor {
+                    if !investors.contains(&investor) {
+                        investors.push_back(investor);
+                    }
+                }
+            }
+        }
+        investors
+    }
+}
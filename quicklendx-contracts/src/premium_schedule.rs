@@ -0,0 +1,224 @@
+//! Risk-based insurance premium schedule.
+//!
+//! The premium rate for a policy is the schedule's base rate plus one adjustment for each of:
+//! the invoice's estimated default probability (its risk grade), its category, the coverage
+//! percentage bought, and the time left until the invoice is due. Adjustments may be negative
+//! so the admin can discount low-risk cover. The resulting rate is applied to the covered
+//! amount, in basis points. The default schedule charges a flat
+//! `DEFAULT_INSURANCE_PREMIUM_BPS` with no adjustments.
+
+use crate::errors::QuickLendXError;
+use crate::investment::{Investment, DEFAULT_INSURANCE_PREMIUM_BPS};
+use crate::invoice::{Invoice, InvoiceCategory};
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, Env, Symbol, Vec};
+
+const SCHEDULE_KEY: Symbol = symbol_short!("prm_sch");
+/// Highest premium rate a schedule may produce or set as its base (50%)
+pub const MAX_PREMIUM_RATE_BPS: u32 = 5_000;
+/// Most bands per factor and most category adjustments in a schedule
+pub const MAX_PREMIUM_BANDS: u32 = 10;
+
+/// Adjustment applied to values up to and including `up_to`
+///
+/// Bands are listed in strictly ascending `up_to` order; a value takes the first band that
+/// covers it, and a value above every band takes the last one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PremiumBand {
+    pub up_to: u64,
+    pub adjustment_bps: i32,
+}
+
+/// Adjustment for invoices of one category
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryPremium {
+    pub category: InvoiceCategory,
+    pub adjustment_bps: i32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PremiumSchedule {
+    pub base_bps: u32,
+    /// Banded by the invoice's estimated default probability, in basis points
+    pub risk_bands: Vec<PremiumBand>,
+    pub category_adjustments: Vec<CategoryPremium>,
+    /// Banded by coverage percentage (1-100)
+    pub coverage_bands: Vec<PremiumBand>,
+    /// Banded by seconds left until the invoice due date
+    pub term_bands: Vec<PremiumBand>,
+}
+
+/// Premium for a prospective policy and how its rate was built up
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PremiumQuote {
+    pub coverage_percentage: u32,
+    pub coverage_amount: i128,
+    pub premium: i128,
+    /// Rate applied to `coverage_amount`, after clamping to 0..=`MAX_PREMIUM_RATE_BPS`
+    pub rate_bps: u32,
+    pub base_bps: u32,
+    pub risk_adjustment_bps: i32,
+    pub category_adjustment_bps: i32,
+    pub coverage_adjustment_bps: i32,
+    pub term_adjustment_bps: i32,
+    pub default_probability_bps: u32,
+    pub term_seconds: u64,
+}
+
+pub struct PremiumSchedules;
+
+impl PremiumSchedules {
+    pub fn default_schedule(env: &Env) -> PremiumSchedule {
+        PremiumSchedule {
+            base_bps: DEFAULT_INSURANCE_PREMIUM_BPS as u32,
+            risk_bands: Vec::new(env),
+            category_adjustments: Vec::new(env),
+            coverage_bands: Vec::new(env),
+            term_bands: Vec::new(env),
+        }
+    }
+
+    pub fn get(env: &Env) -> PremiumSchedule {
+        env.storage()
+            .instance()
+            .get(&SCHEDULE_KEY)
+            .unwrap_or_else(|| Self::default_schedule(env))
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if the base rate exceeds `MAX_PREMIUM_RATE_BPS`, an adjustment's
+    ///   magnitude exceeds it, or a factor has more than `MAX_PREMIUM_BANDS` entries
+    /// * `OperationNotAllowed` if bands are not in strictly ascending order or a category is
+    ///   listed twice
+    pub fn set(env: &Env, schedule: &PremiumSchedule) -> Result<(), QuickLendXError> {
+        if schedule.base_bps > MAX_PREMIUM_RATE_BPS
+            || schedule.category_adjustments.len() > MAX_PREMIUM_BANDS
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        for bands in [
+            &schedule.risk_bands,
+            &schedule.coverage_bands,
+            &schedule.term_bands,
+        ] {
+            Self::validate_bands(bands)?;
+        }
+        for (i, entry) in schedule.category_adjustments.iter().enumerate() {
+            Self::validate_adjustment(entry.adjustment_bps)?;
+            for other in schedule.category_adjustments.iter().skip(i + 1) {
+                if other.category == entry.category {
+                    return Err(QuickLendXError::OperationNotAllowed);
+                }
+            }
+        }
+        env.storage().instance().set(&SCHEDULE_KEY, schedule);
+        Ok(())
+    }
+
+    fn validate_bands(bands: &Vec<PremiumBand>) -> Result<(), QuickLendXError> {
+        if bands.len() > MAX_PREMIUM_BANDS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let mut previous: Option<u64> = None;
+        for band in bands.iter() {
+            Self::validate_adjustment(band.adjustment_bps)?;
+            if previous.is_some_and(|previous| band.up_to <= previous) {
+                return Err(QuickLendXError::OperationNotAllowed);
+            }
+            previous = Some(band.up_to);
+        }
+        Ok(())
+    }
+
+    fn validate_adjustment(adjustment_bps: i32) -> Result<(), QuickLendXError> {
+        if adjustment_bps.unsigned_abs() > MAX_PREMIUM_RATE_BPS {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    fn band_adjustment(bands: &Vec<PremiumBand>, value: u64) -> i32 {
+        let mut adjustment = 0;
+        for band in bands.iter() {
+            adjustment = band.adjustment_bps;
+            if value <= band.up_to {
+                break;
+            }
+        }
+        adjustment
+    }
+
+    /// Quote the premium for covering `coverage_percentage` of an investment in `invoice`
+    ///
+    /// A zero rate still charges the minimum premium of 1, as with the flat rate.
+    ///
+    /// # Errors
+    /// * `InvalidCoveragePercentage` if `coverage_percentage` exceeds 100
+    /// * `InvalidAmount` if nothing would be covered (zero coverage or investment amount)
+    pub fn quote(
+        env: &Env,
+        investment: &Investment,
+        invoice: &Invoice,
+        coverage_percentage: u32,
+    ) -> Result<PremiumQuote, QuickLendXError> {
+        if coverage_percentage > 100 {
+            return Err(QuickLendXError::InvalidCoveragePercentage);
+        }
+        let schedule = Self::get(env);
+        let default_probability_bps = invoice
+            .default_probability_bps
+            .unwrap_or_else(|| crate::risk::estimate_default_probability(env, invoice));
+        let term_seconds = invoice.due_date.saturating_sub(env.ledger().timestamp());
+
+        let risk_adjustment_bps =
+            Self::band_adjustment(&schedule.risk_bands, default_probability_bps as u64);
+        let category_adjustment_bps = schedule
+            .category_adjustments
+            .iter()
+            .find(|entry| entry.category == invoice.category)
+            .map_or(0, |entry| entry.adjustment_bps);
+        let coverage_adjustment_bps =
+            Self::band_adjustment(&schedule.coverage_bands, coverage_percentage as u64);
+        let term_adjustment_bps = Self::band_adjustment(&schedule.term_bands, term_seconds);
+
+        let rate = schedule.base_bps as i64
+            + risk_adjustment_bps as i64
+            + category_adjustment_bps as i64
+            + coverage_adjustment_bps as i64
+            + term_adjustment_bps as i64;
+        let rate_bps = rate.clamp(0, MAX_PREMIUM_RATE_BPS as i64) as u32;
+
+        let coverage_amount = rounding::mul_div(
+            investment.amount,
+            coverage_percentage as i128,
+            100,
+            Rounding::Floor,
+        );
+        let premium = Investment::calculate_premium_at_rate(
+            investment.amount,
+            coverage_percentage,
+            rate_bps as i128,
+        );
+        if premium <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+
+        Ok(PremiumQuote {
+            coverage_percentage,
+            coverage_amount,
+            premium,
+            rate_bps,
+            base_bps: schedule.base_bps,
+            risk_adjustment_bps,
+            category_adjustment_bps,
+            coverage_adjustment_bps,
+            term_adjustment_bps,
+            default_probability_bps,
+            term_seconds,
+        })
+    }
+}
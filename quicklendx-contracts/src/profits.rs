@@ -18,7 +18,7 @@
 //!
 //! # Rounding Strategy
 //!
-//! - All divisions use integer floor division (truncation toward zero)
+//! - All divisions go through the `rounding` module with `Rounding::Floor`
 //! - Fees are always rounded DOWN to favor investors
 //! - This ensures: `investor_return + platform_fee == payment_amount` (no dust)
 //! - The platform absorbs any rounding loss
@@ -35,21 +35,21 @@
 //! - Immutable calculation functions (no state modification in core logic)
 //! - Bounds checking on all inputs
 //! - Fee configuration requires admin authorization
+//!
+//! # Fee Configuration
+//!
+//! The fee rate is read from `fees::FeeManager`, which owns the single platform fee
+//! configuration. This module only holds the calculation formulas.
 
 use crate::errors::QuickLendXError;
-use crate::events::emit_platform_fee_updated;
-use soroban_sdk::{contracttype, symbol_short, Address, Env};
+use crate::fees::{self, FeeManager};
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, Address, Env};
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-/// Default platform fee in basis points (2% = 200 bps)
-pub const DEFAULT_PLATFORM_FEE_BPS: i128 = 200;
-
-/// Maximum allowed platform fee in basis points (10% = 1000 bps)
-pub const MAX_PLATFORM_FEE_BPS: i128 = 1_000;
-
 /// Basis points denominator for percentage calculations (100% = 10,000 bps)
 pub const BPS_DENOMINATOR: i128 = 10_000;
 
@@ -60,7 +60,8 @@ pub const MIN_VALID_AMOUNT: i128 = 0;
 // Data Types
 // ============================================================================
 
-/// Platform fee configuration stored on-chain
+/// Platform fee configuration as returned by the deprecated `get_platform_fee`, and as
+/// stored by the setter it replaced
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct PlatformFeeConfig {
@@ -72,6 +73,16 @@ pub struct PlatformFeeConfig {
     pub updated_by: Address,
 }
 
+impl From<fees::PlatformFeeConfig> for PlatformFeeConfig {
+    fn from(config: fees::PlatformFeeConfig) -> Self {
+        PlatformFeeConfig {
+            fee_bps: config.fee_bps as i128,
+            updated_at: config.updated_at,
+            updated_by: config.updated_by,
+        }
+    }
+}
+
 /// Complete breakdown of profit and fee calculation
 ///
 /// This struct provides full transparency into how funds are distributed
@@ -107,68 +118,18 @@ pub struct ProfitFeeBreakdown {
 pub struct PlatformFee;
 
 impl PlatformFee {
-    /// Storage key for fee configuration
-    /// Note: Uses "pf_cfg" to avoid conflict with fees.rs which uses "fee_cfg" for FeeStructure list
-    const STORAGE_KEY: soroban_sdk::Symbol = symbol_short!("pf_cfg");
-
-    /// Creates the default fee configuration
-    fn default_config(env: &Env) -> PlatformFeeConfig {
-        PlatformFeeConfig {
-            fee_bps: DEFAULT_PLATFORM_FEE_BPS,
-            updated_at: 0,
-            updated_by: env.current_contract_address(),
-        }
-    }
-
     /// Retrieves the current platform fee configuration
     ///
-    /// Returns the stored configuration or default (2%) if not configured.
+    /// Reads the unified configuration owned by `fees::FeeManager`, so the rate used
+    /// here always matches the one applied at settlement.
     ///
     /// # Example
     /// ```ignore
     /// let config = PlatformFee::get_config(&env);
     /// assert_eq!(config.fee_bps, 200); // 2%
     /// ```
-    pub fn get_config(env: &Env) -> PlatformFeeConfig {
-        env.storage()
-            .instance()
-            .get(&Self::STORAGE_KEY)
-            .unwrap_or_else(|| Self::default_config(env))
-    }
-
-    /// Updates the platform fee configuration
-    ///
-    /// # Arguments
-    /// * `env` - Soroban environment
-    /// * `admin` - Admin address (must be authorized)
-    /// * `new_fee_bps` - New fee in basis points (0-1000)
-    ///
-    /// # Errors
-    /// * `InvalidAmount` - If fee_bps < 0 or > 1000 (10%)
-    ///
-    /// # Security
-    /// Requires admin authorization via `require_auth()`
-    pub fn set_config(
-        env: &Env,
-        admin: &Address,
-        new_fee_bps: i128,
-    ) -> Result<PlatformFeeConfig, QuickLendXError> {
-        admin.require_auth();
-
-        // Validate fee bounds
-        if new_fee_bps < 0 || new_fee_bps > MAX_PLATFORM_FEE_BPS {
-            return Err(QuickLendXError::InvalidAmount);
-        }
-
-        let config = PlatformFeeConfig {
-            fee_bps: new_fee_bps,
-            updated_at: env.ledger().timestamp(),
-            updated_by: admin.clone(),
-        };
-
-        env.storage().instance().set(&Self::STORAGE_KEY, &config);
-        emit_platform_fee_updated(env, &config);
-        Ok(config)
+    pub fn get_config(env: &Env) -> fees::PlatformFeeConfig {
+        FeeManager::load_platform_fee_config(env)
     }
 
     /// Core calculation: computes investor return and platform fee
@@ -211,7 +172,7 @@ impl PlatformFee {
     /// ```
     pub fn calculate(env: &Env, investment_amount: i128, payment_amount: i128) -> (i128, i128) {
         let config = Self::get_config(env);
-        Self::calculate_with_fee_bps(investment_amount, payment_amount, config.fee_bps)
+        Self::calculate_with_fee_bps(investment_amount, payment_amount, config.fee_bps as i128)
     }
 
     /// Calculate with explicit fee basis points (pure function)
@@ -243,12 +204,8 @@ impl PlatformFee {
             return (payment_amount, 0);
         }
 
-        // Calculate platform fee using integer division (rounds down)
-        // This ensures no dust and favors the investor
-        let platform_fee = gross_profit
-            .saturating_mul(fee_bps)
-            .checked_div(BPS_DENOMINATOR)
-            .unwrap_or(0);
+        // The platform fee rounds down, which favors the investor
+        let platform_fee = rounding::bps_of(gross_profit, fee_bps, Rounding::Floor);
 
         // Investor return = total payment - platform fee
         // This guarantees: investor_return + platform_fee == payment_amount
@@ -286,7 +243,11 @@ impl PlatformFee {
         payment_amount: i128,
     ) -> ProfitFeeBreakdown {
         let config = Self::get_config(env);
-        Self::calculate_breakdown_with_fee_bps(investment_amount, payment_amount, config.fee_bps)
+        Self::calculate_breakdown_with_fee_bps(
+            investment_amount,
+            payment_amount,
+            config.fee_bps as i128,
+        )
     }
 
     /// Calculate breakdown with explicit fee basis points (pure function)
@@ -426,15 +387,8 @@ pub fn calculate_treasury_split(platform_fee: i128, treasury_share_bps: i128) ->
         return (platform_fee, 0);
     }
 
-    let treasury_amount = platform_fee
-        .saturating_mul(treasury_share_bps)
-        .checked_div(BPS_DENOMINATOR)
-        .unwrap_or(0);
-
-    // Remaining amount is computed by subtraction to avoid dust
-    let remaining = platform_fee.saturating_sub(treasury_amount);
-
-    (treasury_amount, remaining)
+    // The remaining amount takes the rounding dust
+    rounding::split_bps(platform_fee, treasury_share_bps)
 }
 
 // ============================================================================
@@ -1,5 +1,7 @@
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, String, Vec};
 
+use crate::bid::BidStorage;
+use crate::config_log::{ConfigLog, ConfigParameter, ConfigValue};
 use crate::QuickLendXError;
 
 #[contracttype]
@@ -10,10 +12,328 @@ pub struct ProtocolLimits {
     pub grace_period_seconds: u64,
 }
 
+/// Per-investor bid limits enforced in `place_bid`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidLimits {
+    /// Open bids an investor may hold on a single invoice
+    pub max_active_bids_per_invoice: u32,
+    /// Open bids an investor may hold across all invoices (0 = unlimited)
+    pub max_active_bids_per_investor: u32,
+    /// Bids an investor may place within `bid_rate_window_seconds` (0 = unlimited)
+    pub max_bids_per_window: u32,
+    pub bid_rate_window_seconds: u64,
+}
+
+/// Characters allowed in user-supplied text
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CharacterPolicy {
+    Any,
+    /// Reject ASCII control characters; other UTF-8 text is allowed
+    NoControl,
+    /// Only printable ASCII (0x20..=0x7E)
+    PrintableAscii,
+}
+
+impl CharacterPolicy {
+    /// Stable code recorded in the config history
+    pub fn code(&self) -> u32 {
+        match self {
+            CharacterPolicy::Any => 0,
+            CharacterPolicy::NoControl => 1,
+            CharacterPolicy::PrintableAscii => 2,
+        }
+    }
+
+    fn allows(&self, byte: u8) -> bool {
+        match self {
+            CharacterPolicy::Any => true,
+            CharacterPolicy::NoControl => byte >= 0x20 && byte != 0x7F,
+            CharacterPolicy::PrintableAscii => (0x20..=0x7E).contains(&byte),
+        }
+    }
+}
+
+/// Validation applied to user-supplied text: invoice descriptions and metadata on upload and
+/// update, tags, rating feedback and dispute text
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContentPolicy {
+    /// Applies to invoice descriptions, metadata fields and line item descriptions
+    pub max_description_length: u32,
+    pub max_tag_length: u32,
+    pub max_tags_per_invoice: u32,
+    pub max_feedback_length: u32,
+    /// Applies to dispute reasons and resolutions
+    pub max_dispute_reason_length: u32,
+    pub max_dispute_evidence_length: u32,
+    pub character_policy: CharacterPolicy,
+    /// Rejected anywhere in the text, ignoring ASCII case
+    pub blocked_terms: Vec<String>,
+}
+
 const LIMITS_KEY: &str = "protocol_limits";
+const BID_LIMITS_KEY: &str = "bid_limits";
+const CONTENT_POLICY_KEY: &str = "content_policy";
+/// Upper bound on any text length the content policy can allow
+pub const MAX_TEXT_LENGTH: u32 = 2_048;
+/// Upper bound on tags per invoice the content policy can allow
+pub const MAX_TAGS_LIMIT: u32 = 50;
+pub const MAX_BLOCKED_TERMS: u32 = 20;
+pub const MAX_BLOCKED_TERM_LENGTH: u32 = 32;
+/// Upper bound on the length of a business-assigned invoice number
+pub const MAX_INVOICE_NUMBER_LENGTH: u32 = 64;
+/// Upper bound on the length of an invoice message board post
+pub const MAX_MESSAGE_LENGTH: u32 = 500;
 const DEFAULT_MIN_AMOUNT: i128 = 1_000_000; // 1 token (6 decimals)
 const DEFAULT_MAX_DUE_DAYS: u64 = 365;
 const DEFAULT_GRACE_PERIOD: u64 = 86400; // 24 hours
+const MAX_BID_RATE_WINDOW: u64 = 2_592_000; // 30 days
+
+impl BidLimits {
+    pub fn get(env: &Env) -> BidLimits {
+        env.storage()
+            .instance()
+            .get(&BID_LIMITS_KEY)
+            .unwrap_or(BidLimits {
+                max_active_bids_per_invoice: 1,
+                max_active_bids_per_investor: 0,
+                max_bids_per_window: 0,
+                bid_rate_window_seconds: 0,
+            })
+    }
+
+    pub fn set(env: &Env, limits: &BidLimits) -> Result<(), QuickLendXError> {
+        if limits.max_active_bids_per_invoice == 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if limits.max_bids_per_window > 0
+            && (limits.bid_rate_window_seconds == 0
+                || limits.bid_rate_window_seconds > MAX_BID_RATE_WINDOW)
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        env.storage().instance().set(&BID_LIMITS_KEY, limits);
+        Ok(())
+    }
+
+    /// Check that `investor` may place another bid on `invoice_id`
+    ///
+    /// Open bids count toward the per-invoice and platform-wide limits until they expire; every
+    /// bid placed within the window counts toward the rate limit, whatever its status.
+    pub fn enforce(
+        env: &Env,
+        investor: &Address,
+        invoice_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let limits = Self::get(env);
+        let now = env.ledger().timestamp();
+        let window_start = now.saturating_sub(limits.bid_rate_window_seconds);
+        let mut active_on_invoice = 0u32;
+        let mut active_total = 0u32;
+        let mut placed_in_window = 0u32;
+        for bid_id in BidStorage::get_bids_by_investor_all(env, investor).iter() {
+            let bid = match BidStorage::get_bid(env, &bid_id) {
+                Some(bid) => bid,
+                None => continue,
+            };
+            if bid.is_open() && !bid.is_expired(now) {
+                active_total += 1;
+                if bid.invoice_id == *invoice_id {
+                    active_on_invoice += 1;
+                }
+            }
+            if bid.timestamp >= window_start {
+                placed_in_window += 1;
+            }
+        }
+
+        if active_on_invoice >= limits.max_active_bids_per_invoice
+            || (limits.max_active_bids_per_investor > 0
+                && active_total >= limits.max_active_bids_per_investor)
+            || (limits.max_bids_per_window > 0 && placed_in_window >= limits.max_bids_per_window)
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+}
+
+impl ContentPolicy {
+    pub fn get(env: &Env) -> ContentPolicy {
+        env.storage()
+            .instance()
+            .get(&CONTENT_POLICY_KEY)
+            .unwrap_or(ContentPolicy {
+                max_description_length: 1_024,
+                max_tag_length: 50,
+                max_tags_per_invoice: 10,
+                max_feedback_length: 500,
+                max_dispute_reason_length: 500,
+                max_dispute_evidence_length: 1_000,
+                character_policy: CharacterPolicy::Any,
+                blocked_terms: Vec::new(env),
+            })
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if a limit is zero or above `MAX_TEXT_LENGTH` / `MAX_TAGS_LIMIT`
+    /// * `InvalidDescription` if there are more than `MAX_BLOCKED_TERMS` blocked terms or one
+    ///   is empty or longer than `MAX_BLOCKED_TERM_LENGTH`
+    pub fn set(env: &Env, policy: &ContentPolicy) -> Result<(), QuickLendXError> {
+        for limit in [
+            policy.max_description_length,
+            policy.max_tag_length,
+            policy.max_feedback_length,
+            policy.max_dispute_reason_length,
+            policy.max_dispute_evidence_length,
+        ] {
+            if limit == 0 || limit > MAX_TEXT_LENGTH {
+                return Err(QuickLendXError::InvalidAmount);
+            }
+        }
+        if policy.max_tags_per_invoice == 0 || policy.max_tags_per_invoice > MAX_TAGS_LIMIT {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if policy.blocked_terms.len() > MAX_BLOCKED_TERMS
+            || policy
+                .blocked_terms
+                .iter()
+                .any(|term| term.len() == 0 || term.len() > MAX_BLOCKED_TERM_LENGTH)
+        {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        env.storage().instance().set(&CONTENT_POLICY_KEY, policy);
+        Ok(())
+    }
+
+    /// Whether `text` is at most `max_length` bytes and passes the character policy and
+    /// blocked terms
+    fn accepts(&self, text: &String, max_length: u32) -> bool {
+        let length = text.len();
+        if length > max_length {
+            return false;
+        }
+        if self.character_policy == CharacterPolicy::Any && self.blocked_terms.is_empty() {
+            return true;
+        }
+
+        let mut buffer = [0u8; MAX_TEXT_LENGTH as usize];
+        let bytes = &mut buffer[..length as usize];
+        text.copy_into_slice(bytes);
+        if !bytes.iter().all(|byte| self.character_policy.allows(*byte)) {
+            return false;
+        }
+        for term in self.blocked_terms.iter() {
+            let mut term_buffer = [0u8; MAX_BLOCKED_TERM_LENGTH as usize];
+            let term_bytes = &mut term_buffer[..term.len() as usize];
+            term.copy_into_slice(term_bytes);
+            if bytes
+                .windows(term_bytes.len())
+                .any(|window| window.eq_ignore_ascii_case(term_bytes))
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Invoice descriptions, metadata fields and line item descriptions
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the text is empty or rejected by the policy
+    pub fn validate_description(env: &Env, text: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if text.len() == 0 || !policy.accepts(text, policy.max_description_length) {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    /// * `TagLimitExceeded` if there are more than `max_tags_per_invoice` tags
+    /// * `InvalidTag` if a tag is empty or rejected by the policy
+    pub fn validate_tags(env: &Env, tags: &Vec<String>) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if tags.len() > policy.max_tags_per_invoice {
+            return Err(QuickLendXError::TagLimitExceeded);
+        }
+        for tag in tags.iter() {
+            policy.check_tag(&tag)?;
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    /// * `InvalidTag` if the tag is empty or rejected by the policy
+    pub fn check_tag(&self, tag: &String) -> Result<(), QuickLendXError> {
+        if tag.len() == 0 || !self.accepts(tag, self.max_tag_length) {
+            return Err(QuickLendXError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    /// Business-assigned invoice numbers
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the number is empty, longer than `MAX_INVOICE_NUMBER_LENGTH`
+    ///   or rejected by the policy
+    pub fn validate_invoice_number(env: &Env, number: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if number.len() == 0 || !policy.accepts(number, MAX_INVOICE_NUMBER_LENGTH) {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        Ok(())
+    }
+
+    /// Rating feedback may be empty
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the feedback is rejected by the policy
+    pub fn validate_feedback(env: &Env, feedback: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if !policy.accepts(feedback, policy.max_feedback_length) {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        Ok(())
+    }
+
+    /// Invoice message board posts
+    ///
+    /// # Errors
+    /// * `InvalidDescription` if the message is empty, longer than `MAX_MESSAGE_LENGTH` or
+    ///   rejected by the policy
+    pub fn validate_message(env: &Env, message: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if message.len() == 0 || !policy.accepts(message, MAX_MESSAGE_LENGTH) {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        Ok(())
+    }
+
+    /// Dispute reasons and resolutions
+    ///
+    /// # Errors
+    /// * `InvalidDisputeReason` if the text is empty or rejected by the policy
+    pub fn validate_dispute_reason(env: &Env, text: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if text.len() == 0 || !policy.accepts(text, policy.max_dispute_reason_length) {
+            return Err(QuickLendXError::InvalidDisputeReason);
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    /// * `InvalidDisputeEvidence` if the evidence is empty or rejected by the policy
+    pub fn validate_dispute_evidence(env: &Env, evidence: &String) -> Result<(), QuickLendXError> {
+        let policy = Self::get(env);
+        if evidence.len() == 0 || !policy.accepts(evidence, policy.max_dispute_evidence_length) {
+            return Err(QuickLendXError::InvalidDisputeEvidence);
+        }
+        Ok(())
+    }
+}
 
 #[contract]
 pub struct ProtocolLimitsContract;
@@ -67,6 +387,7 @@ impl ProtocolLimitsContract {
             return Err(QuickLendXError::InvalidTimestamp);
         }
 
+        let old = Self::get_protocol_limits(env.clone());
         let limits = ProtocolLimits {
             min_invoice_amount,
             max_due_date_days,
@@ -74,6 +395,28 @@ impl ProtocolLimitsContract {
         };
 
         env.storage().instance().set(&LIMITS_KEY, &limits);
+
+        ConfigLog::record(
+            &env,
+            ConfigParameter::MinInvoiceAmount,
+            ConfigValue::I128(old.min_invoice_amount),
+            ConfigValue::I128(min_invoice_amount),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::MaxDueDateDays,
+            ConfigValue::U64(old.max_due_date_days),
+            ConfigValue::U64(max_due_date_days),
+            &admin,
+        );
+        ConfigLog::record(
+            &env,
+            ConfigParameter::GracePeriodSeconds,
+            ConfigValue::U64(old.grace_period_seconds),
+            ConfigValue::U64(grace_period_seconds),
+            &admin,
+        );
         Ok(())
     }
 
@@ -0,0 +1,195 @@
+//! Compact state digest for reconciliation.
+//!
+//! Off-chain systems (indexers, accounting, monitoring) poll `export_state_digest` to detect
+//! drift from the contract without paging through every invoice. The digest reads the status
+//! indexes, the funded invoices and the ID counters, so its cost grows with the number of
+//! funded invoices rather than with the full history.
+//!
+//! `reconcile_escrows` checks one currency's ledger against the token itself: the escrows still
+//! held must be covered by the tracked segregated balance, which in turn must be covered by the
+//! contract's actual token balance. A gap means an accounting bug, and raises an alert event.
+
+use crate::config_log::ConfigLog;
+use crate::currency::CurrencyWhitelist;
+use crate::events::emit_escrow_reconciliation_alert;
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::payments::{EscrowStatus, EscrowStorage, SegregatedBalances};
+use soroban_sdk::{contracttype, symbol_short, token, Address, Env, Symbol, Vec};
+
+/// Per-currency totals in a state digest
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CurrencyDigest {
+    pub currency: Address,
+    /// Sum of escrows still held for funded invoices, in the token they were funded in
+    pub escrowed: i128,
+    /// Face value still owed on funded invoices (amount minus payments received)
+    pub outstanding: i128,
+    /// Tokens the contract tracks as held for participants, see `get_segregated_balance`
+    pub segregated_balance: i128,
+}
+
+/// Snapshot of invoice counts, per-currency totals and the latest ID sequence numbers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateDigest {
+    pub generated_at: u64,
+    pub ledger_sequence: u32,
+    pub pending_count: u32,
+    pub verified_count: u32,
+    pub funded_count: u32,
+    pub paid_count: u32,
+    pub defaulted_count: u32,
+    pub cancelled_count: u32,
+    pub refunded_count: u32,
+    pub expired_count: u32,
+    /// Whitelisted currencies first, then any other currency with funded invoices
+    pub currencies: Vec<CurrencyDigest>,
+    /// Number of IDs issued so far by each generator
+    pub invoice_sequence: u32,
+    pub bid_sequence: u64,
+    pub investment_sequence: u64,
+    pub escrow_sequence: u64,
+    pub audit_sequence: u64,
+    pub config_change_sequence: u64,
+}
+
+/// Escrows held in one currency compared with what the contract tracks and actually holds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowReconciliation {
+    pub currency: Address,
+    pub checked_at: u64,
+    /// Number of escrows still held or frozen
+    pub active_escrows: u32,
+    /// Amount still held in those escrows
+    pub escrowed: i128,
+    /// Tokens the contract tracks as held for participants, see `get_segregated_balance`
+    pub segregated_balance: i128,
+    /// The contract's balance as reported by the token
+    pub token_balance: i128,
+    /// `token_balance - escrowed`; negative when the contract cannot cover its escrows
+    pub discrepancy: i128,
+    /// Whether `escrowed <= segregated_balance <= token_balance`
+    pub balanced: bool,
+}
+
+fn counter(env: &Env, key: Symbol) -> u64 {
+    env.storage().instance().get(&key).unwrap_or(0u64)
+}
+
+fn currency_index(env: &Env, currencies: &mut Vec<CurrencyDigest>, currency: &Address) -> u32 {
+    if let Some(index) = currencies
+        .iter()
+        .position(|entry| entry.currency == *currency)
+    {
+        return index as u32;
+    }
+    currencies.push_back(CurrencyDigest {
+        currency: currency.clone(),
+        escrowed: 0,
+        outstanding: 0,
+        segregated_balance: SegregatedBalances::get(env, currency),
+    });
+    currencies.len() - 1
+}
+
+/// Build the current state digest
+pub fn export_state_digest(env: &Env) -> StateDigest {
+    let count = |status: InvoiceStatus| InvoiceStorage::get_invoices_by_status(env, &status).len();
+
+    let mut currencies: Vec<CurrencyDigest> = Vec::new(env);
+    for currency in CurrencyWhitelist::get_whitelisted_currencies(env).iter() {
+        currency_index(env, &mut currencies, &currency);
+    }
+    for invoice_id in InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded).iter() {
+        let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) else {
+            continue;
+        };
+        let index = currency_index(env, &mut currencies, &invoice.currency);
+        let mut entry = currencies.get_unchecked(index);
+        entry.outstanding = entry
+            .outstanding
+            .saturating_add(invoice.amount.saturating_sub(invoice.total_paid).max(0));
+        currencies.set(index, entry);
+        // Escrows are counted in the token actually held, which may differ from the invoice's
+        if let Some(escrow) = EscrowStorage::get_escrow_by_invoice(env, &invoice_id) {
+            if escrow.status == EscrowStatus::Held || escrow.status == EscrowStatus::Frozen {
+                let index = currency_index(env, &mut currencies, &escrow.funding_currency);
+                let mut entry = currencies.get_unchecked(index);
+                entry.escrowed = entry
+                    .escrowed
+                    .saturating_add(escrow.funding_amount - escrow.funding_released);
+                currencies.set(index, entry);
+            }
+        }
+    }
+
+    StateDigest {
+        generated_at: env.ledger().timestamp(),
+        ledger_sequence: env.ledger().sequence(),
+        pending_count: count(InvoiceStatus::Pending),
+        verified_count: count(InvoiceStatus::Verified),
+        funded_count: count(InvoiceStatus::Funded),
+        paid_count: count(InvoiceStatus::Paid),
+        defaulted_count: count(InvoiceStatus::Defaulted),
+        cancelled_count: count(InvoiceStatus::Cancelled),
+        refunded_count: count(InvoiceStatus::Refunded),
+        expired_count: count(InvoiceStatus::Expired),
+        currencies,
+        invoice_sequence: env
+            .storage()
+            .instance()
+            .get(&symbol_short!("inv_cnt"))
+            .unwrap_or(0u32),
+        bid_sequence: counter(env, symbol_short!("bid_cnt")),
+        investment_sequence: counter(env, symbol_short!("invst_cnt")),
+        escrow_sequence: counter(env, symbol_short!("esc_cnt")),
+        audit_sequence: counter(env, symbol_short!("aud_cnt")),
+        config_change_sequence: ConfigLog::count(env),
+    }
+}
+
+/// Compare the escrows held in `currency` with the tracked and actual balances
+///
+/// Scans the escrows of funded, paid and defaulted invoices, since an escrow may still be held
+/// after its invoice settled or defaulted. Emits an alert event when the report is not balanced.
+pub fn reconcile_escrows(env: &Env, currency: &Address) -> EscrowReconciliation {
+    let mut active_escrows = 0u32;
+    let mut escrowed = 0i128;
+    for status in [
+        InvoiceStatus::Funded,
+        InvoiceStatus::Paid,
+        InvoiceStatus::Defaulted,
+    ] {
+        for invoice_id in InvoiceStorage::get_invoices_by_status(env, &status).iter() {
+            let Some(escrow) = EscrowStorage::get_escrow_by_invoice(env, &invoice_id) else {
+                continue;
+            };
+            if escrow.funding_currency != *currency
+                || (escrow.status != EscrowStatus::Held && escrow.status != EscrowStatus::Frozen)
+            {
+                continue;
+            }
+            active_escrows += 1;
+            escrowed = escrowed.saturating_add(escrow.funding_amount - escrow.funding_released);
+        }
+    }
+
+    let segregated_balance = SegregatedBalances::get(env, currency);
+    let token_balance = token::Client::new(env, currency).balance(&env.current_contract_address());
+    let report = EscrowReconciliation {
+        currency: currency.clone(),
+        checked_at: env.ledger().timestamp(),
+        active_escrows,
+        escrowed,
+        segregated_balance,
+        token_balance,
+        discrepancy: token_balance.saturating_sub(escrowed),
+        balanced: escrowed <= segregated_balance && segregated_balance <= token_balance,
+    };
+    if !report.balanced {
+        emit_escrow_reconciliation_alert(env, &report);
+    }
+    report
+}
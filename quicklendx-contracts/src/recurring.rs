@@ -0,0 +1,255 @@
+//! Recurring invoice schedules.
+//!
+//! A business registers a template (amount, customer, interval, count) once; the
+//! keeper-callable `mint_due` then creates the next Pending invoice each time the interval
+//! elapses, until `total_count` invoices have been minted or the schedule is cancelled.
+//! Every minted invoice is linked back to its schedule.
+
+use crate::audit;
+use crate::currency::CurrencyWhitelist;
+use crate::errors::QuickLendXError;
+use crate::events::{emit_invoice_uploaded, emit_recurring_invoice_minted};
+use crate::invoice::{Invoice, InvoiceCategory, InvoiceStorage};
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::protocol_limits::ContentPolicy;
+use crate::verification::{self, BusinessVerificationStorage};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const SCHEDULE_COUNTER_KEY: Symbol = symbol_short!("rec_cnt");
+const ACTIVE_SCHEDULES_KEY: Symbol = symbol_short!("rec_act");
+const BUSINESS_SCHEDULES_KEY: Symbol = symbol_short!("rec_bus");
+const INVOICE_SCHEDULE_KEY: Symbol = symbol_short!("rec_of");
+/// Upper bound on invoices per schedule
+pub const MAX_RECURRING_COUNT: u32 = 120;
+/// Shortest allowed interval between invoices (1 day)
+pub const MIN_RECURRING_INTERVAL: u64 = 24 * 60 * 60;
+/// Upper bound on invoices minted per `mint_due` call
+pub const MAX_MINTS_PER_CALL: u32 = 25;
+
+/// What each invoice of a schedule looks like
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurringTemplate {
+    pub amount: i128,
+    pub currency: Address,
+    pub description: String,
+    pub category: InvoiceCategory,
+    pub customer_name: String,
+    /// Seconds between invoices
+    pub interval_seconds: u64,
+    /// Each invoice is due this long after it is minted
+    pub payment_terms_seconds: u64,
+    /// Number of invoices to mint
+    pub total_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecurringSchedule {
+    pub schedule_id: BytesN<32>,
+    pub business: Address,
+    pub template: RecurringTemplate,
+    pub created_at: u64,
+    /// When the keeper may mint the next invoice
+    pub next_mint_at: u64,
+    pub minted_count: u32,
+    pub invoice_ids: Vec<BytesN<32>>,
+    pub active: bool,
+}
+
+pub struct RecurringSchedules;
+
+impl RecurringSchedules {
+    pub fn get(env: &Env, schedule_id: &BytesN<32>) -> Option<RecurringSchedule> {
+        env.storage().instance().get(schedule_id)
+    }
+
+    pub fn get_business_schedules(env: &Env, business: &Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(BUSINESS_SCHEDULES_KEY, business.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Schedule an invoice was minted from, if any
+    pub fn get_invoice_schedule(env: &Env, invoice_id: &BytesN<32>) -> Option<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(INVOICE_SCHEDULE_KEY, invoice_id.clone()))
+    }
+
+    fn get_active(env: &Env) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&ACTIVE_SCHEDULES_KEY)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn remove_active(env: &Env, schedule_id: &BytesN<32>) {
+        let mut active = Self::get_active(env);
+        if let Some(index) = active.first_index_of(schedule_id) {
+            active.remove(index);
+            env.storage().instance().set(&ACTIVE_SCHEDULES_KEY, &active);
+        }
+    }
+
+    fn generate_id(env: &Env) -> BytesN<32> {
+        let counter: u32 = env
+            .storage()
+            .instance()
+            .get(&SCHEDULE_COUNTER_KEY)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&SCHEDULE_COUNTER_KEY, &(counter + 1));
+
+        let mut id_bytes = [0u8; 32];
+        id_bytes[0] = 0x52; // 'R' for Recurring
+        id_bytes[1..9].copy_from_slice(&env.ledger().timestamp().to_be_bytes());
+        id_bytes[9..13].copy_from_slice(&counter.to_be_bytes());
+        BytesN::from_array(env, &id_bytes)
+    }
+
+    /// Register a schedule; the first invoice is due to be minted immediately.
+    ///
+    /// # Errors
+    /// * `BusinessNotVerified`, `CurrencyNotWhitelisted`, `InvalidDescription`
+    /// * `InvalidAmount` if the amount is not positive or the count is outside 1..=MAX_RECURRING_COUNT
+    /// * `InvalidTimestamp` if the interval is under a day or the payment terms are zero
+    pub fn create(
+        env: &Env,
+        business: &Address,
+        template: RecurringTemplate,
+    ) -> Result<RecurringSchedule, QuickLendXError> {
+        if !BusinessVerificationStorage::is_business_verified(env, business) {
+            return Err(QuickLendXError::BusinessNotVerified);
+        }
+        if template.amount <= 0
+            || template.total_count == 0
+            || template.total_count > MAX_RECURRING_COUNT
+        {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        if template.interval_seconds < MIN_RECURRING_INTERVAL || template.payment_terms_seconds == 0
+        {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        ContentPolicy::validate_description(env, &template.description)?;
+        ContentPolicy::validate_description(env, &template.customer_name)?;
+        CurrencyWhitelist::require_allowed_currency(env, &template.currency)?;
+        verification::validate_invoice_category(&template.category)?;
+
+        let now = env.ledger().timestamp();
+        let schedule = RecurringSchedule {
+            schedule_id: Self::generate_id(env),
+            business: business.clone(),
+            template,
+            created_at: now,
+            next_mint_at: now,
+            minted_count: 0,
+            invoice_ids: Vec::new(env),
+            active: true,
+        };
+        env.storage()
+            .instance()
+            .set(&schedule.schedule_id, &schedule);
+
+        let mut active = Self::get_active(env);
+        active.push_back(schedule.schedule_id.clone());
+        env.storage().instance().set(&ACTIVE_SCHEDULES_KEY, &active);
+
+        let mut owned = Self::get_business_schedules(env, business);
+        owned.push_back(schedule.schedule_id.clone());
+        env.storage()
+            .instance()
+            .set(&(BUSINESS_SCHEDULES_KEY, business.clone()), &owned);
+
+        Ok(schedule)
+    }
+
+    /// Stop minting further invoices. Invoices already minted are unaffected.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound`, `InvalidStatus` if the schedule is no longer active
+    pub fn cancel(env: &Env, schedule_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        let mut schedule =
+            Self::get(env, schedule_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if !schedule.active {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        schedule.active = false;
+        env.storage().instance().set(schedule_id, &schedule);
+        Self::remove_active(env, schedule_id);
+        Ok(())
+    }
+
+    /// Mint the next invoice of every active schedule whose interval has elapsed, at most one
+    /// per schedule and `MAX_MINTS_PER_CALL` in total. Schedules of businesses that are no
+    /// longer verified are skipped. Returns `(minted, scanned)`.
+    pub fn mint_due(env: &Env) -> (u32, u32) {
+        let now = env.ledger().timestamp();
+        let active = Self::get_active(env);
+        let mut minted = 0u32;
+        let mut scanned = 0u32;
+
+        for schedule_id in active.iter() {
+            if minted >= MAX_MINTS_PER_CALL {
+                break;
+            }
+            scanned += 1;
+            let Some(mut schedule) = Self::get(env, &schedule_id) else {
+                continue;
+            };
+            if now < schedule.next_mint_at
+                || !BusinessVerificationStorage::is_business_verified(env, &schedule.business)
+            {
+                continue;
+            }
+
+            let invoice = Self::mint_invoice(env, &schedule);
+            schedule.minted_count += 1;
+            schedule.invoice_ids.push_back(invoice.id.clone());
+            schedule.next_mint_at = now.saturating_add(schedule.template.interval_seconds);
+            if schedule.minted_count >= schedule.template.total_count {
+                schedule.active = false;
+                Self::remove_active(env, &schedule_id);
+            }
+            env.storage().instance().set(&schedule_id, &schedule);
+            emit_recurring_invoice_minted(env, &schedule_id, &invoice.id, schedule.minted_count);
+            minted += 1;
+        }
+        (minted, scanned)
+    }
+
+    fn mint_invoice(env: &Env, schedule: &RecurringSchedule) -> Invoice {
+        let template = &schedule.template;
+        let mut invoice = Invoice::new(
+            env,
+            schedule.business.clone(),
+            template.amount,
+            template.currency.clone(),
+            env.ledger()
+                .timestamp()
+                .saturating_add(template.payment_terms_seconds),
+            template.description.clone(),
+            template.category.clone(),
+            Vec::new(env),
+        );
+        invoice.metadata_customer_name = Some(template.customer_name.clone());
+        InvoiceStorage::store_invoice(env, &invoice);
+        env.storage().instance().set(
+            &(INVOICE_SCHEDULE_KEY, invoice.id.clone()),
+            &schedule.schedule_id,
+        );
+
+        emit_invoice_uploaded(env, &invoice);
+        audit::log_invoice_uploaded(
+            env,
+            invoice.id.clone(),
+            invoice.business.clone(),
+            invoice.amount,
+        );
+        NotificationHooks::fire(env, &invoice, NotificationHook::InvoiceCreated);
+        invoice
+    }
+}
@@ -0,0 +1,174 @@
+//! Referral attribution: brokers who introduce invoices earn a share of the platform fee.
+//!
+//! The admin registers each broker with its share of the platform fee, in basis points, and an
+//! optional cap per invoice. A business may tag its invoice with a registered broker until the
+//! invoice is funded. At settlement, the broker's share of the platform fee left after the keeper
+//! reward share is paid to it directly, and its earnings are tallied per currency. Deactivated
+//! brokers keep their earnings but are paid nothing further.
+
+use crate::errors::QuickLendXError;
+use crate::events::emit_broker_fee_paid;
+use crate::invoice::Invoice;
+use crate::payments::transfer_funds;
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Map, Symbol};
+
+const BROKER_KEY: Symbol = symbol_short!("brk");
+const INVOICE_BROKER_KEY: Symbol = symbol_short!("brk_inv");
+const BROKER_EARNINGS_KEY: Symbol = symbol_short!("brk_ern");
+/// Most of the platform fee a broker may be granted (50%)
+pub const MAX_BROKER_FEE_BPS: u32 = 5_000;
+
+/// A registered introducer and its terms
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Broker {
+    pub broker: Address,
+    /// Share of the platform fee paid to the broker, in basis points
+    pub fee_bps: u32,
+    /// Most the broker earns on a single invoice; `None` for no cap
+    pub max_fee_per_invoice: Option<i128>,
+    pub active: bool,
+    pub registered_at: u64,
+    pub invoices_referred: u32,
+    pub invoices_paid: u32,
+}
+
+pub struct Referrals;
+
+impl Referrals {
+    pub fn get_broker(env: &Env, broker: &Address) -> Option<Broker> {
+        env.storage().instance().get(&(BROKER_KEY, broker.clone()))
+    }
+
+    fn set_broker(env: &Env, broker: &Broker) {
+        env.storage()
+            .instance()
+            .set(&(BROKER_KEY, broker.broker.clone()), broker);
+    }
+
+    /// Register a broker or update its terms; reactivates a deactivated broker
+    ///
+    /// # Errors
+    /// * `InvalidFeeBasisPoints` if `fee_bps` exceeds `MAX_BROKER_FEE_BPS`
+    /// * `InvalidAmount` if `max_fee_per_invoice` is negative
+    pub fn register_broker(
+        env: &Env,
+        broker: &Address,
+        fee_bps: u32,
+        max_fee_per_invoice: Option<i128>,
+    ) -> Result<Broker, QuickLendXError> {
+        if fee_bps > MAX_BROKER_FEE_BPS {
+            return Err(QuickLendXError::InvalidFeeBasisPoints);
+        }
+        if max_fee_per_invoice.is_some_and(|cap| cap < 0) {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let record = match Self::get_broker(env, broker) {
+            Some(existing) => Broker {
+                fee_bps,
+                max_fee_per_invoice,
+                active: true,
+                ..existing
+            },
+            None => Broker {
+                broker: broker.clone(),
+                fee_bps,
+                max_fee_per_invoice,
+                active: true,
+                registered_at: env.ledger().timestamp(),
+                invoices_referred: 0,
+                invoices_paid: 0,
+            },
+        };
+        Self::set_broker(env, &record);
+        Ok(record)
+    }
+
+    /// # Errors
+    /// * `StorageKeyNotFound` if the broker is not registered
+    pub fn deactivate_broker(env: &Env, broker: &Address) -> Result<(), QuickLendXError> {
+        let mut record =
+            Self::get_broker(env, broker).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        record.active = false;
+        Self::set_broker(env, &record);
+        Ok(())
+    }
+
+    pub fn get_invoice_broker(env: &Env, invoice_id: &BytesN<32>) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&(INVOICE_BROKER_KEY, invoice_id.clone()))
+    }
+
+    /// Attribute an invoice to a broker; the caller has checked the business's authorization
+    /// and that the invoice is not yet funded
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the broker is not registered
+    /// * `OperationNotAllowed` if the broker is deactivated or the invoice is already tagged
+    pub fn tag_invoice(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        broker: &Address,
+    ) -> Result<(), QuickLendXError> {
+        let mut record =
+            Self::get_broker(env, broker).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if !record.active || Self::get_invoice_broker(env, invoice_id).is_some() {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        env.storage()
+            .instance()
+            .set(&(INVOICE_BROKER_KEY, invoice_id.clone()), broker);
+        record.invoices_referred += 1;
+        Self::set_broker(env, &record);
+        Ok(())
+    }
+
+    /// Earnings paid to a broker so far, by currency
+    pub fn get_earnings(env: &Env, broker: &Address) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get(&(BROKER_EARNINGS_KEY, broker.clone()))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    /// Pay the invoice's broker its share of `platform_fee` from `payer`
+    ///
+    /// # Returns
+    /// * The amount paid (0 when the invoice has no active broker); the caller routes the rest
+    ///   of the fee as usual
+    pub fn pay_broker_fee(
+        env: &Env,
+        invoice: &Invoice,
+        payer: &Address,
+        platform_fee: i128,
+    ) -> Result<i128, QuickLendXError> {
+        let Some(broker) = Self::get_invoice_broker(env, &invoice.id) else {
+            return Ok(0);
+        };
+        let Some(mut record) = Self::get_broker(env, &broker).filter(|record| record.active) else {
+            return Ok(0);
+        };
+        let mut fee = rounding::bps_of(platform_fee, record.fee_bps as i128, Rounding::Floor);
+        if let Some(cap) = record.max_fee_per_invoice {
+            fee = fee.min(cap);
+        }
+        if fee <= 0 {
+            return Ok(0);
+        }
+        transfer_funds(env, &invoice.currency, payer, &broker, fee)?;
+
+        let mut earnings = Self::get_earnings(env, &broker);
+        let earned = earnings.get(invoice.currency.clone()).unwrap_or(0);
+        earnings.set(invoice.currency.clone(), earned.saturating_add(fee));
+        env.storage()
+            .instance()
+            .set(&(BROKER_EARNINGS_KEY, broker.clone()), &earnings);
+        record.invoices_paid += 1;
+        Self::set_broker(env, &record);
+
+        emit_broker_fee_paid(env, &invoice.id, &broker, &invoice.currency, fee);
+        Ok(fee)
+    }
+}
@@ -0,0 +1,181 @@
+//! Required insurance for low-credit businesses.
+//!
+//! The admin can require that invoices from businesses whose credit score (see
+//! `risk::business_credit_score`) is below a threshold are only funded with insurance attached.
+//! The requirement is stamped on the invoice at verification as `required_insurance_pct`, so
+//! investors can see it before bidding; changing the policy affects invoices verified afterwards.
+//!
+//! An investor attaches coverage to a bid with `set_bid_insurance`. When the bid is accepted the
+//! coverage is bought in the same call, with the premium drawn from the investor's allowance like
+//! the escrowed funds. Accepting a bid without enough coverage for a required invoice fails.
+
+use crate::bid::Bid;
+use crate::errors::QuickLendXError;
+use crate::insurance::InsurancePurchases;
+use crate::investment::{Investment, InvestmentStatus};
+use crate::invoice::Invoice;
+use crate::risk::business_credit_score;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const REQUIREMENT_KEY: Symbol = symbol_short!("ins_req");
+const BID_INSURANCE_KEY: Symbol = symbol_short!("bid_ins");
+
+/// Businesses scoring below `min_credit_score` need `min_coverage_percentage` of cover
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsuranceRequirement {
+    /// Credit score in basis points, 1-10,000
+    pub min_credit_score: u32,
+    /// Coverage percentage, 1-100
+    pub min_coverage_percentage: u32,
+}
+
+/// Coverage an investor will buy if their bid is accepted
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidInsurance {
+    pub provider: Address,
+    pub coverage_percentage: u32,
+}
+
+pub struct InsuranceRequirements;
+
+impl InsuranceRequirements {
+    pub fn get_policy(env: &Env) -> Option<InsuranceRequirement> {
+        env.storage().instance().get(&REQUIREMENT_KEY)
+    }
+
+    /// # Errors
+    /// * `InvalidAmount` if the credit score threshold is not 1-10,000
+    /// * `InvalidCoveragePercentage` if the coverage is not 1-100
+    pub fn set_policy(
+        env: &Env,
+        policy: Option<InsuranceRequirement>,
+    ) -> Result<(), QuickLendXError> {
+        match policy {
+            Some(policy) => {
+                if policy.min_credit_score == 0 || policy.min_credit_score > 10_000 {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                if policy.min_coverage_percentage == 0 || policy.min_coverage_percentage > 100 {
+                    return Err(QuickLendXError::InvalidCoveragePercentage);
+                }
+                env.storage().instance().set(&REQUIREMENT_KEY, &policy);
+            }
+            None => env.storage().instance().remove(&REQUIREMENT_KEY),
+        }
+        Ok(())
+    }
+
+    /// Coverage percentage the current policy requires for `business`'s invoices, if any
+    pub fn coverage_for(env: &Env, business: &Address) -> Option<u32> {
+        let policy = Self::get_policy(env)?;
+        (business_credit_score(env, business) < policy.min_credit_score)
+            .then_some(policy.min_coverage_percentage)
+    }
+
+    pub fn get_bid_insurance(env: &Env, bid_id: &BytesN<32>) -> Option<BidInsurance> {
+        env.storage()
+            .instance()
+            .get(&(BID_INSURANCE_KEY, bid_id.clone()))
+    }
+
+    /// Attach coverage to a bid, or clear it with `None`; the caller has checked the bid is
+    /// placed and the investor's authorization
+    ///
+    /// # Errors
+    /// * `InvalidCoveragePercentage` if the coverage is not 1-100
+    pub fn set_bid_insurance(
+        env: &Env,
+        bid_id: &BytesN<32>,
+        election: Option<BidInsurance>,
+    ) -> Result<(), QuickLendXError> {
+        let key = (BID_INSURANCE_KEY, bid_id.clone());
+        match election {
+            Some(election) => {
+                if election.coverage_percentage == 0 || election.coverage_percentage > 100 {
+                    return Err(QuickLendXError::InvalidCoveragePercentage);
+                }
+                env.storage().instance().set(&key, &election);
+            }
+            None => env.storage().instance().remove(&key),
+        }
+        Ok(())
+    }
+
+    /// Check that a bid carries the coverage the invoice requires
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if the invoice requires more coverage than the bid has attached
+    pub fn ensure_met(
+        env: &Env,
+        invoice: &Invoice,
+        bid_id: &BytesN<32>,
+    ) -> Result<(), QuickLendXError> {
+        let Some(required) = invoice.required_insurance_pct else {
+            return Ok(());
+        };
+        match Self::get_bid_insurance(env, bid_id) {
+            Some(election) if election.coverage_percentage >= required => Ok(()),
+            _ => Err(QuickLendXError::OperationNotAllowed),
+        }
+    }
+
+    /// Premium `buy_at_funding` will charge if `bid` funds `invoice` now, or 0 if the bid has no
+    /// coverage attached, checking the purchase without making it
+    ///
+    /// # Errors
+    /// * Any error from `InsurancePurchases::check`
+    pub fn premium_at_funding(
+        env: &Env,
+        invoice: &Invoice,
+        bid: &Bid,
+    ) -> Result<i128, QuickLendXError> {
+        let Some(election) = Self::get_bid_insurance(env, &bid.bid_id) else {
+            return Ok(0);
+        };
+        // The investment the bid would create; its id is not used by the checks
+        let investment = Investment {
+            investment_id: bid.bid_id.clone(),
+            invoice_id: invoice.id.clone(),
+            investor: bid.investor.clone(),
+            amount: bid.bid_amount,
+            funded_at: env.ledger().timestamp(),
+            status: InvestmentStatus::Active,
+            insurance: Vec::new(env),
+        };
+        InsurancePurchases::check(
+            env,
+            &investment,
+            invoice,
+            &election.provider,
+            election.coverage_percentage,
+        )
+    }
+
+    /// Buy the coverage attached to an accepted bid for the new investment, if any
+    ///
+    /// # Errors
+    /// * Any error from `InsurancePurchases::buy`
+    pub fn buy_at_funding(
+        env: &Env,
+        invoice: &Invoice,
+        bid_id: &BytesN<32>,
+        investment: &mut Investment,
+    ) -> Result<(), QuickLendXError> {
+        let Some(election) = Self::get_bid_insurance(env, bid_id) else {
+            return Ok(());
+        };
+        env.storage()
+            .instance()
+            .remove(&(BID_INSURANCE_KEY, bid_id.clone()));
+        InsurancePurchases::buy(
+            env,
+            investment,
+            invoice,
+            &election.provider,
+            election.coverage_percentage,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+//! Default probability estimate: a simple, deterministic score stored on each invoice at
+//! verification so investors and the UI can show a standardized risk figure.
+//!
+//! The estimate starts from the category's historical loss rate, blends in the business's own
+//! repayment record, and scales it up for invoices larger than anything the business has repaid
+//! and for longer terms, and lowers it when the debtor has acknowledged the invoice. All rates are
+//! in basis points.
+//!
+//! `portfolio_risk` rolls an investor's active investments up into dashboard metrics: remaining
+//! duration, concentration by business and category, insured share, and the loss expected from
+//! each category's historical default rate on the uninsured part.
+
+use crate::debtor::DebtorRegistry;
+use crate::investment::{InvestmentStatus, InvestmentStorage};
+use crate::invoice::{Invoice, InvoiceCategory, InvoiceStatus, InvoiceStorage};
+use crate::rounding::{self, Rounding};
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
+
+/// Loss rate assumed before any invoices have been resolved (5%)
+const PRIOR_DEFAULT_BPS: i128 = 500;
+/// Weight of the prior, in resolved invoices; history dominates once it exceeds this
+const PRIOR_WEIGHT: i128 = 10;
+/// Surcharge when the amount exceeds the business's largest repaid invoice (+25%)
+const LARGE_AMOUNT_SURCHARGE_BPS: i128 = 2_500;
+/// Discount when the debtor has acknowledged the invoice (-25%)
+const ACKNOWLEDGED_DISCOUNT_BPS: i128 = 2_500;
+/// Terms up to this long scale the estimate linearly, up to double
+const MAX_TERM_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+/// Defaulted and paid counts, with `None` matching any category or business
+fn resolved_counts(
+    env: &Env,
+    category: Option<&InvoiceCategory>,
+    business: Option<&Address>,
+) -> (i128, i128, i128) {
+    let mut defaulted = 0i128;
+    let mut paid = 0i128;
+    let mut largest_repaid = 0i128;
+    for status in [InvoiceStatus::Defaulted, InvoiceStatus::Paid] {
+        for invoice_id in InvoiceStorage::get_invoices_by_status(env, &status).iter() {
+            let invoice = match InvoiceStorage::get_invoice(env, &invoice_id) {
+                Some(invoice) => invoice,
+                None => continue,
+            };
+            if category.is_some_and(|category| invoice.category != *category)
+                || business.is_some_and(|business| invoice.business != *business)
+            {
+                continue;
+            }
+            if status == InvoiceStatus::Defaulted {
+                defaulted += 1;
+            } else {
+                paid += 1;
+                largest_repaid = largest_repaid.max(invoice.amount);
+            }
+        }
+    }
+    (defaulted, paid, largest_repaid)
+}
+
+/// Smoothed default rate: observed defaults blended with `prior_bps` weighted as
+/// `PRIOR_WEIGHT` resolved invoices
+fn smoothed_rate_bps(defaulted: i128, resolved: i128, prior_bps: i128) -> i128 {
+    (defaulted * 10_000 + prior_bps * PRIOR_WEIGHT) / (resolved + PRIOR_WEIGHT)
+}
+
+/// Historical default rate of a category, in basis points
+pub fn category_loss_rate_bps(env: &Env, category: &InvoiceCategory) -> u32 {
+    let (defaulted, paid, _) = resolved_counts(env, Some(category), None);
+    smoothed_rate_bps(defaulted, defaulted + paid, PRIOR_DEFAULT_BPS) as u32
+}
+
+/// Credit score of a business, in basis points: 10,000 less its smoothed default rate, so a
+/// business with no resolved invoices scores 9,500
+pub fn business_credit_score(env: &Env, business: &Address) -> u32 {
+    let (defaulted, paid, _) = resolved_counts(env, None, Some(business));
+    (10_000 - smoothed_rate_bps(defaulted, defaulted + paid, PRIOR_DEFAULT_BPS)) as u32
+}
+
+/// Estimate the probability that an invoice defaults, in basis points (1..=10_000)
+pub fn estimate_default_probability(env: &Env, invoice: &Invoice) -> u32 {
+    let category_rate = category_loss_rate_bps(env, &invoice.category) as i128;
+    let (defaulted, paid, largest_repaid) = resolved_counts(env, None, Some(&invoice.business));
+    let mut estimate = smoothed_rate_bps(defaulted, defaulted + paid, category_rate);
+
+    if invoice.amount > largest_repaid {
+        estimate = estimate * (10_000 + LARGE_AMOUNT_SURCHARGE_BPS) / 10_000;
+    }
+
+    let term = invoice
+        .due_date
+        .saturating_sub(env.ledger().timestamp())
+        .min(MAX_TERM_SECONDS) as i128;
+    estimate = estimate * (MAX_TERM_SECONDS as i128 + term) / MAX_TERM_SECONDS as i128;
+
+    if DebtorRegistry::is_acknowledged(env, &invoice.id) {
+        estimate = estimate * (10_000 - ACKNOWLEDGED_DISCOUNT_BPS) / 10_000;
+    }
+
+    estimate.clamp(1, 10_000) as u32
+}
+
+/// Amount an investor has active with one business
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BusinessConcentration {
+    pub business: Address,
+    pub amount: i128,
+    /// Share of the investor's active amount, in basis points
+    pub share_bps: i128,
+}
+
+/// Amount an investor has active in one invoice category
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryConcentration {
+    pub category: InvoiceCategory,
+    pub amount: i128,
+    /// Share of the investor's active amount, in basis points
+    pub share_bps: i128,
+}
+
+/// Risk metrics over an investor's active investments
+///
+/// Shares and rates are in basis points, rounded half to even; all are 0 for an empty portfolio.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PortfolioRisk {
+    pub investor: Address,
+    pub active_investments: u32,
+    pub total_invested: i128,
+    /// Seconds until the invoices fall due, averaged weighted by amount; 0 once all are overdue
+    pub weighted_avg_duration: u64,
+    /// Per business, in order of first investment
+    pub business_concentration: Vec<BusinessConcentration>,
+    /// Per category, in order of first investment
+    pub category_concentration: Vec<CategoryConcentration>,
+    /// Share held with the single largest business
+    pub largest_business_share_bps: i128,
+    /// Amount covered by active insurance, capped at each investment's amount
+    pub insured_amount: i128,
+    pub insured_share_bps: i128,
+    /// Uninsured amount times its category's historical default rate
+    pub expected_loss: i128,
+    pub expected_loss_bps: i128,
+}
+
+/// Compute `investor`'s portfolio risk metrics
+///
+/// Cost grows with the investor's investments, plus one scan of the paid and defaulted invoices
+/// per distinct category held.
+pub fn portfolio_risk(env: &Env, investor: &Address) -> PortfolioRisk {
+    let now = env.ledger().timestamp();
+    let share = |amount: i128, total: i128| {
+        rounding::mul_div(amount, rounding::BPS, total, Rounding::HalfEven)
+    };
+
+    let mut active_investments = 0u32;
+    let mut total_invested = 0i128;
+    let mut duration_weight = 0i128;
+    let mut insured_amount = 0i128;
+    let mut expected_loss = 0i128;
+    let mut businesses: Vec<BusinessConcentration> = Vec::new(env);
+    let mut categories: Vec<CategoryConcentration> = Vec::new(env);
+    let mut loss_rates: Map<InvoiceCategory, i128> = Map::new(env);
+
+    for investment_id in InvestmentStorage::get_investments_by_investor(env, investor).iter() {
+        let Some(investment) = InvestmentStorage::get_investment(env, &investment_id) else {
+            continue;
+        };
+        if investment.status != InvestmentStatus::Active {
+            continue;
+        }
+        let Some(invoice) = InvoiceStorage::get_invoice(env, &investment.invoice_id) else {
+            continue;
+        };
+        let amount = investment.amount;
+        active_investments += 1;
+        total_invested = total_invested.saturating_add(amount);
+        duration_weight = duration_weight
+            .saturating_add(amount.saturating_mul(invoice.due_date.saturating_sub(now) as i128));
+
+        match businesses
+            .iter()
+            .position(|entry| entry.business == invoice.business)
+        {
+            Some(idx) => {
+                let mut entry = businesses.get_unchecked(idx as u32);
+                entry.amount = entry.amount.saturating_add(amount);
+                businesses.set(idx as u32, entry);
+            }
+            None => businesses.push_back(BusinessConcentration {
+                business: invoice.business.clone(),
+                amount,
+                share_bps: 0,
+            }),
+        }
+        match categories
+            .iter()
+            .position(|entry| entry.category == invoice.category)
+        {
+            Some(idx) => {
+                let mut entry = categories.get_unchecked(idx as u32);
+                entry.amount = entry.amount.saturating_add(amount);
+                categories.set(idx as u32, entry);
+            }
+            None => categories.push_back(CategoryConcentration {
+                category: invoice.category.clone(),
+                amount,
+                share_bps: 0,
+            }),
+        }
+
+        let mut covered = 0i128;
+        for coverage in investment.insurance.iter() {
+            if coverage.active {
+                covered = covered.saturating_add(coverage.coverage_amount);
+            }
+        }
+        let covered = covered.min(amount);
+        insured_amount = insured_amount.saturating_add(covered);
+
+        let loss_rate = match loss_rates.get(invoice.category.clone()) {
+            Some(rate) => rate,
+            None => {
+                let rate = category_loss_rate_bps(env, &invoice.category) as i128;
+                loss_rates.set(invoice.category.clone(), rate);
+                rate
+            }
+        };
+        expected_loss = expected_loss.saturating_add(rounding::bps_of(
+            amount - covered,
+            loss_rate,
+            Rounding::HalfEven,
+        ));
+    }
+
+    let mut largest_business_share_bps = 0i128;
+    for idx in 0..businesses.len() {
+        let mut entry = businesses.get_unchecked(idx);
+        entry.share_bps = share(entry.amount, total_invested);
+        largest_business_share_bps = largest_business_share_bps.max(entry.share_bps);
+        businesses.set(idx, entry);
+    }
+    for idx in 0..categories.len() {
+        let mut entry = categories.get_unchecked(idx);
+        entry.share_bps = share(entry.amount, total_invested);
+        categories.set(idx, entry);
+    }
+
+    PortfolioRisk {
+        investor: investor.clone(),
+        active_investments,
+        total_invested,
+        weighted_avg_duration: rounding::mul_div(
+            duration_weight,
+            1,
+            total_invested,
+            Rounding::HalfEven,
+        ) as u64,
+        business_concentration: businesses,
+        category_concentration: categories,
+        largest_business_share_bps,
+        insured_amount,
+        insured_share_bps: share(insured_amount, total_invested),
+        expected_loss,
+        expected_loss_bps: share(expected_loss, total_invested),
+    }
+}
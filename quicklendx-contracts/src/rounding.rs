@@ -0,0 +1,86 @@
+//! Rounding policy for financial math.
+//!
+//! Every proportional amount in the protocol (fees, premiums, pro-rata releases, profit splits)
+//! is computed through `mul_div`, with the rounding mode chosen explicitly at the call site:
+//!
+//! - `Floor` for any amount taken out of a payment (fees, shares, discounts). The party
+//!   receiving the remainder gets the dust, and `split` returns both parts so they always sum
+//!   back to the original amount.
+//! - `Ceil` for minimums a user must post (bid bonds, bid increments), so a small amount can
+//!   never round its requirement down to zero.
+//! - `HalfEven` for reported rates that do not move funds, so ties do not bias the result in
+//!   either direction.
+//!
+//! Products saturate instead of overflowing, and a non-positive denominator yields 0.
+
+/// Basis points in 100%
+pub const BPS: i128 = 10_000;
+
+/// How `mul_div` rounds an inexact quotient
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Toward negative infinity
+    Floor,
+    /// Toward positive infinity
+    Ceil,
+    /// To the nearest integer, ties to the even one (banker's rounding)
+    HalfEven,
+}
+
+/// `amount * numerator / denominator`, rounded as requested
+pub fn mul_div(amount: i128, numerator: i128, denominator: i128, rounding: Rounding) -> i128 {
+    if denominator <= 0 {
+        return 0;
+    }
+    let product = amount.saturating_mul(numerator);
+    let quotient = product.div_euclid(denominator);
+    let remainder = product.rem_euclid(denominator);
+    if remainder == 0 {
+        return quotient;
+    }
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => true,
+        Rounding::HalfEven => {
+            let above = denominator - remainder;
+            remainder > above || (remainder == above && quotient % 2 != 0)
+        }
+    };
+    if round_up {
+        quotient.saturating_add(1)
+    } else {
+        quotient
+    }
+}
+
+/// `amount * bps / 10_000`, rounded as requested
+pub fn bps_of(amount: i128, bps: i128, rounding: Rounding) -> i128 {
+    mul_div(amount, bps, BPS, rounding)
+}
+
+/// Split `amount` into `(share, remainder)` where `share` is the floored `numerator /
+/// denominator` fraction; `share + remainder == amount` for any fraction up to 1
+pub fn split(amount: i128, numerator: i128, denominator: i128) -> (i128, i128) {
+    let share = mul_div(amount, numerator, denominator, Rounding::Floor);
+    (share, amount.saturating_sub(share))
+}
+
+/// `split` with the fraction given in basis points
+pub fn split_bps(amount: i128, bps: i128) -> (i128, i128) {
+    split(amount, bps, BPS)
+}
+
+/// Carve floored basis-point shares out of `amount`; the residual party receives what is left
+///
+/// # Returns
+/// * `(shares, residual)`, where the shares and the residual sum to `amount` as long as the
+///   shares total at most 10_000 bps
+pub fn allocate_bps<const N: usize>(amount: i128, shares_bps: [i128; N]) -> ([i128; N], i128) {
+    let mut shares = [0i128; N];
+    let mut residual = amount;
+    for (share, bps) in shares.iter_mut().zip(shares_bps) {
+        *share = bps_of(amount, bps, Rounding::Floor);
+        residual = residual.saturating_sub(*share);
+    }
+    (shares, residual)
+}
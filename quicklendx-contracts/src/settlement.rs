@@ -1,21 +1,584 @@
 //! Invoice settlement: partial payments and full settlement (transfer out to investor + fees).
 //! `settle_invoice` is called from lib with a reentrancy guard.
+//!
+//! When the admin sets a `SettlementTimelockPolicy`, payments at or above its threshold go
+//! through a two-step flow instead: the business initiates the settlement, and it can only be
+//! confirmed once the delay has elapsed. The business can cancel in between, so a mistyped
+//! amount never moves funds.
+//!
+//! A settlement paying more than the amount due only runs the waterfall on the amount due. The
+//! surplus is collected into the contract and held as a refund the payer claims with
+//! `claim_overpayment_refund`.
+//!
+//! Every settlement records a `SettlementReceipt` for the business with the net amounts, so
+//! accounting exports can page through a business's recent settlements instead of scraping
+//! events.
 
 use crate::audit::{log_payment_processed, log_settlement_completed};
+use crate::debtor::DebtorRegistry;
 use crate::errors::QuickLendXError;
-use crate::events::{emit_invoice_settled, emit_partial_payment};
+use crate::events::{
+    emit_investor_claim_paid, emit_investor_payout_held, emit_invoice_settled,
+    emit_late_penalty_applied, emit_overpayment_recorded, emit_overpayment_refunded,
+    emit_partial_payment, emit_platform_fee_waived, emit_settlement_cancelled,
+    emit_settlement_initiated, emit_settlement_receipt,
+};
 use crate::investment::{InvestmentStatus, InvestmentStorage};
-use crate::invoice::{InvoiceStatus, InvoiceStorage};
-use crate::notifications::NotificationSystem;
-use crate::payments::transfer_funds;
-use soroban_sdk::{BytesN, Env, String};
+use crate::invoice::{Invoice, InvoiceStatus, InvoiceStorage};
+use crate::invoice_freeze::InvoiceFreezes;
+use crate::keeper::KeeperStorage;
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::payments::{ensure_can_pay, transfer_funds, try_transfer_funds};
+use crate::state_machine::TransitionRole;
+use crate::tax_lots::{LotDisposal, TaxLots};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+const TIMELOCK_POLICY_KEY: Symbol = symbol_short!("stl_pol");
+const PENDING_SETTLEMENT_KEY: Symbol = symbol_short!("stl_pend");
+const OVERPAYMENT_KEY: Symbol = symbol_short!("overpay");
+const INVESTOR_CLAIM_KEY: Symbol = symbol_short!("inv_clm");
+const PAYMENT_TX_KEY: Symbol = symbol_short!("pay_tx");
+const SETTLEMENT_RECEIPTS_KEY: Symbol = symbol_short!("stl_rcpt");
+/// Longest delay the admin can impose on large settlements (7 days)
+pub const MAX_SETTLEMENT_DELAY: u64 = 7 * 24 * 60 * 60;
+/// Upper bound on entries per `settle_invoices` call
+pub const MAX_BATCH_SETTLEMENT: u32 = 20;
+/// Upper bound on invoices settled per `auto_settle_due` call
+pub const MAX_AUTO_SETTLEMENTS: u32 = 10;
+/// Receipts kept per business; the oldest is dropped beyond this
+pub const MAX_SETTLEMENT_RECEIPTS: u32 = 50;
+
+/// Payments at or above `threshold_amount` must be initiated and confirmed after
+/// `delay_seconds`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementTimelockPolicy {
+    pub threshold_amount: i128,
+    pub delay_seconds: u64,
+}
+
+/// A large settlement waiting out the timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingSettlement {
+    pub invoice_id: BytesN<32>,
+    pub business: Address,
+    pub payment_amount: i128,
+    pub initiated_at: u64,
+    /// Earliest time the settlement can be confirmed
+    pub executable_at: u64,
+}
+
+/// Surplus paid at settlement above the amount due, held for the payer to claim
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OverpaymentRefund {
+    pub invoice_id: BytesN<32>,
+    pub payer: Address,
+    pub currency: Address,
+    pub amount: i128,
+    pub recorded_at: u64,
+    pub claimed: bool,
+}
+
+/// A business's record of one settled invoice, with the net amounts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementReceipt {
+    pub invoice_id: BytesN<32>,
+    pub business: Address,
+    /// Who paid: the business, the invoice's debtor, or a keeper-collected debtor allowance
+    pub payer: Address,
+    pub currency: Address,
+    /// Amount the settlement ran on, excluding any overpayment
+    pub amount_settled: i128,
+    /// Amount the investor advanced when funding the invoice
+    pub amount_advanced: i128,
+    pub investor_return: i128,
+    pub platform_fee: i128,
+    /// Late penalty included in `amount_settled`
+    pub late_penalty: i128,
+    /// Surplus above the amount due, refundable to the payer
+    pub overpayment: i128,
+    /// What the financing cost the business: `amount_settled - amount_advanced`
+    pub financing_cost: i128,
+    pub settled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceSettlementOutcome {
+    Settled,
+    NotFound,
+    /// Skipped: the invoice is not Funded
+    WrongStatus,
+    /// Skipped: the amount is not positive, or does not cover the invoice
+    InvalidPayment,
+    /// Skipped: the amount is at or above the timelock threshold
+    RequiresTimelock,
+    /// Skipped: the business's balance or allowance does not cover the amount
+    InsufficientFunds,
+    /// Skipped: the invoice appears more than once in the batch
+    Duplicate,
+    Failed,
+}
+
+impl InvoiceSettlementOutcome {
+    pub fn from_error(error: QuickLendXError) -> Self {
+        match error {
+            QuickLendXError::InvoiceNotFound => Self::NotFound,
+            QuickLendXError::InvalidStatus => Self::WrongStatus,
+            QuickLendXError::InvalidAmount | QuickLendXError::PaymentTooLow => {
+                Self::InvalidPayment
+            }
+            QuickLendXError::OperationNotAllowed => Self::RequiresTimelock,
+            QuickLendXError::InsufficientFunds => Self::InsufficientFunds,
+            _ => Self::Failed,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceSettlementResult {
+    pub invoice_id: BytesN<32>,
+    pub payment_amount: i128,
+    pub outcome: InvoiceSettlementOutcome,
+}
+
+/// Per-invoice results and aggregate counts of a batch settlement
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchSettlementResult {
+    pub results: Vec<InvoiceSettlementResult>,
+    pub settled: u32,
+    pub skipped: u32,
+    /// Sum of the payments of settled entries
+    pub total_settled: i128,
+}
+
+/// An invoice settled by a keeper from its debtor's pre-approved funds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSettlement {
+    pub invoice_id: BytesN<32>,
+    pub debtor: Address,
+    /// Payoff amount collected from the debtor
+    pub amount: i128,
+    pub keeper_reward: i128,
+}
+
+/// Invoices settled by one `auto_settle_due` call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoSettlementResult {
+    pub settlements: Vec<AutoSettlement>,
+    /// Funded invoices examined, settled or not
+    pub scanned: u32,
+    pub total_reward: i128,
+}
+
+/// Amount a business must pass to `settle_invoice` to pay an invoice off, with its breakdown
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoffQuote {
+    pub invoice_id: BytesN<32>,
+    pub as_of: u64,
+    /// Amount the investor funded
+    pub principal: i128,
+    /// Investor return on top of the principal (face value minus principal)
+    pub accrued_return: i128,
+    /// Flat late penalty plus daily accrual on the fee terms quoted at funding; zero until
+    /// the due date passes
+    pub late_fee: i128,
+    /// Payments already recorded against the invoice
+    pub total_paid: i128,
+    /// Total to settle: principal + accrued return + late fee
+    pub payoff_amount: i128,
+}
+
+/// Quote the amount that pays off a funded invoice at `as_of`
+///
+/// Recorded partial payments are bookkeeping only, so the payoff amount is the full total the
+/// settlement will collect from the business; `total_paid` is reported for reference.
+///
+/// # Errors
+/// * `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded, `NotInvestor`
+pub fn get_payoff_quote(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    as_of: u64,
+) -> Result<PayoffQuote, QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    quote_payoff(env, &invoice, as_of)
+}
+
+/// `get_payoff_quote` for an invoice the caller already loaded
+fn quote_payoff(env: &Env, invoice: &Invoice, as_of: u64) -> Result<PayoffQuote, QuickLendXError> {
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let invoice_id = &invoice.id;
+    let investor = invoice
+        .investor
+        .clone()
+        .ok_or(QuickLendXError::NotInvestor)?;
+    let principal = invoice.funded_amount;
+
+    let late_fee = if invoice.is_overdue(as_of) {
+        let quote = crate::fees::FeeManager::get_fee_quote(env, invoice_id).unwrap_or_else(|| {
+            crate::fees::FeeManager::current_fee_quote(env, &invoice.business, &investor)
+        });
+        // Payment is unbounded here, so the full penalty is collectible
+        crate::fees::FeeManager::calculate_late_penalty(
+            &quote,
+            invoice.amount,
+            i128::MAX,
+            crate::fees::FeeManager::days_overdue(invoice, as_of),
+        )
+    } else {
+        0
+    };
+
+    Ok(PayoffQuote {
+        invoice_id: invoice_id.clone(),
+        as_of,
+        principal,
+        accrued_return: invoice.amount.saturating_sub(principal).max(0),
+        late_fee,
+        total_paid: invoice.total_paid,
+        payoff_amount: invoice.amount.saturating_add(late_fee),
+    })
+}
+
+pub struct SettlementTimelock;
+
+impl SettlementTimelock {
+    pub fn get_policy(env: &Env) -> Option<SettlementTimelockPolicy> {
+        env.storage().instance().get(&TIMELOCK_POLICY_KEY)
+    }
+
+    /// Set or clear the policy. Settlements already pending keep their `executable_at`.
+    ///
+    /// # Errors
+    /// * `InvalidAmount` if the threshold is not positive or the delay is outside
+    ///   1..=`MAX_SETTLEMENT_DELAY`
+    pub fn set_policy(
+        env: &Env,
+        policy: Option<SettlementTimelockPolicy>,
+    ) -> Result<(), QuickLendXError> {
+        match policy {
+            Some(policy) => {
+                if policy.threshold_amount <= 0
+                    || policy.delay_seconds == 0
+                    || policy.delay_seconds > MAX_SETTLEMENT_DELAY
+                {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                env.storage().instance().set(&TIMELOCK_POLICY_KEY, &policy);
+            }
+            None => env.storage().instance().remove(&TIMELOCK_POLICY_KEY),
+        }
+        Ok(())
+    }
+
+    /// Whether a payment of `amount` must go through the two-step flow
+    pub fn requires_timelock(env: &Env, amount: i128) -> bool {
+        Self::get_policy(env).is_some_and(|policy| amount >= policy.threshold_amount)
+    }
+
+    pub fn get_pending(env: &Env, invoice_id: &BytesN<32>) -> Option<PendingSettlement> {
+        env.storage()
+            .instance()
+            .get(&(PENDING_SETTLEMENT_KEY, invoice_id.clone()))
+    }
+
+    fn remove_pending(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage()
+            .instance()
+            .remove(&(PENDING_SETTLEMENT_KEY, invoice_id.clone()));
+    }
+
+    /// Start a timelocked settlement on behalf of the invoice's business.
+    ///
+    /// # Errors
+    /// * `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded
+    /// * `OperationNotAllowed` if the amount is below the policy threshold (or no policy is
+    ///   set) or a settlement is already pending
+    pub fn initiate(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+        payment_amount: i128,
+    ) -> Result<PendingSettlement, QuickLendXError> {
+        if payment_amount <= 0 {
+            return Err(QuickLendXError::InvalidAmount);
+        }
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        if invoice.status != InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        let policy = Self::get_policy(env).ok_or(QuickLendXError::OperationNotAllowed)?;
+        if payment_amount < policy.threshold_amount || Self::get_pending(env, invoice_id).is_some()
+        {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let now = env.ledger().timestamp();
+        let pending = PendingSettlement {
+            invoice_id: invoice_id.clone(),
+            business: invoice.business,
+            payment_amount,
+            initiated_at: now,
+            executable_at: now.saturating_add(policy.delay_seconds),
+        };
+        env.storage()
+            .instance()
+            .set(&(PENDING_SETTLEMENT_KEY, invoice_id.clone()), &pending);
+
+        emit_settlement_initiated(env, &pending);
+        Ok(pending)
+    }
+
+    /// Drop the pending settlement of an invoice.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no settlement is pending
+    pub fn cancel(env: &Env, invoice_id: &BytesN<32>) -> Result<(), QuickLendXError> {
+        let pending =
+            Self::get_pending(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        Self::remove_pending(env, invoice_id);
+
+        emit_settlement_cancelled(env, &pending);
+        Ok(())
+    }
+
+    /// Execute the pending settlement once its delay has elapsed. The business authorized the
+    /// payment when initiating, so anyone may confirm it.
+    ///
+    /// # Returns
+    /// * `Ok(pending)` - the settlement that was executed
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no settlement is pending
+    /// * `OperationNotAllowed` if the delay has not elapsed
+    /// * Settlement errors, as for `settle_invoice`
+    pub fn confirm(
+        env: &Env,
+        invoice_id: &BytesN<32>,
+    ) -> Result<PendingSettlement, QuickLendXError> {
+        let pending =
+            Self::get_pending(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+
+        let invoice =
+            InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+        settle_invoice_internal(
+            env,
+            invoice,
+            &pending.business,
+            pending.payment_amount,
+            TransitionRole::Business,
+        )?;
+        Self::remove_pending(env, invoice_id);
+        Ok(pending)
+    }
+}
+
+pub struct SettlementReceipts;
+
+impl SettlementReceipts {
+    /// The business's receipts, oldest first
+    fn get_all(env: &Env, business: &Address) -> Vec<SettlementReceipt> {
+        env.storage()
+            .instance()
+            .get(&(SETTLEMENT_RECEIPTS_KEY, business.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn record(env: &Env, receipt: &SettlementReceipt) {
+        let mut receipts = Self::get_all(env, &receipt.business);
+        if receipts.len() >= MAX_SETTLEMENT_RECEIPTS {
+            receipts.pop_front();
+        }
+        receipts.push_back(receipt.clone());
+        env.storage().instance().set(
+            &(SETTLEMENT_RECEIPTS_KEY, receipt.business.clone()),
+            &receipts,
+        );
+    }
+
+    /// A page of the business's receipts, newest first
+    pub fn get_paged(
+        env: &Env,
+        business: &Address,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<SettlementReceipt> {
+        let receipts = Self::get_all(env, business);
+        let mut page = Vec::new(env);
+        let start = offset.min(receipts.len());
+        let end = start.saturating_add(limit).min(receipts.len());
+        for idx in start..end {
+            page.push_back(receipts.get_unchecked(receipts.len() - 1 - idx));
+        }
+        page
+    }
+}
+
+pub struct Overpayments;
+
+impl Overpayments {
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Option<OverpaymentRefund> {
+        env.storage()
+            .instance()
+            .get(&(OVERPAYMENT_KEY, invoice_id.clone()))
+    }
+
+    fn set(env: &Env, refund: &OverpaymentRefund) {
+        env.storage()
+            .instance()
+            .set(&(OVERPAYMENT_KEY, refund.invoice_id.clone()), refund);
+    }
+
+    /// Pay an unclaimed overpayment back to its payer.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if the invoice has no overpayment
+    /// * `OperationNotAllowed` if it was already claimed
+    /// * Transfer errors
+    pub fn claim(env: &Env, invoice_id: &BytesN<32>) -> Result<OverpaymentRefund, QuickLendXError> {
+        let mut refund = Self::get(env, invoice_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if refund.claimed {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        refund.payer.require_auth();
+        transfer_funds(
+            env,
+            &refund.currency,
+            &env.current_contract_address(),
+            &refund.payer,
+            refund.amount,
+        )?;
+        refund.claimed = true;
+        Self::set(env, &refund);
+
+        emit_overpayment_refunded(env, &refund);
+        Ok(refund)
+    }
+}
+
+/// Investor returns held by the contract because the payout transfer was rejected at settlement,
+/// per investor and currency
+pub struct InvestorClaims;
+
+impl InvestorClaims {
+    pub fn get(env: &Env, investor: &Address, currency: &Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(INVESTOR_CLAIM_KEY, investor.clone(), currency.clone()))
+            .unwrap_or(0)
+    }
+
+    fn set(env: &Env, investor: &Address, currency: &Address, amount: i128) {
+        env.storage().instance().set(
+            &(INVESTOR_CLAIM_KEY, investor.clone(), currency.clone()),
+            &amount,
+        );
+    }
+
+    fn credit(env: &Env, investor: &Address, currency: &Address, amount: i128) {
+        let balance = Self::get(env, investor, currency).saturating_add(amount);
+        Self::set(env, investor, currency, balance);
+    }
+
+    /// Pay the investor's whole claimable balance in `currency` out to them.
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if nothing is claimable
+    /// * Transfer errors; the balance stays claimable if the transfer fails
+    pub fn claim(
+        env: &Env,
+        investor: &Address,
+        currency: &Address,
+    ) -> Result<i128, QuickLendXError> {
+        investor.require_auth();
+        let amount = Self::get(env, investor, currency);
+        if amount <= 0 {
+            return Err(QuickLendXError::StorageKeyNotFound);
+        }
+        Self::set(env, investor, currency, 0);
+        transfer_funds(
+            env,
+            currency,
+            &env.current_contract_address(),
+            investor,
+            amount,
+        )?;
+
+        emit_investor_claim_paid(env, investor, currency, amount);
+        Ok(amount)
+    }
+}
+
+/// Current amount owed on an invoice, including accrued late penalties
+///
+/// For a Funded invoice this is the payoff amount as of now. For a Defaulted invoice it is the
+/// amount that was due when it defaulted, less recoveries collected since.
+///
+/// # Errors
+/// * `InvoiceNotFound`, `InvalidStatus` if the invoice is neither Funded nor Defaulted
+pub fn get_amount_due(env: &Env, invoice_id: &BytesN<32>) -> Result<i128, QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    match invoice.status {
+        InvoiceStatus::Funded => {
+            Ok(get_payoff_quote(env, invoice_id, env.ledger().timestamp())?.payoff_amount)
+        }
+        InvoiceStatus::Defaulted => {
+            let due = crate::defaults::get_amount_due_at_default(env, invoice_id)
+                .unwrap_or(invoice.amount);
+            let recovered = crate::defaults::get_default_recovery(env, invoice_id)
+                .map_or(0, |recovery| recovery.total_recovered);
+            Ok(due.saturating_sub(recovered).max(0))
+        }
+        _ => Err(QuickLendXError::InvalidStatus),
+    }
+}
+
+/// External transaction IDs already applied as partial payments, per invoice
+pub struct PaymentTransactions;
+
+impl PaymentTransactions {
+    pub fn is_applied(env: &Env, invoice_id: &BytesN<32>, transaction_id: &String) -> bool {
+        env.storage().instance().has(&(
+            PAYMENT_TX_KEY,
+            invoice_id.clone(),
+            transaction_id.clone(),
+        ))
+    }
+
+    fn mark_applied(env: &Env, invoice_id: &BytesN<32>, transaction_id: &String) {
+        env.storage().instance().set(
+            &(PAYMENT_TX_KEY, invoice_id.clone(), transaction_id.clone()),
+            &true,
+        );
+    }
+}
 
 /// Record a partial payment; if total paid meets or exceeds amount, settles the invoice.
 ///
-/// Business must be authorized. Invoice must be Funded.
+/// Business must be authorized. Invoice must be Funded. Payments at or above the settlement
+/// timelock threshold must go through `SettlementTimelock::initiate` instead. Each
+/// `transaction_id` can be applied to an invoice once, so replaying an off-chain payment fails.
 ///
 /// # Errors
 /// * `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus`, or settlement errors when fully paid
+/// * `OperationNotAllowed` if the payment requires the timelocked flow
+/// * `DuplicatePayment` if `transaction_id` was already applied to the invoice
 pub fn process_partial_payment(
     env: &Env,
     invoice_id: &BytesN<32>,
@@ -25,19 +588,28 @@ pub fn process_partial_payment(
     if payment_amount <= 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
+    if SettlementTimelock::requires_timelock(env, payment_amount) {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
 
     let mut invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    InvoiceFreezes::ensure_not_frozen(env, invoice_id)?;
 
     if invoice.status != InvoiceStatus::Funded {
         return Err(QuickLendXError::InvalidStatus);
     }
 
+    if PaymentTransactions::is_applied(env, invoice_id, &transaction_id) {
+        return Err(QuickLendXError::DuplicatePayment);
+    }
+
     let business = invoice.business.clone();
     business.require_auth();
 
     let tx_for_event = transaction_id.clone();
-    let progress = invoice.record_payment(env, payment_amount, transaction_id)?;
+    PaymentTransactions::mark_applied(env, invoice_id, &transaction_id);
+    let progress = invoice.record_payment(env, &business, payment_amount, transaction_id)?;
     InvoiceStorage::update_invoice(env, &invoice);
 
     emit_partial_payment(
@@ -58,7 +630,8 @@ pub fn process_partial_payment(
 
     if invoice.is_fully_paid() {
         // Use internal function to avoid duplicate require_auth call
-        settle_invoice_internal(env, invoice_id, invoice.total_paid)?;
+        let total_paid = invoice.total_paid;
+        settle_invoice_internal(env, invoice, &business, total_paid, TransitionRole::Business)?;
     }
 
     Ok(())
@@ -67,9 +640,11 @@ pub fn process_partial_payment(
 /// Settle a funded invoice: pay investor (and platform fee), mark invoice Paid, investment Completed.
 ///
 /// Business must be authorized. Invoice must be Funded; total payment must be at least investment amount.
+/// Payments at or above the settlement timelock threshold must be initiated and confirmed instead.
 ///
 /// # Errors
 /// * `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus`, `PaymentTooLow`, `NotInvestor`, `StorageKeyNotFound`, or fee/transfer errors
+/// * `OperationNotAllowed` if the payment requires the timelocked flow
 pub fn settle_invoice(
     env: &Env,
     invoice_id: &BytesN<32>,
@@ -78,6 +653,9 @@ pub fn settle_invoice(
     if payment_amount <= 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
+    if SettlementTimelock::requires_timelock(env, payment_amount) {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
 
     // Get and validate invoice
     let invoice =
@@ -91,22 +669,200 @@ pub fn settle_invoice(
     invoice.business.require_auth();
 
     // Delegate to internal settlement logic
-    settle_invoice_internal(env, invoice_id, payment_amount)
+    let business = invoice.business.clone();
+    settle_invoice_internal(env, invoice, &business, payment_amount, TransitionRole::Business)
 }
 
-/// Internal settlement logic - no auth required (caller must verify authorization)
-fn settle_invoice_internal(
+/// Settle several funded invoices, each as `settle_invoice` would.
+///
+/// Each invoice is loaded once and its business authorized once, however many of its invoices
+/// are in the batch. Before any funds move for an entry, the business's balance and allowance
+/// must cover the whole payment, so a failing entry is skipped without side effects and the
+/// rest of the batch still settles. The caller holds the reentrancy guard for the whole batch.
+///
+/// # Errors
+/// * `OperationNotAllowed` if more than `MAX_BATCH_SETTLEMENT` entries are given
+pub fn settle_invoices(
+    env: &Env,
+    settlements: &Vec<(BytesN<32>, i128)>,
+) -> Result<BatchSettlementResult, QuickLendXError> {
+    if settlements.len() > MAX_BATCH_SETTLEMENT {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    let mut batch = BatchSettlementResult {
+        results: Vec::new(env),
+        settled: 0,
+        skipped: 0,
+        total_settled: 0,
+    };
+    let mut seen: Vec<BytesN<32>> = Vec::new(env);
+    let mut authorized: Vec<Address> = Vec::new(env);
+    for (invoice_id, payment_amount) in settlements.iter() {
+        let outcome = if seen.contains(&invoice_id) {
+            InvoiceSettlementOutcome::Duplicate
+        } else {
+            seen.push_back(invoice_id.clone());
+            match settle_batch_entry(env, &invoice_id, payment_amount, &mut authorized) {
+                Ok(()) => InvoiceSettlementOutcome::Settled,
+                Err(error) => InvoiceSettlementOutcome::from_error(error),
+            }
+        };
+        if outcome == InvoiceSettlementOutcome::Settled {
+            batch.settled += 1;
+            batch.total_settled = batch.total_settled.saturating_add(payment_amount);
+        } else {
+            batch.skipped += 1;
+        }
+        batch.results.push_back(InvoiceSettlementResult {
+            invoice_id,
+            payment_amount,
+            outcome,
+        });
+    }
+    Ok(batch)
+}
+
+fn settle_batch_entry(
     env: &Env,
     invoice_id: &BytesN<32>,
     payment_amount: i128,
+    authorized: &mut Vec<Address>,
 ) -> Result<(), QuickLendXError> {
     if payment_amount <= 0 {
         return Err(QuickLendXError::InvalidAmount);
     }
+    if SettlementTimelock::requires_timelock(env, payment_amount) {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvalidStatus);
+    }
 
-    // Get and validate invoice
-    let mut invoice =
+    let business = invoice.business.clone();
+    if !authorized.contains(&business) {
+        business.require_auth();
+        authorized.push_back(business.clone());
+    }
+    // Settlement pulls the larger of this payment and the recorded total from the business
+    let payable = payment_amount.max(invoice.total_paid);
+    // A short allowance is reported like a short balance, not as a timelock rejection
+    ensure_can_pay(env, &invoice.currency, &business, payable)
+        .map_err(|_| QuickLendXError::InsufficientFunds)?;
+    settle_invoice_internal(env, invoice, &business, payment_amount, TransitionRole::Business)
+}
+
+/// Settle a funded invoice with a payment from its registered debtor.
+///
+/// The debtor pays the contract directly in the invoice currency and the payment runs through
+/// the same waterfall as `settle_invoice`; any overpayment is refundable to the debtor. The
+/// settlement timelock guards the business's own calls and does not apply here.
+///
+/// # Errors
+/// * `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus` if the invoice is not Funded
+/// * `StorageKeyNotFound` if the invoice has no registered debtor
+/// * Settlement errors, as for `settle_invoice`
+pub fn pay_as_debtor(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payment_amount: i128,
+) -> Result<(), QuickLendXError> {
+    if payment_amount <= 0 {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+    let invoice =
         InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let debtor = DebtorRegistry::get_invoice_debtor(env, invoice_id)
+        .ok_or(QuickLendXError::StorageKeyNotFound)?
+        .debtor;
+    debtor.require_auth();
+
+    settle_invoice_internal(env, invoice, &debtor, payment_amount, TransitionRole::Debtor)
+}
+
+/// Settle due invoices whose registered debtors hold and have approved the payoff amount.
+///
+/// Scans Funded invoices past their due date and settles up to `limit` of them for their payoff
+/// amount, collected from the debtor under the allowance the debtor granted the contract; that
+/// allowance stands in for the debtor's authorization. Invoices without a debtor, or whose debtor
+/// cannot cover the payoff, are skipped. The keeper earns the settlement reward for each invoice
+/// settled, paid from the keeper reward pool. The caller holds the reentrancy guard.
+///
+/// # Errors
+/// * `OperationNotAllowed` if `limit` exceeds `MAX_AUTO_SETTLEMENTS`
+pub fn auto_settle_due(
+    env: &Env,
+    keeper: &Address,
+    limit: u32,
+) -> Result<AutoSettlementResult, QuickLendXError> {
+    if limit > MAX_AUTO_SETTLEMENTS {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
+
+    let now = env.ledger().timestamp();
+    let mut result = AutoSettlementResult {
+        settlements: Vec::new(env),
+        scanned: 0,
+        total_reward: 0,
+    };
+    for invoice_id in InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Funded).iter() {
+        if result.settlements.len() >= limit {
+            break;
+        }
+        result.scanned += 1;
+        let Some(invoice) = InvoiceStorage::get_invoice(env, &invoice_id) else {
+            continue;
+        };
+        if invoice.status != InvoiceStatus::Funded || invoice.due_date > now {
+            continue;
+        }
+        let Some(debtor) = DebtorRegistry::get_invoice_debtor(env, &invoice_id) else {
+            continue;
+        };
+        let debtor = debtor.debtor;
+        let Ok(quote) = quote_payoff(env, &invoice, now) else {
+            continue;
+        };
+        let amount = quote.payoff_amount;
+        let currency = invoice.currency.clone();
+        if ensure_can_pay(env, &currency, &debtor, amount.max(invoice.total_paid)).is_err() {
+            continue;
+        }
+        if settle_invoice_internal(env, invoice, &debtor, amount, TransitionRole::Keeper).is_err() {
+            continue;
+        }
+        let keeper_reward = KeeperStorage::pay_settlement_reward(env, keeper, &currency)?;
+        result.total_reward = result.total_reward.saturating_add(keeper_reward);
+        result.settlements.push_back(AutoSettlement {
+            invoice_id,
+            debtor,
+            amount,
+            keeper_reward,
+        });
+    }
+    Ok(result)
+}
+
+/// Internal settlement logic - no auth required (caller must verify authorization).
+/// `invoice` is the stored invoice as loaded by the caller; funds are collected from `payer`,
+/// acting as `role` in the invoice state machine.
+fn settle_invoice_internal(
+    env: &Env,
+    mut invoice: Invoice,
+    payer: &Address,
+    payment_amount: i128,
+    role: TransitionRole,
+) -> Result<(), QuickLendXError> {
+    if payment_amount <= 0 {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+    let invoice_id = invoice.id.clone();
+    InvoiceFreezes::ensure_not_frozen(env, &invoice_id)?;
 
     if invoice.status != InvoiceStatus::Funded {
         return Err(QuickLendXError::InvalidStatus);
@@ -119,18 +875,28 @@ fn settle_invoice_internal(
         .ok_or(QuickLendXError::NotInvestor)?;
 
     // Get investment details
-    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id)
+    let investment = InvestmentStorage::get_investment_by_invoice(env, &invoice_id)
         .ok_or(QuickLendXError::StorageKeyNotFound)?;
 
     // Ensure the recorded total reflects the latest payment attempt
     let mut total_payment = invoice.total_paid;
     if total_payment == 0 {
-        invoice.record_payment(env, payment_amount, String::from_str(env, "settlement"))?;
+        invoice.record_payment(
+            env,
+            payer,
+            payment_amount,
+            String::from_str(env, "settlement"),
+        )?;
         total_payment = invoice.total_paid;
     } else if payment_amount > total_payment {
         let additional = payment_amount.saturating_sub(total_payment);
         if additional > 0 {
-            invoice.record_payment(env, additional, String::from_str(env, "settlement_adj"))?;
+            invoice.record_payment(
+                env,
+                payer,
+                additional,
+                String::from_str(env, "settlement_adj"),
+            )?;
         }
         total_payment = invoice.total_paid;
     } else {
@@ -142,66 +908,161 @@ fn settle_invoice_internal(
         return Err(QuickLendXError::PaymentTooLow);
     }
 
-    // Calculate platform fee using the enhanced fee system
-    let (investor_return, platform_fee) =
-        crate::fees::FeeManager::calculate_platform_fee(env, investment.amount, total_payment)?;
+    // Settle on the amount due; the surplus is refundable to the payer
+    let overpayment = total_payment
+        .saturating_sub(quote_payoff(env, &invoice, env.ledger().timestamp())?.payoff_amount)
+        .max(0);
+    let total_payment = total_payment - overpayment;
 
-    // Transfer funds to investor
-    let business_address = invoice.business.clone();
-    transfer_funds(
+    // Run the fee waterfall on the terms quoted at funding: late penalty split first,
+    // then platform fee on profit
+    let waterfall = crate::fees::FeeManager::calculate_settlement_waterfall(
         env,
-        &invoice.currency,
-        &business_address,
+        &invoice,
         &investor_address,
-        investor_return,
+        investment.amount,
+        total_payment,
     )?;
+    let investor_return = waterfall.investor_return;
+    let platform_fee = waterfall.platform_fee;
+    if waterfall.platform_fee_waived > 0 {
+        emit_platform_fee_waived(env, &invoice_id, waterfall.platform_fee_waived);
+    }
+    if waterfall.late_penalty > 0 {
+        let record = crate::fees::FeeManager::record_late_penalty(env, &invoice_id, &waterfall);
+        emit_late_penalty_applied(env, &record);
+        NotificationHooks::fire(
+            env,
+            &invoice,
+            NotificationHook::LatePenaltyApplied(waterfall.late_penalty),
+        );
+    }
 
-    // Route platform fee to treasury if configured, otherwise to contract
-    if platform_fee > 0 {
-        let fee_recipient = crate::fees::FeeManager::route_platform_fee(
+    // Transfer funds to investor. If the token rejects the payout (e.g. a frozen trustline),
+    // hold the return in the contract for the investor to claim rather than block the settlement.
+    if !try_transfer_funds(
+        env,
+        &invoice.currency,
+        payer,
+        &investor_address,
+        investor_return,
+    )? {
+        transfer_funds(
             env,
             &invoice.currency,
-            &business_address,
-            platform_fee,
+            payer,
+            &env.current_contract_address(),
+            investor_return,
         )?;
+        InvestorClaims::credit(env, &investor_address, &invoice.currency, investor_return);
+        emit_investor_payout_held(
+            env,
+            &invoice_id,
+            &investor_address,
+            &invoice.currency,
+            investor_return,
+        );
+        NotificationHooks::fire(
+            env,
+            &invoice,
+            NotificationHook::PayoutHeld(investor_address.clone()),
+        );
+    }
+
+    // Divert the keeper reward share, pay the introducing broker its share of the remainder,
+    // then route the rest of the platform fee to treasury if configured, otherwise to contract
+    let keeper_share = crate::keeper::KeeperStorage::fund_from_platform_fee(
+        env,
+        &invoice.currency,
+        payer,
+        platform_fee,
+    )?;
+    let broker_fee = crate::referral::Referrals::pay_broker_fee(
+        env,
+        &invoice,
+        payer,
+        platform_fee - keeper_share,
+    )?;
+    let routed_fee = platform_fee - keeper_share - broker_fee;
+    if routed_fee > 0 {
+        let fee_recipient =
+            crate::fees::FeeManager::route_platform_fee(env, &invoice.currency, payer, routed_fee)?;
 
         // Emit fee routing event
-        crate::events::emit_platform_fee_routed(env, invoice_id, &fee_recipient, platform_fee);
+        crate::events::emit_platform_fee_routed(env, &invoice_id, &fee_recipient, routed_fee);
+    }
+
+    if overpayment > 0 {
+        transfer_funds(
+            env,
+            &invoice.currency,
+            payer,
+            &env.current_contract_address(),
+            overpayment,
+        )?;
+        let refund = OverpaymentRefund {
+            invoice_id: invoice_id.clone(),
+            payer: payer.clone(),
+            currency: invoice.currency.clone(),
+            amount: overpayment,
+            recorded_at: env.ledger().timestamp(),
+            claimed: false,
+        };
+        Overpayments::set(env, &refund);
+        emit_overpayment_recorded(env, &refund);
     }
 
     // Update invoice status
     let previous_status = invoice.status.clone();
-    invoice.mark_as_paid(env, business_address.clone(), env.ledger().timestamp());
+    invoice.mark_as_paid(env, payer.clone(), env.ledger().timestamp(), role)?;
     InvoiceStorage::update_invoice(env, &invoice);
     if previous_status != invoice.status {
-        InvoiceStorage::remove_from_status_invoices(env, &previous_status, invoice_id);
-        InvoiceStorage::add_to_status_invoices(env, &invoice.status, invoice_id);
+        InvoiceStorage::remove_from_status_invoices(env, &previous_status, &invoice_id);
+        InvoiceStorage::add_to_status_invoices(env, &invoice.status, &invoice_id);
     }
 
+    let receipt = SettlementReceipt {
+        invoice_id: invoice_id.clone(),
+        business: invoice.business.clone(),
+        payer: payer.clone(),
+        currency: invoice.currency.clone(),
+        amount_settled: total_payment,
+        amount_advanced: investment.amount,
+        investor_return,
+        platform_fee,
+        late_penalty: waterfall.late_penalty,
+        overpayment,
+        financing_cost: total_payment.saturating_sub(investment.amount),
+        settled_at: env.ledger().timestamp(),
+    };
+
     // Update investment status
     let mut updated_investment = investment;
     updated_investment.status = InvestmentStatus::Completed;
     InvestmentStorage::update_investment(env, &updated_investment);
+    TaxLots::dispose(
+        env,
+        &updated_investment.investment_id,
+        LotDisposal::Settlement,
+        investor_return,
+    );
 
     log_payment_processed(
         env,
         invoice.id.clone(),
-        business_address.clone(),
+        payer.clone(),
         total_payment,
         String::from_str(env, "final"),
     );
-    log_settlement_completed(
-        env,
-        invoice.id.clone(),
-        business_address.clone(),
-        total_payment,
-    );
+    log_settlement_completed(env, invoice.id.clone(), payer.clone(), total_payment);
 
     // Emit settlement event
     emit_invoice_settled(env, &invoice, investor_return, platform_fee);
+    SettlementReceipts::record(env, &receipt);
+    emit_settlement_receipt(env, &receipt);
 
     // Send notification about payment received
-    let _ = NotificationSystem::notify_payment_received(env, &invoice, total_payment);
+    NotificationHooks::fire(env, &invoice, NotificationHook::InvoiceSettled(total_payment));
 
     Ok(())
 }
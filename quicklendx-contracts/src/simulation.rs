@@ -0,0 +1,259 @@
+//! Read-only previews of state-changing flows.
+//!
+//! Each simulation runs the same checks as the real entrypoint and returns the statuses,
+//! token movements and fees the call would produce, without writing to storage or moving
+//! tokens. Integrators can preview a flow in one RPC call instead of replaying the contract
+//! logic off-chain.
+
+use crate::bid::{BidStatus, BidStorage};
+use crate::bid_bond::BidBonds;
+use crate::defaults::DEFAULT_GRACE_PERIOD;
+use crate::errors::QuickLendXError;
+use crate::exposure::ExposureLimits;
+use crate::fees::FeeManager;
+use crate::investment::InvestmentStorage;
+use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::profits::PlatformFee;
+use crate::settlement::{get_payoff_quote, SettlementTimelock};
+use soroban_sdk::{contracttype, token, Address, BytesN, Env};
+
+/// Projected result of `accept_bid_and_fund`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AcceptBidSimulation {
+    pub invoice_id: BytesN<32>,
+    pub bid_id: BytesN<32>,
+    pub invoice_status: InvoiceStatus,
+    pub bid_status: BidStatus,
+    pub investor: Address,
+    /// Amount locked in escrow until settlement
+    pub escrow_amount: i128,
+    /// Part of the escrow already held as the bid's bond
+    pub bond_applied: i128,
+    /// Amount transferred from the investor
+    pub investor_transfer: i128,
+    pub investor_balance_after: i128,
+    pub contract_balance_after: i128,
+    /// Platform fee at settlement for full, on-time payment, on the current fee terms
+    pub projected_platform_fee: i128,
+    /// Investor payout at settlement for full, on-time payment
+    pub projected_investor_return: i128,
+}
+
+/// Projected result of defaulting a funded invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultSimulation {
+    pub invoice_id: BytesN<32>,
+    pub invoice_status: InvoiceStatus,
+    /// Investor whose investment becomes `Defaulted`
+    pub investor: Option<Address>,
+    /// Principal the investor funded
+    pub principal: i128,
+    /// Coverage paid on the first active insurance policy, if any
+    pub insurance_payout: i128,
+    pub insurance_provider: Option<Address>,
+    /// Principal not covered by insurance
+    pub investor_loss: i128,
+    /// Face value plus late penalties accrued so far, frozen as the amount due on default
+    pub amount_due: i128,
+    /// `mark_invoice_defaulted` succeeds after this time with the default grace period
+    pub grace_deadline: u64,
+    pub can_default_now: bool,
+}
+
+/// Breakdown of a `settle_invoice` payment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementPreview {
+    pub invoice_id: BytesN<32>,
+    pub payment_amount: i128,
+    /// Payoff amount as of now, including accrued late penalties
+    pub amount_due: i128,
+    pub late_penalty: i128,
+    pub investor: Address,
+    /// Principal the investor funded, returned first
+    pub investor_principal: i128,
+    /// Investor return above the principal, including its late penalty share
+    pub investor_profit: i128,
+    /// Platform fee, including its late penalty share and any keeper reward
+    pub platform_fee: i128,
+    /// Active insurance coverage that lapses once the invoice is paid; no insurance funds move
+    /// at settlement
+    pub insurance_coverage_released: i128,
+    /// Payment above the amount due, held for the business to claim as an overpayment refund
+    pub business_residual: i128,
+    /// Whether the payment is at or above the timelock threshold, so it must go through
+    /// `initiate_settlement` instead of `settle_invoice`
+    pub requires_timelock: bool,
+}
+
+/// Preview `accept_bid_and_fund` for a bid.
+///
+/// # Errors
+/// Same as `accept_bid_and_fund`: `InvoiceNotFound`, `InvoiceAlreadyFunded`,
+/// `InvoiceNotAvailableForFunding`, `StorageKeyNotFound`, `Unauthorized`, `BidNotPlaced`,
+/// `BidExpired`, `OperationNotAllowed` when the category exposure cap would be exceeded
+pub fn simulate_accept_bid(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    bid_id: &BytesN<32>,
+) -> Result<AcceptBidSimulation, QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Verified {
+        if invoice.status == InvoiceStatus::Funded {
+            return Err(QuickLendXError::InvoiceAlreadyFunded);
+        }
+        return Err(QuickLendXError::InvoiceNotAvailableForFunding);
+    }
+
+    let bid = BidStorage::get_bid(env, bid_id).ok_or(QuickLendXError::StorageKeyNotFound)?;
+    if bid.invoice_id != *invoice_id {
+        return Err(QuickLendXError::Unauthorized);
+    }
+    if bid.status != BidStatus::Placed {
+        return Err(QuickLendXError::BidNotPlaced);
+    }
+    if bid.is_expired(env.ledger().timestamp()) {
+        return Err(QuickLendXError::BidExpired);
+    }
+    ExposureLimits::check_funding(env, &invoice.category, bid.bid_amount)?;
+    ExposureLimits::check_business_funding(env, &invoice.business, bid.bid_amount)?;
+
+    let bond_applied = BidBonds::prepaid_amount(env, bid_id, bid.bid_amount);
+    let investor_transfer = bid.bid_amount.saturating_sub(bond_applied);
+    let token_client = token::Client::new(env, &invoice.currency);
+    let investor_balance = token_client.balance(&bid.investor);
+    let contract_balance = token_client.balance(&env.current_contract_address());
+
+    let quote = FeeManager::current_fee_quote(env, &invoice.business, &bid.investor);
+    let (projected_investor_return, projected_platform_fee) = PlatformFee::calculate_with_fee_bps(
+        bid.bid_amount,
+        invoice.amount,
+        quote.platform_fee_bps as i128,
+    );
+
+    Ok(AcceptBidSimulation {
+        invoice_id: invoice_id.clone(),
+        bid_id: bid_id.clone(),
+        invoice_status: InvoiceStatus::Funded,
+        bid_status: BidStatus::Accepted,
+        investor: bid.investor,
+        escrow_amount: bid.bid_amount,
+        bond_applied,
+        investor_transfer,
+        investor_balance_after: investor_balance.saturating_sub(investor_transfer),
+        contract_balance_after: contract_balance.saturating_add(investor_transfer),
+        projected_platform_fee,
+        projected_investor_return,
+    })
+}
+
+/// Preview defaulting a funded invoice. The projection is returned even before the grace
+/// period ends; `can_default_now` reports whether `mark_invoice_defaulted` would succeed.
+///
+/// # Errors
+/// * `InvoiceNotFound`, `InvoiceNotAvailableForFunding` if the invoice is not Funded
+pub fn simulate_default(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+) -> Result<DefaultSimulation, QuickLendXError> {
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvoiceNotAvailableForFunding);
+    }
+
+    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id);
+    let claim = investment
+        .clone()
+        .and_then(|mut investment| investment.process_insurance_claim())
+        .filter(|(_, amount)| *amount > 0);
+    let insurance_payout = claim.as_ref().map_or(0, |(_, amount)| *amount);
+    let principal = investment
+        .as_ref()
+        .map_or(invoice.funded_amount, |investment| investment.amount);
+    let grace_deadline = invoice.grace_deadline(DEFAULT_GRACE_PERIOD);
+
+    Ok(DefaultSimulation {
+        invoice_id: invoice_id.clone(),
+        invoice_status: InvoiceStatus::Defaulted,
+        investor: investment.map(|investment| investment.investor),
+        principal,
+        insurance_payout,
+        insurance_provider: claim.map(|(provider, _)| provider),
+        investor_loss: principal.saturating_sub(insurance_payout).max(0),
+        amount_due: get_payoff_quote(env, invoice_id, env.ledger().timestamp())
+            .map_or(invoice.amount, |quote| quote.payoff_amount),
+        grace_deadline,
+        can_default_now: env.ledger().timestamp() > grace_deadline,
+    })
+}
+
+/// Preview how `settle_invoice` would split `payment_amount`, as of now.
+///
+/// # Errors
+/// Same as `settle_invoice`, except for the timelock, which is reported in the preview:
+/// `InvalidAmount`, `InvoiceNotFound`, `InvalidStatus` unless Funded, `PaymentTooLow` if the
+/// payment (with earlier partial payments) does not cover the invoice
+pub fn preview_settlement(
+    env: &Env,
+    invoice_id: &BytesN<32>,
+    payment_amount: i128,
+) -> Result<SettlementPreview, QuickLendXError> {
+    if payment_amount <= 0 {
+        return Err(QuickLendXError::InvalidAmount);
+    }
+    let invoice =
+        InvoiceStorage::get_invoice(env, invoice_id).ok_or(QuickLendXError::InvoiceNotFound)?;
+    if invoice.status != InvoiceStatus::Funded {
+        return Err(QuickLendXError::InvalidStatus);
+    }
+    let investor = invoice
+        .investor
+        .clone()
+        .ok_or(QuickLendXError::NotInvestor)?;
+    let investment = InvestmentStorage::get_investment_by_invoice(env, invoice_id)
+        .ok_or(QuickLendXError::StorageKeyNotFound)?;
+
+    // Settlement counts earlier partial payments towards the total
+    let total_payment = payment_amount.max(invoice.total_paid);
+    if total_payment < investment.amount || total_payment < invoice.amount {
+        return Err(QuickLendXError::PaymentTooLow);
+    }
+    let amount_due = get_payoff_quote(env, invoice_id, env.ledger().timestamp())?.payoff_amount;
+    let business_residual = total_payment.saturating_sub(amount_due).max(0);
+    let waterfall = FeeManager::calculate_settlement_waterfall(
+        env,
+        &invoice,
+        &investor,
+        investment.amount,
+        total_payment - business_residual,
+    )?;
+
+    let mut insurance_coverage_released = 0i128;
+    for coverage in investment.insurance.iter() {
+        if coverage.active {
+            insurance_coverage_released =
+                insurance_coverage_released.saturating_add(coverage.coverage_amount);
+        }
+    }
+
+    Ok(SettlementPreview {
+        invoice_id: invoice_id.clone(),
+        payment_amount,
+        amount_due,
+        late_penalty: waterfall.late_penalty,
+        investor,
+        investor_principal: investment.amount.min(waterfall.investor_return),
+        investor_profit: waterfall
+            .investor_return
+            .saturating_sub(investment.amount)
+            .max(0),
+        platform_fee: waterfall.platform_fee,
+        insurance_coverage_released,
+        business_residual,
+        requires_timelock: SettlementTimelock::requires_timelock(env, payment_amount),
+    })
+}
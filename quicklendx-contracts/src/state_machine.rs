@@ -0,0 +1,105 @@
+//! Invoice status state machine.
+//!
+//! Every change of an invoice's status is checked against the transition table below, together
+//! with the role of the party making it. Paid, Defaulted, Cancelled, Refunded and Expired are
+//! terminal: no transition leaves them.
+
+use crate::errors::QuickLendXError;
+use crate::invoice::InvoiceStatus;
+use soroban_sdk::{contracttype, Env, Vec};
+
+/// The party on whose behalf a status change is made
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransitionRole {
+    Admin,
+    /// The invoice's business
+    Business,
+    /// An investor funding the invoice directly (buy-it-now, accepted counter-offer)
+    Investor,
+    /// The invoice's registered debtor
+    Debtor,
+    /// Anyone, through a permissionless maintenance entrypoint
+    Keeper,
+}
+
+/// A status an invoice may move to next, and who may move it there
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatusTransition {
+    pub to: InvoiceStatus,
+    pub roles: Vec<TransitionRole>,
+}
+
+use InvoiceStatus as S;
+use TransitionRole as R;
+
+const TRANSITIONS: &[(InvoiceStatus, InvoiceStatus, &[TransitionRole])] = &[
+    (S::Pending, S::Verified, &[R::Admin]),
+    (S::Pending, S::Cancelled, &[R::Business]),
+    (S::Pending, S::Expired, &[R::Keeper]),
+    (
+        S::Verified,
+        S::Funded,
+        &[R::Business, R::Investor, R::Keeper],
+    ),
+    (S::Verified, S::Pending, &[R::Keeper]),
+    (S::Verified, S::Cancelled, &[R::Business]),
+    (S::Verified, S::Expired, &[R::Keeper]),
+    (
+        S::Funded,
+        S::Paid,
+        &[R::Admin, R::Business, R::Debtor, R::Keeper],
+    ),
+    (S::Funded, S::Defaulted, &[R::Admin, R::Keeper]),
+    (S::Funded, S::Refunded, &[R::Admin, R::Business]),
+];
+
+pub struct InvoiceStateMachine;
+
+impl InvoiceStateMachine {
+    /// Roles allowed to move an invoice from `from` to `to`; empty if the transition is illegal
+    pub fn roles_for(from: &InvoiceStatus, to: &InvoiceStatus) -> &'static [TransitionRole] {
+        TRANSITIONS
+            .iter()
+            .find(|(f, t, _)| f == from && t == to)
+            .map_or(&[], |(_, _, roles)| roles)
+    }
+
+    /// # Errors
+    /// * `InvalidStatus` if the transition is not in the table
+    /// * `Unauthorized` if `role` may not make it
+    pub fn ensure_transition(
+        from: &InvoiceStatus,
+        to: &InvoiceStatus,
+        role: TransitionRole,
+    ) -> Result<(), QuickLendXError> {
+        let roles = Self::roles_for(from, to);
+        if roles.is_empty() {
+            return Err(QuickLendXError::InvalidStatus);
+        }
+        if !roles.contains(&role) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Transitions leaving `from`, in table order
+    pub fn allowed_transitions(env: &Env, from: &InvoiceStatus) -> Vec<StatusTransition> {
+        let mut transitions = Vec::new(env);
+        for (f, to, roles) in TRANSITIONS.iter() {
+            if f != from {
+                continue;
+            }
+            let mut allowed = Vec::new(env);
+            for role in roles.iter() {
+                allowed.push_back(*role);
+            }
+            transitions.push_back(StatusTransition {
+                to: to.clone(),
+                roles: allowed,
+            });
+        }
+        transitions
+    }
+}
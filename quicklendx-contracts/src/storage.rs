@@ -19,9 +19,9 @@
 use soroban_sdk::{symbol_short, Address, BytesN, Env, Symbol, Vec};
 
 use crate::bid::{Bid, BidStatus};
+use crate::fees::PlatformFeeConfig;
 use crate::investment::{Investment, InvestmentStatus};
 use crate::invoice::{Invoice, InvoiceStatus};
-use crate::profits::PlatformFeeConfig;
 
 /// Storage keys for the contract
 pub struct StorageKeys;
@@ -82,6 +82,7 @@ impl Indexes {
             InvoiceStatus::Defaulted => symbol_short!("defaulted"),
             InvoiceStatus::Cancelled => symbol_short!("cancelled"),
             InvoiceStatus::Refunded => symbol_short!("refunded"),
+            InvoiceStatus::Expired => symbol_short!("expired"),
         };
         (symbol_short!("inv_stat"), status_symbol)
     }
@@ -104,6 +105,7 @@ impl Indexes {
             BidStatus::Accepted => symbol_short!("accepted"),
             BidStatus::Expired => symbol_short!("expired"),
             BidStatus::Cancelled => symbol_short!("cancelled"),
+            BidStatus::Countered => symbol_short!("countered"),
         };
         (symbol_short!("bids_stat"), status_symbol)
     }
@@ -0,0 +1,102 @@
+//! Investor suitability acknowledgments.
+//!
+//! The admin publishes a risk disclosure by its document hash; each publication bumps the
+//! disclosure version. Once a disclosure is published, an investor must acknowledge the current
+//! version before placing a bid, and again whenever a new version is published.
+
+use crate::errors::QuickLendXError;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
+
+const DISCLOSURE_KEY: Symbol = symbol_short!("suit_dsc");
+const ACK_KEY: Symbol = symbol_short!("suit_ack");
+
+/// The risk disclosure investors must acknowledge
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuitabilityDisclosure {
+    /// Starts at 1 and increases with each publication
+    pub version: u32,
+    /// Hash of the disclosure document
+    pub disclosure_hash: BytesN<32>,
+    pub published_at: u64,
+}
+
+/// An investor's latest acknowledgment of the risk disclosure
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuitabilityAcknowledgment {
+    pub investor: Address,
+    pub version: u32,
+    pub disclosure_hash: BytesN<32>,
+    pub acknowledged_at: u64,
+}
+
+pub struct InvestorSuitability;
+
+impl InvestorSuitability {
+    /// The current disclosure, if one was published
+    pub fn get_disclosure(env: &Env) -> Option<SuitabilityDisclosure> {
+        env.storage().instance().get(&DISCLOSURE_KEY)
+    }
+
+    /// Publish a new disclosure version; investors must re-acknowledge before their next bid
+    pub fn publish(env: &Env, disclosure_hash: &BytesN<32>) -> SuitabilityDisclosure {
+        let version = Self::get_disclosure(env).map_or(0, |current| current.version) + 1;
+        let disclosure = SuitabilityDisclosure {
+            version,
+            disclosure_hash: disclosure_hash.clone(),
+            published_at: env.ledger().timestamp(),
+        };
+        env.storage().instance().set(&DISCLOSURE_KEY, &disclosure);
+        disclosure
+    }
+
+    pub fn get_acknowledgment(env: &Env, investor: &Address) -> Option<SuitabilityAcknowledgment> {
+        env.storage().instance().get(&(ACK_KEY, investor.clone()))
+    }
+
+    /// Record that `investor` acknowledged the current disclosure
+    ///
+    /// # Errors
+    /// * `StorageKeyNotFound` if no disclosure was published
+    /// * `InvalidDescription` if `disclosure_hash` is not the current disclosure's hash
+    pub fn acknowledge(
+        env: &Env,
+        investor: &Address,
+        disclosure_hash: &BytesN<32>,
+    ) -> Result<SuitabilityAcknowledgment, QuickLendXError> {
+        let disclosure = Self::get_disclosure(env).ok_or(QuickLendXError::StorageKeyNotFound)?;
+        if disclosure.disclosure_hash != *disclosure_hash {
+            return Err(QuickLendXError::InvalidDescription);
+        }
+        let acknowledgment = SuitabilityAcknowledgment {
+            investor: investor.clone(),
+            version: disclosure.version,
+            disclosure_hash: disclosure.disclosure_hash,
+            acknowledged_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&(ACK_KEY, investor.clone()), &acknowledgment);
+        Ok(acknowledgment)
+    }
+
+    /// Whether the investor may bid: always while no disclosure is published, otherwise only
+    /// after acknowledging the current version
+    pub fn is_current(env: &Env, investor: &Address) -> bool {
+        let Some(disclosure) = Self::get_disclosure(env) else {
+            return true;
+        };
+        Self::get_acknowledgment(env, investor)
+            .is_some_and(|acknowledgment| acknowledgment.version == disclosure.version)
+    }
+
+    /// # Errors
+    /// * `OperationNotAllowed` if the investor has not acknowledged the current disclosure
+    pub fn require_current(env: &Env, investor: &Address) -> Result<(), QuickLendXError> {
+        if !Self::is_current(env, investor) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Investor tax lots.
+//!
+//! Each investment is tracked as a discrete lot: its cost basis and acquisition date when the
+//! invoice is funded, and its disposal (settlement, refund, default) with the proceeds the
+//! investor received. Statements for jurisdictions that require lot-level reporting can be
+//! built from `get_tax_lots` without replaying settlement and default history.
+
+use crate::analytics::{AnalyticsCalculator, TimePeriod};
+use crate::investment::Investment;
+use crate::invoice::InvoiceStorage;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const TAX_LOT_KEY: Symbol = symbol_short!("tax_lot");
+const INVESTOR_LOTS_KEY: Symbol = symbol_short!("tax_inv");
+
+/// How a lot left the investor's portfolio
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LotDisposal {
+    /// The investor still holds the position
+    Open,
+    /// The invoice was paid and the investor received its return
+    Settlement,
+    /// The position moved to another investor
+    Transfer,
+    /// The invoice defaulted; proceeds are the insurance payout plus later recoveries
+    Default,
+    /// The escrow was refunded before settlement
+    Refund,
+}
+
+/// One investment as a tax lot
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TaxLot {
+    pub investment_id: BytesN<32>,
+    pub invoice_id: BytesN<32>,
+    pub investor: Address,
+    pub currency: Address,
+    /// Amount the investor paid to fund the invoice
+    pub cost_basis: i128,
+    pub acquired_at: u64,
+    pub disposal: LotDisposal,
+    pub disposed_at: u64,
+    /// Amount the investor received on disposal
+    pub proceeds: i128,
+    /// Proceeds minus cost basis; negative for a loss
+    pub realized_gain: i128,
+}
+
+pub struct TaxLots;
+
+impl TaxLots {
+    pub fn get(env: &Env, investment_id: &BytesN<32>) -> Option<TaxLot> {
+        env.storage()
+            .instance()
+            .get(&(TAX_LOT_KEY, investment_id.clone()))
+    }
+
+    fn set(env: &Env, lot: &TaxLot) {
+        env.storage()
+            .instance()
+            .set(&(TAX_LOT_KEY, lot.investment_id.clone()), lot);
+    }
+
+    fn investor_lots(env: &Env, investor: &Address) -> Vec<BytesN<32>> {
+        env.storage()
+            .instance()
+            .get(&(INVESTOR_LOTS_KEY, investor.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Open a lot for a new investment; no-op if the lot already exists
+    pub fn open(env: &Env, investment: &Investment) {
+        if Self::get(env, &investment.investment_id).is_some() {
+            return;
+        }
+        let Some(invoice) = InvoiceStorage::get_invoice(env, &investment.invoice_id) else {
+            return;
+        };
+        Self::set(
+            env,
+            &TaxLot {
+                investment_id: investment.investment_id.clone(),
+                invoice_id: investment.invoice_id.clone(),
+                investor: investment.investor.clone(),
+                currency: invoice.currency,
+                cost_basis: investment.amount,
+                acquired_at: investment.funded_at,
+                disposal: LotDisposal::Open,
+                disposed_at: 0,
+                proceeds: 0,
+                realized_gain: 0,
+            },
+        );
+        let mut lots = Self::investor_lots(env, &investment.investor);
+        lots.push_back(investment.investment_id.clone());
+        env.storage()
+            .instance()
+            .set(&(INVESTOR_LOTS_KEY, investment.investor.clone()), &lots);
+    }
+
+    /// Close an open lot with the proceeds the investor received; no-op for unknown or already
+    /// disposed lots
+    pub fn dispose(env: &Env, investment_id: &BytesN<32>, disposal: LotDisposal, proceeds: i128) {
+        let Some(mut lot) = Self::get(env, investment_id) else {
+            return;
+        };
+        if lot.disposal != LotDisposal::Open {
+            return;
+        }
+        lot.disposal = disposal;
+        lot.disposed_at = env.ledger().timestamp();
+        lot.proceeds = proceeds;
+        lot.realized_gain = proceeds.saturating_sub(lot.cost_basis);
+        Self::set(env, &lot);
+    }
+
+    /// Add proceeds received after disposal, such as recoveries on a defaulted invoice
+    pub fn add_proceeds(env: &Env, investment_id: &BytesN<32>, amount: i128) {
+        let Some(mut lot) = Self::get(env, investment_id) else {
+            return;
+        };
+        if lot.disposal == LotDisposal::Open || amount <= 0 {
+            return;
+        }
+        lot.proceeds = lot.proceeds.saturating_add(amount);
+        lot.realized_gain = lot.proceeds.saturating_sub(lot.cost_basis);
+        Self::set(env, &lot);
+    }
+
+    /// An investor's lots held at any time during `period`: acquired before it ended and
+    /// still open or disposed after it started, in acquisition order
+    pub fn get_for_period(env: &Env, investor: &Address, period: TimePeriod) -> Vec<TaxLot> {
+        let (start, end) = AnalyticsCalculator::get_period_dates(env.ledger().timestamp(), period);
+        let mut lots = Vec::new(env);
+        for investment_id in Self::investor_lots(env, investor).iter() {
+            let Some(lot) = Self::get(env, &investment_id) else {
+                continue;
+            };
+            let held_in_period = lot.disposal == LotDisposal::Open || lot.disposed_at >= start;
+            if lot.acquired_at <= end && held_in_period {
+                lots.push_back(lot);
+            }
+        }
+        lots
+    }
+}
@@ -3,9 +3,13 @@ mod test_invoice_categories;
 
 use super::*;
 use crate::audit::{AuditOperation, AuditOperationFilter, AuditQueryFilter};
+use crate::backup::BACKUP_CHUNK_SIZE;
 use crate::bid::{BidStatus, BidStorage};
 use crate::investment::{Investment, InvestmentStorage};
-use crate::invoice::{DisputeStatus, InvoiceCategory, InvoiceMetadata, LineItemRecord};
+use crate::invoice::{
+    DisputeStatus, InvoiceCategory, InvoiceLineItem, InvoiceMetadata, LineItemRecord,
+};
+use crate::protocol_limits::CharacterPolicy;
 use crate::verification::BusinessVerificationStatus;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
@@ -203,6 +207,9 @@ fn test_update_invoice_status() {
     let invoice = client.get_invoice(&invoice_id);
     assert_eq!(invoice.status, InvoiceStatus::Pending);
 
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
     // Update to verified
     client.update_invoice_status(&invoice_id, &InvoiceStatus::Verified);
 
@@ -344,6 +351,145 @@ fn test_invoice_metadata_validation() {
     assert_eq!(contract_error_line, QuickLendXError::InvalidAmount);
 }
 
+#[test]
+fn test_invoice_line_items_set_and_query() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+
+    let business = Address::generate(&env);
+    let invoice_id = client.store_invoice(
+        &business,
+        &1_250,
+        &Address::generate(&env),
+        &(env.ledger().timestamp() + 86400),
+        &String::from_str(&env, "Itemized invoice"),
+        &InvoiceCategory::Products,
+        &Vec::new(&env),
+    );
+    assert_eq!(client.get_invoice_line_items(&invoice_id).len(), 0);
+
+    let mut items = Vec::new(&env);
+    items.push_back(InvoiceLineItem {
+        description: String::from_str(&env, "Pallets"),
+        quantity: 10,
+        unit_price: 100,
+        subtotal: 1_000,
+    });
+    items.push_back(InvoiceLineItem {
+        description: String::from_str(&env, "Freight"),
+        quantity: 1,
+        unit_price: 250,
+        subtotal: 250,
+    });
+    client.set_invoice_line_items(&invoice_id, &items);
+    assert_eq!(client.get_invoice_line_items(&invoice_id), items);
+
+    // Subtotals must sum to the invoice amount
+    let mut short = items.clone();
+    short.pop_back();
+    assert_eq!(
+        client.try_set_invoice_line_items(&invoice_id, &short),
+        Err(Ok(QuickLendXError::InvoiceAmountInvalid))
+    );
+
+    // Each subtotal must be quantity times unit price
+    let mut mispriced = items.clone();
+    mispriced.set(
+        1,
+        InvoiceLineItem {
+            description: String::from_str(&env, "Freight"),
+            quantity: 2,
+            unit_price: 100,
+            subtotal: 250,
+        },
+    );
+    assert_eq!(
+        client.try_set_invoice_line_items(&invoice_id, &mispriced),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_invoice_line_items(&invoice_id, &Vec::new(&env)),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+    assert_eq!(client.get_invoice_line_items(&invoice_id), items);
+}
+
+#[test]
+fn test_content_policy_applies_to_upload_tags_and_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+
+    let mut policy = client.get_content_policy();
+    assert_eq!(policy.max_tags_per_invoice, 10);
+    policy.max_description_length = 20;
+    policy.max_tags_per_invoice = 1;
+    policy.character_policy = CharacterPolicy::NoControl;
+    policy
+        .blocked_terms
+        .push_back(String::from_str(&env, "scam"));
+    client.set_content_policy(&policy);
+    assert_eq!(client.get_content_policy(), policy);
+
+    let business = Address::generate(&env);
+    let currency = Address::generate(&env);
+    let due_date = env.ledger().timestamp() + 86400;
+    let upload = |description: &str| {
+        client.try_store_invoice(
+            &business,
+            &1_000,
+            &currency,
+            &due_date,
+            &String::from_str(&env, description),
+            &InvoiceCategory::Services,
+            &Vec::new(&env),
+        )
+    };
+    for rejected in ["Twenty-one characters", "Not a SCAM, honest", "Line\nbreak"] {
+        assert_eq!(
+            upload(rejected),
+            Err(Ok(QuickLendXError::InvalidDescription))
+        );
+    }
+    let invoice_id = upload("Consulting").unwrap().unwrap();
+
+    client.add_invoice_tag(&invoice_id, &String::from_str(&env, "retainer"));
+    assert_eq!(
+        client.try_add_invoice_tag(&invoice_id, &String::from_str(&env, "monthly")),
+        Err(Ok(QuickLendXError::TagLimitExceeded))
+    );
+
+    let mut line_items = Vec::new(&env);
+    line_items.push_back(LineItemRecord(
+        String::from_str(&env, "Advice"),
+        1,
+        1_000,
+        1_000,
+    ));
+    let metadata = InvoiceMetadata {
+        customer_name: String::from_str(&env, "Scammers Ltd"),
+        customer_address: String::from_str(&env, "1 Main St"),
+        tax_id: String::from_str(&env, "TAX-1"),
+        line_items,
+        notes: String::from_str(&env, ""),
+    };
+    assert_eq!(
+        client.try_update_invoice_metadata(&invoice_id, &metadata),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+
+    policy.max_tags_per_invoice = 0;
+    assert_eq!(
+        client.try_set_content_policy(&policy),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
 #[test]
 fn test_investor_verification_enforced() {
     let env = Env::default();
@@ -407,7 +553,7 @@ fn test_investor_verification_enforced() {
     let over_limit = client.try_place_bid(&investor, &invoice_id, &1_500, &1_700);
     let limit_err = over_limit.err().expect("expected limit error");
     let limit_contract_error = limit_err.expect("expected invoke error");
-    assert_eq!(limit_contract_error, QuickLendXError::InvalidAmount);
+    assert_eq!(limit_contract_error, QuickLendXError::BidExceedsLimit);
 }
 
 #[test]
@@ -445,6 +591,9 @@ fn test_get_available_invoices() {
     let available_invoices = client.get_available_invoices();
     assert_eq!(available_invoices.len(), 0);
 
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
     // Verify one invoice
     client.update_invoice_status(&invoice1_id, &InvoiceStatus::Verified);
 
@@ -529,18 +678,24 @@ fn test_invoice_lifecycle() {
         &Vec::new(&env),
     );
 
-    // Test lifecycle: Pending -> Verified -> Paid
+    // Test lifecycle: Pending -> Verified; an unfunded invoice cannot be marked Paid
     let mut invoice = client.get_invoice(&invoice_id);
     assert_eq!(invoice.status, InvoiceStatus::Pending);
 
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
     client.update_invoice_status(&invoice_id, &InvoiceStatus::Verified);
     invoice = client.get_invoice(&invoice_id);
     assert_eq!(invoice.status, InvoiceStatus::Verified);
 
-    client.update_invoice_status(&invoice_id, &InvoiceStatus::Paid);
+    assert_eq!(
+        client.try_update_invoice_status(&invoice_id, &InvoiceStatus::Paid),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
     invoice = client.get_invoice(&invoice_id);
-    assert_eq!(invoice.status, InvoiceStatus::Paid);
-    assert!(invoice.settled_at.is_some());
+    assert_eq!(invoice.status, InvoiceStatus::Verified);
+    assert!(invoice.settled_at.is_none());
 }
 
 #[test]
@@ -1264,13 +1419,24 @@ fn test_add_invoice_rating() {
         &Vec::new(&env),
     );
 
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
     // Verify the invoice
     client.update_invoice_status(&invoice_id, &InvoiceStatus::Verified);
 
     // Fund the invoice properly
     env.as_contract(&contract_id, || {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id).unwrap();
-        invoice.mark_as_funded(&env, investor.clone(), 1000, env.ledger().timestamp());
+        invoice
+            .mark_as_funded(
+                &env,
+                investor.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         InvoiceStorage::update_invoice(&env, &invoice);
     });
 
@@ -1322,7 +1488,16 @@ fn test_add_invoice_rating_validation() {
     // Fund the invoice
     env.as_contract(&contract_id, || {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id).unwrap();
-        invoice.mark_as_funded(&env, investor.clone(), 1000, env.ledger().timestamp());
+        invoice.verify(&env, business.clone()).unwrap();
+        invoice
+            .mark_as_funded(
+                &env,
+                investor.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         InvoiceStorage::update_invoice(&env, &invoice);
     });
 
@@ -1389,7 +1564,16 @@ fn test_multiple_ratings() {
 
     env.as_contract(&contract_id, || {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id).unwrap();
-        invoice.mark_as_funded(&env, investor.clone(), 1000, env.ledger().timestamp());
+        invoice.verify(&env, business.clone()).unwrap();
+        invoice
+            .mark_as_funded(
+                &env,
+                investor.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         InvoiceStorage::update_invoice(&env, &invoice);
     });
 
@@ -1439,7 +1623,16 @@ fn test_duplicate_rating_prevention() {
 
     env.as_contract(&contract_id, || {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id).unwrap();
-        invoice.mark_as_funded(&env, investor.clone(), 1000, env.ledger().timestamp());
+        invoice.verify(&env, business.clone()).unwrap();
+        invoice
+            .mark_as_funded(
+                &env,
+                investor.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         InvoiceStorage::update_invoice(&env, &invoice);
     });
 
@@ -1510,7 +1703,16 @@ fn test_rating_queries() {
 
         // Update invoice to have investor and add to funded status list
         let mut invoice1 = InvoiceStorage::get_invoice(&env, &invoice1_id).unwrap();
-        invoice1.mark_as_funded(&env, investor1.clone(), 1000, env.ledger().timestamp());
+        invoice1.verify(&env, business1.clone()).unwrap();
+        invoice1
+            .mark_as_funded(
+                &env,
+                investor1.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         invoice1
             .add_rating(
                 5,
@@ -1575,7 +1777,16 @@ fn test_rating_statistics() {
 
     env.as_contract(&contract_id, || {
         let mut invoice = InvoiceStorage::get_invoice(&env, &invoice_id).unwrap();
-        invoice.mark_as_funded(&env, investor.clone(), 1000, env.ledger().timestamp());
+        invoice.verify(&env, business.clone()).unwrap();
+        invoice
+            .mark_as_funded(
+                &env,
+                investor.clone(),
+                1000,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
         InvoiceStorage::update_invoice(&env, &invoice);
     });
 
@@ -2136,6 +2347,60 @@ fn test_archive_backup() {
     assert!(!backups.contains(&backup_id));
 }
 
+#[test]
+fn test_backup_is_stored_in_chunks() {
+    let env = Env::default();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    client.set_admin(&Address::generate(&env));
+
+    let business = Address::generate(&env);
+    let currency = Address::generate(&env);
+    let due_date = env.ledger().timestamp() + 86400;
+    let mut invoice_ids = Vec::new(&env);
+    for i in 0..(BACKUP_CHUNK_SIZE + 5) {
+        invoice_ids.push_back(client.store_invoice(
+            &business,
+            &(1000 + i as i128),
+            &currency,
+            &due_date,
+            &String::from_str(&env, "Chunked invoice"),
+            &InvoiceCategory::Services,
+            &Vec::new(&env),
+        ));
+    }
+
+    let backup_id = client.create_backup(&String::from_str(&env, "Chunked backup"));
+    let manifest = client.get_backup_manifest(&backup_id).unwrap();
+    assert_eq!(manifest.chunk_count, 2);
+    assert_eq!(manifest.invoice_count, BACKUP_CHUNK_SIZE + 5);
+    assert_eq!(
+        client.get_backup_chunk(&backup_id, &0).unwrap().len(),
+        BACKUP_CHUNK_SIZE
+    );
+    assert_eq!(client.get_backup_chunk(&backup_id, &1).unwrap().len(), 5);
+    assert!(client.get_backup_chunk(&backup_id, &2).is_none());
+
+    // Restore streams every chunk back
+    env.as_contract(&contract_id, || {
+        QuickLendXContract::clear_all_invoices(&env).unwrap();
+    });
+    client.restore_backup(&backup_id);
+    for (i, invoice_id) in invoice_ids.iter().enumerate() {
+        assert_eq!(client.get_invoice(&invoice_id).amount, 1000 + i as i128);
+    }
+
+    // A missing chunk fails validation
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("bkup_chk"), backup_id.clone(), 1u32));
+    });
+    assert!(!client.validate_backup(&backup_id));
+    assert!(client.try_restore_backup(&backup_id).is_err());
+}
+
 // TODO: Fix authorization issues in test environment
 // #[test]
 fn test_audit_trail_creation() {
@@ -2499,6 +2764,17 @@ fn test_notification_creation_on_invoice_status_change() {
     assert!(updated_notifications.len() > initial_count);
 }
 
+#[test]
+fn test_every_status_transition_sends_a_notification() {
+    let env = Env::default();
+    let contract_id = env.register(QuickLendXContract, ());
+
+    let uncovered = env.as_contract(&contract_id, || {
+        crate::notification_hooks::NotificationHooks::uncovered_transitions(&env)
+    });
+    assert!(uncovered.is_empty(), "uncovered transitions: {:?}", uncovered);
+}
+
 #[test]
 fn test_notification_delivery_status_update() {
     let env = Env::default();
@@ -2546,6 +2822,52 @@ fn test_notification_delivery_status_update() {
     );
 }
 
+#[test]
+fn test_failed_notification_is_dead_lettered_and_replayed() {
+    use crate::notifications::NotificationTrigger;
+
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    let mut preferences = client.get_notification_preferences(&business);
+    preferences.invoice_verified = false;
+    client.update_notification_preferences(&business, &preferences);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 1_000);
+    let dead_letters = client.get_notification_dead_letters();
+    assert_eq!(dead_letters.len(), 1);
+    let dead_letter = dead_letters.get(0).unwrap();
+    assert_eq!(dead_letter.invoice_id, invoice_id);
+    assert_eq!(dead_letter.trigger, NotificationTrigger::InvoiceVerified);
+    assert_eq!(
+        dead_letter.error_code,
+        QuickLendXError::NotificationBlocked as u32
+    );
+
+    // Still blocked: the entry stays with the attempt counted
+    assert!(!client.replay_notification_dead_letter(&dead_letter.id));
+    assert_eq!(
+        client
+            .get_notification_dead_letters()
+            .get(0)
+            .unwrap()
+            .replay_attempts,
+        1
+    );
+
+    preferences.invoice_verified = true;
+    client.update_notification_preferences(&business, &preferences);
+    let before = client.get_user_notifications(&business).len();
+    assert!(client.replay_notification_dead_letter(&dead_letter.id));
+    assert_eq!(client.get_user_notifications(&business).len(), before + 1);
+    assert!(client.get_notification_dead_letters().is_empty());
+    assert_eq!(
+        client.try_replay_notification_dead_letter(&dead_letter.id),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
+
 #[test]
 fn test_user_notification_stats() {
     let env = Env::default();
@@ -3283,6 +3605,9 @@ fn test_investment_insurance_lifecycle() {
     let investment = client.get_invoice_investment(&invoice_id);
     let investment_id = investment.investment_id.clone();
 
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
+
     let invalid_attempt = client.try_add_investment_insurance(&investment_id, &provider, &150u32);
     let err = invalid_attempt.err().expect("expected contract error");
     let contract_error = err.expect("expected contract invoke error");
@@ -3382,6 +3707,8 @@ fn test_query_investment_insurance_single_coverage() {
     let insurance_before = client.try_query_investment_insurance(&investment_id).unwrap().unwrap();
     assert_eq!(insurance_before.len(), 0);
 
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
     // Add insurance
     let coverage_percentage = 75u32;
     client.add_investment_insurance(&investment_id, &provider, &coverage_percentage);
@@ -3473,6 +3800,8 @@ fn test_query_investment_insurance_premium_calculation() {
 
     let investment = client.get_invoice_investment(&invoice_id);
     let investment_id = investment.investment_id.clone();
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
 
     // Test multiple coverage percentages
     let test_cases: [(u32, i128); 3] = [
@@ -3559,6 +3888,8 @@ fn test_query_investment_insurance_inactive_coverage() {
     let investment = client.get_invoice_investment(&invoice_id);
     let investment_id = investment.investment_id.clone();
 
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
     // Add insurance
     client.add_investment_insurance(&investment_id, &provider, &60u32);
 
@@ -3583,6 +3914,76 @@ fn test_query_investment_insurance_inactive_coverage() {
     assert_eq!(coverage_after.coverage_amount, coverage_before.coverage_amount);
 }
 
+#[test]
+fn test_dispute_template_resolution_splits_escrow_and_records_outcome() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let invoice_id = &fixture.invoice_id;
+    let partial = String::from_str(env, "Partial delivery, investor refunded 40%");
+
+    client.add_dispute_template(&1, &String::from_str(env, "Delivery confirmed"), &0);
+    client.add_dispute_template(&2, &partial, &4_000);
+    assert_eq!(
+        client.try_add_dispute_template(&2, &partial, &4_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_add_dispute_template(&3, &partial, &10_001),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    client.set_dispute_template_active(&1, &false);
+    assert_eq!(client.get_dispute_templates().len(), 2);
+
+    client.create_dispute(
+        invoice_id,
+        &fixture.investor,
+        &String::from_str(env, "Half the goods missing"),
+        &String::from_str(env, "Delivery note"),
+    );
+    client.put_dispute_under_review(invoice_id, &scenario.admin);
+    assert_eq!(
+        client.try_resolve_dispute_with_template(invoice_id, &scenario.admin, &1),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_resolve_dispute_with_template(invoice_id, &fixture.business, &2),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    let token_client = token::Client::new(env, &fixture.currency);
+    let investor_before = token_client.balance(&fixture.investor);
+    let business_before = token_client.balance(&fixture.business);
+    let outcome = client.resolve_dispute_with_template(invoice_id, &scenario.admin, &2);
+    assert!(outcome.executed);
+    assert_eq!(outcome.template_code, 2);
+    assert_eq!(outcome.investor_amount, 3_600);
+    assert_eq!(outcome.business_amount, 5_400);
+    assert_eq!(client.get_dispute_outcome(invoice_id), Some(outcome));
+    assert_eq!(
+        token_client.balance(&fixture.investor),
+        investor_before + 3_600
+    );
+    assert_eq!(
+        token_client.balance(&fixture.business),
+        business_before + 5_400
+    );
+    assert_eq!(
+        client.get_escrow_status(invoice_id),
+        crate::payments::EscrowStatus::Refunded
+    );
+    assert_eq!(
+        client.get_invoice(invoice_id).status,
+        InvoiceStatus::Refunded
+    );
+    assert_eq!(
+        client.get_dispute_details(invoice_id).unwrap().resolution,
+        partial
+    );
+    assert_eq!(client.get_dispute_template(&2).unwrap().times_used, 1);
+}
+
 // Test basic functionality from README.md
 #[test]
 fn test_basic_readme_queries() {
@@ -4311,3 +4712,130 @@ fn test_get_invoices_by_status_cancelled() {
         assert!(found);
     }
 }
+
+// ========================================
+// Analytics Report Tests
+// ========================================
+
+#[test]
+fn test_aging_report_tracks_funded_invoices_by_days_to_and_past_due() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let start = env.ledger().timestamp();
+    let day = 86_400;
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &first, &9_000, &10_000);
+    client.accept_bid(&first, &bid_id);
+
+    env.ledger().set_timestamp(start + 40 * day);
+    let second = scenario.verified_invoice(&business, &currency, 5_000);
+    let bid_id = client.place_bid(&investor, &second, &4_000, &5_000);
+    client.accept_bid(&second, &bid_id);
+    // Unfunded invoices are not aged
+    scenario.verified_invoice(&business, &currency, 2_000);
+
+    // The first is 11 days overdue, the second due in 29 days
+    env.ledger().set_timestamp(start + 41 * day);
+    let report = client.get_aging_report(&None);
+    assert_eq!(report.overdue_1_30.count, 1);
+    assert_eq!(report.overdue_1_30.outstanding_amount, 10_000);
+    assert_eq!(report.due_in_1_30.count, 1);
+    assert_eq!(report.due_in_1_30.outstanding_amount, 5_000);
+    assert_eq!(report.current.count, 0);
+    assert_eq!(report.total.count, 2);
+    assert_eq!(report.total.outstanding_amount, 15_000);
+
+    let business_report = client.get_aging_report(&Some(business.clone()));
+    assert_eq!(business_report.total, report.total);
+    let other = client.get_aging_report(&Some(Address::generate(env)));
+    assert_eq!(other.total.count, 0);
+
+    // Partial payments reduce the outstanding amount
+    client.process_partial_payment(&second, &1_000, &String::from_str(env, "tx-1"));
+    assert_eq!(
+        client.get_aging_report(&None).due_in_1_30.outstanding_amount,
+        4_000
+    );
+
+    // On its due day the second is current and the first has aged into 31-60
+    env.ledger().set_timestamp(start + 70 * day);
+    let report = client.get_aging_report(&None);
+    assert_eq!(report.current.count, 1);
+    assert_eq!(report.current.outstanding_amount, 4_000);
+    assert_eq!(report.overdue_31_60.outstanding_amount, 10_000);
+
+    // Settled and defaulted invoices leave the totals
+    client.settle_invoice(&second, &5_000);
+    client.mark_invoice_defaulted(&first, &None);
+    let report = client.get_aging_report(&Some(business));
+    assert_eq!(report.total.count, 0);
+    assert_eq!(report.total.outstanding_amount, 0);
+}
+
+#[test]
+fn test_expected_cashflows_by_window_and_investor() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let other_investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor, &other_investor]);
+    let start = env.ledger().timestamp();
+    let due_in = crate::testutils::DEFAULT_DUE_IN_SECONDS;
+
+    // Created one day apart, so the later one falls due a day after the earlier one
+    env.ledger().set_timestamp(start + 86_400);
+    let later = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &later, &9_000, &10_000);
+    client.accept_bid(&later, &bid_id);
+    env.ledger().set_timestamp(start);
+    let earlier = scenario.verified_invoice(&business, &currency, 4_000);
+    let bid_id = client.place_bid(&other_investor, &earlier, &3_000, &4_000);
+    client.accept_bid(&earlier, &bid_id);
+
+    let end = start + due_in + 10 * 86_400;
+    let all = client.get_expected_cashflows(&start, &end, &None);
+    assert_eq!(all.len(), 2);
+    assert_eq!(all.get(0).unwrap().invoice_id, earlier);
+    assert_eq!(all.get(0).unwrap().amount_due, 4_000);
+    assert_eq!(all.get(1).unwrap().invoice_id, later);
+
+    let mine = client.get_expected_cashflows(&start, &end, &Some(investor.clone()));
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine.get(0).unwrap().investor, investor);
+
+    // The window bounds are inclusive and filter by due date
+    let window = client.get_expected_cashflows(&start, &(start + due_in), &None);
+    assert_eq!(window.len(), 1);
+    assert_eq!(window.get(0).unwrap().invoice_id, earlier);
+}
+
+// ========================================
+// Test Fixture Tests
+// ========================================
+
+#[test]
+fn test_scenario_builder_produces_funded_invoices() {
+    let scenario = crate::testutils::Scenario::new();
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+
+    let invoice = scenario.client.get_invoice(&fixture.invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(fixture.investor.clone()));
+    assert_eq!(
+        scenario.client.get_escrow_status(&fixture.invoice_id),
+        crate::payments::EscrowStatus::Held
+    );
+
+    let invoice_ids = scenario.funded_invoices(3, 1_000);
+    assert_eq!(invoice_ids.len(), 3);
+    let third = scenario.client.get_invoice(&invoice_ids.get(2).unwrap());
+    assert_eq!(third.amount, 3_000);
+    assert_eq!(third.funded_amount, 2_700);
+}
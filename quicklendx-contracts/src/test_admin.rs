@@ -5,6 +5,8 @@
 /// 2. Admin Transfer - role transfer and authorization
 /// 3. Authorization - admin-gated operations
 /// 4. Query Functions - get_admin and is_admin correctness
+/// 5. Relayers - registration and relayer-callable automated processes
+/// 6. Invoice freezes - frozen invoices and escrow cannot move until unfrozen
 ///
 /// Target: 95%+ test coverage
 #[cfg(test)]
@@ -182,6 +184,206 @@ mod test_admin {
         // The actual authorization check happens in verify_invoice
     }
 
+    #[test]
+    fn test_high_value_invoice_requires_distinct_signoffs() {
+        let (env, client) = setup();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let verifier = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        let business = Address::generate(&env);
+        let currency = Address::generate(&env);
+        client.initialize_admin(&admin);
+        client.set_invoice_signoff_policy(&Some(crate::verification::InvoiceSignoffPolicy {
+            threshold_amount: 50_000,
+            required_signoffs: 2,
+        }));
+        client.set_invoice_verifier(&verifier, &true);
+
+        let store = |amount: i128| {
+            client.store_invoice(
+                &business,
+                &amount,
+                &currency,
+                &(env.ledger().timestamp() + 86400),
+                &String::from_str(&env, "Test Invoice"),
+                &crate::invoice::InvoiceCategory::Services,
+                &Vec::new(&env),
+            )
+        };
+
+        // Below the threshold a single sign-off verifies
+        let small = store(10_000);
+        assert!(client.sign_off_invoice(&verifier, &small));
+        assert_eq!(
+            client.get_invoice(&small).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+
+        // The admin's verification is one sign-off; the invoice stays pending
+        let large = store(50_000);
+        client.verify_invoice(&large);
+        assert_eq!(
+            client.get_invoice(&large).status,
+            crate::invoice::InvoiceStatus::Pending
+        );
+        assert_eq!(
+            client.try_verify_invoice(&large),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+        assert_eq!(
+            client.try_sign_off_invoice(&outsider, &large),
+            Err(Ok(crate::errors::QuickLendXError::Unauthorized))
+        );
+
+        assert!(client.sign_off_invoice(&verifier, &large));
+        assert_eq!(
+            client.get_invoice(&large).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+        let checklist = client.get_verification_checklist(&large).unwrap();
+        assert_eq!(checklist.signoffs.len(), 2);
+        assert!(checklist.completed);
+    }
+
+    #[test]
+    fn test_verification_cooling_off_and_emergency_override() {
+        let (env, client) = setup();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let verifier = Address::generate(&env);
+        let business = Address::generate(&env);
+        let currency = Address::generate(&env);
+        client.initialize_admin(&admin);
+        assert_eq!(
+            client.try_set_verification_cooling_off(
+                &(crate::verification::MAX_VERIFICATION_COOLING_OFF + 1)
+            ),
+            Err(Ok(crate::errors::QuickLendXError::InvalidTimestamp))
+        );
+        client.set_verification_cooling_off(&3_600);
+        assert_eq!(client.get_verification_cooling_off(), 3_600);
+
+        let store = || {
+            client.store_invoice(
+                &business,
+                &10_000,
+                &currency,
+                &(env.ledger().timestamp() + 86400),
+                &String::from_str(&env, "Test Invoice"),
+                &crate::invoice::InvoiceCategory::Services,
+                &Vec::new(&env),
+            )
+        };
+
+        // No sign-off inside the window
+        let invoice_id = store();
+        assert_eq!(
+            client.try_verify_invoice(&invoice_id),
+            Err(Ok(crate::errors::QuickLendXError::InvalidTimestamp))
+        );
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3_600);
+        client.verify_invoice(&invoice_id);
+        assert_eq!(
+            client.get_invoice(&invoice_id).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+
+        // The override needs a registered verifier other than the admin
+        let urgent = store();
+        assert_eq!(
+            client.try_emergency_verify_invoice(&urgent, &verifier),
+            Err(Ok(crate::errors::QuickLendXError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_emergency_verify_invoice(&urgent, &admin),
+            Err(Ok(crate::errors::QuickLendXError::Unauthorized))
+        );
+        client.set_invoice_verifier(&verifier, &true);
+        assert!(client.emergency_verify_invoice(&urgent, &verifier));
+        assert_eq!(
+            client.get_invoice(&urgent).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+    }
+
+    #[test]
+    fn test_batch_verification_skips_ineligible_invoices() {
+        use crate::verification::{InvoiceVerificationOutcome, MAX_BATCH_VERIFICATION};
+
+        let (env, client) = setup();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let business = Address::generate(&env);
+        let currency = Address::generate(&env);
+        client.initialize_admin(&admin);
+        client.set_invoice_signoff_policy(&Some(crate::verification::InvoiceSignoffPolicy {
+            threshold_amount: 50_000,
+            required_signoffs: 2,
+        }));
+
+        let store = |amount: i128| {
+            client.store_invoice(
+                &business,
+                &amount,
+                &currency,
+                &(env.ledger().timestamp() + 86400),
+                &String::from_str(&env, "Test Invoice"),
+                &crate::invoice::InvoiceCategory::Services,
+                &Vec::new(&env),
+            )
+        };
+        let first = store(1_000);
+        let second = store(2_000);
+        let already_verified = store(3_000);
+        let large = store(60_000);
+        let missing = soroban_sdk::BytesN::from_array(&env, &[9u8; 32]);
+        client.verify_invoice(&already_verified);
+
+        let mut ids = Vec::new(&env);
+        for id in [&first, &already_verified, &missing, &large, &second, &large] {
+            ids.push_back(id.clone());
+        }
+        let batch = client.verify_invoices(&ids);
+        assert_eq!(batch.verified, 2);
+        assert_eq!(batch.awaiting_signoffs, 1);
+        assert_eq!(batch.skipped, 3);
+
+        let expected = [
+            InvoiceVerificationOutcome::Verified,
+            InvoiceVerificationOutcome::WrongStatus,
+            InvoiceVerificationOutcome::NotFound,
+            InvoiceVerificationOutcome::AwaitingSignoffs,
+            InvoiceVerificationOutcome::Verified,
+            InvoiceVerificationOutcome::AlreadySignedOff,
+        ];
+        assert_eq!(batch.results.len(), expected.len() as u32);
+        for (result, outcome) in batch.results.iter().zip(expected) {
+            assert_eq!(result.outcome, outcome);
+        }
+        assert_eq!(batch.results.get(0).unwrap().invoice_id, first);
+        assert_eq!(
+            client.get_invoice(&second).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+        assert_eq!(
+            client.get_invoice(&large).status,
+            crate::invoice::InvoiceStatus::Pending
+        );
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_BATCH_VERIFICATION {
+            too_many.push_back(first.clone());
+        }
+        assert_eq!(
+            client.try_verify_invoices(&too_many),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+    }
+
     // ============================================================================
     // Category 4: Authorization Tests - Fee Configuration
     // ============================================================================
@@ -300,4 +502,183 @@ mod test_admin {
         let result = client.try_set_platform_fee(&200);
         assert!(result.is_err(), "Fee configuration must fail without admin");
     }
+
+    // ============================================================================
+    // Category 5: Relayer Tests
+    // ============================================================================
+
+    #[test]
+    fn test_relayer_can_run_metrics_snapshots() {
+        let (env, client) = setup();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        client.initialize_admin(&admin);
+        let relayer = Address::generate(&env);
+
+        client.add_relayer(&admin, &relayer);
+        assert_eq!(client.get_relayers().len(), 1);
+
+        assert!(client.try_update_platform_metrics(&relayer).is_ok());
+        assert!(client.try_update_performance_metrics(&relayer).is_ok());
+        assert!(client
+            .try_update_investor_performance_data(&relayer)
+            .is_ok());
+        assert!(client.try_update_platform_metrics(&admin).is_ok());
+    }
+
+    #[test]
+    fn test_non_relayer_cannot_run_metrics_snapshots() {
+        let (env, client) = setup();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        client.initialize_admin(&admin);
+        let relayer = Address::generate(&env);
+        client.add_relayer(&admin, &relayer);
+
+        let stranger = Address::generate(&env);
+        let result = client.try_update_platform_metrics(&stranger);
+        assert_eq!(
+            result,
+            Err(Ok(crate::errors::QuickLendXError::Unauthorized))
+        );
+
+        client.remove_relayer(&admin, &relayer);
+        assert_eq!(client.get_relayers().len(), 0);
+        let result = client.try_update_performance_metrics(&relayer);
+        assert_eq!(
+            result,
+            Err(Ok(crate::errors::QuickLendXError::Unauthorized))
+        );
+    }
+
+    #[test]
+    fn test_relayer_management_requires_admin() {
+        let (env, client) = setup();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        client.initialize_admin(&admin);
+        let relayer = Address::generate(&env);
+
+        let result = client.try_add_relayer(&relayer, &relayer);
+        assert_eq!(result, Err(Ok(crate::errors::QuickLendXError::NotAdmin)));
+
+        let result = client.try_remove_relayer(&admin, &relayer);
+        assert_eq!(
+            result,
+            Err(Ok(crate::errors::QuickLendXError::StorageKeyNotFound))
+        );
+    }
+
+    // ============================================================================
+    // Category 7: Invoice Freezes
+    // ============================================================================
+
+    #[test]
+    fn test_admin_freeze_blocks_invoice_until_lifted() {
+        let scenario = crate::testutils::Scenario::new();
+        let env = &scenario.env;
+        let client = &scenario.client;
+        let fixture = scenario.funded_invoice(10_000, 9_000);
+        let invoice_id = &fixture.invoice_id;
+        let reason = String::from_str(env, "Suspected duplicate financing");
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + 60);
+        client.freeze_invoice(invoice_id, &reason);
+        let freeze = client.get_invoice_freeze(invoice_id).unwrap();
+        assert_eq!(freeze.reason, reason);
+        assert_eq!(freeze.frozen_by, scenario.admin);
+        assert_eq!(
+            client.try_freeze_invoice(invoice_id, &reason),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+
+        for party in [&fixture.business, &fixture.investor] {
+            let notifications = client.get_user_notifications(party);
+            let latest = client
+                .get_notification(&notifications.get(notifications.len() - 1).unwrap())
+                .unwrap();
+            assert_eq!(latest.title, String::from_str(env, "Invoice Frozen"));
+        }
+        let trail = client.get_invoice_audit_trail(invoice_id);
+        let entry = client.get_audit_entry(&trail.get(trail.len() - 1).unwrap());
+        assert_eq!(entry.operation, crate::audit::AuditOperation::InvoiceFrozen);
+        assert_eq!(entry.additional_data, Some(reason));
+
+        // Escrow is still held, but neither it nor the invoice can move
+        assert_eq!(
+            client.get_escrow_status(invoice_id),
+            crate::payments::EscrowStatus::Held
+        );
+        assert_eq!(
+            client.try_release_escrow_funds(invoice_id),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+        assert_eq!(
+            client.try_refund_escrow_funds(invoice_id, &fixture.business),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+        assert_eq!(
+            client.try_settle_invoice(invoice_id, &10_000),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+        assert_eq!(
+            client.try_add_invoice_tag(invoice_id, &String::from_str(env, "urgent")),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+
+        // Overdue checks skip the frozen invoice instead of defaulting it
+        let invoice = client.get_invoice(invoice_id);
+        env.ledger()
+            .set_timestamp(invoice.due_date + crate::invoice::Invoice::DEFAULT_GRACE_PERIOD + 1);
+        client.check_overdue_invoices();
+        assert_eq!(
+            client.get_invoice(invoice_id).status,
+            crate::invoice::InvoiceStatus::Funded
+        );
+
+        client.unfreeze_invoice(invoice_id);
+        assert_eq!(client.get_invoice_freeze(invoice_id), None);
+        assert_eq!(
+            client.try_unfreeze_invoice(invoice_id),
+            Err(Ok(crate::errors::QuickLendXError::StorageKeyNotFound))
+        );
+        client.refund_escrow_funds(invoice_id, &fixture.business);
+        assert_eq!(
+            client.get_escrow_status(invoice_id),
+            crate::payments::EscrowStatus::Refunded
+        );
+    }
+
+    #[test]
+    fn test_admin_freeze_blocks_bid_acceptance() {
+        let scenario = crate::testutils::Scenario::new();
+        let env = &scenario.env;
+        let client = &scenario.client;
+        let business = scenario.verified_business();
+        let investor = scenario.verified_investor();
+        let currency = scenario.create_token(&[&business, &investor]);
+        let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+        let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+
+        client.freeze_invoice(&invoice_id, &String::from_str(env, "Disputed receivable"));
+        let token = soroban_sdk::token::Client::new(env, &currency);
+        let investor_balance = token.balance(&investor);
+        assert_eq!(
+            client.try_accept_bid(&invoice_id, &bid_id),
+            Err(Ok(crate::errors::QuickLendXError::OperationNotAllowed))
+        );
+        assert_eq!(token.balance(&investor), investor_balance);
+        assert_eq!(
+            client.get_invoice(&invoice_id).status,
+            crate::invoice::InvoiceStatus::Verified
+        );
+        assert_eq!(
+            client.get_bid(&bid_id).unwrap().status,
+            crate::bid::BidStatus::Placed
+        );
+
+        client.unfreeze_invoice(&invoice_id);
+        client.accept_bid(&invoice_id, &bid_id);
+        assert_eq!(token.balance(&investor), investor_balance - 9_000);
+    }
 }
@@ -6,12 +6,18 @@
 /// 2. Withdrawal - authorize only bid owner can withdraw
 /// 3. Indexing - multiple bids properly indexed and queryable
 /// 4. Ranking - profit-based bid comparison works correctly
+/// 5. Investment limits - admin-set limits enforced when bidding
+/// 6. Buy-it-now - bids at the instant funding price fund immediately
+/// 7. Counter-offers - accepting or rejecting a business counter
+/// 8. Bid bonds - locked at placement, returned to losing or withdrawn bids
 use super::*;
-use crate::bid::BidStatus;
-use crate::invoice::InvoiceCategory;
+use crate::bid::{BidIncrementRule, BidStatus};
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::payments::EscrowStatus;
+use crate::testutils::DEFAULT_TOKEN_BALANCE;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, BytesN, Env, String, Vec,
+    token, Address, BytesN, Env, String, Vec,
 };
 
 // Helper: Setup contract with admin
@@ -55,6 +61,30 @@ fn create_verified_invoice(
     invoice_id
 }
 
+// Helper: Create verified buy-it-now invoice
+fn create_buy_now_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    amount: i128,
+    instant_funding_price: i128,
+    currency: &Address,
+) -> BytesN<32> {
+    let due_date = env.ledger().timestamp() + 86400;
+    let invoice_id = client.upload_invoice_with_buy_now(
+        business,
+        &amount,
+        currency,
+        &due_date,
+        &String::from_str(env, "Buy-it-now Invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(env),
+        &instant_funding_price,
+    );
+    client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
 // ============================================================================
 // Category 1: Status Gating - Invoice Verification Required
 // ============================================================================
@@ -388,6 +418,250 @@ fn test_bid_expiration_and_cleanup() {
         "Bid must be marked expired"
     );
 }
+
+/// Test: Investor-chosen expiration must fall within the admin TTL bounds
+#[test]
+fn test_place_bid_with_expiration_respects_bounds() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let investor = add_verified_investor(&env, &client, 100_000);
+    let business = Address::generate(&env);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+
+    client.set_bid_ttl_bounds(&3_600, &172_800);
+    let now = env.ledger().timestamp();
+
+    let too_soon =
+        client.try_place_bid_with_expiration(&investor, &invoice_id, &5_000, &6_000, &(now + 60));
+    assert_eq!(too_soon, Err(Ok(QuickLendXError::InvalidTimestamp)));
+    let too_late = client.try_place_bid_with_expiration(
+        &investor,
+        &invoice_id,
+        &5_000,
+        &6_000,
+        &(now + 172_801),
+    );
+    assert_eq!(too_late, Err(Ok(QuickLendXError::InvalidTimestamp)));
+
+    let bid_id =
+        client.place_bid_with_expiration(&investor, &invoice_id, &5_000, &6_000, &(now + 7_200));
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.expiration_timestamp, now + 7_200);
+
+    env.ledger().set_timestamp(now + 7_201);
+    assert_eq!(client.cleanup_expired_bids(&invoice_id), 1);
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Expired);
+}
+
+/// Test: Invalid TTL bounds are rejected
+#[test]
+fn test_set_bid_ttl_bounds_validation() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+
+    assert_eq!(
+        client.try_set_bid_ttl_bounds(&0, &3_600),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_set_bid_ttl_bounds(&7_200, &3_600),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    client.set_bid_ttl_bounds(&60, &3_600);
+    let bounds = client.get_bid_ttl_bounds();
+    assert_eq!(bounds.min_ttl, 60);
+    assert_eq!(bounds.max_ttl, 3_600);
+}
+
+/// Test: Bid owner can extend an open bid, capped at the maximum TTL
+#[test]
+fn test_extend_bid_expiration() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let investor = add_verified_investor(&env, &client, 100_000);
+    let business = Address::generate(&env);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+
+    let now = env.ledger().timestamp();
+    let bid_id = client.place_bid(&investor, &invoice_id, &5_000, &6_000);
+
+    // 7 days default + 7 days extension, within the 30 day default maximum
+    let extended = client.extend_bid_expiration(&bid_id);
+    assert_eq!(extended, now + 2 * 604_800);
+    assert_eq!(
+        client.get_bid(&bid_id).unwrap().expiration_timestamp,
+        extended
+    );
+
+    // Further extensions are capped at now + 30 days
+    client.extend_bid_expiration(&bid_id);
+    client.extend_bid_expiration(&bid_id);
+    client.extend_bid_expiration(&bid_id);
+    let capped = client.get_bid(&bid_id).unwrap().expiration_timestamp;
+    assert_eq!(capped, now + 30 * 86_400);
+    assert_eq!(
+        client.try_extend_bid_expiration(&bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Expired bids cannot be extended
+    env.ledger().set_timestamp(capped + 1);
+    assert_eq!(
+        client.try_extend_bid_expiration(&bid_id),
+        Err(Ok(QuickLendXError::BidExpired))
+    );
+}
+
+/// Test: Absolute bid increment is enforced against the highest open bid
+#[test]
+fn test_bid_increment_absolute_rule() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let business = Address::generate(&env);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let investor1 = add_verified_investor(&env, &client, 100_000);
+    let investor2 = add_verified_investor(&env, &client, 100_000);
+    let investor3 = add_verified_investor(&env, &client, 100_000);
+
+    client.set_bid_increment_rule(&Some(BidIncrementRule::Absolute(100)));
+    assert_eq!(client.get_min_next_bid(&invoice_id), 100);
+
+    client.place_bid(&investor1, &invoice_id, &5_000, &6_000);
+    assert_eq!(client.get_min_next_bid(&invoice_id), 5_100);
+
+    let result = client.try_place_bid(&investor2, &invoice_id, &5_001, &6_000);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+    client.place_bid(&investor2, &invoice_id, &5_100, &6_000);
+
+    // Clearing the rule allows bids below the highest open bid again
+    client.set_bid_increment_rule(&None);
+    client.place_bid(&investor3, &invoice_id, &4_000, &5_000);
+}
+
+/// Test: Bps bid increment rounds up and rejects invalid rules
+#[test]
+fn test_bid_increment_bps_rule() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let business = Address::generate(&env);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let investor = add_verified_investor(&env, &client, 100_000);
+
+    assert_eq!(
+        client.try_set_bid_increment_rule(&Some(BidIncrementRule::Bps(0))),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_bid_increment_rule(&Some(BidIncrementRule::Absolute(0))),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+
+    client.set_bid_increment_rule(&Some(BidIncrementRule::Bps(250)));
+    client.place_bid(&investor, &invoice_id, &5_001, &6_000);
+    // 2.5% of 5,001 is 125.025, rounded up to 126
+    assert_eq!(client.get_min_next_bid(&invoice_id), 5_127);
+    assert_eq!(
+        client.get_bid_increment_rule(),
+        Some(BidIncrementRule::Bps(250))
+    );
+}
+
+/// Test: Discount-rate bids derive amounts, store the rate and rank by effective APR
+#[test]
+fn test_discount_rate_bid_and_apr_ranking() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let business = Address::generate(&env);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let investor1 = add_verified_investor(&env, &client, 100_000);
+    let investor2 = add_verified_investor(&env, &client, 100_000);
+
+    assert_eq!(
+        client.try_place_bid_at_discount(&investor1, &invoice_id, &10_000),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let discount_bid = client.place_bid_at_discount(&investor1, &invoice_id, &200);
+    let bid = client.get_bid(&discount_bid).unwrap();
+    assert_eq!(bid.bid_amount, 9_800);
+    assert_eq!(bid.expected_return, 10_000);
+    assert_eq!(bid.discount_rate_bps, Some(200));
+
+    // Higher absolute profit, but a costlier annualized rate for the business
+    let raw_bid = client.place_bid(&investor2, &invoice_id, &9_000, &9_500);
+    assert_eq!(client.get_bid(&raw_bid).unwrap().discount_rate_bps, None);
+
+    // One-day term: 200 / 9,800 and 500 / 9,000 annualized
+    assert_eq!(client.get_bid_effective_apr(&discount_bid), 74_490);
+    assert_eq!(client.get_bid_effective_apr(&raw_bid), 202_778);
+
+    let ranked = client.get_bids_ranked_by_apr(&invoice_id);
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(ranked.get(0).unwrap().bid_id, discount_bid);
+    assert_eq!(ranked.get(1).unwrap().bid_id, raw_bid);
+}
+
+/// Test: Per-invoice, platform-wide and rate limits on bid placement
+#[test]
+fn test_bid_limits_enforced_in_place_bid() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let _ = client.set_admin(&admin);
+    let business = Address::generate(&env);
+    let investor = add_verified_investor(&env, &client, 100_000);
+    let invoice_a = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let invoice_b = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let invoice_c = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+
+    // Defaults keep one open bid per invoice
+    assert_eq!(client.get_bid_limits().max_active_bids_per_invoice, 1);
+    client.place_bid(&investor, &invoice_a, &1_000, &1_100);
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_a, &2_000, &2_200),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Two open bids per invoice, but only two across the platform
+    client.set_bid_limits(&2, &2, &0, &0);
+    client.place_bid(&investor, &invoice_a, &2_000, &2_200);
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_b, &1_000, &1_100),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Rate limit: at most three bids per hour, counting withdrawn bids
+    client.set_bid_limits(&2, &0, &3, &3_600);
+    let bid_id = client.place_bid(&investor, &invoice_b, &1_000, &1_100);
+    client.withdraw_bid(&bid_id);
+    assert_eq!(
+        client.try_place_bid(&investor, &invoice_c, &1_000, &1_100),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    env.ledger().set_timestamp(env.ledger().timestamp() + 3_601);
+    client.place_bid(&investor, &invoice_c, &1_000, &1_100);
+
+    assert_eq!(
+        client.try_set_bid_limits(&0, &0, &0, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_set_bid_limits(&1, &0, &3, &0),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+}
+
 // ============================================================================
 // Category 5: Investment Limit Management
 // ============================================================================
@@ -500,3 +774,300 @@ fn test_updated_limit_enforced_in_bidding() {
     let result = client.try_place_bid(&investor, &invoice_id, &15_000, &16_000);
     assert!(result.is_ok(), "Bid should succeed after limit increase");
 }
+
+#[test]
+fn test_invoice_bid_analytics_track_bids_as_they_arrive() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investors = [
+        scenario.verified_investor(),
+        scenario.verified_investor(),
+        scenario.verified_investor(),
+    ];
+    let currency = scenario.create_token(&[&investors[0], &investors[1], &investors[2]]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+
+    let empty = client.get_invoice_bid_analytics(&invoice_id);
+    assert_eq!(empty.bid_count, 0);
+    assert_eq!(empty.best_open_discount_bps, None);
+    assert_eq!(empty.time_to_first_bid, None);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 600);
+    let mut bid_ids = Vec::new(env);
+    for (investor, bid_amount) in investors.iter().zip([9_000i128, 9_500, 9_200]) {
+        bid_ids.push_back(client.place_bid(investor, &invoice_id, &bid_amount, &10_000));
+    }
+
+    let analytics = client.get_invoice_bid_analytics(&invoice_id);
+    assert_eq!(analytics.bid_count, 3);
+    assert_eq!(analytics.min_discount_bps, 500);
+    assert_eq!(analytics.median_discount_bps, 800);
+    assert_eq!(analytics.best_open_discount_bps, Some(500));
+    assert_eq!(analytics.time_to_first_bid, Some(600));
+    let tier = client
+        .get_investor_verification(&investors[0])
+        .unwrap()
+        .tier;
+    assert_eq!(analytics.tier_distribution.len(), 1);
+    assert_eq!(analytics.tier_distribution.get(0).unwrap().tier, tier);
+    assert_eq!(analytics.tier_distribution.get(0).unwrap().bids, 3);
+
+    // Withdrawn bids still count towards the history, but not the best open discount
+    client.withdraw_bid(&bid_ids.get(1).unwrap());
+    let analytics = client.get_invoice_bid_analytics(&invoice_id);
+    assert_eq!(analytics.bid_count, 3);
+    assert_eq!(analytics.min_discount_bps, 500);
+    assert_eq!(analytics.best_open_discount_bps, Some(800));
+
+    assert_eq!(
+        client.try_get_invoice_bid_analytics(&BytesN::from_array(env, &[9; 32])),
+        Err(Ok(QuickLendXError::InvoiceNotFound))
+    );
+}
+
+// ============================================================================
+// Category 6: Buy-it-now - Instant Funding
+// ============================================================================
+
+#[test]
+fn test_buy_now_bid_funds_immediately() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let token_client = token::Client::new(env, &currency);
+
+    let invoice_id = create_buy_now_invoice(env, client, &business, 10_000, 9_500, &currency);
+    assert_eq!(
+        client.get_invoice(&invoice_id).instant_funding_price,
+        Some(9_500)
+    );
+
+    let investor_before = token_client.balance(&investor);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_500, &10_500);
+
+    // Funded in the same call, without accept_bid
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(investor.clone()));
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Accepted);
+    assert_eq!(client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+    assert_eq!(investor_before - token_client.balance(&investor), 9_500);
+}
+
+#[test]
+fn test_buy_now_bid_below_price_waits_for_acceptance() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    let invoice_id = create_buy_now_invoice(env, client, &business, 10_000, 9_500, &currency);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_500);
+
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Placed);
+
+    // Regular acceptance still works
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+}
+
+#[test]
+fn test_buy_now_price_validation() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    let due_date = env.ledger().timestamp() + 86400;
+
+    for price in [0i128, 10_001] {
+        let result = client.try_upload_invoice_with_buy_now(
+            &business,
+            &10_000,
+            &currency,
+            &due_date,
+            &String::from_str(env, "Invalid buy-it-now"),
+            &InvoiceCategory::Services,
+            &Vec::new(env),
+            &price,
+        );
+        assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+    }
+
+    // Invoices uploaded without opting in have no instant funding price
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    assert_eq!(client.get_invoice(&invoice_id).instant_funding_price, None);
+}
+
+// ============================================================================
+// Category 7: Counter-Offers - Business Proposes New Terms
+// ============================================================================
+
+#[test]
+fn test_accept_counter_funds_on_countered_terms() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let token_client = token::Client::new(env, &currency);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+
+    client.counter_bid(&invoice_id, &bid_id, &9_000, &9_800);
+    assert_eq!(
+        client.get_bid(&bid_id).unwrap().status,
+        BidStatus::Countered
+    );
+    let counter = client.get_counter_offer(&bid_id).unwrap();
+    assert_eq!(counter.proposed_amount, 9_000);
+
+    // A countered bid cannot be accepted on its original terms
+    let result = client.try_accept_bid(&invoice_id, &bid_id);
+    assert_eq!(result, Err(Ok(QuickLendXError::BidNotPlaced)));
+
+    let investor_before = token_client.balance(&investor);
+    client.accept_counter(&bid_id);
+
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.status, BidStatus::Accepted);
+    assert_eq!(bid.bid_amount, 9_000);
+    assert_eq!(bid.expected_return, 9_800);
+    assert_eq!(token_client.balance(&investor), investor_before - 9_000);
+    assert_eq!(client.get_escrow_details(&invoice_id).amount, 9_000);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+    assert!(client.get_counter_offer(&bid_id).is_none());
+}
+
+#[test]
+fn test_reject_counter_restores_original_bid() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &9_500);
+
+    // Invalid counter terms
+    let result = client.try_counter_bid(&invoice_id, &bid_id, &9_000, &9_000);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+    let result = client.try_counter_bid(&invoice_id, &bid_id, &10_001, &11_000);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+
+    client.counter_bid(&invoice_id, &bid_id, &9_000, &9_800);
+    let result = client.try_counter_bid(&invoice_id, &bid_id, &9_100, &9_800);
+    assert_eq!(result, Err(Ok(QuickLendXError::BidNotPlaced)));
+
+    client.reject_counter(&bid_id);
+    let bid = client.get_bid(&bid_id).unwrap();
+    assert_eq!(bid.status, BidStatus::Placed);
+    assert_eq!(bid.bid_amount, 8_000);
+    assert!(client.get_counter_offer(&bid_id).is_none());
+
+    let result = client.try_accept_counter(&bid_id);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidStatus)));
+
+    // The original bid can still be accepted
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(client.get_escrow_details(&invoice_id).amount, 8_000);
+}
+
+// ============================================================================
+// Category 8: Bid Bonds - Locked at Placement, Returned or Applied
+// ============================================================================
+
+#[test]
+fn test_bid_bond_applied_on_acceptance_and_returned_to_losing_bid() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let contract_id = client.address.clone();
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let other_investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor, &other_investor]);
+    let token_client = token::Client::new(env, &currency);
+
+    assert_eq!(
+        client.try_set_bid_bond_bps(&1_001),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    client.set_bid_bond_bps(&500);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    let losing_bid_id = client.place_bid(&other_investor, &invoice_id, &8_000, &9_000);
+
+    // 5% of each bid is locked in the contract
+    assert_eq!(client.get_bid_bond(&bid_id).unwrap().amount, 450);
+    assert_eq!(token_client.balance(&investor), DEFAULT_TOKEN_BALANCE - 450);
+    assert_eq!(
+        token_client.balance(&other_investor),
+        DEFAULT_TOKEN_BALANCE - 400
+    );
+    assert_eq!(token_client.balance(&contract_id), 850);
+
+    // The winning bond counts toward escrow; the losing bond goes back
+    client.accept_bid_and_fund(&invoice_id, &bid_id);
+    assert_eq!(
+        token_client.balance(&investor),
+        DEFAULT_TOKEN_BALANCE - 9_000
+    );
+    assert_eq!(token_client.balance(&other_investor), DEFAULT_TOKEN_BALANCE);
+    assert_eq!(token_client.balance(&contract_id), 9_000);
+    assert_eq!(client.get_escrow_details(&invoice_id).amount, 9_000);
+    assert!(client.get_bid_bond(&bid_id).is_none());
+    assert!(client.get_bid_bond(&losing_bid_id).is_none());
+}
+
+#[test]
+fn test_bid_bond_returned_on_withdrawal_and_expiry() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let token_client = token::Client::new(env, &currency);
+    client.set_bid_bond_bps(&100);
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &first, &5_000, &6_000);
+    assert_eq!(token_client.balance(&investor), DEFAULT_TOKEN_BALANCE - 50);
+    client.withdraw_bid(&bid_id);
+    assert_eq!(token_client.balance(&investor), DEFAULT_TOKEN_BALANCE);
+    assert!(client.get_bid_bond(&bid_id).is_none());
+
+    let second = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &second, &5_000, &6_000);
+    assert_eq!(
+        client.try_reclaim_bid_bond(&bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    env.ledger()
+        .set_timestamp(client.get_bid(&bid_id).unwrap().expiration_timestamp + 1);
+    assert_eq!(client.cleanup_expired_bids(&second), 1);
+    assert_eq!(token_client.balance(&investor), DEFAULT_TOKEN_BALANCE);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
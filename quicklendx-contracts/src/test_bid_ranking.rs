@@ -241,6 +241,7 @@ fn test_compare_bids_by_profit() {
         timestamp: 100,
         status: BidStatus::Placed,
         expiration_timestamp: 1000,
+        discount_rate_bps: None,
     };
     let low_profit = Bid {
         bid_amount: 1000,
@@ -275,6 +276,7 @@ fn test_compare_bids_by_expected_return_when_profit_equal() {
         timestamp: 100,
         status: BidStatus::Placed,
         expiration_timestamp: 1000,
+        discount_rate_bps: None,
     };
     let b = Bid {
         bid_amount: 1200,
@@ -303,6 +305,7 @@ fn test_compare_bids_tie_break_timestamp() {
         timestamp: 100,
         status: BidStatus::Placed,
         expiration_timestamp: 1000,
+        discount_rate_bps: None,
     };
     let later = Bid {
         timestamp: 200,
@@ -334,6 +337,7 @@ fn test_compare_bids_equal() {
         timestamp: 100,
         status: BidStatus::Placed,
         expiration_timestamp: 1000,
+        discount_rate_bps: None,
     };
     assert_eq!(BidStorage::compare_bids(&bid, &bid), Ordering::Equal);
 }
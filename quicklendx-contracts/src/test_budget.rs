@@ -0,0 +1,88 @@
+//! Cost regression tests for the hot entrypoints; budgets are recorded in `bench.rs`.
+//!
+//! Each test builds a representative state, invokes the entrypoint once and checks the
+//! metered cost of that invocation against its budget.
+use crate::bench::{self, assert_within_budget};
+use crate::testutils::Scenario;
+use soroban_sdk::{Address, BytesN, Vec};
+
+/// Bids already on the invoice when the measured call runs
+const OPEN_BIDS: u32 = 5;
+/// Verified invoices listed when measuring marketplace queries
+const LISTED_INVOICES: u32 = 10;
+
+/// A verified invoice of 10,000 with `bids` open bids from distinct investors
+fn invoice_with_bids(scenario: &Scenario, bids: u32) -> (Address, BytesN<32>, Vec<BytesN<32>>) {
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let mut bid_ids = Vec::new(&scenario.env);
+    for i in 0..bids {
+        let investor = scenario.verified_investor();
+        scenario.fund(&currency, &investor);
+        let bid_amount = 8_000 + i as i128 * 100;
+        bid_ids.push_back(
+            scenario
+                .client
+                .place_bid(&investor, &invoice_id, &bid_amount, &10_000),
+        );
+    }
+    (currency, invoice_id, bid_ids)
+}
+
+#[test]
+fn test_place_bid_within_budget() {
+    let scenario = Scenario::new();
+    let (currency, invoice_id, _) = invoice_with_bids(&scenario, OPEN_BIDS - 1);
+    let investor = scenario.verified_investor();
+    scenario.fund(&currency, &investor);
+
+    scenario
+        .client
+        .place_bid(&investor, &invoice_id, &9_000, &10_000);
+    assert_within_budget(&scenario.env, &bench::PLACE_BID);
+}
+
+#[test]
+fn test_accept_bid_and_fund_within_budget() {
+    let scenario = Scenario::new();
+    let (_, invoice_id, bid_ids) = invoice_with_bids(&scenario, OPEN_BIDS);
+
+    scenario
+        .client
+        .accept_bid_and_fund(&invoice_id, &bid_ids.get(OPEN_BIDS - 1).unwrap());
+    assert_within_budget(&scenario.env, &bench::ACCEPT_BID_AND_FUND);
+}
+
+#[test]
+fn test_settle_invoice_within_budget() {
+    let scenario = Scenario::new();
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+
+    scenario
+        .client
+        .settle_invoice(&fixture.invoice_id, &fixture.amount);
+    assert_within_budget(&scenario.env, &bench::SETTLE_INVOICE);
+}
+
+#[test]
+fn test_marketplace_queries_within_budget() {
+    let scenario = Scenario::new();
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    for i in 1..=LISTED_INVOICES {
+        scenario.verified_invoice(&business, &currency, 1_000 * i as i128);
+    }
+    let (_, invoice_id, _) = invoice_with_bids(&scenario, OPEN_BIDS);
+
+    let page =
+        scenario
+            .client
+            .get_available_invoices_paged(&None, &None, &None, &0, &LISTED_INVOICES);
+    assert_eq!(page.len(), LISTED_INVOICES);
+    assert_within_budget(&scenario.env, &bench::GET_AVAILABLE_INVOICES_PAGED);
+
+    let ranked = scenario.client.get_ranked_bids(&invoice_id);
+    assert_eq!(ranked.len(), OPEN_BIDS);
+    assert_within_budget(&scenario.env, &bench::GET_RANKED_BIDS);
+}
@@ -1,10 +1,11 @@
 #![cfg(test)]
 extern crate alloc;
 
+use crate::errors::QuickLendXError;
 use crate::invoice::InvoiceCategory;
 use crate::verification::BusinessVerificationStatus;
 use crate::QuickLendXContract;
-use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env, String, Vec};
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, Address, Env, String, Vec};
 
 // The client is auto-generated by the Soroban SDK
 type QuickLendXContractClient<'a> = crate::QuickLendXContractClient<'a>;
@@ -526,3 +527,68 @@ fn test_timestamp_accuracy() {
     assert!(verification.verified_at.is_some());
     assert!(verification.verified_at.unwrap() >= verification_time);
 }
+
+// ============================================================================
+// Payout Address Tests
+// ============================================================================
+
+#[test]
+fn test_payout_address_change_is_timelocked_and_alerts_investors() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let admin = &scenario.admin;
+    let funded = scenario.funded_invoice(10_000, 9_000);
+    let (invoice_id, business, investor) = (&funded.invoice_id, &funded.business, &funded.investor);
+    let token_client = token::Client::new(env, &funded.currency);
+    let new_address = Address::generate(env);
+    assert_eq!(client.get_payout_address(business), *business);
+
+    let investor_alerts = client.get_user_notifications(investor).len();
+    let change = client.request_payout_address_change(business, &new_address);
+    assert_eq!(change.investors_notified, 1);
+    assert_eq!(
+        client.get_user_notifications(investor).len(),
+        investor_alerts + 1
+    );
+    assert_eq!(
+        client.get_pending_payout_change(business),
+        Some(change.clone())
+    );
+
+    // Too early: the current payout address stays in effect
+    assert_eq!(
+        client.try_confirm_payout_address_change(business),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(client.get_payout_address(business), *business);
+
+    // Only the business or the admin can cancel
+    let stranger = Address::generate(env);
+    assert_eq!(
+        client.try_cancel_payout_address_change(&stranger, business),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    client.cancel_payout_address_change(admin, business);
+    assert_eq!(client.get_pending_payout_change(business), None);
+    assert_eq!(
+        client.try_confirm_payout_address_change(business),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    // A fresh request restarts the timelock
+    let change = client.request_payout_address_change(business, &new_address);
+    env.ledger().set_timestamp(change.effective_at);
+    assert_eq!(client.confirm_payout_address_change(business), new_address);
+    assert_eq!(client.get_payout_address(business), new_address);
+    assert_eq!(
+        client.try_request_payout_address_change(business, &new_address),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Escrow releases now go to the new payout address
+    let business_before = token_client.balance(business);
+    client.release_escrow_funds(invoice_id);
+    assert_eq!(token_client.balance(&new_address), 9_000);
+    assert_eq!(token_client.balance(business), business_before);
+}
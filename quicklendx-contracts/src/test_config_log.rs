@@ -0,0 +1,114 @@
+use super::*;
+use crate::config_log::{ConfigLog, ConfigParameter, ConfigValue};
+use crate::fees::ParticipantTier;
+use crate::protocol_limits::{ProtocolLimitsContract, ProtocolLimitsContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+fn setup() -> (Env, QuickLendXContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    client.initialize_fee_system(&admin);
+    (env, client, admin)
+}
+
+#[test]
+fn test_fee_changes_are_recorded() {
+    let (env, client, admin) = setup();
+    let treasury = Address::generate(&env);
+
+    client.update_platform_fee_bps(&300);
+    client.configure_treasury(&treasury);
+    client.set_tier_fee_rate(&ParticipantTier::VerifiedBusiness, &150);
+    client.remove_tier_fee_rate(&ParticipantTier::VerifiedBusiness);
+
+    let history = client.get_config_history(&0, &10);
+    assert_eq!(history.len(), 4);
+
+    let fee_change = history.get(0).unwrap();
+    assert_eq!(fee_change.change_id, 0);
+    assert_eq!(fee_change.parameter, ConfigParameter::PlatformFeeBps);
+    assert_eq!(fee_change.old_value, ConfigValue::U32(200));
+    assert_eq!(fee_change.new_value, ConfigValue::U32(300));
+    assert_eq!(fee_change.actor, admin);
+    assert_eq!(fee_change.timestamp, 1_000);
+
+    let treasury_change = history.get(1).unwrap();
+    assert_eq!(treasury_change.parameter, ConfigParameter::TreasuryAddress);
+    assert_eq!(treasury_change.old_value, ConfigValue::Unset);
+    assert_eq!(treasury_change.new_value, ConfigValue::Address(treasury));
+
+    let tier_removed = history.get(3).unwrap();
+    assert_eq!(
+        tier_removed.parameter,
+        ConfigParameter::TierFeeRate(ParticipantTier::VerifiedBusiness)
+    );
+    assert_eq!(tier_removed.old_value, ConfigValue::U32(150));
+    assert_eq!(tier_removed.new_value, ConfigValue::Unset);
+}
+
+#[test]
+fn test_unchanged_values_are_not_recorded() {
+    let (env, client, admin) = setup();
+    let currency = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+
+    client.update_platform_fee_bps(&200);
+    client.add_currency(&admin, &currency);
+    client.add_currency(&admin, &currency);
+    client.set_bid_ttl_bounds(&3_600, &172_800);
+
+    let history = client.get_config_history(&0, &10);
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        history.get(0).unwrap().parameter,
+        ConfigParameter::CurrencyWhitelisted(currency)
+    );
+    // Only the maximum TTL differs from its default
+    let ttl_change = history.get(1).unwrap();
+    assert_eq!(ttl_change.parameter, ConfigParameter::BidMaxTtl);
+    assert_eq!(ttl_change.new_value, ConfigValue::U64(172_800));
+}
+
+#[test]
+fn test_config_history_paging() {
+    let (_env, client, _admin) = setup();
+
+    for fee_bps in 201..206u32 {
+        client.update_platform_fee_bps(&fee_bps);
+    }
+
+    let page = client.get_config_history(&3, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().change_id, 3);
+    assert_eq!(page.get(1).unwrap().new_value, ConfigValue::U32(205));
+    assert_eq!(client.get_config_history(&1, &2).len(), 2);
+    assert_eq!(client.get_config_history(&5, &10).len(), 0);
+}
+
+#[test]
+fn test_protocol_limit_changes_are_recorded() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(ProtocolLimitsContract, ());
+    let client = ProtocolLimitsContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    client.set_protocol_limits(&admin, &1_000_000, &365, &172_800);
+
+    let history = env.as_contract(&contract_id, || ConfigLog::get_history(&env, 0, 10));
+    assert_eq!(history.len(), 1);
+    let change = history.get(0).unwrap();
+    assert_eq!(change.parameter, ConfigParameter::GracePeriodSeconds);
+    assert_eq!(change.old_value, ConfigValue::U64(86_400));
+    assert_eq!(change.new_value, ConfigValue::U64(172_800));
+}
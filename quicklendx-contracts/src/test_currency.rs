@@ -21,10 +21,15 @@ fn setup() -> (Env, QuickLendXContractClient<'static>, Address) {
     (env, client, admin)
 }
 
+fn create_token(env: &Env, admin: &Address) -> Address {
+    env.register_stellar_asset_contract_v2(admin.clone())
+        .address()
+}
+
 #[test]
 fn test_add_remove_currency_admin_only() {
     let (env, client, admin) = setup();
-    let currency = Address::generate(&env);
+    let currency = create_token(&env, &admin);
     client.add_currency(&admin, &currency);
     assert!(client.is_allowed_currency(&currency));
     let list = client.get_whitelisted_currencies();
@@ -40,7 +45,7 @@ fn test_add_remove_currency_admin_only() {
 #[test]
 fn test_non_admin_cannot_add_currency() {
     let (env, client, admin) = setup();
-    let currency = Address::generate(&env);
+    let currency = create_token(&env, &admin);
     client.add_currency(&admin, &currency);
     let non_admin = Address::generate(&env);
     let res = client.try_add_currency(&non_admin, &currency);
@@ -50,7 +55,7 @@ fn test_non_admin_cannot_add_currency() {
 #[test]
 fn test_non_admin_cannot_remove_currency() {
     let (env, client, admin) = setup();
-    let currency = Address::generate(&env);
+    let currency = create_token(&env, &admin);
     client.add_currency(&admin, &currency);
     let non_admin = Address::generate(&env);
     let res = client.try_remove_currency(&non_admin, &currency);
@@ -60,7 +65,7 @@ fn test_non_admin_cannot_remove_currency() {
 #[test]
 fn test_invoice_with_non_whitelisted_currency_fails_when_whitelist_set() {
     let (env, client, admin) = setup();
-    let allowed_currency = Address::generate(&env);
+    let allowed_currency = create_token(&env, &admin);
     client.add_currency(&admin, &allowed_currency);
     let disallowed_currency = Address::generate(&env);
     let business = Address::generate(&env);
@@ -80,7 +85,7 @@ fn test_invoice_with_non_whitelisted_currency_fails_when_whitelist_set() {
 #[test]
 fn test_invoice_with_whitelisted_currency_succeeds() {
     let (env, client, admin) = setup();
-    let currency = Address::generate(&env);
+    let currency = create_token(&env, &admin);
     client.add_currency(&admin, &currency);
     let business = Address::generate(&env);
     let due_date = env.ledger().timestamp() + 86400;
@@ -100,8 +105,8 @@ fn test_invoice_with_whitelisted_currency_succeeds() {
 #[test]
 fn test_bid_on_invoice_with_non_whitelisted_currency_fails_when_whitelist_set() {
     let (env, client, admin) = setup();
-    let currency_a = Address::generate(&env);
-    let currency_b = Address::generate(&env);
+    let currency_a = create_token(&env, &admin);
+    let currency_b = create_token(&env, &admin);
     client.add_currency(&admin, &currency_a);
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
@@ -127,9 +132,27 @@ fn test_bid_on_invoice_with_non_whitelisted_currency_fails_when_whitelist_set()
 #[test]
 fn test_add_currency_idempotent() {
     let (env, client, admin) = setup();
-    let currency = Address::generate(&env);
+    let currency = create_token(&env, &admin);
     client.add_currency(&admin, &currency);
     client.add_currency(&admin, &currency);
     let list = client.get_whitelisted_currencies();
     assert_eq!(list.len(), 1);
 }
+
+#[test]
+fn test_add_currency_rejects_non_token_address() {
+    let (env, client, admin) = setup();
+    let not_a_token = Address::generate(&env);
+    assert_eq!(
+        client.try_add_currency(&admin, &not_a_token),
+        Err(Ok(QuickLendXError::InvalidCurrency))
+    );
+
+    // A deployed contract that is not a token is rejected too
+    let other_contract = env.register(QuickLendXContract, ());
+    assert_eq!(
+        client.try_add_currency(&admin, &other_contract),
+        Err(Ok(QuickLendXError::InvalidCurrency))
+    );
+    assert_eq!(client.get_whitelisted_currencies().len(), 0);
+}
@@ -7,12 +7,14 @@
 /// 3. Unfunded invoices - cannot default unfunded invoices
 /// 4. Admin-only operations - verify authorization
 /// 5. Edge cases - multiple defaults, already defaulted invoices
+/// 6. Default recoveries - insurance claim clawback, collection cases
 use super::*;
+use crate::collections::CollectionStatus;
 use crate::errors::QuickLendXError;
 use crate::invoice::{InvoiceCategory, InvoiceStatus};
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, BytesN, Env, String, Vec,
+    token, Address, BytesN, Env, String, Vec,
 };
 
 // Helper: Setup contract with admin
@@ -437,3 +439,379 @@ fn test_multiple_invoices_default_handling() {
         InvoiceStatus::Funded
     );
 }
+
+// ============================================================================
+// Default probability estimate
+// ============================================================================
+
+#[test]
+fn test_default_probability_set_at_verification_and_recalculated() {
+    let (env, client, admin) = setup();
+    let business = create_verified_business(&env, &client, &admin);
+    let currency = Address::generate(&env);
+    let due_date = env.ledger().timestamp() + 86_400;
+    let upload = |amount: i128| {
+        client.store_invoice(
+            &business,
+            &amount,
+            &currency,
+            &due_date,
+            &String::from_str(&env, "Invoice"),
+            &InvoiceCategory::Services,
+            &Vec::new(&env),
+        )
+    };
+
+    let invoice_id = upload(1_000);
+    assert_eq!(
+        client.get_invoice(&invoice_id).default_probability_bps,
+        None
+    );
+    client.verify_invoice(&invoice_id);
+    // No history: 5% prior, +25% for a first invoice of this size, scaled for a one-day term
+    assert_eq!(
+        client.get_category_loss_rate(&InvoiceCategory::Services),
+        500
+    );
+    assert_eq!(
+        client.get_invoice(&invoice_id).default_probability_bps,
+        Some(626)
+    );
+
+    // A default by the same business raises both the category and business rates
+    let defaulted = upload(1_000);
+    client.verify_invoice(&defaulted);
+    env.as_contract(&client.address, || {
+        let mut invoice = InvoiceStorage::get_invoice(&env, &defaulted).unwrap();
+        invoice
+            .mark_as_funded(
+                &env,
+                Address::generate(&env),
+                900,
+                env.ledger().timestamp(),
+                TransitionRole::Business,
+            )
+            .unwrap();
+        InvoiceStorage::update_invoice(&env, &invoice);
+        InvoiceStorage::remove_from_status_invoices(&env, &InvoiceStatus::Verified, &defaulted);
+        InvoiceStorage::add_to_status_invoices(&env, &InvoiceStatus::Funded, &defaulted);
+    });
+    client.update_invoice_status(&defaulted, &InvoiceStatus::Defaulted);
+    assert_eq!(
+        client.get_category_loss_rate(&InvoiceCategory::Services),
+        1_363
+    );
+    assert_eq!(client.recalculate_default_probability(&invoice_id), 2_692);
+    assert_eq!(
+        client.get_invoice(&invoice_id).default_probability_bps,
+        Some(2_692)
+    );
+
+    assert_eq!(
+        client.try_recalculate_default_probability(&defaulted),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_debtor_acknowledgment_lowers_default_probability() {
+    let (env, client, admin) = setup();
+    let business = create_verified_business(&env, &client, &admin);
+    let debtor = Address::generate(&env);
+    let currency = Address::generate(&env);
+    let invoice_id = client.store_invoice(
+        &business,
+        &1_000,
+        &currency,
+        &(env.ledger().timestamp() + 86_400),
+        &String::from_str(&env, "Invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+
+    assert_eq!(
+        client.try_acknowledge_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+    assert_eq!(
+        client.try_register_invoice_debtor(&invoice_id, &business),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.register_invoice_debtor(&invoice_id, &debtor);
+    assert!(!client.is_invoice_acknowledged(&invoice_id));
+
+    let record = client.acknowledge_invoice(&invoice_id);
+    assert_eq!(record.debtor, debtor);
+    assert_eq!(record.acknowledged_at, Some(env.ledger().timestamp()));
+    assert!(client.is_invoice_acknowledged(&invoice_id));
+    assert_eq!(
+        client.get_debtor_invoices(&debtor),
+        Vec::from_array(&env, [invoice_id.clone()])
+    );
+    assert_eq!(
+        client.get_acknowledged_invoices(),
+        Vec::from_array(&env, [invoice_id.clone()])
+    );
+    assert_eq!(
+        client.try_acknowledge_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_register_invoice_debtor(&invoice_id, &Address::generate(&env)),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // Same inputs as the unacknowledged 626 bps estimate, less 25%
+    client.verify_invoice(&invoice_id);
+    assert_eq!(
+        client.get_invoice(&invoice_id).default_probability_bps,
+        Some(469)
+    );
+}
+
+#[test]
+fn test_debtor_history_aggregates_across_businesses() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let debtor = Address::generate(env);
+    let investor = scenario.verified_investor();
+    let first_business = scenario.verified_business();
+    let second_business = scenario.verified_business();
+    let currency = scenario.create_token(&[&first_business, &second_business, &investor]);
+    let invoice_for = |business: &Address, fund: bool| {
+        let invoice_id = scenario.verified_invoice(business, &currency, 1_000);
+        client.register_invoice_debtor(&invoice_id, &debtor);
+        if fund {
+            let bid_id = client.place_bid(&investor, &invoice_id, &900, &1_000);
+            client.accept_bid_and_fund(&invoice_id, &bid_id);
+        }
+        invoice_id
+    };
+
+    let on_time = invoice_for(&first_business, true);
+    let late = invoice_for(&second_business, true);
+    let defaulted = invoice_for(&first_business, true);
+    let outstanding = invoice_for(&second_business, true);
+    invoice_for(&first_business, false);
+    client.acknowledge_invoice(&on_time);
+
+    client.settle_invoice(&on_time, &1_000);
+    let due_date = client.get_invoice(&late).due_date;
+    env.ledger().set_timestamp(due_date + 1);
+    client.settle_invoice(&late, &1_000);
+    env.ledger()
+        .set_timestamp(due_date + crate::defaults::DEFAULT_GRACE_PERIOD + 1);
+    client.mark_invoice_defaulted(&defaulted, &None);
+
+    let history = client.get_debtor_history(&debtor);
+    assert_eq!(history.invoice_count, 5);
+    assert_eq!(history.business_count, 2);
+    assert_eq!(history.acknowledged_count, 1);
+    assert_eq!(history.financed_count, 4);
+    assert_eq!(history.financed_amount, 3_600);
+    assert_eq!(history.paid_count, 2);
+    assert_eq!(history.paid_on_time_count, 1);
+    assert_eq!(history.defaulted_count, 1);
+    assert_eq!(history.outstanding_count, 1);
+    assert_eq!(
+        client.get_invoice(&outstanding).status,
+        InvoiceStatus::Funded
+    );
+
+    assert_eq!(
+        client
+            .get_debtor_history(&Address::generate(env))
+            .invoice_count,
+        0
+    );
+}
+
+#[test]
+fn test_portfolio_risk_metrics() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let investor = scenario.verified_investor();
+    let first_business = scenario.verified_business();
+    let second_business = scenario.verified_business();
+    let currency = scenario.create_token(&[&first_business, &second_business, &investor]);
+    let fund = |business: &Address, amount: i128, bid_amount: i128| {
+        let invoice_id = scenario.verified_invoice(business, &currency, amount);
+        let bid_id = client.place_bid(&investor, &invoice_id, &bid_amount, &amount);
+        client.accept_bid_and_fund(&invoice_id, &bid_id);
+        invoice_id
+    };
+
+    let empty = client.get_portfolio_risk(&investor);
+    assert_eq!(empty.active_investments, 0);
+    assert_eq!(empty.weighted_avg_duration, 0);
+    assert_eq!(empty.expected_loss_bps, 0);
+
+    let day = 24 * 60 * 60;
+    let insured = fund(&first_business, 7_000, 6_000);
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 10 * day);
+    fund(&second_business, 5_000, 4_000);
+    let investment = client.get_invoice_investment(&insured);
+    let insurer = Address::generate(env);
+    client.register_insurance_provider(&insurer, &1_000_000);
+    client.approve_provider(&insurer);
+    client.add_investment_insurance(&investment.investment_id, &insurer, &50);
+
+    let risk = client.get_portfolio_risk(&investor);
+    assert_eq!(risk.active_investments, 2);
+    assert_eq!(risk.total_invested, 10_000);
+    // 6,000 due in 20 days and 4,000 due in 30 days
+    assert_eq!(risk.weighted_avg_duration, 24 * day);
+    assert_eq!(risk.business_concentration.len(), 2);
+    assert_eq!(
+        risk.business_concentration.get_unchecked(0).business,
+        first_business
+    );
+    assert_eq!(
+        risk.business_concentration.get_unchecked(0).share_bps,
+        6_000
+    );
+    assert_eq!(risk.largest_business_share_bps, 6_000);
+    assert_eq!(risk.category_concentration.len(), 1);
+    assert_eq!(
+        risk.category_concentration.get_unchecked(0).share_bps,
+        10_000
+    );
+    assert_eq!(risk.insured_amount, 3_000);
+    assert_eq!(risk.insured_share_bps, 3_000);
+    // No history yet: the 5% prior applies to the 7,000 left uninsured
+    assert_eq!(risk.expected_loss, 350);
+    assert_eq!(risk.expected_loss_bps, 350);
+}
+
+// ============================================================================
+// Recoveries and collections
+// ============================================================================
+
+#[test]
+fn test_default_recovery_claws_back_insurance_claim() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let funded = scenario.funded_invoice(10_000, 9_000);
+    let (invoice_id, business, investor, currency) = (
+        &funded.invoice_id,
+        &funded.business,
+        &funded.investor,
+        &funded.currency,
+    );
+    let token_client = token::Client::new(env, currency);
+
+    let provider = Address::generate(env);
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
+    let investment = client.get_invoice_investment(invoice_id);
+    client.add_investment_insurance(&investment.investment_id, &provider, &60);
+    scenario.fund(currency, &provider);
+    client.stake_insurance_pool(&provider, currency, &5_400);
+    let provider_balance = token_client.balance(&provider);
+
+    // Recoveries are only collected on defaulted invoices
+    assert_eq!(
+        client.try_record_default_recovery(invoice_id, business, &1_000),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    let invoice = client.get_invoice(invoice_id);
+    env.ledger()
+        .set_timestamp(invoice.due_date + 7 * 24 * 60 * 60 + 1);
+    client.mark_invoice_defaulted(invoice_id, &None);
+
+    // The claim covered 5,400 of the 9,000 loss; the investor gets the first 3,600
+    let investor_before = token_client.balance(investor);
+    client.record_default_recovery(invoice_id, business, &3_000);
+    assert_eq!(token_client.balance(investor) - investor_before, 3_000);
+    assert_eq!(token_client.balance(&provider), provider_balance);
+
+    let recovery = client.record_default_recovery(invoice_id, business, &4_000);
+    assert_eq!(recovery.investor_recovered, 3_600);
+    assert_eq!(recovery.claim_clawback, 3_400);
+    assert_eq!(
+        client.get_insurance_pool_state(currency).claims_recovered,
+        3_400
+    );
+
+    // Once the claim is fully clawed back, the investor receives the rest
+    let recovery = client.record_default_recovery(invoice_id, business, &3_000);
+    assert_eq!(recovery.total_recovered, 10_000);
+    assert_eq!(recovery.claim_clawback, 5_400);
+    assert_eq!(recovery.investor_recovered, 4_600);
+    assert_eq!(token_client.balance(investor) - investor_before, 4_600);
+    assert_eq!(client.get_default_recovery(invoice_id), Some(recovery));
+
+    let stats = client.get_insurance_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.claims_paid_amount, 5_400);
+    assert_eq!(stats.claims_recovered_amount, 5_400);
+    assert_eq!(stats.loss_ratio_bps, 0);
+
+    assert_eq!(
+        client.try_record_default_recovery(invoice_id, business, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_collection_case_tracks_recoveries_until_recovered_or_written_off() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let recovered = scenario.funded_invoice(10_000, 9_000);
+    let written_off = scenario.funded_invoice(5_000, 4_500);
+
+    // No case before the invoice defaults
+    assert_eq!(client.get_collection_case(&recovered.invoice_id), None);
+    let due_date = client.get_invoice(&written_off.invoice_id).due_date;
+    env.ledger().set_timestamp(due_date + 7 * 24 * 60 * 60 + 1);
+    client.mark_invoice_defaulted(&recovered.invoice_id, &None);
+    client.mark_invoice_defaulted(&written_off.invoice_id, &None);
+
+    let case = client.get_collection_case(&recovered.invoice_id).unwrap();
+    assert_eq!(case.status, CollectionStatus::Open);
+    assert_eq!(case.total_recovered, 0);
+    assert_eq!(case.outstanding, case.amount_due);
+    let amount_due = case.amount_due;
+
+    let invoice_id = &recovered.invoice_id;
+    client.record_default_recovery(invoice_id, &recovered.business, &4_000);
+    let case = client.get_collection_case(invoice_id).unwrap();
+    assert_eq!(case.status, CollectionStatus::PartiallyRecovered);
+    assert_eq!(case.outstanding, amount_due - 4_000);
+    assert_eq!(case.closed_at, None);
+
+    client.record_default_recovery(invoice_id, &recovered.business, &(amount_due - 4_000));
+    let case = client.get_collection_case(invoice_id).unwrap();
+    assert_eq!(case.status, CollectionStatus::Recovered);
+    assert_eq!(case.outstanding, 0);
+    assert_eq!(case.closed_at, Some(env.ledger().timestamp()));
+    let history = client.get_collection_history(invoice_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().amount, 4_000);
+    assert_eq!(history.get(0).unwrap().investor_amount, 4_000);
+    assert_eq!(history.get(0).unwrap().clawback_amount, 0);
+    assert_eq!(
+        client.try_write_off_collection(invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // A written-off case accepts no further recoveries
+    let invoice_id = &written_off.invoice_id;
+    client.record_default_recovery(invoice_id, &written_off.business, &1_000);
+    let case = client.write_off_collection(invoice_id);
+    assert_eq!(case.status, CollectionStatus::WrittenOff);
+    assert_eq!(case.total_recovered, 1_000);
+    assert_eq!(
+        client.try_record_default_recovery(invoice_id, &written_off.business, &1_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_write_off_collection(invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
@@ -0,0 +1,97 @@
+use super::*;
+use crate::deposits::WithdrawalQueuePolicy;
+use crate::payments::EscrowStatus;
+use soroban_sdk::{testutils::Ledger, token};
+
+#[test]
+fn test_accept_bid_draws_from_investor_deposit() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let token_client = token::Client::new(env, &currency);
+    let wallet_before = token_client.balance(&investor);
+
+    assert_eq!(
+        client.try_deposit(&investor, &currency, &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    assert_eq!(client.deposit(&investor, &currency, &12_000), 12_000);
+    assert_eq!(token_client.balance(&investor), wallet_before - 12_000);
+    // Revoke the allowance: a prefunded bid no longer needs it
+    token_client.approve(&investor, &client.address, &0, &0);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+    assert_eq!(client.get_deposit_balance(&investor, &currency), 3_000);
+
+    // The shortfall of a larger bid must come from the wallet
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    assert_eq!(
+        client.try_accept_bid(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    assert_eq!(
+        client.try_withdraw(&investor, &currency, &3_001),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    assert_eq!(client.withdraw(&investor, &currency, &3_000), 0);
+    assert_eq!(token_client.balance(&investor), wallet_before - 9_000);
+}
+
+#[test]
+fn test_large_deposit_withdrawal_is_queued_until_delay_elapses() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&investor]);
+    let token_client = token::Client::new(env, &currency);
+    let wallet_before = token_client.balance(&investor);
+    client.deposit(&investor, &currency, &50_000);
+
+    let policy = WithdrawalQueuePolicy {
+        threshold_amount: 10_000,
+        delay_seconds: 3_600,
+    };
+    client.set_withdrawal_queue(&Some(policy));
+
+    // Small withdrawals still leave instantly; large ones must be queued
+    assert_eq!(client.withdraw(&investor, &currency, &5_000), 45_000);
+    assert_eq!(
+        client.try_withdraw(&investor, &currency, &10_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_request_withdrawal(&investor, &currency, &5_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let start = env.ledger().timestamp();
+    let first = client.request_withdrawal(&investor, &currency, &20_000);
+    let second = client.request_withdrawal(&investor, &currency, &15_000);
+    assert_eq!(first.executable_at, start + 3_600);
+    assert_eq!(client.get_deposit_balance(&investor, &currency), 10_000);
+    assert_eq!(client.get_pending_withdrawals().len(), 2);
+
+    assert_eq!(
+        client.try_execute_withdrawal(&first.id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(client.cancel_withdrawal(&second.id), 25_000);
+
+    env.ledger().set_timestamp(start + 3_600);
+    client.execute_withdrawal(&first.id);
+    assert_eq!(token_client.balance(&investor), wallet_before - 25_000);
+    assert!(client.get_pending_withdrawals().is_empty());
+    assert_eq!(
+        client.try_execute_withdrawal(&first.id),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
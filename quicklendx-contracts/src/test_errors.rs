@@ -159,7 +159,7 @@ fn test_invoice_not_verified_error() {
     assert!(result.is_err());
     let err = result.err().unwrap();
     let contract_err = err.expect("expected contract error");
-    assert_eq!(contract_err, QuickLendXError::InvalidStatus);
+    assert_eq!(contract_err, QuickLendXError::InvoiceNotVerified);
 }
 
 #[test]
@@ -328,10 +328,11 @@ fn test_invalid_status_error() {
     let business = create_verified_business(&env, &client, &admin);
     let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 1000);
 
-    // Try to update status to invalid transition
-    let result = client.update_invoice_status(&invoice_id, &crate::invoice::InvoiceStatus::Paid);
-    // This might succeed or fail depending on implementation, but should not panic
-    let _ = result;
+    // Verified invoices must be funded before they can be paid
+    let result =
+        client.try_update_invoice_status(&invoice_id, &crate::invoice::InvoiceStatus::Paid);
+    let contract_err = result.err().unwrap().expect("expected contract error");
+    assert_eq!(contract_err, QuickLendXError::InvalidStatus);
 }
 
 #[test]
@@ -407,3 +408,63 @@ fn test_error_message_consistency() {
     assert_eq!(QuickLendXError::StorageError as u32, 1300);
     assert_eq!(QuickLendXError::InsufficientFunds as u32, 1400);
 }
+
+#[test]
+fn test_granular_bid_errors() {
+    let (env, client, admin) = setup();
+    let business = create_verified_business(&env, &client, &admin);
+    let invoice_id = create_verified_invoice(&env, &client, &admin, &business, 10_000);
+    let investor = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &5_000);
+    let limit = client
+        .get_investor_verification(&investor)
+        .unwrap()
+        .investment_limit;
+
+    // Over the investor's limit, as opposed to a non-positive amount
+    let result = client.try_place_bid(&investor, &invoice_id, &(limit + 1), &(limit + 2));
+    assert_eq!(result, Err(Ok(QuickLendXError::BidExceedsLimit)));
+    let result = client.try_place_bid(&investor, &invoice_id, &0, &7_000);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &limit, &(limit + 500));
+    client.withdraw_bid(&bid_id);
+    assert_eq!(
+        client.try_withdraw_bid(&bid_id),
+        Err(Ok(QuickLendXError::BidNotPlaced))
+    );
+    assert_eq!(
+        client.try_accept_bid(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::BidNotPlaced))
+    );
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &limit, &(limit + 500));
+    let bid = client.get_bid(&bid_id).unwrap();
+    env.ledger().set_timestamp(bid.expiration_timestamp + 1);
+    assert_eq!(
+        client.try_accept_bid_and_fund(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::BidExpired))
+    );
+}
+
+#[test]
+fn test_currency_not_whitelisted_error() {
+    let (env, client, admin) = setup();
+    let business = create_verified_business(&env, &client, &admin);
+    let currency = env
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    client.add_currency(&admin, &currency);
+
+    let result = client.try_upload_invoice(
+        &business,
+        &1_000,
+        &Address::generate(&env),
+        &(env.ledger().timestamp() + 86400),
+        &String::from_str(&env, "Test invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(&env),
+    );
+    assert_eq!(result, Err(Ok(QuickLendXError::CurrencyNotWhitelisted)));
+}
@@ -16,7 +16,10 @@ use super::*;
 use crate::bid::BidStatus;
 use crate::invoice::{InvoiceCategory, InvoiceStatus};
 use crate::payments::EscrowStatus;
-use soroban_sdk::{testutils::Address as _, token, Address, BytesN, Env, String, Vec};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String, Vec,
+};
 
 // ============================================================================
 // Helper Functions
@@ -576,3 +579,481 @@ fn test_escrow_invariants() {
         "Escrow created_at cannot be in future"
     );
 }
+
+#[test]
+fn test_finalize_bidding_requires_passed_deadline() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let invoice = client.get_invoice(&invoice_id);
+
+    // No deadline set
+    let result = client.try_finalize_bidding(&invoice_id);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+
+    // Deadline must be in the future and no later than the due date
+    let now = env.ledger().timestamp();
+    let result = client.try_set_bidding_deadline(&invoice_id, &now);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidTimestamp)));
+    let result = client.try_set_bidding_deadline(&invoice_id, &(invoice.due_date + 1));
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidTimestamp)));
+
+    client.set_bidding_deadline(&invoice_id, &(now + 3600));
+    assert_eq!(
+        client.get_invoice(&invoice_id).bidding_deadline,
+        Some(now + 3600)
+    );
+
+    // Deadline not reached yet
+    let result = client.try_finalize_bidding(&invoice_id);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+}
+
+#[test]
+fn test_finalize_bidding_accepts_best_bid() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor1 = setup_verified_investor(&env, &client, 50_000);
+    let investor2 = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor1, &contract_id);
+    let sac_client = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    sac_client.mint(&investor2, &100_000);
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(&investor2, &contract_id, &100_000, &expiration);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let deadline = env.ledger().timestamp() + 3600;
+    client.set_bidding_deadline(&invoice_id, &deadline);
+
+    let _low_bid = place_test_bid(&client, &investor1, &invoice_id, 9_000, 9_500);
+    let best_bid = place_test_bid(&client, &investor2, &invoice_id, 9_000, 10_000);
+
+    env.ledger().set_timestamp(deadline);
+    let accepted = client.finalize_bidding(&invoice_id);
+    assert_eq!(accepted, Some(best_bid.clone()));
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(investor2));
+    assert_eq!(
+        client.get_bid(&best_bid).unwrap().status,
+        BidStatus::Accepted
+    );
+    assert_eq!(client.get_escrow_status(&invoice_id), EscrowStatus::Held);
+
+    // Already funded
+    let result = client.try_finalize_bidding(&invoice_id);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidStatus)));
+}
+
+#[test]
+fn test_finalize_bidding_skips_top_bid_that_cannot_pay() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor1 = setup_verified_investor(&env, &client, 50_000);
+    let investor2 = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor1, &contract_id);
+    let sac_client = token::StellarAssetClient::new(&env, &currency);
+    let token_client = token::Client::new(&env, &currency);
+    sac_client.mint(&investor2, &100_000);
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(&investor2, &contract_id, &100_000, &expiration);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let deadline = env.ledger().timestamp() + 3600;
+    client.set_bidding_deadline(&invoice_id, &deadline);
+
+    let top_bid = place_test_bid(&client, &investor1, &invoice_id, 9_000, 10_000);
+    let next_bid = place_test_bid(&client, &investor2, &invoice_id, 9_000, 9_500);
+
+    // The top bidder prefunds part of the bid, then revokes the allowance the rest needs
+    client.deposit(&investor1, &currency, &3_000);
+    token_client.approve(&investor1, &contract_id, &0, &0);
+    let wallet_before = token_client.balance(&investor1);
+
+    env.ledger().set_timestamp(deadline);
+    assert_eq!(client.finalize_bidding(&invoice_id), Some(next_bid.clone()));
+
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.status, InvoiceStatus::Funded);
+    assert_eq!(invoice.investor, Some(investor2));
+    assert_eq!(client.get_bid(&top_bid).unwrap().status, BidStatus::Placed);
+    // Nothing was taken from the skipped bidder
+    assert_eq!(client.get_deposit_balance(&investor1, &currency), 3_000);
+    assert_eq!(token_client.balance(&investor1), wallet_before);
+}
+
+#[test]
+fn test_finalize_bidding_expires_invoice_without_bids() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let deadline = env.ledger().timestamp() + 3600;
+    client.set_bidding_deadline(&invoice_id, &deadline);
+
+    env.ledger().set_timestamp(deadline + 1);
+    assert_eq!(client.finalize_bidding(&invoice_id), None);
+
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Expired
+    );
+    assert!(client
+        .get_invoices_by_status(&InvoiceStatus::Expired)
+        .contains(&invoice_id));
+    assert!(!client
+        .get_invoices_by_status(&InvoiceStatus::Verified)
+        .contains(&invoice_id));
+}
+
+#[test]
+fn test_expire_unfunded_invoice_after_funding_deadline() {
+    let (env, client, admin) = setup();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    client.set_bid_bond_bps(&100);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let deadline = env.ledger().timestamp() + 3600;
+    assert_eq!(
+        client.try_set_funding_deadline(&invoice_id, &(deadline + 86_400)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    client.set_funding_deadline(&invoice_id, &deadline);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 5_000, 6_000);
+
+    assert_eq!(
+        client.try_expire_unfunded_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(deadline + 1);
+    assert_eq!(client.expire_unfunded_invoice(&invoice_id), 1);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Expired
+    );
+    assert!(client
+        .get_invoices_by_status(&InvoiceStatus::Expired)
+        .contains(&invoice_id));
+    assert!(!client
+        .get_invoices_by_status(&InvoiceStatus::Verified)
+        .contains(&invoice_id));
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Expired);
+    assert_eq!(token_client.balance(&investor), 100_000);
+
+    assert_eq!(
+        client.try_expire_unfunded_invoice(&invoice_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_payouts_limited_to_segregated_balance() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let other = scenario.funded_invoice(5_000, 4_000);
+    assert_eq!(client.get_segregated_balance(&fixture.currency), 9_000);
+    assert_eq!(client.get_segregated_balance(&other.currency), 4_000);
+
+    // Tokens sent to the contract outside the tracked paths cannot be paid out
+    token::StellarAssetClient::new(env, &fixture.currency).mint(&client.address, &50_000);
+    let result = env.as_contract(&client.address, || {
+        crate::payments::transfer_funds(
+            env,
+            &fixture.currency,
+            &client.address,
+            &fixture.investor,
+            9_001,
+        )
+    });
+    assert_eq!(result, Err(QuickLendXError::InsufficientFunds));
+
+    client.release_escrow_funds(&fixture.invoice_id);
+    assert_eq!(client.get_segregated_balance(&fixture.currency), 0);
+    assert_eq!(client.get_segregated_balance(&other.currency), 4_000);
+    assert_eq!(
+        token::Client::new(env, &fixture.currency).balance(&client.address),
+        50_000
+    );
+}
+
+#[test]
+fn test_milestone_escrow_released_in_stages() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let due_date = client.get_invoice(&invoice_id).due_date;
+    let now = env.ledger().timestamp();
+
+    let milestone = |amount: i128, due: u64, text: &str| crate::milestones::MilestoneSpec {
+        amount,
+        due_date: due,
+        description: String::from_str(env, text),
+    };
+    let mut specs = Vec::new(env);
+    specs.push_back(milestone(3_000, now + 1_000, "Design"));
+    specs.push_back(milestone(3_000, now + 1_000, "Build"));
+    assert_eq!(
+        client.try_set_invoice_milestones(&invoice_id, &specs),
+        Err(Ok(QuickLendXError::InvoiceAmountInvalid))
+    );
+    specs.push_back(milestone(4_000, due_date + 1, "Deliver"));
+    assert_eq!(
+        client.try_set_invoice_milestones(&invoice_id, &specs),
+        Err(Ok(QuickLendXError::InvoiceDueDateInvalid))
+    );
+    specs.set(2, milestone(4_000, due_date, "Deliver"));
+    client.set_invoice_milestones(&invoice_id, &specs);
+    assert_eq!(client.get_invoice_milestones(&invoice_id).len(), 3);
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        client.try_set_invoice_milestones(&invoice_id, &Vec::new(env)),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_release_escrow_funds(&invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let token_client = token::Client::new(env, &currency);
+    let business_before = token_client.balance(&business);
+    assert_eq!(client.release_milestone(&invoice_id, &1), 2_700);
+    assert_eq!(
+        client.try_release_milestone(&invoice_id, &1),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_release_milestone(&invoice_id, &3),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+    let escrow = client.get_escrow_details(&invoice_id);
+    assert_eq!(escrow.status, EscrowStatus::Held);
+    assert_eq!(escrow.released_amount, 2_700);
+
+    assert_eq!(client.release_milestone(&invoice_id, &0), 2_700);
+    assert_eq!(client.release_milestone(&invoice_id, &2), 3_600);
+    assert_eq!(token_client.balance(&business), business_before + 9_000);
+    assert_eq!(
+        client.get_escrow_details(&invoice_id).status,
+        EscrowStatus::Released
+    );
+    let milestones = client.get_invoice_milestones(&invoice_id);
+    assert!(milestones
+        .iter()
+        .all(|m| m.status == crate::milestones::MilestoneStatus::Released));
+}
+
+#[test]
+fn test_refund_after_milestone_release_returns_remainder() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let due_date = client.get_invoice(&invoice_id).due_date;
+
+    let mut specs = Vec::new(env);
+    for (amount, text) in [(5_000, "Phase 1"), (5_000, "Phase 2")] {
+        specs.push_back(crate::milestones::MilestoneSpec {
+            amount,
+            due_date,
+            description: String::from_str(env, text),
+        });
+    }
+    client.set_invoice_milestones(&invoice_id, &specs);
+    let bid_id = client.place_bid(&investor, &invoice_id, &8_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    client.release_milestone(&invoice_id, &0);
+
+    let token_client = token::Client::new(env, &currency);
+    let investor_before = token_client.balance(&investor);
+    client.refund_escrow_funds(&invoice_id, &business);
+    assert_eq!(token_client.balance(&investor), investor_before + 4_000);
+    assert_eq!(client.get_segregated_balance(&currency), 0);
+}
+
+#[test]
+fn test_dispute_freezes_escrow_until_resolved() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let invoice_id = &fixture.invoice_id;
+
+    client.create_dispute(
+        invoice_id,
+        &fixture.investor,
+        &String::from_str(env, "Goods not delivered"),
+        &String::from_str(env, "Shipping record"),
+    );
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Frozen);
+    assert_eq!(
+        client.try_release_escrow_funds(invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_refund_escrow_funds(invoice_id, &fixture.business),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    client.put_dispute_under_review(invoice_id, &scenario.admin);
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Frozen);
+    client.resolve_dispute(
+        invoice_id,
+        &scenario.admin,
+        &String::from_str(env, "Delivery confirmed"),
+    );
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Held);
+
+    let token_client = token::Client::new(env, &fixture.currency);
+    let investor_before = token_client.balance(&fixture.investor);
+    client.refund_escrow_funds(invoice_id, &fixture.business);
+    assert_eq!(
+        token_client.balance(&fixture.investor),
+        investor_before + 9_000
+    );
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Refunded);
+}
+
+#[test]
+fn test_release_requires_separate_approval_when_enabled() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let invoice_id = &fixture.invoice_id;
+    let verifier = Address::generate(env);
+    client.set_invoice_verifier(&verifier, &true);
+
+    assert!(!client.is_release_approval_required());
+    client.set_release_approval_required(&true);
+    assert!(client.is_release_approval_required());
+    assert_eq!(
+        client.try_release_escrow_funds(invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    assert_eq!(
+        client.try_approve_release(invoice_id, &fixture.business),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    let approval = client.approve_release(invoice_id, &verifier);
+    assert_eq!(approval.approver, verifier);
+    assert_eq!(client.get_release_approval(invoice_id), Some(approval));
+    assert_eq!(
+        client.try_approve_release(invoice_id, &scenario.admin),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    // Approving moves no funds
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Held);
+
+    let token_client = token::Client::new(env, &fixture.currency);
+    let business_before = token_client.balance(&fixture.business);
+    client.release_escrow_funds(invoice_id);
+    assert_eq!(
+        token_client.balance(&fixture.business),
+        business_before + 9_000
+    );
+    assert_eq!(client.get_escrow_status(invoice_id), EscrowStatus::Released);
+}
+
+#[soroban_sdk::contract]
+struct FixedRateOracle;
+
+#[soroban_sdk::contractimpl]
+impl FixedRateOracle {
+    /// 0.9 units of `quote` per unit of `base`
+    pub fn rate(_env: Env, _base: Address, _quote: Address) -> i128 {
+        9 * crate::currency::RATE_SCALE / 10
+    }
+}
+
+#[test]
+fn test_funding_in_another_currency_escrows_both_legs() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let invoice_currency = scenario.create_token(&[&business]);
+    let funding_currency = scenario.create_token(&[&investor]);
+    client.add_currency(&scenario.admin, &invoice_currency);
+    client.add_currency(&scenario.admin, &funding_currency);
+    let released_invoice = scenario.verified_invoice(&business, &invoice_currency, 10_000);
+    let refunded_invoice = scenario.verified_invoice(&business, &invoice_currency, 10_000);
+
+    assert_eq!(
+        client.try_place_bid_in_currency(
+            &investor,
+            &released_invoice,
+            &9_000,
+            &10_000,
+            &funding_currency
+        ),
+        Err(Ok(QuickLendXError::InvalidCurrency))
+    );
+    let oracle = env.register(FixedRateOracle, ());
+    client.set_conversion_oracle(&invoice_currency, &funding_currency, &Some(oracle.clone()));
+    assert_eq!(
+        client.get_conversion_oracle(&invoice_currency, &funding_currency),
+        Some(oracle)
+    );
+
+    let funding_token = token::Client::new(env, &funding_currency);
+    let investor_before = funding_token.balance(&investor);
+    for invoice_id in [&released_invoice, &refunded_invoice] {
+        let bid_id =
+            client.place_bid_in_currency(&investor, invoice_id, &9_000, &10_000, &funding_currency);
+        assert_eq!(
+            client.get_bid_funding_currency(&bid_id),
+            Some(funding_currency.clone())
+        );
+        client.accept_bid(invoice_id, &bid_id);
+
+        let escrow = client.get_escrow_details(invoice_id);
+        assert_eq!(escrow.amount, 9_000);
+        assert_eq!(escrow.currency, invoice_currency);
+        assert_eq!(escrow.funding_currency, funding_currency);
+        assert_eq!(escrow.funding_amount, 8_100);
+    }
+    assert_eq!(funding_token.balance(&investor), investor_before - 16_200);
+    let reconciliation = client.reconcile_escrows(&funding_currency);
+    assert_eq!(reconciliation.escrowed, 16_200);
+    assert!(reconciliation.balanced);
+
+    let business_before = funding_token.balance(&business);
+    client.release_escrow_funds(&released_invoice);
+    assert_eq!(funding_token.balance(&business), business_before + 8_100);
+    client.refund_escrow_funds(&refunded_invoice, &business);
+    assert_eq!(funding_token.balance(&investor), investor_before - 8_100);
+    assert_eq!(client.reconcile_escrows(&funding_currency).escrowed, 0);
+}
@@ -0,0 +1,212 @@
+use super::*;
+use crate::bid::BidFundingSource;
+use crate::exposure::BusinessCapOverride;
+use crate::invoice::InvoiceCategory;
+use soroban_sdk::testutils::Ledger;
+
+#[test]
+fn test_category_cap_limits_outstanding_funded_volume() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    client.set_category_cap(&InvoiceCategory::Services, &Some(15_000));
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let first_bid = client.place_bid(&investor, &first, &9_000, &10_000);
+    client.accept_bid(&first, &first_bid);
+
+    let exposure = client.get_category_exposure(&InvoiceCategory::Services);
+    assert_eq!(exposure.cap, Some(15_000));
+    assert_eq!(exposure.outstanding, 9_000);
+    assert_eq!(exposure.remaining, Some(6_000));
+
+    // 9,000 + 7,000 would exceed the cap on both funding paths
+    let second = scenario.verified_invoice(&business, &currency, 10_000);
+    let second_bid = client.place_bid(&investor, &second, &7_000, &8_000);
+    let result = client.try_accept_bid(&second, &second_bid);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+    let result = client.try_accept_bid_and_fund(&second, &second_bid);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+
+    // Other categories are unaffected and clearing the cap lifts the limit
+    assert_eq!(
+        client.get_category_exposure(&InvoiceCategory::Products).cap,
+        None
+    );
+    assert_eq!(client.get_category_exposures().len(), 1);
+    client.set_category_cap(&InvoiceCategory::Services, &None);
+    client.accept_bid(&second, &second_bid);
+    assert_eq!(
+        client
+            .get_category_exposure(&InvoiceCategory::Services)
+            .outstanding,
+        16_000
+    );
+}
+
+#[test]
+fn test_funding_source_limits_per_investor() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    client.set_funding_source_limit(&BidFundingSource::CreditFacility, &Some(12_000));
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let first_bid = client.place_bid(&investor, &first, &9_000, &10_000);
+    assert_eq!(
+        client.get_bid_funding_source(&first_bid),
+        BidFundingSource::Personal
+    );
+    client.set_bid_funding_source(&first_bid, &BidFundingSource::CreditFacility);
+    client.accept_bid(&first, &first_bid);
+    let investment_id = client.get_invoice_investment(&first).investment_id;
+    assert_eq!(
+        client.get_investment_funding_source(&investment_id),
+        BidFundingSource::CreditFacility
+    );
+
+    let exposure = client.get_funding_source_exposure(&investor, &BidFundingSource::CreditFacility);
+    assert_eq!(exposure.limit, Some(12_000));
+    assert_eq!(exposure.outstanding, 9_000);
+    assert_eq!(exposure.remaining, Some(3_000));
+
+    // 9,000 + 7,000 from the credit facility would exceed its limit on both funding paths
+    let second = scenario.verified_invoice(&business, &currency, 10_000);
+    let second_bid = client.place_bid(&investor, &second, &7_000, &8_000);
+    client.set_bid_funding_source(&second_bid, &BidFundingSource::CreditFacility);
+    let result = client.try_accept_bid(&second, &second_bid);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+    let result = client.try_accept_bid_and_fund(&second, &second_bid);
+    assert_eq!(result, Err(Ok(QuickLendXError::OperationNotAllowed)));
+
+    // Other sources have their own, unset, limits
+    client.set_bid_funding_source(&second_bid, &BidFundingSource::Pool);
+    client.accept_bid_and_fund(&second, &second_bid);
+    let exposure = client.get_funding_source_exposure(&investor, &BidFundingSource::Pool);
+    assert_eq!(exposure.limit, None);
+    assert_eq!(exposure.outstanding, 7_000);
+    assert_eq!(
+        client
+            .get_funding_source_exposure(&investor, &BidFundingSource::CreditFacility)
+            .outstanding,
+        9_000
+    );
+
+    assert_eq!(
+        client.try_set_bid_funding_source(&second_bid, &BidFundingSource::Personal),
+        Err(Ok(QuickLendXError::BidNotPlaced))
+    );
+    assert_eq!(
+        client.try_set_funding_source_limit(&BidFundingSource::Pool, &Some(0)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_business_cap_override_applies_and_reverts_on_schedule() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    client.set_business_financing_cap(&business, &Some(15_000));
+    let now = env.ledger().timestamp();
+    let season = BusinessCapOverride {
+        cap: 25_000,
+        starts_at: now + 100,
+        ends_at: now + 1_000,
+    };
+    client.add_business_cap_override(&business, &season);
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let first_bid = client.place_bid(&investor, &first, &9_000, &10_000);
+    client.accept_bid(&first, &first_bid);
+
+    // Before the override starts the base cap applies
+    let second = scenario.verified_invoice(&business, &currency, 10_000);
+    let second_bid = client.place_bid(&investor, &second, &9_000, &10_000);
+    assert_eq!(
+        client.try_accept_bid_and_fund(&second, &second_bid),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(now + 100);
+    client.accept_bid_and_fund(&second, &second_bid);
+    let exposure = client.get_business_exposure(&business);
+    assert_eq!(exposure.base_cap, Some(15_000));
+    assert_eq!(exposure.cap, Some(25_000));
+    assert_eq!(exposure.override_ends_at, Some(now + 1_000));
+    assert_eq!(exposure.outstanding, 18_000);
+    assert_eq!(exposure.remaining, Some(7_000));
+
+    // Once the override ends the base cap is back without any admin call
+    let third = scenario.verified_invoice(&business, &currency, 2_000);
+    let third_bid = client.place_bid(&investor, &third, &1_000, &2_000);
+    env.ledger().set_timestamp(now + 1_000);
+    let exposure = client.get_business_exposure(&business);
+    assert_eq!(exposure.cap, Some(15_000));
+    assert_eq!(exposure.override_ends_at, None);
+    assert_eq!(exposure.remaining, Some(0));
+    assert_eq!(client.get_business_cap_overrides(&business).len(), 0);
+    assert_eq!(
+        client.try_accept_bid(&third, &third_bid),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let later = BusinessCapOverride {
+        cap: 30_000,
+        starts_at: now + 5_000,
+        ends_at: now + 9_000,
+    };
+    client.add_business_cap_override(&business, &later);
+    assert_eq!(
+        client.try_add_business_cap_override(
+            &business,
+            &BusinessCapOverride {
+                cap: 20_000,
+                starts_at: now + 8_000,
+                ends_at: now + 10_000,
+            }
+        ),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_add_business_cap_override(
+            &business,
+            &BusinessCapOverride {
+                cap: 20_000,
+                starts_at: now + 12_000,
+                ends_at: now + 12_000,
+            }
+        ),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    assert_eq!(
+        client.try_add_business_cap_override(
+            &business,
+            &BusinessCapOverride {
+                cap: 0,
+                starts_at: now + 12_000,
+                ends_at: now + 13_000,
+            }
+        ),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    client.remove_business_cap_override(&business, &later.starts_at);
+    assert_eq!(
+        client.try_remove_business_cap_override(&business, &later.starts_at),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    client.set_business_financing_cap(&business, &None);
+    client.accept_bid(&third, &third_bid);
+    assert_eq!(client.get_business_exposure(&business).cap, None);
+}
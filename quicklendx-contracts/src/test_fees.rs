@@ -1,6 +1,9 @@
 use super::*;
-use crate::fees::FeeType;
-use soroban_sdk::{testutils::Address as _, Address, Env, Map, String};
+use crate::fees::{FeePromotion, FeeType, ParticipantTier};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, Env, Map, String, Vec,
+};
 
 /// Helper function to set up admin for testing
 fn setup_admin(env: &Env, client: &QuickLendXContractClient) -> Address {
@@ -526,3 +529,432 @@ fn test_comprehensive_fee_calculation() {
 
     assert_eq!(fees, 1403);
 }
+
+/// Test that the deprecated and current fee entrypoints share one configuration
+#[test]
+fn test_legacy_and_unified_fee_config_agree() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+
+    client.set_platform_fee(&300);
+    assert_eq!(client.get_platform_fee_config().fee_bps, 300);
+
+    client.update_platform_fee_bps(&450);
+    let config = client.get_platform_fee();
+    assert_eq!(config.fee_bps, 450);
+    assert_eq!(config.updated_by, admin);
+
+    // Settlement math uses the same rate: 450 bps of a 10_000 profit
+    let (investor_return, platform_fee) = client.calculate_profit(&100_000, &110_000);
+    assert_eq!(platform_fee, 450);
+    assert_eq!(investor_return, 109_550);
+
+    // Nothing stored under the legacy key, so migration is a no-op
+    assert!(!client.migrate_platform_fee_config());
+    assert_eq!(client.get_platform_fee_config().fee_bps, 450);
+}
+
+/// Test late penalty split configuration and validation
+#[test]
+fn test_late_penalty_split_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+
+    // Default is an even split
+    let config = client.get_late_penalty_split();
+    assert_eq!(config.investor_share_bps, 5_000);
+    assert_eq!(config.platform_share_bps, 5_000);
+
+    // Admin must be set before the split can be configured
+    let result = client.try_set_late_penalty_split(&7_000);
+    assert_eq!(result, Err(Ok(QuickLendXError::NotAdmin)));
+
+    let admin = setup_admin(&env, &client);
+    client.set_late_penalty_split(&7_000);
+    let config = client.get_late_penalty_split();
+    assert_eq!(config.investor_share_bps, 7_000);
+    assert_eq!(config.platform_share_bps, 3_000);
+    assert_eq!(config.updated_by, admin);
+
+    let result = client.try_set_late_penalty_split(&10_001);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+}
+
+/// Helper to mint and approve a test token for the business and investor
+fn setup_currency(
+    env: &Env,
+    contract_id: &Address,
+    business: &Address,
+    investor: &Address,
+) -> (Address, token::Client<'static>) {
+    let token_admin = Address::generate(env);
+    let currency = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let token_client = token::Client::new(env, &currency);
+    let sac_client = token::StellarAssetClient::new(env, &currency);
+    sac_client.mint(business, &10_000);
+    sac_client.mint(investor, &10_000);
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(business, contract_id, &10_000, &expiration);
+    token_client.approve(investor, contract_id, &10_000, &expiration);
+    (currency, token_client)
+}
+
+/// Helper to create, verify and fund a 1,000 invoice with a 900 bid
+fn setup_funded_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    investor: &Address,
+    currency: &Address,
+    due_date: u64,
+) -> soroban_sdk::BytesN<32> {
+    let invoice_id = client.store_invoice(
+        business,
+        &1_000,
+        currency,
+        &due_date,
+        &String::from_str(env, "Fee test invoice"),
+        &crate::invoice::InvoiceCategory::Services,
+        &Vec::new(env),
+    );
+    client.verify_invoice(&invoice_id);
+    let bid_id = client.place_bid(investor, &invoice_id, &900, &1_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    invoice_id
+}
+
+/// Test that a late settlement splits the penalty between investor and platform
+#[test]
+fn test_late_settlement_splits_penalty() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, token_client) = setup_currency(&env, &contract_id, &business, &investor);
+
+    // 5% late penalty on face value, 60% of it to the investor
+    client.initialize_fee_system(&admin);
+    client.update_fee_structure(&admin, &FeeType::LatePayment, &500, &0, &1_000_000, &true);
+    client.set_late_penalty_split(&6_000);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = setup_funded_invoice(&env, &client, &business, &investor, &currency, due_date);
+
+    env.ledger().set_timestamp(due_date + 1);
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_id, &1_100);
+
+    // Penalty: 5% of 1000 = 50 -> investor 30, platform 20; 1050 is due, 50 is refundable
+    // Remaining 1000 on a 900 investment: profit 100, platform fee 2% = 2
+    let record = client.get_late_penalty_record(&invoice_id).unwrap();
+    assert_eq!(record.penalty_amount, 50);
+    assert_eq!(record.investor_share, 30);
+    assert_eq!(record.platform_share, 20);
+    assert_eq!(
+        token_client.balance(&investor) - investor_before,
+        998 + 30
+    );
+    assert_eq!(client.get_overpayment_refund(&invoice_id).unwrap().amount, 50);
+
+    // Analytics count each penalty share once, and only the platform fee reduces net profit
+    let metrics = client.get_financial_metrics(&crate::analytics::TimePeriod::AllTime);
+    assert_eq!(metrics.total_fees, 2 + 20);
+    let row = |rows: &Vec<(String, i128)>, name: &str| {
+        rows.iter()
+            .find(|(label, _)| *label == String::from_str(&env, name))
+            .unwrap()
+            .1
+    };
+    assert_eq!(row(&metrics.fee_breakdown, "platform_fees"), 2);
+    assert_eq!(row(&metrics.fee_breakdown, "late_penalty_platform"), 20);
+    assert_eq!(row(&metrics.fee_breakdown, "late_penalty_investor"), 30);
+    assert_eq!(
+        row(&metrics.profit_margins, "net_profit"),
+        metrics.total_profits - 2
+    );
+}
+
+/// Test that the payoff quote adds the late fee after the due date and settles exactly
+#[test]
+fn test_payoff_amount_includes_late_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, token_client) = setup_currency(&env, &contract_id, &business, &investor);
+    client.initialize_fee_system(&admin);
+    client.update_fee_structure(&admin, &FeeType::LatePayment, &500, &0, &1_000_000, &true);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = setup_funded_invoice(&env, &client, &business, &investor, &currency, due_date);
+
+    let quote = client.get_payoff_amount(&invoice_id, &due_date);
+    assert_eq!(quote.principal, 900);
+    assert_eq!(quote.accrued_return, 100);
+    assert_eq!(quote.late_fee, 0);
+    assert_eq!(quote.payoff_amount, 1_000);
+
+    // 5% of face value once overdue
+    let quote = client.get_payoff_amount(&invoice_id, &(due_date + 1));
+    assert_eq!(quote.late_fee, 50);
+    assert_eq!(quote.payoff_amount, 1_050);
+
+    env.ledger().set_timestamp(due_date + 1);
+    let business_before = token_client.balance(&business);
+    client.settle_invoice(&invoice_id, &quote.payoff_amount);
+    assert_eq!(
+        client
+            .get_late_penalty_record(&invoice_id)
+            .unwrap()
+            .penalty_amount,
+        50
+    );
+    assert_eq!(business_before - token_client.balance(&business), 1_050);
+
+    assert_eq!(
+        client.try_get_payoff_amount(&invoice_id, &due_date),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+/// Test that settlement uses the fee terms quoted at funding
+#[test]
+fn test_fee_quote_locked_at_funding() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, token_client) = setup_currency(&env, &contract_id, &business, &investor);
+    client.initialize_fee_system(&admin);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = setup_funded_invoice(&env, &client, &business, &investor, &currency, due_date);
+
+    let quote = client.get_invoice_fee_quote(&invoice_id).unwrap();
+    assert_eq!(quote.platform_fee_bps, 200);
+    assert!(!quote.late_penalty_active);
+
+    // Raise fees and introduce a late penalty after funding
+    client.update_platform_fee_bps(&1_000);
+    client.update_fee_structure(&admin, &FeeType::LatePayment, &500, &0, &1_000_000, &true);
+
+    env.ledger().set_timestamp(due_date + 1);
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_id, &1_100);
+
+    // Quoted terms: no penalty, so 1000 is due and 100 is refundable; 2% of the 100 profit = 2
+    assert_eq!(client.get_late_penalty_record(&invoice_id), None);
+    assert_eq!(token_client.balance(&investor) - investor_before, 998);
+    assert_eq!(client.get_overpayment_refund(&invoice_id).unwrap().amount, 100);
+}
+
+/// Test platform fee rates configured per participant tier
+#[test]
+fn test_tier_fee_rates() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let unverified_business = Address::generate(&env);
+    let unverified_investor = Address::generate(&env);
+    client.initialize_fee_system(&admin);
+
+    // No tier rates: base rate for everyone
+    assert_eq!(client.get_effective_fee_bps(&business, &investor), 200);
+
+    client.set_tier_fee_rate(&ParticipantTier::VerifiedBusiness, &150);
+    client.set_tier_fee_rate(&ParticipantTier::UnverifiedBusiness, &300);
+    assert_eq!(client.get_effective_fee_bps(&business, &investor), 150);
+    assert_eq!(
+        client.get_effective_fee_bps(&unverified_business, &unverified_investor),
+        300
+    );
+
+    // The lowest matching rate applies
+    let tier = client.get_investor_verification(&investor).unwrap().tier;
+    client.set_tier_fee_rate(&ParticipantTier::Investor(tier.clone()), &100);
+    assert_eq!(client.get_effective_fee_bps(&business, &investor), 100);
+    assert_eq!(
+        client.get_effective_fee_bps(&unverified_business, &investor),
+        100
+    );
+    assert_eq!(client.get_tier_fee_rates().len(), 3);
+
+    client.remove_tier_fee_rate(&ParticipantTier::Investor(tier.clone()));
+    assert_eq!(client.get_effective_fee_bps(&business, &investor), 150);
+
+    let result = client.try_remove_tier_fee_rate(&ParticipantTier::Investor(tier));
+    assert_eq!(result, Err(Ok(QuickLendXError::StorageKeyNotFound)));
+    let result = client.try_set_tier_fee_rate(&ParticipantTier::VerifiedBusiness, &1_001);
+    assert_eq!(result, Err(Ok(QuickLendXError::InvalidAmount)));
+}
+
+/// Test that the tier rate is locked into the fee quote and used at settlement
+#[test]
+fn test_tier_fee_rate_applied_at_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, token_client) = setup_currency(&env, &contract_id, &business, &investor);
+    client.initialize_fee_system(&admin);
+    client.set_tier_fee_rate(&ParticipantTier::VerifiedBusiness, &500);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = setup_funded_invoice(&env, &client, &business, &investor, &currency, due_date);
+    assert_eq!(
+        client
+            .get_invoice_fee_quote(&invoice_id)
+            .unwrap()
+            .platform_fee_bps,
+        500
+    );
+
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_id, &1_000);
+
+    // 5% of the 100 profit = 5
+    assert_eq!(token_client.balance(&investor) - investor_before, 995);
+}
+
+/// Test that a fee promotion waives the platform fee on settlements inside its window only
+#[test]
+fn test_fee_promotion_waives_platform_fee_within_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, token_client) = setup_currency(&env, &contract_id, &business, &investor);
+    client.initialize_fee_system(&admin);
+
+    let now = env.ledger().timestamp();
+    let due_date = now + 86_400;
+    let mut invoice_ids = Vec::new(&env);
+    for _ in 0..3 {
+        invoice_ids.push_back(setup_funded_invoice(
+            &env, &client, &business, &investor, &currency, due_date,
+        ));
+    }
+
+    let mut launch = FeePromotion {
+        starts_at: now + 100,
+        ends_at: now + 1_000,
+        categories: Vec::new(&env),
+    };
+    client.set_fee_promotion(&launch);
+    assert_eq!(client.get_fee_promotion(), Some(launch.clone()));
+
+    // Before the window opens the quoted 2% applies
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_ids.get(0).unwrap(), &1_000);
+    assert_eq!(token_client.balance(&investor) - investor_before, 998);
+
+    env.ledger().set_timestamp(now + 100);
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_ids.get(1).unwrap(), &1_000);
+    assert_eq!(token_client.balance(&investor) - investor_before, 1_000);
+
+    // A promotion limited to other categories leaves Services invoices paying the fee
+    launch
+        .categories
+        .push_back(crate::invoice::InvoiceCategory::Products);
+    client.set_fee_promotion(&launch);
+    let investor_before = token_client.balance(&investor);
+    client.settle_invoice(&invoice_ids.get(2).unwrap(), &1_000);
+    assert_eq!(token_client.balance(&investor) - investor_before, 998);
+
+    // The promotion lapses on its own at the end of the window
+    env.ledger().set_timestamp(now + 1_000);
+    assert_eq!(client.get_fee_promotion(), None);
+    assert_eq!(
+        client.try_cancel_fee_promotion(),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    let invalid = FeePromotion {
+        starts_at: now + 2_000,
+        ends_at: now + 2_000 + 181 * 86_400,
+        categories: Vec::new(&env),
+    };
+    assert_eq!(
+        client.try_set_fee_promotion(&invalid),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    launch.starts_at = now + 2_000;
+    launch.ends_at = now + 3_000;
+    client.set_fee_promotion(&launch);
+    client.cancel_fee_promotion();
+    assert_eq!(client.get_fee_promotion(), None);
+}
+
+/// Test daily late fee accrual on top of the flat penalty, up to the cap
+#[test]
+fn test_late_fee_accrues_daily_up_to_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(crate::QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = setup_admin(&env, &client);
+    let business = setup_business(&env, &client, &admin);
+    let investor = setup_investor(&env, &client, &admin);
+    let (currency, _token_client) = setup_currency(&env, &contract_id, &business, &investor);
+    client.initialize_fee_system(&admin);
+    client.update_fee_structure(&admin, &FeeType::LatePayment, &500, &0, &1_000_000, &true);
+    assert_eq!(
+        client.try_set_late_fee_accrual(&600, &500),
+        Err(Ok(QuickLendXError::InvalidFeeBasisPoints))
+    );
+    client.set_late_fee_accrual(&100, &500);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = setup_funded_invoice(&env, &client, &business, &investor, &currency, due_date);
+    let quote = client.get_invoice_fee_quote(&invoice_id).unwrap();
+    assert_eq!(quote.late_daily_bps, 100);
+    assert_eq!(quote.late_accrual_cap_bps, 500);
+
+    assert_eq!(client.get_amount_due(&invoice_id), 1_000);
+    // Flat 5% on the first day overdue, then 1% per full day
+    env.ledger().set_timestamp(due_date + 1);
+    assert_eq!(client.get_amount_due(&invoice_id), 1_050);
+    env.ledger().set_timestamp(due_date + 3 * 86_400);
+    assert_eq!(client.get_amount_due(&invoice_id), 1_080);
+    // Accrual stops at the 5% cap
+    env.ledger().set_timestamp(due_date + 10 * 86_400);
+    assert_eq!(
+        client
+            .get_payoff_amount(&invoice_id, &(due_date + 10 * 86_400))
+            .late_fee,
+        100
+    );
+
+    // Default freezes the amount due, penalties included
+    assert_eq!(client.simulate_default(&invoice_id).amount_due, 1_100);
+    client.mark_invoice_defaulted(&invoice_id, &None);
+    env.ledger().set_timestamp(due_date + 20 * 86_400);
+    assert_eq!(client.get_amount_due(&invoice_id), 1_100);
+}
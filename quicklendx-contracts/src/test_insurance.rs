@@ -7,13 +7,21 @@
 /// 4. Coverage/premium math - exact rounding and overflow boundaries
 /// 5. Query correctness - insurance list and ordering
 /// 6. Security edges - duplicates, invalid inputs, and non-mutation on failures
+/// 7. Insurance program - providers, pools, premiums, claims and reporting
+
+extern crate std;
 
 use super::*;
 use crate::errors::QuickLendXError;
+use crate::insurance::{ClaimAdjudication, InsuranceClaimStatus};
+use crate::insurance_registry::InsuranceProviderStatus;
 use crate::investment::{Investment, InvestmentStatus, InvestmentStorage, DEFAULT_INSURANCE_PREMIUM_BPS};
+use crate::invoice::InvoiceCategory;
+use crate::premium_schedule::{CategoryPremium, PremiumBand, PremiumSchedule};
+use crate::required_insurance::{BidInsurance, InsuranceRequirement};
 use soroban_sdk::{
-    testutils::{Address as _, MockAuth, MockAuthInvoke},
-    Address, BytesN, Env, IntoVal, Vec,
+    testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke},
+    token, Address, BytesN, Env, IntoVal, String, Vec,
 };
 
 // ============================================================================
@@ -35,12 +43,14 @@ fn invoice_id_from_seed(env: &Env, seed: u8) -> BytesN<32> {
 
 fn store_investment(
     env: &Env,
+    contract_id: &Address,
     investor: &Address,
     amount: i128,
     status: InvestmentStatus,
     seed: u8,
 ) -> BytesN<32> {
-    let investment_id = InvestmentStorage::generate_unique_investment_id(env);
+    let investment_id =
+        env.as_contract(contract_id, || InvestmentStorage::generate_unique_investment_id(env));
     let investment = Investment {
         investment_id: investment_id.clone(),
         invoice_id: invoice_id_from_seed(env, seed),
@@ -50,20 +60,160 @@ fn store_investment(
         status,
         insurance: Vec::new(env),
     };
-    InvestmentStorage::store_investment(env, &investment);
+    env.as_contract(contract_id, || {
+        InvestmentStorage::store_investment(env, &investment)
+    });
     investment_id
 }
 
-fn set_insurance_inactive(env: &Env, investment_id: &BytesN<32>, idx: u32) {
-    let mut investment =
-        InvestmentStorage::get_investment(env, investment_id).expect("investment must exist");
+/// Investment of `amount` in a freshly funded invoice of twice that amount
+fn funded_investment(scenario: &crate::testutils::Scenario, amount: i128) -> BytesN<32> {
+    let fixture = scenario.funded_invoice(amount * 2, amount);
+    scenario
+        .client
+        .get_invoice_investment(&fixture.invoice_id)
+        .investment_id
+}
+
+fn approved_provider(scenario: &crate::testutils::Scenario) -> Address {
+    let provider = Address::generate(&scenario.env);
+    scenario
+        .client
+        .register_insurance_provider(&provider, &1_000_000);
+    scenario.client.approve_provider(&provider);
+    provider
+}
+
+fn set_insurance_inactive(
+    env: &Env,
+    contract_id: &Address,
+    investment_id: &BytesN<32>,
+    idx: u32,
+) {
+    let mut investment = env
+        .as_contract(contract_id, || InvestmentStorage::get_investment(env, investment_id))
+        .expect("investment must exist");
     let mut coverage = investment
         .insurance
         .get(idx)
         .expect("insurance entry must exist");
     coverage.active = false;
     investment.insurance.set(idx, coverage);
-    InvestmentStorage::update_investment(env, &investment);
+    env.as_contract(contract_id, || {
+        InvestmentStorage::update_investment(env, &investment)
+    });
+}
+
+/// Setup test environment with contract and admin
+fn setup_with_admin() -> (Env, QuickLendXContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    (env, client, admin)
+}
+
+/// Create a Stellar Asset Contract token for testing with proper balances
+fn setup_token(
+    env: &Env,
+    business: &Address,
+    investor: &Address,
+    contract_id: &Address,
+) -> Address {
+    let token_admin = Address::generate(env);
+    let currency = env
+        .register_stellar_asset_contract_v2(token_admin.clone())
+        .address();
+
+    let token_client = token::Client::new(env, &currency);
+    let sac_client = token::StellarAssetClient::new(env, &currency);
+
+    // Mint tokens to business and investor
+    let initial_balance = 100_000i128;
+    sac_client.mint(business, &initial_balance);
+    sac_client.mint(investor, &initial_balance);
+
+    // Approve contract to spend tokens
+    let expiration = env.ledger().sequence() + 10_000;
+    token_client.approve(business, contract_id, &initial_balance, &expiration);
+    token_client.approve(investor, contract_id, &initial_balance, &expiration);
+
+    currency
+}
+
+fn stake_insurance_pool(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    provider: &Address,
+    currency: &Address,
+    amount: i128,
+) {
+    token::StellarAssetClient::new(env, currency).mint(provider, &amount);
+    let expiration = env.ledger().sequence() + 10_000;
+    token::Client::new(env, currency).approve(provider, &client.address, &amount, &expiration);
+    client.stake_insurance_pool(provider, currency, &amount);
+}
+
+fn approved_insurance_provider(env: &Env, client: &QuickLendXContractClient) -> Address {
+    let provider = Address::generate(env);
+    client.register_insurance_provider(&provider, &1_000_000);
+    client.approve_provider(&provider);
+    provider
+}
+
+/// Create and verify a business
+fn setup_verified_business(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    admin: &Address,
+) -> Address {
+    let business = Address::generate(env);
+    client.submit_kyc_application(&business, &String::from_str(env, "Business KYC"));
+    client.verify_business(admin, &business);
+    business
+}
+
+/// Create and verify an investor with specified limit
+fn setup_verified_investor(env: &Env, client: &QuickLendXContractClient, limit: i128) -> Address {
+    let investor = Address::generate(env);
+    client.submit_investor_kyc(&investor, &String::from_str(env, "Investor KYC"));
+    client.verify_investor(&investor, &limit);
+    investor
+}
+
+/// Create a verified invoice ready for bidding
+fn create_verified_invoice(
+    env: &Env,
+    client: &QuickLendXContractClient,
+    business: &Address,
+    amount: i128,
+    currency: &Address,
+) -> BytesN<32> {
+    let due_date = env.ledger().timestamp() + 86400; // 1 day from now
+    let invoice_id = client.store_invoice(
+        business,
+        &amount,
+        currency,
+        &due_date,
+        &String::from_str(env, "Test Invoice"),
+        &InvoiceCategory::Services,
+        &Vec::new(env),
+    );
+    client.verify_invoice(&invoice_id);
+    invoice_id
+}
+
+/// Place a bid on an invoice
+fn place_test_bid(
+    client: &QuickLendXContractClient,
+    investor: &Address,
+    invoice_id: &BytesN<32>,
+    bid_amount: i128,
+    expected_return: i128,
+) -> BytesN<32> {
+    client.place_bid(investor, invoice_id, &bid_amount, &expected_return)
 }
 
 // ============================================================================
@@ -77,7 +227,7 @@ fn test_add_insurance_requires_investor_auth() {
     let attacker = Address::generate(&env);
     let provider = Address::generate(&env);
 
-    let investment_id = store_investment(&env, &investor, 10_000, InvestmentStatus::Active, 1);
+    let investment_id = store_investment(&env, &contract_id, &investor, 10_000, InvestmentStatus::Active, 1);
 
     let auth = MockAuth {
         address: &attacker,
@@ -97,10 +247,10 @@ fn test_add_insurance_requires_investor_auth() {
     let invoke_err = err.err().expect("expected invoke error");
     assert_eq!(invoke_err, soroban_sdk::InvokeError::Abort);
 
-    let stored = client.get_investment(&investment_id).unwrap();
+    let stored = client.get_investment(&investment_id);
     assert_eq!(stored.insurance.len(), 0);
 
-    let err_debug = format!("{:?}", invoke_err);
+    let err_debug = std::format!("{:?}", invoke_err);
     assert!(!err_debug.contains("ed25519"));
 }
 
@@ -110,7 +260,7 @@ fn test_add_insurance_requires_investor_auth() {
 
 #[test]
 fn test_add_insurance_requires_active_investment() {
-    let (env, client, _contract_id) = setup();
+    let (env, client, contract_id) = setup();
     env.mock_all_auths();
 
     let investor = Address::generate(&env);
@@ -124,7 +274,7 @@ fn test_add_insurance_requires_active_investment() {
 
     for (idx, status) in statuses.iter().enumerate() {
         let investment_id =
-            store_investment(&env, &investor, 5_000, status.clone(), (idx + 2) as u8);
+            store_investment(&env, &contract_id, &investor, 5_000, status.clone(), (idx + 2) as u8);
 
         let result =
             client.try_add_investment_insurance(&investment_id, &provider, &50u32);
@@ -132,7 +282,7 @@ fn test_add_insurance_requires_active_investment() {
         let contract_error = err.expect("expected contract error");
         assert_eq!(contract_error, QuickLendXError::InvalidStatus);
 
-        let stored = client.get_investment(&investment_id).unwrap();
+        let stored = client.get_investment(&investment_id);
         assert_eq!(stored.insurance.len(), 0);
     }
 }
@@ -153,24 +303,26 @@ fn test_add_insurance_storage_key_not_found() {
 
 #[test]
 fn test_state_transition_before_add_rejected() {
-    let (env, client, _contract_id) = setup();
+    let (env, client, contract_id) = setup();
     env.mock_all_auths();
 
     let investor = Address::generate(&env);
     let provider = Address::generate(&env);
 
-    let investment_id = store_investment(&env, &investor, 7_500, InvestmentStatus::Active, 9);
+    let investment_id = store_investment(&env, &contract_id, &investor, 7_500, InvestmentStatus::Active, 9);
 
-    let mut investment = InvestmentStorage::get_investment(&env, &investment_id).unwrap();
-    investment.status = InvestmentStatus::Completed;
-    InvestmentStorage::update_investment(&env, &investment);
+    env.as_contract(&contract_id, || {
+        let mut investment = InvestmentStorage::get_investment(&env, &investment_id).unwrap();
+        investment.status = InvestmentStatus::Completed;
+        InvestmentStorage::update_investment(&env, &investment);
+    });
 
     let result = client.try_add_investment_insurance(&investment_id, &provider, &35u32);
     let err = result.err().expect("expected invalid status error");
     let contract_error = err.expect("expected contract error");
     assert_eq!(contract_error, QuickLendXError::InvalidStatus);
 
-    let stored = client.get_investment(&investment_id).unwrap();
+    let stored = client.get_investment(&investment_id);
     assert_eq!(stored.insurance.len(), 0);
 }
 
@@ -180,17 +332,14 @@ fn test_state_transition_before_add_rejected() {
 
 #[test]
 fn test_premium_and_coverage_math_exact() {
-    let (env, client, _contract_id) = setup();
-    env.mock_all_auths();
-
-    let investor = Address::generate(&env);
-    let provider = Address::generate(&env);
-
-    let investment_id = store_investment(&env, &investor, 10_000, InvestmentStatus::Active, 4);
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let provider = approved_provider(&scenario);
 
+    let investment_id = funded_investment(&scenario, 10_000);
     client.add_investment_insurance(&investment_id, &provider, &80u32);
 
-    let stored = client.get_investment(&investment_id).unwrap();
+    let stored = client.get_investment(&investment_id);
     let insurance = stored.insurance.get(0).unwrap();
     assert_eq!(insurance.coverage_amount, 8_000);
     assert_eq!(insurance.premium_amount, 160);
@@ -199,11 +348,10 @@ fn test_premium_and_coverage_math_exact() {
         Investment::calculate_premium(10_000, 80)
     );
 
-    let investment_id_small =
-        store_investment(&env, &investor, 500, InvestmentStatus::Active, 5);
+    let investment_id_small = funded_investment(&scenario, 500);
     client.add_investment_insurance(&investment_id_small, &provider, &1u32);
 
-    let stored_small = client.get_investment(&investment_id_small).unwrap();
+    let stored_small = client.get_investment(&investment_id_small);
     let insurance_small = stored_small.insurance.get(0).unwrap();
     assert_eq!(insurance_small.coverage_amount, 5);
     assert_eq!(insurance_small.premium_amount, 1);
@@ -211,13 +359,11 @@ fn test_premium_and_coverage_math_exact() {
 
 #[test]
 fn test_zero_coverage_and_invalid_inputs() {
-    let (env, client, _contract_id) = setup();
-    env.mock_all_auths();
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let provider = approved_provider(&scenario);
 
-    let investor = Address::generate(&env);
-    let provider = Address::generate(&env);
-
-    let investment_id = store_investment(&env, &investor, 1_000, InvestmentStatus::Active, 6);
+    let investment_id = funded_investment(&scenario, 1_000);
 
     let result = client.try_add_investment_insurance(&investment_id, &provider, &0u32);
     let err = result.err().expect("expected invalid amount error");
@@ -229,39 +375,46 @@ fn test_zero_coverage_and_invalid_inputs() {
     let contract_error = err.expect("expected contract error");
     assert_eq!(contract_error, QuickLendXError::InvalidCoveragePercentage);
 
-    let small_amount_id = store_investment(&env, &investor, 50, InvestmentStatus::Active, 7);
-    let result = client.try_add_investment_insurance(&small_amount_id, &provider, &1u32);
-    let err = result.err().expect("expected invalid amount error");
+    // Providers must be registered and approved by the admin
+    let unapproved = Address::generate(&scenario.env);
+    let result = client.try_add_investment_insurance(&investment_id, &unapproved, &10u32);
+    let err = result.err().expect("expected unauthorized provider error");
     let contract_error = err.expect("expected contract error");
-    assert_eq!(contract_error, QuickLendXError::InvalidAmount);
+    assert_eq!(contract_error, QuickLendXError::Unauthorized);
 
-    let negative_amount_id = store_investment(&env, &investor, -10, InvestmentStatus::Active, 8);
-    let result = client.try_add_investment_insurance(&negative_amount_id, &provider, &10u32);
-    let err = result.err().expect("expected invalid amount error");
-    let contract_error = err.expect("expected contract error");
-    assert_eq!(contract_error, QuickLendXError::InvalidAmount);
+    assert_eq!(client.get_investment(&investment_id).insurance.len(), 0);
 }
 
 #[test]
 fn test_large_values_handle_saturation() {
-    let (env, client, _contract_id) = setup();
-    env.mock_all_auths();
-
+    let env = Env::default();
     let investor = Address::generate(&env);
     let provider = Address::generate(&env);
 
     let amount = i128::MAX;
-    let investment_id = store_investment(&env, &investor, amount, InvestmentStatus::Active, 10);
-
-    client.add_investment_insurance(&investment_id, &provider, &100u32);
+    let mut investment = Investment {
+        investment_id: BytesN::from_array(&env, &[3u8; 32]),
+        invoice_id: BytesN::from_array(&env, &[4u8; 32]),
+        investor,
+        amount,
+        funded_at: env.ledger().timestamp(),
+        status: InvestmentStatus::Active,
+        insurance: Vec::new(&env),
+    };
 
-    let stored = client.get_investment(&investment_id).unwrap();
-    let insurance = stored.insurance.get(0).unwrap();
+    let premium = Investment::calculate_premium(amount, 100);
+    let coverage_amount = investment
+        .add_insurance(provider, 100, premium, env.ledger().timestamp())
+        .expect("insurance should be added");
+    let insurance = investment.insurance.get(0).unwrap();
 
     let expected_coverage = amount.saturating_mul(100).checked_div(100).unwrap_or(0);
-    let expected_premium =
-        expected_coverage.saturating_mul(DEFAULT_INSURANCE_PREMIUM_BPS).checked_div(10_000).unwrap_or(0);
+    let expected_premium = expected_coverage
+        .saturating_mul(DEFAULT_INSURANCE_PREMIUM_BPS)
+        .checked_div(10_000)
+        .unwrap_or(0);
 
+    assert_eq!(coverage_amount, expected_coverage);
     assert_eq!(insurance.coverage_amount, expected_coverage);
     assert_eq!(insurance.premium_amount, expected_premium);
 }
@@ -272,23 +425,23 @@ fn test_large_values_handle_saturation() {
 
 #[test]
 fn test_multiple_entries_and_no_cross_investment_leakage() {
-    let (env, client, _contract_id) = setup();
-    env.mock_all_auths();
-
-    let investor = Address::generate(&env);
-    let provider_one = Address::generate(&env);
-    let provider_two = Address::generate(&env);
-    let provider_three = Address::generate(&env);
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let contract_id = client.address.clone();
+    let provider_one = approved_provider(&scenario);
+    let provider_two = approved_provider(&scenario);
+    let provider_three = approved_provider(&scenario);
 
-    let investment_a = store_investment(&env, &investor, 12_000, InvestmentStatus::Active, 11);
-    let investment_b = store_investment(&env, &investor, 8_000, InvestmentStatus::Active, 12);
+    let investment_a = funded_investment(&scenario, 12_000);
+    let investment_b = funded_investment(&scenario, 8_000);
 
     client.add_investment_insurance(&investment_a, &provider_one, &60u32);
 
-    set_insurance_inactive(&env, &investment_a, 0);
+    set_insurance_inactive(env, &contract_id, &investment_a, 0);
     client.add_investment_insurance(&investment_a, &provider_two, &40u32);
 
-    let stored_a = client.get_investment(&investment_a).unwrap();
+    let stored_a = client.get_investment(&investment_a);
     assert_eq!(stored_a.insurance.len(), 2);
     let first = stored_a.insurance.get(0).unwrap();
     let second = stored_a.insurance.get(1).unwrap();
@@ -297,13 +450,13 @@ fn test_multiple_entries_and_no_cross_investment_leakage() {
     assert_eq!(second.provider, provider_two);
     assert!(second.active);
 
-    let stored_b = client.get_investment(&investment_b).unwrap();
+    let stored_b = client.get_investment(&investment_b);
     assert_eq!(stored_b.insurance.len(), 0);
 
     client.add_investment_insurance(&investment_b, &provider_three, &50u32);
 
-    let stored_a_after = client.get_investment(&investment_a).unwrap();
-    let stored_b_after = client.get_investment(&investment_b).unwrap();
+    let stored_a_after = client.get_investment(&investment_a);
+    let stored_b_after = client.get_investment(&investment_b);
 
     assert_eq!(stored_a_after.insurance.len(), 2);
     assert_eq!(stored_b_after.insurance.len(), 1);
@@ -316,17 +469,15 @@ fn test_multiple_entries_and_no_cross_investment_leakage() {
 
 #[test]
 fn test_duplicate_submission_rejected_and_state_unchanged() {
-    let (env, client, _contract_id) = setup();
-    env.mock_all_auths();
-
-    let investor = Address::generate(&env);
-    let provider = Address::generate(&env);
-    let provider_two = Address::generate(&env);
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let provider = approved_provider(&scenario);
+    let provider_two = approved_provider(&scenario);
 
-    let investment_id = store_investment(&env, &investor, 9_000, InvestmentStatus::Active, 13);
+    let investment_id = funded_investment(&scenario, 9_000);
     client.add_investment_insurance(&investment_id, &provider, &70u32);
 
-    let before = client.get_investment(&investment_id).unwrap();
+    let before = client.get_investment(&investment_id);
     assert_eq!(before.insurance.len(), 1);
 
     let result = client.try_add_investment_insurance(&investment_id, &provider_two, &30u32);
@@ -334,7 +485,7 @@ fn test_duplicate_submission_rejected_and_state_unchanged() {
     let contract_error = err.expect("expected contract error");
     assert_eq!(contract_error, QuickLendXError::OperationNotAllowed);
 
-    let after = client.get_investment(&investment_id).unwrap();
+    let after = client.get_investment(&investment_id);
     assert_eq!(after.insurance.len(), 1);
     assert_eq!(after.insurance.get(0).unwrap().provider, provider);
 }
@@ -360,20 +511,20 @@ fn test_investment_helpers_cover_branches() {
 
     let premium = Investment::calculate_premium(1_000, 50);
     let coverage_amount = investment
-        .add_insurance(provider.clone(), 50, premium)
+        .add_insurance(provider.clone(), 50, premium, env.ledger().timestamp())
         .expect("insurance should be added");
     assert_eq!(coverage_amount, 500);
     assert!(investment.has_active_insurance());
 
-    let duplicate = investment.add_insurance(provider.clone(), 40, premium);
+    let duplicate = investment.add_insurance(provider.clone(), 40, premium, env.ledger().timestamp());
     assert_eq!(duplicate, Err(QuickLendXError::OperationNotAllowed));
 
     let mut empty_investment = investment.clone();
     empty_investment.insurance = Vec::new(&env);
-    let invalid = empty_investment.add_insurance(provider.clone(), 150, premium);
+    let invalid = empty_investment.add_insurance(provider.clone(), 150, premium, env.ledger().timestamp());
     assert_eq!(invalid, Err(QuickLendXError::InvalidCoveragePercentage));
 
-    let invalid_premium = empty_investment.add_insurance(provider.clone(), 50, 0);
+    let invalid_premium = empty_investment.add_insurance(provider.clone(), 50, 0, env.ledger().timestamp());
     assert_eq!(invalid_premium, Err(QuickLendXError::InvalidAmount));
 
     let claim = investment.process_insurance_claim().expect("claim should succeed");
@@ -384,3 +535,806 @@ fn test_investment_helpers_cover_branches() {
     let no_claim = investment.process_insurance_claim();
     assert!(no_claim.is_none());
 }
+
+#[test]
+fn test_coverage_limited_by_provider_reserves() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let first = scenario.funded_invoice(10_000, 8_000);
+    let second = scenario.funded_invoice(10_000, 8_000);
+    let first_investment = client.get_invoice_investment(&first.invoice_id);
+    let second_investment = client.get_invoice_investment(&second.invoice_id);
+    let provider = approved_provider(&scenario);
+
+    // Disabled by default: no reserves needed
+    let solvency = client.get_insurance_solvency(&provider);
+    assert_eq!(solvency.coverage_limit, None);
+    assert_eq!(solvency.solvency_ratio_bps, None);
+
+    // A 2x multiplier on 5,000 of reserves allows 10,000 of coverage
+    client.set_insurance_reserve(&provider, &5_000);
+    client.set_insurance_multiplier(&20_000);
+    client.add_investment_insurance(&first_investment.investment_id, &provider, &75);
+
+    let solvency = client.get_insurance_solvency(&provider);
+    assert_eq!(solvency.reserve, 5_000);
+    assert_eq!(solvency.active_coverage, 6_000);
+    assert_eq!(solvency.coverage_limit, Some(10_000));
+    assert_eq!(solvency.solvency_ratio_bps, Some(8_333));
+
+    let result =
+        client.try_add_investment_insurance(&second_investment.investment_id, &provider, &75);
+    assert_eq!(result, Err(Ok(QuickLendXError::InsufficientFunds)));
+    assert!(client
+        .query_investment_insurance(&second_investment.investment_id)
+        .is_empty());
+
+    client.add_investment_insurance(&second_investment.investment_id, &provider, &50);
+    assert_eq!(
+        client.get_insurance_solvency(&provider).active_coverage,
+        10_000
+    );
+    assert_eq!(
+        client.try_set_insurance_reserve(&provider, &-1),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_cancel_insurance_refunds_unused_premium() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let token_client = token::Client::new(env, &fixture.currency);
+    let provider = approved_provider(&scenario);
+
+    let investment_id = client.get_invoice_investment(&fixture.invoice_id).investment_id;
+    client.add_investment_insurance(&investment_id, &provider, &60);
+    let due_date = client.get_invoice(&fixture.invoice_id).due_date;
+    let term = due_date - env.ledger().timestamp();
+
+    // A quarter of the term used: 81 of the 108 premium unused, 10% (9) kept
+    env.ledger().set_timestamp(env.ledger().timestamp() + term / 4);
+    let investor_before = token_client.balance(&fixture.investor);
+    let refund = client.cancel_investment_insurance(&investment_id);
+    assert_eq!(refund.premium, 108);
+    assert_eq!(refund.unused_premium, 81);
+    assert_eq!(refund.cancellation_fee, 9);
+    assert_eq!(refund.refund, 72);
+    assert_eq!(token_client.balance(&fixture.investor) - investor_before, 72);
+
+    let coverage = client
+        .get_investment(&investment_id)
+        .insurance
+        .get(0)
+        .unwrap();
+    assert!(!coverage.active);
+    assert_eq!(coverage.cancelled_at, Some(env.ledger().timestamp()));
+    let pool = client.get_insurance_pool_state(&fixture.currency);
+    assert_eq!(pool.total_assets, 36);
+    assert_eq!(pool.premiums_refunded, 72);
+    assert_eq!(
+        client
+            .get_insurance_stats(&TimePeriod::AllTime)
+            .premiums_collected,
+        36
+    );
+    assert_eq!(
+        client.try_cancel_investment_insurance(&investment_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // New coverage can be bought, but not cancelled once the invoice is due
+    client.add_investment_insurance(&investment_id, &provider, &50);
+    env.ledger().set_timestamp(due_date);
+    assert_eq!(
+        client.try_cancel_investment_insurance(&investment_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    assert_eq!(client.get_insurance_cancellation_fee(), 1_000);
+    assert_eq!(
+        client.try_set_insurance_cancellation_fee(&10_001),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_provider_coverage_totals_follow_purchase_cancel_settle_and_default() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let provider = approved_provider(&scenario);
+    let cancelled = scenario.funded_invoice(10_000, 8_000);
+    let settled = scenario.funded_invoice(10_000, 8_000);
+    let defaulted = scenario.funded_invoice(10_000, 8_000);
+    let investment_of = |fixture: &crate::testutils::FundedInvoiceFixture| {
+        client
+            .get_invoice_investment(&fixture.invoice_id)
+            .investment_id
+    };
+    let active_coverage = || client.get_insurance_solvency(&provider).active_coverage;
+
+    client.add_investment_insurance(&investment_of(&cancelled), &provider, &75);
+    client.add_investment_insurance(&investment_of(&settled), &provider, &50);
+    client.add_investment_insurance(&investment_of(&defaulted), &provider, &25);
+    assert_eq!(active_coverage(), 6_000 + 4_000 + 2_000);
+    let capacity = client.get_insurance_provider_capacity(&provider).unwrap();
+    assert_eq!(capacity.outstanding_coverage, 12_000);
+    assert_eq!(
+        client.get_insurance_pool(&settled.currency).active_coverage,
+        4_000
+    );
+
+    client.cancel_investment_insurance(&investment_of(&cancelled));
+    assert_eq!(active_coverage(), 6_000);
+
+    client.settle_invoice(&settled.invoice_id, &10_000);
+    assert_eq!(active_coverage(), 2_000);
+    assert_eq!(
+        client.get_insurance_pool(&settled.currency).active_coverage,
+        0
+    );
+
+    let due_date = client.get_invoice(&defaulted.invoice_id).due_date;
+    env.ledger().set_timestamp(due_date + 8 * 24 * 60 * 60);
+    client.mark_invoice_defaulted(&defaulted.invoice_id, &None);
+    assert_eq!(active_coverage(), 0);
+}
+
+// ============================================================================
+// Insurance Program Tests
+// ============================================================================
+
+#[test]
+fn test_insurance_stats_track_premiums_claims_and_coverage() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let provider = approved_insurance_provider(&env, &client);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    client.add_investment_insurance(&investment.investment_id, &provider, &60);
+    stake_insurance_pool(&env, &client, &provider, &currency, 5_400);
+
+    // 60% of 9,000 covered at a 2% premium
+    let stats = client.get_insurance_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.policies_written, 1);
+    assert_eq!(stats.premiums_collected, 108);
+    assert_eq!(stats.active_coverage, 5_400);
+    assert_eq!(stats.claims_paid, 0);
+    assert_eq!(stats.providers.len(), 1);
+
+    let invoice = client.get_invoice(&invoice_id);
+    env.ledger()
+        .set_timestamp(invoice.due_date + 7 * 24 * 60 * 60 + 1);
+    client.mark_invoice_defaulted(&invoice_id, &None);
+
+    let stats = client.get_insurance_stats(&TimePeriod::AllTime);
+    let provider_stats = stats.providers.get(0).unwrap();
+    assert_eq!(provider_stats.provider, provider);
+    assert_eq!(provider_stats.claims_filed, 1);
+    assert_eq!(provider_stats.claims_approved, 1);
+    assert_eq!(provider_stats.claims_paid, 1);
+    assert_eq!(provider_stats.claims_paid_amount, 5_400);
+    assert_eq!(provider_stats.loss_ratio_bps, 500_000);
+    assert_eq!(stats.active_coverage, 0);
+
+    // Activity outside the period is excluded
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + 2 * 24 * 60 * 60);
+    assert_eq!(
+        client.get_insurance_stats(&TimePeriod::Daily).claims_paid,
+        0
+    );
+}
+
+#[test]
+fn test_default_pays_insurance_claim_from_pool() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider = approved_insurance_provider(&env, &client);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    client.add_investment_insurance(&investment.investment_id, &provider, &60);
+    stake_insurance_pool(&env, &client, &provider, &currency, 4_000);
+
+    // The 108 premium joins the stake; the pool cannot be drained below 5,400 of coverage
+    assert_eq!(
+        client.get_insurance_stake(&provider, &currency).value,
+        4_108
+    );
+    assert_eq!(
+        client.try_unstake_insurance_pool(&provider, &currency, &1),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+
+    let invoice = client.get_invoice(&invoice_id);
+    env.ledger()
+        .set_timestamp(invoice.due_date + 7 * 24 * 60 * 60 + 1);
+    let investor_before = token_client.balance(&investor);
+    client.mark_invoice_defaulted(&invoice_id, &None);
+
+    // The pool pays what it holds; the rest stays owed and the drained stake is void
+    assert_eq!(token_client.balance(&investor) - investor_before, 4_108);
+    let claim = client
+        .get_insurance_claim(&investment.investment_id)
+        .unwrap();
+    assert_eq!(claim.coverage_amount, 5_400);
+    assert_eq!(claim.paid_amount, 4_108);
+    assert_eq!(client.get_insurance_stake(&provider, &currency).shares, 0);
+    assert_eq!(client.get_insurance_pool(&currency).owed_claims, 1_292);
+
+    stake_insurance_pool(&env, &client, &provider, &currency, 2_000);
+    assert_eq!(
+        client.try_unstake_insurance_pool(&provider, &currency, &1_000),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    let claim = client.pay_insurance_claim(&investment.investment_id);
+    assert_eq!(claim.paid_amount, 5_400);
+    assert_eq!(token_client.balance(&investor) - investor_before, 5_400);
+
+    // Once the claim is paid in full, the surplus can be withdrawn
+    assert_eq!(
+        client
+            .unstake_insurance_pool(&provider, &currency, &708)
+            .value,
+        0
+    );
+    assert_eq!(token_client.balance(&provider), 708);
+    let stats = client.get_insurance_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.claims_paid_amount, 5_400);
+
+    let trail = client.get_invoice_audit_trail(&invoice_id);
+    let paid_entries = trail
+        .iter()
+        .filter(|audit_id| {
+            client.get_audit_entry(audit_id).operation
+                == crate::audit::AuditOperation::InsuranceClaimPaid
+        })
+        .count();
+    assert_eq!(paid_entries, 2);
+}
+
+#[test]
+fn test_insurance_pool_shares_premiums_and_losses_between_stakers() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider_a = approved_insurance_provider(&env, &client);
+    let provider_b = Address::generate(&env);
+    client.set_insurance_pool_lockup(&86_400);
+
+    stake_insurance_pool(&env, &client, &provider_a, &currency, 6_000);
+    stake_insurance_pool(&env, &client, &provider_b, &currency, 2_000);
+    let stake_b = client.get_insurance_stake(&provider_b, &currency);
+    assert_eq!(stake_b.shares, 2_000);
+    assert_eq!(stake_b.locked_until, env.ledger().timestamp() + 86_400);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    client.add_investment_insurance(&investment.investment_id, &provider_a, &60);
+
+    // 5,400 of coverage against 8,108 of assets
+    let pool = client.get_insurance_pool(&currency);
+    assert_eq!(pool.total_assets, 8_108);
+    assert_eq!(pool.active_coverage, 5_400);
+    assert_eq!(pool.utilization_bps, Some(6_660));
+    assert_eq!(pool.available, 2_708);
+    assert_eq!(
+        client.try_unstake_insurance_pool(&provider_b, &currency, &1),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    let invoice = client.get_invoice(&invoice_id);
+    env.ledger()
+        .set_timestamp(invoice.due_date + 7 * 24 * 60 * 60 + 1);
+    client.mark_invoice_defaulted(&invoice_id, &None);
+
+    // Both stakers bear the claim in proportion to their shares
+    assert_eq!(
+        client.get_insurance_stake(&provider_a, &currency).value,
+        2_031
+    );
+    assert_eq!(
+        client.get_insurance_stake(&provider_b, &currency).value,
+        677
+    );
+    let state = client.get_insurance_pool_state(&currency);
+    assert_eq!(state.premiums_collected, 108);
+    assert_eq!(state.claims_paid, 5_400);
+
+    assert_eq!(
+        client.try_unstake_insurance_pool(&provider_a, &currency, &2_032),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    let stake_b = client.unstake_insurance_pool(&provider_b, &currency, &677);
+    assert_eq!(stake_b.shares, 0);
+    assert_eq!(token_client.balance(&provider_b), 677);
+    assert_eq!(
+        client.get_insurance_stake(&provider_a, &currency).value,
+        2_031
+    );
+
+    assert_eq!(
+        client.try_set_insurance_pool_lockup(&(91 * 24 * 60 * 60)),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_only_approved_providers_within_capacity_can_insure() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let provider = Address::generate(&env);
+
+    let mut investment_ids = Vec::new(&env);
+    for _ in 0..2 {
+        let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+        let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+        client.accept_bid(&invoice_id, &bid_id);
+        investment_ids.push_back(client.get_invoice_investment(&invoice_id).investment_id);
+    }
+    let first = investment_ids.get(0).unwrap();
+    let second = investment_ids.get(1).unwrap();
+
+    // Unregistered and pending providers cannot be named on coverage
+    assert_eq!(
+        client.try_add_investment_insurance(&first, &provider, &60),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    let record = client.register_insurance_provider(&provider, &5_000);
+    assert_eq!(record.status, InsuranceProviderStatus::Pending);
+    assert_eq!(
+        client.try_add_investment_insurance(&first, &provider, &60),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_suspend_provider(&provider),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // 60% of 9,000 exceeds the 5,000 capacity until the admin raises it
+    client.approve_provider(&provider);
+    assert_eq!(
+        client.try_add_investment_insurance(&first, &provider, &60),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+    client.set_provider_capacity(&provider, &6_000);
+    client.add_investment_insurance(&first, &provider, &60);
+    let usage = client.get_insurance_provider_capacity(&provider).unwrap();
+    assert_eq!(usage.capacity, 6_000);
+    assert_eq!(usage.outstanding_coverage, 5_400);
+    assert_eq!(usage.remaining, 600);
+    assert_eq!(
+        client.try_add_investment_insurance(&second, &provider, &7),
+        Err(Ok(QuickLendXError::InsufficientFunds))
+    );
+
+    // Suspension blocks new coverage but leaves existing coverage in force
+    client.suspend_provider(&provider);
+    assert_eq!(
+        client.try_add_investment_insurance(&second, &provider, &5),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert!(
+        client
+            .get_investment(&first)
+            .insurance
+            .get(0)
+            .unwrap()
+            .active
+    );
+
+    env.ledger().set_timestamp(env.ledger().timestamp() + 100);
+    let record = client.approve_provider(&provider);
+    assert_eq!(record.approved_at, Some(env.ledger().timestamp()));
+    client.add_investment_insurance(&second, &provider, &5);
+    assert_eq!(
+        client.try_add_investment_insurance(&second, &provider, &5),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client
+            .get_insurance_provider_capacity(&provider)
+            .unwrap()
+            .remaining,
+        150
+    );
+
+    assert_eq!(client.get_insurance_providers().len(), 1);
+    assert_eq!(
+        client.try_register_insurance_provider(&provider, &5_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_register_insurance_provider(&Address::generate(&env), &0),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_insurance_premium_split_between_pool_and_treasury() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider = approved_insurance_provider(&env, &client);
+    let treasury = Address::generate(&env);
+    client.configure_treasury(&treasury);
+    client.set_insurance_premium_fee(&2_500);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    let investor_before = token_client.balance(&investor);
+    client.add_investment_insurance(&investment.investment_id, &provider, &60);
+
+    // The 108 premium: a quarter to the treasury, the rest to the pool
+    assert_eq!(investor_before - token_client.balance(&investor), 108);
+    assert_eq!(token_client.balance(&treasury), 27);
+    let pool = client.get_insurance_pool_state(&currency);
+    assert_eq!(pool.premiums_collected, 81);
+    assert_eq!(pool.total_assets, 81);
+    assert_eq!(
+        client
+            .get_insurance_stats(&TimePeriod::AllTime)
+            .premiums_collected,
+        108
+    );
+
+    assert_eq!(
+        client.try_set_insurance_premium_fee(&5_001),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_insurance_premium_follows_risk_schedule() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider = approved_insurance_provider(&env, &client);
+
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment_id = client.get_invoice_investment(&invoice_id).investment_id;
+
+    // The default schedule is the flat 2% of the covered amount
+    let quote = client.quote_insurance_premium(&investment_id, &60);
+    assert_eq!(quote.coverage_amount, 5_400);
+    assert_eq!(quote.rate_bps, 200);
+    assert_eq!(quote.premium, 108);
+
+    let band = |up_to: u64, adjustment_bps: i32| PremiumBand {
+        up_to,
+        adjustment_bps,
+    };
+    let mut schedule = PremiumSchedule {
+        base_bps: 200,
+        risk_bands: Vec::from_array(&env, [band(1_000, 100), band(10_000, 300)]),
+        category_adjustments: Vec::from_array(
+            &env,
+            [CategoryPremium {
+                category: InvoiceCategory::Services,
+                adjustment_bps: 50,
+            }],
+        ),
+        coverage_bands: Vec::from_array(&env, [band(50, 0), band(100, 150)]),
+        term_bands: Vec::from_array(
+            &env,
+            [band(7 * 24 * 60 * 60, -50), band(90 * 24 * 60 * 60, 100)],
+        ),
+    };
+    client.set_insurance_premium_schedule(&schedule);
+
+    // Low risk, a Services invoice, 60% cover and a one-day term: 200 + 100 + 50 + 150 - 50
+    let quote = client.quote_insurance_premium(&investment_id, &60);
+    assert!(quote.default_probability_bps <= 1_000);
+    assert_eq!(quote.risk_adjustment_bps, 100);
+    assert_eq!(quote.category_adjustment_bps, 50);
+    assert_eq!(quote.coverage_adjustment_bps, 150);
+    assert_eq!(quote.term_adjustment_bps, -50);
+    assert_eq!(quote.term_seconds, 86_400);
+    assert_eq!(quote.rate_bps, 450);
+    assert_eq!(quote.premium, 243);
+    // Lighter cover falls in the cheaper coverage band
+    assert_eq!(
+        client.quote_insurance_premium(&investment_id, &50).rate_bps,
+        300
+    );
+    assert_eq!(
+        client.try_quote_insurance_premium(&investment_id, &101),
+        Err(Ok(QuickLendXError::InvalidCoveragePercentage))
+    );
+
+    let investor_before = token_client.balance(&investor);
+    client.add_investment_insurance(&investment_id, &provider, &60);
+    assert_eq!(investor_before - token_client.balance(&investor), 243);
+    let investment = client.get_investment(&investment_id);
+    assert_eq!(investment.insurance.get(0).unwrap().premium_amount, 243);
+
+    schedule.term_bands = Vec::from_array(&env, [band(100, 0), band(100, 0)]);
+    assert_eq!(
+        client.try_set_insurance_premium_schedule(&schedule),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    schedule.term_bands = Vec::from_array(&env, [band(100, 5_001)]);
+    assert_eq!(
+        client.try_set_insurance_premium_schedule(&schedule),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_low_credit_invoices_require_insurance_at_funding() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider = approved_insurance_provider(&env, &client);
+
+    // Invoices verified before the policy carry no requirement
+    let unrestricted = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    assert_eq!(
+        client.get_invoice(&unrestricted).required_insurance_pct,
+        None
+    );
+
+    // A business without repayment history scores 9,500, below the threshold
+    assert_eq!(client.get_business_credit_score(&business), 9_500);
+    client.set_insurance_requirement(&Some(InsuranceRequirement {
+        min_credit_score: 9_600,
+        min_coverage_percentage: 50,
+    }));
+    let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+    assert_eq!(
+        client.get_invoice(&invoice_id).required_insurance_pct,
+        Some(50)
+    );
+
+    let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+    assert_eq!(
+        client.try_accept_bid_and_fund(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.set_bid_insurance(
+        &bid_id,
+        &Some(BidInsurance {
+            provider: provider.clone(),
+            coverage_percentage: 40,
+        }),
+    );
+    assert_eq!(
+        client.try_accept_bid_and_fund(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // With enough coverage attached, acceptance buys it along with funding the escrow
+    client.set_bid_insurance(
+        &bid_id,
+        &Some(BidInsurance {
+            provider: provider.clone(),
+            coverage_percentage: 60,
+        }),
+    );
+    let investor_before = token_client.balance(&investor);
+    client.accept_bid_and_fund(&invoice_id, &bid_id);
+    assert_eq!(
+        investor_before - token_client.balance(&investor),
+        9_000 + 108
+    );
+    let investment = client.get_invoice_investment(&invoice_id);
+    let coverage = investment.insurance.get(0).unwrap();
+    assert!(coverage.active);
+    assert_eq!(coverage.provider, provider);
+    assert_eq!(coverage.coverage_amount, 5_400);
+    assert_eq!(client.get_bid_insurance(&bid_id), None);
+
+    assert_eq!(
+        client.try_set_insurance_requirement(&Some(InsuranceRequirement {
+            min_credit_score: 9_600,
+            min_coverage_percentage: 101,
+        })),
+        Err(Ok(QuickLendXError::InvalidCoveragePercentage))
+    );
+    client.set_insurance_requirement(&None);
+    assert_eq!(client.get_insurance_requirement(), None);
+}
+
+#[test]
+fn test_adjudicated_insurance_claims_are_reviewed_or_auto_approved() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let token_client = token::Client::new(&env, &currency);
+    let provider = approved_insurance_provider(&env, &client);
+    let review_period = 3 * 24 * 60 * 60;
+
+    assert_eq!(
+        client.try_set_insurance_claim_adjudication(&Some(ClaimAdjudication {
+            review_period: 0,
+            arbitrator: None,
+        })),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    client.set_insurance_claim_adjudication(&Some(ClaimAdjudication {
+        review_period,
+        arbitrator: None,
+    }));
+
+    let mut investment_ids = Vec::new(&env);
+    let mut due_date = 0;
+    for _ in 0..2 {
+        let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+        let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+        client.accept_bid(&invoice_id, &bid_id);
+        let investment = client.get_invoice_investment(&invoice_id);
+        client.add_investment_insurance(&investment.investment_id, &provider, &60);
+        investment_ids.push_back(investment.investment_id);
+        due_date = client.get_invoice(&invoice_id).due_date;
+    }
+    stake_insurance_pool(&env, &client, &provider, &currency, 12_000);
+    let denied_id = investment_ids.get(0).unwrap();
+    let expired_id = investment_ids.get(1).unwrap();
+
+    // Defaults no longer pay out; the investor files each claim for review
+    env.ledger().set_timestamp(due_date + 7 * 24 * 60 * 60 + 1);
+    let investor_before = token_client.balance(&investor);
+    client.check_overdue_invoices();
+    assert_eq!(token_client.balance(&investor), investor_before);
+    assert_eq!(client.get_insurance_claim(&denied_id), None);
+    assert_eq!(
+        client.try_file_insurance_claim(&Address::generate(&env), &denied_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+
+    let filed_at = env.ledger().timestamp();
+    let claim = client.file_insurance_claim(&investor, &denied_id);
+    assert_eq!(claim.status, InsuranceClaimStatus::Pending);
+    assert_eq!(claim.coverage_amount, 5_400);
+    assert_eq!(claim.review_deadline, filed_at + review_period);
+    assert_eq!(
+        client.try_file_insurance_claim(&investor, &denied_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_pay_insurance_claim(&denied_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+    assert_eq!(
+        client.try_deny_insurance_claim(&Address::generate(&env), &denied_id),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    let claim = client.deny_insurance_claim(&provider, &denied_id);
+    assert_eq!(claim.status, InsuranceClaimStatus::Denied);
+    assert_eq!(claim.resolved_at, Some(filed_at));
+    assert_eq!(
+        client.try_approve_insurance_claim(&admin, &denied_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // Nobody reviews the second claim before its deadline, so anyone can approve it
+    client.file_insurance_claim(&investor, &expired_id);
+    assert_eq!(
+        client.try_approve_expired_insurance_claim(&expired_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    env.ledger().set_timestamp(filed_at + review_period + 1);
+    assert_eq!(
+        client.try_deny_insurance_claim(&admin, &expired_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let claim = client.approve_expired_insurance_claim(&expired_id);
+    assert_eq!(claim.status, InsuranceClaimStatus::Approved);
+    assert_eq!(claim.paid_amount, 5_400);
+    assert_eq!(token_client.balance(&investor) - investor_before, 5_400);
+
+    let stats = client.get_insurance_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.claims_filed, 2);
+    assert_eq!(stats.claims_approved, 1);
+    assert_eq!(stats.claims_denied, 1);
+    assert_eq!(stats.claims_paid_amount, 5_400);
+}
+
+#[test]
+fn test_insurance_metrics_report_loss_ratios_and_uninsured_exposure() {
+    let (env, client, admin) = setup_with_admin();
+    let contract_id = client.address.clone();
+
+    let business = setup_verified_business(&env, &client, &admin);
+    let investor = setup_verified_investor(&env, &client, 50_000);
+    let currency = setup_token(&env, &business, &investor, &contract_id);
+    let provider = approved_insurance_provider(&env, &client);
+
+    let mut invoice_ids = Vec::new(&env);
+    for _ in 0..2 {
+        let invoice_id = create_verified_invoice(&env, &client, &business, 10_000, &currency);
+        let bid_id = place_test_bid(&client, &investor, &invoice_id, 9_000, 10_000);
+        client.accept_bid(&invoice_id, &bid_id);
+        invoice_ids.push_back(invoice_id);
+    }
+    let insured = invoice_ids.get(0).unwrap();
+    let investment = client.get_invoice_investment(&insured);
+    client.add_investment_insurance(&investment.investment_id, &provider, &60);
+    stake_insurance_pool(&env, &client, &provider, &currency, 5_400);
+
+    // 5,400 of the 18,000 funded is covered
+    let metrics = client.get_insurance_metrics(&TimePeriod::AllTime);
+    assert_eq!(metrics.total_premiums, 108);
+    assert_eq!(metrics.total_claims_paid, 0);
+    assert_eq!(metrics.funded_exposure, 18_000);
+    assert_eq!(metrics.coverage_outstanding, 5_400);
+    assert_eq!(metrics.uninsured_exposure, 12_600);
+
+    let invoice = client.get_invoice(&insured);
+    env.ledger()
+        .set_timestamp(invoice.due_date + 7 * 24 * 60 * 60 + 1);
+    client.mark_invoice_defaulted(&insured, &None);
+
+    let metrics = client.get_insurance_metrics(&TimePeriod::AllTime);
+    assert_eq!(metrics.total_claims_paid, 5_400);
+    assert_eq!(metrics.loss_ratio_bps, 500_000);
+    assert_eq!(metrics.loss_ratio_by_provider.len(), 1);
+    assert_eq!(
+        metrics.loss_ratio_by_provider.get(0).unwrap(),
+        (provider.clone(), 500_000)
+    );
+    assert_eq!(metrics.funded_exposure, 9_000);
+    assert_eq!(metrics.coverage_outstanding, 0);
+    assert_eq!(metrics.uninsured_exposure, 9_000);
+
+    // Snapshots are stored per period
+    assert!(client
+        .try_update_insurance_metrics(&Address::generate(&env), &TimePeriod::AllTime)
+        .is_err());
+    client.update_insurance_metrics(&admin, &TimePeriod::AllTime);
+    assert_eq!(
+        client.get_stored_insurance_metrics(&TimePeriod::AllTime),
+        Some(metrics)
+    );
+    assert_eq!(
+        client.get_stored_insurance_metrics(&TimePeriod::Daily),
+        None
+    );
+}
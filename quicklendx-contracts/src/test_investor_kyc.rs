@@ -322,7 +322,40 @@ mod test_investor_kyc {
         assert!(result.is_err(), "Bid exceeding investment limit must fail");
 
         let error = result.unwrap_err().unwrap();
-        assert_eq!(error, QuickLendXError::InvalidAmount);
+        assert_eq!(error, QuickLendXError::BidExceedsLimit);
+    }
+
+    #[test]
+    fn test_bid_requires_current_suitability_acknowledgment() {
+        let (env, client, _admin) = setup();
+        let investor = Address::generate(&env);
+        let business = Address::generate(&env);
+        let _ = client.try_submit_investor_kyc(&investor, &String::from_str(&env, "KYC data"));
+        let _ = client.try_verify_investor(&investor, &100_000);
+
+        let first = soroban_sdk::BytesN::from_array(&env, &[1u8; 32]);
+        let second = soroban_sdk::BytesN::from_array(&env, &[2u8; 32]);
+        assert_eq!(client.set_suitability_disclosure(&first).version, 1);
+
+        let invoice_id = create_verified_invoice(&env, &client, &business, 50_000);
+        let result = client.try_place_bid(&investor, &invoice_id, &10_000, &12_000);
+        assert_eq!(result.unwrap_err().unwrap(), QuickLendXError::OperationNotAllowed);
+
+        // The hash must match the published disclosure
+        let result = client.try_acknowledge_suitability(&investor, &second);
+        assert_eq!(result.unwrap_err().unwrap(), QuickLendXError::InvalidDescription);
+        let ack = client.acknowledge_suitability(&investor, &first);
+        assert_eq!(ack.version, 1);
+        assert_eq!(client.get_suitability_acknowledgment(&investor), Some(ack));
+        client.place_bid(&investor, &invoice_id, &10_000, &12_000);
+
+        // A new disclosure version requires re-acknowledgment
+        client.set_suitability_disclosure(&second);
+        let other_invoice = create_verified_invoice(&env, &client, &business, 50_000);
+        let result = client.try_place_bid(&investor, &other_invoice, &10_000, &12_000);
+        assert_eq!(result.unwrap_err().unwrap(), QuickLendXError::OperationNotAllowed);
+        assert_eq!(client.acknowledge_suitability(&investor, &second).version, 2);
+        client.place_bid(&investor, &other_invoice, &10_000, &12_000);
     }
 
     #[test]
@@ -0,0 +1,287 @@
+use super::*;
+use crate::analytics::TimePeriod;
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::keeper::KeeperOperation;
+use crate::recurring::RecurringTemplate;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, Address, BytesN, Env, String,
+};
+
+fn setup() -> (Env, QuickLendXContractClient<'static>, Address) {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000_000);
+    let contract_id = env.register(QuickLendXContract, ());
+    let client = QuickLendXContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    (env, client, admin)
+}
+
+#[test]
+fn test_keeper_stats_empty() {
+    let (_env, client, _admin) = setup();
+
+    let stats = client.get_keeper_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.total_calls, 0);
+    assert_eq!(stats.calls_by_operation.len(), 0);
+    assert_eq!(stats.last_call_at, None);
+}
+
+#[test]
+fn test_keeper_calls_are_recorded_by_operation() {
+    let (env, client, admin) = setup();
+    let invoice_id = BytesN::from_array(&env, &[7u8; 32]);
+
+    client.cleanup_expired_bids(&invoice_id);
+    client.check_overdue_invoices_grace(&86_400u64);
+    client.check_overdue_invoices_grace(&86_400u64);
+    client.update_platform_metrics(&admin);
+
+    let stats = client.get_keeper_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.total_calls, 4);
+    assert_eq!(stats.total_rewards_paid, 0);
+    assert_eq!(stats.last_call_at, Some(1_000_000));
+    assert_eq!(stats.identified_callers.len(), 1);
+    assert_eq!(stats.identified_callers.get(0).unwrap(), admin);
+
+    let mut overdue_calls = 0;
+    for count in stats.calls_by_operation.iter() {
+        if count.operation == KeeperOperation::OverdueCheck {
+            overdue_calls = count.calls;
+        }
+    }
+    assert_eq!(overdue_calls, 2);
+    assert_eq!(stats.calls_by_operation.len(), 3);
+}
+
+#[test]
+fn test_keeper_stats_respect_period() {
+    let (env, client, _admin) = setup();
+    let invoice_id = BytesN::from_array(&env, &[7u8; 32]);
+
+    client.cleanup_expired_bids(&invoice_id);
+    env.ledger().set_timestamp(1_000_000 + 3 * 86_400);
+    client.check_overdue_invoices_grace(&86_400u64);
+
+    let daily = client.get_keeper_stats(&TimePeriod::Daily);
+    assert_eq!(daily.total_calls, 1);
+    assert_eq!(
+        daily.calls_by_operation.get(0).unwrap().operation,
+        KeeperOperation::OverdueCheck
+    );
+
+    let all_time = client.get_keeper_stats(&TimePeriod::AllTime);
+    assert_eq!(all_time.total_calls, 2);
+}
+
+#[test]
+fn test_mint_due_recurring_invoices_follows_schedule() {
+    let (env, client, admin) = setup();
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let currency = Address::generate(&env);
+    let day = 24 * 60 * 60;
+    let template = RecurringTemplate {
+        amount: 5_000,
+        currency: currency.clone(),
+        description: String::from_str(&env, "Monthly retainer"),
+        category: InvoiceCategory::Services,
+        customer_name: String::from_str(&env, "Acme Corp"),
+        interval_seconds: 30 * day,
+        payment_terms_seconds: 45 * day,
+        total_count: 2,
+    };
+    let schedule_id = client.create_recurring_schedule(&business, &template);
+
+    // First invoice is due immediately, the next only after the interval
+    assert_eq!(client.mint_due_recurring_invoices(), 1);
+    assert_eq!(client.mint_due_recurring_invoices(), 0);
+    env.ledger().set_timestamp(1_000_000 + 30 * day);
+    assert_eq!(client.mint_due_recurring_invoices(), 1);
+    env.ledger().set_timestamp(1_000_000 + 60 * day);
+    assert_eq!(client.mint_due_recurring_invoices(), 0);
+
+    let schedule = client.get_recurring_schedule(&schedule_id).unwrap();
+    assert!(!schedule.active);
+    assert_eq!(schedule.minted_count, 2);
+    assert_eq!(schedule.invoice_ids.len(), 2);
+    let second = client.get_invoice(&schedule.invoice_ids.get(1).unwrap());
+    assert_eq!(second.status, InvoiceStatus::Pending);
+    assert_eq!(second.amount, 5_000);
+    assert_eq!(second.due_date, 1_000_000 + 75 * day);
+    assert_eq!(
+        second.metadata_customer_name,
+        Some(String::from_str(&env, "Acme Corp"))
+    );
+    assert_eq!(
+        client.get_invoice_recurring_schedule(&second.id),
+        Some(schedule_id.clone())
+    );
+    assert_eq!(client.get_business_recurring_schedules(&business).len(), 1);
+    assert_eq!(
+        client.try_cancel_recurring_schedule(&schedule_id),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    let stats = client.get_keeper_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.total_calls, 4);
+    assert_eq!(
+        stats.calls_by_operation.get(0).unwrap().operation,
+        KeeperOperation::RecurringMint
+    );
+}
+
+#[test]
+fn test_cancelled_recurring_schedule_stops_minting() {
+    let (env, client, admin) = setup();
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let mut template = RecurringTemplate {
+        amount: 1_000,
+        currency: Address::generate(&env),
+        description: String::from_str(&env, "Weekly supply"),
+        category: InvoiceCategory::Products,
+        customer_name: String::from_str(&env, "Globex"),
+        interval_seconds: 7 * 24 * 60 * 60,
+        payment_terms_seconds: 14 * 24 * 60 * 60,
+        total_count: 4,
+    };
+    let schedule_id = client.create_recurring_schedule(&business, &template);
+    client.cancel_recurring_schedule(&schedule_id);
+    assert_eq!(client.mint_due_recurring_invoices(), 0);
+
+    template.interval_seconds = 60;
+    assert_eq!(
+        client.try_create_recurring_schedule(&business, &template),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_create_recurring_schedule(&outsider, &template),
+        Err(Ok(QuickLendXError::BusinessNotVerified))
+    );
+}
+
+#[test]
+fn test_stale_verifications_revert_to_pending() {
+    let (env, client, admin) = setup();
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+    let currency = Address::generate(&env);
+    let day = 24 * 60 * 60;
+    let upload = |description: &str| {
+        client.upload_invoice(
+            &business,
+            &5_000,
+            &currency,
+            &(1_000_000 + 90 * day),
+            &String::from_str(&env, description),
+            &InvoiceCategory::Services,
+            &Vec::new(&env),
+        )
+    };
+    let older = upload("Older invoice");
+    let newer = upload("Newer invoice");
+    client.verify_invoice(&older);
+    env.ledger().set_timestamp(1_000_000 + 10 * day);
+    client.verify_invoice(&newer);
+
+    // Nothing goes stale while the window is disabled
+    assert_eq!(client.get_verification_stale_at(&older), None);
+    client.set_verification_staleness(&(30 * day));
+    assert_eq!(
+        client.get_verification_stale_at(&older),
+        Some(1_000_000 + 30 * day)
+    );
+
+    env.ledger().set_timestamp(1_000_000 + 30 * day + 1);
+    let notifications_before = client.get_user_notifications(&business).len();
+    let reverted = client.revert_stale_verifications(&10);
+    assert_eq!(reverted.len(), 1);
+    assert_eq!(reverted.get(0).unwrap(), older);
+    assert_eq!(client.get_invoice(&older).status, InvoiceStatus::Pending);
+    assert_eq!(client.get_invoice(&newer).status, InvoiceStatus::Verified);
+    assert_eq!(client.get_verification_stale_at(&older), None);
+    assert_eq!(
+        client.get_user_notifications(&business).len(),
+        notifications_before + 1
+    );
+
+    // Re-verification restarts the window
+    client.verify_invoice(&older);
+    assert_eq!(
+        client.get_verification_stale_at(&older),
+        Some(env.ledger().timestamp() + 30 * day)
+    );
+    assert_eq!(client.revert_stale_verifications(&10).len(), 0);
+
+    let stats = client.get_keeper_stats(&TimePeriod::AllTime);
+    let stale_calls = stats
+        .calls_by_operation
+        .iter()
+        .find(|count| count.operation == KeeperOperation::StaleVerification)
+        .unwrap();
+    assert_eq!(stale_calls.calls, 2);
+    assert_eq!(stale_calls.items_processed, 1);
+
+    assert_eq!(
+        client.try_revert_stale_verifications(&21),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_set_verification_staleness(&(366 * day)),
+        Err(Ok(QuickLendXError::InvalidTimestamp))
+    );
+}
+
+#[test]
+fn test_keeper_paid_for_expired_bid_cleanup_from_platform_fees() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let token_client = token::Client::new(env, &currency);
+    client.set_keeper_reward_config(&2, &5, &5_000);
+
+    // Half of the platform fee on the 1,000 profit goes to the reward pool
+    let funded = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &funded, &9_000, &10_000);
+    client.accept_bid_and_fund(&funded, &bid_id);
+    client.settle_invoice(&funded, &10_000);
+    let pool = client.get_keeper_reward_pool(&currency);
+    assert_eq!(pool, 10);
+
+    let open = scenario.verified_invoice(&business, &currency, 10_000);
+    let investor2 = scenario.verified_investor();
+    let keeper = scenario.verified_investor();
+    let mut expiry = 0;
+    for (bidder, amount) in [(&investor, 4_000), (&investor2, 5_000), (&keeper, 6_000)] {
+        let bid_id = client.place_bid(bidder, &open, &amount, &(amount + 500));
+        expiry = client.get_bid(&bid_id).unwrap().expiration_timestamp;
+    }
+
+    // Nothing expired yet: no reward
+    assert_eq!(client.keeper_cleanup_expired_bids(&keeper, &open), 0);
+
+    // Three expired bids, but the keeper's own is not rewarded: 2 x 2 (a third would hit
+    // the cap of 5 per call)
+    env.ledger().set_timestamp(expiry + 1);
+    assert_eq!(client.keeper_cleanup_expired_bids(&keeper, &open), 4);
+    assert_eq!(token_client.balance(&keeper), 4);
+    assert_eq!(client.get_keeper_reward_pool(&currency), pool - 4);
+
+    let stats = client.get_keeper_stats(&TimePeriod::AllTime);
+    assert_eq!(stats.total_rewards_paid, 4);
+
+    assert_eq!(
+        client.try_set_keeper_reward_config(&-1, &5, &5_000),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+}
@@ -0,0 +1,381 @@
+//! Property-based state machine tests for the invoice lifecycle.
+//!
+//! Each case drives the contract through a random sequence of operations (upload, verify,
+//! bid, accept, pay, dispute, default, cancel) generated from a fixed seed, ignoring the
+//! operations the contract rejects, and checks the invariants after every step:
+//! - storage indexes agree with each invoice's status (`InvoiceStorage::check_invariants`)
+//! - at most one active investment per invoice, exactly one while it is funded
+//! - no negative token balances or invoice amounts
+//! - every status change is a transition of the invoice state machine
+//!
+//! A failure reports the seed and step so the sequence can be replayed.
+use crate::defaults::DEFAULT_GRACE_PERIOD;
+use crate::investment::InvestmentStorage;
+use crate::invoice::{InvoiceCategory, InvoiceStatus, InvoiceStorage};
+use crate::state_machine::{InvoiceStateMachine, TransitionRole};
+use crate::testutils::{Scenario, DEFAULT_DUE_IN_SECONDS};
+use soroban_sdk::testutils::Ledger;
+use soroban_sdk::{token, Address, BytesN, Env, IntoVal, String, TryFromVal, Val, Vec};
+
+/// Seeds of the default run, picked so that together they reach every interesting status
+/// within `STEPS_PER_CASE` steps; keep the run to a few seconds
+const SEEDS: [u64; 2] = [0, 4];
+const STEPS_PER_CASE: u32 = 22;
+/// Case count and length of the `#[ignore]`d long run
+const LONG_CASES: u64 = 12;
+const LONG_STEPS_PER_CASE: u32 = 30;
+
+/// xorshift64* generator; deterministic per seed so failures can be replayed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        (self.next() % n as u64) as u32
+    }
+
+    fn pick<T>(&mut self, items: &Vec<T>) -> Option<T>
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        if items.is_empty() {
+            None
+        } else {
+            items.get(self.below(items.len()))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Upload,
+    Verify,
+    Bid,
+    Accept,
+    Pay,
+    PartialPay,
+    Dispute,
+    Default,
+    Cancel,
+}
+
+impl Op {
+    /// Whether the operation can succeed on an invoice in `status`
+    fn applies_to(self, status: &InvoiceStatus) -> bool {
+        match self {
+            Op::Upload => false,
+            Op::Verify => *status == InvoiceStatus::Pending,
+            Op::Bid | Op::Accept => *status == InvoiceStatus::Verified,
+            Op::Pay | Op::PartialPay | Op::Dispute | Op::Default => {
+                *status == InvoiceStatus::Funded
+            }
+            Op::Cancel => matches!(status, InvoiceStatus::Pending | InvoiceStatus::Verified),
+        }
+    }
+}
+
+const OPS: [Op; 9] = [
+    Op::Upload,
+    Op::Verify,
+    Op::Bid,
+    Op::Accept,
+    Op::Pay,
+    Op::PartialPay,
+    Op::Dispute,
+    Op::Default,
+    Op::Cancel,
+];
+
+struct Model {
+    scenario: Scenario,
+    currency: Address,
+    businesses: Vec<Address>,
+    investors: Vec<Address>,
+    invoices: Vec<BytesN<32>>,
+}
+
+impl Model {
+    fn new() -> Self {
+        let scenario = Scenario::new();
+        let env = scenario.env.clone();
+        let mut businesses = Vec::new(&env);
+        let mut investors = Vec::new(&env);
+        for _ in 0..2 {
+            businesses.push_back(scenario.verified_business());
+        }
+        for _ in 0..3 {
+            investors.push_back(scenario.verified_investor());
+        }
+        let currency = scenario.create_token(&[]);
+        for party in businesses.iter().chain(investors.iter()) {
+            scenario.fund(&currency, &party);
+        }
+        Self {
+            scenario,
+            currency,
+            businesses,
+            investors,
+            invoices: Vec::new(&env),
+        }
+    }
+
+    /// Apply `op` to a random invoice, picked using the invoices' current `statuses`
+    fn apply(&mut self, op: Op, statuses: &Vec<InvoiceStatus>, rng: &mut Rng) {
+        let env = self.scenario.env.clone();
+        let client = &self.scenario.client;
+        if let Op::Upload = op {
+            let business = rng.pick(&self.businesses).unwrap();
+            let amount = 1_000 + rng.below(19) as i128 * 500;
+            let invoice_id = client.upload_invoice(
+                &business,
+                &amount,
+                &self.currency,
+                &(env.ledger().timestamp() + DEFAULT_DUE_IN_SECONDS),
+                &String::from_str(&env, "Property test invoice"),
+                &InvoiceCategory::Services,
+                &Vec::new(&env),
+            );
+            self.invoices.push_back(invoice_id);
+            return;
+        }
+
+        let Some(invoice_id) = self.pick_target(op, statuses, rng) else {
+            return;
+        };
+        let invoice = client.get_invoice(&invoice_id);
+        match op {
+            Op::Upload => {}
+            Op::Verify => {
+                let _ = client.try_verify_invoice(&invoice_id);
+            }
+            Op::Bid => {
+                let investor = rng.pick(&self.investors).unwrap();
+                let bid_amount = invoice.amount * (80 + rng.below(16) as i128) / 100;
+                let _ = client.try_place_bid(&investor, &invoice_id, &bid_amount, &invoice.amount);
+            }
+            Op::Accept => {
+                if let Some(bid) = rng.pick(&client.get_bids_for_invoice(&invoice_id)) {
+                    let _ = client.try_accept_bid(&invoice_id, &bid.bid_id);
+                }
+            }
+            Op::Pay => {
+                let _ = client.try_settle_invoice(&invoice_id, &invoice.amount);
+            }
+            Op::PartialPay => {
+                let _ = client.try_process_partial_payment(
+                    &invoice_id,
+                    &(invoice.amount / 4),
+                    &String::from_str(&env, "tx"),
+                );
+            }
+            Op::Dispute => {
+                let _ = client.try_create_dispute(
+                    &invoice_id,
+                    &invoice.business,
+                    &String::from_str(&env, "Disputed amount"),
+                    &String::from_str(&env, "Evidence"),
+                );
+            }
+            Op::Default => {
+                let past_grace = invoice.due_date + DEFAULT_GRACE_PERIOD + 1;
+                if env.ledger().timestamp() < past_grace {
+                    env.ledger().set_timestamp(past_grace);
+                }
+                let _ = client.try_mark_invoice_defaulted(&invoice_id, &None);
+            }
+            Op::Cancel => {
+                let _ = client.try_cancel_invoice(&invoice_id);
+            }
+        }
+    }
+
+    /// Usually an invoice the operation applies to, sometimes any invoice so that rejected
+    /// operations are exercised too
+    fn pick_target(
+        &self,
+        op: Op,
+        statuses: &Vec<InvoiceStatus>,
+        rng: &mut Rng,
+    ) -> Option<BytesN<32>> {
+        if rng.below(4) == 0 {
+            return rng.pick(&self.invoices);
+        }
+        let mut candidates = Vec::new(&self.scenario.env);
+        for (invoice_id, status) in self.invoices.iter().zip(statuses.iter()) {
+            if op.applies_to(&status) {
+                candidates.push_back(invoice_id);
+            }
+        }
+        rng.pick(&candidates).or_else(|| rng.pick(&self.invoices))
+    }
+
+    /// Status of every invoice, read straight from storage to keep long sequences fast
+    fn statuses(&self) -> Vec<InvoiceStatus> {
+        let env = &self.scenario.env;
+        env.as_contract(&self.scenario.client.address, || {
+            let mut statuses = Vec::new(env);
+            for invoice_id in self.invoices.iter() {
+                statuses.push_back(
+                    InvoiceStorage::get_invoice(env, &invoice_id)
+                        .unwrap()
+                        .status,
+                );
+            }
+            statuses
+        })
+    }
+
+    /// Assert that every invoice whose status changed from `before` to `after` made a legal
+    /// transition
+    fn check_transitions(
+        &self,
+        before: &Vec<InvoiceStatus>,
+        after: &Vec<InvoiceStatus>,
+        seed: u64,
+        step: u32,
+        op: Op,
+    ) {
+        for (old, new) in before.iter().zip(after.iter()) {
+            assert!(
+                old == new || !InvoiceStateMachine::roles_for(&old, &new).is_empty(),
+                "seed {seed} step {step} ({op:?}): illegal transition {old:?} -> {new:?}"
+            );
+        }
+    }
+
+    /// Assert the invariants given the invoices' current `statuses` and return a bitmask of
+    /// the statuses present
+    fn check_invariants(&self, statuses: &Vec<InvoiceStatus>, seed: u64, step: u32, op: Op) -> u32 {
+        let mut statuses_seen = 0;
+        let env = &self.scenario.env;
+        let client = &self.scenario.client;
+        for (invoice_id, status) in self.invoices.iter().zip(statuses.iter()) {
+            statuses_seen |= 1 << status.clone() as u32;
+            let (storage, active) = env.as_contract(&client.address, || {
+                (
+                    InvoiceStorage::check_invariants(env, &invoice_id),
+                    InvestmentStorage::count_active_for_invoice(env, &invoice_id, &self.investors),
+                )
+            });
+            if let Err(violation) = storage {
+                panic!("seed {seed} step {step} ({op:?}): {violation}");
+            }
+            assert!(
+                active <= 1,
+                "seed {seed} step {step} ({op:?}): {active} active investments"
+            );
+            if status == InvoiceStatus::Funded {
+                assert_eq!(
+                    active, 1,
+                    "seed {seed} step {step} ({op:?}): funded invoice without an active investment"
+                );
+            }
+        }
+
+        let token = token::Client::new(env, &self.currency);
+        let contract = client.address.clone();
+        for party in self
+            .businesses
+            .iter()
+            .chain(self.investors.iter())
+            .chain([contract])
+        {
+            assert!(
+                token.balance(&party) >= 0,
+                "seed {seed} step {step} ({op:?}): negative balance"
+            );
+        }
+        statuses_seen
+    }
+}
+
+/// Run one random sequence of `steps` operations per seed and assert that together they
+/// reached every interesting status
+fn run_random_lifecycle_sequences(seeds: impl IntoIterator<Item = u64>, steps: u32) {
+    let mut statuses_seen = 0;
+    for seed in seeds {
+        let mut rng = Rng::new(seed);
+        let mut model = Model::new();
+        for step in 0..steps {
+            // Bias towards uploads early so later operations have invoices to act on
+            let op = if model.invoices.len() < 2 {
+                Op::Upload
+            } else {
+                OPS[rng.below(OPS.len() as u32) as usize]
+            };
+            let before = model.statuses();
+            model.apply(op, &before, &mut rng);
+            let after = model.statuses();
+            model.check_transitions(&before, &after, seed, step, op);
+            statuses_seen |= model.check_invariants(&after, seed, step, op);
+        }
+    }
+
+    // The generator must actually reach the interesting states
+    for status in [
+        InvoiceStatus::Verified,
+        InvoiceStatus::Funded,
+        InvoiceStatus::Paid,
+        InvoiceStatus::Defaulted,
+        InvoiceStatus::Cancelled,
+    ] {
+        assert!(
+            statuses_seen & (1 << status.clone() as u32) != 0,
+            "no sequence reached {status:?}"
+        );
+    }
+}
+
+#[test]
+fn test_random_lifecycle_sequences_preserve_invariants() {
+    run_random_lifecycle_sequences(SEEDS, STEPS_PER_CASE);
+}
+
+/// Longer run for local soak testing: `cargo test -- --ignored test_long_random_lifecycle`
+#[test]
+#[ignore]
+fn test_long_random_lifecycle_sequences_preserve_invariants() {
+    run_random_lifecycle_sequences(0..LONG_CASES, LONG_STEPS_PER_CASE);
+}
+
+#[test]
+fn test_allowed_transitions_follow_invoice_status() {
+    let scenario = Scenario::new();
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(1_000, 900);
+
+    let transitions = client.get_allowed_transitions(&fixture.invoice_id);
+    let mut targets = Vec::new(&scenario.env);
+    for transition in transitions.iter() {
+        targets.push_back(transition.to);
+    }
+    assert_eq!(
+        targets,
+        soroban_sdk::vec![
+            &scenario.env,
+            InvoiceStatus::Paid,
+            InvoiceStatus::Defaulted,
+            InvoiceStatus::Refunded
+        ]
+    );
+    let paid = transitions.get(0).unwrap();
+    assert!(paid.roles.contains(TransitionRole::Debtor));
+    assert!(!paid.roles.contains(TransitionRole::Investor));
+
+    client.settle_invoice(&fixture.invoice_id, &1_000);
+    assert_eq!(client.get_allowed_transitions(&fixture.invoice_id).len(), 0);
+    assert_eq!(
+        client.try_update_invoice_status(&fixture.invoice_id, &InvoiceStatus::Defaulted),
+        Err(Ok(crate::errors::QuickLendXError::InvalidStatus))
+    );
+}
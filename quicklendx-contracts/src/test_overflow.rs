@@ -89,6 +89,7 @@ fn test_compare_bids_safe_overflow() {
         timestamp: 100,
         status: BidStatus::Placed,
         expiration_timestamp: 1000,
+        discount_rate_bps: None,
     };
 
     // Bid 2: MAX - 1000 return
@@ -2,11 +2,36 @@
 extern crate std;
 
 use crate::fees::FeeManager;
+use crate::invoice::{Invoice, InvoiceCategory};
 use crate::QuickLendXContract;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+
+/// Investor return and platform fee of an on-time settlement, as computed by the settlement
+/// waterfall
+fn settlement_split(env: &Env, investment_amount: i128, payment_amount: i128) -> (i128, i128) {
+    let invoice = Invoice::new(
+        env,
+        Address::generate(env),
+        payment_amount,
+        Address::generate(env),
+        env.ledger().timestamp() + 86_400,
+        String::from_str(env, "Fee test invoice"),
+        InvoiceCategory::Services,
+        Vec::new(env),
+    );
+    let waterfall = FeeManager::calculate_settlement_waterfall(
+        env,
+        &invoice,
+        &Address::generate(env),
+        investment_amount,
+        payment_amount,
+    )
+    .unwrap();
+    (waterfall.investor_return, waterfall.platform_fee)
+}
 
 #[test]
-fn test_calculate_platform_fee_full_payment() {
+fn test_settlement_platform_fee_full_payment() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -25,7 +50,7 @@ fn test_calculate_platform_fee_full_payment() {
         let payment_amount = 1100i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 2);
         assert_eq!(investor_return, 1098);
@@ -33,7 +58,7 @@ fn test_calculate_platform_fee_full_payment() {
 }
 
 #[test]
-fn test_calculate_platform_fee_no_profit() {
+fn test_settlement_platform_fee_no_profit() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -51,7 +76,7 @@ fn test_calculate_platform_fee_no_profit() {
         let payment_amount = 1000i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 0);
         assert_eq!(investor_return, 1000);
@@ -59,7 +84,7 @@ fn test_calculate_platform_fee_no_profit() {
 }
 
 #[test]
-fn test_calculate_platform_fee_partial_loss() {
+fn test_settlement_platform_fee_partial_loss() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -77,7 +102,7 @@ fn test_calculate_platform_fee_partial_loss() {
         let payment_amount = 800i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 0);
         assert_eq!(investor_return, 800);
@@ -85,7 +110,7 @@ fn test_calculate_platform_fee_partial_loss() {
 }
 
 #[test]
-fn test_calculate_platform_fee_rounding() {
+fn test_settlement_platform_fee_rounding() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -103,7 +128,7 @@ fn test_calculate_platform_fee_rounding() {
         let payment_amount = 1001i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 0);
         assert_eq!(investor_return, 1001);
@@ -111,7 +136,7 @@ fn test_calculate_platform_fee_rounding() {
 }
 
 #[test]
-fn test_calculate_platform_fee_small_fee() {
+fn test_settlement_platform_fee_small_fee() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -129,7 +154,7 @@ fn test_calculate_platform_fee_small_fee() {
         let payment_amount = 1050i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 1);
         assert_eq!(investor_return, 1049);
@@ -137,7 +162,7 @@ fn test_calculate_platform_fee_small_fee() {
 }
 
 #[test]
-fn test_calculate_platform_fee_updated_bps() {
+fn test_settlement_platform_fee_updated_bps() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -162,7 +187,7 @@ fn test_calculate_platform_fee_updated_bps() {
         let payment_amount = 1100i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 10);
         assert_eq!(investor_return, 1090);
@@ -170,7 +195,7 @@ fn test_calculate_platform_fee_updated_bps() {
 }
 
 #[test]
-fn test_calculate_platform_fee_large_numbers() {
+fn test_settlement_platform_fee_large_numbers() {
     let env = Env::default();
     env.mock_all_auths();
 
@@ -187,7 +212,7 @@ fn test_calculate_platform_fee_large_numbers() {
         let payment_amount = 2_000_000i128;
 
         let (investor_return, platform_fee) =
-            FeeManager::calculate_platform_fee(&env, investment_amount, payment_amount).unwrap();
+            settlement_split(&env, investment_amount, payment_amount);
 
         assert_eq!(platform_fee, 20_000);
         assert_eq!(investor_return, 1_980_000);
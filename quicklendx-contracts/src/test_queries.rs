@@ -2,9 +2,10 @@ use super::*;
 use crate::audit::{AuditOperation, AuditOperationFilter, AuditQueryFilter};
 use crate::bid::BidStatus;
 use crate::invoice::{InvoiceCategory, InvoiceStatus};
+use crate::invoice_template::TemplateMetadata;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    Address, BytesN, Env, String, Vec,
+    token, Address, BytesN, Env, String, Vec,
 };
 
 // Helper: basic setup returning env and client
@@ -358,3 +359,643 @@ fn test_query_audit_logs_filters_and_limit() {
         "Limit should restrict number of returned entries"
     );
 }
+
+#[test]
+fn test_deal_room_access_log_for_private_invoice() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    let business = Address::generate(&env);
+    let invoice_id = create_invoice(
+        &env,
+        &client,
+        &business,
+        5_000,
+        InvoiceCategory::Services,
+        false,
+    );
+
+    let investor = Address::generate(&env);
+    let unverified = Address::generate(&env);
+    client.submit_investor_kyc(&investor, &String::from_str(&env, "Investor KYC"));
+    client.verify_investor(&investor, &50_000);
+
+    // Public invoices have no deal room
+    assert_eq!(
+        client.try_request_access(&investor, &invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.set_invoice_private(&invoice_id, &true);
+    assert!(client.is_invoice_private(&invoice_id));
+
+    assert_eq!(
+        client.try_request_access(&unverified, &invoice_id),
+        Err(Ok(QuickLendXError::NotInvestor))
+    );
+    assert_eq!(
+        client.try_grant_access(&invoice_id, &investor),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    env.ledger().set_timestamp(100);
+    client.request_access(&investor, &invoice_id);
+    assert!(!client.has_deal_room_access(&invoice_id, &investor));
+    assert_eq!(
+        client.try_request_access(&investor, &invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(200);
+    let access = client.grant_access(&invoice_id, &investor);
+    assert_eq!(access.requested_at, 100);
+    assert_eq!(access.granted_at, Some(200));
+    assert!(client.has_deal_room_access(&invoice_id, &investor));
+    assert_eq!(
+        client.try_grant_access(&invoice_id, &investor),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+
+    // Both parties see the same entry
+    let business_log = client.get_deal_room_access_log(&invoice_id);
+    assert_eq!(business_log.len(), 1);
+    assert_eq!(business_log.get(0).unwrap(), access);
+    let investor_log = client.get_investor_deal_room_access(&investor);
+    assert_eq!(investor_log.len(), 1);
+    assert_eq!(investor_log.get(0).unwrap(), access);
+}
+
+#[test]
+fn test_upload_from_template_applies_defaults() {
+    let (env, client) = setup();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    client.set_admin(&admin);
+    let business = Address::generate(&env);
+    client.submit_kyc_application(&business, &String::from_str(&env, "Business KYC"));
+    client.verify_business(&admin, &business);
+
+    let currency = Address::generate(&env);
+    let mut tags = Vec::new(&env);
+    tags.push_back(String::from_str(&env, "logistics"));
+    let metadata = TemplateMetadata {
+        customer_name: String::from_str(&env, "Acme Corp"),
+        customer_address: String::from_str(&env, "1 Main St"),
+        tax_id: String::from_str(&env, "TAX-42"),
+        notes: String::from_str(&env, "Net 30"),
+    };
+    let template_id = client.create_invoice_template(
+        &business,
+        &String::from_str(&env, "Freight"),
+        &currency,
+        &InvoiceCategory::Services,
+        &tags,
+        &String::from_str(&env, "Freight services"),
+        &Some(metadata),
+    );
+    assert_eq!(client.get_business_invoice_templates(&business).len(), 1);
+
+    let due_date = env.ledger().timestamp() + 86_400;
+    let invoice_id = client.upload_from_template(&template_id, &7_500, &due_date);
+    let invoice = client.get_invoice(&invoice_id);
+    assert_eq!(invoice.business, business);
+    assert_eq!(invoice.amount, 7_500);
+    assert_eq!(invoice.currency, currency);
+    assert_eq!(invoice.due_date, due_date);
+    assert_eq!(invoice.category, InvoiceCategory::Services);
+    assert_eq!(invoice.tags, tags);
+    assert_eq!(invoice.status, InvoiceStatus::Pending);
+    let applied = invoice.metadata().unwrap();
+    assert_eq!(applied.tax_id, String::from_str(&env, "TAX-42"));
+    assert_eq!(applied.line_items.get(0).unwrap().3, 7_500);
+    assert_eq!(
+        client.get_invoices_by_customer(&String::from_str(&env, "Acme Corp")),
+        Vec::from_array(&env, [invoice_id])
+    );
+
+    client.delete_invoice_template(&template_id);
+    assert_eq!(client.get_invoice_template(&template_id), None);
+    assert_eq!(client.get_business_invoice_templates(&business).len(), 0);
+    assert_eq!(
+        client.try_upload_from_template(&template_id, &7_500, &due_date),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
+
+#[test]
+fn test_invoice_documents_attach_list_and_remove() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+
+    let pdf_hash = BytesN::from_array(env, &[1u8; 32]);
+    let po_hash = BytesN::from_array(env, &[2u8; 32]);
+    let pdf = client.attach_document(
+        &invoice_id,
+        &pdf_hash,
+        &String::from_str(env, "invoice_pdf"),
+        &String::from_str(env, "ipfs://bafy-invoice"),
+    );
+    assert_eq!(pdf.attached_by, business);
+    client.attach_document(
+        &invoice_id,
+        &po_hash,
+        &String::from_str(env, "purchase_order"),
+        &String::from_str(env, "ipfs://bafy-po"),
+    );
+    assert_eq!(
+        client.try_attach_document(
+            &invoice_id,
+            &pdf_hash,
+            &String::from_str(env, "invoice_pdf"),
+            &String::from_str(env, "ipfs://bafy-copy"),
+        ),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_attach_document(
+            &invoice_id,
+            &BytesN::from_array(env, &[3u8; 32]),
+            &String::from_str(env, ""),
+            &String::from_str(env, "ipfs://bafy-empty"),
+        ),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+
+    let documents = client.get_invoice_documents(&invoice_id);
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents.get(0).unwrap(), pdf);
+
+    client.remove_document(&invoice_id, &po_hash);
+    assert_eq!(client.get_invoice_documents(&invoice_id).len(), 1);
+    assert_eq!(
+        client.try_remove_document(&invoice_id, &po_hash),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    let funded = scenario.funded_invoice(10_000, 9_000);
+    client.attach_document(
+        &funded.invoice_id,
+        &pdf_hash,
+        &String::from_str(env, "invoice_pdf"),
+        &String::from_str(env, "ipfs://bafy-funded"),
+    );
+    assert_eq!(
+        client.try_remove_document(&funded.invoice_id, &pdf_hash),
+        Err(Ok(QuickLendXError::InvalidStatus))
+    );
+}
+
+#[test]
+fn test_invoice_number_lookup_is_unique_per_business() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let other_business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business, &other_business]);
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let second = scenario.verified_invoice(&business, &currency, 20_000);
+    let other = scenario.verified_invoice(&other_business, &currency, 30_000);
+
+    let number = String::from_str(env, "INV-2024-001");
+    assert_eq!(client.get_invoice_by_number(&business, &number), None);
+    client.set_invoice_number(&first, &number);
+    assert_eq!(
+        client.get_invoice_by_number(&business, &number),
+        Some(first.clone())
+    );
+    assert_eq!(
+        client.get_invoice(&first).invoice_number,
+        Some(number.clone())
+    );
+
+    // Same business, same number: rejected; another business may reuse it
+    assert_eq!(
+        client.try_set_invoice_number(&second, &number),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.set_invoice_number(&other, &number);
+    assert_eq!(
+        client.get_invoice_by_number(&other_business, &number),
+        Some(other)
+    );
+    assert_eq!(
+        client.try_set_invoice_number(&second, &String::from_str(env, "")),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+
+    // Renumbering frees the old number
+    let renumbered = String::from_str(env, "INV-2024-001-A");
+    client.set_invoice_number(&first, &renumbered);
+    assert_eq!(client.get_invoice_by_number(&business, &number), None);
+    client.set_invoice_number(&second, &number);
+    assert_eq!(
+        client.get_invoice_by_number(&business, &number),
+        Some(second)
+    );
+    assert_eq!(
+        client.get_invoice_by_number(&business, &renumbered),
+        Some(first)
+    );
+}
+
+#[test]
+fn test_export_state_digest_reports_counts_totals_and_sequences() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let first = scenario.funded_invoice(10_000, 9_000);
+    let second = scenario.funded_invoice(20_000, 18_000);
+    scenario.verified_invoice(&first.business, &first.currency, 5_000);
+    client.process_partial_payment(
+        &first.invoice_id,
+        &4_000,
+        &String::from_str(env, "tx-digest"),
+    );
+
+    let digest = client.export_state_digest();
+    assert_eq!(digest.generated_at, env.ledger().timestamp());
+    assert_eq!(digest.pending_count, 0);
+    assert_eq!(digest.verified_count, 1);
+    assert_eq!(digest.funded_count, 2);
+    assert_eq!(digest.paid_count, 0);
+    assert_eq!(digest.currencies.len(), 2);
+
+    let totals = digest.currencies.get(0).unwrap();
+    assert_eq!(totals.currency, first.currency);
+    assert_eq!(totals.escrowed, 9_000);
+    assert_eq!(totals.outstanding, 6_000);
+    assert_eq!(
+        totals.segregated_balance,
+        client.get_segregated_balance(&first.currency)
+    );
+    let totals = digest.currencies.get(1).unwrap();
+    assert_eq!(totals.currency, second.currency);
+    assert_eq!(totals.escrowed, 18_000);
+    assert_eq!(totals.outstanding, 20_000);
+
+    assert_eq!(digest.invoice_sequence, 3);
+    assert_eq!(digest.bid_sequence, 2);
+    assert_eq!(digest.escrow_sequence, 2);
+    assert_eq!(digest.investment_sequence, 2);
+    assert!(digest.audit_sequence > 0);
+}
+
+#[test]
+fn test_reconcile_escrows_flags_uncovered_escrows() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let token_client = soroban_sdk::token::Client::new(env, &fixture.currency);
+
+    let report = client.reconcile_escrows(&fixture.currency);
+    assert_eq!(report.active_escrows, 1);
+    assert_eq!(report.escrowed, 9_000);
+    assert_eq!(
+        report.segregated_balance,
+        client.get_segregated_balance(&fixture.currency)
+    );
+    assert_eq!(report.token_balance, token_client.balance(&client.address));
+    assert_eq!(report.discrepancy, report.token_balance - 9_000);
+    assert!(report.balanced);
+
+    // Tokens leaving the contract outside the protocol's accounting break the invariant
+    let drain = Address::generate(env);
+    token_client.transfer(&client.address, &drain, &report.token_balance);
+    let report = client.reconcile_escrows(&fixture.currency);
+    assert_eq!(report.token_balance, 0);
+    assert_eq!(report.discrepancy, -9_000);
+    assert!(!report.balanced);
+}
+
+#[test]
+fn test_invoice_watchers_track_involved_parties() {
+    use crate::watchers::WatcherRole;
+
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let funder = scenario.verified_investor();
+    let other_bidder = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &funder, &other_bidder]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+
+    let watchers = client.get_invoice_watchers(&invoice_id);
+    assert_eq!(watchers.len(), 1);
+    assert_eq!(watchers.get(0).unwrap().address, business);
+    assert_eq!(watchers.get(0).unwrap().role, WatcherRole::Business);
+
+    let bid_id = client.place_bid(&funder, &invoice_id, &9_000, &10_000);
+    client.place_bid(&other_bidder, &invoice_id, &8_500, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investment = client.get_invoice_investment(&invoice_id);
+    let insurer = Address::generate(env);
+    client.register_insurance_provider(&insurer, &1_000_000);
+    client.approve_provider(&insurer);
+    client.add_investment_insurance(&investment.investment_id, &insurer, &50);
+
+    let mut roles = Vec::new(env);
+    for watcher in client.get_invoice_watchers(&invoice_id).iter() {
+        roles.push_back((watcher.address, watcher.role));
+    }
+    let mut expected = Vec::new(env);
+    expected.push_back((business, WatcherRole::Business));
+    expected.push_back((funder.clone(), WatcherRole::Bidder));
+    expected.push_back((other_bidder, WatcherRole::Bidder));
+    expected.push_back((funder, WatcherRole::Funder));
+    expected.push_back((insurer, WatcherRole::Insurer));
+    assert_eq!(roles, expected);
+}
+
+#[test]
+fn test_list_version_detects_mutation_between_pages() {
+    use crate::list_versions::ListIndex;
+
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    scenario.verified_invoice(&business, &currency, 5_000);
+    let business_index = ListIndex::BusinessInvoices(business.clone());
+    let verified_index = ListIndex::InvoicesByStatus(InvoiceStatus::Verified);
+
+    // Paging without mutations leaves the version unchanged
+    let version = client.get_list_version(&business_index);
+    let first = client.get_business_invoices_paged(&business, &None, &0, &1);
+    let second = client.get_business_invoices_paged(&business, &None, &1, &1);
+    assert_eq!(client.get_list_version(&business_index), version);
+    assert_ne!(first, second);
+
+    scenario.verified_invoice(&business, &currency, 2_500);
+    assert_ne!(client.get_list_version(&business_index), version);
+
+    // A status change moves the invoice between status lists, not the business list
+    let business_version = client.get_list_version(&business_index);
+    let verified_version = client.get_list_version(&verified_index);
+    let verified =
+        client.get_business_invoices_paged(&business, &Some(InvoiceStatus::Verified), &0, &10);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(client.get_list_version(&business_index), business_version);
+    assert_ne!(client.get_list_version(&verified_index), verified_version);
+    let verified_after =
+        client.get_business_invoices_paged(&business, &Some(InvoiceStatus::Verified), &0, &10);
+    assert_eq!(verified_after.len(), verified.len() - 1);
+}
+
+#[test]
+fn test_list_versions_track_index_mutations() {
+    use crate::list_versions::ListIndex;
+
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let business_index = ListIndex::BusinessInvoices(business.clone());
+    let verified_index = ListIndex::InvoicesByStatus(InvoiceStatus::Verified);
+    assert_eq!(client.get_list_version(&business_index), 0);
+
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let business_version = client.get_list_version(&business_index);
+    let verified_version = client.get_list_version(&verified_index);
+    assert!(business_version > 0);
+    assert!(verified_version > 0);
+
+    let bids_index = ListIndex::InvoiceBids(invoice_id.clone());
+    let investor_bids_index = ListIndex::InvestorBids(investor.clone());
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+    let bids_version = client.get_list_version(&bids_index);
+    assert!(bids_version > 0);
+    assert!(client.get_list_version(&investor_bids_index) > 0);
+
+    client.accept_bid(&invoice_id, &bid_id);
+    assert!(client.get_list_version(&verified_index) > verified_version);
+    assert!(client.get_list_version(&bids_index) > bids_version);
+    assert!(client.get_list_version(&ListIndex::InvestorInvestments(investor)) > 0);
+    assert_eq!(client.get_list_version(&business_index), business_version);
+}
+
+#[test]
+fn test_boosted_invoices_listed_first_until_boost_expires() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let currency = scenario.create_token(&[&business]);
+    let treasury = Address::generate(env);
+    client.configure_treasury(&treasury);
+
+    let first = scenario.verified_invoice(&business, &currency, 10_000);
+    let second = scenario.verified_invoice(&business, &currency, 10_000);
+    let third = scenario.verified_invoice(&business, &currency, 10_000);
+    assert_eq!(
+        client.try_boost_invoice(&third, &2),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    client.set_boost_config(&50, &7);
+    assert_eq!(
+        client.try_boost_invoice(&third, &8),
+        Err(Ok(QuickLendXError::InvalidAmount))
+    );
+    let start = env.ledger().timestamp();
+    let boosted = client.boost_invoice(&third, &2);
+    assert_eq!(boosted.boosted_until, Some(start + 2 * 86_400));
+    assert_eq!(
+        soroban_sdk::token::Client::new(env, &currency).balance(&treasury),
+        100
+    );
+
+    let page = client.get_available_invoices_paged(&None, &None, &None, &0, &10);
+    assert_eq!(
+        page,
+        Vec::from_array(env, [third.clone(), first.clone(), second.clone()])
+    );
+
+    // Boosting again extends from the current expiry
+    let boosted = client.boost_invoice(&third, &1);
+    assert_eq!(boosted.boosted_until, Some(start + 3 * 86_400));
+    let trail = client.get_invoice_audit_trail(&third);
+    let entry = client.get_audit_entry(&trail.get(trail.len() - 1).unwrap());
+    assert_eq!(entry.operation, AuditOperation::InvoiceBoosted);
+    assert_eq!(entry.amount, Some(50));
+
+    env.ledger().set_timestamp(start + 3 * 86_400);
+    let page = client.get_available_invoices_paged(&None, &None, &None, &0, &10);
+    assert_eq!(page, Vec::from_array(env, [first, second, third]));
+}
+
+#[test]
+fn test_invoice_message_board_for_participants() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let invoice_id = &fixture.invoice_id;
+    let outsider = Address::generate(env);
+
+    let message = client.post_invoice_message(
+        invoice_id,
+        &fixture.business,
+        &String::from_str(env, "Shipment confirmed by the buyer"),
+    );
+    assert_eq!(message.message_id, 0);
+    client.post_invoice_message(
+        invoice_id,
+        &fixture.investor,
+        &String::from_str(env, "Thanks, noted"),
+    );
+
+    // Only the business and investors may post, within the size limit
+    assert_eq!(
+        client.try_post_invoice_message(invoice_id, &outsider, &String::from_str(env, "Hi")),
+        Err(Ok(QuickLendXError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_post_invoice_message(invoice_id, &fixture.investor, &String::from_str(env, "")),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+    let long = [b'a'; 501];
+    assert_eq!(
+        client.try_post_invoice_message(
+            invoice_id,
+            &fixture.investor,
+            &String::from_bytes(env, &long)
+        ),
+        Err(Ok(QuickLendXError::InvalidDescription))
+    );
+
+    for _ in 0..3 {
+        client.post_invoice_message(
+            invoice_id,
+            &fixture.business,
+            &String::from_str(env, "Update"),
+        );
+    }
+    assert_eq!(client.get_invoice_message_count(invoice_id), 5);
+    let page = client.get_invoice_messages(invoice_id, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().author, fixture.investor);
+    assert_eq!(page.get(1).unwrap().message_id, 2);
+    assert_eq!(client.get_invoice_messages(invoice_id, &4, &10).len(), 1);
+
+    // Moderation clears the post but keeps its place in the thread
+    let removed = client.remove_invoice_message(invoice_id, &1);
+    assert!(removed.removed);
+    assert_eq!(removed.body, String::from_str(env, ""));
+    assert_eq!(client.get_invoice_messages(invoice_id, &0, &50).len(), 5);
+    assert_eq!(
+        client.try_remove_invoice_message(invoice_id, &1),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(
+        client.try_remove_invoice_message(invoice_id, &9),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
+
+#[test]
+fn test_entity_existence_checks() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let investment_id = client
+        .get_invoice_investment(&fixture.invoice_id)
+        .investment_id;
+
+    assert!(client.invoice_exists(&fixture.invoice_id));
+    assert!(client.bid_exists(&fixture.bid_id));
+    assert!(client.investment_exists(&investment_id));
+
+    // IDs of one entity type never match another type's check
+    assert!(!client.invoice_exists(&fixture.bid_id));
+    assert!(!client.invoice_exists(&investment_id));
+    assert!(!client.bid_exists(&fixture.invoice_id));
+    assert!(!client.bid_exists(&investment_id));
+    assert!(!client.investment_exists(&fixture.invoice_id));
+    assert!(!client.investment_exists(&fixture.bid_id));
+
+    // Invoice-shaped ID with a counter that was never issued
+    let mut unknown = [0u8; 32];
+    unknown[15] = 99;
+    let unknown = BytesN::from_array(env, &unknown);
+    assert!(!client.invoice_exists(&unknown));
+    assert!(!client.bid_exists(&unknown));
+    assert!(!client.investment_exists(&unknown));
+}
+
+#[test]
+fn test_simulations_match_committed_state() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 10_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &9_000, &10_000);
+
+    let preview = client.simulate_accept_bid(&invoice_id, &bid_id);
+    // Previewing commits nothing
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Verified
+    );
+    assert_eq!(client.get_bid(&bid_id).unwrap().status, BidStatus::Placed);
+
+    client.accept_bid_and_fund(&invoice_id, &bid_id);
+    let token_client = token::Client::new(env, &currency);
+    assert_eq!(
+        preview.invoice_status,
+        client.get_invoice(&invoice_id).status
+    );
+    assert_eq!(preview.bid_status, client.get_bid(&bid_id).unwrap().status);
+    assert_eq!(preview.escrow_amount, 9_000);
+    assert_eq!(preview.investor_transfer, 9_000);
+    assert_eq!(
+        preview.investor_balance_after,
+        token_client.balance(&investor)
+    );
+    assert_eq!(
+        preview.contract_balance_after,
+        token_client.balance(&client.address)
+    );
+    assert_eq!(
+        preview.projected_investor_return + preview.projected_platform_fee,
+        10_000
+    );
+    assert_eq!(
+        client.try_simulate_accept_bid(&invoice_id, &bid_id),
+        Err(Ok(QuickLendXError::InvoiceAlreadyFunded))
+    );
+
+    let default_preview = client.simulate_default(&invoice_id);
+    assert_eq!(default_preview.invoice_status, InvoiceStatus::Defaulted);
+    assert_eq!(default_preview.investor, Some(investor));
+    assert_eq!(default_preview.principal, 9_000);
+    assert_eq!(default_preview.investor_loss, 9_000);
+    assert!(!default_preview.can_default_now);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    env.ledger()
+        .set_timestamp(default_preview.grace_deadline + 1);
+    assert!(client.simulate_default(&invoice_id).can_default_now);
+    client.mark_invoice_defaulted(&invoice_id, &None);
+    assert_eq!(
+        client.get_invoice(&invoice_id).status,
+        default_preview.invoice_status
+    );
+}
@@ -0,0 +1,112 @@
+//! Tests for the rounding policy: every split reconciles to the amount it was taken from.
+
+use crate::profits::{calculate_treasury_split, PlatformFee};
+use crate::rounding::{allocate_bps, bps_of, mul_div, split, split_bps, Rounding, BPS};
+
+const LARGE_AMOUNTS: [i128; 6] = [
+    999_999_999,
+    1_000_000_007,
+    123_456_789_012_345,
+    i64::MAX as i128,
+    i128::MAX / BPS,
+    i128::MAX,
+];
+
+#[test]
+fn test_split_bps_reconciles_for_every_rate() {
+    for amount in (0..=1_000).chain(LARGE_AMOUNTS) {
+        for bps in 0..=BPS {
+            let (share, remainder) = split_bps(amount, bps);
+            assert_eq!(share + remainder, amount, "amount {amount}, bps {bps}");
+            assert!(share >= 0 && remainder >= 0, "amount {amount}, bps {bps}");
+        }
+    }
+}
+
+#[test]
+fn test_split_reconciles_for_every_fraction() {
+    for amount in 0..=300 {
+        for denominator in 1..=60 {
+            for numerator in 0..=denominator {
+                let (share, remainder) = split(amount, numerator, denominator);
+                assert_eq!(share + remainder, amount);
+                assert_eq!(share, amount * numerator / denominator);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_allocate_bps_reconciles_for_every_share_pair() {
+    for amount in [0, 1, 7, 99, 1_001, 123_457, 1_000_000_007] {
+        for first in (0..=BPS).step_by(37) {
+            for second in (0..=BPS - first).step_by(41) {
+                let ([a, b], residual) = allocate_bps(amount, [first, second]);
+                assert_eq!(a + b + residual, amount, "{amount}: {first}/{second}");
+                assert!(residual >= 0);
+                assert_eq!(a, amount * first / BPS);
+                assert_eq!(b, amount * second / BPS);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rounding_modes_bracket_the_exact_quotient() {
+    for product in -500..=500 {
+        for denominator in 1..=40 {
+            let floor = mul_div(product, 1, denominator, Rounding::Floor);
+            let ceil = mul_div(product, 1, denominator, Rounding::Ceil);
+            let half_even = mul_div(product, 1, denominator, Rounding::HalfEven);
+            assert!(floor * denominator <= product && product < (floor + 1) * denominator);
+            assert_eq!(ceil - floor, (product % denominator != 0) as i128);
+            assert!(half_even == floor || half_even == ceil);
+            // Never more than half a unit away from the exact quotient
+            assert!((half_even * denominator - product).abs() * 2 <= denominator);
+        }
+    }
+}
+
+#[test]
+fn test_half_even_breaks_ties_to_even() {
+    assert_eq!(mul_div(5, 1, 2, Rounding::HalfEven), 2);
+    assert_eq!(mul_div(7, 1, 2, Rounding::HalfEven), 4);
+    assert_eq!(mul_div(-5, 1, 2, Rounding::HalfEven), -2);
+    assert_eq!(mul_div(-7, 1, 2, Rounding::HalfEven), -4);
+    assert_eq!(mul_div(6, 1, 4, Rounding::HalfEven), 2);
+    assert_eq!(mul_div(5, 1, 4, Rounding::HalfEven), 1);
+    assert_eq!(mul_div(7, 1, 4, Rounding::HalfEven), 2);
+}
+
+#[test]
+fn test_mul_div_edge_cases() {
+    assert_eq!(mul_div(100, 1, 0, Rounding::Ceil), 0);
+    assert_eq!(mul_div(100, 1, -3, Rounding::Floor), 0);
+    assert_eq!(bps_of(1, 1, Rounding::Ceil), 1);
+    assert_eq!(bps_of(1, 1, Rounding::Floor), 0);
+    assert_eq!(bps_of(-1, 1, Rounding::Floor), -1);
+    // Saturates instead of overflowing
+    assert_eq!(bps_of(i128::MAX, BPS, Rounding::Floor), i128::MAX / BPS);
+    assert_eq!(
+        split_bps(i128::MAX, BPS / 2).0 + split_bps(i128::MAX, BPS / 2).1,
+        i128::MAX
+    );
+}
+
+#[test]
+fn test_profit_and_treasury_splits_reconcile() {
+    for investment in [0, 1, 999, 10_000, 1_000_003] {
+        for profit in 0..=1_500 {
+            let payment = investment + profit;
+            for fee_bps in (0..=1_000).step_by(13) {
+                let (investor_return, platform_fee) =
+                    PlatformFee::calculate_with_fee_bps(investment, payment, fee_bps);
+                assert_eq!(investor_return + platform_fee, payment);
+                assert!(platform_fee * BPS <= profit * fee_bps);
+
+                let (treasury, remaining) = calculate_treasury_split(platform_fee, 3_333);
+                assert_eq!(treasury + remaining, platform_fee);
+            }
+        }
+    }
+}
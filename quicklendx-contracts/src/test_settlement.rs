@@ -1,11 +1,11 @@
 use super::*;
 use crate::investment::{InvestmentStatus, InvestmentStorage};
-use crate::invoice::{InvoiceStatus, InvoiceStorage};
+use crate::invoice::{InvoiceCategory, InvoiceStatus};
 use crate::profits::calculate_profit;
-use crate::settlement::settle_invoice;
+use crate::settlement::InvoiceSettlementOutcome;
 use soroban_sdk::{
     testutils::{Address as _, Ledger},
-    token, Address, BytesN, Env, String, Vec,
+    token, vec, Address, BytesN, Env, String, Vec,
 };
 
 /// Helper function to verify investor for testing
@@ -41,8 +41,8 @@ fn setup_funded_invoice(
     sac_client.mint(investor, &initial_balance);
 
     let expiration = env.ledger().sequence() + 1_000;
-    token_client.approve(business, &env.current_contract_address(), &initial_balance, &expiration);
-    token_client.approve(investor, &env.current_contract_address(), &initial_balance, &expiration);
+    token_client.approve(business, &client.address, &initial_balance, &expiration);
+    token_client.approve(investor, &client.address, &initial_balance, &expiration);
 
     // Verify business
     client.submit_kyc_application(business, &String::from_str(env, "KYC data"));
@@ -75,11 +75,13 @@ fn setup_funded_invoice(
 fn test_cannot_settle_unfunded_invoice() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
     let admin = Address::generate(&env);
     client.set_admin(&admin);
 
@@ -111,7 +113,7 @@ fn test_cannot_settle_unfunded_invoice() {
     let result = client.try_settle_invoice(&invoice_id, &1_000);
     assert!(result.is_err());
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::InvalidStatus,
         "Should fail with InvalidStatus when trying to settle unfunded invoice"
     );
@@ -122,11 +124,13 @@ fn test_cannot_settle_unfunded_invoice() {
 fn test_cannot_settle_pending_invoice() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
     let admin = Address::generate(&env);
     client.set_admin(&admin);
 
@@ -154,7 +158,7 @@ fn test_cannot_settle_pending_invoice() {
     let result = client.try_settle_invoice(&invoice_id, &1_000);
     assert!(result.is_err());
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::InvalidStatus,
         "Should fail with InvalidStatus when trying to settle pending invoice"
     );
@@ -165,12 +169,14 @@ fn test_cannot_settle_pending_invoice() {
 fn test_payout_matches_expected_return() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     // Set up funded invoice: $1000 invoice, $900 investment
     let invoice_amount = 1_000i128;
@@ -191,12 +197,12 @@ fn test_payout_matches_expected_return() {
     let token_client = token::Client::new(&env, &currency);
     let initial_business_balance = token_client.balance(&business);
     let initial_investor_balance = token_client.balance(&investor);
-    let platform_address = env.current_contract_address();
+    let platform_address = contract_id.clone();
     let initial_platform_balance = token_client.balance(&platform_address);
 
     // Calculate expected returns using the same logic as settlement
     let (expected_investor_return, expected_platform_fee) =
-        calculate_profit(&env, investment_amount, payment_amount);
+        env.as_contract(&contract_id, || calculate_profit(&env, investment_amount, payment_amount));
 
     // Ensure business has enough balance to pay
     let sac_client = token::StellarAssetClient::new(&env, &currency);
@@ -204,7 +210,7 @@ fn test_payout_matches_expected_return() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &payment_amount,
         &expiration,
     );
@@ -251,12 +257,14 @@ fn test_payout_matches_expected_return() {
 fn test_payout_with_profit() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     // Set up: $1000 invoice, $800 investment, $1000 payment (profit = $200)
     let invoice_amount = 1_000i128;
@@ -278,7 +286,7 @@ fn test_payout_with_profit() {
 
     // Calculate expected returns
     let (expected_investor_return, expected_platform_fee) =
-        calculate_profit(&env, investment_amount, payment_amount);
+        env.as_contract(&contract_id, || calculate_profit(&env, investment_amount, payment_amount));
 
     // Profit = payment - investment = 1000 - 800 = 200
     // Platform fee (2%) = 200 * 0.02 = 4
@@ -290,7 +298,7 @@ fn test_payout_with_profit() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &payment_amount,
         &expiration,
     );
@@ -317,12 +325,14 @@ fn test_payout_with_profit() {
 fn test_status_transitions_correct() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -362,7 +372,7 @@ fn test_status_transitions_correct() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &payment_amount,
         &expiration,
     );
@@ -413,12 +423,14 @@ fn test_status_transitions_correct() {
 fn test_prevents_double_settle() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -438,7 +450,7 @@ fn test_prevents_double_settle() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &payment_amount,
         &expiration,
     );
@@ -457,7 +469,7 @@ fn test_prevents_double_settle() {
         "Second settlement attempt should fail"
     );
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::InvalidStatus,
         "Should fail with InvalidStatus when trying to settle already-paid invoice"
     );
@@ -476,12 +488,14 @@ fn test_prevents_double_settle() {
 fn test_settlement_payment_too_low() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     // Set up: $1000 invoice, $900 investment
     let invoice_id = setup_funded_invoice(
@@ -503,7 +517,7 @@ fn test_settlement_payment_too_low() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &low_payment,
         &expiration,
     );
@@ -514,7 +528,7 @@ fn test_settlement_payment_too_low() {
         "Settlement with payment less than investment should fail"
     );
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::PaymentTooLow,
         "Should fail with PaymentTooLow error"
     );
@@ -533,12 +547,14 @@ fn test_settlement_payment_too_low() {
 fn test_settlement_payment_less_than_invoice() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     // Set up: $1000 invoice, $900 investment
     let invoice_id = setup_funded_invoice(
@@ -560,7 +576,7 @@ fn test_settlement_payment_less_than_invoice() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &low_payment,
         &expiration,
     );
@@ -571,7 +587,7 @@ fn test_settlement_payment_less_than_invoice() {
         "Settlement with payment less than invoice amount should fail"
     );
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::PaymentTooLow,
         "Should fail with PaymentTooLow error"
     );
@@ -582,12 +598,14 @@ fn test_settlement_payment_less_than_invoice() {
 fn test_settlement_zero_payment() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -606,7 +624,7 @@ fn test_settlement_zero_payment() {
         "Settlement with zero payment should fail"
     );
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::InvalidAmount,
         "Should fail with InvalidAmount error"
     );
@@ -617,12 +635,14 @@ fn test_settlement_zero_payment() {
 fn test_settlement_negative_payment() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -641,7 +661,7 @@ fn test_settlement_negative_payment() {
         "Settlement with negative payment should fail"
     );
     assert_eq!(
-        result.unwrap_err(),
+        result.unwrap_err().unwrap(),
         QuickLendXError::InvalidAmount,
         "Should fail with InvalidAmount error"
     );
@@ -652,12 +672,14 @@ fn test_settlement_negative_payment() {
 fn test_settlement_updates_total_paid() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -681,7 +703,7 @@ fn test_settlement_updates_total_paid() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &payment_amount,
         &expiration,
     );
@@ -703,12 +725,14 @@ fn test_settlement_updates_total_paid() {
 fn test_settlement_with_existing_partial_payments() {
     let env = Env::default();
     env.mock_all_auths();
-    let contract_id = env.register_contract(None, QuickLendXContract);
+    let contract_id = env.register(QuickLendXContract, ());
     let client = QuickLendXContractClient::new(&env, &contract_id);
 
     let business = Address::generate(&env);
     let investor = Address::generate(&env);
-    let currency = Address::generate(&env);
+    let currency = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
 
     let invoice_id = setup_funded_invoice(
         &env,
@@ -728,7 +752,7 @@ fn test_settlement_with_existing_partial_payments() {
     let expiration = env.ledger().sequence() + 1_000;
     token_client.approve(
         &business,
-        &env.current_contract_address(),
+        &client.address,
         &partial_amount,
         &expiration,
     );
@@ -743,14 +767,9 @@ fn test_settlement_with_existing_partial_payments() {
     // Now settle with full payment amount
     let remaining_amount = 600i128;
     sac_client.mint(&business, &remaining_amount);
-    token_client.approve(
-        &business,
-        &env.current_contract_address(),
-        &remaining_amount,
-        &expiration,
-    );
-
     let full_payment = 1_000i128;
+    token_client.approve(&business, &client.address, &full_payment, &expiration);
+
     client.settle_invoice(&invoice_id, &full_payment);
 
     // Verify final state
@@ -758,3 +777,539 @@ fn test_settlement_with_existing_partial_payments() {
     assert_eq!(final_invoice.total_paid, full_payment);
     assert_eq!(final_invoice.status, InvoiceStatus::Paid);
 }
+
+#[test]
+fn test_large_settlement_requires_timelocked_confirmation() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(10_000, 9_000);
+    let invoice_id = &fixture.invoice_id;
+
+    client.set_settlement_timelock(&Some(crate::settlement::SettlementTimelockPolicy {
+        threshold_amount: 5_000,
+        delay_seconds: 3_600,
+    }));
+    assert_eq!(
+        client.try_settle_invoice(invoice_id, &10_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    // A mistyped amount is cancelled before any funds move
+    let token_client = token::Client::new(env, &fixture.currency);
+    let business_before = token_client.balance(&fixture.business);
+    client.initiate_settlement(invoice_id, &100_000);
+    assert_eq!(
+        client.try_initiate_settlement(invoice_id, &10_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.cancel_settlement(invoice_id);
+    assert_eq!(client.get_pending_settlement(invoice_id), None);
+    assert_eq!(token_client.balance(&fixture.business), business_before);
+
+    let pending = client.initiate_settlement(invoice_id, &10_000);
+    assert_eq!(pending.executable_at, pending.initiated_at + 3_600);
+    assert_eq!(
+        client.get_pending_settlement(invoice_id),
+        Some(pending.clone())
+    );
+    assert_eq!(
+        client.try_confirm_settlement(invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+
+    env.ledger().set_timestamp(pending.executable_at);
+    client.confirm_settlement(invoice_id);
+    assert_eq!(client.get_invoice(invoice_id).status, InvoiceStatus::Paid);
+    assert_eq!(client.get_pending_settlement(invoice_id), None);
+    assert_eq!(
+        client.try_confirm_settlement(invoice_id),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
+
+#[test]
+fn test_settlement_below_timelock_threshold_settles_directly() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(1_000, 900);
+
+    client.set_settlement_timelock(&Some(crate::settlement::SettlementTimelockPolicy {
+        threshold_amount: 5_000,
+        delay_seconds: 3_600,
+    }));
+    assert_eq!(
+        client.try_initiate_settlement(&fixture.invoice_id, &1_000),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    client.settle_invoice(&fixture.invoice_id, &1_000);
+    assert_eq!(
+        client.get_invoice(&fixture.invoice_id).status,
+        InvoiceStatus::Paid
+    );
+}
+
+#[test]
+fn test_settlement_disposes_investor_tax_lot() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(1_000, 900);
+    let period = crate::analytics::TimePeriod::AllTime;
+
+    let lots = client.get_tax_lots(&fixture.investor, &period);
+    assert_eq!(lots.len(), 1);
+    let lot = lots.get(0).unwrap();
+    assert_eq!(lot.invoice_id, fixture.invoice_id);
+    assert_eq!(lot.currency, fixture.currency);
+    assert_eq!(lot.cost_basis, 900);
+    assert_eq!(lot.disposal, crate::tax_lots::LotDisposal::Open);
+
+    client.settle_invoice(&fixture.invoice_id, &1_000);
+    let lot = client
+        .get_tax_lots(&fixture.investor, &period)
+        .get(0)
+        .unwrap();
+    assert_eq!(lot.disposal, crate::tax_lots::LotDisposal::Settlement);
+    assert!(lot.proceeds > lot.cost_basis);
+    assert_eq!(lot.realized_gain, lot.proceeds - lot.cost_basis);
+    assert_eq!(lot.disposed_at, scenario.env.ledger().timestamp());
+}
+
+#[test]
+fn test_payment_history_records_partial_payments_and_settlement() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let fixture = scenario.funded_invoice(1_000, 900);
+
+    assert_eq!(client.get_payment_history(&fixture.invoice_id).len(), 0);
+
+    client.process_partial_payment(&fixture.invoice_id, &300, &String::from_str(env, "TXN-A"));
+    client.process_partial_payment(&fixture.invoice_id, &200, &String::from_str(env, "TXN-B"));
+
+    let history = client.get_payment_history(&fixture.invoice_id);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(first.amount, 300);
+    assert_eq!(first.payer, fixture.business);
+    assert_eq!(first.transaction_id, String::from_str(env, "TXN-A"));
+    assert_eq!(history.get(1).unwrap().amount, 200);
+    assert_eq!(client.get_invoice(&fixture.invoice_id).total_paid, 500);
+
+    let missing = BytesN::from_array(env, &[9u8; 32]);
+    assert_eq!(
+        client.try_get_payment_history(&missing),
+        Err(Ok(QuickLendXError::InvoiceNotFound))
+    );
+}
+
+#[test]
+fn test_overpayment_is_refundable_to_payer() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(1_000, 900);
+    let token_client = token::Client::new(&scenario.env, &fixture.currency);
+    let business_before = token_client.balance(&fixture.business);
+
+    client.settle_invoice(&fixture.invoice_id, &1_250);
+    assert_eq!(
+        business_before - token_client.balance(&fixture.business),
+        1_250
+    );
+    let refund = client.get_overpayment_refund(&fixture.invoice_id).unwrap();
+    assert_eq!(refund.payer, fixture.business);
+    assert_eq!(refund.amount, 250);
+    assert!(!refund.claimed);
+
+    assert_eq!(client.claim_overpayment_refund(&fixture.invoice_id), 250);
+    assert_eq!(
+        business_before - token_client.balance(&fixture.business),
+        1_000
+    );
+    assert!(
+        client
+            .get_overpayment_refund(&fixture.invoice_id)
+            .unwrap()
+            .claimed
+    );
+    assert_eq!(
+        client.try_claim_overpayment_refund(&fixture.invoice_id),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_debtor_pays_invoice_directly() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let debtor = Address::generate(env);
+    let currency = scenario.create_token(&[&business, &investor, &debtor]);
+    let token_client = token::Client::new(env, &currency);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 1_000);
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &900, &1_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    assert_eq!(
+        client.try_pay_invoice_as_debtor(&invoice_id, &1_000),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+
+    let other_id = scenario.verified_invoice(&business, &currency, 1_000);
+    client.register_invoice_debtor(&other_id, &debtor);
+    let bid_id = client.place_bid(&investor, &other_id, &900, &1_000);
+    client.accept_bid(&other_id, &bid_id);
+
+    let business_before = token_client.balance(&business);
+    let debtor_before = token_client.balance(&debtor);
+    assert_eq!(
+        client.try_pay_invoice_as_debtor(&other_id, &500),
+        Err(Ok(QuickLendXError::PaymentTooLow))
+    );
+    client.pay_invoice_as_debtor(&other_id, &1_100);
+
+    assert_eq!(client.get_invoice(&other_id).status, InvoiceStatus::Paid);
+    assert_eq!(token_client.balance(&business), business_before);
+    assert_eq!(debtor_before - token_client.balance(&debtor), 1_100);
+    let history = client.get_payment_history(&other_id);
+    assert_eq!(history.get(0).unwrap().payer, debtor);
+
+    // The surplus goes back to the debtor, not the business
+    assert_eq!(client.get_overpayment_refund(&other_id).unwrap().payer, debtor);
+    client.claim_overpayment_refund(&other_id);
+    assert_eq!(debtor_before - token_client.balance(&debtor), 1_000);
+}
+
+#[test]
+fn test_settle_invoices_batch_reports_each_entry() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let mut funded = Vec::new(env);
+    for _ in 0..2 {
+        let invoice_id = scenario.verified_invoice(&business, &currency, 1_000);
+        let bid_id = client.place_bid(&investor, &invoice_id, &900, &1_000);
+        client.accept_bid(&invoice_id, &bid_id);
+        funded.push_back(invoice_id);
+    }
+    let unfunded = scenario.verified_invoice(&business, &currency, 1_000);
+    let first = funded.get(0).unwrap();
+    let second = funded.get(1).unwrap();
+
+    let batch = client.settle_invoices(&vec![
+        env,
+        (first.clone(), 1_000i128),
+        (unfunded.clone(), 1_000i128),
+        (second.clone(), 500i128),
+        (first.clone(), 1_000i128),
+    ]);
+
+    assert_eq!(batch.settled, 1);
+    assert_eq!(batch.skipped, 3);
+    assert_eq!(batch.total_settled, 1_000);
+    let mut outcomes = Vec::new(env);
+    for result in batch.results.iter() {
+        outcomes.push_back(result.outcome);
+    }
+    assert_eq!(
+        outcomes,
+        vec![
+            env,
+            InvoiceSettlementOutcome::Settled,
+            InvoiceSettlementOutcome::WrongStatus,
+            InvoiceSettlementOutcome::InvalidPayment,
+            InvoiceSettlementOutcome::Duplicate,
+        ]
+    );
+    assert_eq!(client.get_invoice(&first).status, InvoiceStatus::Paid);
+    assert_eq!(client.get_invoice(&second).status, InvoiceStatus::Funded);
+
+    let mut too_many = Vec::new(env);
+    for _ in 0..21 {
+        too_many.push_back((first.clone(), 1_000i128));
+    }
+    assert_eq!(
+        client.try_settle_invoices(&too_many),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_rejected_investor_payout_is_held_for_claim() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    // A revocable asset, so the issuer can freeze the investor's trustline
+    let asset = env.register_stellar_asset_contract_v2(Address::generate(env));
+    asset
+        .issuer()
+        .set_flag(soroban_sdk::testutils::IssuerFlags::RevocableFlag);
+    let currency = asset.address();
+    scenario.fund(&currency, &business);
+    scenario.fund(&currency, &investor);
+    let token_client = token::Client::new(env, &currency);
+    let sac_client = token::StellarAssetClient::new(env, &currency);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 1_000);
+    let bid_id = client.place_bid(&investor, &invoice_id, &900, &1_000);
+    client.accept_bid(&invoice_id, &bid_id);
+    let investor_before = token_client.balance(&investor);
+
+    sac_client.set_authorized(&investor, &false);
+    client.settle_invoice(&invoice_id, &1_000);
+
+    assert_eq!(client.get_invoice(&invoice_id).status, InvoiceStatus::Paid);
+    assert_eq!(token_client.balance(&investor), investor_before);
+    let held = client.get_investor_claimable_balance(&investor, &currency);
+    assert!(held > 0);
+
+    sac_client.set_authorized(&investor, &true);
+    assert_eq!(client.claim_investor_balance(&investor, &currency), held);
+    assert_eq!(token_client.balance(&investor), investor_before + held);
+    assert_eq!(
+        client.try_claim_investor_balance(&investor, &currency),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+}
+
+#[test]
+fn test_preview_settlement_matches_settlement() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let fixture = scenario.funded_invoice(1_000, 900);
+    let token_client = token::Client::new(&scenario.env, &fixture.currency);
+    let investor_before = token_client.balance(&fixture.investor);
+
+    assert_eq!(
+        client.try_preview_settlement(&fixture.invoice_id, &500),
+        Err(Ok(QuickLendXError::PaymentTooLow))
+    );
+    let preview = client.preview_settlement(&fixture.invoice_id, &1_100);
+    assert_eq!(preview.amount_due, 1_000);
+    assert_eq!(preview.investor, fixture.investor);
+    assert_eq!(preview.investor_principal, 900);
+    assert_eq!(preview.investor_profit + preview.platform_fee, 100);
+    assert!(preview.platform_fee > 0);
+    assert_eq!(preview.insurance_coverage_released, 0);
+    assert_eq!(preview.business_residual, 100);
+    assert!(!preview.requires_timelock);
+    assert_eq!(
+        client.get_invoice(&fixture.invoice_id).status,
+        InvoiceStatus::Funded
+    );
+
+    client.settle_invoice(&fixture.invoice_id, &1_100);
+    assert_eq!(
+        token_client.balance(&fixture.investor) - investor_before,
+        preview.investor_principal + preview.investor_profit
+    );
+    assert_eq!(
+        client.get_overpayment_refund(&fixture.invoice_id).unwrap().amount,
+        preview.business_residual
+    );
+}
+
+#[test]
+fn test_partial_payment_transaction_ids_are_idempotent() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let fixture = scenario.funded_invoice(1_000, 900);
+    let tx = String::from_str(env, "TXN-1");
+
+    assert!(!client.is_payment_transaction_applied(&fixture.invoice_id, &tx));
+    client.process_partial_payment(&fixture.invoice_id, &300, &tx);
+    assert!(client.is_payment_transaction_applied(&fixture.invoice_id, &tx));
+
+    assert_eq!(
+        client.try_process_partial_payment(&fixture.invoice_id, &300, &tx),
+        Err(Ok(QuickLendXError::DuplicatePayment))
+    );
+    assert_eq!(client.get_invoice(&fixture.invoice_id).total_paid, 300);
+
+    client.process_partial_payment(&fixture.invoice_id, &200, &String::from_str(env, "TXN-2"));
+    assert_eq!(client.get_invoice(&fixture.invoice_id).total_paid, 500);
+}
+
+#[test]
+fn test_keeper_auto_settles_due_invoices_from_debtor_funds() {
+    let scenario = crate::testutils::Scenario::new();
+    let client = &scenario.client;
+    let env = &scenario.env;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let debtor = Address::generate(env);
+    let unfunded_debtor = Address::generate(env);
+    let keeper = Address::generate(env);
+    let currency = scenario.create_token(&[&business, &investor, &debtor]);
+    let token_client = token::Client::new(env, &currency);
+    client.set_keeper_reward_config(&0, &0, &10_000);
+    client.set_keeper_settlement_reward(&1);
+
+    // One invoice the debtor can pay, one whose debtor holds nothing, one without a debtor
+    let mut invoices = Vec::new(env);
+    for payer in [Some(&debtor), Some(&unfunded_debtor), None] {
+        let invoice_id = scenario.verified_invoice(&business, &currency, 1_000);
+        if let Some(payer) = payer {
+            client.register_invoice_debtor(&invoice_id, payer);
+        }
+        let bid_id = client.place_bid(&investor, &invoice_id, &900, &1_000);
+        client.accept_bid(&invoice_id, &bid_id);
+        invoices.push_back(invoice_id);
+    }
+    let payable = invoices.get(0).unwrap();
+
+    // Nothing is due yet
+    let result = client.auto_settle_due(&keeper, &10);
+    assert_eq!(result.settlements.len(), 0);
+    assert_eq!(result.scanned, 3);
+
+    env.ledger()
+        .set_timestamp(client.get_invoice(&payable).due_date + 1);
+    let payoff = client
+        .get_payoff_amount(&payable, &env.ledger().timestamp())
+        .payoff_amount;
+    let debtor_before = token_client.balance(&debtor);
+    let result = client.auto_settle_due(&keeper, &10);
+
+    assert_eq!(result.settlements.len(), 1);
+    let settled = result.settlements.get(0).unwrap();
+    assert_eq!(settled.invoice_id, payable);
+    assert_eq!(settled.debtor, debtor);
+    assert_eq!(settled.amount, payoff);
+    assert_eq!(settled.keeper_reward, 1);
+    assert_eq!(result.total_reward, 1);
+    assert_eq!(client.get_invoice(&payable).status, InvoiceStatus::Paid);
+    assert_eq!(debtor_before - token_client.balance(&debtor), payoff);
+    assert_eq!(token_client.balance(&keeper), 1);
+    for skipped in [invoices.get(1).unwrap(), invoices.get(2).unwrap()] {
+        assert_eq!(client.get_invoice(&skipped).status, InvoiceStatus::Funded);
+    }
+
+    assert_eq!(
+        client.try_auto_settle_due(&keeper, &11),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+}
+
+#[test]
+fn test_business_settlement_receipts_are_paged_newest_first() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+
+    let mut invoice_ids = Vec::new(env);
+    for amount in [10_000i128, 20_000] {
+        let invoice_id = scenario.verified_invoice(&business, &currency, amount);
+        let bid_id = client.place_bid(&investor, &invoice_id, &(amount * 9 / 10), &amount);
+        client.accept_bid(&invoice_id, &bid_id);
+        client.settle_invoice(&invoice_id, &amount);
+        invoice_ids.push_back(invoice_id);
+    }
+
+    let receipts = client.get_business_settlements_paged(&business, &0, &10);
+    assert_eq!(receipts.len(), 2);
+    let newest = receipts.get(0).unwrap();
+    assert_eq!(newest.invoice_id, invoice_ids.get(1).unwrap());
+    assert_eq!(newest.payer, business);
+    assert_eq!(newest.amount_settled, 20_000);
+    assert_eq!(newest.amount_advanced, 18_000);
+    assert_eq!(newest.financing_cost, 2_000);
+    assert_eq!(newest.overpayment, 0);
+    assert_eq!(
+        newest.investor_return + newest.platform_fee,
+        newest.amount_settled
+    );
+    assert!(newest.platform_fee > 0);
+
+    let second_page = client.get_business_settlements_paged(&business, &1, &10);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(
+        second_page.get(0).unwrap().invoice_id,
+        invoice_ids.get(0).unwrap()
+    );
+    assert_eq!(
+        client
+            .get_business_settlements_paged(&business, &2, &10)
+            .len(),
+        0
+    );
+    assert_eq!(
+        client
+            .get_business_settlements_paged(&Address::generate(env), &0, &10)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_broker_is_paid_share_of_platform_fee() {
+    let scenario = crate::testutils::Scenario::new();
+    let env = &scenario.env;
+    let client = &scenario.client;
+    let business = scenario.verified_business();
+    let investor = scenario.verified_investor();
+    let currency = scenario.create_token(&[&business, &investor]);
+    let broker = Address::generate(env);
+    let invoice_id = scenario.verified_invoice(&business, &currency, 20_000);
+
+    assert_eq!(
+        client.try_set_invoice_introducer(&invoice_id, &broker),
+        Err(Ok(QuickLendXError::StorageKeyNotFound))
+    );
+    assert_eq!(
+        client.try_register_broker(&broker, &5_001, &None),
+        Err(Ok(QuickLendXError::InvalidFeeBasisPoints))
+    );
+    client.register_broker(&broker, &5_000, &Some(15));
+    client.set_invoice_introducer(&invoice_id, &broker);
+    assert_eq!(
+        client.get_invoice_introducer(&invoice_id),
+        Some(broker.clone())
+    );
+    assert_eq!(
+        client.try_set_invoice_introducer(&invoice_id, &broker),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    assert_eq!(client.get_broker(&broker).unwrap().invoices_referred, 1);
+
+    let bid_id = client.place_bid(&investor, &invoice_id, &18_000, &20_000);
+    client.accept_bid_and_fund(&invoice_id, &bid_id);
+    let platform_fee = client
+        .preview_settlement(&invoice_id, &20_000)
+        .platform_fee;
+    assert!(platform_fee / 2 > 15);
+    client.settle_invoice(&invoice_id, &20_000);
+
+    // Half the platform fee, capped at 15
+    let token_client = token::Client::new(env, &currency);
+    assert_eq!(token_client.balance(&broker), 15);
+    assert_eq!(
+        client.get_broker_earnings(&broker).get(currency.clone()),
+        Some(15)
+    );
+    assert_eq!(client.get_broker(&broker).unwrap().invoices_paid, 1);
+
+    // A deactivated broker cannot be tagged and is not paid on invoices already tagged
+    let tagged = scenario.verified_invoice(&business, &currency, 10_000);
+    client.set_invoice_introducer(&tagged, &broker);
+    client.deactivate_broker(&broker);
+    let untagged = scenario.verified_invoice(&business, &currency, 10_000);
+    assert_eq!(
+        client.try_set_invoice_introducer(&untagged, &broker),
+        Err(Ok(QuickLendXError::OperationNotAllowed))
+    );
+    let bid_id = client.place_bid(&investor, &tagged, &9_000, &10_000);
+    client.accept_bid_and_fund(&tagged, &bid_id);
+    client.settle_invoice(&tagged, &10_000);
+    assert_eq!(token_client.balance(&broker), 15);
+}
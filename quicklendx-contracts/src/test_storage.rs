@@ -224,6 +224,14 @@ fn test_invoice_storage() {
                 dispute: dispute.clone(),
                 total_paid: 0,
                 payment_history: Vec::new(&env),
+                instant_funding_price: None,
+                bidding_deadline: None,
+                default_probability_bps: None,
+                funding_deadline: None,
+                relisted_from: None,
+                invoice_number: None,
+                boosted_until: None,
+                required_insurance_pct: None,
             };
 
             // Test storing invoice
@@ -307,6 +315,7 @@ fn test_bid_storage() {
             timestamp: 1234567890,
             status: BidStatus::Placed,
             expiration_timestamp: 1234567890 + 7 * 24 * 60 * 60,
+            discount_rate_bps: None,
         };
 
         // Test storing bid
@@ -566,6 +575,14 @@ fn create_test_invoice(env: &Env, id: BytesN<32>, business: Address) -> Invoice
         dispute,
         total_paid: 0,
         payment_history: Vec::new(env),
+        instant_funding_price: None,
+        bidding_deadline: None,
+        default_probability_bps: None,
+        funding_deadline: None,
+        relisted_from: None,
+        invoice_number: None,
+        boosted_until: None,
+        required_insurance_pct: None,
     }
 }
 
@@ -765,11 +782,13 @@ fn create_complex_invoice(env: &Env) -> Invoice {
         env,
         PaymentRecord {
             amount: 1000,
+            payer: business.clone(),
             timestamp: 1234567890,
             transaction_id: String::from_str(env, "TXN001"),
         },
         PaymentRecord {
             amount: 2000,
+            payer: business.clone(),
             timestamp: 1234567900,
             transaction_id: String::from_str(env, "TXN002"),
         },
@@ -816,6 +835,14 @@ fn create_complex_invoice(env: &Env) -> Invoice {
         dispute,
         total_paid: 3000,
         payment_history: payments,
+        instant_funding_price: None,
+        bidding_deadline: None,
+        default_probability_bps: None,
+        funding_deadline: None,
+        relisted_from: None,
+        invoice_number: None,
+        boosted_until: None,
+        required_insurance_pct: None,
     }
 }
 
@@ -906,6 +933,14 @@ fn test_maximum_values(env: &Env) {
         },
         total_paid: 0,
         payment_history: Vec::new(env),
+        instant_funding_price: None,
+        bidding_deadline: None,
+        default_probability_bps: None,
+        funding_deadline: None,
+        relisted_from: None,
+        invoice_number: None,
+        boosted_until: None,
+        required_insurance_pct: None,
     };
 
     // Should handle maximum values without issues
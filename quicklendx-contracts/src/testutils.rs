@@ -0,0 +1,164 @@
+//! Scenario builders for integration tests and simulations.
+//!
+//! Enabled for this crate's unit tests and, for downstream crates, with the `testutils`
+//! feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! quicklendx-contracts = { path = "...", features = ["testutils"] }
+//! ```
+//!
+//! A [`Scenario`] registers the contract in a fresh `Env` with all auths mocked and an admin
+//! set, and builds common states (verified business, verified investor, funded invoice) in
+//! one call each.
+
+use crate::invoice::InvoiceCategory;
+use crate::{QuickLendXContract, QuickLendXContractClient};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{token, Address, BytesN, Env, String, Vec};
+
+/// Token balance minted to each holder by [`Scenario::fund`]
+pub const DEFAULT_TOKEN_BALANCE: i128 = 1_000_000;
+/// Investment limit given to investors by [`Scenario::verified_investor`]
+pub const DEFAULT_INVESTMENT_LIMIT: i128 = 1_000_000;
+/// Due date offset used by [`Scenario::verified_invoice`] (30 days)
+pub const DEFAULT_DUE_IN_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// A funded invoice and the parties behind it
+#[derive(Clone, Debug)]
+pub struct FundedInvoiceFixture {
+    pub invoice_id: BytesN<32>,
+    pub bid_id: BytesN<32>,
+    pub business: Address,
+    pub investor: Address,
+    pub currency: Address,
+    pub amount: i128,
+    pub bid_amount: i128,
+}
+
+pub struct Scenario {
+    pub env: Env,
+    pub client: QuickLendXContractClient<'static>,
+    pub admin: Address,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    /// Register the contract with all auths mocked and a generated admin
+    pub fn new() -> Self {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(QuickLendXContract, ());
+        let client = QuickLendXContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.set_admin(&admin);
+        Self { env, client, admin }
+    }
+
+    /// Create a Stellar asset and [`fund`](Self::fund) each holder with it
+    pub fn create_token(&self, holders: &[&Address]) -> Address {
+        let token_admin = Address::generate(&self.env);
+        let currency = self
+            .env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        for holder in holders {
+            self.fund(&currency, holder);
+        }
+        currency
+    }
+
+    /// Mint `DEFAULT_TOKEN_BALANCE` of `currency` to `holder` and approve the contract to
+    /// spend it
+    pub fn fund(&self, currency: &Address, holder: &Address) {
+        let token_client = token::Client::new(&self.env, currency);
+        let sac_client = token::StellarAssetClient::new(&self.env, currency);
+        let expiration = self.env.ledger().sequence() + 10_000;
+        sac_client.mint(holder, &DEFAULT_TOKEN_BALANCE);
+        token_client.approve(
+            holder,
+            &self.client.address,
+            &DEFAULT_TOKEN_BALANCE,
+            &expiration,
+        );
+    }
+
+    /// Generate a business and take it through KYC approval
+    pub fn verified_business(&self) -> Address {
+        let business = Address::generate(&self.env);
+        self.client
+            .submit_kyc_application(&business, &String::from_str(&self.env, "Business KYC"));
+        self.client.verify_business(&self.admin, &business);
+        business
+    }
+
+    /// Generate an investor and verify it with `DEFAULT_INVESTMENT_LIMIT`
+    pub fn verified_investor(&self) -> Address {
+        let investor = Address::generate(&self.env);
+        self.client
+            .submit_investor_kyc(&investor, &String::from_str(&self.env, "Investor KYC"));
+        self.client
+            .verify_investor(&investor, &DEFAULT_INVESTMENT_LIMIT);
+        investor
+    }
+
+    /// Upload and verify an invoice due in `DEFAULT_DUE_IN_SECONDS`
+    pub fn verified_invoice(
+        &self,
+        business: &Address,
+        currency: &Address,
+        amount: i128,
+    ) -> BytesN<32> {
+        let invoice_id = self.client.upload_invoice(
+            business,
+            &amount,
+            currency,
+            &(self.env.ledger().timestamp() + DEFAULT_DUE_IN_SECONDS),
+            &String::from_str(&self.env, "Fixture invoice"),
+            &InvoiceCategory::Services,
+            &Vec::new(&self.env),
+        );
+        self.client.verify_invoice(&invoice_id);
+        invoice_id
+    }
+
+    /// Build a funded invoice from scratch: fresh verified business and investor, a funded
+    /// token, a verified invoice of `amount` and an accepted bid of `bid_amount` returning
+    /// `amount`
+    pub fn funded_invoice(&self, amount: i128, bid_amount: i128) -> FundedInvoiceFixture {
+        let business = self.verified_business();
+        let investor = self.verified_investor();
+        let currency = self.create_token(&[&business, &investor]);
+        let invoice_id = self.verified_invoice(&business, &currency, amount);
+        let bid_id = self
+            .client
+            .place_bid(&investor, &invoice_id, &bid_amount, &amount);
+        self.client.accept_bid(&invoice_id, &bid_id);
+        FundedInvoiceFixture {
+            invoice_id,
+            bid_id,
+            business,
+            investor,
+            currency,
+            amount,
+            bid_amount,
+        }
+    }
+
+    /// Generate `count` funded invoices with amounts `base_amount`, `2 * base_amount`, ...,
+    /// each funded at 90% of its amount
+    pub fn funded_invoices(&self, count: u32, base_amount: i128) -> Vec<BytesN<32>> {
+        let mut invoice_ids = Vec::new(&self.env);
+        for i in 1..=count {
+            let amount = base_amount * i as i128;
+            let fixture = self.funded_invoice(amount, amount * 9 / 10);
+            invoice_ids.push_back(fixture.invoice_id);
+        }
+        invoice_ids
+    }
+}
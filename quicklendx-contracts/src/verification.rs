@@ -1,7 +1,11 @@
-use crate::bid::{BidStatus, BidStorage};
+use crate::bid::BidStorage;
 use crate::errors::QuickLendXError;
-use crate::invoice::{Invoice, InvoiceMetadata};
-use soroban_sdk::{contracttype, symbol_short, vec, Address, Env, String, Vec};
+use crate::events::emit_invoice_verification_expired;
+use crate::invoice::{Invoice, InvoiceMetadata, InvoiceStatus, InvoiceStorage, LineItemRecord};
+use crate::invoice_freeze::InvoiceFreezes;
+use crate::notification_hooks::{NotificationHook, NotificationHooks};
+use crate::protocol_limits::{BidLimits, ContentPolicy};
+use soroban_sdk::{contracttype, symbol_short, vec, Address, BytesN, Env, String, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -503,18 +507,30 @@ pub fn validate_bid(
     validate_investor_investment(env, investor, bid_amount)?;
 
     BidStorage::cleanup_expired_bids(env, &invoice.id);
-    let existing_bids = BidStorage::get_bids_for_invoice(env, &invoice.id);
-    for bid_id in existing_bids.iter() {
-        if let Some(existing_bid) = BidStorage::get_bid(env, &bid_id) {
-            if existing_bid.investor == *investor && existing_bid.status == BidStatus::Placed {
-                return Err(QuickLendXError::OperationNotAllowed);
-            }
-        }
+    if bid_amount < min_next_bid_amount(env, &invoice.id) {
+        return Err(QuickLendXError::InvalidAmount);
     }
+    BidLimits::enforce(env, investor, &invoice.id)?;
 
     Ok(())
 }
 
+/// Smallest bid amount currently accepted for an invoice
+///
+/// With a bid increment rule configured and open bids present, this is the highest open bid
+/// plus the increment; otherwise it is the minimum bid amount.
+pub fn min_next_bid_amount(env: &Env, invoice_id: &BytesN<32>) -> i128 {
+    match (
+        BidStorage::get_increment_rule(env),
+        BidStorage::get_highest_open_bid_amount(env, invoice_id),
+    ) {
+        (Some(rule), Some(best_amount)) => best_amount
+            .saturating_add(rule.increment(best_amount))
+            .max(MIN_BID_AMOUNT),
+        _ => MIN_BID_AMOUNT,
+    }
+}
+
 pub fn submit_kyc_application(
     env: &Env,
     business: &Address,
@@ -642,10 +658,7 @@ pub fn verify_invoice_data(
     if due_date <= current_timestamp {
         return Err(QuickLendXError::InvoiceDueDateInvalid);
     }
-    if description.len() == 0 {
-        return Err(QuickLendXError::InvalidDescription);
-    }
-    Ok(())
+    ContentPolicy::validate_description(env, description)
 }
 
 // Event emission functions (from main)
@@ -687,25 +700,9 @@ pub fn validate_invoice_category(
     }
 }
 
-/// Validate invoice tags
-pub fn validate_invoice_tags(tags: &Vec<String>) -> Result<(), QuickLendXError> {
-    // Check tag count limit (max 10 tags per invoice)
-    if tags.len() > 10 {
-        return Err(QuickLendXError::TagLimitExceeded);
-    }
-
-    // Validate each tag
-    for tag in tags.iter() {
-        // Check tag length (1-50 characters)
-        if tag.len() < 1 || tag.len() > 50 {
-            return Err(QuickLendXError::InvalidTag);
-        }
-
-        // Check for empty tags (length 0 is already checked above)
-        // Note: Soroban String doesn't have trim() method
-    }
-
-    Ok(())
+/// Validate invoice tags against the content policy
+pub fn validate_invoice_tags(env: &Env, tags: &Vec<String>) -> Result<(), QuickLendXError> {
+    ContentPolicy::validate_tags(env, tags)
 }
 
 pub fn submit_investor_kyc(
@@ -965,7 +962,7 @@ pub fn validate_investor_investment(
 
         // Check investment limit
         if investment_amount > verification.investment_limit {
-            return Err(QuickLendXError::InvalidAmount);
+            return Err(QuickLendXError::BidExceedsLimit);
         }
 
         // Check risk level restrictions
@@ -973,13 +970,13 @@ pub fn validate_investor_investment(
             InvestorRiskLevel::VeryHigh => {
                 // Very high risk investors have additional restrictions
                 if investment_amount > 10000 {
-                    return Err(QuickLendXError::InvalidAmount);
+                    return Err(QuickLendXError::BidExceedsLimit);
                 }
             }
             InvestorRiskLevel::High => {
                 // High risk investors have moderate restrictions
                 if investment_amount > 50000 {
-                    return Err(QuickLendXError::InvalidAmount);
+                    return Err(QuickLendXError::BidExceedsLimit);
                 }
             }
             _ => {
@@ -1033,30 +1030,40 @@ pub fn set_investment_limit(
 
 /// Validate structured invoice metadata against the invoice amount
 pub fn validate_invoice_metadata(
+    env: &Env,
     metadata: &InvoiceMetadata,
     invoice_amount: i128,
 ) -> Result<(), QuickLendXError> {
-    if metadata.customer_name.len() == 0 {
-        return Err(QuickLendXError::InvalidDescription);
+    ContentPolicy::validate_description(env, &metadata.customer_name)?;
+    ContentPolicy::validate_description(env, &metadata.customer_address)?;
+    ContentPolicy::validate_description(env, &metadata.tax_id)?;
+    if metadata.notes.len() > 0 {
+        ContentPolicy::validate_description(env, &metadata.notes)?;
     }
 
-    if metadata.customer_address.len() == 0 {
-        return Err(QuickLendXError::InvalidDescription);
-    }
+    validate_line_items(env, &metadata.line_items, invoice_amount)
+}
 
-    if metadata.tax_id.len() == 0 {
-        return Err(QuickLendXError::InvalidDescription);
-    }
+/// Upper bound on line items per invoice
+pub const MAX_LINE_ITEMS: u32 = 50;
 
-    if metadata.line_items.len() == 0 {
+/// Validate an invoice itemization: each subtotal is quantity times unit price and the
+/// subtotals sum to the invoice amount
+pub fn validate_line_items(
+    env: &Env,
+    line_items: &Vec<LineItemRecord>,
+    invoice_amount: i128,
+) -> Result<(), QuickLendXError> {
+    if line_items.len() == 0 {
         return Err(QuickLendXError::InvalidDescription);
     }
+    if line_items.len() > MAX_LINE_ITEMS {
+        return Err(QuickLendXError::OperationNotAllowed);
+    }
 
     let mut computed_total = 0i128;
-    for record in metadata.line_items.iter() {
-        if record.0.len() == 0 {
-            return Err(QuickLendXError::InvalidDescription);
-        }
+    for record in line_items.iter() {
+        ContentPolicy::validate_description(env, &record.0)?;
 
         if record.1 <= 0 || record.2 < 0 {
             return Err(QuickLendXError::InvalidAmount);
@@ -1076,3 +1083,360 @@ pub fn validate_invoice_metadata(
 
     Ok(())
 }
+
+/// Sign-offs required before invoices at or above `threshold_amount` are verified
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceSignoffPolicy {
+    pub threshold_amount: i128,
+    /// Distinct verifiers (the admin or registered invoice verifiers) that must sign off
+    pub required_signoffs: u32,
+}
+
+/// Verification checklist of a high-value invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationChecklist {
+    pub invoice_id: BytesN<32>,
+    pub required_signoffs: u32,
+    pub signoffs: Vec<Address>,
+    pub completed: bool,
+}
+
+pub struct InvoiceSignoffs;
+
+impl InvoiceSignoffs {
+    const POLICY_KEY: &'static str = "signoff_policy";
+    const VERIFIERS_KEY: &'static str = "invoice_verifiers";
+    /// Upper bound on required sign-offs
+    pub const MAX_REQUIRED_SIGNOFFS: u32 = 5;
+
+    fn checklist_key(invoice_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("inv_chk"), invoice_id.clone())
+    }
+
+    pub fn get_policy(env: &Env) -> Option<InvoiceSignoffPolicy> {
+        env.storage().instance().get(&Self::POLICY_KEY)
+    }
+
+    pub fn set_policy(
+        env: &Env,
+        policy: Option<InvoiceSignoffPolicy>,
+    ) -> Result<(), QuickLendXError> {
+        match policy {
+            Some(policy) => {
+                if policy.threshold_amount <= 0
+                    || policy.required_signoffs == 0
+                    || policy.required_signoffs > Self::MAX_REQUIRED_SIGNOFFS
+                {
+                    return Err(QuickLendXError::InvalidAmount);
+                }
+                env.storage().instance().set(&Self::POLICY_KEY, &policy);
+            }
+            None => env.storage().instance().remove(&Self::POLICY_KEY),
+        }
+        Ok(())
+    }
+
+    pub fn get_verifiers(env: &Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&Self::VERIFIERS_KEY)
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Register or remove an invoice verifier; returns whether the registry changed
+    pub fn set_verifier(env: &Env, verifier: &Address, enabled: bool) -> bool {
+        let mut verifiers = Self::get_verifiers(env);
+        match (enabled, verifiers.first_index_of(verifier)) {
+            (true, None) => verifiers.push_back(verifier.clone()),
+            (false, Some(idx)) => {
+                verifiers.remove(idx);
+            }
+            _ => return false,
+        }
+        env.storage()
+            .instance()
+            .set(&Self::VERIFIERS_KEY, &verifiers);
+        true
+    }
+
+    /// The admin or a registered invoice verifier
+    pub fn can_sign_off(env: &Env, signer: &Address) -> bool {
+        BusinessVerificationStorage::is_admin(env, signer)
+            || Self::get_verifiers(env).contains(signer)
+    }
+
+    pub fn get_checklist(env: &Env, invoice_id: &BytesN<32>) -> Option<VerificationChecklist> {
+        env.storage()
+            .instance()
+            .get(&Self::checklist_key(invoice_id))
+    }
+
+    /// Discard an invoice's sign-offs so it must collect them again
+    pub fn clear_checklist(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage()
+            .instance()
+            .remove(&Self::checklist_key(invoice_id));
+    }
+
+    /// Sign-offs an invoice needs before it can be verified (1 below the policy threshold)
+    pub fn required_signoffs(env: &Env, invoice: &Invoice) -> u32 {
+        match Self::get_policy(env) {
+            Some(policy) if invoice.amount >= policy.threshold_amount => policy.required_signoffs,
+            _ => 1,
+        }
+    }
+
+    /// Record `signer`'s sign-off on a pending invoice
+    ///
+    /// # Returns
+    /// * `Ok(true)` once the checklist is complete and the invoice may be verified
+    ///
+    /// # Errors
+    /// * `Unauthorized` if `signer` is neither the admin nor a registered verifier
+    /// * `OperationNotAllowed` if `signer` already signed off
+    pub fn record_signoff(
+        env: &Env,
+        invoice: &Invoice,
+        signer: &Address,
+    ) -> Result<bool, QuickLendXError> {
+        if !Self::can_sign_off(env, signer) {
+            return Err(QuickLendXError::Unauthorized);
+        }
+        let required = Self::required_signoffs(env, invoice);
+        if required <= 1 {
+            return Ok(true);
+        }
+        let mut checklist =
+            Self::get_checklist(env, &invoice.id).unwrap_or(VerificationChecklist {
+                invoice_id: invoice.id.clone(),
+                required_signoffs: required,
+                signoffs: Vec::new(env),
+                completed: false,
+            });
+        if checklist.signoffs.contains(signer) {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        checklist.signoffs.push_back(signer.clone());
+        checklist.completed = checklist.signoffs.len() >= checklist.required_signoffs;
+        env.storage()
+            .instance()
+            .set(&Self::checklist_key(&invoice.id), &checklist);
+        Ok(checklist.completed)
+    }
+}
+
+/// Upper bound on the verification cooling-off window (7 days)
+pub const MAX_VERIFICATION_COOLING_OFF: u64 = 7 * 24 * 60 * 60;
+
+/// Minimum time between an invoice's upload and its verification, giving monitoring systems
+/// time to flag suspicious submissions before investors can bid
+pub struct VerificationCoolingOff;
+
+impl VerificationCoolingOff {
+    const WINDOW_KEY: &'static str = "verify_cooloff";
+
+    /// Cooling-off window in seconds (0 when disabled)
+    pub fn get_window(env: &Env) -> u64 {
+        env.storage().instance().get(&Self::WINDOW_KEY).unwrap_or(0)
+    }
+
+    /// Set the cooling-off window; 0 disables it
+    ///
+    /// # Errors
+    /// * `InvalidTimestamp` if the window exceeds `MAX_VERIFICATION_COOLING_OFF`
+    pub fn set_window(env: &Env, seconds: u64) -> Result<(), QuickLendXError> {
+        if seconds > MAX_VERIFICATION_COOLING_OFF {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        if seconds == 0 {
+            env.storage().instance().remove(&Self::WINDOW_KEY);
+        } else {
+            env.storage().instance().set(&Self::WINDOW_KEY, &seconds);
+        }
+        Ok(())
+    }
+
+    /// Earliest time the invoice can be signed off on
+    pub fn verifiable_at(env: &Env, invoice: &Invoice) -> u64 {
+        invoice.created_at.saturating_add(Self::get_window(env))
+    }
+
+    /// # Errors
+    /// * `InvalidTimestamp` if the invoice is still inside its cooling-off window
+    pub fn check(env: &Env, invoice: &Invoice) -> Result<(), QuickLendXError> {
+        if env.ledger().timestamp() < Self::verifiable_at(env, invoice) {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        Ok(())
+    }
+}
+
+/// Upper bound on the verification staleness window (365 days)
+pub const MAX_VERIFICATION_STALENESS: u64 = 365 * 24 * 60 * 60;
+/// Upper bound on invoices sent back to Pending per keeper call
+pub const MAX_STALE_REVERSIONS: u32 = 20;
+
+/// How long an invoice's verification stays valid while it waits for funding; business data
+/// checked long ago may be out of date, so stale invoices must be verified again
+pub struct VerificationStaleness;
+
+impl VerificationStaleness {
+    const WINDOW_KEY: &'static str = "verify_stale";
+
+    fn verified_at_key(invoice_id: &BytesN<32>) -> (soroban_sdk::Symbol, BytesN<32>) {
+        (symbol_short!("inv_vat"), invoice_id.clone())
+    }
+
+    /// Staleness window in seconds (0 when disabled)
+    pub fn get_window(env: &Env) -> u64 {
+        env.storage().instance().get(&Self::WINDOW_KEY).unwrap_or(0)
+    }
+
+    /// Set the staleness window; 0 disables it
+    ///
+    /// # Errors
+    /// * `InvalidTimestamp` if the window exceeds `MAX_VERIFICATION_STALENESS`
+    pub fn set_window(env: &Env, seconds: u64) -> Result<(), QuickLendXError> {
+        if seconds > MAX_VERIFICATION_STALENESS {
+            return Err(QuickLendXError::InvalidTimestamp);
+        }
+        if seconds == 0 {
+            env.storage().instance().remove(&Self::WINDOW_KEY);
+        } else {
+            env.storage().instance().set(&Self::WINDOW_KEY, &seconds);
+        }
+        Ok(())
+    }
+
+    pub fn record_verified(env: &Env, invoice_id: &BytesN<32>) {
+        env.storage().instance().set(
+            &Self::verified_at_key(invoice_id),
+            &env.ledger().timestamp(),
+        );
+    }
+
+    /// When the invoice was last verified; its upload time if it was verified before
+    /// verification times were recorded
+    pub fn verified_at(env: &Env, invoice: &Invoice) -> u64 {
+        env.storage()
+            .instance()
+            .get(&Self::verified_at_key(&invoice.id))
+            .unwrap_or(invoice.created_at)
+    }
+
+    /// When a verified invoice's verification goes stale; `None` while the window is disabled
+    /// or the invoice is not Verified
+    pub fn stale_at(env: &Env, invoice: &Invoice) -> Option<u64> {
+        let window = Self::get_window(env);
+        if window == 0 || invoice.status != InvoiceStatus::Verified {
+            return None;
+        }
+        Some(Self::verified_at(env, invoice).saturating_add(window))
+    }
+
+    /// Send up to `limit` Verified invoices whose verification is stale back to Pending and
+    /// notify their businesses; frozen invoices are skipped
+    ///
+    /// Sign-offs from the previous verification are discarded so high-value invoices collect
+    /// their full checklist again. Open bids stay placed and can be accepted once the invoice
+    /// is verified again.
+    ///
+    /// # Returns
+    /// * The invoices sent back to Pending, and the number of invoices scanned
+    ///
+    /// # Errors
+    /// * `OperationNotAllowed` if `limit` exceeds `MAX_STALE_REVERSIONS`
+    pub fn revert_stale(env: &Env, limit: u32) -> Result<(Vec<BytesN<32>>, u32), QuickLendXError> {
+        if limit > MAX_STALE_REVERSIONS {
+            return Err(QuickLendXError::OperationNotAllowed);
+        }
+        let now = env.ledger().timestamp();
+        let mut reverted = Vec::new(env);
+        let mut scanned = 0u32;
+        for invoice_id in
+            InvoiceStorage::get_invoices_by_status(env, &InvoiceStatus::Verified).iter()
+        {
+            if reverted.len() >= limit {
+                break;
+            }
+            scanned += 1;
+            let Some(mut invoice) = InvoiceStorage::get_invoice(env, &invoice_id) else {
+                continue;
+            };
+            if Self::stale_at(env, &invoice).is_none_or(|stale_at| now < stale_at)
+                || InvoiceFreezes::is_frozen(env, &invoice_id)
+            {
+                continue;
+            }
+            let verified_at = Self::verified_at(env, &invoice);
+            invoice.revert_to_pending(env, env.current_contract_address())?;
+            InvoiceStorage::update_invoice(env, &invoice);
+            InvoiceStorage::remove_from_status_invoices(env, &InvoiceStatus::Verified, &invoice_id);
+            InvoiceStorage::add_to_status_invoices(env, &InvoiceStatus::Pending, &invoice_id);
+            InvoiceSignoffs::clear_checklist(env, &invoice_id);
+            env.storage()
+                .instance()
+                .remove(&Self::verified_at_key(&invoice_id));
+
+            emit_invoice_verification_expired(env, &invoice, verified_at);
+            NotificationHooks::fire(
+                env,
+                &invoice,
+                NotificationHook::StatusChanged(InvoiceStatus::Verified, InvoiceStatus::Pending),
+            );
+            reverted.push_back(invoice_id);
+        }
+        Ok((reverted, scanned))
+    }
+}
+
+/// Upper bound on invoices per `verify_invoices` call
+pub const MAX_BATCH_VERIFICATION: u32 = 50;
+
+/// What happened to one invoice in a batch verification
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvoiceVerificationOutcome {
+    Verified,
+    /// Sign-off recorded; the invoice still needs other verifiers
+    AwaitingSignoffs,
+    NotFound,
+    /// Skipped: the invoice is not Pending
+    WrongStatus,
+    /// Skipped: the caller already signed off on this invoice
+    AlreadySignedOff,
+    /// Skipped: the invoice is still inside its verification cooling-off window
+    CoolingOff,
+    Failed,
+}
+
+impl InvoiceVerificationOutcome {
+    pub fn from_error(error: QuickLendXError) -> Self {
+        match error {
+            QuickLendXError::InvoiceNotFound => Self::NotFound,
+            QuickLendXError::InvalidStatus => Self::WrongStatus,
+            QuickLendXError::OperationNotAllowed => Self::AlreadySignedOff,
+            QuickLendXError::InvalidTimestamp => Self::CoolingOff,
+            _ => Self::Failed,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceVerificationResult {
+    pub invoice_id: BytesN<32>,
+    pub outcome: InvoiceVerificationOutcome,
+}
+
+/// Per-invoice results and aggregate counts of a batch verification
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchVerificationResult {
+    pub results: Vec<InvoiceVerificationResult>,
+    pub verified: u32,
+    pub awaiting_signoffs: u32,
+    pub skipped: u32,
+}
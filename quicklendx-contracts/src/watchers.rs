@@ -0,0 +1,61 @@
+//! Per-invoice watcher lists: who should hear about an invoice's state changes.
+//!
+//! The contract records each party as it becomes involved with an invoice: the business on
+//! upload, every investor who bids, the funder when the invoice is funded and insurers when
+//! coverage is added. The notification system and off-chain relayers read the list instead of
+//! reconstructing it from bids, investments and insurance records.
+
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, Vec};
+
+const WATCHERS_KEY: Symbol = symbol_short!("watchers");
+
+/// Why a party watches an invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WatcherRole {
+    Business,
+    Bidder,
+    Funder,
+    Insurer,
+}
+
+/// A party interested in an invoice's state changes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvoiceWatcher {
+    pub address: Address,
+    pub role: WatcherRole,
+    pub added_at: u64,
+}
+
+pub struct InvoiceWatchers;
+
+impl InvoiceWatchers {
+    /// Watchers of an invoice, in the order they became involved. A party holding several
+    /// roles (a bidder that funds the invoice) appears once per role.
+    pub fn get(env: &Env, invoice_id: &BytesN<32>) -> Vec<InvoiceWatcher> {
+        env.storage()
+            .instance()
+            .get(&(WATCHERS_KEY, invoice_id.clone()))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    /// Record `address` as a watcher of the invoice in `role`; no-op if already recorded
+    pub fn add(env: &Env, invoice_id: &BytesN<32>, address: &Address, role: WatcherRole) {
+        let mut watchers = Self::get(env, invoice_id);
+        if watchers
+            .iter()
+            .any(|watcher| watcher.address == *address && watcher.role == role)
+        {
+            return;
+        }
+        watchers.push_back(InvoiceWatcher {
+            address: address.clone(),
+            role,
+            added_at: env.ledger().timestamp(),
+        });
+        env.storage()
+            .instance()
+            .set(&(WATCHERS_KEY, invoice_id.clone()), &watchers);
+    }
+}